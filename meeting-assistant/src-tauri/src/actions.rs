@@ -0,0 +1,140 @@
+// Actions - Action items extracted from a transcript through the AI chain
+//
+// The model is asked for the meeting's action items as a JSON array, and each
+// becomes an action-item note flagged for review, as trigger-phrase notes
+// are, since the model can get an owner or a date wrong. Note ids are derived
+// from the meeting and a hash of the item's task, ignoring case and spacing,
+// so extracting again finds the same notes in whatever order the model lists
+// them, and never duplicates or overwrites one the user has already
+// confirmed, completed or deferred. An item worded like an action item the
+// meeting already has is skipped too.
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::ai::{self, ChatMessage, ChatRequest, ProviderConfig};
+use crate::db::Database;
+use crate::inbox::local_midnight;
+use crate::models::{Meeting, Note, NoteType, TranscriptEntry};
+use crate::summaries::transcript_prompt;
+
+const SYSTEM_PROMPT: &str = "You are a meeting assistant that finds the action items agreed in meeting transcripts. \
+Only use information from the transcript.";
+
+const INSTRUCTIONS: &str = "List the action items from this meeting as a JSON array and nothing else. \
+Each item is an object with \"task\" (what needs doing), \"assignee\" (who, or null), \
+\"deadline\" (YYYY-MM-DD, or null) and \"time\" (the MM:SS where it was agreed). \
+Answer [] if there are none.";
+
+#[derive(Deserialize)]
+struct ExtractedAction {
+    task: String,
+    assignee: Option<String>,
+    deadline: Option<String>,
+    time: Option<String>,
+}
+
+/// Extracts the meeting's action items, adds the new ones as notes and
+/// records the run. Returns the notes added.
+pub async fn extract(db: &Database, chain: &[ProviderConfig], meeting: &Meeting) -> Result<Vec<Note>, String> {
+    let transcript = db.get_transcript(&meeting.id)
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))?;
+    if transcript.is_empty() {
+        return Err("Meeting has no transcript to extract action items from".to_string());
+    }
+
+    let request = ChatRequest {
+        messages: vec![
+            ChatMessage { role: "system".to_string(), content: SYSTEM_PROMPT.to_string() },
+            ChatMessage {
+                role: "user".to_string(),
+                content: format!("{}\n{}", transcript_prompt(meeting, &transcript), INSTRUCTIONS),
+            },
+        ],
+        max_tokens: 1000,
+        temperature: 0.2,
+        purpose: "action_items".to_string(),
+    };
+    let completion = ai::complete(db, chain, &request).await?;
+    let items = parse_actions(&completion.content)?;
+
+    let existing: Vec<String> = db.get_notes(&meeting.id)
+        .map_err(|e| format!("Failed to fetch notes: {}", e))?
+        .into_iter()
+        .filter(|note| note.note_type == NoteType::ActionItem)
+        .map(|note| normalized(&note.content))
+        .collect();
+
+    let mut added = Vec::new();
+    for item in items.into_iter().filter(|item| !item.task.trim().is_empty()) {
+        if existing.contains(&normalized(&item.task)) {
+            continue;
+        }
+        let note = to_note(meeting, &transcript, item);
+        if db.insert_note_if_absent(&note).map_err(|e| format!("Failed to save action item: {}", e))? {
+            added.push(note);
+        }
+    }
+
+    db.record_action_extraction(&meeting.id, &completion.provider, &completion.model, added.len())
+        .map_err(|e| format!("Failed to record action extraction: {}", e))?;
+    Ok(added)
+}
+
+/// The JSON array in the reply, ignoring any prose or code fence around it
+fn parse_actions(content: &str) -> Result<Vec<ExtractedAction>, String> {
+    let (Some(start), Some(end)) = (content.find('['), content.rfind(']')) else {
+        return Err("AI response contained no list of action items".to_string());
+    };
+    if end < start {
+        return Err("AI response contained no list of action items".to_string());
+    }
+    serde_json::from_str(&content[start..=end])
+        .map_err(|e| format!("Failed to parse action items: {}", e))
+}
+
+fn to_note(meeting: &Meeting, transcript: &[TranscriptEntry], item: ExtractedAction) -> Note {
+    let timestamp = item.time.as_deref().and_then(parse_time).unwrap_or(0);
+    // The segment the item was agreed in, i.e. the last one starting at or before it
+    let source = transcript.iter().rev().find(|entry| entry.timestamp <= timestamp);
+
+    Note {
+        id: note_id(&meeting.id, &item.task),
+        meeting_id: meeting.id.clone(),
+        note_type: NoteType::ActionItem,
+        content: item.task.trim().to_string(),
+        timestamp,
+        source_refs: source.map(|entry| vec![entry.id.clone()]).unwrap_or_default(),
+        assignee: item.assignee.map(|a| a.trim().to_string()).filter(|a| !a.is_empty()),
+        deadline: item.deadline
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+            .map(local_midnight),
+        completed: false,
+        completed_at: None,
+        deferred_until: None,
+        needs_review: true,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// The meeting and the first 16 hex digits of SHA-256 over the normalized task
+fn note_id(meeting_id: &str, task: &str) -> String {
+    let digest = Sha256::digest(normalized(task).as_bytes());
+    let key: String = digest.iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+    format!("ai-{}-{}", meeting_id, key)
+}
+
+/// Lowercase, with runs of whitespace as single spaces
+fn normalized(task: &str) -> String {
+    task.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>().join(" ")
+}
+
+/// "MM:SS" as milliseconds into the meeting
+fn parse_time(time: &str) -> Option<i64> {
+    let (minutes, seconds) = time.trim().split_once(':')?;
+    let minutes: i64 = minutes.parse().ok()?;
+    let seconds: i64 = seconds.parse().ok()?;
+    Some((minutes * 60 + seconds) * 1000)
+}
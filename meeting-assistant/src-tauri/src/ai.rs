@@ -0,0 +1,360 @@
+// AI - Chat completions with an ordered provider fallback chain
+
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::budget;
+use crate::db::Database;
+use crate::models::AiUsageRecord;
+
+/// Setting holding the fallback chain as a JSON array of `ProviderConfig`
+pub const PROVIDER_CHAIN_KEY: &str = "ai_provider_chain";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Setting naming the OpenAI model used for embeddings
+pub const EMBEDDING_MODEL_KEY: &str = "ai_embedding_model";
+const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String, // system, user, assistant
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    pub provider: String, // openai, anthropic, groq
+    pub model: String,
+    /// May be left empty in the stored chain to reuse the key of the
+    /// primary provider when it is the same service
+    #[serde(default)]
+    pub api_key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AiCompletion {
+    pub content: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    /// Providers tried, including the one that answered
+    pub attempts: usize,
+    /// Set when a provider was used even though the request pushes it past
+    /// its monthly budget (budget mode "warn")
+    pub budget_warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Embedding {
+    pub vector: Vec<f32>,
+    pub provider: String,
+    pub model: String,
+}
+
+pub struct ChatRequest {
+    pub messages: Vec<ChatMessage>,
+    pub max_tokens: u32,
+    pub temperature: f64,
+    pub purpose: String,
+}
+
+enum CallError {
+    /// Timeouts, rate limits and server errors: try the next provider
+    Retryable { status: &'static str, message: String },
+    /// Bad requests, auth failures and the like: the next provider would
+    /// likely fail the same way, or the caller needs to fix something
+    Fatal(String),
+}
+
+struct Reply {
+    content: String,
+    prompt_tokens: i64,
+    completion_tokens: i64,
+}
+
+pub fn load_chain(db: &Database) -> Vec<ProviderConfig> {
+    db.get_setting(PROVIDER_CHAIN_KEY).ok().flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// The primary provider (if given) followed by the configured fallbacks,
+/// without duplicates and with missing keys filled in from the primary
+pub fn resolve_chain(primary: Option<ProviderConfig>, fallbacks: Vec<ProviderConfig>) -> Vec<ProviderConfig> {
+    let mut chain: Vec<ProviderConfig> = Vec::new();
+    for mut entry in primary.iter().cloned().chain(fallbacks) {
+        if entry.api_key.is_empty() {
+            if let Some(ref p) = primary {
+                if p.provider == entry.provider {
+                    entry.api_key = p.api_key.clone();
+                }
+            }
+        }
+        let duplicate = chain.iter().any(|c| c.provider == entry.provider && c.model == entry.model);
+        if !entry.api_key.is_empty() && !duplicate {
+            chain.push(entry);
+        }
+    }
+    chain
+}
+
+/// Sends the request to each provider in turn until one answers, recording
+/// every attempt in `ai_usage`. Providers over their monthly budget are
+/// skipped in budget mode "block".
+pub async fn complete(db: &Database, chain: &[ProviderConfig], request: &ChatRequest) -> Result<AiCompletion, String> {
+    if chain.is_empty() {
+        return Err("No AI provider configured".to_string());
+    }
+
+    let timeout = db.get_setting("ai_timeout_seconds").ok().flatten()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let request_id = Uuid::new_v4().to_string();
+    let block_over_budget = budget::blocks_over_budget(db);
+    let mut failures = Vec::new();
+    let mut budget_warnings = Vec::new();
+
+    for (index, provider) in chain.iter().enumerate() {
+        if let Some(warning) = budget::check(db, provider, request)? {
+            log::warn!("{}", warning);
+            if block_over_budget {
+                record_attempt(db, &request_id, provider, &request.purpose, "over_budget", None, 0, Some(warning.clone()));
+                failures.push(format!("{}/{}: {}", provider.provider, provider.model, warning));
+                continue;
+            }
+            budget_warnings.push(warning);
+        }
+
+        let started = Instant::now();
+        let result = call_provider(&client, provider, request).await;
+        let latency_ms = started.elapsed().as_millis() as i64;
+
+        let (status, reply, error) = match result {
+            Ok(reply) => ("ok", Some(reply), None),
+            Err(CallError::Retryable { status, message }) => (status, None, Some(message)),
+            Err(CallError::Fatal(message)) => ("error", None, Some(message)),
+        };
+        record_attempt(db, &request_id, provider, &request.purpose, status, reply.as_ref(), latency_ms, error.clone());
+
+        if let Some(reply) = reply {
+            if index > 0 {
+                log::info!("AI request served by fallback {}/{}", provider.provider, provider.model);
+            }
+            return Ok(AiCompletion {
+                content: reply.content,
+                provider: provider.provider.clone(),
+                model: provider.model.clone(),
+                prompt_tokens: reply.prompt_tokens,
+                completion_tokens: reply.completion_tokens,
+                attempts: index + 1,
+                budget_warnings,
+            });
+        }
+
+        let message = error.unwrap_or_default();
+        log::warn!("AI provider {}/{} failed ({}): {}", provider.provider, provider.model, status, message);
+        if status == "error" {
+            return Err(message);
+        }
+        failures.push(format!("{}/{}: {}", provider.provider, provider.model, message));
+    }
+
+    Err(format!("All AI providers failed: {}", failures.join("; ")))
+}
+
+/// Whether an error from `complete` or `embed` came from a provider's rate
+/// limit, in which case the same request may succeed after a pause
+pub fn is_rate_limited(error: &str) -> bool {
+    error.contains("HTTP 429")
+}
+
+/// Embeds `input` with the first OpenAI provider in the chain, the only
+/// service here offering embeddings, using the `ai_embedding_model` model
+pub async fn embed(db: &Database, chain: &[ProviderConfig], input: &str, purpose: &str) -> Result<Embedding, String> {
+    let openai = chain.iter()
+        .find(|p| p.provider == "openai")
+        .ok_or_else(|| "Embeddings need an OpenAI provider in the AI chain".to_string())?;
+    let model = db.get_setting(EMBEDDING_MODEL_KEY).ok().flatten()
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+    let provider = ProviderConfig { provider: openai.provider.clone(), model, api_key: openai.api_key.clone() };
+
+    let timeout = db.get_setting("ai_timeout_seconds").ok().flatten()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let started = Instant::now();
+    let response = client.post("https://api.openai.com/v1/embeddings")
+        .bearer_auth(&provider.api_key)
+        .json(&json!({ "model": provider.model, "input": input }))
+        .send()
+        .await;
+    let result = read_response(response).await;
+    let latency_ms = started.elapsed().as_millis() as i64;
+    let request_id = Uuid::new_v4().to_string();
+
+    let data = match result {
+        Ok(data) => data,
+        Err(CallError::Retryable { status, message }) => {
+            record_attempt(db, &request_id, &provider, purpose, status, None, latency_ms, Some(message.clone()));
+            return Err(message);
+        }
+        Err(CallError::Fatal(message)) => {
+            record_attempt(db, &request_id, &provider, purpose, "error", None, latency_ms, Some(message.clone()));
+            return Err(message);
+        }
+    };
+
+    let reply = Reply {
+        content: String::new(),
+        prompt_tokens: data["usage"]["prompt_tokens"].as_i64().unwrap_or(0),
+        completion_tokens: 0,
+    };
+    record_attempt(db, &request_id, &provider, purpose, "ok", Some(&reply), latency_ms, None);
+
+    let vector: Vec<f32> = data["data"][0]["embedding"].as_array()
+        .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+        .unwrap_or_default();
+    if vector.is_empty() {
+        return Err("AI response contained no embedding".to_string());
+    }
+
+    Ok(Embedding { vector, provider: provider.provider, model: provider.model })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_attempt(
+    db: &Database,
+    request_id: &str,
+    provider: &ProviderConfig,
+    purpose: &str,
+    status: &str,
+    reply: Option<&Reply>,
+    latency_ms: i64,
+    error: Option<String>,
+) {
+    let prompt_tokens = reply.map_or(0, |r| r.prompt_tokens);
+    let completion_tokens = reply.map_or(0, |r| r.completion_tokens);
+    let record = AiUsageRecord {
+        id: Uuid::new_v4().to_string(),
+        request_id: request_id.to_string(),
+        provider: provider.provider.clone(),
+        model: provider.model.clone(),
+        purpose: purpose.to_string(),
+        status: status.to_string(),
+        prompt_tokens,
+        completion_tokens,
+        cost_usd: budget::cost_usd(&provider.provider, &provider.model, prompt_tokens, completion_tokens),
+        latency_ms,
+        error,
+        created_at: Utc::now(),
+    };
+    if let Err(e) = db.record_ai_usage(&record) {
+        log::warn!("Failed to record AI usage: {}", e);
+    }
+}
+
+async fn call_provider(client: &reqwest::Client, provider: &ProviderConfig, request: &ChatRequest) -> Result<Reply, CallError> {
+    match provider.provider.as_str() {
+        "openai" => call_openai_compatible(client, "https://api.openai.com/v1/chat/completions", provider, request).await,
+        "groq" => call_openai_compatible(client, "https://api.groq.com/openai/v1/chat/completions", provider, request).await,
+        "anthropic" => call_anthropic(client, provider, request).await,
+        other => Err(CallError::Fatal(format!("Unknown AI provider: {}", other))),
+    }
+}
+
+async fn call_openai_compatible(
+    client: &reqwest::Client,
+    url: &str,
+    provider: &ProviderConfig,
+    request: &ChatRequest,
+) -> Result<Reply, CallError> {
+    let body = json!({
+        "model": provider.model,
+        "messages": request.messages,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature,
+    });
+    let response = client.post(url).bearer_auth(&provider.api_key).json(&body).send().await;
+    let data = read_response(response).await?;
+
+    Ok(Reply {
+        content: data["choices"][0]["message"]["content"].as_str().unwrap_or_default().to_string(),
+        prompt_tokens: data["usage"]["prompt_tokens"].as_i64().unwrap_or(0),
+        completion_tokens: data["usage"]["completion_tokens"].as_i64().unwrap_or(0),
+    })
+}
+
+async fn call_anthropic(client: &reqwest::Client, provider: &ProviderConfig, request: &ChatRequest) -> Result<Reply, CallError> {
+    let system: Vec<&str> = request.messages.iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect();
+    let messages: Vec<&ChatMessage> = request.messages.iter().filter(|m| m.role != "system").collect();
+
+    let body = json!({
+        "model": provider.model,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature,
+        "system": system.join("\n\n"),
+        "messages": messages,
+    });
+    let response = client.post("https://api.anthropic.com/v1/messages")
+        .header("x-api-key", &provider.api_key)
+        .header("anthropic-version", "2023-06-01")
+        .json(&body)
+        .send()
+        .await;
+    let data = read_response(response).await?;
+
+    Ok(Reply {
+        content: data["content"][0]["text"].as_str().unwrap_or_default().to_string(),
+        prompt_tokens: data["usage"]["input_tokens"].as_i64().unwrap_or(0),
+        completion_tokens: data["usage"]["output_tokens"].as_i64().unwrap_or(0),
+    })
+}
+
+async fn read_response(response: reqwest::Result<reqwest::Response>) -> Result<Value, CallError> {
+    let response = response.map_err(|e| {
+        if e.is_timeout() {
+            CallError::Retryable { status: "timeout", message: "Request timed out".to_string() }
+        } else if e.is_connect() {
+            CallError::Retryable { status: "unavailable", message: format!("Connection failed: {}", e) }
+        } else {
+            CallError::Fatal(format!("HTTP request failed: {}", e))
+        }
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        let message = format!("HTTP {}: {}", status, text);
+        return Err(if status.as_u16() == 429 {
+            CallError::Retryable { status: "rate_limited", message }
+        } else if status.is_server_error() {
+            CallError::Retryable { status: "unavailable", message }
+        } else {
+            CallError::Fatal(message)
+        });
+    }
+
+    response.json::<Value>().await.map_err(|e| {
+        if e.is_timeout() {
+            CallError::Retryable { status: "timeout", message: "Request timed out".to_string() }
+        } else {
+            CallError::Fatal(format!("Failed to parse AI response: {}", e))
+        }
+    })
+}
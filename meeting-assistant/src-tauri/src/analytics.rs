@@ -0,0 +1,145 @@
+// Analytics - Derived statistics over meetings and notes
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::models::{series_key, FollowthroughGroup, FollowthroughStats, Meeting, MeetingDiff, Note, NoteDiff, NoteType};
+
+/// Token overlap at which two notes are treated as the same item
+const SAME_ITEM_SIMILARITY: f64 = 0.6;
+
+/// Median time-to-completion of action items overall, per assignee and per
+/// meeting series. Input pairs are (meeting title, action item).
+pub fn followthrough_stats(items: &[(String, Note)]) -> FollowthroughStats {
+    let mut by_assignee: BTreeMap<String, Vec<&Note>> = BTreeMap::new();
+    let mut by_series: BTreeMap<String, Vec<&Note>> = BTreeMap::new();
+
+    for (title, note) in items {
+        let assignee = note.assignee.clone()
+            .filter(|a| !a.trim().is_empty())
+            .unwrap_or_else(|| "Unassigned".to_string());
+        by_assignee.entry(assignee).or_default().push(note);
+        by_series.entry(series_key(title)).or_default().push(note);
+    }
+
+    let all: Vec<&Note> = items.iter().map(|(_, note)| note).collect();
+    let overall = group_stats(String::new(), &all);
+
+    FollowthroughStats {
+        total: overall.total,
+        completed: overall.completed,
+        median_hours_to_complete: overall.median_hours_to_complete,
+        by_assignee: sorted_groups(by_assignee),
+        by_series: sorted_groups(by_series),
+    }
+}
+
+fn sorted_groups(groups: BTreeMap<String, Vec<&Note>>) -> Vec<FollowthroughGroup> {
+    let mut stats: Vec<_> = groups.into_iter()
+        .map(|(key, notes)| group_stats(key, &notes))
+        .collect();
+    stats.sort_by_key(|g| std::cmp::Reverse(g.total));
+    stats
+}
+
+fn group_stats(key: String, notes: &[&Note]) -> FollowthroughGroup {
+    let latencies: Vec<f64> = notes.iter().filter_map(|n| hours_to_complete(n)).collect();
+
+    FollowthroughGroup {
+        key,
+        total: notes.len(),
+        completed: notes.iter().filter(|n| n.completed).count(),
+        median_hours_to_complete: median(latencies),
+    }
+}
+
+fn hours_to_complete(note: &Note) -> Option<f64> {
+    if !note.completed {
+        return None;
+    }
+    let completed_at = note.completed_at?;
+    let seconds = completed_at.signed_duration_since(note.created_at).num_seconds().max(0);
+    Some(seconds as f64 / 3600.0)
+}
+
+pub fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
+
+/// Compare the decisions and action items of two meetings, `a` being the
+/// earlier occurrence. Notes are matched by wording, since a carried-over item
+/// is usually re-entered rather than linked.
+pub fn diff_meetings(meeting_a: Meeting, notes_a: &[Note], meeting_b: Meeting, notes_b: &[Note]) -> MeetingDiff {
+    let of_type = |notes: &[Note], note_type: NoteType| -> Vec<Note> {
+        notes.iter().filter(|n| n.note_type == note_type).cloned().collect()
+    };
+
+    MeetingDiff {
+        meeting_a,
+        meeting_b,
+        decisions: diff_decisions(&of_type(notes_a, NoteType::Decision), &of_type(notes_b, NoteType::Decision)),
+        action_items: diff_action_items(&of_type(notes_a, NoteType::ActionItem), &of_type(notes_b, NoteType::ActionItem)),
+    }
+}
+
+fn diff_action_items(earlier: &[Note], later: &[Note]) -> NoteDiff {
+    let mut diff = NoteDiff::default();
+    let mut matched = HashSet::new();
+
+    for item in earlier {
+        let carried = later.iter().position(|l| same_item(item, l));
+        if let Some(i) = carried {
+            matched.insert(i);
+        }
+        let latest = carried.map(|i| &later[i]).unwrap_or(item);
+        if item.completed || latest.completed {
+            diff.resolved.push(latest.clone());
+        } else {
+            diff.still_open.push(latest.clone());
+        }
+    }
+
+    diff.new = later.iter().enumerate()
+        .filter(|(i, _)| !matched.contains(i))
+        .map(|(_, n)| n.clone())
+        .collect();
+    diff
+}
+
+fn diff_decisions(earlier: &[Note], later: &[Note]) -> NoteDiff {
+    let mut diff = NoteDiff::default();
+
+    for decision in earlier {
+        if later.iter().any(|l| same_item(decision, l)) {
+            diff.still_open.push(decision.clone());
+        } else {
+            diff.resolved.push(decision.clone());
+        }
+    }
+    diff.new = later.iter()
+        .filter(|l| !earlier.iter().any(|e| same_item(e, l)))
+        .cloned()
+        .collect();
+    diff
+}
+
+fn same_item(a: &Note, b: &Note) -> bool {
+    let words = |note: &Note| -> HashSet<String> {
+        note.content
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 2)
+            .map(|w| w.to_lowercase())
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    union > 0 && a.intersection(&b).count() as f64 / union as f64 >= SAME_ITEM_SIMILARITY
+}
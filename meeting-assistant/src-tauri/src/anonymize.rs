@@ -0,0 +1,135 @@
+// Anonymize - Replace identities with roles in exported meetings
+//
+// Speakers are shown under the alias set for them in `speaker_aliases` ("PM",
+// "Engineer 1"), or as "Speaker N" in order of first appearance when none is
+// set. Participants take the alias of the speaker with the same name, or
+// "Participant N". Wherever one of those full names appears as a whole word
+// in the title, notes, summary or transcript it is replaced as well. Names
+// that are neither a speaker label nor a participant, and first names on
+// their own, are left as they are, so an anonymized export still deserves a
+// read before it is shared.
+
+use std::collections::HashMap;
+
+use crate::db::Database;
+use crate::models::{Annotation, Bookmark, Meeting, Note, TranscriptEntry};
+
+pub struct Anonymizer {
+    /// Speaker id to alias
+    speakers: HashMap<String, String>,
+    /// Participant id to alias
+    participants: HashMap<String, String>,
+    /// (name, alias), longest name first so "Ann Lee" is matched before "Ann"
+    names: Vec<(String, String)>,
+}
+
+impl Anonymizer {
+    pub fn load(db: &Database, meeting: &Meeting) -> Result<Self, String> {
+        let aliases: HashMap<String, String> = db.get_speaker_aliases(&meeting.id)
+            .map_err(|e| format!("Failed to fetch speaker aliases: {}", e))?
+            .into_iter()
+            .map(|alias| (alias.speaker_id, alias.alias))
+            .collect();
+        let transcript_speakers = db.get_transcript_speakers(&meeting.id)
+            .map_err(|e| format!("Failed to fetch speakers: {}", e))?;
+
+        let mut speakers: HashMap<String, String> = HashMap::new();
+        let mut names = Vec::new();
+        let mut unaliased = 0;
+        for (speaker_id, speaker_name) in transcript_speakers {
+            let alias = match speakers.get(&speaker_id).or_else(|| aliases.get(&speaker_id)) {
+                Some(alias) => alias.clone(),
+                None => {
+                    unaliased += 1;
+                    format!("Speaker {}", unaliased)
+                }
+            };
+            speakers.insert(speaker_id, alias.clone());
+            names.push((speaker_name, alias));
+        }
+
+        let mut participants = HashMap::new();
+        for (i, participant) in meeting.participants.iter().enumerate() {
+            let alias = names.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&participant.name))
+                .map(|(_, alias)| alias.clone())
+                .unwrap_or_else(|| format!("Participant {}", i + 1));
+            participants.insert(participant.id.clone(), alias.clone());
+            names.push((participant.name.clone(), alias));
+        }
+
+        names.retain(|(name, _)| !name.trim().is_empty());
+        names.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        names.dedup_by(|a, b| a.0 == b.0);
+
+        Ok(Anonymizer { speakers, participants, names })
+    }
+
+    /// Replaces every known name appearing as a whole word in `text`
+    pub fn text(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        'scan: while let Some(c) = text[i..].chars().next() {
+            let at_word_start = text[..i].chars().next_back().map_or(true, |c| !c.is_alphanumeric());
+            if at_word_start {
+                for (name, alias) in &self.names {
+                    let end = i + name.len();
+                    let at_word_end = text.get(end..).is_some_and(|rest| rest.chars().next().map_or(true, |c| !c.is_alphanumeric()));
+                    if at_word_end && text[i..end] == *name {
+                        out.push_str(alias);
+                        i = end;
+                        continue 'scan;
+                    }
+                }
+            }
+            out.push(c);
+            i += c.len_utf8();
+        }
+        out
+    }
+
+    pub fn meeting(&self, meeting: &Meeting) -> Meeting {
+        let mut meeting = meeting.clone();
+        meeting.title = self.text(&meeting.title);
+        for participant in &mut meeting.participants {
+            if let Some(alias) = self.participants.get(&participant.id) {
+                participant.name = alias.clone();
+            }
+        }
+        meeting
+    }
+
+    pub fn note(&self, note: &Note) -> Note {
+        Note {
+            content: self.text(&note.content),
+            assignee: note.assignee.as_ref().map(|assignee| self.text(assignee)),
+            ..note.clone()
+        }
+    }
+
+    pub fn entry(&self, entry: &TranscriptEntry) -> TranscriptEntry {
+        TranscriptEntry {
+            speaker_name: self.speakers.get(&entry.speaker_id).cloned().unwrap_or_else(|| "Speaker".to_string()),
+            text: self.text(&entry.text),
+            translation: entry.translation.as_ref().map(|translation| self.text(translation)),
+            // A voice profile identifies its speaker too
+            voice_pitch: None,
+            voice_energy: None,
+            ..entry.clone()
+        }
+    }
+
+    pub fn annotation(&self, annotation: &Annotation) -> Annotation {
+        Annotation {
+            content: self.text(&annotation.content),
+            ..annotation.clone()
+        }
+    }
+
+    pub fn bookmark(&self, bookmark: &Bookmark) -> Bookmark {
+        Bookmark {
+            label: bookmark.label.as_deref().map(|label| self.text(label)),
+            ..bookmark.clone()
+        }
+    }
+}
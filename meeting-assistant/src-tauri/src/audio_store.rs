@@ -0,0 +1,361 @@
+// Audio Store - Where meeting recordings are kept
+//
+// Recordings are large, so they don't have to sit next to the database.
+// `audio_storage` picks where new ones go: "local", a folder given by
+// `audio_storage_dir` (a mounted NAS or network share works like any other
+// folder; empty means the app's own "recordings" folder), or "s3", a bucket
+// on any S3-compatible service set up through the `audio_s3_*` settings.
+// Meeting metadata stays in SQLite either way.
+//
+// A meeting's `audio_path` holds the recording's location: a file path, or
+// "s3://bucket/key" for a bucket. Since a location tells which store it
+// belongs to, recordings made before the setting changed stay readable, and
+// `relocate` can move them all to the configured store of either kind.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::db::Database;
+
+pub const STORAGE_KEY: &str = "audio_storage";
+pub const DIR_KEY: &str = "audio_storage_dir";
+pub const S3_ACCESS_KEY_KEY: &str = "audio_s3_access_key";
+pub const S3_SECRET_KEY_KEY: &str = "audio_s3_secret_key";
+const S3_SCHEME: &str = "s3://";
+const DEFAULT_DIR: &str = "recordings";
+const DEFAULT_REGION: &str = "us-east-1";
+/// Recordings downloaded from a bucket for playback
+const CACHE_DIR: &str = "audio-cache";
+
+pub trait AudioStore: Send {
+    /// Stores a recording under `name` and returns its location
+    fn put(&self, name: &str, data: &[u8]) -> Result<String, String>;
+    fn get(&self, location: &str) -> Result<Vec<u8>, String>;
+    fn delete(&self, location: &str) -> Result<(), String>;
+    /// Whether `location` is in this store
+    fn holds(&self, location: &str) -> bool;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelocateAudioResult {
+    pub target: String,
+    pub moved: usize,
+    /// Recordings already in the target store
+    pub skipped: usize,
+    /// (meeting id, error)
+    pub failed: Vec<(String, String)>,
+    /// Whether new recordings now go to `target`; only once none failed
+    pub switched: bool,
+}
+
+// ========================================
+// Local folder
+// ========================================
+
+pub struct LocalStore {
+    dir: PathBuf,
+}
+
+impl AudioStore for LocalStore {
+    fn put(&self, name: &str, data: &[u8]) -> Result<String, String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+        let path = self.dir.join(name);
+        fs::write(&path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+
+    fn get(&self, location: &str) -> Result<Vec<u8>, String> {
+        fs::read(location).map_err(|e| format!("Failed to read {}: {}", location, e))
+    }
+
+    fn delete(&self, location: &str) -> Result<(), String> {
+        fs::remove_file(location).map_err(|e| format!("Failed to delete {}: {}", location, e))
+    }
+
+    fn holds(&self, location: &str) -> bool {
+        !location.starts_with(S3_SCHEME) && Path::new(location).starts_with(&self.dir)
+    }
+}
+
+// ========================================
+// S3-compatible bucket
+// ========================================
+
+/// Requests are signed with AWS Signature Version 4 and use path-style URLs
+/// (endpoint/bucket/key), which AWS, MinIO, Backblaze B2, Wasabi and the
+/// like all accept
+pub struct S3Store {
+    endpoint: String,
+    region: String,
+    bucket: String,
+    prefix: String,
+    access_key: String,
+    secret_key: String,
+    client: reqwest::blocking::Client,
+}
+
+impl S3Store {
+    fn from_settings(db: &Database) -> Result<Self, String> {
+        let setting = |key: &str| db.get_setting(key).ok().flatten().map(|v| v.trim().to_string()).unwrap_or_default();
+        let required = |key: &str| {
+            let value = setting(key);
+            if value.is_empty() {
+                Err(format!("Set '{}' to store recordings in a bucket", key))
+            } else {
+                Ok(value)
+            }
+        };
+
+        let region = setting("audio_s3_region");
+        Ok(S3Store {
+            endpoint: required("audio_s3_endpoint")?.trim_end_matches('/').to_string(),
+            region: if region.is_empty() { DEFAULT_REGION.to_string() } else { region },
+            bucket: required("audio_s3_bucket")?,
+            prefix: setting("audio_s3_prefix"),
+            access_key: required(S3_ACCESS_KEY_KEY)?,
+            secret_key: required(S3_SECRET_KEY_KEY)?,
+            client: reqwest::blocking::Client::new(),
+        })
+    }
+
+    fn key_of<'a>(&self, location: &'a str) -> Result<&'a str, String> {
+        location.strip_prefix(S3_SCHEME)
+            .and_then(|rest| rest.strip_prefix(self.bucket.as_str()))
+            .and_then(|rest| rest.strip_prefix('/'))
+            .ok_or_else(|| format!("{} is not in bucket '{}'", location, self.bucket))
+    }
+
+    fn send(&self, method: reqwest::Method, key: &str, body: Vec<u8>) -> Result<reqwest::blocking::Response, String> {
+        let path = format!("/{}/{}", uri_encode(&self.bucket), uri_encode(key));
+        let url = reqwest::Url::parse(&format!("{}{}", self.endpoint, path))
+            .map_err(|e| format!("Invalid S3 endpoint: {}", e))?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex(&Sha256::digest(&body));
+
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method, path, host, payload_hash, amz_date, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date, scope, hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = [self.region.as_str(), "s3", "aws4_request"].iter().fold(
+            hmac_sha256(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes()),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.access_key, scope, hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()))
+        );
+
+        let response = self.client.request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .map_err(|e| format!("S3 request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(format!("S3 request failed: HTTP {}: {}", status, response.text().unwrap_or_default()));
+        }
+        Ok(response)
+    }
+}
+
+impl AudioStore for S3Store {
+    fn put(&self, name: &str, data: &[u8]) -> Result<String, String> {
+        let key = format!("{}{}", self.prefix, name);
+        self.send(reqwest::Method::PUT, &key, data.to_vec())?;
+        Ok(format!("{}{}/{}", S3_SCHEME, self.bucket, key))
+    }
+
+    fn get(&self, location: &str) -> Result<Vec<u8>, String> {
+        let key = self.key_of(location)?;
+        let response = self.send(reqwest::Method::GET, key, Vec::new())?;
+        response.bytes()
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| format!("Failed to download {}: {}", location, e))
+    }
+
+    fn delete(&self, location: &str) -> Result<(), String> {
+        let key = self.key_of(location)?;
+        self.send(reqwest::Method::DELETE, key, Vec::new()).map(|_| ())
+    }
+
+    fn holds(&self, location: &str) -> bool {
+        self.key_of(location).is_ok()
+    }
+}
+
+/// Percent-encodes everything but unreserved characters and '/', as S3
+/// expects in the canonical request
+fn uri_encode(value: &str) -> String {
+    value.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// ========================================
+// Choosing a store
+// ========================================
+
+/// The store of kind "local" or "s3" as configured in the settings
+pub fn open(db: &Database, app_dir: &Path, kind: &str) -> Result<Box<dyn AudioStore>, String> {
+    match kind {
+        "local" => {
+            let dir = db.get_setting(DIR_KEY).ok().flatten()
+                .map(|dir| dir.trim().to_string())
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| app_dir.join(DEFAULT_DIR));
+            Ok(Box::new(LocalStore { dir }))
+        }
+        "s3" => Ok(Box::new(S3Store::from_settings(db)?)),
+        other => Err(format!("Unknown audio storage '{}'", other)),
+    }
+}
+
+/// The store new recordings go to
+pub fn configured(db: &Database, app_dir: &Path) -> Result<Box<dyn AudioStore>, String> {
+    let kind = db.get_setting(STORAGE_KEY).ok().flatten().unwrap_or_else(|| "local".to_string());
+    open(db, app_dir, kind.trim())
+}
+
+/// The store that can read `location`, whatever is configured now
+fn store_for(db: &Database, app_dir: &Path, location: &str) -> Result<Box<dyn AudioStore>, String> {
+    if location.starts_with(S3_SCHEME) {
+        open(db, app_dir, "s3")
+    } else {
+        // Any path is readable; the folder only matters for new recordings
+        Ok(Box::new(LocalStore { dir: PathBuf::new() }))
+    }
+}
+
+/// Moves the recording at `source` (as written by the frontend) into the
+/// configured store and records it on the meeting. Returns its location.
+pub fn save(db: &Database, app_dir: &Path, meeting_id: &str, source: &Path) -> Result<String, String> {
+    let store = configured(db, app_dir)?;
+    let source_location = source.to_string_lossy();
+    if store.holds(&source_location) {
+        return set_audio_path(db, meeting_id, &source_location);
+    }
+
+    let data = fs::read(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let name = source.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| format!("{}.webm", meeting_id));
+    let location = store.put(&name, &data)?;
+    set_audio_path(db, meeting_id, &location)?;
+    if let Err(e) = fs::remove_file(source) {
+        log::warn!("Failed to remove {} after storing it: {}", source.display(), e);
+    }
+    Ok(location)
+}
+
+fn set_audio_path(db: &Database, meeting_id: &str, location: &str) -> Result<String, String> {
+    db.set_meeting_audio_path(meeting_id, Some(location))
+        .map_err(|e| format!("Failed to save audio location: {}", e))?;
+    Ok(location.to_string())
+}
+
+/// A local file with the meeting's recording, downloading it first when it
+/// is kept in a bucket
+pub fn local_file(db: &Database, app_dir: &Path, meeting_id: &str) -> Result<PathBuf, String> {
+    let location = db.get_meeting(meeting_id)
+        .map_err(|e| format!("Failed to fetch meeting: {}", e))?
+        .and_then(|meeting| meeting.audio_path)
+        .ok_or_else(|| "Meeting has no recording".to_string())?;
+    if !location.starts_with(S3_SCHEME) {
+        return Ok(PathBuf::from(location));
+    }
+
+    let name = location.rsplit('/').next().unwrap_or(meeting_id);
+    let cached = app_dir.join(CACHE_DIR).join(name);
+    if !cached.exists() {
+        let data = store_for(db, app_dir, &location)?.get(&location)?;
+        fs::create_dir_all(cached.parent().unwrap_or(app_dir)).map_err(|e| format!("Failed to create audio cache: {}", e))?;
+        fs::write(&cached, data).map_err(|e| format!("Failed to write {}: {}", cached.display(), e))?;
+    }
+    Ok(cached)
+}
+
+/// Moves every recording not yet in the `target` store ("local" or "s3")
+/// there, one at a time: each is copied, the meeting updated, and only then
+/// the original deleted. New recordings go to `target` afterwards, unless
+/// some failed to move, so a retry can pick those up.
+pub fn relocate(db: &Database, app_dir: &Path, target: &str) -> Result<RelocateAudioResult, String> {
+    let store = open(db, app_dir, target)?;
+    let recordings = db.get_meeting_audio_paths()
+        .map_err(|e| format!("Failed to fetch recordings: {}", e))?;
+
+    let mut result = RelocateAudioResult { target: target.to_string(), moved: 0, skipped: 0, failed: vec![], switched: false };
+    for (meeting_id, location) in recordings {
+        if store.holds(&location) {
+            result.skipped += 1;
+            continue;
+        }
+
+        let moved = store_for(db, app_dir, &location).and_then(|source| {
+            let data = source.get(&location)?;
+            // Files of different meetings may share a name
+            let name = Path::new(location.rsplit('/').next().unwrap_or(&location))
+                .file_name()
+                .map(|name| format!("{}-{}", meeting_id, name.to_string_lossy()))
+                .unwrap_or_else(|| format!("{}.webm", meeting_id));
+            let new_location = store.put(&name, &data)?;
+            set_audio_path(db, &meeting_id, &new_location)?;
+            if let Err(e) = source.delete(&location) {
+                log::warn!("Moved {} but failed to delete the original: {}", location, e);
+            }
+            Ok(())
+        });
+        match moved {
+            Ok(()) => result.moved += 1,
+            Err(e) => {
+                log::warn!("Failed to relocate audio for {}: {}", meeting_id, e);
+                result.failed.push((meeting_id, e));
+            }
+        }
+    }
+
+    if !result.failed.is_empty() {
+        log::warn!(
+            "Relocated {} recordings to {} storage, {} failed; storage left unchanged",
+            result.moved, target, result.failed.len()
+        );
+        return Ok(result);
+    }
+
+    db.set_setting(STORAGE_KEY, target).map_err(|e| format!("Failed to save audio storage: {}", e))?;
+    result.switched = true;
+    log::info!("Relocated {} recordings to {} storage", result.moved, target);
+    Ok(result)
+}
@@ -0,0 +1,262 @@
+// Backlog - AI processing for meetings recorded before a feature existed
+//
+// `process_backlog` queues one job per meeting and operation (summary, action
+// items, embedding) for every finished meeting that has nothing yet from that
+// operation, and works through them in the background. At most
+// `backlog_concurrency` jobs run at once. When a provider answers with a rate
+// limit, every job holds off for a while that doubles on each retry, rather
+// than piling more requests onto a provider that is already refusing them;
+// a job fails after `MAX_RETRIES` such retries. Progress goes to the frontend
+// as `backlog-progress` events and can be polled with `get_backlog_progress`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::actions;
+use crate::ai::{self, ProviderConfig};
+use crate::commands::AppState;
+use crate::db::Database;
+use crate::models::{BacklogFailure, BacklogOperation, BacklogProgress, Meeting, MeetingEmbedding};
+use crate::summaries;
+
+pub const PROGRESS_EVENT: &str = "backlog-progress";
+const CONCURRENCY_KEY: &str = "backlog_concurrency";
+const DEFAULT_CONCURRENCY: usize = 2;
+const MAX_CONCURRENCY: usize = 8;
+const MAX_RETRIES: u32 = 4;
+const RATE_LIMIT_BACKOFF_SECS: i64 = 15;
+/// Embedding input is cut to roughly the model's 8k token window
+const MAX_EMBEDDING_CHARS: usize = 24_000;
+
+/// The current or last backlog run
+#[derive(Default)]
+pub struct Backlog {
+    progress: Mutex<Option<BacklogProgress>>,
+    cancelled: AtomicBool,
+}
+
+impl Backlog {
+    pub fn progress(&self) -> Option<BacklogProgress> {
+        self.progress.lock().unwrap().clone()
+    }
+
+    /// Stops queued jobs from starting; jobs already running finish.
+    /// Returns whether there was a run to cancel.
+    pub fn cancel(&self) -> bool {
+        let running = self.progress.lock().unwrap().as_ref().is_some_and(|p| p.running);
+        if running {
+            self.cancelled.store(true, Ordering::SeqCst);
+        }
+        running
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn update(&self, app: &AppHandle, change: impl FnOnce(&mut BacklogProgress)) {
+        let snapshot = {
+            let mut progress = self.progress.lock().unwrap();
+            let Some(progress) = progress.as_mut() else {
+                return;
+            };
+            change(progress);
+            progress.clone()
+        };
+        if let Err(e) = app.emit_all(PROGRESS_EVENT, &snapshot) {
+            log::warn!("Failed to emit backlog progress: {}", e);
+        }
+    }
+}
+
+/// Queues the jobs for `operations` and starts working through them in the
+/// background; returns the run's initial progress
+pub fn start(app: &AppHandle, chain: Vec<ProviderConfig>, operations: Vec<BacklogOperation>) -> Result<BacklogProgress, String> {
+    if chain.is_empty() {
+        return Err("No AI provider configured".to_string());
+    }
+    // Summaries first, since embeddings use them when they exist
+    let mut operations = operations;
+    operations.sort();
+    operations.dedup();
+    if operations.is_empty() {
+        return Err("Choose at least one operation".to_string());
+    }
+
+    let state = app.state::<AppState>();
+    let mut jobs = Vec::new();
+    for operation in &operations {
+        let ids = state.db.get_meetings_missing(*operation)
+            .map_err(|e| format!("Failed to fetch meetings: {}", e))?;
+        jobs.extend(ids.into_iter().map(|id| (id, *operation)));
+    }
+
+    let progress = BacklogProgress {
+        operations,
+        total: jobs.len(),
+        completed: 0,
+        failed: vec![],
+        running: !jobs.is_empty(),
+        cancelled: false,
+        paused_until: None,
+        started_at: Utc::now(),
+        finished_at: if jobs.is_empty() { Some(Utc::now()) } else { None },
+    };
+    {
+        let mut current = state.backlog.progress.lock().unwrap();
+        if current.as_ref().is_some_and(|p| p.running) {
+            return Err("Backlog processing is already running".to_string());
+        }
+        *current = Some(progress.clone());
+    }
+    state.backlog.cancelled.store(false, Ordering::SeqCst);
+
+    if !jobs.is_empty() {
+        log::info!("Processing {} backlog jobs", jobs.len());
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { run(app, chain, jobs).await });
+    }
+    Ok(progress)
+}
+
+async fn run(app: AppHandle, chain: Vec<ProviderConfig>, jobs: Vec<(String, BacklogOperation)>) {
+    let concurrency = concurrency(&app.state::<AppState>().db);
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let chain = Arc::new(chain);
+
+    let mut tasks = JoinSet::new();
+    for (meeting_id, operation) in jobs {
+        let app = app.clone();
+        let chain = Arc::clone(&chain);
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = run_job(&app, &chain, &meeting_id, operation).await;
+            (meeting_id, operation, result)
+        });
+    }
+
+    let state = app.state::<AppState>();
+    while let Some(joined) = tasks.join_next().await {
+        let (meeting_id, operation, result) = match joined {
+            Ok(done) => done,
+            Err(e) => {
+                log::warn!("Backlog job panicked: {}", e);
+                continue;
+            }
+        };
+        match result {
+            Some(Ok(())) => state.backlog.update(&app, |p| p.completed += 1),
+            Some(Err(error)) => {
+                log::warn!("Backlog {:?} failed for {}: {}", operation, meeting_id, error);
+                state.backlog.update(&app, |p| p.failed.push(BacklogFailure { meeting_id, operation, error }));
+            }
+            None => {}
+        }
+    }
+
+    let cancelled = state.backlog.is_cancelled();
+    state.backlog.update(&app, |p| {
+        p.running = false;
+        p.cancelled = cancelled;
+        p.paused_until = None;
+        p.finished_at = Some(Utc::now());
+    });
+    log::info!("Backlog processing {}", if cancelled { "cancelled" } else { "finished" });
+}
+
+/// Runs one job, waiting out rate limits shared by all jobs; `None` when the
+/// run was cancelled before the job got to go
+async fn run_job(
+    app: &AppHandle,
+    chain: &[ProviderConfig],
+    meeting_id: &str,
+    operation: BacklogOperation,
+) -> Option<Result<(), String>> {
+    let state = app.state::<AppState>();
+    let meeting = match state.db.get_meeting(meeting_id) {
+        Ok(Some(meeting)) => meeting,
+        Ok(None) => return Some(Err("Meeting not found".to_string())),
+        Err(e) => return Some(Err(format!("Failed to fetch meeting: {}", e))),
+    };
+
+    let mut retries = 0;
+    loop {
+        wait_for_rate_limit(&state.backlog).await;
+        if state.backlog.is_cancelled() {
+            return None;
+        }
+
+        match perform(&state.db, chain, &meeting, operation).await {
+            Err(e) if ai::is_rate_limited(&e) && retries < MAX_RETRIES => {
+                let backoff = RATE_LIMIT_BACKOFF_SECS << retries;
+                retries += 1;
+                log::info!("AI provider rate limited, holding backlog jobs for {}s", backoff);
+                let until = Utc::now() + chrono::Duration::seconds(backoff);
+                state.backlog.update(app, |p| {
+                    p.paused_until = Some(p.paused_until.map_or(until, |current| current.max(until)));
+                });
+            }
+            result => return Some(result),
+        }
+    }
+}
+
+async fn wait_for_rate_limit(backlog: &Backlog) {
+    loop {
+        let paused_until = backlog.progress.lock().unwrap().as_ref().and_then(|p| p.paused_until);
+        let Some(wait) = paused_until.and_then(|until| (until - Utc::now()).to_std().ok()) else {
+            return;
+        };
+        tokio::time::sleep(wait.max(Duration::from_millis(100))).await;
+    }
+}
+
+async fn perform(db: &Database, chain: &[ProviderConfig], meeting: &Meeting, operation: BacklogOperation) -> Result<(), String> {
+    match operation {
+        BacklogOperation::Summarize => summaries::summarize(db, chain, meeting).await.map(|_| ()),
+        BacklogOperation::ExtractActions => actions::extract(db, chain, meeting).await.map(|_| ()),
+        BacklogOperation::Embed => embed_meeting(db, chain, meeting).await,
+    }
+}
+
+/// Embeds the meeting's latest summary, or its transcript when it has none
+async fn embed_meeting(db: &Database, chain: &[ProviderConfig], meeting: &Meeting) -> Result<(), String> {
+    let summary = db.get_summary_versions(&meeting.id)
+        .map_err(|e| format!("Failed to fetch summaries: {}", e))?
+        .into_iter()
+        .next();
+    let mut input = match summary {
+        Some(summary) => format!("{}\n\n{}", meeting.title, summary.content),
+        None => {
+            let transcript = db.get_transcript(&meeting.id)
+                .map_err(|e| format!("Failed to fetch transcript: {}", e))?;
+            summaries::transcript_prompt(meeting, &transcript)
+        }
+    };
+    if let Some((cut, _)) = input.char_indices().nth(MAX_EMBEDDING_CHARS) {
+        input.truncate(cut);
+    }
+
+    let embedding = ai::embed(db, chain, &input, "embedding").await?;
+    db.save_meeting_embedding(&MeetingEmbedding {
+        meeting_id: meeting.id.clone(),
+        provider: embedding.provider,
+        model: embedding.model,
+        vector: embedding.vector,
+        created_at: Utc::now(),
+    })
+    .map_err(|e| format!("Failed to save embedding: {}", e))
+}
+
+fn concurrency(db: &Database) -> usize {
+    db.get_setting(CONCURRENCY_KEY).ok().flatten()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, MAX_CONCURRENCY)
+}
@@ -0,0 +1,136 @@
+// Budget - Monthly spending limits per AI provider
+
+use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeZone, Utc};
+
+use crate::ai::{ChatRequest, ProviderConfig};
+use crate::db::Database;
+use crate::models::BudgetStatus;
+
+/// Setting holding `{"provider": monthly_usd}`; providers without an entry
+/// are unlimited
+pub const BUDGETS_KEY: &str = "ai_monthly_budgets";
+/// "warn" lets the call through and reports it, "block" skips the provider
+pub const BUDGET_MODE_KEY: &str = "ai_budget_mode";
+
+/// USD per million prompt / completion tokens. Matched by model name prefix,
+/// longest first, falling back to the provider default.
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+    ("text-embedding-3-small", 0.02, 0.0),
+    ("text-embedding-3-large", 0.13, 0.0),
+    ("claude-3-haiku", 0.25, 1.25),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("claude-3-5-sonnet", 3.00, 15.00),
+    ("claude-3-7-sonnet", 3.00, 15.00),
+    ("claude-sonnet", 3.00, 15.00),
+    ("claude-3-opus", 15.00, 75.00),
+    ("claude-opus", 15.00, 75.00),
+    ("llama-3.1-8b-instant", 0.05, 0.08),
+    ("llama-3.3-70b-versatile", 0.59, 0.79),
+    ("mixtral-8x7b", 0.24, 0.24),
+];
+
+fn provider_default_price(provider: &str) -> (f64, f64) {
+    match provider {
+        "openai" => (2.50, 10.00),
+        "anthropic" => (3.00, 15.00),
+        "groq" => (0.59, 0.79),
+        _ => (0.0, 0.0),
+    }
+}
+
+pub fn cost_usd(provider: &str, model: &str, prompt_tokens: i64, completion_tokens: i64) -> f64 {
+    let (prompt_price, completion_price) = MODEL_PRICES.iter()
+        .filter(|(prefix, _, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _, _)| prefix.len())
+        .map(|(_, p, c)| (*p, *c))
+        .unwrap_or_else(|| provider_default_price(provider));
+
+    (prompt_tokens as f64 * prompt_price + completion_tokens as f64 * completion_price) / 1_000_000.0
+}
+
+/// Worst-case cost of a request before it is sent: prompt tokens estimated
+/// at ~4 characters each and the full `max_tokens` of output
+pub fn estimate_cost(provider: &ProviderConfig, request: &ChatRequest) -> f64 {
+    let chars: usize = request.messages.iter().map(|m| m.content.len()).sum();
+    let prompt_tokens = (chars / 4) as i64 + 4 * request.messages.len() as i64;
+    cost_usd(&provider.provider, &provider.model, prompt_tokens, request.max_tokens as i64)
+}
+
+pub fn load_budgets(db: &Database) -> HashMap<String, f64> {
+    db.get_setting(BUDGETS_KEY).ok().flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn blocks_over_budget(db: &Database) -> bool {
+    db.get_setting(BUDGET_MODE_KEY).ok().flatten().as_deref() == Some("block")
+}
+
+pub fn month_start() -> DateTime<Utc> {
+    let today = Local::now().date_naive();
+    let first = today.with_day(1).unwrap_or(today).and_time(NaiveTime::MIN);
+    Local.from_local_datetime(&first)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&first))
+}
+
+/// `Some(message)` when sending `request` to `provider` would take it past
+/// its monthly budget
+pub fn check(db: &Database, provider: &ProviderConfig, request: &ChatRequest) -> Result<Option<String>, String> {
+    let Some(&budget) = load_budgets(db).get(&provider.provider) else {
+        return Ok(None);
+    };
+
+    let spent = db.get_ai_spend(month_start())
+        .map_err(|e| format!("Failed to read AI spend: {}", e))?
+        .into_iter()
+        .find(|(name, _, _)| *name == provider.provider)
+        .map_or(0.0, |(_, cost, _)| cost);
+    let estimate = estimate_cost(provider, request);
+
+    if spent + estimate > budget {
+        Ok(Some(format!(
+            "{} monthly budget of ${:.2} would be exceeded (spent ${:.2}, this request up to ${:.4})",
+            provider.provider, budget, spent, estimate
+        )))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Spend this month for every provider that has a budget or has been used
+pub fn budget_status(db: &Database) -> Result<Vec<BudgetStatus>, String> {
+    let budgets = load_budgets(db);
+    let spend = db.get_ai_spend(month_start())
+        .map_err(|e| format!("Failed to read AI spend: {}", e))?;
+
+    let mut providers: Vec<&str> = budgets.keys().map(String::as_str)
+        .chain(spend.iter().map(|(name, _, _)| name.as_str()))
+        .collect();
+    providers.sort_unstable();
+    providers.dedup();
+
+    Ok(providers.into_iter().map(|provider| {
+        let (spent_usd, requests) = spend.iter()
+            .find(|(name, _, _)| name == provider)
+            .map_or((0.0, 0), |(_, cost, count)| (*cost, *count));
+        let budget_usd = budgets.get(provider).copied();
+
+        BudgetStatus {
+            provider: provider.to_string(),
+            budget_usd,
+            spent_usd,
+            remaining_usd: budget_usd.map(|b| (b - spent_usd).max(0.0)),
+            requests,
+            exceeded: budget_usd.is_some_and(|b| spent_usd >= b),
+        }
+    }).collect())
+}
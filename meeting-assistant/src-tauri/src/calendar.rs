@@ -0,0 +1,291 @@
+// Calendar - Import scheduled meetings from iCalendar (.ics) files
+//
+// Only what the meeting load forecast needs is read: each event's UID,
+// summary, start and end. All-day events and cancelled events are skipped,
+// since they don't take up meeting time. Times with a TZID are read as the
+// local time of this machine, which is right for the usual case of a
+// calendar exported in the user's own zone. Daily and weekly recurrences
+// (INTERVAL, COUNT, UNTIL, BYDAY, EXDATE) are expanded from the start of this
+// week up to `RECURRENCE_HORIZON_WEEKS` ahead; moved occurrences
+// (RECURRENCE-ID) replace the occurrence they override.
+
+use std::collections::BTreeMap;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+
+use crate::inbox::local_midnight;
+use crate::meeting_load;
+use crate::models::CalendarEvent;
+
+/// How far past today recurring events are expanded
+const RECURRENCE_HORIZON_WEEKS: i64 = 13;
+/// Upper bound on occurrences per recurring event within the window, for
+/// rules without an end
+const MAX_OCCURRENCES: usize = 1000;
+
+/// Events found in an .ics file and the number of events left out
+pub fn parse_ics(content: &str) -> (Vec<CalendarEvent>, usize) {
+    let horizon = Utc::now() + Duration::weeks(RECURRENCE_HORIZON_WEEKS);
+    // Recurring events only matter from the first week a forecast covers
+    let window_start = local_midnight(meeting_load::week_of(Local::now().date_naive()));
+    let mut events: BTreeMap<String, CalendarEvent> = BTreeMap::new();
+    let mut overrides = Vec::new();
+    let mut skipped = 0;
+
+    for properties in vevents(&unfold(content)) {
+        let prop = |name: &str| properties.iter().find(|p| p.name == name);
+        let Some(start) = prop("DTSTART").and_then(parse_time) else {
+            skipped += 1;
+            continue;
+        };
+        let end = prop("DTEND").and_then(parse_time)
+            .or_else(|| prop("DURATION").and_then(|p| parse_duration(&p.value)).map(|d| start + d));
+        let Some(end) = end.filter(|end| *end > start) else {
+            skipped += 1;
+            continue;
+        };
+        let cancelled = prop("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED"));
+        let title = prop("SUMMARY").map(|p| unescape(&p.value)).unwrap_or_else(|| "(no title)".to_string());
+        let uid = prop("UID").map(|p| p.value.trim().to_string())
+            .unwrap_or_else(|| format!("{}-{}", start.timestamp(), title));
+
+        if let Some(recurrence_id) = prop("RECURRENCE-ID").and_then(parse_time) {
+            overrides.push((occurrence_id(&uid, recurrence_id), cancelled, CalendarEvent {
+                id: String::new(),
+                title,
+                start_time: start,
+                end_time: end,
+            }));
+            continue;
+        }
+        if cancelled {
+            skipped += 1;
+            continue;
+        }
+
+        match prop("RRULE") {
+            Some(rule) => {
+                let excluded: Vec<DateTime<Utc>> = properties.iter()
+                    .filter(|p| p.name == "EXDATE")
+                    .flat_map(|p| p.value.split(',').filter_map(|v| parse_time_value(v, p)))
+                    .collect();
+                let Some(starts) = expand(&rule.value, start, window_start, horizon) else {
+                    skipped += 1;
+                    continue;
+                };
+                for occurrence in starts.into_iter().filter(|s| !excluded.contains(s)) {
+                    let id = occurrence_id(&uid, occurrence);
+                    events.insert(id.clone(), CalendarEvent {
+                        id,
+                        title: title.clone(),
+                        start_time: occurrence,
+                        end_time: occurrence + (end - start),
+                    });
+                }
+            }
+            None => {
+                events.insert(uid.clone(), CalendarEvent { id: uid, title, start_time: start, end_time: end });
+            }
+        }
+    }
+
+    for (id, cancelled, event) in overrides {
+        if cancelled {
+            events.remove(&id);
+        } else {
+            events.insert(id.clone(), CalendarEvent { id, ..event });
+        }
+    }
+
+    (events.into_values().collect(), skipped)
+}
+
+fn occurrence_id(uid: &str, start: DateTime<Utc>) -> String {
+    format!("{}/{}", uid, start.format("%Y%m%dT%H%M%SZ"))
+}
+
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+/// Joins folded lines back together
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// The properties of each top-level VEVENT; nested components such as
+/// VALARM are left out
+fn vevents(lines: &[String]) -> Vec<Vec<Property>> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    let mut depth = 0;
+
+    for line in lines {
+        let Some((head, value)) = line.split_once(':') else { continue };
+        let mut parts = head.split(';');
+        let name = parts.next().unwrap_or_default().trim().to_ascii_uppercase();
+        match (name.as_str(), value.trim().to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") if current.is_none() => current = Some(Vec::new()),
+            ("BEGIN", _) if current.is_some() => depth += 1,
+            ("END", "VEVENT") if depth == 0 => events.extend(current.take()),
+            ("END", _) if depth > 0 => depth -= 1,
+            _ => {
+                if let (Some(properties), 0) = (current.as_mut(), depth) {
+                    let params = parts
+                        .filter_map(|p| p.split_once('='))
+                        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim_matches('"').to_string()))
+                        .collect();
+                    properties.push(Property { name, params, value: value.to_string() });
+                }
+            }
+        }
+    }
+    events
+}
+
+fn parse_time(property: &Property) -> Option<DateTime<Utc>> {
+    parse_time_value(&property.value, property)
+}
+
+/// A DATE-TIME in UTC, with a TZID or floating. Plain dates are all-day and
+/// yield `None`.
+fn parse_time_value(value: &str, property: &Property) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let all_day = property.params.iter().any(|(k, v)| k == "VALUE" && v.eq_ignore_ascii_case("DATE"));
+    if all_day || !value.contains('T') {
+        return None;
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local.from_local_datetime(&naive).earliest().map(|t| t.with_timezone(&Utc))
+}
+
+/// An RFC 5545 duration such as "PT1H30M" or "P1W"
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().strip_prefix('+').unwrap_or(value.trim());
+    let rest = value.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    'S' => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Occurrence starts of a DAILY or WEEKLY rule between `window_start` and
+/// `horizon`. Other frequencies aren't supported and yield `None`.
+fn expand(
+    rule: &str,
+    start: DateTime<Utc>,
+    window_start: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+) -> Option<Vec<DateTime<Utc>>> {
+    let parts: BTreeMap<String, String> = rule.split(';')
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim().to_ascii_uppercase()))
+        .collect();
+    let interval: i64 = parts.get("INTERVAL").and_then(|v| v.parse().ok()).filter(|n| *n > 0).unwrap_or(1);
+    let count: Option<usize> = parts.get("COUNT").and_then(|v| v.parse().ok());
+    let until = parts.get("UNTIL").and_then(|v| {
+        let property = Property { name: "UNTIL".to_string(), params: Vec::new(), value: v.clone() };
+        parse_time(&property).or_else(|| {
+            // A plain date runs to the end of that day
+            NaiveDate::parse_from_str(v, "%Y%m%d").ok()
+                .and_then(|d| d.and_hms_opt(23, 59, 59))
+                .map(|t| Utc.from_utc_datetime(&t))
+        })
+    });
+    let end = until.map_or(horizon, |until| until.min(horizon));
+
+    // Occurrences keep their local time of day across DST changes
+    let local_start = start.with_timezone(&Local);
+    let time = local_start.time();
+    let first_day = local_start.date_naive();
+    let at = |day: NaiveDate| {
+        Local.from_local_datetime(&day.and_time(time)).earliest().map(|t| t.with_timezone(&Utc))
+    };
+
+    // COUNT is counted from DTSTART, so only rules without one can skip
+    // straight to the window
+    let from = match count {
+        Some(_) => first_day,
+        None => first_day.max(window_start.with_timezone(&Local).date_naive()),
+    };
+
+    let days: Box<dyn Iterator<Item = NaiveDate>> = match parts.get("FREQ").map(String::as_str) {
+        Some("DAILY") => Box::new(from.iter_days().filter(move |day| (*day - first_day).num_days() % interval == 0)),
+        Some("WEEKLY") => {
+            let mut weekdays: Vec<Weekday> = parts.get("BYDAY")
+                .map(|days| days.split(',').filter_map(parse_weekday).collect())
+                .unwrap_or_default();
+            if weekdays.is_empty() {
+                weekdays.push(first_day.weekday());
+            }
+            let week_of = |day: NaiveDate| day - Duration::days(day.weekday().num_days_from_monday() as i64);
+            let first_week = week_of(first_day);
+            Box::new(from.iter_days().filter(move |day| {
+                let weeks = (week_of(*day) - first_week).num_weeks();
+                weeks % interval == 0 && weekdays.contains(&day.weekday())
+            }))
+        }
+        _ => return None,
+    };
+
+    let mut starts = Vec::new();
+    let mut seen = 0;
+    for day in days {
+        let Some(occurrence) = at(day) else { continue };
+        if occurrence > end || count.is_some_and(|count| seen >= count) || starts.len() >= MAX_OCCURRENCES {
+            break;
+        }
+        seen += 1;
+        if occurrence >= window_start {
+            starts.push(occurrence);
+        }
+    }
+    Some(starts)
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    // Ordinals such as "1MO" only apply to monthly rules
+    match day.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", " ").replace("\\N", " ").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
@@ -0,0 +1,158 @@
+// Chapters - Topic segmentation of transcripts and screen capture thumbnails
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::models::{Chapter, ScreenCapture, TranscriptEntry};
+
+/// A pause this long always starts a new chapter
+const GAP_BOUNDARY_MS: i64 = 60_000;
+/// Chapters shorter than this are never split further
+const MIN_CHAPTER_MS: i64 = 120_000;
+/// Entries compared on each side of a candidate boundary
+const WINDOW: usize = 6;
+/// Vocabulary overlap below which the topic is considered to have changed
+const SIMILARITY_THRESHOLD: f64 = 0.08;
+
+const THUMBNAIL_WIDTH: u32 = 320;
+const THUMBNAIL_HEIGHT: u32 = 180;
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "also", "because", "been", "before", "being", "could", "does", "doing",
+    "going", "have", "just", "know", "like", "make", "maybe", "more", "really", "right",
+    "should", "some", "that", "then", "there", "these", "they", "thing", "think", "this",
+    "those", "want", "well", "were", "what", "when", "where", "which", "will", "with",
+    "would", "yeah", "your",
+];
+
+/// Split a transcript into chapters at long pauses and at points where the
+/// vocabulary of the surrounding entries shifts. Entries must be sorted by
+/// timestamp.
+pub fn detect_chapters(entries: &[TranscriptEntry]) -> Vec<Chapter> {
+    if entries.is_empty() {
+        return vec![];
+    }
+
+    let words: Vec<HashSet<String>> = entries.iter().map(|e| content_words(&e.text)).collect();
+    let mut starts = vec![0];
+
+    for i in 1..entries.len() {
+        let chapter_start = entries[*starts.last().unwrap()].timestamp;
+        let gap = entries[i].timestamp - entries[i - 1].end_timestamp;
+        if gap >= GAP_BOUNDARY_MS {
+            starts.push(i);
+            continue;
+        }
+        if entries[i].timestamp - chapter_start < MIN_CHAPTER_MS || i < WINDOW || i + WINDOW > entries.len() {
+            continue;
+        }
+
+        let before = union(&words[i - WINDOW..i]);
+        let after = union(&words[i..i + WINDOW]);
+        if jaccard(&before, &after) < SIMILARITY_THRESHOLD {
+            starts.push(i);
+        }
+    }
+
+    starts.iter().enumerate().map(|(index, &start)| {
+        let end = starts.get(index + 1).copied().unwrap_or(entries.len());
+        let section = &entries[start..end];
+        Chapter {
+            index,
+            title: chapter_title(section).unwrap_or_else(|| format!("Chapter {}", index + 1)),
+            start_time: section[0].timestamp,
+            end_time: section.iter().map(|e| e.end_timestamp).max().unwrap_or(section[0].timestamp),
+            first_entry_id: section[0].id.clone(),
+            entry_count: section.len(),
+            thumbnail_path: None,
+        }
+    }).collect()
+}
+
+/// Pick the capture that best shows a chapter: the first one taken inside it,
+/// otherwise the last one taken before it started. `captures` must be sorted
+/// by timestamp; `meeting_start_ms` converts their wall-clock timestamps to
+/// offsets from the meeting start.
+pub fn capture_for_chapter<'a>(
+    chapter: &Chapter,
+    captures: &'a [ScreenCapture],
+    meeting_start_ms: i64,
+) -> Option<&'a ScreenCapture> {
+    let offset = |c: &ScreenCapture| c.timestamp - meeting_start_ms;
+
+    captures.iter()
+        .find(|c| (chapter.start_time..=chapter.end_time).contains(&offset(c)))
+        .or_else(|| captures.iter().rev().find(|c| offset(c) < chapter.start_time))
+}
+
+/// Create (or reuse) a small JPEG thumbnail next to the capture image
+pub fn ensure_thumbnail(image_path: &str) -> Result<String, String> {
+    let source = Path::new(image_path);
+    let stem = source.file_stem()
+        .ok_or_else(|| format!("Invalid capture path: {}", image_path))?;
+    let dir = source.parent().map(|p| p.join("thumbs")).unwrap_or_else(|| PathBuf::from("thumbs"));
+    let target = dir.join(format!("{}.jpg", stem.to_string_lossy()));
+
+    if !target.exists() {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+        let image = image::open(source)
+            .map_err(|e| format!("Failed to read capture {}: {}", image_path, e))?;
+        image.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+            .to_rgb8()
+            .save(&target)
+            .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+    }
+
+    Ok(target.to_string_lossy().into_owned())
+}
+
+fn content_words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\'').to_lowercase())
+        .filter(|w| w.chars().count() > 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+fn union(sets: &[HashSet<String>]) -> HashSet<&str> {
+    sets.iter().flatten().map(|w| w.as_str()).collect()
+}
+
+fn jaccard(a: &HashSet<&str>, b: &HashSet<&str>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// The three most frequent content words of a chapter, e.g. "Budget, Hiring, Roadmap"
+fn chapter_title(section: &[TranscriptEntry]) -> Option<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for entry in section {
+        for word in content_words(&entry.text) {
+            *counts.entry(word).or_default() += 1;
+        }
+    }
+
+    let mut ranked: Vec<_> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let top: Vec<String> = ranked.into_iter()
+        .take(3)
+        .map(|(word, _)| capitalize(&word))
+        .collect();
+
+    if top.is_empty() {
+        None
+    } else {
+        Some(top.join(", "))
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -1,19 +1,63 @@
 // Tauri Commands - Bridge between frontend and backend
 
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 use uuid::Uuid;
 use chrono::Utc;
-use std::sync::Arc;
-use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use crate::db::Database;
-use crate::models::{Meeting, MeetingStatus, TranscriptEntry, Note, NoteType, AudioSource};
+use crate::ai::{self, AiCompletion, ChatMessage, ChatRequest, ProviderConfig};
+use crate::analytics;
+use crate::audio_store::{self, RelocateAudioResult};
+use crate::backlog::{self, Backlog};
+use crate::budget;
+use crate::calendar;
+use crate::chapters;
+use crate::email;
+use crate::export::{self, ExportFileResult, ExportFormat, ExportOptions};
+use crate::inbox;
+use crate::knowledge_base::{self, KnowledgeBaseResult};
+use crate::live::{self, AudioLevels};
+use crate::local_api::{self, LocalApiInfo};
+use crate::locale::Locale;
+use crate::meeting_load;
+use crate::metadata;
+use crate::plugins::{self, PluginExportResult, PluginHost};
+use crate::profile;
+use crate::share::{self, ShareLink, Shares};
+use crate::db::{self, Database};
+use crate::dnd::DoNotDisturb;
+use crate::error::CommandError;
+use crate::triggers;
+use crate::typography;
+use crate::silence::{SilenceConfig, SilenceDetector, SilenceTransition};
+use crate::speakers;
+use crate::summaries;
+use crate::models::{
+    Meeting, MeetingStatus, TranscriptEntry, TranscriptPage, TranscriptWindow, Note, NoteType, NoteTemplate, AudioSource, QueryResult,
+    DataScope, IntegrationPermissions, FollowthroughStats, RecordingHandoff,
+    ScreenCapture, Chapter, MeetingDiff, InboxItem, TriggerPhrase, Attachment, Annotation, Bookmark, ContextSearchHit,
+    AiUsageRecord, BudgetStatus, SummaryVersion, SummaryFilter, RegenerateSummariesResult,
+    SpeakerCountCheck, ReclusterResult, SpeakerAlias, WorkspaceReport, ConfigProfile, ProfileImportResult,
+    CalendarImportResult, MeetingLoadForecast, LiveState,
+    MetadataBreakdown, MetadataField, MetadataFieldType, MetadataFilter, MetadataValue,
+    BacklogOperation, BacklogProgress, Plugin,
+};
+use std::collections::HashMap;
+use chrono::DateTime;
 
 // Database state wrapper
 pub struct AppState {
     pub db: Arc<Database>,
     pub db_path: PathBuf,
+    pub silence: Mutex<SilenceDetector>,
+    pub shares: Shares,
+    pub dnd: DoNotDisturb,
+    pub levels: AudioLevels,
+    pub backlog: Backlog,
+    pub plugins: Arc<PluginHost>,
 }
 
 // ============================================================
@@ -21,8 +65,39 @@ pub struct AppState {
 // ============================================================
 
 #[tauri::command]
-pub async fn start_meeting(title: String, state: State<'_, AppState>) -> Result<Meeting, String> {
-    let meeting = Meeting {
+pub async fn start_meeting(title: String, state: State<'_, AppState>) -> Result<Meeting, CommandError> {
+    begin_recording(&state, title)
+}
+
+/// Starts recording a new meeting, refusing if another is already recording
+pub fn begin_recording(state: &AppState, title: String) -> Result<Meeting, CommandError> {
+    let meeting = new_meeting(title);
+
+    // Save to database, refusing if another meeting is already recording
+    state.db.save_recording_meeting(&meeting)?;
+
+    state.silence.lock().unwrap().reset();
+    log::info!("Started meeting: {}", meeting.id);
+    Ok(meeting)
+}
+
+/// Ends the meeting currently recording (if any) and starts a new one in the
+/// same transaction, so there is never a moment with zero or two recordings.
+#[tauri::command]
+pub async fn handoff_recording(new_title: String, state: State<'_, AppState>) -> Result<RecordingHandoff, CommandError> {
+    let started = new_meeting(new_title);
+    let ended = state.db.handoff_recording(&started)?;
+
+    state.silence.lock().unwrap().reset();
+    match ended {
+        Some(ref ended) => log::info!("Handed off recording from {} to {}", ended.id, started.id),
+        None => log::info!("Started meeting: {}", started.id),
+    }
+    Ok(RecordingHandoff { ended, started })
+}
+
+fn new_meeting(title: String) -> Meeting {
+    Meeting {
         id: Uuid::new_v4().to_string(),
         title: if title.is_empty() {
             format!("Meeting {}", chrono::Local::now().format("%Y-%m-%d %H:%M"))
@@ -38,20 +113,18 @@ pub async fn start_meeting(title: String, state: State<'_, AppState>) -> Result<
         audio_path: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
-    };
-
-    // Save to database
-    state.db.save_meeting(&meeting)
-        .map_err(|e| format!("Failed to save meeting: {}", e))?;
-
-    log::info!("Started meeting: {}", meeting.id);
-    Ok(meeting)
+        auto_closed: false,
+    }
 }
 
 #[tauri::command]
-pub async fn end_meeting(meeting_id: String, state: State<'_, AppState>) -> Result<Meeting, String> {
+pub async fn end_meeting(meeting_id: String, app: AppHandle, state: State<'_, AppState>) -> Result<Meeting, String> {
+    finish_meeting(&app, &state, &meeting_id)
+}
+
+pub fn finish_meeting(app: &AppHandle, state: &AppState, meeting_id: &str) -> Result<Meeting, String> {
     // Get existing meeting
-    let meeting = state.db.get_meeting(&meeting_id)
+    let meeting = state.db.get_meeting(meeting_id)
         .map_err(|e| format!("Database error: {}", e))?
         .ok_or_else(|| "Meeting not found".to_string())?;
 
@@ -65,6 +138,8 @@ pub async fn end_meeting(meeting_id: String, state: State<'_, AppState>) -> Resu
 
     state.db.save_meeting(&updated_meeting)
         .map_err(|e| format!("Failed to update meeting: {}", e))?;
+    speakers::validate_speakers(app, &state.db, &updated_meeting);
+    plugins::run_meeting_end_hooks(app, &state.db, &state.plugins, &app_data_dir(state), &updated_meeting);
 
     log::info!("Ended meeting: {}", meeting_id);
     Ok(updated_meeting)
@@ -72,7 +147,11 @@ pub async fn end_meeting(meeting_id: String, state: State<'_, AppState>) -> Resu
 
 #[tauri::command]
 pub async fn pause_meeting(meeting_id: String, state: State<'_, AppState>) -> Result<(), String> {
-    let meeting = state.db.get_meeting(&meeting_id)
+    pause_recording(&state, &meeting_id)
+}
+
+pub fn pause_recording(state: &AppState, meeting_id: &str) -> Result<(), String> {
+    let meeting = state.db.get_meeting(meeting_id)
         .map_err(|e| format!("Database error: {}", e))?
         .ok_or_else(|| "Meeting not found".to_string())?;
 
@@ -84,16 +163,22 @@ pub async fn pause_meeting(meeting_id: String, state: State<'_, AppState>) -> Re
 
     state.db.save_meeting(&updated)
         .map_err(|e| format!("Failed to update meeting: {}", e))?;
+    state.db.record_pause_change(meeting_id, true)
+        .map_err(|e| format!("Failed to record the pause: {}", e))?;
 
+    state.silence.lock().unwrap().reset();
     log::info!("Paused meeting: {}", meeting_id);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn resume_meeting(meeting_id: String, state: State<'_, AppState>) -> Result<(), String> {
-    let meeting = state.db.get_meeting(&meeting_id)
-        .map_err(|e| format!("Database error: {}", e))?
-        .ok_or_else(|| "Meeting not found".to_string())?;
+pub async fn resume_meeting(meeting_id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
+    resume_recording(&state, &meeting_id)
+}
+
+pub fn resume_recording(state: &AppState, meeting_id: &str) -> Result<(), CommandError> {
+    let meeting = state.db.get_meeting(meeting_id)?
+        .ok_or(CommandError::NotFound)?;
 
     let updated = Meeting {
         status: MeetingStatus::Recording,
@@ -101,17 +186,37 @@ pub async fn resume_meeting(meeting_id: String, state: State<'_, AppState>) -> R
         ..meeting
     };
 
-    state.db.save_meeting(&updated)
-        .map_err(|e| format!("Failed to update meeting: {}", e))?;
+    state.db.save_recording_meeting(&updated)?;
+    state.db.record_pause_change(meeting_id, false)?;
 
+    state.silence.lock().unwrap().reset();
     log::info!("Resumed meeting: {}", meeting_id);
     Ok(())
 }
 
+/// All meetings, or only those whose metadata matches every filter
 #[tauri::command]
-pub async fn get_meetings(state: State<'_, AppState>) -> Result<Vec<Meeting>, String> {
-    state.db.get_all_meetings()
-        .map_err(|e| format!("Failed to fetch meetings: {}", e))
+pub async fn get_meetings(metadata: Option<Vec<MetadataFilter>>, state: State<'_, AppState>) -> Result<Vec<Meeting>, String> {
+    let meetings = state.db.get_all_meetings()
+        .map_err(|e| format!("Failed to fetch meetings: {}", e))?;
+    let filters = metadata.unwrap_or_default();
+    if filters.is_empty() {
+        return Ok(meetings);
+    }
+
+    let filters = filters.into_iter()
+        .map(|filter| {
+            let field = state.db.get_metadata_field(&filter.field_id)
+                .map_err(|e| format!("Failed to fetch metadata field: {}", e))?
+                .ok_or_else(|| "Metadata field not found".to_string())?;
+            // Compared as stored, so "yes" finds meetings saved as "true"
+            let value = metadata::normalize(field.field_type, &filter.value)?.unwrap_or_default();
+            Ok(MetadataFilter { field_id: filter.field_id, value })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let matching = state.db.find_meetings_by_metadata(&filters)
+        .map_err(|e| format!("Failed to filter meetings: {}", e))?;
+    Ok(meetings.into_iter().filter(|meeting| matching.contains(&meeting.id)).collect())
 }
 
 #[tauri::command]
@@ -130,26 +235,404 @@ pub async fn delete_meeting(meeting_id: String, state: State<'_, AppState>) -> R
     Ok(())
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoPauseEvent {
+    pub meeting_id: String,
+    pub silent_seconds: u64,
+}
+
+/// Receives the current audio level from the capture pipeline and pauses or
+/// resumes the meeting when prolonged silence starts or speech returns.
+/// Emits `meeting-auto-paused` / `meeting-auto-resumed` so the UI can follow.
+#[tauri::command]
+pub async fn report_audio_level(
+    meeting_id: String,
+    level: f64,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<MeetingStatus>, String> {
+    state.levels.record(&meeting_id, level);
+    let config = SilenceConfig::load(&state.db);
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let now = Instant::now();
+    let (transition, silent_for) = {
+        let mut detector = state.silence.lock().unwrap();
+        let silent_for = detector.silent_for(now);
+        (detector.observe(&meeting_id, level, &config, now), silent_for)
+    };
+    let Some(transition) = transition else {
+        return Ok(None);
+    };
+
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+
+    let (expected, next, event) = match transition {
+        SilenceTransition::AutoPause => (MeetingStatus::Recording, MeetingStatus::Paused, "meeting-auto-paused"),
+        SilenceTransition::AutoResume => (MeetingStatus::Paused, MeetingStatus::Recording, "meeting-auto-resumed"),
+    };
+    if meeting.status != expected {
+        return Ok(None);
+    }
+
+    let updated = Meeting {
+        status: next.clone(),
+        updated_at: Utc::now(),
+        ..meeting
+    };
+    match state.db.save_recording_meeting(&updated) {
+        Ok(()) => {}
+        // Someone started another meeting while this one was auto-paused
+        Err(CommandError::Conflict { .. }) => return Ok(None),
+        Err(e) => return Err(format!("Failed to update meeting: {}", e)),
+    }
+    state.db.record_pause_change(&meeting_id, next == MeetingStatus::Paused)
+        .map_err(|e| format!("Failed to record the pause: {}", e))?;
+
+    let payload = AutoPauseEvent { meeting_id: meeting_id.clone(), silent_seconds: silent_for.as_secs() };
+    if let Err(e) = app.emit_all(event, payload) {
+        log::warn!("Failed to emit {}: {}", event, e);
+    }
+
+    log::info!("{} for meeting {}", event, meeting_id);
+    Ok(Some(next))
+}
+
+/// The meeting being recorded or paused right now, for widgets that mirror
+/// the recording. `None` when nothing is being recorded.
+#[tauri::command]
+pub async fn get_live_state(state: State<'_, AppState>) -> Result<Option<LiveState>, String> {
+    live::live_state(&state.db, &state.levels)
+}
+
+/// Where the local API listens and the token it expects. Enabling it or
+/// changing its port takes effect on the next start.
+#[tauri::command]
+pub async fn get_local_api_info(state: State<'_, AppState>) -> Result<LocalApiInfo, String> {
+    local_api::info(&state.db)
+        .map_err(|e| format!("Failed to read local API settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn regenerate_local_api_token(state: State<'_, AppState>) -> Result<LocalApiInfo, String> {
+    local_api::regenerate_token(&state.db)
+        .and_then(|_| local_api::info(&state.db))
+        .map_err(|e| format!("Failed to regenerate the local API token: {}", e))
+}
+
+// ============================================================
+// Settings Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<HashMap<String, String>, String> {
+    state.db.get_all_settings()
+        .map(|settings| settings.into_iter().collect())
+        .map_err(|e| format!("Failed to fetch settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_setting(key: String, value: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.set_setting(&key, &value)
+        .map_err(|e| format!("Failed to save setting: {}", e))
+}
+
+/// A length of time in the `locale` setting's language, e.g. "1 hr 5 min"
+#[tauri::command]
+pub async fn format_duration(seconds: i64, state: State<'_, AppState>) -> Result<String, String> {
+    Ok(Locale::from_settings(&state.db).format_duration(seconds))
+}
+
+/// How long ago (or until) `time` is, e.g. "3 hours ago", in the `locale`
+/// setting's language
+#[tauri::command]
+pub async fn format_relative_time(time: DateTime<Utc>, state: State<'_, AppState>) -> Result<String, String> {
+    Ok(Locale::from_settings(&state.db).format_relative(time, Utc::now()))
+}
+
 // ============================================================
 // Transcript Commands
 // ============================================================
 
+const TRANSCRIPT_PAGE_SIZE: usize = 200;
+const TRANSCRIPT_WINDOW: usize = 50;
+
 #[tauri::command]
-pub async fn get_transcript(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<TranscriptEntry>, String> {
+pub async fn get_transcript(
+    meeting_id: String,
+    integration_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<TranscriptEntry>, String> {
+    authorize_scopes(&state.db, integration_id.as_deref(), &[DataScope::Transcripts])?;
     state.db.get_transcript(&meeting_id)
         .map_err(|e| format!("Failed to fetch transcript: {}", e))
 }
 
+/// Fetches the transcript a page at a time; pass the last timestamp of the
+/// previous page as `after_timestamp`
+#[tauri::command]
+pub async fn get_transcript_page(
+    meeting_id: String,
+    after_timestamp: Option<i64>,
+    limit: Option<usize>,
+    integration_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<TranscriptPage, String> {
+    authorize_scopes(&state.db, integration_id.as_deref(), &[DataScope::Transcripts])?;
+    let limit = limit.unwrap_or(TRANSCRIPT_PAGE_SIZE).max(1);
+    state.db.get_transcript_page(&meeting_id, after_timestamp, limit)
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))
+}
+
+/// Fetches up to `window` entries either side of a playback position
+#[tauri::command]
+pub async fn get_transcript_around(
+    meeting_id: String,
+    timestamp: i64,
+    window: Option<usize>,
+    integration_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<TranscriptWindow, String> {
+    authorize_scopes(&state.db, integration_id.as_deref(), &[DataScope::Transcripts])?;
+    let window = window.unwrap_or(TRANSCRIPT_WINDOW);
+    state.db.get_transcript_around(&meeting_id, timestamp, window)
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_transcript_count(meeting_id: String, state: State<'_, AppState>) -> Result<usize, String> {
+    state.db.count_transcript_entries(&meeting_id)
+        .map_err(|e| format!("Failed to count transcript entries: {}", e))
+}
+
 #[tauri::command]
-pub async fn save_transcript_entry(entry: TranscriptEntry, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn save_transcript_entry(mut entry: TranscriptEntry, app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    typography::beautify(&mut entry);
     state.db.save_transcript_entry(&entry)
-        .map_err(|e| format!("Failed to save transcript entry: {}", e))
+        .map_err(|e| format!("Failed to save transcript entry: {}", e))?;
+    apply_trigger_phrases(&app, &state.db, std::slice::from_ref(&entry));
+    plugins::run_segment_hooks(&app, &state.db, &state.plugins, &app_data_dir(&state), std::slice::from_ref(&entry));
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn save_transcript_batch(entries: Vec<TranscriptEntry>, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn save_transcript_batch(mut entries: Vec<TranscriptEntry>, app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    entries.iter_mut().for_each(typography::beautify);
     state.db.save_transcript_batch(&entries)
-        .map_err(|e| format!("Failed to save transcript batch: {}", e))
+        .map_err(|e| format!("Failed to save transcript batch: {}", e))?;
+    apply_trigger_phrases(&app, &state.db, &entries);
+    plugins::run_segment_hooks(&app, &state.db, &state.plugins, &app_data_dir(&state), &entries);
+    Ok(())
+}
+
+/// Speakers found in the transcript against the participant list; `None`
+/// when the meeting has no participants or no transcript
+#[tauri::command]
+pub async fn check_speaker_count(meeting_id: String, state: State<'_, AppState>) -> Result<Option<SpeakerCountCheck>, String> {
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+    speakers::check_speaker_count(&state.db, &meeting)
+}
+
+/// Re-clusters the whole transcript into exactly `expected_speakers` speakers
+#[tauri::command]
+pub async fn recluster_speakers(
+    meeting_id: String,
+    expected_speakers: usize,
+    state: State<'_, AppState>,
+) -> Result<ReclusterResult, String> {
+    speakers::recluster(&state.db, &meeting_id, expected_speakers)
+}
+
+/// Creates notes for trigger phrases in newly saved segments and emits
+/// `auto-notes-created` with them. Failures are logged rather than returned
+/// so they never cost us transcript data.
+fn apply_trigger_phrases(app: &AppHandle, db: &Database, entries: &[TranscriptEntry]) {
+    let phrases = match db.get_trigger_phrases() {
+        Ok(phrases) => phrases,
+        Err(e) => {
+            log::warn!("Failed to load trigger phrases: {}", e);
+            return;
+        }
+    };
+
+    let mut created = Vec::new();
+    for note in triggers::notes_from_transcript(entries, &phrases) {
+        match db.insert_note_if_absent(&note) {
+            Ok(true) => created.push(note),
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to save auto-note {}: {}", note.id, e),
+        }
+    }
+
+    if !created.is_empty() {
+        log::info!("Created {} note(s) from trigger phrases", created.len());
+        if let Err(e) = app.emit_all("auto-notes-created", &created) {
+            log::warn!("Failed to emit auto-notes-created: {}", e);
+        }
+    }
+}
+
+// ============================================================
+// Screen Capture & Chapter Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn save_screen_capture(
+    meeting_id: String,
+    timestamp: i64,
+    image_path: String,
+    ocr_text: String,
+    relevance_score: f64,
+    state: State<'_, AppState>,
+) -> Result<ScreenCapture, String> {
+    let capture = ScreenCapture {
+        id: Uuid::new_v4().to_string(),
+        meeting_id,
+        timestamp,
+        image_path,
+        ocr_text,
+        relevance_score,
+        thumbnail_path: None,
+        created_at: Utc::now(),
+    };
+
+    state.db.save_screen_capture(&capture)
+        .map_err(|e| format!("Failed to save screen capture: {}", e))?;
+
+    Ok(capture)
+}
+
+/// Splits the transcript into topic chapters. For meetings with screen
+/// captures, each chapter also gets a thumbnail of the screen at its start;
+/// thumbnails are generated on first request and cached.
+#[tauri::command]
+pub async fn get_meeting_chapters(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<Chapter>, String> {
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+
+    let mut entries = state.db.get_transcript(&meeting_id)
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))?;
+    entries.sort_by_key(|e| e.timestamp);
+    let mut chapters = chapters::detect_chapters(&entries);
+
+    let captures = state.db.get_screen_captures(&meeting_id)
+        .map_err(|e| format!("Failed to fetch screen captures: {}", e))?;
+    let meeting_start_ms = meeting.start_time.timestamp_millis();
+
+    for chapter in &mut chapters {
+        let Some(capture) = chapters::capture_for_chapter(chapter, &captures, meeting_start_ms) else {
+            continue;
+        };
+        if let Some(ref path) = capture.thumbnail_path {
+            chapter.thumbnail_path = Some(path.clone());
+            continue;
+        }
+        match chapters::ensure_thumbnail(&capture.image_path) {
+            Ok(path) => {
+                if let Err(e) = state.db.set_capture_thumbnail(&capture.id, &path) {
+                    log::warn!("Failed to record thumbnail for capture {}: {}", capture.id, e);
+                }
+                chapter.thumbnail_path = Some(path);
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
+    Ok(chapters)
+}
+
+// ============================================================
+// Attachment Commands
+// ============================================================
+
+/// Upper bound on attachment text included in an AI prompt
+const MAX_CONTEXT_CHARS: usize = 12_000;
+
+/// Parses an .eml file and stores its text as searchable meeting context
+#[tauri::command]
+pub async fn attach_email(meeting_id: String, path: String, state: State<'_, AppState>) -> Result<Attachment, String> {
+    let raw = std::fs::read(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let parsed = email::parse_eml(&raw)?;
+
+    let mut content = String::new();
+    if let Some(ref from) = parsed.from {
+        content.push_str(&format!("From: {}\n", from));
+    }
+    if let Some(ref to) = parsed.to {
+        content.push_str(&format!("To: {}\n", to));
+    }
+    content.push('\n');
+    content.push_str(&parsed.body);
+
+    let attachment = Attachment {
+        id: Uuid::new_v4().to_string(),
+        meeting_id,
+        kind: "email".to_string(),
+        title: parsed.subject,
+        author: parsed.from,
+        sent_at: parsed.sent_at,
+        source_path: Some(path),
+        content,
+        created_at: Utc::now(),
+    };
+
+    state.db.save_attachment(&attachment)
+        .map_err(|e| format!("Failed to save attachment: {}", e))?;
+
+    log::info!("Attached email \"{}\" to meeting {}", attachment.title, attachment.meeting_id);
+    Ok(attachment)
+}
+
+#[tauri::command]
+pub async fn get_attachments(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<Attachment>, String> {
+    state.db.get_attachments(&meeting_id)
+        .map_err(|e| format!("Failed to fetch attachments: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_attachment(attachment_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.delete_attachment(&attachment_id)
+        .map_err(|e| format!("Failed to delete attachment: {}", e))
+}
+
+#[tauri::command]
+pub async fn search_context(
+    query: String,
+    meeting_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ContextSearchHit>, String> {
+    state.db.search_attachments(&query, meeting_id.as_deref())
+        .map_err(|e| format!("Search failed: {}", e))
+}
+
+/// Attachment text formatted for the AI system prompt, oldest first and
+/// truncated to `MAX_CONTEXT_CHARS`
+#[tauri::command]
+pub async fn get_meeting_context(meeting_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let attachments = state.db.get_attachments(&meeting_id)
+        .map_err(|e| format!("Failed to fetch attachments: {}", e))?;
+
+    let mut context = String::new();
+    for attachment in attachments {
+        let sent = attachment.sent_at
+            .map(|d| format!(" ({})", d.format("%Y-%m-%d")))
+            .unwrap_or_default();
+        context.push_str(&format!("### {}{}\n{}\n\n", attachment.title, sent, attachment.content));
+    }
+
+    if context.chars().count() > MAX_CONTEXT_CHARS {
+        context = context.chars().take(MAX_CONTEXT_CHARS).collect();
+        context.push_str("\n[…truncated]");
+    }
+    Ok(context)
 }
 
 // ============================================================
@@ -162,6 +645,45 @@ pub async fn get_notes(meeting_id: String, state: State<'_, AppState>) -> Result
         .map_err(|e| format!("Failed to fetch notes: {}", e))
 }
 
+/// Adds a key point at the current position of the meeting being recorded,
+/// e.g. from a hotkey or a hardware button
+#[tauri::command]
+pub async fn mark_moment(label: Option<String>, state: State<'_, AppState>) -> Result<Note, String> {
+    add_moment(&state, label)
+}
+
+pub fn add_moment(state: &AppState, label: Option<String>) -> Result<Note, String> {
+    let meeting = state.db.get_recording_meeting()
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "No meeting is recording".to_string())?;
+    let now = Utc::now();
+
+    let note = Note {
+        id: Uuid::new_v4().to_string(),
+        meeting_id: meeting.id,
+        note_type: NoteType::KeyPoint,
+        content: label.map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| "Marked moment".to_string()),
+        timestamp: now.signed_duration_since(meeting.start_time).num_milliseconds().max(0),
+        source_refs: vec![],
+        assignee: None,
+        deadline: None,
+        completed: false,
+        completed_at: None,
+        deferred_until: None,
+        needs_review: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    state.db.save_note(&note)
+        .map_err(|e| format!("Failed to save note: {}", e))?;
+
+    log::info!("Marked a moment in meeting {}", note.meeting_id);
+    Ok(note)
+}
+
 #[tauri::command]
 pub async fn add_note(
     meeting_id: String,
@@ -180,6 +702,9 @@ pub async fn add_note(
         assignee: None,
         deadline: None,
         completed: false,
+        completed_at: None,
+        deferred_until: None,
+        needs_review: false,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -211,161 +736,857 @@ pub async fn delete_note(note_id: String, state: State<'_, AppState>) -> Result<
         .map_err(|e| format!("Failed to delete note: {}", e))
 }
 
+/// Keep an auto-created note; dismissing one is a plain `delete_note`
+#[tauri::command]
+pub async fn confirm_auto_note(note_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.mark_note_reviewed(&note_id)
+        .map_err(|e| format!("Failed to update note: {}", e))
+}
+
 // ============================================================
-// Export Commands
+// Speaker Alias Commands
 // ============================================================
 
-#[derive(Serialize)]
-pub struct ExportResult {
-    pub file_path: String,
-    pub content: String,
+#[tauri::command]
+pub async fn get_speaker_aliases(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<SpeakerAlias>, String> {
+    state.db.get_speaker_aliases(&meeting_id)
+        .map_err(|e| format!("Failed to fetch speaker aliases: {}", e))
 }
 
+/// Sets the role or alias a speaker is shown under in anonymized exports;
+/// an empty alias clears it
 #[tauri::command]
-pub async fn export_meeting_markdown(
-    meeting_id: String, 
-    include_transcript: bool,
-    include_notes: bool,
-    include_summary: Option<String>,
+pub async fn set_speaker_alias(
+    meeting_id: String,
+    speaker_id: String,
+    alias: String,
     state: State<'_, AppState>,
-) -> Result<ExportResult, String> {
-    // Get meeting data
-    let meeting = state.db.get_meeting(&meeting_id)
-        .map_err(|e| format!("Database error: {}", e))?
-        .ok_or_else(|| "Meeting not found".to_string())?;
+) -> Result<(), String> {
+    let alias = alias.trim();
+    if alias.is_empty() {
+        return state.db.delete_speaker_alias(&meeting_id, &speaker_id)
+            .map_err(|e| format!("Failed to clear speaker alias: {}", e));
+    }
 
-    let transcript = if include_transcript {
-        state.db.get_transcript(&meeting_id)
-            .map_err(|e| format!("Failed to fetch transcript: {}", e))?
-    } else {
-        vec![]
-    };
+    let alias = SpeakerAlias { meeting_id, speaker_id, alias: alias.to_string() };
+    state.db.set_speaker_alias(&alias)
+        .map_err(|e| format!("Failed to save speaker alias: {}", e))
+}
 
-    let notes = if include_notes {
-        state.db.get_notes(&meeting_id)
-            .map_err(|e| format!("Failed to fetch notes: {}", e))?
-    } else {
-        vec![]
-    };
+// ============================================================
+// Annotation Commands
+// ============================================================
 
-    // Generate markdown content
-    let mut md = String::new();
-    
-    // Title
-    md.push_str(&format!("# {}\n\n", meeting.title));
-    
-    // Metadata
-    md.push_str("## Meeting Information\n\n");
-    md.push_str(&format!("- **Date**: {}\n", meeting.start_time.format("%B %d, %Y")));
-    md.push_str(&format!("- **Start Time**: {}\n", meeting.start_time.format("%H:%M")));
-    if let Some(end) = meeting.end_time {
-        md.push_str(&format!("- **End Time**: {}\n", end.format("%H:%M")));
-        let duration = end.signed_duration_since(meeting.start_time);
-        let hours = duration.num_hours();
-        let mins = duration.num_minutes() % 60;
-        if hours > 0 {
-            md.push_str(&format!("- **Duration**: {}h {}m\n", hours, mins));
-        } else {
-            md.push_str(&format!("- **Duration**: {}m\n", mins));
-        }
+#[tauri::command]
+pub async fn get_annotations(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<Annotation>, String> {
+    state.db.get_annotations(&meeting_id)
+        .map_err(|e| format!("Failed to fetch annotations: {}", e))
+}
+
+/// Private remark on a transcript entry, kept out of exports and
+/// integrations unless they ask for annotations explicitly
+#[tauri::command]
+pub async fn add_annotation(
+    meeting_id: String,
+    transcript_entry_id: String,
+    content: String,
+    state: State<'_, AppState>,
+) -> Result<Annotation, String> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err("Annotation cannot be empty".to_string());
     }
-    md.push_str(&format!("- **Participants**: {}\n", 
-        if meeting.participants.is_empty() { 
-            "N/A".to_string() 
-        } else { 
-            meeting.participants.iter().map(|p| p.name.clone()).collect::<Vec<_>>().join(", ")
-        }
-    ));
-    md.push_str("\n---\n\n");
 
-    // Summary (if provided)
-    if let Some(summary) = include_summary {
-        md.push_str("## Summary\n\n");
-        md.push_str(&summary);
-        md.push_str("\n\n---\n\n");
-    }
-
-    // Notes
-    if !notes.is_empty() {
-        md.push_str("## Notes\n\n");
-        
-        // Group notes by type
-        let action_items: Vec<_> = notes.iter().filter(|n| matches!(n.note_type, NoteType::ActionItem)).collect();
-        let key_points: Vec<_> = notes.iter().filter(|n| matches!(n.note_type, NoteType::KeyPoint)).collect();
-        let decisions: Vec<_> = notes.iter().filter(|n| matches!(n.note_type, NoteType::Decision)).collect();
-        let other_notes: Vec<_> = notes.iter().filter(|n| !matches!(n.note_type, NoteType::ActionItem | NoteType::KeyPoint | NoteType::Decision)).collect();
-
-        if !action_items.is_empty() {
-            md.push_str("### Action Items\n\n");
-            for note in action_items {
-                let checkbox = if note.completed { "[x]" } else { "[ ]" };
-                md.push_str(&format!("- {} {}", checkbox, note.content));
-                if let Some(ref assignee) = note.assignee {
-                    md.push_str(&format!(" *({})*", assignee));
-                }
-                md.push('\n');
-            }
-            md.push('\n');
-        }
+    let annotation = Annotation {
+        id: Uuid::new_v4().to_string(),
+        meeting_id,
+        transcript_entry_id,
+        content: content.to_string(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
 
-        if !key_points.is_empty() {
-            md.push_str("### Key Points\n\n");
-            for note in key_points {
-                md.push_str(&format!("- {}\n", note.content));
-            }
-            md.push('\n');
-        }
+    state.db.save_annotation(&annotation)
+        .map_err(|e| format!("Failed to save annotation: {}", e))?;
 
-        if !decisions.is_empty() {
-            md.push_str("### Decisions\n\n");
-            for note in decisions {
-                md.push_str(&format!("- {}\n", note.content));
-            }
-            md.push('\n');
-        }
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub async fn update_annotation(
+    annotation_id: String,
+    content: String,
+    state: State<'_, AppState>,
+) -> Result<Annotation, String> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err("Annotation cannot be empty".to_string());
+    }
+
+    let mut annotation = state.db.get_annotation(&annotation_id)
+        .map_err(|e| format!("Failed to fetch annotation: {}", e))?
+        .ok_or_else(|| "Annotation not found".to_string())?;
+    annotation.content = content.to_string();
+    annotation.updated_at = Utc::now();
+
+    state.db.save_annotation(&annotation)
+        .map_err(|e| format!("Failed to update annotation: {}", e))?;
+
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub async fn delete_annotation(annotation_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.delete_annotation(&annotation_id)
+        .map_err(|e| format!("Failed to delete annotation: {}", e))
+}
+
+// ============================================================
+// Bookmark Commands
+// ============================================================
+
+/// Marks a moment `timestamp` milliseconds into the meeting to revisit
+#[tauri::command]
+pub async fn add_bookmark(
+    meeting_id: String,
+    timestamp: i64,
+    label: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Bookmark, String> {
+    if timestamp < 0 {
+        return Err("Bookmark timestamp cannot be negative".to_string());
+    }
+    state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+
+    let bookmark = Bookmark {
+        id: Uuid::new_v4().to_string(),
+        meeting_id,
+        timestamp,
+        label: label.map(|label| label.trim().to_string()).filter(|label| !label.is_empty()),
+        transcript_entry_id: None,
+        created_at: Utc::now(),
+    };
+    state.db.save_bookmark(&bookmark)
+        .map_err(|e| format!("Failed to save bookmark: {}", e))?;
+
+    // Read back so the transcript entry to jump to is filled in
+    let bookmarks = state.db.get_bookmarks(&bookmark.meeting_id)
+        .map_err(|e| format!("Failed to fetch bookmarks: {}", e))?;
+    Ok(bookmarks.into_iter().find(|b| b.id == bookmark.id).unwrap_or(bookmark))
+}
+
+/// The meeting's bookmarks in playback order, for a jump list
+#[tauri::command]
+pub async fn list_bookmarks(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<Bookmark>, String> {
+    state.db.get_bookmarks(&meeting_id)
+        .map_err(|e| format!("Failed to fetch bookmarks: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_bookmark(bookmark_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let deleted = state.db.delete_bookmark(&bookmark_id)
+        .map_err(|e| format!("Failed to delete bookmark: {}", e))?;
+    if !deleted {
+        return Err("Bookmark not found".to_string());
+    }
+    Ok(())
+}
+
+// ============================================================
+// Trigger Phrase Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn get_trigger_phrases(state: State<'_, AppState>) -> Result<Vec<TriggerPhrase>, String> {
+    state.db.get_trigger_phrases()
+        .map_err(|e| format!("Failed to fetch trigger phrases: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_trigger_phrase(
+    phrase_id: Option<String>,
+    phrase: String,
+    note_type: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<TriggerPhrase, String> {
+    if phrase.trim().is_empty() {
+        return Err("Trigger phrase cannot be empty".to_string());
+    }
+
+    let trigger = TriggerPhrase {
+        id: phrase_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+        phrase: phrase.trim().to_string(),
+        note_type: NoteType::from_str(&note_type),
+        enabled,
+    };
+
+    state.db.save_trigger_phrase(&trigger)
+        .map_err(|e| format!("Failed to save trigger phrase: {}", e))?;
+    Ok(trigger)
+}
+
+#[tauri::command]
+pub async fn delete_trigger_phrase(phrase_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.delete_trigger_phrase(&phrase_id)
+        .map_err(|e| format!("Failed to delete trigger phrase: {}", e))
+}
+
+// ============================================================
+// Meeting Metadata Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn get_metadata_fields(state: State<'_, AppState>) -> Result<Vec<MetadataField>, String> {
+    state.db.get_metadata_fields()
+        .map_err(|e| format!("Failed to fetch metadata fields: {}", e))
+}
+
+/// Defines a new field, or renames or retypes one. Retyping clears the
+/// field's values.
+#[tauri::command]
+pub async fn save_metadata_field(
+    field_id: Option<String>,
+    name: String,
+    field_type: MetadataFieldType,
+    state: State<'_, AppState>,
+) -> Result<MetadataField, String> {
+    if name.trim().is_empty() {
+        return Err("Field name cannot be empty".to_string());
+    }
+
+    let field = MetadataField {
+        id: field_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+        name: name.trim().to_string(),
+        field_type,
+    };
+
+    state.db.save_metadata_field(&field)
+        .map_err(|e| format!("Failed to save metadata field: {}", e))?;
+    Ok(field)
+}
+
+#[tauri::command]
+pub async fn delete_metadata_field(field_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.delete_metadata_field(&field_id)
+        .map_err(|e| format!("Failed to delete metadata field: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_meeting_metadata(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<MetadataValue>, String> {
+    state.db.get_meeting_metadata(&meeting_id)
+        .map_err(|e| format!("Failed to fetch meeting metadata: {}", e))
+}
+
+/// Sets a meeting's value for a field; a missing or blank value clears it
+#[tauri::command]
+pub async fn set_meeting_metadata(
+    meeting_id: String,
+    field_id: String,
+    value: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<MetadataValue>, String> {
+    let field = state.db.get_metadata_field(&field_id)
+        .map_err(|e| format!("Failed to fetch metadata field: {}", e))?
+        .ok_or_else(|| "Metadata field not found".to_string())?;
+    let value = metadata::normalize(field.field_type, value.as_deref().unwrap_or_default())?;
+
+    state.db.set_meeting_metadata(&meeting_id, &field_id, value.as_deref())
+        .map_err(|e| format!("Failed to save meeting metadata: {}", e))?;
+    state.db.get_meeting_metadata(&meeting_id)
+        .map_err(|e| format!("Failed to fetch meeting metadata: {}", e))
+}
+
+// ============================================================
+// Inbox Commands
+// ============================================================
 
-        if !other_notes.is_empty() {
-            md.push_str("### Other Notes\n\n");
-            for note in other_notes {
-                md.push_str(&format!("- {}\n", note.content));
+/// Action items across meetings. Pass `assignee: "me"` for the user's own
+/// items, resolved through the `my_name` setting.
+#[tauri::command]
+pub async fn list_action_items(
+    assignee: Option<String>,
+    include_completed: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<InboxItem>, String> {
+    let assignee = match assignee {
+        Some(a) if a.eq_ignore_ascii_case("me") => Some(inbox::my_name(&state.db)?),
+        other => other,
+    };
+
+    state.db.list_action_items(assignee.as_deref(), include_completed.unwrap_or(false), None)
+        .map_err(|e| format!("Failed to fetch action items: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_inbox(state: State<'_, AppState>) -> Result<Vec<InboxItem>, String> {
+    inbox::inbox_items(&state.db)
+}
+
+#[tauri::command]
+pub async fn complete_action_item(note_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.update_note(&note_id, None, Some(true))
+        .map_err(|e| format!("Failed to complete action item: {}", e))
+}
+
+/// Hide an action item from the inbox until `until`; `None` brings it back now
+#[tauri::command]
+pub async fn defer_action_item(
+    note_id: String,
+    until: Option<DateTime<Utc>>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.db.defer_note(&note_id, until)
+        .map_err(|e| format!("Failed to defer action item: {}", e))
+}
+
+// ============================================================
+// Note Template Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn get_note_templates(state: State<'_, AppState>) -> Result<Vec<NoteTemplate>, String> {
+    state.db.get_note_templates()
+        .map_err(|e| format!("Failed to fetch note templates: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_note_template(
+    template_id: Option<String>,
+    name: String,
+    note_type: String,
+    body: String,
+    state: State<'_, AppState>,
+) -> Result<NoteTemplate, String> {
+    if name.trim().is_empty() || body.trim().is_empty() {
+        return Err("Template name and body are required".to_string());
+    }
+
+    let existing = match template_id {
+        Some(ref id) => state.db.get_note_template(id)
+            .map_err(|e| format!("Database error: {}", e))?,
+        None => None,
+    };
+
+    let template = NoteTemplate {
+        id: template_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+        name,
+        note_type: NoteType::from_str(&note_type),
+        variables: NoteTemplate::extract_variables(&body),
+        body,
+        created_at: existing.map(|t| t.created_at).unwrap_or_else(Utc::now),
+        updated_at: Utc::now(),
+    };
+
+    state.db.save_note_template(&template)
+        .map_err(|e| format!("Failed to save note template: {}", e))?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub async fn delete_note_template(template_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.db.delete_note_template(&template_id)
+        .map_err(|e| format!("Failed to delete note template: {}", e))
+}
+
+#[tauri::command]
+pub async fn add_note_from_template(
+    meeting_id: String,
+    template_id: String,
+    vars: HashMap<String, String>,
+    timestamp: i64,
+    state: State<'_, AppState>,
+) -> Result<Note, String> {
+    let template = state.db.get_note_template(&template_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Note template not found".to_string())?;
+
+    let note = Note {
+        id: Uuid::new_v4().to_string(),
+        meeting_id,
+        note_type: template.note_type.clone(),
+        content: template.render(&vars)?,
+        timestamp,
+        source_refs: vec![],
+        assignee: None,
+        deadline: None,
+        completed: false,
+        completed_at: None,
+        deferred_until: None,
+        needs_review: false,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    state.db.save_note(&note)
+        .map_err(|e| format!("Failed to save note: {}", e))?;
+
+    Ok(note)
+}
+
+// ============================================================
+// Export Commands
+// ============================================================
+
+#[derive(Serialize)]
+pub struct ExportResult {
+    pub file_path: String,
+    pub content: String,
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_meeting_markdown(
+    meeting_id: String, 
+    include_transcript: bool,
+    include_notes: bool,
+    include_summary: Option<String>,
+    include_annotations: Option<bool>,
+    anonymize: Option<bool>,
+    integration_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ExportResult, String> {
+    // Get meeting data
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+
+    let options = ExportOptions {
+        include_transcript,
+        include_notes,
+        include_summary,
+        include_annotations: include_annotations.unwrap_or(false),
+        anonymize: anonymize.unwrap_or(false),
+        index_link: None,
+    };
+    authorize_scopes(&state.db, integration_id.as_deref(), &options.scopes())?;
+    let mut md = Vec::new();
+    export::write_meeting(&state.db, &meeting, ExportFormat::Markdown, &options, &mut md, |_, _, _| {})?;
+
+    Ok(ExportResult {
+        file_path: export::file_name(&meeting.title, "", ExportFormat::Markdown),
+        content: String::from_utf8_lossy(&md).into_owned(),
+    })
+}
+
+/// Writes the export straight to `file_path` in chunks, emitting
+/// `export-progress` events as it goes. Suited to long meetings whose
+/// documents are too large to pass around in memory.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_meeting_to_file(
+    meeting_id: String,
+    format: ExportFormat,
+    file_path: String,
+    include_transcript: bool,
+    include_notes: bool,
+    include_summary: Option<String>,
+    include_annotations: Option<bool>,
+    anonymize: Option<bool>,
+    integration_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ExportFileResult, String> {
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+
+    let options = ExportOptions {
+        include_transcript,
+        include_notes,
+        include_summary,
+        include_annotations: include_annotations.unwrap_or(false),
+        anonymize: anonymize.unwrap_or(false),
+        index_link: None,
+    };
+    authorize_scopes(&state.db, integration_id.as_deref(), &options.scopes())?;
+    let db = Arc::clone(&state.db);
+    tauri::async_runtime::spawn_blocking(move || {
+        export::export_to_file(&db, &meeting, format, &options, Path::new(&file_path), |progress| {
+            if let Err(e) = app.emit_all(export::PROGRESS_EVENT, progress) {
+                log::warn!("Failed to emit export progress: {}", e);
             }
-            md.push('\n');
-        }
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-        md.push_str("---\n\n");
+/// Writes every completed meeting into `dest_dir` as a static site with
+/// index pages by date, series, tag and participant. Transcripts are
+/// included unless `include_transcripts` is false.
+#[tauri::command]
+pub async fn export_knowledge_base(
+    dest_dir: String,
+    include_transcripts: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<KnowledgeBaseResult, String> {
+    if dest_dir.trim().is_empty() {
+        return Err("Choose a folder to export to".to_string());
     }
+    let db = Arc::clone(&state.db);
+    tauri::async_runtime::spawn_blocking(move || {
+        knowledge_base::export_knowledge_base(&db, Path::new(dest_dir.trim()), include_transcripts.unwrap_or(true))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    // Transcript
-    if !transcript.is_empty() {
-        md.push_str("## Transcript\n\n");
-        
-        for entry in &transcript {
-            let time = format_timestamp(entry.timestamp);
-            md.push_str(&format!("**[{}] {}**: {}\n\n", time, entry.speaker_name, entry.text));
-        }
+/// Serves the meeting's minutes read-only at a tokenized URL for
+/// `ttl_minutes` (default 60, at most a day). With `lan` the link works from
+/// other machines on the network, otherwise only on this one.
+#[tauri::command]
+pub async fn share_meeting(
+    meeting_id: String,
+    ttl_minutes: Option<u32>,
+    lan: Option<bool>,
+    integration_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ShareLink, String> {
+    authorize_scopes(&state.db, integration_id.as_deref(), &share::SCOPES)?;
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+
+    let ttl_minutes = ttl_minutes.unwrap_or(share::DEFAULT_TTL_MINUTES).clamp(1, share::MAX_TTL_MINUTES);
+    share::share_meeting(&state.shares, &state.db, &meeting, ttl_minutes, lan.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn get_active_shares(state: State<'_, AppState>) -> Result<Vec<ShareLink>, String> {
+    Ok(state.shares.list())
+}
+
+/// Stops serving a share link before it expires
+#[tauri::command]
+pub async fn stop_sharing(share_id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.shares.stop(&share_id))
+}
+
+/// Structured diff of decisions and action items between two occurrences
+/// of a meeting series; the earlier meeting is treated as the baseline.
+#[tauri::command]
+pub async fn diff_meetings(
+    meeting_a: String,
+    meeting_b: String,
+    state: State<'_, AppState>,
+) -> Result<MeetingDiff, String> {
+    let load = |meeting_id: &str| -> Result<(Meeting, Vec<Note>), String> {
+        let meeting = state.db.get_meeting(meeting_id)
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| format!("Meeting not found: {}", meeting_id))?;
+        let notes = state.db.get_notes(meeting_id)
+            .map_err(|e| format!("Failed to fetch notes: {}", e))?;
+        Ok((meeting, notes))
+    };
+
+    let (mut a, mut b) = (load(&meeting_a)?, load(&meeting_b)?);
+    if a.0.start_time > b.0.start_time {
+        std::mem::swap(&mut a, &mut b);
     }
 
-    // Footer
+    Ok(analytics::diff_meetings(a.0, &a.1, b.0, &b.1))
+}
+
+/// Redlined minutes for `diff_meetings`: new items in bold, resolved items
+/// struck through, carried-over items as-is.
+#[tauri::command]
+pub async fn export_meeting_diff_markdown(
+    meeting_a: String,
+    meeting_b: String,
+    state: State<'_, AppState>,
+) -> Result<ExportResult, String> {
+    let locale = Locale::from_settings(&state.db);
+    let diff = diff_meetings(meeting_a, meeting_b, state).await?;
+
+    let mut md = String::new();
+    md.push_str(&format!("# {} — changes since {}\n\n", diff.meeting_b.title, locale.format_date(diff.meeting_a.start_time)));
+    md.push_str(&format!("- **Previous**: {} ({})\n", diff.meeting_a.title, diff.meeting_a.start_time.format("%Y-%m-%d")));
+    md.push_str(&format!("- **Current**: {} ({})\n", diff.meeting_b.title, diff.meeting_b.start_time.format("%Y-%m-%d")));
     md.push_str("\n---\n\n");
+
+    for (heading, section, open_label) in [
+        ("Action Items", &diff.action_items, "Still Open"),
+        ("Decisions", &diff.decisions, "Revisited"),
+    ] {
+        md.push_str(&format!("## {}\n\n", heading));
+        if section.new.is_empty() && section.resolved.is_empty() && section.still_open.is_empty() {
+            md.push_str("*No changes*\n\n");
+            continue;
+        }
+        for (label, notes, style) in [
+            ("New", &section.new, "**"),
+            ("Resolved", &section.resolved, "~~"),
+            (open_label, &section.still_open, ""),
+        ] {
+            if notes.is_empty() {
+                continue;
+            }
+            md.push_str(&format!("### {}\n\n", label));
+            for note in notes {
+                md.push_str(&format!("- {}{}{}", style, note.content, style));
+                if let Some(ref assignee) = note.assignee {
+                    md.push_str(&format!(" *({})*", assignee));
+                }
+                md.push('\n');
+            }
+            md.push('\n');
+        }
+    }
+
+    md.push_str("---\n\n");
     md.push_str(&format!("*Exported from Meeting Assistant on {}*\n", Utc::now().format("%Y-%m-%d %H:%M UTC")));
 
     Ok(ExportResult {
-        file_path: format!("{}.md", meeting.title.replace(" ", "_").replace("/", "-")),
+        file_path: export::file_name(&diff.meeting_b.title, "_changes", ExportFormat::Markdown),
         content: md,
     })
 }
 
-fn format_timestamp(ms: i64) -> String {
-    let total_secs = ms / 1000;
-    let hours = total_secs / 3600;
-    let mins = (total_secs % 3600) / 60;
-    let secs = total_secs % 60;
 
-    if hours > 0 {
-        format!("{:02}:{:02}:{:02}", hours, mins, secs)
-    } else {
-        format!("{:02}:{:02}", mins, secs)
+// ============================================================
+// Integration Permission Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn get_integration_permissions(
+    integration_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<IntegrationPermissions>, String> {
+    state.db.get_integration_permissions(integration_id.as_deref())
+        .map_err(|e| format!("Failed to fetch integration permissions: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_integration_permission(
+    integration_id: String,
+    scope: DataScope,
+    granted: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.db.set_integration_permission(&integration_id, scope, granted)
+        .map_err(|e| format!("Failed to save integration permission: {}", e))?;
+
+    log::info!("Integration {} scope {} granted={}", integration_id, scope.as_str(), granted);
+    Ok(())
+}
+
+/// Gate called by integration modules before they read or send any data
+#[tauri::command]
+pub async fn authorize_integration_access(
+    integration_id: String,
+    scope: DataScope,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    authorize_scopes(&state.db, Some(&integration_id), &[scope])
+}
+
+/// Fails unless the integration asking, if any, was granted every scope.
+/// Commands that hand data out take an `integration_id` for this, so an
+/// integration can't read more than it was granted by invoking them itself.
+fn authorize_scopes(db: &Database, integration_id: Option<&str>, scopes: &[DataScope]) -> Result<(), String> {
+    let Some(integration_id) = integration_id else {
+        return Ok(());
+    };
+    for &scope in scopes {
+        let granted = db.is_scope_granted(integration_id, scope)
+            .map_err(|e| format!("Failed to check integration permission: {}", e))?;
+        if !granted {
+            return Err(format!("Integration '{}' has not been granted access to {}", integration_id, scope.as_str()));
+        }
+    }
+    Ok(())
+}
+
+// ============================================================
+// Plugin Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn get_plugins(state: State<'_, AppState>) -> Result<Vec<Plugin>, String> {
+    state.db.get_plugins()
+        .map_err(|e| format!("Failed to fetch plugins: {}", e))
+}
+
+/// Installs the plugin described by the `plugin.json` at `manifest_path`,
+/// or updates it. New plugins start disabled, with their permissions
+/// waiting for the user under the plugin's `integration_id`.
+#[tauri::command]
+pub async fn install_plugin(manifest_path: String, state: State<'_, AppState>) -> Result<Plugin, String> {
+    let (db, host, app_dir) = (Arc::clone(&state.db), Arc::clone(&state.plugins), app_data_dir(&state));
+    tauri::async_runtime::spawn_blocking(move || plugins::install(&db, &host, &app_dir, Path::new(&manifest_path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn set_plugin_enabled(plugin_id: String, enabled: bool, state: State<'_, AppState>) -> Result<(), String> {
+    let found = state.db.set_plugin_enabled(&plugin_id, enabled)
+        .map_err(|e| format!("Failed to update plugin: {}", e))?;
+    if !found {
+        return Err("Plugin not found".to_string());
+    }
+
+    log::info!("Plugin {} enabled={}", plugin_id, enabled);
+    Ok(())
+}
+
+/// Removes the plugin, its module and its permissions
+#[tauri::command]
+pub async fn uninstall_plugin(plugin_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    plugins::uninstall(&state.db, &state.plugins, &app_data_dir(&state), &plugin_id)
+}
+
+/// Exports the meeting to `file_path` in an exporter plugin's format
+#[tauri::command]
+pub async fn export_meeting_with_plugin(
+    meeting_id: String,
+    plugin_id: String,
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<PluginExportResult, String> {
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+
+    let (db, host, app_dir) = (Arc::clone(&state.db), Arc::clone(&state.plugins), app_data_dir(&state));
+    tauri::async_runtime::spawn_blocking(move || {
+        plugins::export(&db, &host, &app_dir, &plugin_id, &meeting, Path::new(&file_path))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================================
+// Analytics Commands
+// ============================================================
+
+/// Action-item follow-through for meetings starting within the optional range
+#[tauri::command]
+pub async fn get_followthrough_stats(
+    range_start: Option<DateTime<Utc>>,
+    range_end: Option<DateTime<Utc>>,
+    state: State<'_, AppState>,
+) -> Result<FollowthroughStats, String> {
+    let items = state.db.get_action_items_with_meeting(range_start, range_end)
+        .map_err(|e| format!("Failed to fetch action items: {}", e))?;
+    Ok(analytics::followthrough_stats(&items))
+}
+
+/// Meeting count and time per value of a metadata field, for meetings
+/// starting within the optional range
+#[tauri::command]
+pub async fn get_metadata_breakdown(
+    field_id: String,
+    range_start: Option<DateTime<Utc>>,
+    range_end: Option<DateTime<Utc>>,
+    state: State<'_, AppState>,
+) -> Result<MetadataBreakdown, String> {
+    metadata::breakdown(&state.db, &field_id, range_start, range_end)
+}
+
+// ============================================================
+// Query Console Commands
+// ============================================================
+
+#[tauri::command]
+pub async fn run_readonly_query(sql: String, state: State<'_, AppState>) -> Result<QueryResult, String> {
+    log::info!("Running read-only query: {}", sql);
+    let db_path = state.db_path.clone();
+    tauri::async_runtime::spawn_blocking(move || db::run_readonly_query(&db_path, &sql))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ============================================================
+// Workspace Check Commands
+// ============================================================
+
+/// Reports orphaned rows, missing audio files and search index drift, and
+/// fixes them when `repair` is set
+#[tauri::command]
+pub async fn check_workspace(repair: Option<bool>, state: State<'_, AppState>) -> Result<WorkspaceReport, String> {
+    let repair = repair.unwrap_or(false);
+    let report = state.db.check_workspace(repair)
+        .map_err(|e| format!("Failed to check workspace: {}", e))?;
+    if repair {
+        log::info!(
+            "Repaired workspace: {} orphaned rows, {} orphaned annotations, {} missing audio files",
+            report.orphaned_rows.iter().map(|orphans| orphans.count).sum::<usize>(),
+            report.orphaned_annotations,
+            report.missing_audio.len()
+        );
     }
+    Ok(report)
+}
+
+// ============================================================
+// Configuration Profile Commands
+// ============================================================
+
+/// Writes the configuration to `path` as a JSON profile, without API keys.
+/// `app_settings` are the frontend's own settings, carried along as they are.
+#[tauri::command]
+pub async fn export_profile(
+    path: String,
+    app_settings: Option<serde_json::Value>,
+    state: State<'_, AppState>,
+) -> Result<ConfigProfile, String> {
+    let profile = profile::export(&state.db, app_settings)?;
+    let json = serde_json::to_string_pretty(&profile)
+        .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write profile: {}", e))?;
+    log::info!("Exported configuration profile to {}", path);
+    Ok(profile)
+}
+
+/// Applies a profile; the frontend settings it carried come back in the
+/// result for the frontend to apply
+#[tauri::command]
+pub async fn import_profile(path: String, state: State<'_, AppState>) -> Result<ProfileImportResult, String> {
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read profile: {}", e))?;
+    let config: ConfigProfile = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse profile: {}", e))?;
+    let result = profile::import(&state.db, config)?;
+    log::info!("Imported configuration profile from {}", path);
+    Ok(result)
+}
+
+// ============================================================
+// Calendar & Meeting Load Commands
+// ============================================================
+
+/// Imports the events of an .ics file, replacing earlier imports of the
+/// same events
+#[tauri::command]
+pub async fn import_calendar(path: String, app: AppHandle, state: State<'_, AppState>) -> Result<CalendarImportResult, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read calendar: {}", e))?;
+    let (events, skipped) = calendar::parse_ics(&content);
+    let imported = state.db.save_calendar_events(&events)
+        .map_err(|e| format!("Failed to save calendar events: {}", e))?;
+    log::info!("Imported {} calendar events from {} ({} skipped)", imported, path, skipped);
+
+    if let Err(e) = meeting_load::warn_if_overloaded(&app) {
+        log::warn!("Meeting load check failed: {}", e);
+    }
+    Ok(CalendarImportResult { imported, skipped })
+}
+
+/// Meeting load for the week containing `week` ("YYYY-MM-DD"), by default
+/// this week
+#[tauri::command]
+pub async fn get_meeting_load_forecast(week: Option<String>, state: State<'_, AppState>) -> Result<MeetingLoadForecast, String> {
+    let date = match week {
+        Some(week) => chrono::NaiveDate::parse_from_str(week.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date \"{}\", expected YYYY-MM-DD", week))?,
+        None => chrono::Local::now().date_naive(),
+    };
+    meeting_load::forecast(&state.db, date)
 }
 
 // ============================================================
@@ -394,6 +1615,133 @@ pub async fn ask_ai(
     })
 }
 
+/// Chat completion through the provider fallback chain: `primary` first,
+/// then the providers configured in `ai_provider_chain`
+#[tauri::command]
+pub async fn ai_chat(
+    messages: Vec<ChatMessage>,
+    primary: Option<ProviderConfig>,
+    max_tokens: Option<u32>,
+    temperature: Option<f64>,
+    purpose: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<AiCompletion, String> {
+    let chain = ai::resolve_chain(primary, ai::load_chain(&state.db));
+    let request = ChatRequest {
+        messages,
+        max_tokens: max_tokens.unwrap_or(1000),
+        temperature: temperature.unwrap_or(0.7),
+        purpose: purpose.unwrap_or_else(|| "chat".to_string()),
+    };
+    ai::complete(&state.db, &chain, &request).await
+}
+
+#[tauri::command]
+pub async fn get_ai_provider_chain(state: State<'_, AppState>) -> Result<Vec<ProviderConfig>, String> {
+    Ok(ai::load_chain(&state.db))
+}
+
+#[tauri::command]
+pub async fn set_ai_provider_chain(chain: Vec<ProviderConfig>, state: State<'_, AppState>) -> Result<(), String> {
+    let json = serde_json::to_string(&chain)
+        .map_err(|e| format!("Failed to serialize provider chain: {}", e))?;
+    state.db.set_setting(ai::PROVIDER_CHAIN_KEY, &json)
+        .map_err(|e| format!("Failed to save provider chain: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_ai_usage(
+    since: Option<DateTime<Utc>>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AiUsageRecord>, String> {
+    state.db.get_ai_usage(since)
+        .map_err(|e| format!("Failed to fetch AI usage: {}", e))
+}
+
+/// Summarizes a meeting with the current prompt, stored as a new version
+#[tauri::command]
+pub async fn generate_summary(
+    meeting_id: String,
+    primary: Option<ProviderConfig>,
+    state: State<'_, AppState>,
+) -> Result<SummaryVersion, String> {
+    let meeting = state.db.get_meeting(&meeting_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Meeting not found".to_string())?;
+    let chain = ai::resolve_chain(primary, ai::load_chain(&state.db));
+    summaries::summarize(&state.db, &chain, &meeting).await
+}
+
+#[tauri::command]
+pub async fn get_summary_versions(meeting_id: String, state: State<'_, AppState>) -> Result<Vec<SummaryVersion>, String> {
+    state.db.get_summary_versions(&meeting_id)
+        .map_err(|e| format!("Failed to fetch summaries: {}", e))
+}
+
+/// Bulk re-summarization with the latest prompt and `primary` model (or the
+/// head of the configured chain), keeping earlier versions for comparison
+#[tauri::command]
+pub async fn regenerate_summaries(
+    filter: Option<SummaryFilter>,
+    primary: Option<ProviderConfig>,
+    state: State<'_, AppState>,
+) -> Result<RegenerateSummariesResult, String> {
+    let chain = ai::resolve_chain(primary, ai::load_chain(&state.db));
+    summaries::regenerate(&state.db, &chain, &filter.unwrap_or_default()).await
+}
+
+/// Queues `operations` for every finished meeting that hasn't had them yet
+/// and processes them in the background with `primary` (or the head of the
+/// configured chain). Progress is emitted as `backlog-progress` events.
+#[tauri::command]
+pub async fn process_backlog(
+    operations: Vec<BacklogOperation>,
+    primary: Option<ProviderConfig>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BacklogProgress, String> {
+    let chain = ai::resolve_chain(primary, ai::load_chain(&state.db));
+    backlog::start(&app, chain, operations)
+}
+
+#[tauri::command]
+pub async fn get_backlog_progress(state: State<'_, AppState>) -> Result<Option<BacklogProgress>, String> {
+    Ok(state.backlog.progress())
+}
+
+/// Stops queued backlog jobs from starting; returns whether a run was going
+#[tauri::command]
+pub async fn cancel_backlog(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.backlog.cancel())
+}
+
+#[tauri::command]
+pub async fn get_budget_status(state: State<'_, AppState>) -> Result<Vec<BudgetStatus>, String> {
+    budget::budget_status(&state.db)
+}
+
+/// Sets the monthly budget in USD for a provider; `None` removes the limit
+#[tauri::command]
+pub async fn set_ai_budget(
+    provider: String,
+    monthly_usd: Option<f64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<BudgetStatus>, String> {
+    let mut budgets = budget::load_budgets(&state.db);
+    match monthly_usd {
+        Some(amount) if amount >= 0.0 => { budgets.insert(provider, amount); }
+        Some(_) => return Err("Budget must not be negative".to_string()),
+        None => { budgets.remove(&provider); }
+    }
+
+    let json = serde_json::to_string(&budgets)
+        .map_err(|e| format!("Failed to serialize budgets: {}", e))?;
+    state.db.set_setting(budget::BUDGETS_KEY, &json)
+        .map_err(|e| format!("Failed to save budget: {}", e))?;
+
+    budget::budget_status(&state.db)
+}
+
 // ============================================================
 // Audio Commands
 // ============================================================
@@ -417,6 +1765,44 @@ pub async fn set_audio_sources(source_ids: Vec<String>) -> Result<(), String> {
     Ok(())
 }
 
+/// Moves a recording the frontend wrote to `file_path` into the configured
+/// audio storage and attaches it to the meeting; returns its location
+#[tauri::command]
+pub async fn save_meeting_audio(meeting_id: String, file_path: String, state: State<'_, AppState>) -> Result<String, String> {
+    let db = Arc::clone(&state.db);
+    let app_dir = app_data_dir(&state);
+    tauri::async_runtime::spawn_blocking(move || audio_store::save(&db, &app_dir, &meeting_id, Path::new(&file_path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// A local file to play the meeting's recording from, downloaded first when
+/// it is kept in a bucket
+#[tauri::command]
+pub async fn get_meeting_audio_file(meeting_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let db = Arc::clone(&state.db);
+    let app_dir = app_data_dir(&state);
+    tauri::async_runtime::spawn_blocking(move || audio_store::local_file(&db, &app_dir, &meeting_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Moves every recording to `target` storage ("local" or "s3") and keeps
+/// new recordings there
+#[tauri::command]
+pub async fn relocate_audio(target: String, state: State<'_, AppState>) -> Result<RelocateAudioResult, String> {
+    let db = Arc::clone(&state.db);
+    let app_dir = app_data_dir(&state);
+    tauri::async_runtime::spawn_blocking(move || audio_store::relocate(&db, &app_dir, target.trim()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn app_data_dir(state: &AppState) -> PathBuf {
+    state.db_path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
 // ============================================================
 // HTTP Proxy Commands (for CORS bypass)
 // ============================================================
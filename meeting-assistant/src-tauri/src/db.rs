@@ -1,11 +1,48 @@
 // Database Module - SQLite operations
 
-use rusqlite::{Connection, Result, params, OptionalExtension};
+use rusqlite::{Batch, Connection, OpenFlags, Result, params, OptionalExtension};
+use rusqlite::types::ValueRef;
+use std::collections::HashSet;
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc};
 
-use crate::models::{Meeting, MeetingStatus, Participant, TranscriptEntry, Note, NoteType};
+use crate::error::CommandError;
+use crate::models::{
+    Meeting, MeetingStatus, Participant, TranscriptEntry, TranscriptPage, TranscriptWindow, ScreenCapture, InboxItem, TriggerPhrase,
+    Attachment, Annotation, Bookmark, SpeakerAlias, ContextSearchHit, AiUsageRecord, SummaryVersion, Note, NoteType, NoteTemplate, QueryResult,
+    DataScope, IntegrationManifest, IntegrationPermissions, ScopeGrant, MissingAudio, OrphanedRows, WorkspaceReport, ConfigProfile, ProfileImportResult,
+    CalendarEvent, MetadataField, MetadataFieldType, MetadataFilter, MetadataValue, BacklogOperation, MeetingEmbedding,
+    Plugin, PluginManifest, plugin_integration_id,
+};
+
+/// Maximum rows returned by the read-only query console
+pub const QUERY_MAX_ROWS: usize = 1000;
+/// Wall-clock limit for a single console query
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a statement waits for another connection's lock before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tables whose rows belong to a meeting through `meeting_id`
+const MEETING_TABLES: [&str; 12] = [
+    "participants",
+    "transcript_entries",
+    "notes",
+    "screen_captures",
+    "attachments",
+    "annotations",
+    "bookmarks",
+    "speaker_aliases",
+    "meeting_summaries",
+    "meeting_metadata",
+    "action_extractions",
+    "meeting_embeddings",
+];
+
+/// A finished meeting's value for a metadata field, if set, with its start
+/// and end
+pub type MetadataSpan = (Option<String>, DateTime<Utc>, DateTime<Utc>);
 
 /// Database wrapper for thread-safe access
 pub struct Database {
@@ -14,9 +51,7 @@ pub struct Database {
 
 impl Database {
     pub fn new(db_path: &Path) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-        // Enable foreign keys
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
+        let conn = get_connection(db_path)?;
         Ok(Database {
             conn: Mutex::new(conn),
         })
@@ -28,80 +63,119 @@ impl Database {
 
     pub fn save_meeting(&self, meeting: &Meeting) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        
-        conn.execute(
-            "INSERT OR REPLACE INTO meetings (id, title, start_time, end_time, language, translation_target, status, audio_path, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                meeting.id,
-                meeting.title,
-                meeting.start_time.to_rfc3339(),
-                meeting.end_time.map(|t| t.to_rfc3339()),
-                meeting.language,
-                meeting.translation_target,
-                status_to_string(&meeting.status),
-                meeting.audio_path,
-                meeting.created_at.to_rfc3339(),
-                meeting.updated_at.to_rfc3339(),
-            ],
-        )?;
+        upsert_meeting(&conn, meeting)
+    }
 
-        // Save participants
-        for participant in &meeting.participants {
-            conn.execute(
-                "INSERT OR REPLACE INTO participants (id, meeting_id, name, color, is_local)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![
-                    participant.id,
-                    meeting.id,
-                    participant.name,
-                    participant.color,
-                    participant.is_local as i32,
-                ],
-            )?;
+    /// Saves a meeting that is (or is about to be) recording, refusing if a
+    /// different meeting is already recording. The check and the write happen
+    /// under one lock, so two callers cannot both win.
+    pub fn save_recording_meeting(&self, meeting: &Meeting) -> std::result::Result<(), CommandError> {
+        let conn = self.conn.lock().unwrap();
+
+        if let Some(active_meeting_id) = active_recording_id(&conn)? {
+            if active_meeting_id != meeting.id {
+                return Err(CommandError::Conflict { active_meeting_id });
+            }
         }
 
+        upsert_meeting(&conn, meeting)?;
         Ok(())
     }
 
-    pub fn get_meeting(&self, meeting_id: &str) -> Result<Option<Meeting>> {
+    /// Ends whichever meeting is recording and saves `next` as the new
+    /// recording in a single transaction. Returns the meeting that was ended.
+    pub fn handoff_recording(&self, next: &Meeting) -> std::result::Result<Option<Meeting>, CommandError> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let ended = match active_recording_id(&tx)? {
+            Some(active_id) => {
+                let now = Utc::now();
+                let meeting = query_meeting(&tx, &active_id)?.ok_or(CommandError::NotFound)?;
+                let ended = Meeting {
+                    end_time: Some(now),
+                    status: MeetingStatus::Completed,
+                    updated_at: now,
+                    ..meeting
+                };
+                upsert_meeting(&tx, &ended)?;
+                Some(ended)
+            }
+            None => None,
+        };
+
+        upsert_meeting(&tx, next)?;
+        tx.commit()?;
+        Ok(ended)
+    }
+
+    /// The meeting currently recording, if any
+    pub fn get_recording_meeting(&self) -> Result<Option<Meeting>> {
         let conn = self.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, title, start_time, end_time, language, translation_target, status, audio_path, created_at, updated_at
-             FROM meetings WHERE id = ?1"
-        )?;
+        match active_recording_id(&conn)? {
+            Some(meeting_id) => query_meeting(&conn, &meeting_id),
+            None => Ok(None),
+        }
+    }
 
-        let meeting = stmt.query_row(params![meeting_id], |row| {
-            Ok(Meeting {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                start_time: parse_datetime(row.get::<_, String>(2)?),
-                end_time: row.get::<_, Option<String>>(3)?.map(parse_datetime),
-                participants: vec![], // Will be populated below
-                language: row.get(4)?,
-                translation_target: row.get(5)?,
-                status: string_to_status(&row.get::<_, String>(6)?),
-                audio_path: row.get(7)?,
-                created_at: parse_datetime(row.get::<_, String>(8)?),
-                updated_at: parse_datetime(row.get::<_, String>(9)?),
-            })
-        }).optional()?;
+    /// The meeting currently recording or paused, if any
+    pub fn get_live_meeting(&self) -> Result<Option<Meeting>> {
+        let conn = self.conn.lock().unwrap();
+        let meeting_id: Option<String> = conn.query_row(
+            "SELECT id FROM meetings WHERE status IN (?1, ?2) ORDER BY start_time DESC LIMIT 1",
+            params![status_to_string(&MeetingStatus::Recording), status_to_string(&MeetingStatus::Paused)],
+            |row| row.get(0),
+        ).optional()?;
+        match meeting_id {
+            Some(meeting_id) => query_meeting(&conn, &meeting_id),
+            None => Ok(None),
+        }
+    }
 
-        if let Some(mut meeting) = meeting {
-            // Get participants
-            meeting.participants = self.get_participants(&meeting.id)?;
-            Ok(Some(meeting))
+    /// Opens a pause when the meeting is paused and closes it when it
+    /// resumes, so live time can leave paused stretches out
+    pub fn record_pause_change(&self, meeting_id: &str, paused: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        if paused {
+            conn.execute(
+                "INSERT INTO meeting_pauses (meeting_id, paused_at)
+                 SELECT ?1, ?2 WHERE NOT EXISTS (SELECT 1 FROM meeting_pauses WHERE meeting_id = ?1 AND resumed_at IS NULL)",
+                params![meeting_id, now],
+            )?;
         } else {
-            Ok(None)
+            conn.execute(
+                "UPDATE meeting_pauses SET resumed_at = ?2 WHERE meeting_id = ?1 AND resumed_at IS NULL",
+                params![meeting_id, now],
+            )?;
         }
+        Ok(())
+    }
+
+    /// Time the meeting has spent paused, counting a pause still open up to now
+    pub fn paused_duration(&self, meeting_id: &str) -> Result<chrono::Duration> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT paused_at, resumed_at FROM meeting_pauses WHERE meeting_id = ?1")?;
+        let pauses = stmt.query_map(params![meeting_id], |row| {
+            Ok((parse_datetime(row.get::<_, String>(0)?), row.get::<_, Option<String>>(1)?.map(parse_datetime)))
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        let now = Utc::now();
+        Ok(pauses.into_iter()
+            .map(|(paused_at, resumed_at)| resumed_at.unwrap_or(now).signed_duration_since(paused_at))
+            .fold(chrono::Duration::zero(), |total, pause| total + pause.max(chrono::Duration::zero())))
+    }
+
+    pub fn get_meeting(&self, meeting_id: &str) -> Result<Option<Meeting>> {
+        let conn = self.conn.lock().unwrap();
+        query_meeting(&conn, meeting_id)
     }
 
     pub fn get_all_meetings(&self) -> Result<Vec<Meeting>> {
         let conn = self.conn.lock().unwrap();
         
         let mut stmt = conn.prepare(
-            "SELECT id, title, start_time, end_time, language, translation_target, status, audio_path, created_at, updated_at
+            "SELECT id, title, start_time, end_time, language, translation_target, status, audio_path, created_at, updated_at, auto_closed
              FROM meetings ORDER BY start_time DESC"
         )?;
 
@@ -118,42 +192,67 @@ impl Database {
                 audio_path: row.get(7)?,
                 created_at: parse_datetime(row.get::<_, String>(8)?),
                 updated_at: parse_datetime(row.get::<_, String>(9)?),
+                auto_closed: row.get::<_, i32>(10)? != 0,
             })
         })?;
 
         let mut meetings = Vec::new();
         for meeting in meetings_iter {
             let mut m = meeting?;
-            m.participants = self.get_participants(&m.id)?;
+            m.participants = query_participants(&conn, &m.id)?;
             meetings.push(m);
         }
         
         Ok(meetings)
     }
 
+    /// Meetings still Recording or Paused whose last sign of life (latest
+    /// transcript entry, or the meeting's own last update) is before `cutoff`.
+    /// Returns (meeting id, last activity) pairs.
+    pub fn find_stale_meetings(&self, cutoff: DateTime<Utc>) -> Result<Vec<(String, DateTime<Utc>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, last_activity FROM (
+                SELECT m.id,
+                       MAX(m.updated_at, COALESCE((SELECT MAX(t.created_at) FROM transcript_entries t
+                                                   WHERE t.meeting_id = m.id), '')) AS last_activity
+                FROM meetings m
+                WHERE m.status IN ('recording', 'paused')
+             )
+             WHERE last_activity < ?1"
+        )?;
+
+        let stale = stmt.query_map(params![cutoff.to_rfc3339()], |row| {
+            Ok((row.get::<_, String>(0)?, parse_datetime(row.get::<_, String>(1)?)))
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(stale)
+    }
+
     pub fn delete_meeting(&self, meeting_id: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute("DELETE FROM meetings WHERE id = ?1", params![meeting_id])?;
         Ok(())
     }
 
-    fn get_participants(&self, meeting_id: &str) -> Result<Vec<Participant>> {
+    pub fn set_meeting_audio_path(&self, meeting_id: &str, audio_path: Option<&str>) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, name, color, is_local FROM participants WHERE meeting_id = ?1"
+        conn.execute(
+            "UPDATE meetings SET audio_path = ?2, updated_at = ?3 WHERE id = ?1",
+            params![meeting_id, audio_path, Utc::now().to_rfc3339()],
         )?;
+        Ok(())
+    }
 
-        let participants = stmt.query_map(params![meeting_id], |row| {
-            Ok(Participant {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                color: row.get(2)?,
-                is_local: row.get::<_, i32>(3)? != 0,
-            })
-        })?.collect::<Result<Vec<_>, _>>()?;
-
-        Ok(participants)
+    /// (meeting id, audio location) for every meeting with a recording
+    pub fn get_meeting_audio_paths(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, audio_path FROM meetings WHERE audio_path IS NOT NULL AND audio_path != '' ORDER BY start_time"
+        )?;
+        let paths = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(paths)
     }
 
     // ========================================
@@ -164,8 +263,8 @@ impl Database {
         let conn = self.conn.lock().unwrap();
         
         conn.execute(
-            "INSERT OR REPLACE INTO transcript_entries (id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            "INSERT OR REPLACE INTO transcript_entries (id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
             params![
                 entry.id,
                 entry.meeting_id,
@@ -178,6 +277,8 @@ impl Database {
                 entry.language,
                 entry.translation,
                 entry.created_at.to_rfc3339(),
+                entry.voice_pitch,
+                entry.voice_energy,
             ],
         )?;
 
@@ -188,29 +289,140 @@ impl Database {
         let conn = self.conn.lock().unwrap();
         
         let mut stmt = conn.prepare(
-            "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at
+            "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy
              FROM transcript_entries WHERE meeting_id = ?1 ORDER BY timestamp"
         )?;
 
-        let entries = stmt.query_map(params![meeting_id], |row| {
-            Ok(TranscriptEntry {
-                id: row.get(0)?,
-                meeting_id: row.get(1)?,
-                speaker_id: row.get(2)?,
-                speaker_name: row.get(3)?,
-                text: row.get(4)?,
-                timestamp: row.get(5)?,
-                end_timestamp: row.get(6)?,
-                confidence: row.get(7)?,
-                language: row.get(8)?,
-                translation: row.get(9)?,
-                created_at: parse_datetime(row.get::<_, String>(10)?),
-            })
-        })?.collect::<Result<Vec<_>, _>>()?;
+        let entries = stmt.query_map(params![meeting_id], row_to_transcript_entry)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// The entry that starts last, i.e. what was said most recently
+    pub fn get_latest_transcript_entry(&self, meeting_id: &str) -> Result<Option<TranscriptEntry>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy
+             FROM transcript_entries WHERE meeting_id = ?1
+             ORDER BY timestamp DESC, id DESC
+             LIMIT 1",
+            params![meeting_id],
+            row_to_transcript_entry,
+        ).optional()
+    }
+
+    pub fn count_transcript_entries(&self, meeting_id: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        count_transcript(&conn, meeting_id)
+    }
+
+    /// Up to `limit` entries ordered by (timestamp, id), starting after the
+    /// `(timestamp, id)` cursor of the previous chunk. The lock is released
+    /// between chunks so recording can continue during long reads.
+    pub fn get_transcript_chunk(&self, meeting_id: &str, after: Option<(i64, &str)>, limit: usize) -> Result<Vec<TranscriptEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy
+             FROM transcript_entries
+             WHERE meeting_id = ?1 AND (?2 IS NULL OR timestamp > ?2 OR (timestamp = ?2 AND id > ?3))
+             ORDER BY timestamp, id
+             LIMIT ?4"
+        )?;
+
+        let (after_timestamp, after_id) = after.unzip();
+        let entries = stmt.query_map(params![meeting_id, after_timestamp, after_id, limit as i64], row_to_transcript_entry)?
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(entries)
     }
 
+    /// Up to `limit` entries starting after `after_timestamp`. A page never
+    /// ends partway through entries sharing a timestamp, so paging by the
+    /// last timestamp seen cannot skip any.
+    pub fn get_transcript_page(&self, meeting_id: &str, after_timestamp: Option<i64>, limit: usize) -> Result<TranscriptPage> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy
+             FROM transcript_entries
+             WHERE meeting_id = ?1 AND (?2 IS NULL OR timestamp > ?2)
+             ORDER BY timestamp, id
+             LIMIT ?3"
+        )?;
+        let mut entries = stmt.query_map(params![meeting_id, after_timestamp, limit as i64], row_to_transcript_entry)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(last) = entries.last().filter(|_| entries.len() == limit) {
+            let mut stmt = conn.prepare(
+                "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy
+                 FROM transcript_entries
+                 WHERE meeting_id = ?1 AND timestamp = ?2 AND id > ?3
+                 ORDER BY id"
+            )?;
+            let ties = stmt.query_map(params![meeting_id, last.timestamp, last.id], row_to_transcript_entry)?
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.extend(ties);
+        }
+
+        let last_timestamp = entries.last().map(|e| e.timestamp);
+        let has_more = match last_timestamp {
+            Some(ts) => conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM transcript_entries WHERE meeting_id = ?1 AND timestamp > ?2)",
+                params![meeting_id, ts],
+                |row| row.get(0),
+            )?,
+            None => false,
+        };
+        let total = count_transcript(&conn, meeting_id)?;
+
+        Ok(TranscriptPage { entries, total, has_more })
+    }
+
+    /// The entry playing at `timestamp` with up to `window` entries on each
+    /// side, and its position in the whole transcript
+    pub fn get_transcript_around(&self, meeting_id: &str, timestamp: i64, window: usize) -> Result<TranscriptWindow> {
+        let conn = self.conn.lock().unwrap();
+
+        // Entries that started before `timestamp`, nearest first; the first
+        // of them is the one still playing if it hasn't ended yet
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy
+             FROM transcript_entries
+             WHERE meeting_id = ?1 AND timestamp < ?2
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?3"
+        )?;
+        let mut entries = stmt.query_map(params![meeting_id, timestamp, (window + 1) as i64], row_to_transcript_entry)?
+            .collect::<Result<Vec<_>, _>>()?;
+        let playing_before = entries.first().is_some_and(|e| e.end_timestamp > timestamp);
+        if !playing_before {
+            entries.truncate(window);
+        }
+        entries.reverse();
+        let before = entries.len();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, speaker_id, speaker_name, text, timestamp, end_timestamp, confidence, language, translation, created_at, voice_pitch, voice_energy
+             FROM transcript_entries
+             WHERE meeting_id = ?1 AND timestamp >= ?2
+             ORDER BY timestamp, id
+             LIMIT ?3"
+        )?;
+        let after_limit = if playing_before { window } else { window + 1 };
+        let after = stmt.query_map(params![meeting_id, timestamp, after_limit as i64], row_to_transcript_entry)?
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.extend(after);
+
+        let preceding: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM transcript_entries WHERE meeting_id = ?1 AND timestamp < ?2",
+            params![meeting_id, timestamp],
+            |row| row.get(0),
+        )?;
+        let total = count_transcript(&conn, meeting_id)?;
+
+        Ok(TranscriptWindow { entries, total, first_index: preceding as usize - before })
+    }
+
     pub fn save_transcript_batch(&self, entries: &[TranscriptEntry]) -> Result<()> {
         for entry in entries {
             self.save_transcript_entry(entry)?;
@@ -218,192 +430,1597 @@ impl Database {
         Ok(())
     }
 
+    /// Number of distinct speakers in a meeting's transcript
+    pub fn count_speakers(&self, meeting_id: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(DISTINCT speaker_id) FROM transcript_entries WHERE meeting_id = ?1",
+            params![meeting_id],
+            |row| row.get::<_, i64>(0),
+        ).map(|count| count as usize)
+    }
+
+    /// Distinct (speaker id, speaker name) pairs in order of first appearance
+    pub fn get_transcript_speakers(&self, meeting_id: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT speaker_id, speaker_name FROM transcript_entries
+             WHERE meeting_id = ?1
+             GROUP BY speaker_id, speaker_name
+             ORDER BY MIN(timestamp)"
+        )?;
+        let speakers = stmt.query_map(params![meeting_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(speakers)
+    }
+
+    /// Reassigns entries to speakers as (entry id, speaker id, speaker name)
+    pub fn set_speakers(&self, assignments: &[(String, String, String)]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("UPDATE transcript_entries SET speaker_id = ?2, speaker_name = ?3 WHERE id = ?1")?;
+            for (entry_id, speaker_id, speaker_name) in assignments {
+                stmt.execute(params![entry_id, speaker_id, speaker_name])?;
+            }
+        }
+        tx.commit()
+    }
+
     // ========================================
-    // Notes Operations
+    // Screen Capture Operations
     // ========================================
 
-    pub fn save_note(&self, note: &Note) -> Result<()> {
+    pub fn save_screen_capture(&self, capture: &ScreenCapture) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        
         conn.execute(
-            "INSERT OR REPLACE INTO notes (id, meeting_id, note_type, content, timestamp, assignee, deadline, completed, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT OR REPLACE INTO screen_captures (id, meeting_id, timestamp, image_path, ocr_text, relevance_score, thumbnail_path, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
-                note.id,
-                note.meeting_id,
-                note_type_to_string(&note.note_type),
-                note.content,
-                note.timestamp,
-                note.assignee,
-                note.deadline.map(|d| d.to_rfc3339()),
-                note.completed as i32,
-                note.created_at.to_rfc3339(),
-                note.updated_at.to_rfc3339(),
+                capture.id,
+                capture.meeting_id,
+                capture.timestamp,
+                capture.image_path,
+                capture.ocr_text,
+                capture.relevance_score,
+                capture.thumbnail_path,
+                capture.created_at.to_rfc3339(),
             ],
         )?;
-
         Ok(())
     }
 
-    pub fn get_notes(&self, meeting_id: &str) -> Result<Vec<Note>> {
+    pub fn get_screen_captures(&self, meeting_id: &str) -> Result<Vec<ScreenCapture>> {
         let conn = self.conn.lock().unwrap();
-        
         let mut stmt = conn.prepare(
-            "SELECT id, meeting_id, note_type, content, timestamp, assignee, deadline, completed, created_at, updated_at
-             FROM notes WHERE meeting_id = ?1 ORDER BY timestamp"
+            "SELECT id, meeting_id, timestamp, image_path, ocr_text, relevance_score, thumbnail_path, created_at
+             FROM screen_captures WHERE meeting_id = ?1 ORDER BY timestamp"
         )?;
 
-        let notes = stmt.query_map(params![meeting_id], |row| {
-            Ok(Note {
+        let captures = stmt.query_map(params![meeting_id], |row| {
+            Ok(ScreenCapture {
                 id: row.get(0)?,
                 meeting_id: row.get(1)?,
-                note_type: NoteType::from_str(&row.get::<_, String>(2)?),
-                content: row.get(3)?,
-                timestamp: row.get(4)?,
-                source_refs: vec![],
-                assignee: row.get(5)?,
-                deadline: row.get::<_, Option<String>>(6)?.map(parse_datetime),
-                completed: row.get::<_, i32>(7)? != 0,
-                created_at: parse_datetime(row.get::<_, String>(8)?),
-                updated_at: parse_datetime(row.get::<_, String>(9)?),
+                timestamp: row.get(2)?,
+                image_path: row.get(3)?,
+                ocr_text: row.get(4)?,
+                relevance_score: row.get(5)?,
+                thumbnail_path: row.get(6)?,
+                created_at: parse_datetime(row.get::<_, String>(7)?),
             })
         })?.collect::<Result<Vec<_>, _>>()?;
 
-        Ok(notes)
+        Ok(captures)
     }
 
-    pub fn update_note(&self, note_id: &str, content: Option<&str>, completed: Option<bool>) -> Result<()> {
+    pub fn set_capture_thumbnail(&self, capture_id: &str, thumbnail_path: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        
-        if let Some(c) = content {
-            conn.execute(
-                "UPDATE notes SET content = ?1, updated_at = ?2 WHERE id = ?3",
-                params![c, Utc::now().to_rfc3339(), note_id],
-            )?;
-        }
-        
-        if let Some(comp) = completed {
-            conn.execute(
-                "UPDATE notes SET completed = ?1, updated_at = ?2 WHERE id = ?3",
-                params![comp as i32, Utc::now().to_rfc3339(), note_id],
-            )?;
-        }
-
+        conn.execute(
+            "UPDATE screen_captures SET thumbnail_path = ?1 WHERE id = ?2",
+            params![thumbnail_path, capture_id],
+        )?;
         Ok(())
     }
 
-    pub fn delete_note(&self, note_id: &str) -> Result<()> {
+    // ========================================
+    // Attachment Operations
+    // ========================================
+
+    pub fn save_attachment(&self, attachment: &Attachment) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM notes WHERE id = ?1", params![note_id])?;
+        conn.execute(
+            "INSERT INTO attachments (id, meeting_id, kind, title, author, sent_at, source_path, content, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                attachment.id,
+                attachment.meeting_id,
+                attachment.kind,
+                attachment.title,
+                attachment.author,
+                attachment.sent_at.map(|d| d.to_rfc3339()),
+                attachment.source_path,
+                attachment.content,
+                attachment.created_at.to_rfc3339(),
+            ],
+        )?;
         Ok(())
     }
 
-    pub fn get_note(&self, note_id: &str) -> Result<Option<Note>> {
+    pub fn get_attachments(&self, meeting_id: &str) -> Result<Vec<Attachment>> {
         let conn = self.conn.lock().unwrap();
-        
         let mut stmt = conn.prepare(
-            "SELECT id, meeting_id, note_type, content, timestamp, assignee, deadline, completed, created_at, updated_at
-             FROM notes WHERE id = ?1"
+            "SELECT id, meeting_id, kind, title, author, sent_at, source_path, content, created_at
+             FROM attachments WHERE meeting_id = ?1 ORDER BY COALESCE(sent_at, created_at)"
         )?;
 
-        let note = stmt.query_row(params![note_id], |row| {
-            Ok(Note {
+        let attachments = stmt.query_map(params![meeting_id], |row| {
+            Ok(Attachment {
                 id: row.get(0)?,
                 meeting_id: row.get(1)?,
-                note_type: NoteType::from_str(&row.get::<_, String>(2)?),
-                content: row.get(3)?,
-                timestamp: row.get(4)?,
-                source_refs: vec![],
-                assignee: row.get(5)?,
-                deadline: row.get::<_, Option<String>>(6)?.map(parse_datetime),
-                completed: row.get::<_, i32>(7)? != 0,
+                kind: row.get(2)?,
+                title: row.get(3)?,
+                author: row.get(4)?,
+                sent_at: row.get::<_, Option<String>>(5)?.map(parse_datetime),
+                source_path: row.get(6)?,
+                content: row.get(7)?,
                 created_at: parse_datetime(row.get::<_, String>(8)?),
-                updated_at: parse_datetime(row.get::<_, String>(9)?),
             })
-        }).optional()?;
+        })?.collect::<Result<Vec<_>, _>>()?;
 
-        Ok(note)
+        Ok(attachments)
     }
-}
-
-// Helper functions
-fn parse_datetime(s: String) -> DateTime<Utc> {
-    DateTime::parse_from_rfc3339(&s)
-        .map(|dt| dt.with_timezone(&Utc))
-        .unwrap_or_else(|_| Utc::now())
-}
 
-fn status_to_string(status: &MeetingStatus) -> &'static str {
-    match status {
-        MeetingStatus::Idle => "idle",
-        MeetingStatus::Recording => "recording",
-        MeetingStatus::Paused => "paused",
-        MeetingStatus::Completed => "completed",
+    pub fn delete_attachment(&self, attachment_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM attachments WHERE id = ?1", params![attachment_id])?;
+        Ok(())
     }
-}
 
-fn string_to_status(s: &str) -> MeetingStatus {
-    match s {
-        "recording" => MeetingStatus::Recording,
-        "paused" => MeetingStatus::Paused,
-        "completed" => MeetingStatus::Completed,
-        _ => MeetingStatus::Idle,
-    }
-}
+    /// Full-text search over attachment titles and content, best matches first.
+    /// `query` uses FTS5 syntax; plain words are ANDed together.
+    pub fn search_attachments(&self, query: &str, meeting_id: Option<&str>) -> Result<Vec<ContextSearchHit>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT attachment_id, meeting_id, title, snippet(attachments_fts, 3, '[', ']', '…', 16)
+             FROM attachments_fts
+             WHERE attachments_fts MATCH ?1 AND (?2 IS NULL OR meeting_id = ?2)
+             ORDER BY rank
+             LIMIT 50"
+        )?;
 
-fn note_type_to_string(note_type: &NoteType) -> &'static str {
-    match note_type {
-        NoteType::KeyPoint => "key-point",
-        NoteType::ActionItem => "action-item",
-        NoteType::Decision => "decision",
-        NoteType::Question => "question",
-        NoteType::FollowUp => "follow-up",
-        NoteType::Manual => "manual",
+        let hits = stmt.query_map(params![query, meeting_id], |row| {
+            Ok(ContextSearchHit {
+                attachment_id: row.get(0)?,
+                meeting_id: row.get(1)?,
+                title: row.get(2)?,
+                snippet: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(hits)
     }
-}
 
-/// Initialize the SQLite database with required tables
-pub fn init_database(db_path: &Path) -> Result<()> {
-    let conn = Connection::open(db_path)?;
+    // ========================================
+    // Speaker Alias Operations
+    // ========================================
 
-    // Enable foreign keys
-    conn.execute("PRAGMA foreign_keys = ON", [])?;
+    pub fn get_speaker_aliases(&self, meeting_id: &str) -> Result<Vec<SpeakerAlias>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT meeting_id, speaker_id, alias FROM speaker_aliases WHERE meeting_id = ?1 ORDER BY speaker_id"
+        )?;
 
-    // Create meetings table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS meetings (
-            id TEXT PRIMARY KEY,
-            title TEXT NOT NULL,
-            start_time TEXT NOT NULL,
-            end_time TEXT,
-            language TEXT NOT NULL DEFAULT 'en',
-            translation_target TEXT,
-            status TEXT NOT NULL DEFAULT 'idle',
-            audio_path TEXT,
-            created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
-        )",
-        [],
-    )?;
+        let aliases = stmt.query_map(params![meeting_id], |row| {
+            Ok(SpeakerAlias {
+                meeting_id: row.get(0)?,
+                speaker_id: row.get(1)?,
+                alias: row.get(2)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
 
-    // Create participants table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS participants (
-            id TEXT PRIMARY KEY,
-            meeting_id TEXT NOT NULL,
-            name TEXT NOT NULL,
-            color TEXT NOT NULL,
-            is_local INTEGER NOT NULL DEFAULT 0,
-            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
-        )",
-        [],
-    )?;
+        Ok(aliases)
+    }
 
-    // Create transcript_entries table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS transcript_entries (
-            id TEXT PRIMARY KEY,
-            meeting_id TEXT NOT NULL,
+    pub fn set_speaker_alias(&self, alias: &SpeakerAlias) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO speaker_aliases (meeting_id, speaker_id, alias) VALUES (?1, ?2, ?3)
+             ON CONFLICT(meeting_id, speaker_id) DO UPDATE SET alias = excluded.alias",
+            params![alias.meeting_id, alias.speaker_id, alias.alias],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_speaker_alias(&self, meeting_id: &str, speaker_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM speaker_aliases WHERE meeting_id = ?1 AND speaker_id = ?2",
+            params![meeting_id, speaker_id],
+        )?;
+        Ok(())
+    }
+
+    // ========================================
+    // Annotation Operations
+    // ========================================
+
+    /// Insert or update an annotation; only its content and updated_at change
+    pub fn save_annotation(&self, annotation: &Annotation) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO annotations (id, meeting_id, transcript_entry_id, content, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET content = excluded.content, updated_at = excluded.updated_at",
+            params![
+                annotation.id,
+                annotation.meeting_id,
+                annotation.transcript_entry_id,
+                annotation.content,
+                annotation.created_at.to_rfc3339(),
+                annotation.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_annotation(&self, annotation_id: &str) -> Result<Option<Annotation>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, meeting_id, transcript_entry_id, content, created_at, updated_at
+             FROM annotations WHERE id = ?1",
+            params![annotation_id],
+            row_to_annotation,
+        ).optional()
+    }
+
+    pub fn get_annotations(&self, meeting_id: &str) -> Result<Vec<Annotation>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, transcript_entry_id, content, created_at, updated_at
+             FROM annotations WHERE meeting_id = ?1 ORDER BY created_at"
+        )?;
+
+        let annotations = stmt.query_map(params![meeting_id], row_to_annotation)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(annotations)
+    }
+
+    pub fn delete_annotation(&self, annotation_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM annotations WHERE id = ?1", params![annotation_id])?;
+        Ok(())
+    }
+
+    // ========================================
+    // Bookmark Operations
+    // ========================================
+
+    pub fn save_bookmark(&self, bookmark: &Bookmark) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO bookmarks (id, meeting_id, timestamp, label, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                bookmark.id,
+                bookmark.meeting_id,
+                bookmark.timestamp,
+                bookmark.label,
+                bookmark.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The meeting's bookmarks in playback order, each with the transcript
+    /// entry that had started by then
+    pub fn get_bookmarks(&self, meeting_id: &str) -> Result<Vec<Bookmark>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT b.id, b.meeting_id, b.timestamp, b.label, b.created_at,
+                    (SELECT t.id FROM transcript_entries t
+                     WHERE t.meeting_id = b.meeting_id AND t.timestamp <= b.timestamp
+                     ORDER BY t.timestamp DESC LIMIT 1)
+             FROM bookmarks b WHERE b.meeting_id = ?1 ORDER BY b.timestamp, b.created_at"
+        )?;
+
+        let bookmarks = stmt.query_map(params![meeting_id], |row| {
+            Ok(Bookmark {
+                id: row.get(0)?,
+                meeting_id: row.get(1)?,
+                timestamp: row.get(2)?,
+                label: row.get(3)?,
+                created_at: parse_datetime(row.get::<_, String>(4)?),
+                transcript_entry_id: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(bookmarks)
+    }
+
+    /// Returns whether a bookmark was deleted
+    pub fn delete_bookmark(&self, bookmark_id: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![bookmark_id])?;
+        Ok(deleted > 0)
+    }
+
+    // ========================================
+    // AI Usage Operations
+    // ========================================
+
+    pub fn record_ai_usage(&self, record: &AiUsageRecord) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO ai_usage (id, request_id, provider, model, purpose, status, prompt_tokens,
+                                   completion_tokens, cost_usd, latency_ms, error, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.id,
+                record.request_id,
+                record.provider,
+                record.model,
+                record.purpose,
+                record.status,
+                record.prompt_tokens,
+                record.completion_tokens,
+                record.cost_usd,
+                record.latency_ms,
+                record.error,
+                record.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_ai_usage(&self, since: Option<DateTime<Utc>>) -> Result<Vec<AiUsageRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, request_id, provider, model, purpose, status, prompt_tokens,
+                    completion_tokens, cost_usd, latency_ms, error, created_at
+             FROM ai_usage WHERE ?1 IS NULL OR created_at >= ?1
+             ORDER BY created_at DESC"
+        )?;
+
+        let records = stmt.query_map(params![since.map(|d| d.to_rfc3339())], |row| {
+            Ok(AiUsageRecord {
+                id: row.get(0)?,
+                request_id: row.get(1)?,
+                provider: row.get(2)?,
+                model: row.get(3)?,
+                purpose: row.get(4)?,
+                status: row.get(5)?,
+                prompt_tokens: row.get(6)?,
+                completion_tokens: row.get(7)?,
+                cost_usd: row.get(8)?,
+                latency_ms: row.get(9)?,
+                error: row.get(10)?,
+                created_at: parse_datetime(row.get::<_, String>(11)?),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// `(provider, cost_usd, successful requests)` since `since`
+    pub fn get_ai_spend(&self, since: DateTime<Utc>) -> Result<Vec<(String, f64, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT provider, COALESCE(SUM(cost_usd), 0), SUM(status = 'ok')
+             FROM ai_usage WHERE created_at >= ?1
+             GROUP BY provider ORDER BY provider"
+        )?;
+
+        let spend = stmt.query_map(params![since.to_rfc3339()], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(spend)
+    }
+
+    // ========================================
+    // Summary Operations
+    // ========================================
+
+    /// Stores a summary as the meeting's next version and returns the
+    /// version number it was given; `summary.version` is ignored
+    pub fn add_summary_version(&self, summary: &SummaryVersion) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO meeting_summaries (id, meeting_id, version, template_version, provider, model, content, created_at)
+             VALUES (?1, ?2, (SELECT COALESCE(MAX(version), 0) + 1 FROM meeting_summaries WHERE meeting_id = ?2),
+                     ?3, ?4, ?5, ?6, ?7)",
+            params![
+                summary.id,
+                summary.meeting_id,
+                summary.template_version,
+                summary.provider,
+                summary.model,
+                summary.content,
+                summary.created_at.to_rfc3339(),
+            ],
+        )?;
+        conn.query_row(
+            "SELECT version FROM meeting_summaries WHERE id = ?1",
+            params![summary.id],
+            |row| row.get(0),
+        )
+    }
+
+    /// All summary versions of a meeting, newest first
+    pub fn get_summary_versions(&self, meeting_id: &str) -> Result<Vec<SummaryVersion>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, version, template_version, provider, model, content, created_at
+             FROM meeting_summaries WHERE meeting_id = ?1
+             ORDER BY version DESC"
+        )?;
+
+        let summaries = stmt.query_map(params![meeting_id], |row| {
+            Ok(SummaryVersion {
+                id: row.get(0)?,
+                meeting_id: row.get(1)?,
+                version: row.get(2)?,
+                template_version: row.get(3)?,
+                provider: row.get(4)?,
+                model: row.get(5)?,
+                content: row.get(6)?,
+                created_at: parse_datetime(row.get::<_, String>(7)?),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(summaries)
+    }
+
+    // ========================================
+    // Backlog Operations
+    // ========================================
+
+    /// Finished meetings with a transcript that have nothing yet from
+    /// `operation`, oldest first
+    pub fn get_meetings_missing(&self, operation: BacklogOperation) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let table = match operation {
+            BacklogOperation::Summarize => "meeting_summaries",
+            BacklogOperation::ExtractActions => "action_extractions",
+            BacklogOperation::Embed => "meeting_embeddings",
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT m.id FROM meetings m
+             WHERE m.status = 'completed'
+               AND EXISTS (SELECT 1 FROM transcript_entries t WHERE t.meeting_id = m.id)
+               AND NOT EXISTS (SELECT 1 FROM {} x WHERE x.meeting_id = m.id)
+             ORDER BY m.start_time",
+            table
+        ))?;
+
+        let ids = stmt.query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        Ok(ids)
+    }
+
+    /// Records that action items were extracted from a meeting, so it isn't
+    /// queued again when none were found
+    pub fn record_action_extraction(&self, meeting_id: &str, provider: &str, model: &str, items: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO action_extractions (meeting_id, provider, model, items, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![meeting_id, provider, model, items as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn save_meeting_embedding(&self, embedding: &MeetingEmbedding) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO meeting_embeddings (meeting_id, provider, model, vector, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                embedding.meeting_id,
+                embedding.provider,
+                embedding.model,
+                serde_json::to_string(&embedding.vector).unwrap_or_else(|_| "[]".to_string()),
+                embedding.created_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ========================================
+    // Notes Operations
+    // ========================================
+
+    pub fn save_note(&self, note: &Note) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        // An upsert rather than INSERT OR REPLACE, which would reset the
+        // columns the editor doesn't send. Deferral and review have their own
+        // commands; completed_at keeps the first completion time.
+        conn.execute(
+            "INSERT INTO notes (id, meeting_id, note_type, content, timestamp, assignee, deadline, completed, completed_at,
+                created_at, updated_at, deferred_until, source_refs, needs_review)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, CASE WHEN ?8 = 1 THEN COALESCE(?9, ?11) END, ?10, ?11, ?12, ?13, ?14)
+             ON CONFLICT(id) DO UPDATE SET note_type = excluded.note_type, content = excluded.content,
+                timestamp = excluded.timestamp, assignee = excluded.assignee, deadline = excluded.deadline,
+                completed = excluded.completed,
+                completed_at = CASE WHEN excluded.completed = 1 THEN COALESCE(notes.completed_at, excluded.completed_at) END,
+                source_refs = excluded.source_refs, updated_at = excluded.updated_at",
+            rusqlite::params_from_iter(note_params(note)),
+        )?;
+        Ok(())
+    }
+
+    /// Insert a note unless one with the same id exists; returns whether it was added
+    pub fn insert_note_if_absent(&self, note: &Note) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        Ok(write_note(&conn, note, "INSERT OR IGNORE")? > 0)
+    }
+
+    pub fn get_notes(&self, meeting_id: &str) -> Result<Vec<Note>> {
+        let conn = self.conn.lock().unwrap();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, note_type, content, timestamp, assignee, deadline, completed, created_at, updated_at, completed_at, deferred_until, source_refs, needs_review
+             FROM notes WHERE meeting_id = ?1 ORDER BY timestamp"
+        )?;
+
+        let notes = stmt.query_map(params![meeting_id], row_to_note)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    pub fn update_note(&self, note_id: &str, content: Option<&str>, completed: Option<bool>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        
+        if let Some(c) = content {
+            conn.execute(
+                "UPDATE notes SET content = ?1, updated_at = ?2 WHERE id = ?3",
+                params![c, Utc::now().to_rfc3339(), note_id],
+            )?;
+        }
+        
+        if let Some(comp) = completed {
+            // Keep the first completion time so latency stats stay stable
+            conn.execute(
+                "UPDATE notes SET completed = ?1, updated_at = ?2,
+                    completed_at = CASE WHEN ?1 = 1 THEN COALESCE(completed_at, ?2) ELSE NULL END
+                 WHERE id = ?3",
+                params![comp as i32, Utc::now().to_rfc3339(), note_id],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn delete_note(&self, note_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM notes WHERE id = ?1", params![note_id])?;
+        Ok(())
+    }
+
+    /// Confirm an auto-created note so it no longer shows as needing review
+    pub fn mark_note_reviewed(&self, note_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE notes SET needs_review = 0, updated_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), note_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_note(&self, note_id: &str) -> Result<Option<Note>> {
+        let conn = self.conn.lock().unwrap();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, note_type, content, timestamp, assignee, deadline, completed, created_at, updated_at, completed_at, deferred_until, source_refs, needs_review
+             FROM notes WHERE id = ?1"
+        )?;
+
+        let note = stmt.query_row(params![note_id], row_to_note).optional()?;
+
+        Ok(note)
+    }
+
+    /// Action items joined with their meeting title, for meetings starting in the range.
+    /// Returns (meeting title, note) pairs.
+    pub fn get_action_items_with_meeting(
+        &self,
+        range_start: Option<DateTime<Utc>>,
+        range_end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, Note)>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.meeting_id, n.note_type, n.content, n.timestamp, n.assignee, n.deadline, n.completed,
+                    n.created_at, n.updated_at, n.completed_at, n.deferred_until, n.source_refs, n.needs_review, m.title
+             FROM notes n JOIN meetings m ON m.id = n.meeting_id
+             WHERE n.note_type = 'action-item'
+               AND (?1 IS NULL OR m.start_time >= ?1)
+               AND (?2 IS NULL OR m.start_time < ?2)
+             ORDER BY m.start_time"
+        )?;
+
+        let items = stmt.query_map(
+            params![range_start.map(|d| d.to_rfc3339()), range_end.map(|d| d.to_rfc3339())],
+            |row| Ok((row.get::<_, String>(14)?, row_to_note(row)?)),
+        )?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    /// Action items across all meetings, newest meeting first. `assignee` is
+    /// matched case-insensitively; `meetings_since` limits to meetings that
+    /// started at or after that time.
+    pub fn list_action_items(
+        &self,
+        assignee: Option<&str>,
+        include_completed: bool,
+        meetings_since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<InboxItem>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.meeting_id, n.note_type, n.content, n.timestamp, n.assignee, n.deadline, n.completed,
+                    n.created_at, n.updated_at, n.completed_at, n.deferred_until, n.source_refs, n.needs_review, m.title, m.start_time
+             FROM notes n JOIN meetings m ON m.id = n.meeting_id
+             WHERE n.note_type = 'action-item'
+               AND (?1 IS NULL OR LOWER(TRIM(n.assignee)) = LOWER(TRIM(?1)))
+               AND (?2 = 1 OR n.completed = 0)
+               AND (?3 IS NULL OR m.start_time >= ?3)
+             ORDER BY m.start_time DESC, n.timestamp"
+        )?;
+
+        let items = stmt.query_map(
+            params![assignee, include_completed as i32, meetings_since.map(|d| d.to_rfc3339())],
+            |row| {
+                Ok(InboxItem {
+                    note: row_to_note(row)?,
+                    meeting_title: row.get(14)?,
+                    meeting_start: parse_datetime(row.get::<_, String>(15)?),
+                })
+            },
+        )?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    pub fn defer_note(&self, note_id: &str, until: Option<DateTime<Utc>>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE notes SET deferred_until = ?1, updated_at = ?2 WHERE id = ?3",
+            params![until.map(|d| d.to_rfc3339()), Utc::now().to_rfc3339(), note_id],
+        )?;
+        Ok(())
+    }
+
+    // ========================================
+    // Settings Operations
+    // ========================================
+
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()
+    }
+
+    pub fn get_all_settings(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT key, value FROM settings ORDER BY key")?;
+        let settings = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(settings)
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    // ========================================
+    // Trigger Phrase Operations
+    // ========================================
+
+    pub fn get_trigger_phrases(&self) -> Result<Vec<TriggerPhrase>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, phrase, note_type, enabled FROM trigger_phrases ORDER BY phrase"
+        )?;
+        let phrases = stmt.query_map([], |row| {
+            Ok(TriggerPhrase {
+                id: row.get(0)?,
+                phrase: row.get(1)?,
+                note_type: NoteType::from_str(&row.get::<_, String>(2)?),
+                enabled: row.get::<_, i32>(3)? != 0,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(phrases)
+    }
+
+    pub fn save_trigger_phrase(&self, phrase: &TriggerPhrase) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO trigger_phrases (id, phrase, note_type, enabled) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET phrase = excluded.phrase, note_type = excluded.note_type,
+                enabled = excluded.enabled",
+            params![phrase.id, phrase.phrase, note_type_to_string(&phrase.note_type), phrase.enabled as i32],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_trigger_phrase(&self, phrase_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM trigger_phrases WHERE id = ?1", params![phrase_id])?;
+        Ok(())
+    }
+
+    // ========================================
+    // Note Template Operations
+    // ========================================
+
+    pub fn save_note_template(&self, template: &NoteTemplate) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO note_templates (id, name, note_type, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, note_type = excluded.note_type,
+                body = excluded.body, updated_at = excluded.updated_at",
+            params![
+                template.id,
+                template.name,
+                note_type_to_string(&template.note_type),
+                template.body,
+                template.created_at.to_rfc3339(),
+                template.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_note_templates(&self) -> Result<Vec<NoteTemplate>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, note_type, body, created_at, updated_at FROM note_templates ORDER BY name"
+        )?;
+        let templates = stmt.query_map([], row_to_note_template)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(templates)
+    }
+
+    pub fn get_note_template(&self, template_id: &str) -> Result<Option<NoteTemplate>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, note_type, body, created_at, updated_at FROM note_templates WHERE id = ?1"
+        )?;
+        stmt.query_row(params![template_id], row_to_note_template).optional()
+    }
+
+    pub fn delete_note_template(&self, template_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM note_templates WHERE id = ?1", params![template_id])?;
+        Ok(())
+    }
+
+    // ========================================
+    // Integration Permission Operations
+    // ========================================
+
+    pub fn get_integration_permissions(&self, integration_id: Option<&str>) -> Result<Vec<IntegrationPermissions>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, reads, sends_to FROM integration_manifests
+             WHERE ?1 IS NULL OR id = ?1 ORDER BY name"
+        )?;
+        let manifests = stmt.query_map(params![integration_id], |row| {
+            let reads: String = row.get(2)?;
+            Ok(IntegrationManifest {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                reads: reads.split(',').filter_map(DataScope::from_str).collect(),
+                sends_to: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        let mut grant_stmt = conn.prepare(
+            "SELECT granted, updated_at FROM integration_grants WHERE integration_id = ?1 AND scope = ?2"
+        )?;
+
+        let mut permissions = Vec::new();
+        for manifest in manifests {
+            let mut grants = Vec::new();
+            for scope in &manifest.reads {
+                let grant = grant_stmt.query_row(params![manifest.id, scope.as_str()], |row| {
+                    Ok((row.get::<_, i32>(0)? != 0, parse_datetime(row.get::<_, String>(1)?)))
+                }).optional()?;
+                grants.push(ScopeGrant {
+                    scope: *scope,
+                    granted: grant.as_ref().map(|(granted, _)| *granted),
+                    updated_at: grant.map(|(_, updated_at)| updated_at),
+                });
+            }
+            permissions.push(IntegrationPermissions { manifest, grants });
+        }
+
+        Ok(permissions)
+    }
+
+    pub fn set_integration_permission(&self, integration_id: &str, scope: DataScope, granted: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO integration_grants (integration_id, scope, granted, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![integration_id, scope.as_str(), granted as i32, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// True only if the manifest declares the scope and the user granted it
+    pub fn is_scope_granted(&self, integration_id: &str, scope: DataScope) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let granted = conn.query_row(
+            "SELECT g.granted FROM integration_grants g
+             JOIN integration_manifests m ON m.id = g.integration_id
+             WHERE g.integration_id = ?1 AND g.scope = ?2
+               AND (',' || m.reads || ',') LIKE ('%,' || g.scope || ',%')",
+            params![integration_id, scope.as_str()],
+            |row| row.get::<_, i32>(0),
+        ).optional()?;
+        Ok(granted == Some(1))
+    }
+
+    // ========================================
+    // Plugin Operations
+    // ========================================
+
+    /// Records an installed plugin and registers the data it reads as an
+    /// integration, so it is granted like any other. A new plugin starts
+    /// disabled; reinstalling keeps its enabled state and grants.
+    pub fn save_plugin(&self, manifest: &PluginManifest) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let integration_id = plugin_integration_id(&manifest.id);
+        let reads: Vec<&str> = manifest.permissions.iter().map(|s| s.as_str()).collect();
+        tx.execute(
+            "INSERT INTO integration_manifests (id, name, reads, sends_to) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, reads = excluded.reads, sends_to = excluded.sends_to",
+            params![integration_id, manifest.name, reads.join(","), PLUGIN_SENDS_TO],
+        )?;
+        tx.execute(
+            "INSERT INTO plugins (id, manifest, enabled, installed_at) VALUES (?1, ?2, 0, ?3)
+             ON CONFLICT(id) DO UPDATE SET manifest = excluded.manifest, installed_at = excluded.installed_at",
+            params![
+                manifest.id,
+                serde_json::to_string(manifest).unwrap_or_else(|_| "{}".to_string()),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        tx.commit()
+    }
+
+    /// Plugins whose manifest can still be read, by name
+    pub fn get_plugins(&self) -> Result<Vec<Plugin>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT manifest, enabled, installed_at FROM plugins")?;
+        let mut plugins: Vec<Plugin> = stmt.query_map([], row_to_plugin)?
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        plugins.sort_by_key(|plugin| plugin.manifest.name.to_lowercase());
+        Ok(plugins)
+    }
+
+    /// Returns false if no such plugin is installed
+    pub fn set_plugin_enabled(&self, plugin_id: &str, enabled: bool) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE plugins SET enabled = ?2 WHERE id = ?1",
+            params![plugin_id, enabled as i32],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Removes the plugin along with its permissions and grants
+    pub fn delete_plugin(&self, plugin_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM plugins WHERE id = ?1", params![plugin_id])?;
+        conn.execute("DELETE FROM integration_manifests WHERE id = ?1", params![plugin_integration_id(plugin_id)])?;
+        Ok(())
+    }
+
+    // ========================================
+    // Configuration Profile
+    // ========================================
+
+    /// Applies a configuration profile in one transaction. Templates and
+    /// phrases are matched by id; grants for integrations this version
+    /// doesn't know are skipped.
+    pub fn import_profile(&self, profile: &ConfigProfile) -> Result<ProfileImportResult> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        for (key, value) in &profile.settings {
+            tx.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )?;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        for template in &profile.note_templates {
+            tx.execute(
+                "INSERT INTO note_templates (id, name, note_type, body, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name, note_type = excluded.note_type,
+                    body = excluded.body, updated_at = excluded.updated_at",
+                params![template.id, template.name, note_type_to_string(&template.note_type), template.body, now],
+            )?;
+        }
+
+        for phrase in &profile.trigger_phrases {
+            tx.execute(
+                "INSERT INTO trigger_phrases (id, phrase, note_type, enabled) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(id) DO UPDATE SET phrase = excluded.phrase, note_type = excluded.note_type,
+                    enabled = excluded.enabled",
+                params![phrase.id, phrase.phrase, note_type_to_string(&phrase.note_type), phrase.enabled as i32],
+            )?;
+        }
+
+        // Only integrations unknown here are added, and no grant is written:
+        // the user is asked on this machine like for any new integration
+        let mut integration_manifests = 0;
+        for manifest in &profile.integration_manifests {
+            let reads: Vec<&str> = manifest.reads.iter().map(|s| s.as_str()).collect();
+            integration_manifests += tx.execute(
+                "INSERT OR IGNORE INTO integration_manifests (id, name, reads, sends_to) VALUES (?1, ?2, ?3, ?4)",
+                params![manifest.id, manifest.name, reads.join(","), manifest.sends_to],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(ProfileImportResult {
+            settings: profile.settings.len(),
+            note_templates: profile.note_templates.len(),
+            trigger_phrases: profile.trigger_phrases.len(),
+            integration_manifests,
+            app_settings: profile.app_settings.clone(),
+        })
+    }
+
+    // ========================================
+    // Calendar Operations
+    // ========================================
+
+    /// Adds imported events, replacing any with the same id, in one
+    /// transaction
+    pub fn save_calendar_events(&self, events: &[CalendarEvent]) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let imported_at = Utc::now().to_rfc3339();
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO calendar_events (id, title, start_time, end_time, imported_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)"
+            )?;
+            for event in events {
+                stmt.execute(params![
+                    event.id,
+                    event.title,
+                    event.start_time.to_rfc3339(),
+                    event.end_time.to_rfc3339(),
+                    imported_at,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(events.len())
+    }
+
+    /// Events starting in `[from, to)`, earliest first
+    pub fn get_calendar_events(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<CalendarEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, title, start_time, end_time FROM calendar_events
+             WHERE start_time >= ?1 AND start_time < ?2
+             ORDER BY start_time"
+        )?;
+
+        let events = stmt.query_map(params![from.to_rfc3339(), to.to_rfc3339()], |row| {
+            Ok(CalendarEvent {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                start_time: parse_datetime(row.get::<_, String>(2)?),
+                end_time: parse_datetime(row.get::<_, String>(3)?),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    /// Start and end of the finished meetings starting in `[from, to)`
+    pub fn get_meeting_spans(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT start_time, end_time FROM meetings
+             WHERE end_time IS NOT NULL AND start_time >= ?1 AND start_time < ?2"
+        )?;
+
+        let spans = stmt.query_map(params![from.to_rfc3339(), to.to_rfc3339()], |row| {
+            Ok((parse_datetime(row.get::<_, String>(0)?), parse_datetime(row.get::<_, String>(1)?)))
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(spans)
+    }
+
+    // ========================================
+    // Meeting Metadata Operations
+    // ========================================
+
+    pub fn get_metadata_fields(&self) -> Result<Vec<MetadataField>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, field_type FROM metadata_fields ORDER BY position, name"
+        )?;
+        let fields = stmt.query_map([], |row| {
+            Ok(MetadataField {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                field_type: string_to_field_type(&row.get::<_, String>(2)?),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(fields)
+    }
+
+    pub fn get_metadata_field(&self, field_id: &str) -> Result<Option<MetadataField>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, field_type FROM metadata_fields WHERE id = ?1",
+            params![field_id],
+            |row| Ok(MetadataField {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                field_type: string_to_field_type(&row.get::<_, String>(2)?),
+            }),
+        ).optional()
+    }
+
+    /// Adds the field at the end, or renames it. Changing an existing
+    /// field's type clears its values, which may not fit the new type.
+    pub fn save_metadata_field(&self, field: &MetadataField) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let previous_type: Option<String> = tx.query_row(
+            "SELECT field_type FROM metadata_fields WHERE id = ?1",
+            params![field.id],
+            |row| row.get(0),
+        ).optional()?;
+        let field_type = field_type_to_string(field.field_type);
+        if previous_type.as_deref().is_some_and(|previous| previous != field_type) {
+            tx.execute("DELETE FROM meeting_metadata WHERE field_id = ?1", params![field.id])?;
+        }
+        tx.execute(
+            "INSERT INTO metadata_fields (id, name, field_type, position)
+             VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position), 0) + 1 FROM metadata_fields))
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, field_type = excluded.field_type",
+            params![field.id, field.name, field_type],
+        )?;
+        tx.commit()
+    }
+
+    /// Deletes the field and every meeting's value for it
+    pub fn delete_metadata_field(&self, field_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM metadata_fields WHERE id = ?1", params![field_id])?;
+        Ok(())
+    }
+
+    /// The meeting's values, in field order
+    pub fn get_meeting_metadata(&self, meeting_id: &str) -> Result<Vec<MetadataValue>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.name, f.field_type, m.value
+             FROM meeting_metadata m JOIN metadata_fields f ON f.id = m.field_id
+             WHERE m.meeting_id = ?1
+             ORDER BY f.position, f.name"
+        )?;
+        let values = stmt.query_map(params![meeting_id], |row| {
+            Ok(MetadataValue {
+                field_id: row.get(0)?,
+                name: row.get(1)?,
+                field_type: string_to_field_type(&row.get::<_, String>(2)?),
+                value: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(values)
+    }
+
+    /// Sets the meeting's value for a field, or clears it with `None`
+    pub fn set_meeting_metadata(&self, meeting_id: &str, field_id: &str, value: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        match value {
+            Some(value) => conn.execute(
+                "INSERT INTO meeting_metadata (meeting_id, field_id, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(meeting_id, field_id) DO UPDATE SET value = excluded.value",
+                params![meeting_id, field_id, value],
+            )?,
+            None => conn.execute(
+                "DELETE FROM meeting_metadata WHERE meeting_id = ?1 AND field_id = ?2",
+                params![meeting_id, field_id],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// Ids of the meetings matching every filter
+    pub fn find_meetings_by_metadata(&self, filters: &[MetadataFilter]) -> Result<HashSet<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT meeting_id FROM meeting_metadata WHERE field_id = ?1 AND value = ?2"
+        )?;
+        let mut matching: Option<HashSet<String>> = None;
+        for filter in filters {
+            let ids = stmt.query_map(params![filter.field_id, filter.value], |row| row.get::<_, String>(0))?
+                .collect::<Result<HashSet<_>, _>>()?;
+            matching = Some(match matching {
+                Some(matching) => matching.intersection(&ids).cloned().collect(),
+                None => ids,
+            });
+        }
+        Ok(matching.unwrap_or_default())
+    }
+
+    /// Each finished meeting starting in `[from, to)` with its value for the
+    /// field
+    pub fn get_metadata_spans(
+        &self,
+        field_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MetadataSpan>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT md.value, m.start_time, m.end_time
+             FROM meetings m
+             LEFT JOIN meeting_metadata md ON md.meeting_id = m.id AND md.field_id = ?1
+             WHERE m.end_time IS NOT NULL
+               AND (?2 IS NULL OR m.start_time >= ?2)
+               AND (?3 IS NULL OR m.start_time < ?3)"
+        )?;
+
+        let spans = stmt.query_map(params![field_id, from.map(|t| t.to_rfc3339()), to.map(|t| t.to_rfc3339())], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                parse_datetime(row.get::<_, String>(1)?),
+                parse_datetime(row.get::<_, String>(2)?),
+            ))
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(spans)
+    }
+
+    // ========================================
+    // Workspace Check
+    // ========================================
+
+    /// Looks for rows left behind by deletes that ran without foreign keys
+    /// enforced, audio files that were moved or deleted, and an attachment
+    /// search index out of step with its table. With `repair`, orphaned rows
+    /// are deleted, missing audio paths cleared and the index rebuilt, all in
+    /// one transaction; the report still describes what was found.
+    pub fn check_workspace(&self, repair: bool) -> Result<WorkspaceReport> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let mut orphaned_rows = Vec::new();
+        for table in MEETING_TABLES {
+            let filter = format!("FROM {} WHERE meeting_id NOT IN (SELECT id FROM meetings)", table);
+            let count: usize = tx.query_row(&format!("SELECT COUNT(*) {}", filter), [], |row| row.get(0))?;
+            if count > 0 {
+                if repair {
+                    tx.execute(&format!("DELETE {}", filter), [])?;
+                }
+                orphaned_rows.push(OrphanedRows { table: table.to_string(), count });
+            }
+        }
+
+        // Annotations of deleted meetings are already counted above
+        let annotation_filter = "FROM annotations
+             WHERE transcript_entry_id NOT IN (SELECT id FROM transcript_entries)
+               AND meeting_id IN (SELECT id FROM meetings)";
+        let orphaned_annotations: usize =
+            tx.query_row(&format!("SELECT COUNT(*) {}", annotation_filter), [], |row| row.get(0))?;
+
+        let audio_paths = {
+            let mut stmt = tx.prepare("SELECT id, audio_path FROM meetings WHERE audio_path IS NOT NULL AND audio_path != ''")?;
+            let rows = stmt.query_map([], |row| {
+                Ok(MissingAudio { meeting_id: row.get(0)?, audio_path: row.get(1)? })
+            })?.collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        // Recordings kept in a bucket can't be checked without going online
+        let missing_audio: Vec<MissingAudio> = audio_paths.into_iter()
+            .filter(|audio| !audio.audio_path.starts_with("s3://") && !Path::new(&audio.audio_path).exists())
+            .collect();
+
+        let unindexed_attachments: usize = tx.query_row(
+            "SELECT COUNT(*) FROM attachments WHERE id NOT IN (SELECT attachment_id FROM attachments_fts)",
+            [],
+            |row| row.get(0),
+        )?;
+        let stale_index_rows: usize = tx.query_row(
+            "SELECT COUNT(*) FROM attachments_fts WHERE attachment_id NOT IN (SELECT id FROM attachments)",
+            [],
+            |row| row.get(0),
+        )?;
+
+        if repair {
+            tx.execute(&format!("DELETE {}", annotation_filter), [])?;
+            for audio in &missing_audio {
+                tx.execute("UPDATE meetings SET audio_path = NULL WHERE id = ?1", params![audio.meeting_id])?;
+            }
+            if unindexed_attachments > 0 || stale_index_rows > 0 {
+                tx.execute_batch(
+                    "DELETE FROM attachments_fts;
+                     INSERT INTO attachments_fts (attachment_id, meeting_id, title, content)
+                     SELECT id, meeting_id, title, content FROM attachments;"
+                )?;
+            }
+            tx.commit()?;
+        }
+
+        Ok(WorkspaceReport {
+            orphaned_rows,
+            orphaned_annotations,
+            missing_audio,
+            unindexed_attachments,
+            stale_index_rows,
+            repaired: repair,
+        })
+    }
+}
+
+// Helper functions
+fn upsert_meeting(conn: &Connection, meeting: &Meeting) -> Result<()> {
+    // Upsert rather than INSERT OR REPLACE: REPLACE deletes the old row,
+    // which cascades to the meeting's transcript, notes and participants.
+    conn.execute(
+        "INSERT INTO meetings (id, title, start_time, end_time, language, translation_target, status, audio_path, created_at, updated_at, auto_closed)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(id) DO UPDATE SET
+            title = excluded.title, start_time = excluded.start_time, end_time = excluded.end_time,
+            language = excluded.language, translation_target = excluded.translation_target,
+            status = excluded.status, audio_path = excluded.audio_path, updated_at = excluded.updated_at,
+            auto_closed = excluded.auto_closed",
+        params![
+            meeting.id,
+            meeting.title,
+            meeting.start_time.to_rfc3339(),
+            meeting.end_time.map(|t| t.to_rfc3339()),
+            meeting.language,
+            meeting.translation_target,
+            status_to_string(&meeting.status),
+            meeting.audio_path,
+            meeting.created_at.to_rfc3339(),
+            meeting.updated_at.to_rfc3339(),
+            meeting.auto_closed as i32,
+        ],
+    )?;
+
+    // Save participants
+    for participant in &meeting.participants {
+        conn.execute(
+            "INSERT OR REPLACE INTO participants (id, meeting_id, name, color, is_local)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                participant.id,
+                meeting.id,
+                participant.name,
+                participant.color,
+                participant.is_local as i32,
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn query_meeting(conn: &Connection, meeting_id: &str) -> Result<Option<Meeting>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, start_time, end_time, language, translation_target, status, audio_path, created_at, updated_at, auto_closed
+         FROM meetings WHERE id = ?1"
+    )?;
+
+    let meeting = stmt.query_row(params![meeting_id], |row| {
+        Ok(Meeting {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            start_time: parse_datetime(row.get::<_, String>(2)?),
+            end_time: row.get::<_, Option<String>>(3)?.map(parse_datetime),
+            participants: vec![], // Will be populated below
+            language: row.get(4)?,
+            translation_target: row.get(5)?,
+            status: string_to_status(&row.get::<_, String>(6)?),
+            audio_path: row.get(7)?,
+            created_at: parse_datetime(row.get::<_, String>(8)?),
+            updated_at: parse_datetime(row.get::<_, String>(9)?),
+            auto_closed: row.get::<_, i32>(10)? != 0,
+        })
+    }).optional()?;
+
+    if let Some(mut meeting) = meeting {
+        meeting.participants = query_participants(conn, &meeting.id)?;
+        Ok(Some(meeting))
+    } else {
+        Ok(None)
+    }
+}
+
+fn active_recording_id(conn: &Connection) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT id FROM meetings WHERE status = ?1 ORDER BY start_time DESC LIMIT 1",
+        params![status_to_string(&MeetingStatus::Recording)],
+        |row| row.get(0),
+    ).optional()
+}
+
+fn query_participants(conn: &Connection, meeting_id: &str) -> Result<Vec<Participant>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, color, is_local FROM participants WHERE meeting_id = ?1"
+    )?;
+
+    let participants = stmt.query_map(params![meeting_id], |row| {
+        Ok(Participant {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            color: row.get(2)?,
+            is_local: row.get::<_, i32>(3)? != 0,
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+
+    Ok(participants)
+}
+
+fn write_note(conn: &Connection, note: &Note, insert: &str) -> Result<usize> {
+    conn.execute(
+        &format!(
+            "{} INTO notes (id, meeting_id, note_type, content, timestamp, assignee, deadline, completed, completed_at,
+                created_at, updated_at, deferred_until, source_refs, needs_review)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            insert
+        ),
+        rusqlite::params_from_iter(note_params(note)),
+    )
+}
+
+/// A note's columns in the order `write_note` inserts them
+fn note_params(note: &Note) -> Vec<Box<dyn rusqlite::ToSql>> {
+    vec![
+        Box::new(note.id.clone()),
+        Box::new(note.meeting_id.clone()),
+        Box::new(note_type_to_string(&note.note_type)),
+        Box::new(note.content.clone()),
+        Box::new(note.timestamp),
+        Box::new(note.assignee.clone()),
+        Box::new(note.deadline.map(|d| d.to_rfc3339())),
+        Box::new(note.completed as i32),
+        Box::new(note.completed_at.map(|d| d.to_rfc3339())),
+        Box::new(note.created_at.to_rfc3339()),
+        Box::new(note.updated_at.to_rfc3339()),
+        Box::new(note.deferred_until.map(|d| d.to_rfc3339())),
+        Box::new(serde_json::to_string(&note.source_refs).unwrap_or_else(|_| "[]".to_string())),
+        Box::new(note.needs_review as i32),
+    ]
+}
+
+/// Maps a row selected as `id, meeting_id, note_type, content, timestamp, assignee,
+/// deadline, completed, created_at, updated_at, completed_at, deferred_until,
+/// source_refs, needs_review`
+fn row_to_note(row: &rusqlite::Row) -> Result<Note> {
+    Ok(Note {
+        id: row.get(0)?,
+        meeting_id: row.get(1)?,
+        note_type: NoteType::from_str(&row.get::<_, String>(2)?),
+        content: row.get(3)?,
+        timestamp: row.get(4)?,
+        source_refs: row.get::<_, Option<String>>(12)?
+            .and_then(|refs| serde_json::from_str(&refs).ok())
+            .unwrap_or_default(),
+        assignee: row.get(5)?,
+        deadline: row.get::<_, Option<String>>(6)?.map(parse_datetime),
+        completed: row.get::<_, i32>(7)? != 0,
+        completed_at: row.get::<_, Option<String>>(10)?.map(parse_datetime),
+        deferred_until: row.get::<_, Option<String>>(11)?.map(parse_datetime),
+        needs_review: row.get::<_, Option<i32>>(13)?.unwrap_or(0) != 0,
+        created_at: parse_datetime(row.get::<_, String>(8)?),
+        updated_at: parse_datetime(row.get::<_, String>(9)?),
+    })
+}
+
+/// Maps a row selected as `id, meeting_id, speaker_id, speaker_name, text,
+/// timestamp, end_timestamp, confidence, language, translation, created_at,
+/// voice_pitch, voice_energy`
+fn row_to_transcript_entry(row: &rusqlite::Row) -> Result<TranscriptEntry> {
+    Ok(TranscriptEntry {
+        id: row.get(0)?,
+        meeting_id: row.get(1)?,
+        speaker_id: row.get(2)?,
+        speaker_name: row.get(3)?,
+        text: row.get(4)?,
+        timestamp: row.get(5)?,
+        end_timestamp: row.get(6)?,
+        confidence: row.get(7)?,
+        language: row.get(8)?,
+        translation: row.get(9)?,
+        created_at: parse_datetime(row.get::<_, String>(10)?),
+        voice_pitch: row.get(11)?,
+        voice_energy: row.get(12)?,
+    })
+}
+
+/// Maps a row selected as `id, meeting_id, transcript_entry_id, content,
+/// created_at, updated_at`
+fn row_to_annotation(row: &rusqlite::Row) -> Result<Annotation> {
+    Ok(Annotation {
+        id: row.get(0)?,
+        meeting_id: row.get(1)?,
+        transcript_entry_id: row.get(2)?,
+        content: row.get(3)?,
+        created_at: parse_datetime(row.get::<_, String>(4)?),
+        updated_at: parse_datetime(row.get::<_, String>(5)?),
+    })
+}
+
+fn count_transcript(conn: &Connection, meeting_id: &str) -> Result<usize> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM transcript_entries WHERE meeting_id = ?1",
+        params![meeting_id],
+        |row| row.get::<_, i64>(0),
+    ).map(|count| count as usize)
+}
+
+fn row_to_note_template(row: &rusqlite::Row) -> Result<NoteTemplate> {
+    let body: String = row.get(3)?;
+    Ok(NoteTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        note_type: NoteType::from_str(&row.get::<_, String>(2)?),
+        variables: NoteTemplate::extract_variables(&body),
+        body,
+        created_at: parse_datetime(row.get::<_, String>(4)?),
+        updated_at: parse_datetime(row.get::<_, String>(5)?),
+    })
+}
+
+fn parse_datetime(s: String) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+fn status_to_string(status: &MeetingStatus) -> &'static str {
+    match status {
+        MeetingStatus::Idle => "idle",
+        MeetingStatus::Recording => "recording",
+        MeetingStatus::Paused => "paused",
+        MeetingStatus::Completed => "completed",
+    }
+}
+
+fn string_to_status(s: &str) -> MeetingStatus {
+    match s {
+        "recording" => MeetingStatus::Recording,
+        "paused" => MeetingStatus::Paused,
+        "completed" => MeetingStatus::Completed,
+        _ => MeetingStatus::Idle,
+    }
+}
+
+fn field_type_to_string(field_type: MetadataFieldType) -> &'static str {
+    match field_type {
+        MetadataFieldType::Text => "text",
+        MetadataFieldType::Number => "number",
+        MetadataFieldType::Boolean => "boolean",
+    }
+}
+
+fn string_to_field_type(s: &str) -> MetadataFieldType {
+    match s {
+        "number" => MetadataFieldType::Number,
+        "boolean" => MetadataFieldType::Boolean,
+        _ => MetadataFieldType::Text,
+    }
+}
+
+fn note_type_to_string(note_type: &NoteType) -> &'static str {
+    match note_type {
+        NoteType::KeyPoint => "key-point",
+        NoteType::ActionItem => "action-item",
+        NoteType::Decision => "decision",
+        NoteType::Question => "question",
+        NoteType::FollowUp => "follow-up",
+        NoteType::Manual => "manual",
+    }
+}
+
+/// Where plugins send what they read, for the permission prompt
+const PLUGIN_SENDS_TO: &str = "Nowhere: plugins run sandboxed, without network or file access";
+
+/// `None` when the stored manifest no longer parses
+fn row_to_plugin(row: &rusqlite::Row) -> Result<Option<Plugin>> {
+    let manifest: String = row.get(0)?;
+    let enabled: i32 = row.get(1)?;
+    let installed_at = parse_datetime(row.get::<_, String>(2)?);
+    Ok(serde_json::from_str::<PluginManifest>(&manifest).ok().map(|manifest| Plugin {
+        integration_id: plugin_integration_id(&manifest.id),
+        manifest,
+        enabled: enabled != 0,
+        installed_at,
+    }))
+}
+
+/// Capability manifests for the integrations shipped with the app.
+/// Refreshed on every start so manifest changes ship with app updates.
+fn builtin_integration_manifests() -> Vec<IntegrationManifest> {
+    vec![
+        IntegrationManifest {
+            id: "slack".to_string(),
+            name: "Slack".to_string(),
+            reads: vec![DataScope::MeetingMetadata, DataScope::Notes, DataScope::ActionItems, DataScope::Summaries],
+            sends_to: "Slack channel (incoming webhook or bot token)".to_string(),
+        },
+        IntegrationManifest {
+            id: "notion".to_string(),
+            name: "Notion".to_string(),
+            reads: vec![DataScope::MeetingMetadata, DataScope::Notes, DataScope::ActionItems, DataScope::Summaries],
+            sends_to: "Notion database".to_string(),
+        },
+    ]
+}
+
+/// Initialize the SQLite database with required tables
+pub fn init_database(db_path: &Path) -> Result<()> {
+    let conn = get_connection(db_path)?;
+
+    // Create meetings table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS meetings (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            start_time TEXT NOT NULL,
+            end_time TEXT,
+            language TEXT NOT NULL DEFAULT 'en',
+            translation_target TEXT,
+            status TEXT NOT NULL DEFAULT 'idle',
+            audio_path TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create participants table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS participants (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            color TEXT NOT NULL,
+            is_local INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create transcript_entries table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS transcript_entries (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
             speaker_id TEXT NOT NULL,
             speaker_name TEXT NOT NULL,
             text TEXT NOT NULL,
@@ -436,6 +2053,341 @@ pub fn init_database(db_path: &Path) -> Result<()> {
         [],
     )?;
 
+    // Create screen_captures table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS screen_captures (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            image_path TEXT NOT NULL,
+            ocr_text TEXT NOT NULL DEFAULT '',
+            relevance_score REAL NOT NULL DEFAULT 0.0,
+            thumbnail_path TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create attachments table with a full-text index kept in sync by triggers
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attachments (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            title TEXT NOT NULL,
+            author TEXT,
+            sent_at TEXT,
+            source_path TEXT,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS attachments_fts USING fts5(
+            attachment_id UNINDEXED, meeting_id UNINDEXED, title, content
+         );
+         CREATE TRIGGER IF NOT EXISTS attachments_fts_insert AFTER INSERT ON attachments BEGIN
+            INSERT INTO attachments_fts (attachment_id, meeting_id, title, content)
+            VALUES (new.id, new.meeting_id, new.title, new.content);
+         END;
+         CREATE TRIGGER IF NOT EXISTS attachments_fts_delete AFTER DELETE ON attachments BEGIN
+            DELETE FROM attachments_fts WHERE attachment_id = old.id;
+         END;"
+    )?;
+
+    // Create annotations table. Entries are re-saved with INSERT OR REPLACE,
+    // so transcript_entry_id is deliberately not a foreign key: a cascade
+    // would delete the annotation every time its entry is updated.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS annotations (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            transcript_entry_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create bookmarks table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS bookmarks (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            label TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create speaker_aliases table, used by anonymized exports
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS speaker_aliases (
+            meeting_id TEXT NOT NULL,
+            speaker_id TEXT NOT NULL,
+            alias TEXT NOT NULL,
+            PRIMARY KEY (meeting_id, speaker_id),
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create AI usage table, one row per provider attempt
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ai_usage (
+            id TEXT PRIMARY KEY,
+            request_id TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            purpose TEXT NOT NULL,
+            status TEXT NOT NULL,
+            prompt_tokens INTEGER NOT NULL DEFAULT 0,
+            completion_tokens INTEGER NOT NULL DEFAULT 0,
+            cost_usd REAL NOT NULL DEFAULT 0,
+            latency_ms INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_ai_usage_created ON ai_usage(created_at)",
+        [],
+    )?;
+
+    // Create meeting summaries table, one row per generated version
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS meeting_summaries (
+            id TEXT PRIMARY KEY,
+            meeting_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            template_version INTEGER NOT NULL,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE (meeting_id, version),
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create action extraction runs, one per meeting, and meeting embeddings
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS action_extractions (
+            meeting_id TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            items INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS meeting_embeddings (
+            meeting_id TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            vector TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create settings table with defaults
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES
+            ('auto_pause_enabled', 'true'),
+            ('auto_pause_silence_seconds', '180'),
+            ('auto_pause_level_threshold', '0.02'),
+            ('auto_resume_speech_seconds', '2'),
+            ('stale_meeting_hours', '6'),
+            ('my_name', ''),
+            ('inbox_digest_enabled', 'true'),
+            ('inbox_digest_hour', '9'),
+            ('ai_provider_chain', '[]'),
+            ('ai_timeout_seconds', '30'),
+            ('ai_monthly_budgets', '{}'),
+            ('ai_budget_mode', 'warn'),
+            ('ai_embedding_model', 'text-embedding-3-small'),
+            ('backlog_concurrency', '2'),
+            ('audio_storage', 'local'),
+            ('audio_storage_dir', ''),
+            ('audio_s3_endpoint', ''),
+            ('audio_s3_region', 'us-east-1'),
+            ('audio_s3_bucket', ''),
+            ('audio_s3_prefix', 'recordings/'),
+            ('audio_s3_access_key', ''),
+            ('audio_s3_secret_key', ''),
+            ('locale', 'en'),
+            ('silence_notifications_while_recording', 'false'),
+            ('work_hours_per_day', '8'),
+            ('meeting_load_warning_share', '0.5'),
+            ('meeting_load_warned_weeks', ''),
+            ('local_api_enabled', 'false'),
+            ('local_api_port', '47616'),
+            ('local_api_token', '')",
+        [],
+    )?;
+
+    // Columns added after the initial release
+    add_column_if_missing(&conn, "meetings", "auto_closed", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "notes", "completed_at", "TEXT")?;
+    add_column_if_missing(&conn, "notes", "deferred_until", "TEXT")?;
+    add_column_if_missing(&conn, "notes", "source_refs", "TEXT")?;
+    add_column_if_missing(&conn, "notes", "needs_review", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "ai_usage", "cost_usd", "REAL NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "transcript_entries", "voice_pitch", "REAL")?;
+    add_column_if_missing(&conn, "transcript_entries", "voice_energy", "REAL")?;
+
+    // Create note_templates table and seed starter templates
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS note_templates (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            note_type TEXT NOT NULL,
+            body TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    let now = Utc::now().to_rfc3339();
+    for (id, name, note_type, body) in [
+        ("builtin-decision", "Decision", "decision", "Decision: {what} — owner {who}"),
+        ("builtin-action-item", "Action item", "action-item", "{who} to {task} by {when}"),
+        ("builtin-question", "Open question", "question", "Open question: {question} (ask {who})"),
+    ] {
+        conn.execute(
+            "INSERT OR IGNORE INTO note_templates (id, name, note_type, body, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            params![id, name, note_type, body, now],
+        )?;
+    }
+
+    // Create trigger_phrases table and seed the default phrases
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS trigger_phrases (
+            id TEXT PRIMARY KEY,
+            phrase TEXT NOT NULL,
+            note_type TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1
+        )",
+        [],
+    )?;
+    for (id, phrase, note_type) in [
+        ("builtin-action-item", "action item:", "action-item"),
+        ("builtin-lets-decide", "let's decide", "decision"),
+        ("builtin-todo", "TODO", "action-item"),
+    ] {
+        conn.execute(
+            "INSERT OR IGNORE INTO trigger_phrases (id, phrase, note_type) VALUES (?1, ?2, ?3)",
+            params![id, phrase, note_type],
+        )?;
+    }
+
+    // Create integration permission tables
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS integration_manifests (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            reads TEXT NOT NULL,
+            sends_to TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS integration_grants (
+            integration_id TEXT NOT NULL,
+            scope TEXT NOT NULL,
+            granted INTEGER NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (integration_id, scope),
+            FOREIGN KEY (integration_id) REFERENCES integration_manifests(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    for manifest in builtin_integration_manifests() {
+        let reads: Vec<&str> = manifest.reads.iter().map(|s| s.as_str()).collect();
+        conn.execute(
+            "INSERT INTO integration_manifests (id, name, reads, sends_to) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, reads = excluded.reads, sends_to = excluded.sends_to",
+            params![manifest.id, manifest.name, reads.join(","), manifest.sends_to],
+        )?;
+    }
+
+    // Create plugins table; what a plugin reads is in integration_manifests
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS plugins (
+            id TEXT PRIMARY KEY,
+            manifest TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 0,
+            installed_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create calendar_events table for imported calendars
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS calendar_events (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            start_time TEXT NOT NULL,
+            end_time TEXT NOT NULL,
+            imported_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create meeting_pauses table; an open pause has no resumed_at
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS meeting_pauses (
+            meeting_id TEXT NOT NULL,
+            paused_at TEXT NOT NULL,
+            resumed_at TEXT,
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create user-defined metadata fields and each meeting's values for them
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS metadata_fields (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            field_type TEXT NOT NULL,
+            position INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS meeting_metadata (
+            meeting_id TEXT NOT NULL,
+            field_id TEXT NOT NULL,
+            value TEXT NOT NULL,
+            PRIMARY KEY (meeting_id, field_id),
+            FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE,
+            FOREIGN KEY (field_id) REFERENCES metadata_fields(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
     // Create indexes for performance
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_transcript_meeting ON transcript_entries(meeting_id)",
@@ -445,12 +2397,159 @@ pub fn init_database(db_path: &Path) -> Result<()> {
         "CREATE INDEX IF NOT EXISTS idx_notes_meeting ON notes(meeting_id)",
         [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_meeting_metadata_field ON meeting_metadata(field_id, value)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_screen_captures_meeting ON screen_captures(meeting_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_attachments_meeting ON attachments(meeting_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_annotations_meeting ON annotations(meeting_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_bookmarks_meeting ON bookmarks(meeting_id, timestamp)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_calendar_events_start ON calendar_events(start_time)",
+        [],
+    )?;
 
     log::info!("Database initialized successfully at {:?}", db_path);
     Ok(())
 }
 
-/// Get a connection to the database
+/// Add a column to an existing table when upgrading an older database
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
+
+/// Get a connection to the database. Every connection is opened here so
+/// they all share the same settings: foreign keys are enforced (SQLite turns
+/// them off per connection by default), the journal is in WAL mode so reads
+/// don't wait for writes, and a locked database is retried for
+/// `BUSY_TIMEOUT` instead of failing at once.
 pub fn get_connection(db_path: &Path) -> Result<Connection> {
-    Connection::open(db_path)
+    let conn = Connection::open(db_path)?;
+    let journal_mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        log::warn!("Database at {:?} is in {} journal mode, WAL is unavailable", db_path, journal_mode);
+    }
+    // Safe in WAL mode, and much cheaper than FULL for transcript writes
+    conn.execute_batch("PRAGMA synchronous = NORMAL")?;
+    configure_connection(&conn)?;
+    Ok(conn)
+}
+
+/// The settings every connection needs, read-only ones included
+fn configure_connection(conn: &Connection) -> Result<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.execute_batch("PRAGMA foreign_keys = ON")
+}
+
+/// Run a user-supplied SELECT on a separate read-only connection.
+///
+/// Only a single SELECT/WITH statement is accepted, the connection is opened
+/// read-only with `query_only` set, results are capped at `QUERY_MAX_ROWS`
+/// and the query is interrupted after `QUERY_TIMEOUT`.
+pub fn run_readonly_query(db_path: &Path, sql: &str) -> std::result::Result<QueryResult, String> {
+    let sql = sql.trim();
+    if sql.is_empty() {
+        return Err("Query is empty".to_string());
+    }
+    let keyword = sql.split_whitespace().next().unwrap_or_default().to_ascii_uppercase();
+    if !["SELECT", "WITH"].contains(&keyword.as_str()) {
+        return Err(format!("Statement '{}' is not allowed; use SELECT", keyword));
+    }
+
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    ).map_err(|e| format!("Failed to open read-only connection: {}", e))?;
+    configure_connection(&conn)
+        .and_then(|_| conn.execute_batch("PRAGMA query_only = ON"))
+        .map_err(|e| format!("Failed to configure connection: {}", e))?;
+
+    // A `;` may be inside a string, so what follows the first statement is
+    // only refused if it prepares to another one
+    let mut batch = Batch::new(&conn, sql);
+    let mut stmt = batch.next()
+        .map_err(|e| format!("Invalid query: {}", e))?
+        .ok_or_else(|| "Query is empty".to_string())?;
+    if batch.next().map_err(|e| format!("Invalid query: {}", e))?.is_some() {
+        return Err("Only a single statement is allowed".to_string());
+    }
+    if !stmt.readonly() {
+        return Err("Only read-only statements are allowed".to_string());
+    }
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+
+    // Watchdog: interrupt the query once the deadline passes. Dropping the
+    // sender when we are done lets the thread exit early.
+    let interrupt = conn.get_interrupt_handle();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    std::thread::spawn(move || {
+        if let Err(mpsc::RecvTimeoutError::Timeout) = done_rx.recv_timeout(QUERY_TIMEOUT) {
+            interrupt.interrupt();
+        }
+    });
+
+    let started = Instant::now();
+    let query_error = |e: rusqlite::Error| {
+        if started.elapsed() >= QUERY_TIMEOUT {
+            format!("Query exceeded the {}s time limit", QUERY_TIMEOUT.as_secs())
+        } else {
+            format!("Query failed: {}", e)
+        }
+    };
+
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    let mut query = stmt.query([]).map_err(query_error)?;
+    while let Some(row) = query.next().map_err(query_error)? {
+        if rows.len() == QUERY_MAX_ROWS {
+            truncated = true;
+            break;
+        }
+        let values = (0..columns.len())
+            .map(|i| row.get_ref(i).map(value_to_json))
+            .collect::<Result<Vec<_>>>()
+            .map_err(query_error)?;
+        rows.push(values);
+    }
+    drop(done_tx);
+
+    Ok(QueryResult {
+        columns,
+        rows,
+        truncated,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+fn value_to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::from(i),
+        ValueRef::Real(f) => serde_json::Value::from(f),
+        ValueRef::Text(t) => serde_json::Value::from(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => serde_json::Value::from(format!("<blob {} bytes>", b.len())),
+    }
 }
@@ -0,0 +1,154 @@
+// Do Not Disturb - Silence system notifications while a meeting is recording
+//
+// With `silence_notifications_while_recording` on, the OS's own Do Not
+// Disturb is switched on whenever a meeting is Recording, so notification
+// sounds don't end up in the audio, and switched back when it ends or pauses.
+// Only what we turned on is turned off again: if the user already had Do Not
+// Disturb on, it is left alone. On GNOME the notification banners are
+// turned off, and a marker setting records that we did so, letting the next
+// start turn them back on if the app was killed mid-recording. Elsewhere
+// there is no API an unpackaged app can use (Windows focus sessions need a
+// Limited Access Feature token), and the setting does nothing.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::AppState;
+use crate::db::Database;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// Set while notifications are silenced by us, so a crash can be undone
+const RESTORE_PENDING_KEY: &str = "dnd_restore_pending";
+
+/// How to undo what we changed
+enum Restore {
+    #[cfg(target_os = "linux")]
+    GnomeBanners,
+}
+
+#[derive(Default)]
+struct Inner {
+    silenced: bool,
+    /// Set when it was us who switched Do Not Disturb on
+    restore: Option<Restore>,
+}
+
+#[derive(Default)]
+pub struct DoNotDisturb(Mutex<Inner>);
+
+impl DoNotDisturb {
+    /// Switches Do Not Disturb on or off to match `silenced`. A failure to
+    /// switch it on is only reported once per recording.
+    pub fn set(&self, db: &Database, silenced: bool) {
+        let mut inner = self.0.lock().unwrap();
+        if inner.silenced == silenced {
+            return;
+        }
+        inner.silenced = silenced;
+
+        if silenced {
+            match silence() {
+                Ok(Some(restore)) => {
+                    log::info!("Silenced system notifications for recording");
+                    inner.restore = Some(restore);
+                    set_restore_pending(db, true);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to silence system notifications: {}", e),
+            }
+        } else if let Some(restore) = inner.restore.take() {
+            match unsilence(restore) {
+                Ok(()) => {
+                    log::info!("Restored system notifications");
+                    set_restore_pending(db, false);
+                }
+                Err(e) => log::warn!("Failed to restore system notifications: {}", e),
+            }
+        }
+    }
+}
+
+/// Keeps Do Not Disturb in step with the recording state, whichever way the
+/// meeting was started, paused or ended
+pub fn spawn_recording_dnd(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        restore_after_crash(&app.state::<AppState>().db);
+        loop {
+            let state = app.state::<AppState>();
+            let silenced = wants_silence(&state.db);
+            state.dnd.set(&state.db, silenced);
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn wants_silence(db: &Database) -> bool {
+    let enabled = db.get_setting("silence_notifications_while_recording").ok().flatten()
+        .is_some_and(|v| v == "true");
+    enabled && db.get_recording_meeting().ok().flatten().is_some()
+}
+
+/// Turns notifications back on if the last run silenced them and never got
+/// to restore them
+fn restore_after_crash(db: &Database) {
+    let pending = db.get_setting(RESTORE_PENDING_KEY).ok().flatten().is_some_and(|v| v == "true");
+    if !pending {
+        return;
+    }
+    #[cfg(target_os = "linux")]
+    match unsilence(Restore::GnomeBanners) {
+        Ok(()) => log::info!("Restored system notifications left silenced by the last run"),
+        Err(e) => {
+            log::warn!("Failed to restore system notifications: {}", e);
+            return;
+        }
+    }
+    set_restore_pending(db, false);
+}
+
+fn set_restore_pending(db: &Database, pending: bool) {
+    if let Err(e) = db.set_setting(RESTORE_PENDING_KEY, if pending { "true" } else { "false" }) {
+        log::warn!("Failed to save the notification restore marker: {}", e);
+    }
+}
+
+/// Turns Do Not Disturb on. Returns `None` if it was already on.
+#[cfg(target_os = "linux")]
+fn silence() -> Result<Option<Restore>, String> {
+    if gsettings(&["get", GNOME_SCHEMA, GNOME_KEY])? == "false" {
+        return Ok(None);
+    }
+    gsettings(&["set", GNOME_SCHEMA, GNOME_KEY, "false"])?;
+    Ok(Some(Restore::GnomeBanners))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn silence() -> Result<Option<Restore>, String> {
+    Err("Do Not Disturb can't be controlled on this platform".to_string())
+}
+
+fn unsilence(restore: Restore) -> Result<(), String> {
+    match restore {
+        #[cfg(target_os = "linux")]
+        Restore::GnomeBanners => gsettings(&["set", GNOME_SCHEMA, GNOME_KEY, "true"]).map(|_| ()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+const GNOME_SCHEMA: &str = "org.gnome.desktop.notifications";
+#[cfg(target_os = "linux")]
+const GNOME_KEY: &str = "show-banners";
+
+/// Runs `gsettings`, which fails where GNOME isn't installed
+#[cfg(target_os = "linux")]
+fn gsettings(args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("gsettings")
+        .args(args)
+        .output()
+        .map_err(|e| format!("gsettings is not available: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
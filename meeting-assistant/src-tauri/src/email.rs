@@ -0,0 +1,100 @@
+// Email - Parse .eml files attached to meetings as context
+
+use chrono::{DateTime, TimeZone, Utc};
+use mailparse::{DispositionType, MailHeaderMap, ParsedMail};
+
+pub struct ParsedEmail {
+    pub subject: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub body: String,
+}
+
+pub fn parse_eml(raw: &[u8]) -> Result<ParsedEmail, String> {
+    let mail = mailparse::parse_mail(raw).map_err(|e| format!("Failed to parse email: {}", e))?;
+    let header = |key: &str| {
+        mail.headers.get_first_value(key)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let body = text_body(&mail)
+        .ok_or_else(|| "Email has no readable text body".to_string())?;
+
+    Ok(ParsedEmail {
+        subject: header("Subject").unwrap_or_else(|| "(no subject)".to_string()),
+        from: header("From"),
+        to: header("To"),
+        sent_at: header("Date")
+            .and_then(|d| mailparse::dateparse(&d).ok())
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).single()),
+        body,
+    })
+}
+
+/// Prefers a text/plain part anywhere in the MIME tree, falling back to
+/// text/html with the markup stripped. Attached files are skipped.
+fn text_body(mail: &ParsedMail) -> Option<String> {
+    find_part(mail, "text/plain")
+        .or_else(|| find_part(mail, "text/html").map(|html| strip_html(&html)))
+        .map(|body| normalize_whitespace(&body))
+        .filter(|body| !body.is_empty())
+}
+
+fn find_part(part: &ParsedMail, mimetype: &str) -> Option<String> {
+    if matches!(part.get_content_disposition().disposition, DispositionType::Attachment) {
+        return None;
+    }
+    if part.subparts.is_empty() {
+        return if part.ctype.mimetype.eq_ignore_ascii_case(mimetype) {
+            part.get_body().ok()
+        } else {
+            None
+        };
+    }
+    part.subparts.iter().find_map(|sub| find_part(sub, mimetype))
+}
+
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    let mut tag = String::new();
+
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag.trim_start_matches('/').split_whitespace().next().unwrap_or("").to_ascii_lowercase();
+                if matches!(name.as_str(), "br" | "br/" | "p" | "div" | "tr" | "li" | "blockquote") {
+                    text.push('\n');
+                }
+            }
+            _ if in_tag => tag.push(c),
+            _ => text.push(c),
+        }
+    }
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Trim trailing spaces and collapse runs of blank lines
+fn normalize_whitespace(text: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().map_or(true, |l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
@@ -0,0 +1,44 @@
+// Command Errors - Typed errors for commands the frontend needs to tell apart
+
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+    /// Another meeting is already recording; only one capture may run at a time
+    #[error("Meeting {active_meeting_id} is already recording")]
+    Conflict { active_meeting_id: String },
+
+    #[error("Meeting not found")]
+    NotFound,
+
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+}
+
+impl CommandError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CommandError::Conflict { .. } => "conflict",
+            CommandError::NotFound => "not_found",
+            CommandError::Database(_) => "database",
+        }
+    }
+}
+
+/// Serialized as `{ kind, message, active_meeting_id? }` so the frontend can
+/// branch on `kind` while still showing `message` to the user
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 3)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            CommandError::Conflict { active_meeting_id } => {
+                state.serialize_field("active_meeting_id", active_meeting_id)?;
+            }
+            _ => state.skip_field("active_meeting_id")?,
+        }
+        state.end()
+    }
+}
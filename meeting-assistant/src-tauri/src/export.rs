@@ -0,0 +1,559 @@
+// Export - Meeting documents streamed to their destination
+//
+// The transcript is read from the database in chunks and each chunk is
+// written out before the next is fetched, so memory use stays flat no matter
+// how long the meeting ran. Markdown, JSON and HTML share the same pipeline.
+// Markdown and HTML format each entry's text for its language (see
+// `typography`), which entries saved before formatting was added still
+// need; JSON keeps the text as stored.
+
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::anonymize::Anonymizer;
+use crate::db::Database;
+use crate::locale::Locale;
+use crate::typography::Typography;
+use crate::models::{Annotation, Bookmark, DataScope, Meeting, MetadataFieldType, MetadataValue, Note, NoteType, TranscriptEntry};
+
+pub const PROGRESS_EVENT: &str = "export-progress";
+/// Transcript entries fetched and written per step
+const CHUNK_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+pub struct ExportOptions {
+    pub include_transcript: bool,
+    pub include_notes: bool,
+    pub include_summary: Option<String>,
+    /// Private annotations are only exported when asked for
+    pub include_annotations: bool,
+    /// Show speakers and participants under their aliases
+    pub anonymize: bool,
+    /// Link back to an index page, shown at the top of HTML documents
+    pub index_link: Option<String>,
+}
+
+impl ExportOptions {
+    /// The data an export with these options hands over
+    pub fn scopes(&self) -> Vec<DataScope> {
+        let mut scopes = vec![DataScope::MeetingMetadata];
+        if self.include_transcript {
+            scopes.push(DataScope::Transcripts);
+        }
+        if self.include_notes {
+            scopes.extend([DataScope::Notes, DataScope::ActionItems]);
+        }
+        if self.include_summary.is_some() {
+            scopes.push(DataScope::Summaries);
+        }
+        if self.include_annotations {
+            scopes.push(DataScope::Annotations);
+        }
+        scopes
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub meeting_id: String,
+    pub file_path: String,
+    pub entries_written: usize,
+    pub total_entries: usize,
+    pub bytes_written: u64,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportFileResult {
+    pub file_path: String,
+    pub format: ExportFormat,
+    pub entries_written: usize,
+    pub bytes_written: u64,
+}
+
+/// Suggested file name for an export, e.g. "Weekly_Sync.md"
+pub fn file_name(title: &str, suffix: &str, format: ExportFormat) -> String {
+    format!("{}{}.{}", title.replace(' ', "_").replace('/', "-"), suffix, format.extension())
+}
+
+/// Writes the export to `path`. The document is built in a sibling
+/// ".partial" file and renamed into place once complete, so a failed export
+/// never leaves a truncated file behind. `on_progress` is called after each
+/// chunk and once more when done.
+pub fn export_to_file(
+    db: &Database,
+    meeting: &Meeting,
+    format: ExportFormat,
+    options: &ExportOptions,
+    path: &Path,
+    mut on_progress: impl FnMut(&ExportProgress),
+) -> Result<ExportFileResult, String> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    let file = File::create(&partial)
+        .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+    let file_path = path.to_string_lossy().into_owned();
+
+    let mut progress = ExportProgress {
+        meeting_id: meeting.id.clone(),
+        file_path: file_path.clone(),
+        entries_written: 0,
+        total_entries: 0,
+        bytes_written: 0,
+        done: false,
+    };
+    let result = write_meeting(db, meeting, format, options, BufWriter::new(file), |entries, total, bytes| {
+        progress.entries_written = entries;
+        progress.total_entries = total;
+        progress.bytes_written = bytes;
+        on_progress(&progress);
+    });
+
+    let (entries_written, bytes_written) = match result {
+        Ok(written) => written,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    fs::rename(&partial, path).map_err(|e| {
+        let _ = fs::remove_file(&partial);
+        format!("Failed to write {}: {}", path.display(), e)
+    })?;
+
+    progress.done = true;
+    on_progress(&progress);
+
+    Ok(ExportFileResult { file_path, format, entries_written, bytes_written })
+}
+
+/// Streams the document into `out` and returns (entries, bytes) written
+pub fn write_meeting<W: Write>(
+    db: &Database,
+    meeting: &Meeting,
+    format: ExportFormat,
+    options: &ExportOptions,
+    out: W,
+    mut on_progress: impl FnMut(usize, usize, u64),
+) -> Result<(usize, u64), String> {
+    let anonymizer = if options.anonymize { Some(Anonymizer::load(db, meeting)?) } else { None };
+    let notes = if options.include_notes {
+        db.get_notes(&meeting.id).map_err(|e| format!("Failed to fetch notes: {}", e))?
+    } else {
+        vec![]
+    };
+    let annotations = if options.include_annotations {
+        db.get_annotations(&meeting.id).map_err(|e| format!("Failed to fetch annotations: {}", e))?
+    } else {
+        vec![]
+    };
+    let bookmarks = db.get_bookmarks(&meeting.id)
+        .map_err(|e| format!("Failed to fetch bookmarks: {}", e))?;
+    let metadata = db.get_meeting_metadata(&meeting.id)
+        .map_err(|e| format!("Failed to fetch meeting metadata: {}", e))?;
+    let total = if options.include_transcript {
+        db.count_transcript_entries(&meeting.id)
+            .map_err(|e| format!("Failed to fetch transcript: {}", e))?
+    } else {
+        0
+    };
+
+    let mut summary = options.include_summary.clone();
+    let mut meeting = Cow::Borrowed(meeting);
+    let (mut notes, mut annotations, mut bookmarks) = (notes, annotations, bookmarks);
+    if let Some(ref anonymizer) = anonymizer {
+        meeting = Cow::Owned(anonymizer.meeting(&meeting));
+        summary = summary.map(|summary| anonymizer.text(&summary));
+        notes = notes.iter().map(|note| anonymizer.note(note)).collect();
+        annotations = annotations.iter().map(|annotation| anonymizer.annotation(annotation)).collect();
+        bookmarks = bookmarks.iter().map(|bookmark| anonymizer.bookmark(bookmark)).collect();
+    }
+
+    let io_error = |e: io::Error| format!("Failed to write export: {}", e);
+    let mut out = CountingWriter { inner: out, bytes: 0 };
+    let document = Document {
+        meeting: &meeting,
+        notes: &notes,
+        annotations: &annotations,
+        bookmarks: &bookmarks,
+        metadata: &metadata,
+        summary: summary.as_deref(),
+        index_link: options.index_link.as_deref(),
+        locale: Locale::from_settings(db),
+    };
+
+    document.begin(format, &mut out, total > 0).map_err(io_error)?;
+
+    let mut written = 0;
+    let mut after: Option<(i64, String)> = None;
+    while written < total {
+        let chunk = db.get_transcript_chunk(&meeting.id, after.as_ref().map(|(ts, id)| (*ts, id.as_str())), CHUNK_SIZE)
+            .map_err(|e| format!("Failed to fetch transcript: {}", e))?;
+        let Some(last) = chunk.last() else { break };
+        after = Some((last.timestamp, last.id.clone()));
+
+        for entry in &chunk {
+            let entry = match anonymizer {
+                Some(ref anonymizer) => Cow::Owned(anonymizer.entry(entry)),
+                None => Cow::Borrowed(entry),
+            };
+            document.entry(format, &mut out, &entry, written == 0).map_err(io_error)?;
+            written += 1;
+        }
+        out.flush().map_err(io_error)?;
+        on_progress(written, total, out.bytes);
+    }
+
+    document.end(format, &mut out, total > 0).map_err(io_error)?;
+    out.flush().map_err(io_error)?;
+    on_progress(written, total, out.bytes);
+
+    Ok((written, out.bytes))
+}
+
+struct CountingWriter<W> {
+    inner: W,
+    bytes: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct Document<'a> {
+    meeting: &'a Meeting,
+    notes: &'a [Note],
+    annotations: &'a [Annotation],
+    bookmarks: &'a [Bookmark],
+    metadata: &'a [MetadataValue],
+    summary: Option<&'a str>,
+    index_link: Option<&'a str>,
+    locale: Locale,
+}
+
+impl Document<'_> {
+    /// Everything before the first transcript entry
+    fn begin(&self, format: ExportFormat, out: &mut impl Write, has_transcript: bool) -> io::Result<()> {
+        match format {
+            ExportFormat::Markdown => self.begin_markdown(out, has_transcript),
+            ExportFormat::Json => self.begin_json(out),
+            ExportFormat::Html => self.begin_html(out, has_transcript),
+        }
+    }
+
+    fn entry(&self, format: ExportFormat, out: &mut impl Write, entry: &TranscriptEntry, first: bool) -> io::Result<()> {
+        match format {
+            ExportFormat::Markdown => {
+                let text = Typography::for_language(&entry.language).format_isolated(&entry.text);
+                write!(out, "**[{}] {}**: {}\n\n", format_timestamp(entry.timestamp), entry.speaker_name, text)?;
+                for annotation in self.annotations_on(entry) {
+                    write!(out, "> *Annotation:* {}\n\n", annotation.content)?;
+                }
+                Ok(())
+            }
+            ExportFormat::Json => {
+                if !first {
+                    out.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut *out, entry).map_err(io::Error::from)
+            }
+            ExportFormat::Html => {
+                let typography = Typography::for_language(&entry.language);
+                let dir = if typography.is_rtl() { "rtl" } else { "auto" };
+                writeln!(
+                    out,
+                    "<p><span class=\"time\">[{}]</span> <strong>{}</strong>: <span dir=\"{}\">{}</span></p>",
+                    format_timestamp(entry.timestamp),
+                    escape_html(&entry.speaker_name),
+                    dir,
+                    escape_html(&typography.format(&entry.text))
+                )?;
+                for annotation in self.annotations_on(entry) {
+                    writeln!(out, "<p class=\"annotation\">{}</p>", escape_html(&annotation.content))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn annotations_on<'b>(&'b self, entry: &'b TranscriptEntry) -> impl Iterator<Item = &'b Annotation> {
+        self.annotations.iter().filter(move |a| a.transcript_entry_id == entry.id)
+    }
+
+    /// Everything after the last transcript entry
+    fn end(&self, format: ExportFormat, out: &mut impl Write, has_transcript: bool) -> io::Result<()> {
+        let exported_at = Utc::now();
+        match format {
+            ExportFormat::Markdown => {
+                out.write_all(b"\n---\n\n")?;
+                writeln!(out, "*Exported from Meeting Assistant on {}*", exported_at.format("%Y-%m-%d %H:%M UTC"))
+            }
+            ExportFormat::Json => write!(out, "],\"exported_at\":{}}}", json!(exported_at)),
+            ExportFormat::Html => {
+                if has_transcript {
+                    out.write_all(b"</section>\n")?;
+                }
+                writeln!(
+                    out,
+                    "<footer>Exported from Meeting Assistant on {}</footer>\n</body>\n</html>",
+                    exported_at.format("%Y-%m-%d %H:%M UTC")
+                )
+            }
+        }
+    }
+
+    fn begin_markdown(&self, out: &mut impl Write, has_transcript: bool) -> io::Result<()> {
+        let meeting = self.meeting;
+        write!(out, "# {}\n\n", meeting.title)?;
+
+        out.write_all(b"## Meeting Information\n\n")?;
+        for (label, value) in self.info() {
+            writeln!(out, "- **{}**: {}", label, value)?;
+        }
+        out.write_all(b"\n---\n\n")?;
+
+        if let Some(summary) = self.summary {
+            out.write_all(b"## Summary\n\n")?;
+            out.write_all(summary.as_bytes())?;
+            out.write_all(b"\n\n---\n\n")?;
+        }
+
+        if !self.notes.is_empty() {
+            out.write_all(b"## Notes\n\n")?;
+            for (heading, notes) in self.note_groups() {
+                write!(out, "### {}\n\n", heading)?;
+                for note in notes {
+                    if matches!(note.note_type, NoteType::ActionItem) {
+                        let checkbox = if note.completed { "[x]" } else { "[ ]" };
+                        write!(out, "- {} {}", checkbox, note.content)?;
+                        if let Some(ref assignee) = note.assignee {
+                            write!(out, " *({})*", assignee)?;
+                        }
+                        out.write_all(b"\n")?;
+                    } else {
+                        writeln!(out, "- {}", note.content)?;
+                    }
+                }
+                out.write_all(b"\n")?;
+            }
+            out.write_all(b"---\n\n")?;
+        }
+
+        if !self.bookmarks.is_empty() {
+            out.write_all(b"## Bookmarks\n\n")?;
+            for bookmark in self.bookmarks {
+                write!(out, "- **[{}]**", format_timestamp(bookmark.timestamp))?;
+                match bookmark.label {
+                    Some(ref label) => writeln!(out, " {}", label)?,
+                    None => out.write_all(b"\n")?,
+                }
+            }
+            out.write_all(b"\n---\n\n")?;
+        }
+
+        if has_transcript {
+            out.write_all(b"## Transcript\n\n")?;
+        }
+        Ok(())
+    }
+
+    fn begin_json(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(
+            out,
+            "{{\"meeting\":{},\"metadata\":{},\"summary\":{},\"notes\":{},",
+            json!(self.meeting),
+            json!(self.metadata_json()),
+            json!(self.summary),
+            json!(self.notes)
+        )?;
+        if !self.annotations.is_empty() {
+            write!(out, "\"annotations\":{},", json!(self.annotations))?;
+        }
+        write!(out, "\"bookmarks\":{},", json!(self.bookmarks))?;
+        out.write_all(b"\"transcript\":[")
+    }
+
+    fn begin_html(&self, out: &mut impl Write, has_transcript: bool) -> io::Result<()> {
+        let title = escape_html(&self.meeting.title);
+        writeln!(out, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>", title)?;
+        out.write_all(
+            b"<style>\n\
+              body { font-family: system-ui, sans-serif; max-width: 48rem; margin: 2rem auto; line-height: 1.5; }\n\
+              .time { color: #6b7280; font-variant-numeric: tabular-nums; }\n\
+              .summary { white-space: pre-wrap; }\n\
+              .annotation { margin-left: 1.5rem; color: #92400e; font-style: italic; }\n\
+              nav { font-size: 0.875rem; }\n\
+              footer { margin-top: 2rem; color: #6b7280; font-size: 0.875rem; }\n\
+              </style>\n</head>\n<body>\n",
+        )?;
+        if let Some(href) = self.index_link {
+            writeln!(out, "<nav><a href=\"{}\">&larr; All meetings</a></nav>", escape_html(href))?;
+        }
+        writeln!(out, "<h1>{}</h1>", title)?;
+
+        out.write_all(b"<section>\n<h2>Meeting Information</h2>\n<ul>\n")?;
+        for (label, value) in self.info() {
+            writeln!(out, "<li><strong>{}</strong>: {}</li>", escape_html(&label), escape_html(&value))?;
+        }
+        out.write_all(b"</ul>\n</section>\n")?;
+
+        if let Some(summary) = self.summary {
+            writeln!(out, "<section>\n<h2>Summary</h2>\n<div class=\"summary\">{}</div>\n</section>", escape_html(summary))?;
+        }
+
+        if !self.notes.is_empty() {
+            out.write_all(b"<section>\n<h2>Notes</h2>\n")?;
+            for (heading, notes) in self.note_groups() {
+                writeln!(out, "<h3>{}</h3>\n<ul>", heading)?;
+                for note in notes {
+                    out.write_all(b"<li>")?;
+                    if matches!(note.note_type, NoteType::ActionItem) {
+                        let checked = if note.completed { " checked" } else { "" };
+                        write!(out, "<input type=\"checkbox\" disabled{}> ", checked)?;
+                    }
+                    out.write_all(escape_html(&note.content).as_bytes())?;
+                    if let Some(ref assignee) = note.assignee {
+                        write!(out, " <em>({})</em>", escape_html(assignee))?;
+                    }
+                    out.write_all(b"</li>\n")?;
+                }
+                out.write_all(b"</ul>\n")?;
+            }
+            out.write_all(b"</section>\n")?;
+        }
+
+        if !self.bookmarks.is_empty() {
+            out.write_all(b"<section>\n<h2>Bookmarks</h2>\n<ul>\n")?;
+            for bookmark in self.bookmarks {
+                writeln!(
+                    out,
+                    "<li><span class=\"time\">[{}]</span> {}</li>",
+                    format_timestamp(bookmark.timestamp),
+                    escape_html(bookmark.label.as_deref().unwrap_or(""))
+                )?;
+            }
+            out.write_all(b"</ul>\n</section>\n")?;
+        }
+
+        if has_transcript {
+            out.write_all(b"<section>\n<h2>Transcript</h2>\n")?;
+        }
+        Ok(())
+    }
+
+    fn info(&self) -> Vec<(Cow<'static, str>, String)> {
+        let meeting = self.meeting;
+        let mut info = vec![
+            ("Date".into(), self.locale.format_date(meeting.start_time)),
+            ("Start Time".into(), meeting.start_time.format("%H:%M").to_string()),
+        ];
+        if let Some(end) = meeting.end_time {
+            info.push(("End Time".into(), end.format("%H:%M").to_string()));
+            let duration = end.signed_duration_since(meeting.start_time).num_seconds();
+            info.push(("Duration".into(), self.locale.format_duration(duration)));
+        }
+        let participants = if meeting.participants.is_empty() {
+            "N/A".to_string()
+        } else {
+            meeting.participants.iter().map(|p| p.name.clone()).collect::<Vec<_>>().join(", ")
+        };
+        info.push(("Participants".into(), participants));
+        for field in self.metadata {
+            let value = match field.field_type {
+                MetadataFieldType::Boolean if field.value == "true" => "Yes".to_string(),
+                MetadataFieldType::Boolean => "No".to_string(),
+                _ => field.value.clone(),
+            };
+            info.push((field.name.clone().into(), value));
+        }
+        info
+    }
+
+    /// Field names to typed values, so numbers and booleans stay usable
+    fn metadata_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.metadata.iter()
+            .map(|field| {
+                let value = match field.field_type {
+                    MetadataFieldType::Number => field.value.parse::<f64>().map(|n| json!(n)).unwrap_or(json!(field.value)),
+                    MetadataFieldType::Boolean => json!(field.value == "true"),
+                    MetadataFieldType::Text => json!(field.value),
+                };
+                (field.name.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Non-empty note groups in display order
+    fn note_groups(&self) -> Vec<(&'static str, Vec<&Note>)> {
+        let of_type = |matches: fn(&NoteType) -> bool| self.notes.iter().filter(|n| matches(&n.note_type)).collect::<Vec<_>>();
+        [
+            ("Action Items", of_type(|t| matches!(t, NoteType::ActionItem))),
+            ("Key Points", of_type(|t| matches!(t, NoteType::KeyPoint))),
+            ("Decisions", of_type(|t| matches!(t, NoteType::Decision))),
+            ("Other Notes", of_type(|t| !matches!(t, NoteType::ActionItem | NoteType::KeyPoint | NoteType::Decision))),
+        ]
+        .into_iter()
+        .filter(|(_, notes)| !notes.is_empty())
+        .collect()
+    }
+}
+
+pub fn format_timestamp(ms: i64) -> String {
+    let total_secs = ms / 1000;
+    let hours = total_secs / 3600;
+    let mins = (total_secs % 3600) / 60;
+    let secs = total_secs % 60;
+
+    if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, mins, secs)
+    } else {
+        format!("{:02}:{:02}", mins, secs)
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
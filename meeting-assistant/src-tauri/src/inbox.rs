@@ -0,0 +1,109 @@
+// Inbox - Action items assigned to the user and the daily digest notification
+
+use std::time::Duration;
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Timelike, Utc};
+use tauri::{AppHandle, Manager};
+use tauri::api::notification::Notification;
+
+use crate::commands::AppState;
+use crate::db::Database;
+use crate::models::InboxItem;
+
+/// How often the digest task wakes up to see whether today's digest is due
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const LAST_DIGEST_KEY: &str = "inbox_last_digest";
+
+/// The user's own name as set in `my_name`, used to resolve assignee "me"
+pub fn my_name(db: &Database) -> Result<String, String> {
+    db.get_setting("my_name")
+        .map_err(|e| format!("Failed to read settings: {}", e))?
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| "Set your name in settings (my_name) to use the inbox".to_string())
+}
+
+/// Open action items assigned to the user that are not deferred into the future
+pub fn inbox_items(db: &Database) -> Result<Vec<InboxItem>, String> {
+    let me = my_name(db)?;
+    let now = Utc::now();
+    let items = db.list_action_items(Some(&me), false, None)
+        .map_err(|e| format!("Failed to fetch action items: {}", e))?;
+
+    Ok(items.into_iter()
+        .filter(|item| item.note.deferred_until.map_or(true, |until| until <= now))
+        .collect())
+}
+
+/// Checks periodically and, once a day after `inbox_digest_hour`, notifies
+/// the user about action items assigned to them in yesterday's meetings
+pub fn spawn_daily_digest(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = send_digest_if_due(&app) {
+                log::warn!("Inbox digest failed: {}", e);
+            }
+            tokio::time::sleep(DIGEST_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn send_digest_if_due(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let db = &state.db;
+    let setting = |key: &str| db.get_setting(key).ok().flatten();
+
+    if setting("inbox_digest_enabled").as_deref() == Some("false") {
+        return Ok(());
+    }
+    let Ok(me) = my_name(db) else {
+        return Ok(());
+    };
+
+    let now = Local::now();
+    let digest_hour = setting("inbox_digest_hour")
+        .and_then(|h| h.parse::<u32>().ok())
+        .unwrap_or(9);
+    let today = now.date_naive().to_string();
+    if now.hour() < digest_hour || setting(LAST_DIGEST_KEY).as_deref() == Some(today.as_str()) {
+        return Ok(());
+    }
+
+    let today_start = local_midnight(now.date_naive());
+    let yesterday_start = local_midnight(now.date_naive() - chrono::Duration::days(1));
+    let items: Vec<InboxItem> = db.list_action_items(Some(&me), false, Some(yesterday_start))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| item.meeting_start < today_start)
+        .collect();
+
+    if !items.is_empty() {
+        let meetings = items.iter()
+            .map(|item| item.meeting_title.as_str())
+            .collect::<std::collections::BTreeSet<_>>();
+        let body = format!(
+            "{} new action item{} from {} meeting{} yesterday",
+            items.len(),
+            if items.len() == 1 { "" } else { "s" },
+            meetings.len(),
+            if meetings.len() == 1 { "" } else { "s" },
+        );
+
+        Notification::new(&app.config().tauri.bundle.identifier)
+            .title("Your inbox")
+            .body(body)
+            .show()
+            .map_err(|e| e.to_string())?;
+    }
+
+    db.set_setting(LAST_DIGEST_KEY, &today).map_err(|e| e.to_string())?;
+    log::info!("Inbox digest sent ({} items)", items.len());
+    Ok(())
+}
+
+pub fn local_midnight(date: chrono::NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local.from_local_datetime(&midnight)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
@@ -0,0 +1,276 @@
+// Knowledge base - The whole meeting archive as a static site
+//
+// `export_knowledge_base` writes every completed meeting into a folder of
+// plain HTML pages that can be opened from disk, put on a shared drive or
+// served by any web server as an internal wiki. Each meeting gets a page
+// rendered by the HTML exporter with its latest summary, notes and,
+// optionally, transcript. Index pages list the meetings by date, by series
+// (recurring meetings, see `series_key`), by tag (the meeting's metadata
+// values) and by participant, and collect every decision in one place.
+// Links are relative, so the folder can be moved as a whole.
+//
+// Exporting into the same folder again replaces the pages; pages of meetings
+// deleted since are left behind. Private annotations are never included.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::export::{self, escape_html, ExportFormat, ExportOptions};
+use crate::locale::Locale;
+use crate::models::{series_key, Meeting, MeetingStatus, MetadataFieldType, NoteType};
+
+/// Folder for meeting pages, relative to the site root
+const MEETINGS_DIR: &str = "meetings";
+/// Characters of a summary shown in the meeting list
+const EXCERPT_CHARS: usize = 240;
+/// Characters of a title kept in a page's file name
+const FILE_TITLE_CHARS: usize = 60;
+
+const INDEXES: [(&str, &str); 5] = [
+    ("index.html", "Meetings"),
+    ("series.html", "Series"),
+    ("tags.html", "Tags"),
+    ("participants.html", "Participants"),
+    ("decisions.html", "Decisions"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeBaseResult {
+    pub dest_dir: String,
+    /// The page to open first
+    pub index_path: String,
+    pub meetings: usize,
+    pub decisions: usize,
+    pub bytes_written: u64,
+}
+
+/// What the index pages need to know about one exported meeting
+struct Entry {
+    meeting: Meeting,
+    /// Page path relative to the site root
+    href: String,
+    date: String,
+    summary: Option<String>,
+    tags: Vec<String>,
+    decisions: Vec<String>,
+}
+
+pub fn export_knowledge_base(db: &Database, dest_dir: &Path, include_transcripts: bool) -> Result<KnowledgeBaseResult, String> {
+    let mut meetings: Vec<Meeting> = db.get_all_meetings()
+        .map_err(|e| format!("Failed to fetch meetings: {}", e))?
+        .into_iter()
+        .filter(|meeting| meeting.status == MeetingStatus::Completed)
+        .collect();
+    meetings.sort_by_key(|meeting| Reverse(meeting.start_time));
+
+    fs::create_dir_all(dest_dir.join(MEETINGS_DIR))
+        .map_err(|e| format!("Failed to create {}: {}", dest_dir.display(), e))?;
+    let locale = Locale::from_settings(db);
+
+    let mut entries = Vec::with_capacity(meetings.len());
+    let mut bytes_written = 0;
+    for meeting in meetings {
+        let summary = db.get_summary_versions(&meeting.id)
+            .map_err(|e| format!("Failed to fetch summaries: {}", e))?
+            .into_iter()
+            .next()
+            .map(|summary| summary.content);
+        let options = ExportOptions {
+            include_transcript: include_transcripts,
+            include_notes: true,
+            include_summary: summary.clone(),
+            include_annotations: false,
+            anonymize: false,
+            index_link: Some("../index.html".to_string()),
+        };
+        let href = format!("{}/{}", MEETINGS_DIR, page_name(&meeting));
+        let written = export::export_to_file(db, &meeting, ExportFormat::Html, &options, &dest_dir.join(&href), |_| {})?;
+        bytes_written += written.bytes_written;
+
+        let tags = db.get_meeting_metadata(&meeting.id)
+            .map_err(|e| format!("Failed to fetch meeting metadata: {}", e))?
+            .into_iter()
+            .filter_map(|field| match field.field_type {
+                MetadataFieldType::Boolean => (field.value == "true").then_some(field.name),
+                _ if field.value.trim().is_empty() => None,
+                _ => Some(format!("{}: {}", field.name, field.value.trim())),
+            })
+            .collect();
+        let decisions = db.get_notes(&meeting.id)
+            .map_err(|e| format!("Failed to fetch notes: {}", e))?
+            .into_iter()
+            .filter(|note| matches!(note.note_type, NoteType::Decision))
+            .map(|note| note.content)
+            .collect();
+
+        entries.push(Entry { date: locale.format_date(meeting.start_time), meeting, href, summary, tags, decisions });
+    }
+
+    let pages = [
+        meetings_page(&entries),
+        groups_page(&group_by_series(&entries)),
+        groups_page(&group_by(&entries, |entry| entry.tags.clone())),
+        groups_page(&group_by(&entries, |entry| entry.meeting.participants.iter().map(|p| p.name.clone()).collect())),
+        decisions_page(&entries),
+    ];
+    for ((file, title), body) in INDEXES.iter().zip(pages) {
+        let html = page(title, file, &body);
+        fs::write(dest_dir.join(file), &html)
+            .map_err(|e| format!("Failed to write {}: {}", dest_dir.join(file).display(), e))?;
+        bytes_written += html.len() as u64;
+    }
+
+    log::info!("Exported {} meetings to the knowledge base in {}", entries.len(), dest_dir.display());
+    Ok(KnowledgeBaseResult {
+        dest_dir: dest_dir.to_string_lossy().into_owned(),
+        index_path: dest_dir.join(INDEXES[0].0).to_string_lossy().into_owned(),
+        meetings: entries.len(),
+        decisions: entries.iter().map(|entry| entry.decisions.len()).sum(),
+        bytes_written,
+    })
+}
+
+/// "2024-03-04-weekly-sync-1a2b3c4d.html": sorts by date, stays the same
+/// between exports and can't collide
+fn page_name(meeting: &Meeting) -> String {
+    let mut slug = String::new();
+    for c in meeting.title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.chars().take(FILE_TITLE_CHARS).collect();
+    let id: String = meeting.id.chars().filter(char::is_ascii_alphanumeric).take(8).collect();
+    format!("{}-{}-{}.html", meeting.start_time.format("%Y-%m-%d"), slug.trim_matches('-'), id)
+}
+
+/// Meetings grouped under each name `names` gives them, ignoring case, with
+/// the groups sorted by name
+fn group_by(entries: &[Entry], names: impl Fn(&Entry) -> Vec<String>) -> Vec<(String, Vec<&Entry>)> {
+    let mut groups: BTreeMap<String, (String, Vec<&Entry>)> = BTreeMap::new();
+    for entry in entries {
+        for name in names(entry) {
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let group = groups.entry(name.to_lowercase()).or_insert_with(|| (name.to_string(), Vec::new()));
+            if !group.1.iter().any(|e| e.meeting.id == entry.meeting.id) {
+                group.1.push(entry);
+            }
+        }
+    }
+    groups.into_values().collect()
+}
+
+/// Titles that recur, each under the title of its latest occurrence
+fn group_by_series(entries: &[Entry]) -> Vec<(String, Vec<&Entry>)> {
+    let mut groups = group_by(entries, |entry| vec![series_key(&entry.meeting.title)]);
+    groups.retain(|(_, meetings)| meetings.len() > 1);
+    for (name, meetings) in &mut groups {
+        *name = meetings[0].meeting.title.clone();
+    }
+    groups.sort_by_key(|(name, _)| name.to_lowercase());
+    groups
+}
+
+fn meeting_item(out: &mut String, entry: &Entry, excerpt: bool) {
+    let _ = write!(
+        out,
+        "<li><span class=\"time\">{}</span> <a href=\"{}\">{}</a>",
+        escape_html(&entry.date),
+        escape_html(&entry.href),
+        escape_html(&entry.meeting.title)
+    );
+    if let Some(summary) = entry.summary.as_deref().filter(|_| excerpt) {
+        let first = summary.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+        let mut text: String = first.chars().take(EXCERPT_CHARS).collect();
+        if first.chars().count() > EXCERPT_CHARS {
+            text.push('…');
+        }
+        let _ = write!(out, "<div class=\"excerpt\">{}</div>", escape_html(&text));
+    }
+    out.push_str("</li>\n");
+}
+
+fn meetings_page(entries: &[Entry]) -> String {
+    if entries.is_empty() {
+        return "<p>No completed meetings yet.</p>\n".to_string();
+    }
+    let mut out = String::from("<ul class=\"meetings\">\n");
+    for entry in entries {
+        meeting_item(&mut out, entry, true);
+    }
+    out.push_str("</ul>\n");
+    out
+}
+
+/// A list of the groups followed by a section per group
+fn groups_page(groups: &[(String, Vec<&Entry>)]) -> String {
+    if groups.is_empty() {
+        return "<p>Nothing to list yet.</p>\n".to_string();
+    }
+    let mut out = String::from("<ul class=\"groups\">\n");
+    for (i, (name, meetings)) in groups.iter().enumerate() {
+        let _ = writeln!(out, "<li><a href=\"#g{}\">{}</a> ({})</li>", i + 1, escape_html(name), meetings.len());
+    }
+    out.push_str("</ul>\n");
+    for (i, (name, meetings)) in groups.iter().enumerate() {
+        let _ = writeln!(out, "<section id=\"g{}\">\n<h2>{}</h2>\n<ul class=\"meetings\">", i + 1, escape_html(name));
+        for entry in meetings {
+            meeting_item(&mut out, entry, false);
+        }
+        out.push_str("</ul>\n</section>\n");
+    }
+    out
+}
+
+fn decisions_page(entries: &[Entry]) -> String {
+    let mut out = String::new();
+    for entry in entries.iter().filter(|entry| !entry.decisions.is_empty()) {
+        let _ = writeln!(
+            out,
+            "<section>\n<h2><a href=\"{}\">{}</a> <span class=\"time\">{}</span></h2>\n<ul>",
+            escape_html(&entry.href),
+            escape_html(&entry.meeting.title),
+            escape_html(&entry.date)
+        );
+        for decision in &entry.decisions {
+            let _ = writeln!(out, "<li>{}</li>", escape_html(decision));
+        }
+        out.push_str("</ul>\n</section>\n");
+    }
+    if out.is_empty() {
+        out.push_str("<p>No decisions recorded yet.</p>\n");
+    }
+    out
+}
+
+/// An index page with the shared style and navigation
+fn page(title: &str, file: &str, body: &str) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n\
+         body {{ font-family: system-ui, sans-serif; max-width: 48rem; margin: 2rem auto; line-height: 1.5; }}\n\
+         nav a {{ margin-right: 1rem; }}\n\
+         nav a.current {{ font-weight: bold; text-decoration: none; color: inherit; }}\n\
+         .time {{ color: #6b7280; font-variant-numeric: tabular-nums; font-size: 0.875rem; }}\n\
+         .meetings li {{ margin-bottom: 0.5rem; }}\n\
+         .excerpt {{ color: #374151; font-size: 0.875rem; }}\n\
+         </style>\n</head>\n<body>\n<nav>",
+        escape_html(title)
+    );
+    for (href, label) in INDEXES {
+        let class = if href == file { " class=\"current\"" } else { "" };
+        let _ = write!(out, "<a href=\"{}\"{}>{}</a>", href, class, label);
+    }
+    let _ = write!(out, "</nav>\n<h1>{}</h1>\n{}</body>\n</html>\n", escape_html(title), body);
+    out
+}
@@ -0,0 +1,73 @@
+// Live State - What is being recorded right now, for widgets
+//
+// The tray, an overlay or an external controller can mirror the recording
+// through `get_live_state`: the meeting recording or paused, how long it has
+// been recording with paused stretches left out, the speaker of the latest
+// transcript entry, how many transcript segments have arrived and the
+// latest audio level from the capture pipeline. A level older than
+// `LEVEL_STALE_AFTER` is left out, as capture has stopped reporting.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use chrono::Utc;
+
+use crate::db::Database;
+use crate::models::{LiveSpeaker, LiveState};
+
+const LEVEL_STALE_AFTER: Duration = Duration::from_secs(5);
+
+struct LevelReading {
+    meeting_id: String,
+    level: f64,
+    at: Instant,
+}
+
+/// The latest audio level reported for a meeting
+#[derive(Default)]
+pub struct AudioLevels(Mutex<Option<LevelReading>>);
+
+impl AudioLevels {
+    pub fn record(&self, meeting_id: &str, level: f64) {
+        *self.0.lock().unwrap() = Some(LevelReading {
+            meeting_id: meeting_id.to_string(),
+            level,
+            at: Instant::now(),
+        });
+    }
+
+    pub fn current(&self, meeting_id: &str) -> Option<f64> {
+        self.0.lock().unwrap().as_ref()
+            .filter(|reading| reading.meeting_id == meeting_id && reading.at.elapsed() < LEVEL_STALE_AFTER)
+            .map(|reading| reading.level)
+    }
+}
+
+/// The recording or paused meeting, or `None` when nothing is being recorded
+pub fn live_state(db: &Database, levels: &AudioLevels) -> Result<Option<LiveState>, String> {
+    let meeting = db.get_live_meeting()
+        .map_err(|e| format!("Failed to fetch the active meeting: {}", e))?;
+    let Some(meeting) = meeting else {
+        return Ok(None);
+    };
+    let latest = db.get_latest_transcript_entry(&meeting.id)
+        .map_err(|e| format!("Failed to fetch the transcript: {}", e))?;
+    let segments_transcribed = db.count_transcript_entries(&meeting.id)
+        .map_err(|e| format!("Failed to count transcript entries: {}", e))?;
+    let paused = db.paused_duration(&meeting.id)
+        .map_err(|e| format!("Failed to fetch pauses: {}", e))?;
+
+    Ok(Some(LiveState {
+        audio_level: levels.current(&meeting.id),
+        elapsed_seconds: (Utc::now().signed_duration_since(meeting.start_time) - paused).num_seconds().max(0),
+        current_speaker: latest.map(|entry| LiveSpeaker {
+            speaker_id: entry.speaker_id,
+            speaker_name: entry.speaker_name,
+            since_ms: entry.timestamp,
+        }),
+        segments_transcribed,
+        meeting_id: meeting.id,
+        title: meeting.title,
+        status: meeting.status,
+        started_at: meeting.start_time,
+    }))
+}
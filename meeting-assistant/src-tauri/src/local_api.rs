@@ -0,0 +1,301 @@
+// Local API - Control recording from scripts and hardware buttons
+//
+// A small HTTP server on 127.0.0.1, off unless `local_api_enabled` is set and
+// started with the app, so an Elgato Stream Deck, a macro pad or a shell
+// script can start, stop, pause and resume recording and mark moments. Every
+// request needs the token from `local_api_token`, generated on first start,
+// either as `Authorization: Bearer <token>` or as a `token` query parameter.
+//
+// The JSON routes live under `/api`. The `/trigger/<action>` routes do the
+// same with a plain GET and a one-line text answer, which is all a Stream
+// Deck "Website" action or a `curl` one-liner can easily send. A recording
+// started here opens the quick-record indicator, which captures the audio;
+// the window capturing a meeting follows `remote-control` events to pause,
+// resume or stop with it.
+
+use std::io::Cursor;
+use std::thread;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+use uuid::Uuid;
+
+use crate::commands::{self, AppState};
+use crate::db::Database;
+use crate::error::CommandError;
+use crate::live;
+use crate::models::MeetingStatus;
+use crate::quick_record;
+
+pub const DEFAULT_PORT: u16 = 47616;
+pub const REMOTE_CONTROL_EVENT: &str = "remote-control";
+pub const TOKEN_KEY: &str = "local_api_token";
+
+type ApiResponse = Response<Cursor<Vec<u8>>>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteControlEvent {
+    /// "started", "stopped", "paused", "resumed" or "marked"
+    pub action: String,
+    pub meeting_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalApiInfo {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+    pub base_url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StartBody {
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MomentBody {
+    label: Option<String>,
+}
+
+/// Whether the API is enabled, where, and the token requests need,
+/// generating one if there is none yet
+pub fn info(db: &Database) -> Result<LocalApiInfo, String> {
+    let enabled = db.get_setting("local_api_enabled")
+        .map_err(|e| e.to_string())?
+        .as_deref() == Some("true");
+    let port = db.get_setting("local_api_port")
+        .map_err(|e| e.to_string())?
+        .and_then(|v| v.trim().parse::<u16>().ok())
+        .unwrap_or(DEFAULT_PORT);
+    let token = match db.get_setting(TOKEN_KEY).map_err(|e| e.to_string())?.filter(|t| !t.is_empty()) {
+        Some(token) => token,
+        None => regenerate_token(db)?,
+    };
+
+    Ok(LocalApiInfo { enabled, port, token, base_url: format!("http://127.0.0.1:{}", port) })
+}
+
+/// Replaces the token, locking out whatever used the old one
+pub fn regenerate_token(db: &Database) -> Result<String, String> {
+    let token = Uuid::new_v4().simple().to_string();
+    db.set_setting(TOKEN_KEY, &token).map_err(|e| e.to_string())?;
+    Ok(token)
+}
+
+pub fn start_if_enabled(app: AppHandle) {
+    let state = app.state::<AppState>();
+    let info = match info(&state.db) {
+        Ok(info) => info,
+        Err(e) => {
+            log::warn!("Failed to read local API settings: {}", e);
+            return;
+        }
+    };
+    if !info.enabled {
+        return;
+    }
+
+    let server = match Server::http(("127.0.0.1", info.port)) {
+        Ok(server) => server,
+        Err(e) => {
+            log::warn!("Failed to start local API on port {}: {}", info.port, e);
+            return;
+        }
+    };
+    log::info!("Local API listening on {}", info.base_url);
+
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let response = handle(&app, &mut request);
+            if let Err(e) = request.respond(response) {
+                log::warn!("Failed to answer local API request: {}", e);
+            }
+        }
+    });
+}
+
+fn handle(app: &AppHandle, request: &mut Request) -> ApiResponse {
+    let state = app.state::<AppState>();
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+
+    // Read on every request, so a regenerated token applies at once
+    let token = match state.db.get_setting(TOKEN_KEY) {
+        Ok(Some(token)) if !token.is_empty() => token,
+        _ => return json_response(503, serde_json::json!({ "error": "No API token configured" })),
+    };
+    if !authorized(request, query, &token) {
+        return json_response(401, serde_json::json!({ "error": "Missing or wrong token" }));
+    }
+
+    if let Some(action) = path.strip_prefix("/trigger/") {
+        if *request.method() != Method::Get {
+            return text_response(405, "Use GET".to_string());
+        }
+        return match trigger(app, &state, action) {
+            Ok(message) => text_response(200, format!("OK: {}", message)),
+            Err((status, message)) => text_response(status, format!("Error: {}", message)),
+        };
+    }
+
+    let mut body = String::new();
+    if request.as_reader().read_to_string(&mut body).is_err() {
+        return json_response(400, serde_json::json!({ "error": "Unreadable body" }));
+    }
+    let result = match (request.method(), path) {
+        (Method::Get, "/api/state") => live::live_state(&state.db, &state.levels)
+            .map(|live| to_json(&live))
+            .map_err(|e| (500, e)),
+        (Method::Post, "/api/recording/start") => {
+            let StartBody { title } = parse_body(&body);
+            start(app, &state, title)
+        }
+        (Method::Post, "/api/recording/stop") => stop(app, &state),
+        (Method::Post, "/api/recording/pause") => pause(app, &state),
+        (Method::Post, "/api/recording/resume") => resume(app, &state),
+        (Method::Post, "/api/moments") => {
+            let MomentBody { label } = parse_body(&body);
+            mark(app, &state, label)
+        }
+        _ => Err((404, "Not found".to_string())),
+    };
+
+    match result {
+        Ok(body) => json_response(200, body),
+        Err((status, message)) => json_response(status, serde_json::json!({ "error": message })),
+    }
+}
+
+/// Runs a `/trigger/<action>` and describes what happened
+fn trigger(app: &AppHandle, state: &AppState, action: &str) -> Result<String, (u16, String)> {
+    match action {
+        "start" => start(app, state, String::new()).map(|_| "recording started".to_string()),
+        "stop" => stop(app, state).map(|_| "recording stopped".to_string()),
+        "pause" => pause(app, state).map(|_| "recording paused".to_string()),
+        "resume" => resume(app, state).map(|_| "recording resumed".to_string()),
+        // One button that pauses and resumes
+        "toggle-pause" => match live_status(state)? {
+            Some(MeetingStatus::Paused) => resume(app, state).map(|_| "recording resumed".to_string()),
+            _ => pause(app, state).map(|_| "recording paused".to_string()),
+        },
+        // One button that starts and stops
+        "toggle" => match live_status(state)? {
+            Some(_) => stop(app, state).map(|_| "recording stopped".to_string()),
+            None => start(app, state, String::new()).map(|_| "recording started".to_string()),
+        },
+        "mark" => mark(app, state, None).map(|_| "moment marked".to_string()),
+        _ => Err((404, format!("Unknown action '{}'", action))),
+    }
+}
+
+fn start(app: &AppHandle, state: &AppState, title: String) -> Result<serde_json::Value, (u16, String)> {
+    let meeting = commands::begin_recording(state, title).map_err(command_error)?;
+    quick_record::open_indicator(app, state, &meeting.id).map_err(|e| (500, e))?;
+    notify(app, "started", &meeting.id);
+    Ok(to_json(&meeting))
+}
+
+fn stop(app: &AppHandle, state: &AppState) -> Result<serde_json::Value, (u16, String)> {
+    let meeting_id = live_meeting_id(state)?;
+    let meeting = commands::finish_meeting(app, state, &meeting_id).map_err(|e| (500, e))?;
+    notify(app, "stopped", &meeting.id);
+    Ok(to_json(&meeting))
+}
+
+fn pause(app: &AppHandle, state: &AppState) -> Result<serde_json::Value, (u16, String)> {
+    let meeting = state.db.get_recording_meeting()
+        .map_err(|e| (500, e.to_string()))?
+        .ok_or_else(|| (409, "No meeting is recording".to_string()))?;
+    commands::pause_recording(state, &meeting.id).map_err(|e| (500, e))?;
+    notify(app, "paused", &meeting.id);
+    Ok(serde_json::json!({ "meeting_id": meeting.id }))
+}
+
+fn resume(app: &AppHandle, state: &AppState) -> Result<serde_json::Value, (u16, String)> {
+    let meeting_id = match live_status(state)? {
+        Some(MeetingStatus::Paused) => live_meeting_id(state)?,
+        Some(_) => return Err((409, "The meeting is not paused".to_string())),
+        None => return Err((409, "No meeting is paused".to_string())),
+    };
+    commands::resume_recording(state, &meeting_id).map_err(command_error)?;
+    notify(app, "resumed", &meeting_id);
+    Ok(serde_json::json!({ "meeting_id": meeting_id }))
+}
+
+fn mark(app: &AppHandle, state: &AppState, label: Option<String>) -> Result<serde_json::Value, (u16, String)> {
+    let note = commands::add_moment(state, label).map_err(|e| (409, e))?;
+    notify(app, "marked", &note.meeting_id);
+    Ok(to_json(&note))
+}
+
+fn live_status(state: &AppState) -> Result<Option<MeetingStatus>, (u16, String)> {
+    state.db.get_live_meeting()
+        .map(|meeting| meeting.map(|meeting| meeting.status))
+        .map_err(|e| (500, e.to_string()))
+}
+
+fn live_meeting_id(state: &AppState) -> Result<String, (u16, String)> {
+    state.db.get_live_meeting()
+        .map_err(|e| (500, e.to_string()))?
+        .map(|meeting| meeting.id)
+        .ok_or_else(|| (409, "No meeting is being recorded".to_string()))
+}
+
+fn notify(app: &AppHandle, action: &str, meeting_id: &str) {
+    let event = RemoteControlEvent { action: action.to_string(), meeting_id: meeting_id.to_string() };
+    if let Err(e) = app.emit_all(REMOTE_CONTROL_EVENT, event) {
+        log::warn!("Failed to emit {}: {}", REMOTE_CONTROL_EVENT, e);
+    }
+}
+
+fn command_error(error: CommandError) -> (u16, String) {
+    let status = match error {
+        CommandError::Conflict { .. } => 409,
+        CommandError::NotFound => 404,
+        CommandError::Database(_) => 500,
+    };
+    (status, error.to_string())
+}
+
+fn authorized(request: &Request, query: &str, token: &str) -> bool {
+    let bearer = request.headers().iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .and_then(|header| header.value.as_str().strip_prefix("Bearer "))
+        .map(str::trim);
+    let param = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| value);
+    bearer.or(param).is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// An empty or unparseable body means "use the defaults"
+fn parse_body<T: for<'de> Deserialize<'de> + Default>(body: &str) -> T {
+    serde_json::from_str(body).unwrap_or_default()
+}
+
+fn to_json<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> ApiResponse {
+    response(status, body.to_string(), "application/json")
+}
+
+fn text_response(status: u16, body: String) -> ApiResponse {
+    response(status, body, "text/plain; charset=utf-8")
+}
+
+fn response(status: u16, body: String, content_type: &str) -> ApiResponse {
+    let header = Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes())
+        .expect("content type is a valid header value");
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header)
+}
@@ -0,0 +1,167 @@
+// Locale - Localized durations, relative times and dates
+//
+// Exports, notifications and the tray tooltip build their human-readable
+// times here so they follow the `locale` setting rather than hardcoded
+// English. A few languages are built in; any other tag falls back to English.
+
+use chrono::{DateTime, Datelike, Utc};
+
+use crate::db::Database;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+/// Wording for one language. Patterns use `{}` for the amount.
+struct Strings {
+    /// Singular and plural for minutes, hours and days
+    units: [(&'static str, &'static str); 3],
+    /// Abbreviations for seconds, minutes and hours in durations
+    short_units: [&'static str; 3],
+    past: &'static str,
+    future: &'static str,
+    just_now: &'static str,
+    /// `{day}`, `{month}` and `{year}` are substituted
+    date: &'static str,
+    months: [&'static str; 12],
+}
+
+const EN: Strings = Strings {
+    units: [("minute", "minutes"), ("hour", "hours"), ("day", "days")],
+    short_units: ["sec", "min", "hr"],
+    past: "{} ago",
+    future: "in {}",
+    just_now: "just now",
+    date: "{month} {day}, {year}",
+    months: [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ],
+};
+
+const DE: Strings = Strings {
+    // Plural days take the dative, as they only appear after "vor"/"in"
+    units: [("Minute", "Minuten"), ("Stunde", "Stunden"), ("Tag", "Tagen")],
+    short_units: ["Sek.", "Min.", "Std."],
+    past: "vor {}",
+    future: "in {}",
+    just_now: "gerade eben",
+    date: "{day}. {month} {year}",
+    months: [
+        "Januar", "Februar", "März", "April", "Mai", "Juni",
+        "Juli", "August", "September", "Oktober", "November", "Dezember",
+    ],
+};
+
+const FR: Strings = Strings {
+    units: [("minute", "minutes"), ("heure", "heures"), ("jour", "jours")],
+    short_units: ["s", "min", "h"],
+    past: "il y a {}",
+    future: "dans {}",
+    just_now: "à l'instant",
+    date: "{day} {month} {year}",
+    months: [
+        "janvier", "février", "mars", "avril", "mai", "juin",
+        "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+    ],
+};
+
+const ES: Strings = Strings {
+    units: [("minuto", "minutos"), ("hora", "horas"), ("día", "días")],
+    short_units: ["s", "min", "h"],
+    past: "hace {}",
+    future: "dentro de {}",
+    just_now: "ahora mismo",
+    date: "{day} de {month} de {year}",
+    months: [
+        "enero", "febrero", "marzo", "abril", "mayo", "junio",
+        "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+    ],
+};
+
+/// Relative times further away than this are shown as a date
+const MAX_RELATIVE_DAYS: i64 = 30;
+
+impl Locale {
+    /// Reads a language tag such as "de" or "fr-CA"
+    pub fn parse(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        match language.as_str() {
+            "de" => Locale::De,
+            "fr" => Locale::Fr,
+            "es" => Locale::Es,
+            _ => Locale::En,
+        }
+    }
+
+    /// The `locale` setting
+    pub fn from_settings(db: &Database) -> Self {
+        db.get_setting("locale").ok().flatten()
+            .map(|tag| Self::parse(&tag))
+            .unwrap_or_default()
+    }
+
+    fn strings(self) -> &'static Strings {
+        match self {
+            Locale::En => &EN,
+            Locale::De => &DE,
+            Locale::Fr => &FR,
+            Locale::Es => &ES,
+        }
+    }
+
+    /// A length of time such as "1 hr 5 min", or "45 sec" under a minute
+    pub fn format_duration(self, seconds: i64) -> String {
+        let [sec, min, hr] = self.strings().short_units;
+        let seconds = seconds.max(0);
+        let (hours, mins) = (seconds / 3600, seconds % 3600 / 60);
+
+        match (hours, mins) {
+            (0, 0) => format!("{} {}", seconds, sec),
+            (0, _) => format!("{} {}", mins, min),
+            (_, 0) => format!("{} {}", hours, hr),
+            _ => format!("{} {} {} {}", hours, hr, mins, min),
+        }
+    }
+
+    /// `time` relative to `now`, such as "3 hours ago" or "in 2 days".
+    /// Beyond a month the date is given instead.
+    pub fn format_relative(self, time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        let strings = self.strings();
+        let delta = now.signed_duration_since(time).num_seconds();
+        let distance = delta.abs();
+
+        if distance < 45 {
+            return strings.just_now.to_string();
+        }
+        if distance >= MAX_RELATIVE_DAYS * 86_400 {
+            return self.format_date(time);
+        }
+
+        let (unit, size) = match distance {
+            d if d < 3_600 => (0, 60),
+            d if d < 86_400 => (1, 3_600),
+            _ => (2, 86_400),
+        };
+        let count = ((distance + size / 2) / size).max(1);
+        let (one, many) = strings.units[unit];
+        let amount = format!("{} {}", count, if count == 1 { one } else { many });
+
+        let pattern = if delta > 0 { strings.past } else { strings.future };
+        pattern.replace("{}", &amount)
+    }
+
+    /// A calendar date such as "March 5, 2026" or "5. März 2026"
+    pub fn format_date(self, time: DateTime<Utc>) -> String {
+        let strings = self.strings();
+        strings.date
+            .replace("{day}", &time.day().to_string())
+            .replace("{month}", strings.months[time.month0() as usize])
+            .replace("{year}", &time.year().to_string())
+    }
+}
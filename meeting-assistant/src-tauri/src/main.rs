@@ -2,14 +2,47 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod actions;
+mod ai;
+mod anonymize;
+mod analytics;
+mod audio_store;
+mod backlog;
+mod budget;
+mod calendar;
+mod chapters;
 mod commands;
 mod db;
+mod dnd;
+mod email;
+mod error;
+mod export;
+mod inbox;
+mod knowledge_base;
+mod live;
+mod local_api;
+mod locale;
+mod meeting_load;
+mod metadata;
 mod models;
+mod plugins;
+mod profile;
+mod quick_record;
+mod share;
+mod silence;
+mod single_instance;
+mod speakers;
+mod summaries;
+mod tray;
+mod triggers;
+mod typography;
+mod watchdog;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tauri::{Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, CustomMenuItem};
 use commands::AppState;
 use db::Database;
+use silence::SilenceDetector;
 
 fn main() {
     // Initialize logger
@@ -17,12 +50,22 @@ fn main() {
 
     // Create system tray menu
     let tray_menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("quick_record", "Quick Record"))
         .add_item(CustomMenuItem::new("show", "Show Window"))
         .add_item(CustomMenuItem::new("hide", "Hide Window"))
         .add_native_item(tauri::SystemTrayMenuItem::Separator)
         .add_item(CustomMenuItem::new("quit", "Quit"));
 
-    let system_tray = SystemTray::new().with_menu(tray_menu);
+    let system_tray = SystemTray::new()
+        .with_menu(tray_menu)
+        .with_tooltip(tray::IDLE_TOOLTIP);
+
+    // A second launch only hands its arguments to the running app
+    let context = tauri::generate_context!();
+    let handover = match single_instance::claim(context.config()) {
+        single_instance::Launch::First(listener) => listener,
+        single_instance::Launch::HandedOver => return,
+    };
 
     tauri::Builder::default()
         .system_tray(system_tray)
@@ -42,7 +85,14 @@ fn main() {
                     let window = app.get_window("main").unwrap();
                     window.hide().unwrap();
                 }
+                "quick_record" => {
+                    if let Err(e) = quick_record::start(app) {
+                        log::warn!("Failed to start quick recording: {}", e);
+                    }
+                }
                 "quit" => {
+                    // Exiting directly skips the run loop's exit handling
+                    restore_notifications(app);
                     std::process::exit(0);
                 }
                 _ => {}
@@ -51,27 +101,113 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::start_meeting,
+            commands::handoff_recording,
             commands::end_meeting,
             commands::pause_meeting,
             commands::resume_meeting,
             commands::get_meetings,
+            commands::get_metadata_fields,
+            commands::save_metadata_field,
+            commands::delete_metadata_field,
+            commands::get_meeting_metadata,
+            commands::set_meeting_metadata,
             commands::get_meeting,
             commands::delete_meeting,
+            commands::report_audio_level,
+            commands::get_live_state,
+            commands::get_local_api_info,
+            commands::regenerate_local_api_token,
+            commands::get_settings,
+            commands::set_setting,
+            commands::format_duration,
+            commands::format_relative_time,
             commands::get_transcript,
+            commands::get_transcript_page,
+            commands::get_transcript_around,
+            commands::get_transcript_count,
             commands::save_transcript_entry,
             commands::save_transcript_batch,
+            commands::check_speaker_count,
+            commands::recluster_speakers,
+            commands::save_screen_capture,
+            commands::get_meeting_chapters,
+            commands::attach_email,
+            commands::get_attachments,
+            commands::delete_attachment,
+            commands::search_context,
+            commands::get_meeting_context,
             commands::get_notes,
             commands::add_note,
+            commands::mark_moment,
             commands::update_note,
             commands::delete_note,
+            commands::confirm_auto_note,
+            commands::get_speaker_aliases,
+            commands::set_speaker_alias,
+            commands::get_annotations,
+            commands::add_annotation,
+            commands::update_annotation,
+            commands::delete_annotation,
+            commands::add_bookmark,
+            commands::list_bookmarks,
+            commands::delete_bookmark,
+            commands::get_trigger_phrases,
+            commands::save_trigger_phrase,
+            commands::delete_trigger_phrase,
+            commands::list_action_items,
+            commands::get_inbox,
+            commands::complete_action_item,
+            commands::defer_action_item,
+            commands::get_note_templates,
+            commands::save_note_template,
+            commands::delete_note_template,
+            commands::add_note_from_template,
             commands::export_meeting_markdown,
+            commands::export_meeting_to_file,
+            commands::export_knowledge_base,
+            commands::share_meeting,
+            commands::get_active_shares,
+            commands::stop_sharing,
+            commands::diff_meetings,
+            commands::export_meeting_diff_markdown,
+            commands::get_followthrough_stats,
+            commands::get_metadata_breakdown,
+            commands::run_readonly_query,
+            commands::check_workspace,
+            commands::export_profile,
+            commands::import_profile,
+            commands::import_calendar,
+            commands::get_meeting_load_forecast,
+            commands::get_integration_permissions,
+            commands::set_integration_permission,
+            commands::authorize_integration_access,
+            commands::get_plugins,
+            commands::install_plugin,
+            commands::set_plugin_enabled,
+            commands::uninstall_plugin,
+            commands::export_meeting_with_plugin,
             commands::ask_ai,
+            commands::ai_chat,
+            commands::get_ai_provider_chain,
+            commands::set_ai_provider_chain,
+            commands::get_ai_usage,
+            commands::generate_summary,
+            commands::get_summary_versions,
+            commands::regenerate_summaries,
+            commands::process_backlog,
+            commands::get_backlog_progress,
+            commands::cancel_backlog,
+            commands::get_budget_status,
+            commands::set_ai_budget,
             commands::get_audio_sources,
             commands::set_audio_sources,
+            commands::save_meeting_audio,
+            commands::get_meeting_audio_file,
+            commands::relocate_audio,
             commands::http_post,
             commands::http_get,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             // Initialize database
             let app_dir = app.path_resolver().app_data_dir().unwrap();
             std::fs::create_dir_all(&app_dir).unwrap();
@@ -86,12 +222,50 @@ fn main() {
             app.manage(AppState {
                 db: Arc::new(database),
                 db_path,
+                silence: Mutex::new(SilenceDetector::new()),
+                shares: Default::default(),
+                dnd: Default::default(),
+                levels: Default::default(),
+                backlog: Default::default(),
+                plugins: Default::default(),
             });
+
+            inbox::spawn_daily_digest(app.handle());
+            watchdog::spawn_stale_meeting_watchdog(app.handle());
+            tray::spawn_tray_tooltip(app.handle());
+            dnd::spawn_recording_dnd(app.handle());
+            meeting_load::spawn_overload_check(app.handle());
+            local_api::start_if_enabled(app.handle());
+            if let Some(listener) = handover {
+                single_instance::serve(app.handle(), listener);
+            }
+
+            // The main window starts hidden so a quick recording doesn't flash it
+            if quick_record::requested() {
+                if let Err(e) = quick_record::start(&app.handle()) {
+                    log::warn!("Failed to start quick recording: {}", e);
+                    app.get_window("main").unwrap().show()?;
+                }
+            } else {
+                app.get_window("main").unwrap().show()?;
+            }
             
             log::info!("Meeting Assistant started. Database at: {:?}", app_dir.join("meetings.db"));
             
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("Error while running Meeting Assistant");
+        .build(context)
+        .expect("Error while running Meeting Assistant")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                restore_notifications(app);
+            }
+        });
+}
+
+/// Hands Do Not Disturb back to the user if a recording had turned it on
+fn restore_notifications(app: &tauri::AppHandle) {
+    if let Some(state) = app.try_state::<AppState>() {
+        state.dnd.set(&state.db, false);
+    }
 }
@@ -0,0 +1,181 @@
+// Meeting Load - Forecast a week's meeting time and warn about overloaded weeks
+//
+// A week's load combines what is on the imported calendar with what is
+// typical: each day is projected as the larger of its scheduled meeting time
+// and the average recorded meeting time on that weekday over the last
+// `HISTORY_WEEKS` weeks, since ad-hoc meetings tend to fill a day up to its
+// usual level. Overlapping events count once. Work hours are
+// `work_hours_per_day` on each weekday.
+//
+// The warning looks at scheduled time only, which is what the user can still
+// change: once scheduled meetings pass `meeting_load_warning_share` of the
+// week's work hours, this week or next, a notification is shown once per week.
+
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use tauri::{AppHandle, Manager};
+use tauri::api::notification::Notification;
+
+use crate::commands::AppState;
+use crate::db::Database;
+use crate::inbox::local_midnight;
+use crate::models::{DayLoad, MeetingLoadForecast};
+
+const HISTORY_WEEKS: i64 = 4;
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_WORK_HOURS: f64 = 8.0;
+const DEFAULT_WARNING_SHARE: f64 = 0.5;
+const WARNED_WEEKS_KEY: &str = "meeting_load_warned_weeks";
+/// Warned weeks remembered, enough to cover this week and next
+const WARNED_WEEKS_KEPT: usize = 8;
+
+/// The Monday of the week containing `date`
+pub fn week_of(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Scheduled and projected meeting time for the week containing `date`
+pub fn forecast(db: &Database, date: NaiveDate) -> Result<MeetingLoadForecast, String> {
+    let week_start = week_of(date);
+    let setting = |key: &str, default: f64| {
+        db.get_setting(key).ok().flatten()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| *v >= 0.0)
+            .unwrap_or(default)
+    };
+    let work_hours = setting("work_hours_per_day", DEFAULT_WORK_HOURS);
+    let warning_share = setting("meeting_load_warning_share", DEFAULT_WARNING_SHARE);
+
+    let days: Vec<NaiveDate> = week_start.iter_days().take(7).collect();
+    let events = db.get_calendar_events(local_midnight(week_start), local_midnight(week_start + chrono::Duration::days(7)))
+        .map_err(|e| format!("Failed to fetch calendar events: {}", e))?;
+
+    // History ends at the week being forecast, or at this week for later ones
+    let history_end = week_start.min(week_of(Local::now().date_naive()));
+    let history_start = history_end - chrono::Duration::weeks(HISTORY_WEEKS);
+    let spans = db.get_meeting_spans(local_midnight(history_start), local_midnight(history_end))
+        .map_err(|e| format!("Failed to fetch meetings: {}", e))?;
+    // Weeks before the first recorded meeting would only dilute the average
+    let history_weeks = spans.iter()
+        .map(|(start, _)| start.with_timezone(&Local).date_naive())
+        .min()
+        .map(|first| (history_end - week_of(first)).num_weeks().clamp(1, HISTORY_WEEKS))
+        .unwrap_or(0);
+
+    let days: Vec<DayLoad> = days.into_iter()
+        .map(|day| {
+            let scheduled: Vec<(DateTime<Utc>, DateTime<Utc>)> = events.iter()
+                .filter(|event| event.start_time.with_timezone(&Local).date_naive() == day)
+                .map(|event| (event.start_time, event.end_time))
+                .collect();
+            let recorded: Vec<(DateTime<Utc>, DateTime<Utc>)> = spans.iter()
+                .filter(|(start, _)| start.with_timezone(&Local).weekday() == day.weekday())
+                .copied()
+                .collect();
+            let scheduled_minutes = union_minutes(&scheduled);
+            let typical_minutes = if history_weeks > 0 { union_minutes(&recorded) / history_weeks } else { 0 };
+
+            DayLoad {
+                date: day,
+                events: scheduled.len(),
+                scheduled_minutes,
+                typical_minutes,
+                projected_minutes: scheduled_minutes.max(typical_minutes),
+            }
+        })
+        .collect();
+
+    let work_minutes = (work_hours * 60.0) as i64 * 5;
+    let scheduled_minutes: i64 = days.iter().map(|day| day.scheduled_minutes).sum();
+    let projected_minutes: i64 = days.iter().map(|day| day.projected_minutes).sum();
+    let share = |minutes: i64| if work_minutes > 0 { minutes as f64 / work_minutes as f64 } else { 0.0 };
+
+    Ok(MeetingLoadForecast {
+        week_start,
+        work_minutes,
+        scheduled_minutes,
+        projected_minutes,
+        scheduled_share: share(scheduled_minutes),
+        projected_share: share(projected_minutes),
+        warning_share,
+        overloaded: work_minutes > 0 && share(scheduled_minutes) > warning_share,
+        days,
+    })
+}
+
+/// Minutes covered by the spans, counting overlaps once
+fn union_minutes(spans: &[(DateTime<Utc>, DateTime<Utc>)]) -> i64 {
+    let mut spans = spans.to_vec();
+    spans.sort();
+    let mut total = 0;
+    let mut current: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+    for (start, end) in spans {
+        match current.as_mut() {
+            Some((_, current_end)) if start <= *current_end => *current_end = (*current_end).max(end),
+            _ => {
+                if let Some((s, e)) = current.replace((start, end)) {
+                    total += (e - s).num_minutes();
+                }
+            }
+        }
+    }
+    if let Some((s, e)) = current {
+        total += (e - s).num_minutes();
+    }
+    total
+}
+
+/// Checks this week and next once at startup and then periodically
+pub fn spawn_overload_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = warn_if_overloaded(&app) {
+                log::warn!("Meeting load check failed: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Notifies about this week or next when overloaded, once per week
+pub fn warn_if_overloaded(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let db = &state.db;
+    let mut warned: Vec<String> = db.get_setting(WARNED_WEEKS_KEY)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default()
+        .split(',')
+        .filter(|week| !week.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let this_week = week_of(Local::now().date_naive());
+    for (week_start, which) in [(this_week, "this week"), (this_week + chrono::Duration::weeks(1), "next week")] {
+        let key = week_start.to_string();
+        if warned.contains(&key) {
+            continue;
+        }
+        let forecast = forecast(db, week_start)?;
+        if !forecast.overloaded {
+            continue;
+        }
+
+        let hours = forecast.scheduled_minutes as f64 / 60.0;
+        Notification::new(&app.config().tauri.bundle.identifier)
+            .title("Heavy meeting week")
+            .body(format!(
+                "{:.1}h of meetings scheduled {}, {:.0}% of your work hours",
+                hours,
+                which,
+                forecast.scheduled_share * 100.0
+            ))
+            .show()
+            .map_err(|e| e.to_string())?;
+        log::info!("Warned about meeting load for the week of {}", key);
+        warned.push(key);
+    }
+
+    let keep_from = warned.len().saturating_sub(WARNED_WEEKS_KEPT);
+    db.set_setting(WARNED_WEEKS_KEY, &warned[keep_from..].join(","))
+        .map_err(|e| e.to_string())
+}
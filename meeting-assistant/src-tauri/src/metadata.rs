@@ -0,0 +1,66 @@
+// Metadata - User-defined fields on meetings
+//
+// Fields such as "Client", "Project code" or "Billable" are defined once and
+// then filled in per meeting. Values are stored as text in
+// `meeting_metadata`, normalized for the field's type on the way in so that
+// filtering and grouping compare like with like: numbers as parsed, booleans
+// as "true" or "false". Meetings can be listed by value, values show up in
+// exports, and meeting time can be broken down by a field.
+
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+
+use crate::db::Database;
+use crate::models::{MetadataBreakdown, MetadataFieldType, MetadataGroup};
+
+/// The value as stored for a field of `field_type`; `None` for a blank value,
+/// which clears the field
+pub fn normalize(field_type: MetadataFieldType, value: &str) -> Result<Option<String>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    match field_type {
+        MetadataFieldType::Text => Ok(Some(value.to_string())),
+        MetadataFieldType::Number => value.parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .map(|number| Some(number.to_string()))
+            .ok_or_else(|| format!("'{}' is not a number", value)),
+        MetadataFieldType::Boolean => match value.to_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(Some("true".to_string())),
+            "false" | "no" | "0" => Ok(Some("false".to_string())),
+            _ => Err(format!("'{}' is not yes or no", value)),
+        },
+    }
+}
+
+/// Meeting count and time per value of the field, for finished meetings
+/// starting within the optional range
+pub fn breakdown(
+    db: &Database,
+    field_id: &str,
+    range_start: Option<DateTime<Utc>>,
+    range_end: Option<DateTime<Utc>>,
+) -> Result<MetadataBreakdown, String> {
+    let field = db.get_metadata_field(field_id)
+        .map_err(|e| format!("Failed to fetch metadata field: {}", e))?
+        .ok_or_else(|| "Metadata field not found".to_string())?;
+    let spans = db.get_metadata_spans(field_id, range_start, range_end)
+        .map_err(|e| format!("Failed to fetch meetings: {}", e))?;
+
+    let mut groups: BTreeMap<Option<String>, (usize, i64)> = BTreeMap::new();
+    for (value, start, end) in spans {
+        let group = groups.entry(value).or_default();
+        group.0 += 1;
+        group.1 += end.signed_duration_since(start).num_minutes().max(0);
+    }
+
+    let mut groups: Vec<MetadataGroup> = groups.into_iter()
+        .map(|(value, (meetings, total_minutes))| MetadataGroup { value, meetings, total_minutes })
+        .collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.total_minutes));
+
+    Ok(MetadataBreakdown { field, groups })
+}
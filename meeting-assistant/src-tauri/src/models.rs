@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 // ============================================================
 // Meeting
@@ -20,9 +21,24 @@ pub struct Meeting {
     pub audio_path: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Finalized by the stale-meeting watchdog rather than by the user
+    #[serde(default)]
+    pub auto_closed: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Key grouping recurring occurrences of the same meeting, derived from the
+/// title with dates and numbers stripped ("Weekly Sync 2024-03-04" and
+/// "Weekly sync #12" both map to "weekly sync")
+pub fn series_key(title: &str) -> String {
+    title
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '/' || c == '#' || c == ':')
+        .filter(|word| !word.is_empty() && !word.chars().any(|c| c.is_ascii_digit()))
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MeetingStatus {
     Idle,
@@ -31,6 +47,14 @@ pub enum MeetingStatus {
     Completed,
 }
 
+/// Result of `handoff_recording`: the meeting that was ended, if one was
+/// recording, and the meeting that took over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingHandoff {
+    pub ended: Option<Meeting>,
+    pub started: Meeting,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Participant {
     pub id: String,
@@ -56,6 +80,58 @@ pub struct TranscriptEntry {
     pub language: String,
     pub translation: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Voice characteristics measured by the live diarizer, kept so speakers
+    /// can be re-clustered after the meeting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_pitch: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_energy: Option<f64>,
+}
+
+/// Role or alias shown instead of a speaker's name in anonymized exports,
+/// e.g. "PM" or "Engineer 1"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerAlias {
+    pub meeting_id: String,
+    pub speaker_id: String,
+    pub alias: String,
+}
+
+/// Speakers found in a transcript compared with the meeting's participants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerCountCheck {
+    pub meeting_id: String,
+    pub detected: usize,
+    pub expected: usize,
+    /// Far enough apart that diarization has likely split or merged voices
+    pub diverges: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReclusterResult {
+    pub meeting_id: String,
+    pub speakers: usize,
+    pub entries_changed: usize,
+    /// Entries without voice data, assigned to the speaker nearest in time
+    pub entries_inferred: usize,
+}
+
+/// One page of `get_transcript_page`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptPage {
+    pub entries: Vec<TranscriptEntry>,
+    /// Entries in the whole transcript, for sizing virtualized lists
+    pub total: usize,
+    pub has_more: bool,
+}
+
+/// Entries around a playback position, from `get_transcript_around`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptWindow {
+    pub entries: Vec<TranscriptEntry>,
+    pub total: usize,
+    /// Position of the first returned entry in the whole transcript
+    pub first_index: usize,
 }
 
 // ============================================================
@@ -73,11 +149,19 @@ pub struct Note {
     pub assignee: Option<String>,
     pub deadline: Option<DateTime<Utc>>,
     pub completed: bool,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Hidden from the inbox until this time
+    #[serde(default)]
+    pub deferred_until: Option<DateTime<Utc>>,
+    /// Created automatically from a trigger phrase and not yet confirmed
+    #[serde(default)]
+    pub needs_review: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum NoteType {
     KeyPoint,
@@ -101,6 +185,93 @@ impl NoteType {
     }
 }
 
+/// Phrase that turns a transcript segment into a note of `note_type`,
+/// e.g. "action item:" or "let's decide"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerPhrase {
+    pub id: String,
+    pub phrase: String,
+    pub note_type: NoteType,
+    pub enabled: bool,
+}
+
+// ============================================================
+// Note Templates
+// ============================================================
+
+/// Reusable note snippet with `{variable}` placeholders,
+/// e.g. "Decision: {what} — owner {who}"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteTemplate {
+    pub id: String,
+    pub name: String,
+    pub note_type: NoteType,
+    pub body: String,
+    pub variables: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl NoteTemplate {
+    /// Placeholder names in order of first appearance
+    pub fn extract_variables(body: &str) -> Vec<String> {
+        let mut variables: Vec<String> = Vec::new();
+        for (_, name) in placeholders(body) {
+            if !variables.iter().any(|v| v == name) {
+                variables.push(name.to_string());
+            }
+        }
+        variables
+    }
+
+    /// Substitute every placeholder; fails if a variable has no value
+    pub fn render(&self, vars: &HashMap<String, String>) -> Result<String, String> {
+        let missing: Vec<&str> = self.variables.iter()
+            .filter(|v| vars.get(*v).map_or(true, |value| value.trim().is_empty()))
+            .map(|v| v.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Missing template values: {}", missing.join(", ")));
+        }
+
+        // Placeholders are matched as `extract_variables` reads them, so
+        // `{ who }` is filled in just like `{who}`
+        let mut content = String::with_capacity(self.body.len());
+        let mut copied = 0;
+        for (span, name) in placeholders(&self.body) {
+            if let Some(value) = vars.get(name).filter(|_| self.variables.iter().any(|v| v == name)) {
+                content.push_str(&self.body[copied..span.start]);
+                content.push_str(value.trim());
+                copied = span.end;
+            }
+        }
+        content.push_str(&self.body[copied..]);
+        Ok(content)
+    }
+}
+
+/// Each `{name}` in `body`, spaces inside the braces allowed: where it is,
+/// braces included, and the name
+fn placeholders(body: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = body[offset..].find('{').map(|i| offset + i) {
+        let Some(end) = body[start + 1..].find('}').map(|i| start + 1 + i) else {
+            break;
+        };
+        let name = body[start + 1..end].trim();
+        if is_variable_name(name) {
+            found.push((start..end + 1, name));
+        }
+        offset = end + 1;
+    }
+    found
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
 // ============================================================
 // Screen Capture
 // ============================================================
@@ -113,6 +284,81 @@ pub struct ScreenCapture {
     pub image_path: String,
     pub ocr_text: String,
     pub relevance_score: f64,
+    #[serde(default)]
+    pub thumbnail_path: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A topical section of a meeting's transcript, with a thumbnail of what was
+/// on screen when it began (for meetings with screen capture enabled)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub index: usize,
+    pub title: String,
+    pub start_time: i64, // milliseconds from meeting start
+    pub end_time: i64,
+    pub first_entry_id: String,
+    pub entry_count: usize,
+    pub thumbnail_path: Option<String>,
+}
+
+// ============================================================
+// Attachments
+// ============================================================
+
+/// External material attached to a meeting as context, e.g. an email thread
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    pub meeting_id: String,
+    pub kind: String, // email
+    pub title: String,
+    pub author: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub source_path: Option<String>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSearchHit {
+    pub attachment_id: String,
+    pub meeting_id: String,
+    pub title: String,
+    pub snippet: String,
+}
+
+// ============================================================
+// Annotations
+// ============================================================
+
+/// A private remark on one transcript entry. Unlike notes, annotations are
+/// left out of exports and integrations unless asked for explicitly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    pub meeting_id: String,
+    pub transcript_entry_id: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============================================================
+// Bookmarks
+// ============================================================
+
+/// A marked moment to revisit, lighter than a note: just a position in the
+/// meeting and an optional label. Bookmarks are included in exports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: String,
+    pub meeting_id: String,
+    pub timestamp: i64, // milliseconds from meeting start
+    pub label: Option<String>,
+    /// The transcript entry being spoken at the bookmark, to jump to
+    #[serde(default)]
+    pub transcript_entry_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -132,6 +378,35 @@ pub struct AIQuery {
     pub created_at: DateTime<Utc>,
 }
 
+/// One attempt against one provider. Attempts belonging to the same chat
+/// request share a `request_id`; the one with status "ok" served it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiUsageRecord {
+    pub id: String,
+    pub request_id: String,
+    pub provider: String,
+    pub model: String,
+    pub purpose: String,
+    pub status: String, // ok, timeout, rate_limited, unavailable, over_budget, error
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+    pub latency_ms: i64,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Spend for the current calendar month against the provider's budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub provider: String,
+    pub budget_usd: Option<f64>,
+    pub spent_usd: f64,
+    pub remaining_usd: Option<f64>,
+    pub requests: i64,
+    pub exceeded: bool,
+}
+
 // ============================================================
 // Audio
 // ============================================================
@@ -170,3 +445,525 @@ pub struct TopicSummary {
     pub end_time: i64,
     pub key_points: Vec<String>,
 }
+
+/// One generated summary of a meeting. Regenerating adds a new version
+/// rather than replacing the old one, so outputs can be compared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryVersion {
+    pub id: String,
+    pub meeting_id: String,
+    /// 1 for the first summary of the meeting, incremented per regeneration
+    pub version: i64,
+    /// `summaries::TEMPLATE_VERSION` of the prompt that produced it
+    pub template_version: i64,
+    pub provider: String,
+    pub model: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Selects meetings for `regenerate_summaries`. Every field narrows the set;
+/// only completed meetings with a transcript are considered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SummaryFilter {
+    pub meeting_ids: Option<Vec<String>>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Skip meetings whose latest summary already uses the current template
+    /// and model (default true)
+    pub outdated_only: Option<bool>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegenerateSummariesResult {
+    pub regenerated: Vec<SummaryVersion>,
+    pub skipped: usize,
+    /// (meeting id, error) for meetings that could not be summarized
+    pub failed: Vec<(String, String)>,
+}
+
+// ============================================================
+// Integration Permissions
+// ============================================================
+
+/// Category of meeting data an integration may read or send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataScope {
+    MeetingMetadata,
+    Notes,
+    ActionItems,
+    Summaries,
+    Transcripts,
+    Audio,
+    Annotations,
+}
+
+impl DataScope {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "meeting-metadata" => Some(DataScope::MeetingMetadata),
+            "notes" => Some(DataScope::Notes),
+            "action-items" => Some(DataScope::ActionItems),
+            "summaries" => Some(DataScope::Summaries),
+            "transcripts" => Some(DataScope::Transcripts),
+            "audio" => Some(DataScope::Audio),
+            "annotations" => Some(DataScope::Annotations),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataScope::MeetingMetadata => "meeting-metadata",
+            DataScope::Notes => "notes",
+            DataScope::ActionItems => "action-items",
+            DataScope::Summaries => "summaries",
+            DataScope::Transcripts => "transcripts",
+            DataScope::Audio => "audio",
+            DataScope::Annotations => "annotations",
+        }
+    }
+}
+
+/// Declares what an integration reads and where it sends it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationManifest {
+    pub id: String,
+    pub name: String,
+    pub reads: Vec<DataScope>,
+    pub sends_to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopeGrant {
+    pub scope: DataScope,
+    /// None until the user has answered the permission prompt
+    pub granted: Option<bool>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationPermissions {
+    pub manifest: IntegrationManifest,
+    pub grants: Vec<ScopeGrant>,
+}
+
+// ============================================================
+// Plugins
+// ============================================================
+
+/// Point in the meeting lifecycle a plugin can run at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginHook {
+    /// Each transcript segment as it is saved
+    TranscriptSegment,
+    /// A meeting being ended
+    MeetingEnd,
+    /// Exporting a meeting in the plugin's own format
+    Exporter,
+}
+
+impl PluginHook {
+    /// The function the plugin's module exports for the hook
+    pub fn export_name(&self) -> &'static str {
+        match self {
+            PluginHook::TranscriptSegment => "on_transcript_segment",
+            PluginHook::MeetingEnd => "on_meeting_end",
+            PluginHook::Exporter => "export",
+        }
+    }
+}
+
+/// File format a plugin exports meetings to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginExporter {
+    /// Shown in the export menu, e.g. "Jira CSV"
+    pub label: String,
+    /// File extension without the dot
+    pub extension: String,
+}
+
+/// Declares what a plugin hooks into and which meeting data it reads; read
+/// from the `plugin.json` next to its module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub hooks: Vec<PluginHook>,
+    /// Data the plugin is given once the user grants it
+    #[serde(default)]
+    pub permissions: Vec<DataScope>,
+    /// Required with the `exporter` hook
+    #[serde(default)]
+    pub exporter: Option<PluginExporter>,
+    /// The WebAssembly module, relative to the manifest
+    #[serde(default = "default_plugin_module")]
+    pub module: String,
+}
+
+fn default_plugin_module() -> String {
+    "plugin.wasm".to_string()
+}
+
+/// Plugins ask for permissions as integrations under this id
+pub fn plugin_integration_id(plugin_id: &str) -> String {
+    format!("plugin:{}", plugin_id)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Plugin {
+    pub manifest: PluginManifest,
+    pub enabled: bool,
+    /// Id of the plugin's permissions among the integrations
+    pub integration_id: String,
+    pub installed_at: DateTime<Utc>,
+}
+
+// ============================================================
+// Analytics
+// ============================================================
+
+/// Action-item completion latency for one assignee or meeting series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowthroughGroup {
+    pub key: String,
+    pub total: usize,
+    pub completed: usize,
+    pub median_hours_to_complete: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowthroughStats {
+    pub total: usize,
+    pub completed: usize,
+    pub median_hours_to_complete: Option<f64>,
+    pub by_assignee: Vec<FollowthroughGroup>,
+    pub by_series: Vec<FollowthroughGroup>,
+}
+
+/// How one kind of note changed between two occurrences of a meeting series
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteDiff {
+    /// Raised in the later meeting only
+    pub new: Vec<Note>,
+    /// Completed action items, or earlier decisions that were not revisited
+    pub resolved: Vec<Note>,
+    /// Open action items carried over, or decisions revisited in the later meeting
+    pub still_open: Vec<Note>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingDiff {
+    pub meeting_a: Meeting,
+    pub meeting_b: Meeting,
+    pub decisions: NoteDiff,
+    pub action_items: NoteDiff,
+}
+
+// ============================================================
+// Inbox
+// ============================================================
+
+/// An action item with the meeting it came from, as listed in the inbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxItem {
+    pub note: Note,
+    pub meeting_title: String,
+    pub meeting_start: DateTime<Utc>,
+}
+
+// ============================================================
+// Query Console
+// ============================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub truncated: bool,
+    pub elapsed_ms: u128,
+}
+
+// ============================================================
+// Workspace Check
+// ============================================================
+
+/// Rows in a table whose meeting no longer exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedRows {
+    pub table: String,
+    pub count: usize,
+}
+
+/// A meeting whose recorded audio file is gone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingAudio {
+    pub meeting_id: String,
+    pub audio_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceReport {
+    pub orphaned_rows: Vec<OrphanedRows>,
+    /// Annotations on transcript entries that no longer exist
+    pub orphaned_annotations: usize,
+    pub missing_audio: Vec<MissingAudio>,
+    /// Attachments missing from the search index
+    pub unindexed_attachments: usize,
+    /// Search index rows left behind by deleted attachments
+    pub stale_index_rows: usize,
+    /// Whether the problems found were fixed
+    pub repaired: bool,
+}
+
+// ============================================================
+// Configuration Profile
+// ============================================================
+
+/// The app configuration without meetings or secrets, for moving to another
+/// machine or sharing across a team
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigProfile {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub settings: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    pub note_templates: Vec<NoteTemplate>,
+    #[serde(default)]
+    pub trigger_phrases: Vec<TriggerPhrase>,
+    /// What each integration reads and where it sends it. Whether the user
+    /// allowed it is never carried; each machine answers for itself.
+    #[serde(default)]
+    pub integration_manifests: Vec<IntegrationManifest>,
+    /// The frontend's own settings (provider, model, language) without API
+    /// keys
+    #[serde(default)]
+    pub app_settings: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileImportResult {
+    pub settings: usize,
+    pub note_templates: usize,
+    pub trigger_phrases: usize,
+    /// Integrations not known here before; their permissions start pending
+    pub integration_manifests: usize,
+    pub app_settings: Option<serde_json::Value>,
+}
+
+// ============================================================
+// Calendar & Meeting Load
+// ============================================================
+
+/// A scheduled meeting imported from a calendar. Occurrences of recurring
+/// events have their own id, derived from the event UID and start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub id: String,
+    pub title: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarImportResult {
+    pub imported: usize,
+    /// All-day, cancelled or unreadable events
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayLoad {
+    pub date: chrono::NaiveDate,
+    /// Calendar events on the day
+    pub events: usize,
+    pub scheduled_minutes: i64,
+    /// Average recorded meeting time on this weekday over recent weeks
+    pub typical_minutes: i64,
+    /// The larger of the two, as unscheduled meetings tend to fill a day up
+    /// to what is typical
+    pub projected_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingLoadForecast {
+    /// Monday of the week
+    pub week_start: chrono::NaiveDate,
+    /// Work hours of the week's weekdays
+    pub work_minutes: i64,
+    pub scheduled_minutes: i64,
+    pub projected_minutes: i64,
+    pub scheduled_share: f64,
+    pub projected_share: f64,
+    /// Share of work hours in meetings above which the week is overloaded
+    pub warning_share: f64,
+    /// Whether scheduled meetings alone pass `warning_share`
+    pub overloaded: bool,
+    pub days: Vec<DayLoad>,
+}
+
+// ============================================================
+// Live State
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveSpeaker {
+    pub speaker_id: String,
+    pub speaker_name: String,
+    /// Start of their latest transcript entry, in milliseconds from the
+    /// start of the meeting
+    pub since_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveState {
+    pub meeting_id: String,
+    pub title: String,
+    /// Recording or Paused
+    pub status: MeetingStatus,
+    pub started_at: DateTime<Utc>,
+    pub elapsed_seconds: i64,
+    /// Speaker of the latest transcript entry, per the live diarizer
+    pub current_speaker: Option<LiveSpeaker>,
+    pub segments_transcribed: usize,
+    /// Latest normalized level (0.0 - 1.0), while capture is reporting
+    pub audio_level: Option<f64>,
+}
+
+// ============================================================
+// Meeting Metadata
+// ============================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataFieldType {
+    Text,
+    Number,
+    Boolean,
+}
+
+/// A user-defined field meetings can be tagged with, e.g. "Client",
+/// "Project code" or "Billable"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataField {
+    pub id: String,
+    pub name: String,
+    pub field_type: MetadataFieldType,
+}
+
+/// A meeting's value for one field, stored as text: numbers as written,
+/// booleans as "true" or "false"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataValue {
+    pub field_id: String,
+    pub name: String,
+    pub field_type: MetadataFieldType,
+    pub value: String,
+}
+
+/// Keeps meetings whose value for `field_id` equals `value`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataFilter {
+    pub field_id: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataGroup {
+    /// The field's value, or None for meetings without one
+    pub value: Option<String>,
+    pub meetings: usize,
+    pub total_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataBreakdown {
+    pub field: MetadataField,
+    /// Largest total first
+    pub groups: Vec<MetadataGroup>,
+}
+
+// ============================================================
+// Backlog Processing
+// ============================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BacklogOperation {
+    Summarize,
+    ExtractActions,
+    Embed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacklogFailure {
+    pub meeting_id: String,
+    pub operation: BacklogOperation,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacklogProgress {
+    pub operations: Vec<BacklogOperation>,
+    /// Jobs queued, one per meeting and operation
+    pub total: usize,
+    pub completed: usize,
+    pub failed: Vec<BacklogFailure>,
+    pub running: bool,
+    pub cancelled: bool,
+    /// Set while jobs wait out a provider's rate limit
+    pub paused_until: Option<DateTime<Utc>>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingEmbedding {
+    pub meeting_id: String,
+    pub provider: String,
+    pub model: String,
+    pub vector: Vec<f32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(body: &str) -> NoteTemplate {
+        NoteTemplate {
+            id: "template".to_string(),
+            name: "Template".to_string(),
+            note_type: NoteType::ActionItem,
+            body: body.to_string(),
+            variables: NoteTemplate::extract_variables(body),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn fills_placeholders_written_with_spaces() {
+        let template = template("{ who } follows up with {who} by {  when}");
+        assert_eq!(template.variables, vec!["who", "when"]);
+
+        let vars = HashMap::from([
+            ("who".to_string(), "Sam".to_string()),
+            ("when".to_string(), " Friday ".to_string()),
+        ]);
+        assert_eq!(template.render(&vars).unwrap(), "Sam follows up with Sam by Friday");
+    }
+
+    #[test]
+    fn leaves_braces_that_are_not_placeholders() {
+        let template = template("{not a name} and { } for {who}");
+        let vars = HashMap::from([("who".to_string(), "Sam".to_string())]);
+        assert_eq!(template.render(&vars).unwrap(), "{not a name} and { } for Sam");
+    }
+}
@@ -0,0 +1,512 @@
+// Plugins - Sandboxed WebAssembly extensions
+//
+// A plugin is a WebAssembly module with a `plugin.json` manifest naming the
+// hooks it implements and the meeting data it wants to read. Installing one
+// copies the module into the app's "plugins" folder and registers its
+// permissions as an integration, so the user grants each kind of data
+// through the same prompt as Slack or Notion. A hook is only given the data
+// its plugin has been granted, and a new plugin starts disabled.
+//
+// Modules run in wasmtime with nothing to import but `env.log`: no files,
+// network, clock or environment. Each call gets a fresh instance with
+// bounded memory, fuel and running time, so a plugin that loops or
+// allocates without end is stopped rather than stalling the app. Fuel counts
+// the plugin's own work; the time limit also covers what a fuel count misses,
+// such as a call spent growing memory or in `env.log`.
+//
+// Calling convention: the module exports its `memory`, `alloc(len) -> ptr`
+// and a function per hook taking `(ptr, len)` of a UTF-8 JSON input. The
+// function returns its output packed as `ptr << 32 | len`, or 0 for none.
+// - `on_transcript_segment`: gets `{ meeting_id, segment }` for each saved
+//   segment; needs the transcripts permission
+// - `on_meeting_end`: gets the ended meeting
+// Both may answer `{ "notes": [{ "note_type", "content", "assignee" }] }`,
+// which are added to the meeting flagged for review, like trigger phrase
+// notes.
+// - `export`: gets the meeting and answers the exported document's bytes
+//
+// Hooks run in the background and their failures are logged, so a broken
+// plugin never costs a transcript segment or blocks ending a meeting.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use wasmtime::{Caller, Config, Engine, Extern, ExternType, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::db::Database;
+use crate::models::{DataScope, Meeting, Note, NoteType, Plugin, PluginHook, PluginManifest, TranscriptEntry};
+
+/// Folder in the app's data directory holding installed modules
+const PLUGINS_DIR: &str = "plugins";
+/// Fuel a single hook call may burn, a second or two of work
+const FUEL_PER_CALL: u64 = 2_000_000_000;
+/// How often the engine's epoch advances
+const EPOCH_TICK: Duration = Duration::from_millis(100);
+/// Epoch ticks a single hook call may run for, about five seconds
+const EPOCHS_PER_CALL: u64 = 50;
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+/// Largest output read back from a hook
+const MAX_OUTPUT_BYTES: usize = 32 * 1024 * 1024;
+/// Characters of a plugin's log message kept
+const MAX_LOG_CHARS: usize = 1000;
+/// Notes a plugin may add per hook call
+const MAX_NOTES_PER_CALL: usize = 50;
+
+/// Compiles installed modules and runs their hooks. Compiled modules are
+/// kept until the plugin is reinstalled or removed.
+pub struct PluginHost {
+    engine: Engine,
+    modules: Mutex<HashMap<String, Module>>,
+}
+
+impl Default for PluginHost {
+    fn default() -> Self {
+        let mut config = Config::new();
+        config.consume_fuel(true).epoch_interruption(true);
+        let engine = Engine::new(&config).expect("Failed to create the plugin engine");
+
+        // The host lives as long as the app, and so does its clock
+        let ticker = engine.clone();
+        thread::Builder::new()
+            .name("plugin-epoch".to_string())
+            .spawn(move || loop {
+                thread::sleep(EPOCH_TICK);
+                ticker.increment_epoch();
+            })
+            .expect("Failed to start the plugin clock");
+
+        PluginHost {
+            engine,
+            modules: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+struct HostState {
+    plugin_id: String,
+    limits: StoreLimits,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginExportResult {
+    pub plugin_id: String,
+    pub file_path: String,
+    pub bytes_written: u64,
+}
+
+/// Input of `on_transcript_segment`
+#[derive(Serialize)]
+struct SegmentInput<'a> {
+    meeting_id: &'a str,
+    segment: &'a TranscriptEntry,
+}
+
+/// Input of `on_meeting_end` and `export`. Data the plugin hasn't been
+/// granted is left out; `granted` tells it which parts to expect.
+#[derive(Serialize)]
+struct MeetingInput {
+    meeting_id: String,
+    granted: Vec<DataScope>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meeting: Option<Meeting>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcript: Option<Vec<TranscriptEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<Vec<Note>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HookOutput {
+    #[serde(default)]
+    notes: Vec<PluginNote>,
+}
+
+#[derive(Deserialize)]
+struct PluginNote {
+    note_type: NoteType,
+    content: String,
+    #[serde(default)]
+    assignee: Option<String>,
+}
+
+impl PluginHost {
+    /// Runs `hook` of the plugin with `input`, returning what it answered
+    fn call(&self, plugins_dir: &Path, plugin_id: &str, hook: PluginHook, input: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        let module = self.module(plugins_dir, plugin_id)?;
+        let mut store = Store::new(&self.engine, HostState {
+            plugin_id: plugin_id.to_string(),
+            limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).instances(1).build(),
+        });
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+        store.set_epoch_deadline(EPOCHS_PER_CALL);
+
+        let instance = self.linker()?
+            .instantiate(&mut store, &module)
+            .map_err(|e| format!("Failed to start plugin {}: {:#}", plugin_id, e))?;
+        let memory = instance.get_memory(&mut store, "memory")
+            .ok_or_else(|| format!("Plugin {} doesn't export its memory", plugin_id))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| format!("Plugin {} has no usable alloc: {:#}", plugin_id, e))?;
+        let run = instance.get_typed_func::<(i32, i32), i64>(&mut store, hook.export_name())
+            .map_err(|e| format!("Plugin {} has no usable {}: {:#}", plugin_id, hook.export_name(), e))?;
+
+        let len = i32::try_from(input.len()).map_err(|_| "Plugin input is too large".to_string())?;
+        let trap = |e: wasmtime::Error| format!("Plugin {} failed in {}: {:#}", plugin_id, hook.export_name(), e);
+        let ptr = alloc.call(&mut store, len).map_err(trap)?;
+        memory.write(&mut store, ptr as u32 as usize, input)
+            .map_err(|e| format!("Plugin {} gave an unusable input buffer: {}", plugin_id, e))?;
+        let packed = run.call(&mut store, (ptr, len)).map_err(trap)? as u64;
+        if packed == 0 {
+            return Ok(None);
+        }
+
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if out_len > MAX_OUTPUT_BYTES {
+            return Err(format!("Plugin {} answered more than {} bytes", plugin_id, MAX_OUTPUT_BYTES));
+        }
+        let mut output = vec![0; out_len];
+        memory.read(&store, out_ptr, &mut output)
+            .map_err(|e| format!("Plugin {} answered outside its memory: {}", plugin_id, e))?;
+        Ok(Some(output))
+    }
+
+    /// The only import a plugin gets: `env.log(ptr, len)` writes a UTF-8
+    /// message to the app's log
+    fn linker(&self) -> Result<Linker<HostState>, String> {
+        let mut linker = Linker::new(&self.engine);
+        linker
+            .func_wrap("env", "log", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                let Some(memory) = caller.get_export("memory").and_then(Extern::into_memory) else {
+                    return;
+                };
+                let start = ptr as u32 as usize;
+                let data = memory.data(&caller);
+                if let Some(bytes) = data.get(start..start.saturating_add(len as u32 as usize)) {
+                    let message: String = String::from_utf8_lossy(bytes).chars().take(MAX_LOG_CHARS).collect();
+                    log::info!("Plugin {}: {}", caller.data().plugin_id, message);
+                }
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(linker)
+    }
+
+    fn module(&self, plugins_dir: &Path, plugin_id: &str) -> Result<Module, String> {
+        if let Some(module) = self.modules.lock().unwrap().get(plugin_id) {
+            return Ok(module.clone());
+        }
+        let path = module_path(plugins_dir, plugin_id);
+        let wasm = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let module = Module::from_binary(&self.engine, &wasm)
+            .map_err(|e| format!("Failed to compile plugin {}: {:#}", plugin_id, e))?;
+        self.modules.lock().unwrap().insert(plugin_id.to_string(), module.clone());
+        Ok(module)
+    }
+
+    /// Compiles the module and checks it provides what the manifest
+    /// promises and imports nothing it won't get
+    fn check(&self, manifest: &PluginManifest, wasm: &[u8]) -> Result<(), String> {
+        let module = Module::from_binary(&self.engine, wasm)
+            .map_err(|e| format!("Not a valid WebAssembly module: {:#}", e))?;
+
+        if let Some(import) = module.imports().find(|import| (import.module(), import.name()) != ("env", "log")) {
+            return Err(format!(
+                "The plugin imports {}.{}, but plugins can only import env.log",
+                import.module(),
+                import.name()
+            ));
+        }
+        if !matches!(module.get_export("memory"), Some(ExternType::Memory(_))) {
+            return Err("The plugin doesn't export its memory".to_string());
+        }
+        let functions = std::iter::once("alloc").chain(manifest.hooks.iter().map(PluginHook::export_name));
+        for name in functions {
+            if !matches!(module.get_export(name), Some(ExternType::Func(_))) {
+                return Err(format!("The plugin doesn't export the function {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    fn forget(&self, plugin_id: &str) {
+        self.modules.lock().unwrap().remove(plugin_id);
+    }
+}
+
+pub fn plugins_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(PLUGINS_DIR)
+}
+
+fn module_path(plugins_dir: &Path, plugin_id: &str) -> PathBuf {
+    plugins_dir.join(format!("{}.wasm", plugin_id))
+}
+
+fn validate(manifest: &PluginManifest) -> Result<(), String> {
+    let id = manifest.id.trim();
+    if id.is_empty() || id.len() > 64 || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("A plugin id must be 1-64 letters, digits, dashes or underscores".to_string());
+    }
+    if manifest.name.trim().is_empty() {
+        return Err("The plugin needs a name".to_string());
+    }
+    if manifest.hooks.is_empty() {
+        return Err("The plugin doesn't hook into anything".to_string());
+    }
+    match (&manifest.exporter, manifest.hooks.contains(&PluginHook::Exporter)) {
+        (None, true) => Err("An exporter plugin must describe its file format".to_string()),
+        (Some(exporter), true) if exporter.label.trim().is_empty()
+            || exporter.extension.is_empty()
+            || !exporter.extension.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            Err("An exporter needs a label and a file extension of letters and digits".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Installs the plugin whose manifest is at `manifest_path`, replacing an
+/// installed version of it
+pub fn install(db: &Database, host: &PluginHost, app_dir: &Path, manifest_path: &Path) -> Result<Plugin, String> {
+    let manifest: PluginManifest = serde_json::from_str(
+        &fs::read_to_string(manifest_path).map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?,
+    )
+    .map_err(|e| format!("Invalid plugin manifest: {}", e))?;
+    validate(&manifest)?;
+
+    let wasm_path = manifest_path.parent().unwrap_or_else(|| Path::new(".")).join(&manifest.module);
+    let wasm = fs::read(&wasm_path).map_err(|e| format!("Failed to read {}: {}", wasm_path.display(), e))?;
+    host.check(&manifest, &wasm)?;
+
+    let dir = plugins_dir(app_dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    fs::write(module_path(&dir, &manifest.id), &wasm).map_err(|e| format!("Failed to install the plugin: {}", e))?;
+    host.forget(&manifest.id);
+    db.save_plugin(&manifest).map_err(|e| format!("Failed to save plugin: {}", e))?;
+
+    log::info!("Installed plugin {} {}", manifest.id, manifest.version);
+    get(db, &manifest.id)?.ok_or_else(|| "Plugin not found".to_string())
+}
+
+pub fn uninstall(db: &Database, host: &PluginHost, app_dir: &Path, plugin_id: &str) -> Result<(), String> {
+    db.delete_plugin(plugin_id).map_err(|e| format!("Failed to remove plugin: {}", e))?;
+    host.forget(plugin_id);
+    let path = module_path(&plugins_dir(app_dir), plugin_id);
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+    }
+    log::info!("Uninstalled plugin {}", plugin_id);
+    Ok(())
+}
+
+pub fn get(db: &Database, plugin_id: &str) -> Result<Option<Plugin>, String> {
+    Ok(db.get_plugins()
+        .map_err(|e| format!("Failed to fetch plugins: {}", e))?
+        .into_iter()
+        .find(|plugin| plugin.manifest.id == plugin_id))
+}
+
+/// Enabled plugins implementing `hook`
+fn enabled_with(db: &Database, hook: PluginHook) -> Vec<Plugin> {
+    match db.get_plugins() {
+        Ok(plugins) => plugins.into_iter()
+            .filter(|plugin| plugin.enabled && plugin.manifest.hooks.contains(&hook))
+            .collect(),
+        Err(e) => {
+            log::warn!("Failed to load plugins: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// The permissions of the plugin the user has granted
+fn granted(db: &Database, plugin: &Plugin) -> Vec<DataScope> {
+    plugin.manifest.permissions.iter()
+        .copied()
+        .filter(|scope| db.is_scope_granted(&plugin.integration_id, *scope).unwrap_or(false))
+        .collect()
+}
+
+fn meeting_input(db: &Database, meeting: &Meeting, granted: Vec<DataScope>) -> Result<MeetingInput, String> {
+    let has = |scope| granted.contains(&scope);
+    let transcript = if has(DataScope::Transcripts) {
+        Some(db.get_transcript(&meeting.id).map_err(|e| format!("Failed to fetch transcript: {}", e))?)
+    } else {
+        None
+    };
+    let notes = if has(DataScope::Notes) || has(DataScope::ActionItems) {
+        let notes = db.get_notes(&meeting.id).map_err(|e| format!("Failed to fetch notes: {}", e))?;
+        Some(notes.into_iter()
+            .filter(|note| if note.note_type == NoteType::ActionItem { has(DataScope::ActionItems) } else { has(DataScope::Notes) })
+            .collect())
+    } else {
+        None
+    };
+    let summary = if has(DataScope::Summaries) {
+        db.get_summary_versions(&meeting.id)
+            .map_err(|e| format!("Failed to fetch summaries: {}", e))?
+            .into_iter()
+            .next()
+            .map(|summary| summary.content)
+    } else {
+        None
+    };
+
+    Ok(MeetingInput {
+        meeting_id: meeting.id.clone(),
+        meeting: has(DataScope::MeetingMetadata).then(|| meeting.clone()),
+        transcript,
+        notes,
+        summary,
+        granted,
+    })
+}
+
+/// Turns the notes a hook answered with into the meeting's notes. `key`
+/// makes their ids stable, so running a hook again adds nothing twice.
+fn notes_from_output(plugin_id: &str, output: &[u8], meeting_id: &str, key: &str, timestamp: i64, source_refs: &[String]) -> Result<Vec<Note>, String> {
+    let output: HookOutput = serde_json::from_slice(output)
+        .map_err(|e| format!("Plugin {} answered invalid JSON: {}", plugin_id, e))?;
+    let now = Utc::now();
+    Ok(output.notes.into_iter()
+        .filter(|note| !note.content.trim().is_empty())
+        .take(MAX_NOTES_PER_CALL)
+        .enumerate()
+        .map(|(i, note)| Note {
+            id: format!("plugin-{}-{}-{}", plugin_id, key, i),
+            meeting_id: meeting_id.to_string(),
+            note_type: note.note_type,
+            content: note.content.trim().to_string(),
+            timestamp,
+            source_refs: source_refs.to_vec(),
+            assignee: note.assignee.filter(|assignee| !assignee.trim().is_empty()),
+            deadline: None,
+            completed: false,
+            completed_at: None,
+            deferred_until: None,
+            needs_review: true,
+            created_at: now,
+            updated_at: now,
+        })
+        .collect())
+}
+
+/// Saves the notes plugins added and emits `auto-notes-created` with them
+fn save_notes(app: &AppHandle, db: &Database, notes: Vec<Note>) {
+    let mut created = Vec::new();
+    for note in notes {
+        match db.insert_note_if_absent(&note) {
+            Ok(true) => created.push(note),
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to save plugin note {}: {}", note.id, e),
+        }
+    }
+
+    if !created.is_empty() {
+        log::info!("Plugins created {} note(s)", created.len());
+        if let Err(e) = app.emit_all("auto-notes-created", &created) {
+            log::warn!("Failed to emit auto-notes-created: {}", e);
+        }
+    }
+}
+
+/// Runs the enabled `on_transcript_segment` hooks over newly saved segments
+/// in the background
+pub fn run_segment_hooks(app: &AppHandle, db: &Arc<Database>, host: &Arc<PluginHost>, app_dir: &Path, entries: &[TranscriptEntry]) {
+    let plugins: Vec<Plugin> = enabled_with(db, PluginHook::TranscriptSegment)
+        .into_iter()
+        .filter(|plugin| granted(db, plugin).contains(&DataScope::Transcripts))
+        .collect();
+    if plugins.is_empty() || entries.is_empty() {
+        return;
+    }
+
+    let (app, db, host, dir, entries) = (app.clone(), Arc::clone(db), Arc::clone(host), plugins_dir(app_dir), entries.to_vec());
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut notes = Vec::new();
+        for plugin in &plugins {
+            let id = &plugin.manifest.id;
+            for entry in &entries {
+                let input = serde_json::to_vec(&SegmentInput { meeting_id: &entry.meeting_id, segment: entry })
+                    .unwrap_or_default();
+                let added = host.call(&dir, id, PluginHook::TranscriptSegment, &input)
+                    .and_then(|output| match output {
+                        Some(output) => notes_from_output(id, &output, &entry.meeting_id, &entry.id, entry.timestamp, std::slice::from_ref(&entry.id)),
+                        None => Ok(Vec::new()),
+                    });
+                match added {
+                    Ok(added) => notes.extend(added),
+                    Err(e) => {
+                        // Likely to fail the same way for the rest of the batch
+                        log::warn!("{}", e);
+                        break;
+                    }
+                }
+            }
+        }
+        save_notes(&app, &db, notes);
+    });
+}
+
+/// Runs the enabled `on_meeting_end` hooks in the background
+pub fn run_meeting_end_hooks(app: &AppHandle, db: &Arc<Database>, host: &Arc<PluginHost>, app_dir: &Path, meeting: &Meeting) {
+    let plugins = enabled_with(db, PluginHook::MeetingEnd);
+    if plugins.is_empty() {
+        return;
+    }
+
+    let (app, db, host, dir, meeting) = (app.clone(), Arc::clone(db), Arc::clone(host), plugins_dir(app_dir), meeting.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        let ended_at = meeting.end_time
+            .map(|end| (end - meeting.start_time).num_milliseconds())
+            .unwrap_or_default();
+        // Segment ids are unique across meetings, but "end" needs the meeting
+        let key = format!("end-{}", meeting.id);
+        let mut notes = Vec::new();
+        for plugin in &plugins {
+            let id = &plugin.manifest.id;
+            let added = meeting_input(&db, &meeting, granted(&db, plugin))
+                .and_then(|input| host.call(&dir, id, PluginHook::MeetingEnd, &serde_json::to_vec(&input).unwrap_or_default()))
+                .and_then(|output| match output {
+                    Some(output) => notes_from_output(id, &output, &meeting.id, &key, ended_at, &[]),
+                    None => Ok(Vec::new()),
+                });
+            match added {
+                Ok(added) => notes.extend(added),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+        save_notes(&app, &db, notes);
+    });
+}
+
+/// Exports the meeting with the plugin's exporter and writes the document
+/// to `path`
+pub fn export(db: &Database, host: &PluginHost, app_dir: &Path, plugin_id: &str, meeting: &Meeting, path: &Path) -> Result<PluginExportResult, String> {
+    let plugin = get(db, plugin_id)?.ok_or_else(|| "Plugin not found".to_string())?;
+    if !plugin.enabled {
+        return Err(format!("{} is disabled", plugin.manifest.name));
+    }
+    if !plugin.manifest.hooks.contains(&PluginHook::Exporter) {
+        return Err(format!("{} doesn't export meetings", plugin.manifest.name));
+    }
+
+    let input = meeting_input(db, meeting, granted(db, &plugin))?;
+    let document = host.call(&plugins_dir(app_dir), plugin_id, PluginHook::Exporter, &serde_json::to_vec(&input).unwrap_or_default())?
+        .ok_or_else(|| format!("{} produced nothing to export", plugin.manifest.name))?;
+    fs::write(path, &document).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    log::info!("Exported meeting {} with plugin {}", meeting.id, plugin_id);
+    Ok(PluginExportResult {
+        plugin_id: plugin_id.to_string(),
+        file_path: path.to_string_lossy().into_owned(),
+        bytes_written: document.len() as u64,
+    })
+}
@@ -0,0 +1,109 @@
+// Profile - Export and import the app configuration
+//
+// A profile carries the settings, the frontend's settings, note templates,
+// trigger phrases and integration manifests as one JSON file, so a team can
+// hand out a standard setup and a user can move to a new machine. Meetings
+// stay out of it, and so do secrets: API keys are blanked in the provider
+// chain and dropped from the frontend settings, and on import each provider
+// keeps the key already configured here. The user's own name and
+// per-machine state aren't exported either.
+//
+// Integration permissions are not part of a profile. Importing one only
+// declares the integrations it knows about; what they may read stays
+// pending until the user answers on this machine.
+
+use std::collections::BTreeMap;
+use chrono::Utc;
+
+use crate::ai::{self, ProviderConfig};
+use crate::audio_store;
+use crate::db::Database;
+use crate::local_api;
+use crate::models::{ConfigProfile, ProfileImportResult};
+
+pub const PROFILE_VERSION: u32 = 2;
+
+/// Where the frontend keeps API keys in its settings
+const APP_SETTINGS_KEYS_FIELD: &str = "apiKeys";
+
+/// Settings that describe this user or machine rather than the setup, or
+/// are secret
+const EXCLUDED_SETTINGS: [&str; 6] = [
+    "my_name",
+    "inbox_last_digest",
+    "meeting_load_warned_weeks",
+    local_api::TOKEN_KEY,
+    audio_store::S3_ACCESS_KEY_KEY,
+    audio_store::S3_SECRET_KEY_KEY,
+];
+
+pub fn export(db: &Database, app_settings: Option<serde_json::Value>) -> Result<ConfigProfile, String> {
+    let mut settings: BTreeMap<String, String> = db.get_all_settings()
+        .map_err(|e| format!("Failed to fetch settings: {}", e))?
+        .into_iter()
+        .filter(|(key, _)| !EXCLUDED_SETTINGS.contains(&key.as_str()))
+        .collect();
+    let chain: Vec<ProviderConfig> = ai::load_chain(db).into_iter()
+        .map(|provider| ProviderConfig { api_key: String::new(), ..provider })
+        .collect();
+    settings.insert(ai::PROVIDER_CHAIN_KEY.to_string(), serde_json::to_string(&chain).map_err(|e| e.to_string())?);
+
+    Ok(ConfigProfile {
+        version: PROFILE_VERSION,
+        exported_at: Utc::now(),
+        settings,
+        note_templates: db.get_note_templates()
+            .map_err(|e| format!("Failed to fetch note templates: {}", e))?,
+        trigger_phrases: db.get_trigger_phrases()
+            .map_err(|e| format!("Failed to fetch trigger phrases: {}", e))?,
+        integration_manifests: db.get_integration_permissions(None)
+            .map_err(|e| format!("Failed to fetch integrations: {}", e))?
+            .into_iter()
+            .map(|permissions| permissions.manifest)
+            .collect(),
+        app_settings: app_settings.map(without_keys),
+    })
+}
+
+pub fn import(db: &Database, mut profile: ConfigProfile) -> Result<ProfileImportResult, String> {
+    if profile.version > PROFILE_VERSION {
+        return Err(format!(
+            "Profile version {} is newer than this app supports ({})",
+            profile.version, PROFILE_VERSION
+        ));
+    }
+
+    profile.settings.retain(|key, _| !EXCLUDED_SETTINGS.contains(&key.as_str()));
+    profile.app_settings = profile.app_settings.map(without_keys);
+    if let Some(json) = profile.settings.get(ai::PROVIDER_CHAIN_KEY) {
+        let chain: Vec<ProviderConfig> = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid provider chain in profile: {}", e))?;
+        let chain = with_local_keys(chain, &ai::load_chain(db));
+        profile.settings.insert(ai::PROVIDER_CHAIN_KEY.to_string(), serde_json::to_string(&chain).map_err(|e| e.to_string())?);
+    }
+
+    db.import_profile(&profile)
+        .map_err(|e| format!("Failed to import profile: {}", e))
+}
+
+/// Fills in blank keys from the provider of the same service configured here
+fn with_local_keys(chain: Vec<ProviderConfig>, local: &[ProviderConfig]) -> Vec<ProviderConfig> {
+    chain.into_iter()
+        .map(|mut provider| {
+            if provider.api_key.is_empty() {
+                if let Some(existing) = local.iter().find(|p| p.provider == provider.provider && !p.api_key.is_empty()) {
+                    provider.api_key = existing.api_key.clone();
+                }
+            }
+            provider
+        })
+        .collect()
+}
+
+/// Drops the API keys from the frontend settings
+fn without_keys(mut app_settings: serde_json::Value) -> serde_json::Value {
+    if let Some(fields) = app_settings.as_object_mut() {
+        fields.remove(APP_SETTINGS_KEYS_FIELD);
+    }
+    app_settings
+}
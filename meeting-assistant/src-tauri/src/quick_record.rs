@@ -0,0 +1,75 @@
+// Quick Record - Start capturing without opening the main window
+//
+// Launching with `--quick-record`, or choosing "Quick Record" in the tray,
+// starts a meeting right away under the default title and opens only a
+// small always-on-top indicator. The indicator window captures the audio,
+// shows how long it has been recording and has the stop button; the main
+// window stays hidden until opened from the tray. When a meeting is already
+// recording nothing new starts: its indicator is brought up, or the main
+// window if the recording was started there. Recordings started through the
+// local API get the same indicator, since something has to capture audio.
+// Launching the app again while it runs only hands the arguments over to the
+// running app, so `--quick-record` from a shortcut still records there.
+
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+
+use crate::commands::{self, AppState};
+
+pub const ARG: &str = "--quick-record";
+const WINDOW_LABEL: &str = "quick-record";
+
+/// Whether the app was launched with `--quick-record`
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == ARG)
+}
+
+/// Handles another launch of the app: `--quick-record` starts recording, any
+/// other launch brings up the main window
+pub fn on_second_launch(app: &AppHandle, argv: &[String]) {
+    if argv.iter().any(|arg| arg == ARG) {
+        if let Err(e) = start(app) {
+            log::warn!("Failed to start quick recording: {}", e);
+        }
+    } else if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+pub fn start(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_window(WINDOW_LABEL) {
+        window.show().map_err(|e| e.to_string())?;
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let state = app.state::<AppState>();
+    if state.db.get_live_meeting().map_err(|e| e.to_string())?.is_some() {
+        let window = app.get_window("main").ok_or("Main window not found")?;
+        window.show().map_err(|e| e.to_string())?;
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let meeting = commands::begin_recording(&state, String::new()).map_err(|e| e.to_string())?;
+    log::info!("Quick recording started: {}", meeting.id);
+    open_indicator(app, &state, &meeting.id)
+}
+
+/// Opens the indicator window that captures audio for `meeting_id`. If it
+/// can't be opened the meeting is ended again.
+pub fn open_indicator(app: &AppHandle, state: &AppState, meeting_id: &str) -> Result<(), String> {
+    let url = WindowUrl::App(format!("quick-record?meeting={}", meeting_id).into());
+    let opened = WindowBuilder::new(app, WINDOW_LABEL, url)
+        .title("Recording")
+        .inner_size(300.0, 64.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build();
+    if let Err(e) = opened {
+        // Nothing would capture audio, so don't leave an empty meeting recording
+        let _ = commands::finish_meeting(app, state, meeting_id);
+        return Err(format!("Failed to open the recording indicator: {}", e));
+    }
+    Ok(())
+}
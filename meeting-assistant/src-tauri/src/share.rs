@@ -0,0 +1,177 @@
+// Share - Temporary read-only links to a meeting's minutes
+//
+// `share_meeting` renders the HTML export once and serves it from a small
+// HTTP server on a random port until the link expires or is stopped, so a
+// colleague can read the minutes in a browser without installing the app.
+// The page is only served under a random token; every other path is a 404.
+// By default the server only listens on localhost; LAN sharing listens on
+// every interface so others on the same network can open the link.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tiny_http::{Header, Method, Response, Server};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::export::{self, ExportFormat, ExportOptions};
+use crate::models::{DataScope, Meeting};
+
+pub const DEFAULT_TTL_MINUTES: u32 = 60;
+pub const MAX_TTL_MINUTES: u32 = 24 * 60;
+/// What a shared page shows: everything but annotations
+pub const SCOPES: [DataScope; 5] = [
+    DataScope::MeetingMetadata,
+    DataScope::Transcripts,
+    DataScope::Notes,
+    DataScope::ActionItems,
+    DataScope::Summaries,
+];
+/// How often the server checks whether its link expired or was stopped
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareLink {
+    pub id: String,
+    pub meeting_id: String,
+    pub url: String,
+    pub lan: bool,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct ActiveShare {
+    link: ShareLink,
+    stopped: Arc<AtomicBool>,
+}
+
+/// The links currently being served
+#[derive(Default)]
+pub struct Shares(Mutex<HashMap<String, ActiveShare>>);
+
+impl Shares {
+    /// Links that have neither expired nor been stopped
+    pub fn list(&self) -> Vec<ShareLink> {
+        let mut shares = self.0.lock().unwrap();
+        let now = Utc::now();
+        shares.retain(|_, share| share.link.expires_at > now && !share.stopped.load(Ordering::Relaxed));
+        shares.values().map(|share| share.link.clone()).collect()
+    }
+
+    /// Stops serving a link. Returns false if it was not active.
+    pub fn stop(&self, share_id: &str) -> bool {
+        match self.0.lock().unwrap().remove(share_id) {
+            Some(share) => {
+                share.stopped.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Renders the meeting with its latest summary and starts serving it for
+/// `ttl_minutes`
+pub fn share_meeting(
+    shares: &Shares,
+    db: &Database,
+    meeting: &Meeting,
+    ttl_minutes: u32,
+    lan: bool,
+) -> Result<ShareLink, String> {
+    let summary = db.get_summary_versions(&meeting.id)
+        .map_err(|e| format!("Failed to fetch summaries: {}", e))?
+        .into_iter()
+        .next()
+        .map(|summary| summary.content);
+    // Annotations are private, so never on a shared page
+    let options = ExportOptions {
+        include_transcript: true,
+        include_notes: true,
+        include_summary: summary,
+        include_annotations: false,
+        anonymize: false,
+        index_link: None,
+    };
+    let mut page = Vec::new();
+    export::write_meeting(db, meeting, ExportFormat::Html, &options, &mut page, |_, _, _| {})?;
+
+    let (bind_ip, host) = if lan {
+        let host = lan_address().ok_or_else(|| "Could not determine this computer's network address".to_string())?;
+        (IpAddr::V4(Ipv4Addr::UNSPECIFIED), host)
+    } else {
+        (IpAddr::V4(Ipv4Addr::LOCALHOST), IpAddr::V4(Ipv4Addr::LOCALHOST))
+    };
+    let server = Server::http(SocketAddr::new(bind_ip, 0))
+        .map_err(|e| format!("Failed to start share server: {}", e))?;
+    let port = server.server_addr().to_ip()
+        .map(|addr| addr.port())
+        .ok_or_else(|| "Share server has no network address".to_string())?;
+
+    let token = Uuid::new_v4().simple().to_string();
+    let path = format!("/m/{}", token);
+    let link = ShareLink {
+        id: Uuid::new_v4().to_string(),
+        meeting_id: meeting.id.clone(),
+        url: format!("http://{}{}", SocketAddr::new(host, port), path),
+        lan,
+        expires_at: Utc::now() + chrono::Duration::minutes(i64::from(ttl_minutes)),
+    };
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    {
+        let stopped = Arc::clone(&stopped);
+        let expires_at = link.expires_at;
+        thread::spawn(move || serve(server, &path, &page, expires_at, &stopped));
+    }
+
+    log::info!("Sharing meeting {} on port {} until {}", meeting.id, port, link.expires_at);
+    shares.0.lock().unwrap().insert(link.id.clone(), ActiveShare { link: link.clone(), stopped });
+    Ok(link)
+}
+
+fn serve(server: Server, path: &str, page: &[u8], expires_at: DateTime<Utc>, stopped: &AtomicBool) {
+    while !stopped.load(Ordering::Relaxed) && Utc::now() < expires_at {
+        let request = match server.recv_timeout(CHECK_INTERVAL) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Share server failed: {}", e);
+                break;
+            }
+        };
+
+        let response = if *request.method() == Method::Get && request.url() == path {
+            Response::from_data(page.to_vec()).with_header(header("Content-Type", "text/html; charset=utf-8"))
+        } else {
+            Response::from_string("Not found").with_status_code(404)
+        };
+        let response = response
+            .with_header(header("Cache-Control", "no-store"))
+            .with_header(header("Referrer-Policy", "no-referrer"))
+            .with_header(header("X-Robots-Tag", "noindex"));
+        if let Err(e) = request.respond(response) {
+            log::warn!("Failed to answer share request: {}", e);
+        }
+    }
+
+    stopped.store(true, Ordering::Relaxed);
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("static header is valid")
+}
+
+/// The address other machines on the network reach us at: the local end of
+/// a UDP socket pointed at a public address. Connecting UDP sends nothing.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80)).ok()?;
+    socket.local_addr().ok()
+        .map(|addr| addr.ip())
+        .filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+}
@@ -0,0 +1,91 @@
+// Silence Detection - Auto-pause/resume recording from reported audio levels
+
+use std::time::{Duration, Instant};
+
+use crate::db::Database;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SilenceConfig {
+    pub enabled: bool,
+    /// Normalized level (0.0 - 1.0) at or above which audio counts as speech
+    pub level_threshold: f64,
+    pub pause_after: Duration,
+    pub resume_after: Duration,
+}
+
+impl SilenceConfig {
+    pub fn load(db: &Database) -> Self {
+        let get = |key: &str, default: f64| -> f64 {
+            db.get_setting(key).ok().flatten()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+
+        SilenceConfig {
+            enabled: db.get_setting("auto_pause_enabled").ok().flatten().as_deref() != Some("false"),
+            level_threshold: get("auto_pause_level_threshold", 0.02),
+            pause_after: Duration::from_secs_f64(get("auto_pause_silence_seconds", 180.0).max(1.0)),
+            resume_after: Duration::from_secs_f64(get("auto_resume_speech_seconds", 2.0).max(0.0)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SilenceTransition {
+    AutoPause,
+    AutoResume,
+}
+
+/// Tracks speech/silence for the active meeting
+pub struct SilenceDetector {
+    meeting_id: Option<String>,
+    last_speech: Instant,
+    speech_started: Option<Instant>,
+    auto_paused: bool,
+}
+
+impl SilenceDetector {
+    pub fn new() -> Self {
+        SilenceDetector {
+            meeting_id: None,
+            last_speech: Instant::now(),
+            speech_started: None,
+            auto_paused: false,
+        }
+    }
+
+    /// Forget any silence accumulated so far, e.g. after a manual pause/resume
+    pub fn reset(&mut self) {
+        *self = SilenceDetector::new();
+    }
+
+    pub fn silent_for(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_speech)
+    }
+
+    pub fn observe(&mut self, meeting_id: &str, level: f64, config: &SilenceConfig, now: Instant) -> Option<SilenceTransition> {
+        if self.meeting_id.as_deref() != Some(meeting_id) {
+            self.reset();
+            self.meeting_id = Some(meeting_id.to_string());
+            self.last_speech = now;
+        }
+
+        if level >= config.level_threshold {
+            self.last_speech = now;
+            let started = *self.speech_started.get_or_insert(now);
+            if self.auto_paused && now.saturating_duration_since(started) >= config.resume_after {
+                self.auto_paused = false;
+                self.speech_started = None;
+                return Some(SilenceTransition::AutoResume);
+            }
+        } else {
+            self.speech_started = None;
+            if !self.auto_paused && self.silent_for(now) >= config.pause_after {
+                self.auto_paused = true;
+                return Some(SilenceTransition::AutoPause);
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,102 @@
+// Single Instance - Hand later launches over to the running app
+//
+// The first launch listens on a loopback port and writes the port to
+// `instance.port` in the app's data folder. A later launch connects there,
+// sends its arguments and exits once the running app confirms it has them,
+// so `--quick-record` from a shortcut records in the app that's already
+// open. If nothing answers, or something other than the app does, the file
+// is stale and the launch becomes the running app itself.
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::quick_record;
+
+const PORT_FILE: &str = "instance.port";
+/// The running app's answer, so a port since reused by another program
+/// isn't mistaken for it
+const ACK: &str = "meeting-assistant";
+const TIMEOUT: Duration = Duration::from_secs(2);
+/// Largest handover read
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+
+pub enum Launch {
+    /// This launch is the running app. Later launches are handed over through
+    /// the listener, when it could be opened.
+    First(Option<TcpListener>),
+    /// The running app has this launch's arguments, so it should exit
+    HandedOver,
+}
+
+/// Hands this launch's arguments to the running app, if there is one
+pub fn claim(config: &tauri::Config) -> Launch {
+    let Some(port_file) = tauri::api::path::app_data_dir(config).map(|dir| dir.join(PORT_FILE)) else {
+        return Launch::First(None);
+    };
+
+    let port = fs::read_to_string(&port_file).ok().and_then(|port| port.trim().parse::<u16>().ok());
+    if let Some(port) = port {
+        match hand_over(port) {
+            Ok(()) => return Launch::HandedOver,
+            Err(e) => log::info!("No running instance on port {}: {}", port, e),
+        }
+    }
+
+    match listen(&port_file) {
+        Ok(listener) => Launch::First(Some(listener)),
+        Err(e) => {
+            log::warn!("Failed to listen for later launches: {}", e);
+            Launch::First(None)
+        }
+    }
+}
+
+/// Passes the arguments of every later launch to the running app
+pub fn serve(app: AppHandle, listener: TcpListener) {
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            match receive(stream) {
+                Ok(argv) => quick_record::on_second_launch(&app, &argv),
+                Err(e) => log::warn!("Ignored a launch handover: {}", e),
+            }
+        }
+    });
+}
+
+fn hand_over(port: u16) -> io::Result<()> {
+    let mut stream = TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, port)), TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let argv: Vec<String> = std::env::args().collect();
+    stream.write_all(format!("{}\n", serde_json::to_string(&argv)?).as_bytes())?;
+
+    let mut answer = String::new();
+    BufReader::new(stream.take(MAX_MESSAGE_BYTES)).read_line(&mut answer)?;
+    if answer.trim_end() == ACK {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidData, "something else answered"))
+    }
+}
+
+fn listen(port_file: &Path) -> io::Result<TcpListener> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    if let Some(dir) = port_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(port_file, listener.local_addr()?.port().to_string())?;
+    Ok(listener)
+}
+
+fn receive(mut stream: TcpStream) -> io::Result<Vec<String>> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_MESSAGE_BYTES)).read_line(&mut line)?;
+    let argv = serde_json::from_str(&line)?;
+    stream.write_all(format!("{}\n", ACK).as_bytes())?;
+    Ok(argv)
+}
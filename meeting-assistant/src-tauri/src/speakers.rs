@@ -0,0 +1,230 @@
+// Speakers - Sanity checks and re-clustering for diarized transcripts
+//
+// Live diarization assigns each entry to a speaker as it arrives and tends to
+// split one voice in two or merge similar ones. When a meeting ends, the
+// number of speakers found is compared with its participant list and a
+// `speaker-count-mismatch` event is emitted if they are far apart. The user
+// can then re-cluster the whole transcript into a given number of speakers
+// using the voice characteristics stored with each entry.
+
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::models::{Meeting, ReclusterResult, SpeakerCountCheck, TranscriptEntry};
+
+pub const SPEAKER_COUNT_EVENT: &str = "speaker-count-mismatch";
+pub const MAX_SPEAKERS: usize = 20;
+/// Detected and expected counts diverge when one is more than this many
+/// times the other
+const DIVERGENCE_RATIO: f64 = 1.5;
+const MAX_ITERATIONS: usize = 100;
+
+/// Compares the speakers in the transcript with the participant list. `None`
+/// when there is nothing to compare, i.e. no participants or no transcript.
+pub fn check_speaker_count(db: &Database, meeting: &Meeting) -> Result<Option<SpeakerCountCheck>, String> {
+    let expected = meeting.participants.len();
+    let detected = db.count_speakers(&meeting.id)
+        .map_err(|e| format!("Failed to count speakers: {}", e))?;
+    if expected == 0 || detected == 0 {
+        return Ok(None);
+    }
+
+    let ratio = detected.max(expected) as f64 / detected.min(expected) as f64;
+    Ok(Some(SpeakerCountCheck {
+        meeting_id: meeting.id.clone(),
+        detected,
+        expected,
+        diverges: ratio > DIVERGENCE_RATIO,
+    }))
+}
+
+/// Runs the speaker count check once diarization is done and warns the
+/// frontend if it fails. Errors are only logged.
+pub fn validate_speakers(app: &AppHandle, db: &Database, meeting: &Meeting) {
+    match check_speaker_count(db, meeting) {
+        Ok(Some(check)) if check.diverges => {
+            log::warn!(
+                "Meeting {} has {} speakers but {} participants",
+                meeting.id, check.detected, check.expected
+            );
+            if let Err(e) = app.emit_all(SPEAKER_COUNT_EVENT, &check) {
+                log::warn!("Failed to emit speaker count warning: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Speaker count check failed for {}: {}", meeting.id, e),
+    }
+}
+
+/// Reassigns every entry of the transcript to one of `speakers` speakers by
+/// k-means over its voice characteristics. Entries recorded without voice
+/// data take the speaker of the nearest entry in time. Names the user gave
+/// speakers are kept for the cluster most of that speaker's entries land in.
+pub fn recluster(db: &Database, meeting_id: &str, speakers: usize) -> Result<ReclusterResult, String> {
+    if speakers == 0 || speakers > MAX_SPEAKERS {
+        return Err(format!("Speaker count must be between 1 and {}", MAX_SPEAKERS));
+    }
+
+    let entries = db.get_transcript(meeting_id)
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))?;
+    let featured: Vec<(usize, [f64; 2])> = entries.iter().enumerate()
+        .filter_map(|(i, entry)| Some((i, [entry.voice_pitch?, entry.voice_energy?])))
+        .collect();
+    if featured.len() < speakers {
+        return Err(format!(
+            "Only {} transcript entries have voice data, too few to find {} speakers",
+            featured.len(), speakers
+        ));
+    }
+
+    let points = normalize(featured.iter().map(|(_, point)| *point).collect());
+    let clusters = kmeans(&points, speakers);
+
+    // Every entry's cluster, borrowing the nearest featured entry's for the rest
+    let mut cluster_of: Vec<Option<usize>> = vec![None; entries.len()];
+    for ((i, _), cluster) in featured.iter().zip(&clusters) {
+        cluster_of[*i] = Some(*cluster);
+    }
+    let entries_inferred = entries.len() - featured.len();
+    let cluster_of: Vec<usize> = (0..entries.len())
+        .map(|i| cluster_of[i].unwrap_or_else(|| nearest_cluster(&entries, &featured, &clusters, i)))
+        .collect();
+
+    let labels = label_clusters(&entries, &cluster_of);
+    let mut assignments = Vec::new();
+    for (entry, cluster) in entries.iter().zip(&cluster_of) {
+        let (speaker_id, speaker_name) = &labels[cluster];
+        if entry.speaker_id != *speaker_id || entry.speaker_name != *speaker_name {
+            assignments.push((entry.id.clone(), speaker_id.clone(), speaker_name.clone()));
+        }
+    }
+    db.set_speakers(&assignments)
+        .map_err(|e| format!("Failed to update speakers: {}", e))?;
+
+    log::info!("Re-clustered meeting {} into {} speakers", meeting_id, labels.len());
+    Ok(ReclusterResult {
+        meeting_id: meeting_id.to_string(),
+        speakers: labels.len(),
+        entries_changed: assignments.len(),
+        entries_inferred,
+    })
+}
+
+/// Scales each dimension to zero mean and unit variance so pitch in Hz
+/// doesn't drown out energy
+fn normalize(mut points: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let n = points.len() as f64;
+    for dim in 0..2 {
+        let mean = points.iter().map(|p| p[dim]).sum::<f64>() / n;
+        let std = (points.iter().map(|p| (p[dim] - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let std = if std > f64::EPSILON { std } else { 1.0 };
+        for point in &mut points {
+            point[dim] = (point[dim] - mean) / std;
+        }
+    }
+    points
+}
+
+fn distance(a: &[f64; 2], b: &[f64; 2]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)
+}
+
+/// Cluster index per point. Seeded deterministically with farthest-point
+/// initialization so re-running gives the same answer.
+fn kmeans(points: &[[f64; 2]], k: usize) -> Vec<usize> {
+    let mut centroids = vec![points[0]];
+    while centroids.len() < k {
+        let farthest = points.iter()
+            .max_by(|a, b| {
+                let da = centroids.iter().map(|c| distance(a, c)).fold(f64::MAX, f64::min);
+                let db = centroids.iter().map(|c| distance(b, c)).fold(f64::MAX, f64::min);
+                da.total_cmp(&db)
+            })
+            .copied()
+            .unwrap_or(points[0]);
+        centroids.push(farthest);
+    }
+
+    let mut clusters = vec![0; points.len()];
+    for iteration in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (point, cluster) in points.iter().zip(clusters.iter_mut()) {
+            let nearest = (0..k)
+                .min_by(|&a, &b| distance(point, &centroids[a]).total_cmp(&distance(point, &centroids[b])))
+                .unwrap_or(0);
+            if nearest != *cluster {
+                *cluster = nearest;
+                changed = true;
+            }
+        }
+        if !changed && iteration > 0 {
+            break;
+        }
+
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            let members: Vec<&[f64; 2]> = points.iter().zip(&clusters)
+                .filter(|(_, cluster)| **cluster == c)
+                .map(|(point, _)| point)
+                .collect();
+            // An emptied cluster keeps its centroid
+            if !members.is_empty() {
+                let n = members.len() as f64;
+                *centroid = [
+                    members.iter().map(|p| p[0]).sum::<f64>() / n,
+                    members.iter().map(|p| p[1]).sum::<f64>() / n,
+                ];
+            }
+        }
+    }
+    clusters
+}
+
+fn nearest_cluster(entries: &[TranscriptEntry], featured: &[(usize, [f64; 2])], clusters: &[usize], i: usize) -> usize {
+    featured.iter().zip(clusters)
+        .min_by_key(|((j, _), _)| (entries[*j].timestamp - entries[i].timestamp).abs())
+        .map(|(_, cluster)| *cluster)
+        .unwrap_or(0)
+}
+
+/// Speaker id and name per cluster, numbered in order of first appearance.
+/// Each name the user gave goes to the cluster holding most of its entries,
+/// biggest shares first.
+fn label_clusters(entries: &[TranscriptEntry], cluster_of: &[usize]) -> HashMap<usize, (String, String)> {
+    let mut order = Vec::new();
+    for cluster in cluster_of {
+        if !order.contains(cluster) {
+            order.push(*cluster);
+        }
+    }
+
+    let mut shares: HashMap<(&str, usize), usize> = HashMap::new();
+    for (entry, cluster) in entries.iter().zip(cluster_of) {
+        if !is_generic_name(&entry.speaker_name) {
+            *shares.entry((entry.speaker_name.as_str(), *cluster)).or_default() += 1;
+        }
+    }
+    let mut shares: Vec<((&str, usize), usize)> = shares.into_iter().collect();
+    shares.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut names: HashMap<usize, &str> = HashMap::new();
+    for ((name, cluster), _) in shares {
+        if !names.contains_key(&cluster) && !names.values().any(|n| *n == name) {
+            names.insert(cluster, name);
+        }
+    }
+
+    order.iter().enumerate()
+        .map(|(n, cluster)| {
+            let name = names.get(cluster).map_or_else(|| format!("Speaker {}", n + 1), |name| name.to_string());
+            (*cluster, (format!("speaker-{}", n + 1), name))
+        })
+        .collect()
+}
+
+/// Placeholder names like "Speaker 2" from the live diarizer
+fn is_generic_name(name: &str) -> bool {
+    name.strip_prefix("Speaker ")
+        .is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+        || name == "Speaker"
+}
@@ -0,0 +1,150 @@
+// Summaries - Versioned meeting summaries generated through the AI chain
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::ai::{self, ChatMessage, ChatRequest, ProviderConfig};
+use crate::db::Database;
+use crate::models::{
+    Meeting, MeetingStatus, RegenerateSummariesResult, SummaryFilter, SummaryVersion, TranscriptEntry,
+};
+
+/// Bump whenever the prompt below changes, so `regenerate_summaries` can
+/// find summaries made with an older prompt
+pub const TEMPLATE_VERSION: i64 = 1;
+
+const SYSTEM_PROMPT: &str = "You are a meeting assistant that writes concise, accurate summaries of meeting transcripts. \
+Only use information from the transcript.";
+
+const INSTRUCTIONS: &str = "Summarize this meeting. Use Markdown with these sections:\n\
+## Overview\nTwo or three sentences on the purpose and outcome.\n\
+## Topics\nOne bullet per topic discussed.\n\
+## Decisions\nOne bullet per decision, or \"None\".\n\
+## Action Items\nOne bullet per action item with the owner and deadline if mentioned, or \"None\".";
+
+/// Summarizes one meeting and stores the result as its next version
+pub async fn summarize(db: &Database, chain: &[ProviderConfig], meeting: &Meeting) -> Result<SummaryVersion, String> {
+    let transcript = db.get_transcript(&meeting.id)
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))?;
+    if transcript.is_empty() {
+        return Err("Meeting has no transcript to summarize".to_string());
+    }
+    summarize_transcript(db, chain, meeting, &transcript).await
+}
+
+async fn summarize_transcript(
+    db: &Database,
+    chain: &[ProviderConfig],
+    meeting: &Meeting,
+    transcript: &[TranscriptEntry],
+) -> Result<SummaryVersion, String> {
+    let request = ChatRequest {
+        messages: vec![
+            ChatMessage { role: "system".to_string(), content: SYSTEM_PROMPT.to_string() },
+            ChatMessage { role: "user".to_string(), content: build_prompt(meeting, transcript) },
+        ],
+        max_tokens: 1500,
+        temperature: 0.3,
+        purpose: "summary".to_string(),
+    };
+    let completion = ai::complete(db, chain, &request).await?;
+
+    let mut summary = SummaryVersion {
+        id: Uuid::new_v4().to_string(),
+        meeting_id: meeting.id.clone(),
+        version: 0,
+        template_version: TEMPLATE_VERSION,
+        provider: completion.provider,
+        model: completion.model,
+        content: completion.content,
+        created_at: Utc::now(),
+    };
+    summary.version = db.add_summary_version(&summary)
+        .map_err(|e| format!("Failed to save summary: {}", e))?;
+    Ok(summary)
+}
+
+/// Re-summarizes the meetings matching `filter` one at a time with the
+/// current prompt and the head of `chain`. Earlier versions are kept.
+pub async fn regenerate(db: &Database, chain: &[ProviderConfig], filter: &SummaryFilter) -> Result<RegenerateSummariesResult, String> {
+    let target = chain.first().ok_or_else(|| "No AI provider configured".to_string())?;
+    let outdated_only = filter.outdated_only.unwrap_or(true);
+
+    let meetings: Vec<Meeting> = db.get_all_meetings()
+        .map_err(|e| format!("Failed to fetch meetings: {}", e))?
+        .into_iter()
+        .filter(|m| matches!(m.status, MeetingStatus::Completed))
+        .filter(|m| filter.meeting_ids.as_ref().map_or(true, |ids| ids.contains(&m.id)))
+        .filter(|m| filter.since.map_or(true, |since| m.start_time >= since))
+        .filter(|m| filter.until.map_or(true, |until| m.start_time < until))
+        .collect();
+
+    let mut result = RegenerateSummariesResult { regenerated: vec![], skipped: 0, failed: vec![] };
+    for meeting in meetings {
+        if filter.limit.is_some_and(|limit| result.regenerated.len() + result.failed.len() >= limit) {
+            break;
+        }
+
+        if outdated_only {
+            let versions = db.get_summary_versions(&meeting.id)
+                .map_err(|e| format!("Failed to fetch summaries: {}", e))?;
+            if versions.first().is_some_and(|latest| !is_outdated(latest, target)) {
+                result.skipped += 1;
+                continue;
+            }
+        }
+
+        let transcript = db.get_transcript(&meeting.id)
+            .map_err(|e| format!("Failed to fetch transcript: {}", e))?;
+        if transcript.is_empty() {
+            result.skipped += 1;
+            continue;
+        }
+
+        match summarize_transcript(db, chain, &meeting, &transcript).await {
+            Ok(summary) => result.regenerated.push(summary),
+            Err(e) => {
+                log::warn!("Failed to regenerate summary for {}: {}", meeting.id, e);
+                result.failed.push((meeting.id.clone(), e));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn is_outdated(summary: &SummaryVersion, target: &ProviderConfig) -> bool {
+    summary.template_version < TEMPLATE_VERSION
+        || summary.provider != target.provider
+        || summary.model != target.model
+}
+
+fn build_prompt(meeting: &Meeting, transcript: &[TranscriptEntry]) -> String {
+    let mut prompt = transcript_prompt(meeting, transcript);
+    prompt.push('\n');
+    prompt.push_str(INSTRUCTIONS);
+    prompt
+}
+
+/// The meeting's title, date and participants followed by its transcript
+/// with "[MM:SS] Speaker: text" lines, for prompts about the whole meeting
+pub fn transcript_prompt(meeting: &Meeting, transcript: &[TranscriptEntry]) -> String {
+    let mut prompt = format!("Meeting: {}\nDate: {}\n", meeting.title, meeting.start_time.format("%B %d, %Y"));
+    if !meeting.participants.is_empty() {
+        let names: Vec<&str> = meeting.participants.iter().map(|p| p.name.as_str()).collect();
+        prompt.push_str(&format!("Participants: {}\n", names.join(", ")));
+    }
+
+    prompt.push_str("\nTranscript:\n");
+    for entry in transcript {
+        let seconds = entry.timestamp / 1000;
+        prompt.push_str(&format!(
+            "[{:02}:{:02}] {}: {}\n",
+            seconds / 60,
+            seconds % 60,
+            entry.speaker_name,
+            entry.text
+        ));
+    }
+    prompt
+}
@@ -0,0 +1,32 @@
+// Tray - Tooltip showing the meeting being recorded and for how long
+
+use std::time::Duration;
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::AppState;
+use crate::locale::Locale;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+pub const IDLE_TOOLTIP: &str = "Meeting Assistant";
+
+/// Keeps the tray tooltip up to date with the current recording
+pub fn spawn_tray_tooltip(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let tooltip = recording_tooltip(&app).unwrap_or_else(|| IDLE_TOOLTIP.to_string());
+            if let Err(e) = app.tray_handle().set_tooltip(&tooltip) {
+                log::warn!("Failed to set tray tooltip: {}", e);
+            }
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+fn recording_tooltip(app: &AppHandle) -> Option<String> {
+    let state = app.state::<AppState>();
+    let meeting = state.db.get_recording_meeting().ok().flatten()?;
+    let elapsed = Utc::now().signed_duration_since(meeting.start_time).num_seconds();
+    let locale = Locale::from_settings(&state.db);
+    Some(format!("Recording \"{}\" · {}", meeting.title, locale.format_duration(elapsed)))
+}
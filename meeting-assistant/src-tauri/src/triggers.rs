@@ -0,0 +1,73 @@
+// Trigger Phrases - Auto-create notes when a configured phrase is spoken
+
+use chrono::Utc;
+
+use crate::models::{Note, TranscriptEntry, TriggerPhrase};
+
+/// Notes for every transcript entry containing an enabled trigger phrase.
+/// Ids are derived from the entry and phrase, so re-processing the same
+/// segment never produces duplicates. Notes are flagged for review since
+/// phrase matching has false positives.
+pub fn notes_from_transcript(entries: &[TranscriptEntry], phrases: &[TriggerPhrase]) -> Vec<Note> {
+    let mut notes = Vec::new();
+
+    for entry in entries {
+        for phrase in phrases.iter().filter(|p| p.enabled && !p.phrase.trim().is_empty()) {
+            let Some(end) = find_phrase(&entry.text, phrase.phrase.trim()) else {
+                continue;
+            };
+
+            // "action item: send the deck" -> "send the deck"; phrases without a
+            // trailing colon are part of the sentence, so keep the whole segment
+            let remainder = entry.text[end..].trim_start_matches([' ', ',', ':', '-']).trim();
+            let content = if phrase.phrase.trim_end().ends_with(':') && !remainder.is_empty() {
+                remainder
+            } else {
+                entry.text.trim()
+            };
+
+            notes.push(Note {
+                id: format!("auto-{}-{}", entry.id, phrase.id),
+                meeting_id: entry.meeting_id.clone(),
+                note_type: phrase.note_type.clone(),
+                content: content.to_string(),
+                timestamp: entry.timestamp,
+                source_refs: vec![entry.id.clone()],
+                assignee: None,
+                deadline: None,
+                completed: false,
+                completed_at: None,
+                deferred_until: None,
+                needs_review: true,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            });
+        }
+    }
+
+    notes
+}
+
+/// Case-insensitive search for `phrase` on word boundaries; returns the byte
+/// offset just past the match
+fn find_phrase(text: &str, phrase: &str) -> Option<usize> {
+    // ASCII lowercasing keeps byte offsets aligned with the original text
+    let haystack = text.to_ascii_lowercase();
+    let needle = phrase.to_ascii_lowercase();
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+
+    let mut from = 0;
+    while let Some(pos) = haystack[from..].find(&needle) {
+        let start = from + pos;
+        let end = start + needle.len();
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[end..].chars().next();
+        let bounded_start = !is_word(before) || !is_word(needle.chars().next());
+        let bounded_end = !is_word(after) || !is_word(needle.chars().next_back());
+        if bounded_start && bounded_end {
+            return Some(end);
+        }
+        from = start + needle.chars().next().map_or(1, |c| c.len_utf8());
+    }
+    None
+}
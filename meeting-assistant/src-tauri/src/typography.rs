@@ -0,0 +1,189 @@
+// Typography - Language-aware formatting of transcript text
+//
+// Speech-to-text output comes with straight quotes and English spacing
+// whatever the language, such as "bonjour , ça va?" or spaces between
+// Japanese characters. Each entry's text is run through the rules for its
+// `TranscriptEntry.language` as it is saved (see `beautify`), and exports
+// run it through them again for entries saved before: the language's quotation
+// marks, French spacing before high punctuation, full-width punctuation and
+// no spaces in Chinese and Japanese, Arabic-script commas and question marks,
+// and right-to-left isolation so Arabic or Hebrew lines keep their direction
+// next to left-to-right timestamps and names. Unknown languages get the
+// spacing fixes and English quotes.
+
+use crate::models::TranscriptEntry;
+
+/// U+202F, the space French puts before `? ! ; :` and inside guillemets
+const NARROW_NBSP: char = '\u{202F}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    /// Chinese and Japanese: no spaces between words
+    Cjk,
+    /// Arabic, Persian and Urdu: Arabic-script punctuation, right to left
+    Arabic,
+    /// Hebrew and Yiddish: Latin punctuation, right to left
+    Hebrew,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Typography {
+    script: Script,
+    quotes: (char, char),
+    /// French: a narrow no-break space before `? ! ; :` and inside quotes
+    spaced_punctuation: bool,
+    /// Japanese uses `、` for commas where Chinese uses `，`
+    ideographic_comma: char,
+}
+
+impl Typography {
+    /// Rules for a language tag such as "fr" or "pt-BR"
+    pub fn for_language(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        let script = match language.as_str() {
+            "zh" | "ja" => Script::Cjk,
+            "ar" | "fa" | "ur" | "ps" => Script::Arabic,
+            "he" | "yi" => Script::Hebrew,
+            _ => Script::Latin,
+        };
+        let quotes = match language.as_str() {
+            "fr" | "es" | "it" | "ru" | "uk" | "ar" | "fa" | "ca" | "el" | "no" | "nb" => ('«', '»'),
+            "de" | "cs" | "sk" | "bg" | "lt" | "et" | "is" => ('„', '“'),
+            "pl" | "ro" | "hu" | "nl" | "he" => ('„', '”'),
+            "sv" | "fi" => ('”', '”'),
+            "ja" => ('「', '」'),
+            _ => ('“', '”'),
+        };
+        Typography {
+            script,
+            quotes,
+            spaced_punctuation: language == "fr",
+            ideographic_comma: if language == "ja" { '、' } else { '，' },
+        }
+    }
+
+    pub fn is_rtl(&self) -> bool {
+        matches!(self.script, Script::Arabic | Script::Hebrew)
+    }
+
+    /// The text with the language's spacing, punctuation and quotes
+    pub fn format(&self, text: &str) -> String {
+        let chars: Vec<char> = text.split_whitespace().collect::<Vec<_>>().join(" ").chars().collect();
+        let mut out = String::with_capacity(text.len() + 8);
+        let mut quote_open = false;
+        let mut after_open_quote = false;
+
+        for (i, &c) in chars.iter().enumerate() {
+            let prev = out.chars().next_back();
+            let next = chars.get(i + 1).copied();
+
+            if c == ' ' {
+                let joins_cjk = self.script == Script::Cjk
+                    && prev.is_some_and(is_cjk) && next.is_some_and(is_cjk);
+                // Speech-to-text often separates sentence punctuation from
+                // the word before it. Only punctuation ending a clause
+                // counts, so ".NET" keeps its space, and French keeps a space
+                // before high punctuation, made narrow below.
+                let detached_punctuation = prev.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(|n| match n {
+                        ',' | '.' => true,
+                        '?' | '!' | ';' | ':' => !self.spaced_punctuation,
+                        _ => false,
+                    })
+                    && chars.get(i + 2).map_or(true, |&after| after == ' ' || after == '"');
+                // French guillemets already in the text keep narrow spaces
+                let inside_guillemets = self.spaced_punctuation
+                    && (prev == Some(self.quotes.0) || next == Some(self.quotes.1));
+                if inside_guillemets {
+                    push_narrow_space(&mut out);
+                } else if !joins_cjk && !detached_punctuation && !after_open_quote {
+                    out.push(' ');
+                }
+                continue;
+            }
+            after_open_quote = false;
+
+            if c == '"' {
+                // Straight quotes don't nest, so they alternate
+                if quote_open {
+                    if self.spaced_punctuation {
+                        push_narrow_space(&mut out);
+                    } else {
+                        while out.ends_with(' ') {
+                            out.pop();
+                        }
+                    }
+                    out.push(self.quotes.1);
+                } else {
+                    out.push(self.quotes.0);
+                    if self.spaced_punctuation {
+                        out.push(NARROW_NBSP);
+                    }
+                    after_open_quote = true;
+                }
+                quote_open = !quote_open;
+                continue;
+            }
+
+            let ends_clause = next.map_or(true, |n| n.is_whitespace() || n == '"');
+            match (self.script, c) {
+                (Script::Cjk, ',' | '.' | '?' | '!' | ':' | ';') if prev.is_some_and(is_cjk) && ends_clause => {
+                    out.push(match c {
+                        ',' => self.ideographic_comma,
+                        '.' => '。',
+                        '?' => '？',
+                        '!' => '！',
+                        ':' => '：',
+                        _ => '；',
+                    });
+                }
+                (Script::Arabic, ',') if ends_clause => out.push('،'),
+                (Script::Arabic, '?') => out.push('؟'),
+                (Script::Arabic, ';') => out.push('؛'),
+                (_, '?' | '!' | ';' | ':') if self.spaced_punctuation && ends_clause && prev.is_some() => {
+                    push_narrow_space(&mut out);
+                    out.push(c);
+                }
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// The formatted text, wrapped in a right-to-left isolate for right to
+    /// left languages so it reads correctly inside a left-to-right line
+    pub fn format_isolated(&self, text: &str) -> String {
+        let formatted = self.format(text);
+        if self.is_rtl() {
+            format!("\u{2067}{}\u{2069}", formatted)
+        } else {
+            formatted
+        }
+    }
+}
+
+/// Formats a transcript entry's text for its language before it is saved.
+/// Translations are left alone, as their language isn't recorded.
+pub fn beautify(entry: &mut TranscriptEntry) {
+    entry.text = Typography::for_language(&entry.language).format(&entry.text);
+}
+
+/// Replaces any space at the end of `out` with one narrow no-break space
+fn push_narrow_space(out: &mut String) {
+    while out.ends_with([' ', NARROW_NBSP]) {
+        out.pop();
+    }
+    out.push(NARROW_NBSP);
+}
+
+/// Han, kana and full-width punctuation
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}'   // CJK punctuation
+        | '\u{3040}'..='\u{30FF}' // Hiragana and katakana
+        | '\u{3400}'..='\u{4DBF}' // Han extension A
+        | '\u{4E00}'..='\u{9FFF}' // Han
+        | '\u{FF00}'..='\u{FFEF}' // Full-width forms
+    )
+}
@@ -0,0 +1,94 @@
+// Watchdog - Finalize meetings left Recording/Paused after the app was closed
+// or the user walked away
+
+use std::time::Duration;
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+use tauri::api::notification::Notification;
+
+use crate::commands::AppState;
+use crate::db::Database;
+use crate::locale::Locale;
+use crate::models::{Meeting, MeetingStatus};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const DEFAULT_STALE_HOURS: f64 = 6.0;
+
+/// Runs once at startup and then periodically
+pub fn spawn_stale_meeting_watchdog(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app.state::<AppState>();
+            match close_stale_meetings(&state.db) {
+                Ok(closed) if !closed.is_empty() => notify_closed(&app, &closed, Locale::from_settings(&state.db)),
+                Ok(_) => {}
+                Err(e) => log::warn!("Stale meeting check failed: {}", e),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Completes every meeting with no activity for `stale_meeting_hours`, ending
+/// it at its last activity and marking it `auto_closed`
+pub fn close_stale_meetings(db: &Database) -> Result<Vec<Meeting>, String> {
+    let hours = db.get_setting("stale_meeting_hours").ok().flatten()
+        .and_then(|h| h.parse::<f64>().ok())
+        .filter(|h| *h > 0.0)
+        .unwrap_or(DEFAULT_STALE_HOURS);
+    let cutoff = Utc::now() - chrono::Duration::seconds((hours * 3600.0) as i64);
+
+    let stale = db.find_stale_meetings(cutoff)
+        .map_err(|e| format!("Failed to find stale meetings: {}", e))?;
+
+    let mut closed = Vec::new();
+    for (meeting_id, last_activity) in stale {
+        let Some(meeting) = db.get_meeting(&meeting_id).map_err(|e| e.to_string())? else {
+            continue;
+        };
+        if !matches!(meeting.status, MeetingStatus::Recording | MeetingStatus::Paused) {
+            continue;
+        }
+
+        let finalized = Meeting {
+            end_time: Some(last_activity.max(meeting.start_time)),
+            status: MeetingStatus::Completed,
+            auto_closed: true,
+            updated_at: Utc::now(),
+            ..meeting
+        };
+        db.save_meeting(&finalized)
+            .map_err(|e| format!("Failed to close meeting {}: {}", meeting_id, e))?;
+
+        log::info!("Auto-closed stale meeting: {}", meeting_id);
+        closed.push(finalized);
+    }
+
+    Ok(closed)
+}
+
+fn notify_closed(app: &AppHandle, closed: &[Meeting], locale: Locale) {
+    if let Err(e) = app.emit_all("meetings-auto-closed", closed) {
+        log::warn!("Failed to emit meetings-auto-closed: {}", e);
+    }
+
+    let body = match closed {
+        [meeting] => {
+            let last_activity = meeting.end_time.unwrap_or(meeting.start_time);
+            let idle = Utc::now().signed_duration_since(last_activity).num_seconds();
+            format!(
+                "\"{}\" was still recording with no activity for {} and has been closed",
+                meeting.title,
+                locale.format_duration(idle),
+            )
+        }
+        _ => format!("{} meetings were still recording with no activity and have been closed", closed.len()),
+    };
+    let result = Notification::new(&app.config().tauri.bundle.identifier)
+        .title("Meetings closed")
+        .body(body)
+        .show();
+    if let Err(e) = result {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
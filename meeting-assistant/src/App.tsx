@@ -3,22 +3,36 @@ import { Layout } from './components/Layout';
 import { HomePage } from './pages/HomePage';
 import { MeetingPage } from './pages/MeetingPage';
 import { HistoryPage } from './pages/HistoryPage';
+import { MeetingDetailPage } from './pages/MeetingDetailPage';
+import { InboxPage } from './pages/InboxPage';
 import { SettingsPage } from './pages/SettingsPage';
 import { IntegrationsPage } from './pages/IntegrationsPage';
+import { QuickRecordPage } from './pages/QuickRecordPage';
 
 function App() {
   return (
     <Router>
-      <Layout>
-        <Routes>
-          <Route path="/" element={<HomePage />} />
-          <Route path="/meeting" element={<MeetingPage />} />
-          <Route path="/meeting/:id" element={<MeetingPage />} />
-          <Route path="/history" element={<HistoryPage />} />
-          <Route path="/settings" element={<SettingsPage />} />
-          <Route path="/integrations" element={<IntegrationsPage />} />
-        </Routes>
-      </Layout>
+      <Routes>
+        {/* Its own window in quick-record mode, without the sidebar */}
+        <Route path="/quick-record" element={<QuickRecordPage />} />
+        <Route
+          path="*"
+          element={
+            <Layout>
+              <Routes>
+                <Route path="/" element={<HomePage />} />
+                <Route path="/meeting" element={<MeetingPage />} />
+                <Route path="/meeting/:id" element={<MeetingPage />} />
+                <Route path="/history" element={<HistoryPage />} />
+                <Route path="/history/:id" element={<MeetingDetailPage />} />
+                <Route path="/inbox" element={<InboxPage />} />
+                <Route path="/settings" element={<SettingsPage />} />
+                <Route path="/integrations" element={<IntegrationsPage />} />
+              </Routes>
+            </Layout>
+          }
+        />
+      </Routes>
     </Router>
   );
 }
@@ -8,6 +8,7 @@ const pageTitles: Record<string, string> = {
   '/': 'Dashboard',
   '/meeting': 'Active Meeting',
   '/history': 'Meeting History',
+  '/inbox': 'Inbox',
   '/settings': 'Settings',
 };
 
@@ -1,6 +1,7 @@
 import { ReactNode } from 'react';
 import { Sidebar } from './Sidebar';
 import { Header } from './Header';
+import { SpeakerCountPrompt } from './SpeakerCountPrompt';
 import './Layout.css';
 
 interface LayoutProps {
@@ -13,6 +14,7 @@ export function Layout({ children }: LayoutProps) {
       <Sidebar />
       <div className="layout-main">
         <Header />
+        <SpeakerCountPrompt />
         <main className="layout-content">
           {children}
         </main>
@@ -3,6 +3,7 @@ import {
   Home, 
   Mic, 
   Clock, 
+  Inbox,
   Settings, 
   MessageSquare,
   ChevronLeft,
@@ -16,6 +17,7 @@ const navItems = [
   { path: '/', icon: Home, label: 'Home' },
   { path: '/meeting', icon: Mic, label: 'Meeting' },
   { path: '/history', icon: Clock, label: 'History' },
+  { path: '/inbox', icon: Inbox, label: 'Inbox' },
   { path: '/integrations', icon: Zap, label: 'Integrations' },
   { path: '/settings', icon: Settings, label: 'Settings' },
 ];
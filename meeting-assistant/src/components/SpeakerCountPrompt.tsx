@@ -0,0 +1,63 @@
+import { useState, useEffect } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { listen } from '@tauri-apps/api/event';
+import { Users, X } from 'lucide-react';
+import { databaseService } from '@/services';
+import type { SpeakerCountCheck } from '@/services/database';
+import './SpeakerCountPrompt.css';
+
+// Offers to re-cluster a finished meeting's speakers when the backend finds
+// far more or fewer voices than the meeting had participants
+export function SpeakerCountPrompt() {
+  const navigate = useNavigate();
+  const [check, setCheck] = useState<SpeakerCountCheck | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    const unlisten = listen<SpeakerCountCheck>('speaker-count-mismatch', (event) => {
+      setCheck(event.payload);
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  if (!check) return null;
+
+  const recluster = async () => {
+    setBusy(true);
+    try {
+      await databaseService.reclusterSpeakers(check.meeting_id, check.expected);
+      navigate(`/history/${check.meeting_id}`);
+      setCheck(null);
+    } catch (e) {
+      console.error('Failed to re-cluster speakers:', e);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="speaker-count-prompt">
+      <Users className="prompt-icon" />
+      <span>
+        The last meeting has {check.detected} speakers but {check.expected} participants.
+      </span>
+      <button className="prompt-button" onClick={recluster} disabled={busy}>
+        Re-cluster into {check.expected}
+      </button>
+      <button
+        className="prompt-button"
+        onClick={() => {
+          navigate(`/history/${check.meeting_id}`);
+          setCheck(null);
+        }}
+      >
+        Review
+      </button>
+      <button className="prompt-close" onClick={() => setCheck(null)} title="Dismiss">
+        <X />
+      </button>
+    </div>
+  );
+}
@@ -5,3 +5,4 @@ export { Header } from './Header';
 export { VolumeMeter, Waveform } from './VolumeMeter';
 export { AudioDeviceSelector } from './AudioDeviceSelector';
 export { RecordingControls } from './RecordingControls';
+export { SpeakerCountPrompt } from './SpeakerCountPrompt';
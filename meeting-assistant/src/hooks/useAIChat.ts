@@ -2,8 +2,8 @@
 // Provides React integration for AI chat service
 
 import { useState, useCallback, useEffect } from 'react';
-import { aiChatService } from '@/services';
-import { useAIChatStore, useTranscriptStore } from '@/store';
+import { aiChatService, databaseService } from '@/services';
+import { useAIChatStore, useMeetingStore, useTranscriptStore } from '@/store';
 
 interface UseAIChatReturn {
     isConfigured: boolean;
@@ -19,6 +19,7 @@ interface UseAIChatReturn {
 export function useAIChat(): UseAIChatReturn {
     const { addMessage, setIsLoading, isLoading, clearMessages } = useAIChatStore();
     const { entries } = useTranscriptStore();
+    const meetingId = useMeetingStore(state => state.currentMeeting?.id);
     const [error, setError] = useState<string | null>(null);
     const [isConfigured, setIsConfigured] = useState(false);
 
@@ -76,6 +77,17 @@ export function useAIChat(): UseAIChatReturn {
         }
     }, [entries]);
 
+    // Include email threads attached to the meeting
+    useEffect(() => {
+        if (!meetingId) {
+            aiChatService.updateAttachmentContext('');
+            return;
+        }
+        databaseService.getMeetingContext(meetingId)
+            .then(context => aiChatService.updateAttachmentContext(context))
+            .catch(e => console.error('Failed to load meeting attachments:', e));
+    }, [meetingId]);
+
     // Send a message to the AI
     const sendMessage = useCallback(async (message: string) => {
         if (!aiChatService.isConfigured()) {
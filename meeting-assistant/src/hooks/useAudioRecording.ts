@@ -85,10 +85,10 @@ export function useAudioRecording(): UseAudioRecordingReturn {
             if (success) {
                 setState(prev => ({ ...prev, isRecording: true, isPaused: false, duration: 0 }));
 
-                // Start duration ticker
+                // Start duration ticker; also picks up automatic pauses
                 durationInterval.current = window.setInterval(() => {
                     const currentState = audioCapture.getState();
-                    setState(prev => ({ ...prev, duration: currentState.duration }));
+                    setState(prev => ({ ...prev, duration: currentState.duration, isPaused: currentState.isPaused }));
                 }, 100);
 
                 return true;
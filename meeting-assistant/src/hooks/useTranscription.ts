@@ -7,7 +7,8 @@ import {
     aiChatService, 
     speakerDiarizationService,
     noteGenerationService,
-    audioCapture
+    audioCapture,
+    meetingSession
 } from '@/services';
 import type { TranscriptionResult } from '@/services';
 import { useTranscriptStore } from '@/store';
@@ -102,6 +103,7 @@ export function useTranscription(): UseTranscriptionReturn {
             };
 
             addEntry(entry);
+            meetingSession.saveEntry(entry);
             noteGenerationService.addTranscriptEntry(entry);
 
             // Update transcript context for AI
@@ -143,9 +145,12 @@ export function useTranscription(): UseTranscriptionReturn {
             speakerId: speakerInfo.speakerId,
             speakerName: speakerInfo.speakerName,
             confidence: speakerInfo.confidence,
+            voicePitch: audioFeatures?.pitch,
+            voiceEnergy: audioFeatures?.energy,
         };
 
         addEntry(entry);
+        meetingSession.saveEntry(entry);
 
         // Add to note generation service
         noteGenerationService.addTranscriptEntry(entry);
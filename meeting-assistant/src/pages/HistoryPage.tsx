@@ -1,49 +1,41 @@
-import { useState } from 'react';
-import { Search, Calendar, Clock, Users, Download, Trash2 } from 'lucide-react';
-import { useMeetingStore } from '@/store';
+import { useState, useEffect } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { Search, Calendar, Clock, Users, Download, Trash2, Sparkles } from 'lucide-react';
+import { databaseService, aiChatService } from '@/services';
+import type { Meeting } from '@/services';
 import { formatDate, formatDuration, formatRelativeTime } from '@/utils/formatters';
 import './HistoryPage.css';
 
 export function HistoryPage() {
-  const { meetings } = useMeetingStore();
+  const [meetings, setMeetings] = useState<Meeting[]>([]);
   const [searchQuery, setSearchQuery] = useState('');
+  const [regenerating, setRegenerating] = useState(false);
+  const [regenerateStatus, setRegenerateStatus] = useState<string | null>(null);
 
-  const filteredMeetings = meetings.filter(meeting =>
-    meeting.title.toLowerCase().includes(searchQuery.toLowerCase())
-  );
+  useEffect(() => {
+    databaseService.getMeetings()
+      .then(setMeetings)
+      .catch(e => console.error('Failed to load meetings:', e));
+  }, []);
 
-  // Demo meetings for UI preview
-  const demoMeetings = [
-    {
-      id: '1',
-      title: 'Product Strategy Meeting',
-      startTime: new Date(Date.now() - 2 * 60 * 60 * 1000),
-      endTime: new Date(Date.now() - 1 * 60 * 60 * 1000),
-      participants: [{ id: '1', name: 'John', color: '#6366f1' }, { id: '2', name: 'Sarah', color: '#10b981' }],
-      duration: 3600000,
-      noteCount: 12,
-    },
-    {
-      id: '2',
-      title: 'Weekly Standup',
-      startTime: new Date(Date.now() - 24 * 60 * 60 * 1000),
-      endTime: new Date(Date.now() - 23.5 * 60 * 60 * 1000),
-      participants: [{ id: '1', name: 'Team', color: '#f59e0b' }],
-      duration: 1800000,
-      noteCount: 5,
-    },
-    {
-      id: '3',
-      title: 'Client Presentation - Q4 Review',
-      startTime: new Date(Date.now() - 48 * 60 * 60 * 1000),
-      endTime: new Date(Date.now() - 47 * 60 * 60 * 1000),
-      participants: [{ id: '1', name: 'Alex', color: '#22d3ee' }],
-      duration: 5400000,
-      noteCount: 18,
-    },
-  ];
+  // Re-summarize meetings whose summary predates the current prompt or model
+  const regenerateSummaries = async () => {
+    setRegenerating(true);
+    setRegenerateStatus(null);
+    try {
+      const result = await databaseService.regenerateSummaries(undefined, aiChatService.primaryProvider());
+      const failed = result.failed.length > 0 ? `, ${result.failed.length} failed` : '';
+      setRegenerateStatus(`${result.regenerated.length} summaries updated${failed}`);
+    } catch (e) {
+      setRegenerateStatus(String(e));
+    } finally {
+      setRegenerating(false);
+    }
+  };
 
-  const displayMeetings = meetings.length > 0 ? filteredMeetings : demoMeetings;
+  const displayMeetings = meetings.filter(meeting =>
+    meeting.title.toLowerCase().includes(searchQuery.toLowerCase())
+  );
 
   return (
     <div className="history-page animate-fade-in">
@@ -59,6 +51,16 @@ export function HistoryPage() {
         </div>
         
         <div className="header-actions">
+          {regenerateStatus && <span className="header-status">{regenerateStatus}</span>}
+          <button
+            className="filter-select"
+            onClick={regenerateSummaries}
+            disabled={regenerating}
+            title="Re-summarize meetings summarized with an older prompt or model"
+          >
+            <Sparkles className="header-action-icon" />
+            {regenerating ? 'Updating...' : 'Update summaries'}
+          </button>
           <select className="filter-select">
             <option value="all">All Meetings</option>
             <option value="week">This Week</option>
@@ -75,7 +77,7 @@ export function HistoryPage() {
         </div>
       ) : (
         <div className="meetings-list">
-          {displayMeetings.map((meeting: any) => (
+          {displayMeetings.map(meeting => (
             <MeetingCard key={meeting.id} meeting={meeting} />
           ))}
         </div>
@@ -84,20 +86,22 @@ export function HistoryPage() {
   );
 }
 
-function MeetingCard({ meeting }: { meeting: any }) {
-  const duration = meeting.endTime 
-    ? meeting.endTime.getTime() - meeting.startTime.getTime()
-    : meeting.duration || 0;
+function MeetingCard({ meeting }: { meeting: Meeting }) {
+  const navigate = useNavigate();
+  const startTime = new Date(meeting.start_time);
+  const duration = meeting.end_time
+    ? new Date(meeting.end_time).getTime() - startTime.getTime()
+    : 0;
 
   return (
     <div className="meeting-card">
-      <div className="card-main">
+      <div className="card-main" onClick={() => navigate(`/history/${meeting.id}`)}>
         <h3 className="card-title">{meeting.title}</h3>
         
         <div className="card-meta">
           <span className="meta-item">
             <Calendar className="meta-icon" />
-            {formatDate(meeting.startTime)}
+            {formatDate(startTime)}
           </span>
           <span className="meta-item">
             <Clock className="meta-icon" />
@@ -105,14 +109,12 @@ function MeetingCard({ meeting }: { meeting: any }) {
           </span>
           <span className="meta-item">
             <Users className="meta-icon" />
-            {meeting.participants?.length || 0} participants
+            {meeting.participants.length} participants
           </span>
         </div>
         
         <div className="card-stats">
-          <span className="stat">{meeting.noteCount || 0} notes</span>
-          <span className="stat-dot">•</span>
-          <span className="stat">{formatRelativeTime(meeting.startTime)}</span>
+          <span className="stat">{formatRelativeTime(startTime)}</span>
         </div>
       </div>
       
@@ -0,0 +1,128 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { Inbox, Check, Clock } from 'lucide-react';
+import { databaseService } from '@/services';
+import type { InboxItem } from '@/services/database';
+import { formatDate } from '@/utils/formatters';
+import './InboxPage.css';
+
+// Open action items assigned to you (the `my_name` setting) across all
+// meetings, minus the ones deferred to later
+export function InboxPage() {
+  const navigate = useNavigate();
+  const [items, setItems] = useState<InboxItem[]>([]);
+  const [myName, setMyName] = useState('');
+  const [nameDraft, setNameDraft] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      const settings = await databaseService.getSettings();
+      const name = settings.my_name?.trim() ?? '';
+      setMyName(name);
+      setNameDraft(name);
+      setItems(name ? await databaseService.getInbox() : []);
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, []);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const saveName = async () => {
+    try {
+      await databaseService.setSetting('my_name', nameDraft.trim());
+      await load();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const complete = async (noteId: string) => {
+    try {
+      await databaseService.completeActionItem(noteId);
+      await load();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const defer = async (noteId: string, days: number) => {
+    const until = new Date();
+    until.setDate(until.getDate() + days);
+    until.setHours(9, 0, 0, 0);
+    try {
+      await databaseService.deferActionItem(noteId, until);
+      await load();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="inbox-page animate-fade-in">
+      <div className="inbox-name">
+        <label className="inbox-name-label" htmlFor="inbox-my-name">
+          Action items assigned to
+        </label>
+        <input
+          id="inbox-my-name"
+          className="inbox-name-input"
+          value={nameDraft}
+          placeholder="Your name as it appears in meetings"
+          onChange={e => setNameDraft(e.target.value)}
+          onKeyDown={e => e.key === 'Enter' && saveName()}
+        />
+        {nameDraft.trim() !== myName && (
+          <button className="inbox-button" onClick={saveName}>Save</button>
+        )}
+      </div>
+
+      {error && <p className="inbox-error">{error}</p>}
+
+      {!myName ? (
+        <div className="empty-state">
+          <Inbox className="empty-icon" />
+          <h3>Who are you?</h3>
+          <p>Enter your name to collect the action items assigned to you</p>
+        </div>
+      ) : items.length === 0 ? (
+        <div className="empty-state">
+          <Inbox className="empty-icon" />
+          <h3>Inbox zero</h3>
+          <p>No open action items are assigned to you</p>
+        </div>
+      ) : (
+        <div className="inbox-list">
+          {items.map(({ note, meeting_title, meeting_start }) => (
+            <div key={note.id} className="inbox-item">
+              <div className="inbox-item-main">
+                <p className="inbox-item-content">{note.content}</p>
+                <div className="inbox-item-meta">
+                  <button className="inbox-meeting-link" onClick={() => navigate(`/history/${note.meeting_id}`)}>
+                    {meeting_title || 'Untitled meeting'} · {formatDate(new Date(meeting_start))}
+                  </button>
+                  {note.deadline && <span>Due {formatDate(new Date(note.deadline))}</span>}
+                </div>
+              </div>
+              <div className="inbox-item-actions">
+                <button className="inbox-button" onClick={() => defer(note.id, 1)} title="Hide until tomorrow morning">
+                  <Clock /> Tomorrow
+                </button>
+                <button className="inbox-button" onClick={() => defer(note.id, 7)} title="Hide until next week">
+                  <Clock /> Next week
+                </button>
+                <button className="inbox-button done" onClick={() => complete(note.id)}>
+                  <Check /> Done
+                </button>
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
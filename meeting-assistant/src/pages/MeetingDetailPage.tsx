@@ -0,0 +1,330 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useNavigate, useParams } from 'react-router-dom';
+import { ArrowLeft, Calendar, Clock, Users, StickyNote, Sparkles } from 'lucide-react';
+import { databaseService, aiChatService } from '@/services';
+import type { Meeting, TranscriptEntry } from '@/services';
+import type { Annotation, SummaryVersion } from '@/services/database';
+import { formatDate, formatDuration, formatTimestamp } from '@/utils/formatters';
+import './MeetingDetailPage.css';
+
+export function MeetingDetailPage() {
+  const { id } = useParams();
+  const navigate = useNavigate();
+  const [meeting, setMeeting] = useState<Meeting | null>(null);
+  const [entries, setEntries] = useState<TranscriptEntry[]>([]);
+  const [annotations, setAnnotations] = useState<Annotation[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    if (!id) return;
+    try {
+      const [loaded, transcript, remarks] = await Promise.all([
+        databaseService.getMeeting(id),
+        databaseService.getTranscript(id),
+        databaseService.getAnnotations(id),
+      ]);
+      setMeeting(loaded);
+      setEntries(transcript);
+      setAnnotations(remarks);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [id]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  if (error) {
+    return (
+      <div className="meeting-detail-page">
+        <button className="back-button" onClick={() => navigate('/history')}>
+          <ArrowLeft /> History
+        </button>
+        <p className="detail-error">{error}</p>
+      </div>
+    );
+  }
+
+  if (!meeting) return null;
+
+  const startTime = new Date(meeting.start_time);
+  const duration = meeting.end_time ? new Date(meeting.end_time).getTime() - startTime.getTime() : 0;
+
+  return (
+    <div className="meeting-detail-page animate-fade-in">
+      <button className="back-button" onClick={() => navigate('/history')}>
+        <ArrowLeft /> History
+      </button>
+
+      <div className="detail-header">
+        <h1>{meeting.title}</h1>
+        <div className="card-meta">
+          <span className="meta-item">
+            <Calendar className="meta-icon" />
+            {formatDate(startTime)}
+          </span>
+          <span className="meta-item">
+            <Clock className="meta-icon" />
+            {formatDuration(duration)}
+          </span>
+          <span className="meta-item">
+            <Users className="meta-icon" />
+            {new Set(entries.map(entry => entry.speaker_id)).size} speakers
+          </span>
+        </div>
+      </div>
+
+      <SummarySection meetingId={meeting.id} hasTranscript={entries.length > 0} />
+
+      <SpeakersSection meetingId={meeting.id} entries={entries} onChanged={load} />
+
+      <section className="detail-section">
+        <h2>Transcript</h2>
+        {entries.length === 0 ? (
+          <p className="detail-empty">No transcript was saved for this meeting</p>
+        ) : (
+          <div className="detail-transcript">
+            {entries.map(entry => (
+              <div key={entry.id} className="detail-entry">
+                <div className="entry-header">
+                  <span className="entry-speaker">{entry.speaker_name}</span>
+                  <span className="entry-time">{formatTimestamp(entry.timestamp)}</span>
+                </div>
+                <p className="entry-text">{entry.text}</p>
+                <EntryAnnotations
+                  meetingId={meeting.id}
+                  entryId={entry.id}
+                  annotations={annotations.filter(a => a.transcript_entry_id === entry.id)}
+                  onChanged={load}
+                />
+              </div>
+            ))}
+          </div>
+        )}
+      </section>
+    </div>
+  );
+}
+
+// The meeting's summary, with the earlier versions kept each time it was
+// regenerated
+function SummarySection({ meetingId, hasTranscript }: { meetingId: string; hasTranscript: boolean }) {
+  const [versions, setVersions] = useState<SummaryVersion[]>([]);
+  const [shown, setShown] = useState(0);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    databaseService.getSummaryVersions(meetingId)
+      .then(setVersions)
+      .catch(e => console.error('Failed to load summaries:', e));
+  }, [meetingId]);
+
+  const generate = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      const summary = await databaseService.generateSummary(meetingId, aiChatService.primaryProvider());
+      setVersions(prev => [summary, ...prev]);
+      setShown(0);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const summary = versions[shown];
+
+  return (
+    <section className="detail-section">
+      <div className="detail-row section-title-row">
+        <h2>Summary</h2>
+        {versions.length > 1 && (
+          <select
+            className="detail-select"
+            value={shown}
+            onChange={e => setShown(Number(e.target.value))}
+          >
+            {versions.map((version, i) => (
+              <option key={version.id} value={i}>
+                v{version.version} · {version.model} · {new Date(version.created_at).toLocaleDateString()}
+              </option>
+            ))}
+          </select>
+        )}
+        {hasTranscript && (
+          <button className="detail-button" onClick={generate} disabled={busy}>
+            <Sparkles className="button-icon" />
+            {busy ? 'Summarizing...' : versions.length > 0 ? 'Regenerate' : 'Summarize'}
+          </button>
+        )}
+      </div>
+      {error && <p className="detail-error">{error}</p>}
+      {summary ? (
+        <p className="detail-summary">{summary.content}</p>
+      ) : (
+        <p className="detail-empty">Not summarized yet</p>
+      )}
+    </section>
+  );
+}
+
+// Re-clustering the transcript's speakers, for when live diarization split
+// or merged voices
+function SpeakersSection({
+  meetingId,
+  entries,
+  onChanged,
+}: {
+  meetingId: string;
+  entries: TranscriptEntry[];
+  onChanged: () => void;
+}) {
+  const detected = new Set(entries.map(entry => entry.speaker_id)).size;
+  const withVoice = entries.filter(entry => entry.voice_pitch != null && entry.voice_energy != null).length;
+  const [speakers, setSpeakers] = useState(Math.max(detected, 1));
+  const [status, setStatus] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    setSpeakers(Math.max(detected, 1));
+  }, [detected]);
+
+  const recluster = async () => {
+    setBusy(true);
+    setStatus(null);
+    try {
+      const result = await databaseService.reclusterSpeakers(meetingId, speakers);
+      setStatus(`Reassigned ${result.entries_changed} entries to ${result.speakers} speakers`);
+      onChanged();
+    } catch (e) {
+      setStatus(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  if (withVoice === 0) return null;
+
+  return (
+    <section className="detail-section">
+      <h2>Speakers</h2>
+      <p className="detail-hint">
+        Wrong number of speakers? Regroup the transcript by voice into a given number.
+      </p>
+      <div className="detail-row">
+        <input
+          type="number"
+          min={1}
+          max={20}
+          value={speakers}
+          onChange={e => setSpeakers(Number(e.target.value))}
+          className="detail-input"
+        />
+        <button className="detail-button" onClick={recluster} disabled={busy || speakers < 1}>
+          Re-cluster speakers
+        </button>
+        {status && <span className="detail-hint">{status}</span>}
+      </div>
+    </section>
+  );
+}
+
+// Private remarks on one transcript entry. They stay out of exports,
+// shares and integrations unless those explicitly ask for annotations.
+function EntryAnnotations({
+  meetingId,
+  entryId,
+  annotations,
+  onChanged,
+}: {
+  meetingId: string;
+  entryId: string;
+  annotations: Annotation[];
+  onChanged: () => void;
+}) {
+  // The annotation being edited, or 'new' while adding one
+  const [editing, setEditing] = useState<string | null>(null);
+  const [draft, setDraft] = useState('');
+
+  const save = async () => {
+    const content = draft.trim();
+    try {
+      if (editing === 'new') {
+        if (content) await databaseService.addAnnotation(meetingId, entryId, content);
+      } else if (editing) {
+        if (content) {
+          await databaseService.updateAnnotation(editing, content);
+        } else {
+          await databaseService.deleteAnnotation(editing);
+        }
+      }
+      setEditing(null);
+      onChanged();
+    } catch (e) {
+      console.error('Failed to save annotation:', e);
+    }
+  };
+
+  const remove = async (annotationId: string) => {
+    try {
+      await databaseService.deleteAnnotation(annotationId);
+      onChanged();
+    } catch (e) {
+      console.error('Failed to delete annotation:', e);
+    }
+  };
+
+  const startEditing = (annotationId: string, content: string) => {
+    setEditing(annotationId);
+    setDraft(content);
+  };
+
+  const editor = (
+    <div className="detail-row">
+      <input
+        className="annotation-input"
+        value={draft}
+        autoFocus
+        placeholder="Private annotation"
+        onChange={e => setDraft(e.target.value)}
+        onKeyDown={e => {
+          if (e.key === 'Enter') save();
+          if (e.key === 'Escape') setEditing(null);
+        }}
+      />
+      <button className="detail-button" onClick={save}>Save</button>
+      <button className="detail-button" onClick={() => setEditing(null)}>Cancel</button>
+    </div>
+  );
+
+  return (
+    <div className="entry-annotations">
+      {annotations.map(annotation =>
+        editing === annotation.id ? (
+          <div key={annotation.id}>{editor}</div>
+        ) : (
+          <div key={annotation.id} className="annotation">
+            <StickyNote className="annotation-icon" />
+            <span className="annotation-content">{annotation.content}</span>
+            <button className="annotation-action" onClick={() => startEditing(annotation.id, annotation.content)}>
+              Edit
+            </button>
+            <button className="annotation-action" onClick={() => remove(annotation.id)}>
+              Delete
+            </button>
+          </div>
+        )
+      )}
+      {editing === 'new' ? (
+        editor
+      ) : (
+        <button className="annotation-action" onClick={() => startEditing('new', '')}>
+          + Annotate
+        </button>
+      )}
+    </div>
+  );
+}
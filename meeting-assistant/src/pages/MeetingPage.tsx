@@ -16,7 +16,7 @@ import {
   useAIChatStore,
 } from '@/store';
 import { useAudioRecording, useTranscription, useAIChat, useNoteGeneration } from '@/hooks';
-import { audioCapture, transcriptionService, speakerDiarizationService } from '@/services';
+import { audioCapture, transcriptionService, speakerDiarizationService, databaseService, meetingSession } from '@/services';
 import { RecordingControls } from '@/components/RecordingControls';
 import { AudioDeviceSelector } from '@/components/AudioDeviceSelector';
 import { formatTimestamp } from '@/utils/formatters';
@@ -24,7 +24,7 @@ import './MeetingPage.css';
 
 export function MeetingPage() {
   const navigate = useNavigate();
-  const { startMeeting, pauseMeeting, resumeMeeting, endMeeting } = useMeetingStore();
+  const { currentMeeting, startMeeting, pauseMeeting, resumeMeeting, endMeeting } = useMeetingStore();
   const { entries } = useTranscriptStore();
   const { notes } = useNotesStore();
   const { messages, isLoading, addMessage } = useAIChatStore();
@@ -78,6 +78,17 @@ export function MeetingPage() {
     return unsubscribe;
   }, [isTranscriptionConfigured]);
 
+  // Mirror pauses and resumes the backend makes on silence
+  useEffect(() => {
+    return meetingSession.onStatusChange((status) => {
+      if (status === 'paused') {
+        pauseMeeting();
+      } else {
+        resumeMeeting();
+      }
+    });
+  }, [pauseMeeting, resumeMeeting]);
+
   // Update speakers list periodically
   useEffect(() => {
     if (!audioState.isRecording) return;
@@ -92,34 +103,56 @@ export function MeetingPage() {
 
   // Handle start recording
   const handleStartRecording = useCallback(async () => {
+    let meeting;
+    try {
+      meeting = await databaseService.startMeeting('');
+    } catch (err) {
+      console.error('Failed to start meeting:', err);
+      return;
+    }
+
     const success = await startRecording();
-    if (success) {
-      startMeeting('');
-      // Start transcription if configured
-      if (isTranscriptionConfigured) {
-        startTranscription();
-      }
+    if (!success) {
+      // Nothing is capturing, so don't leave the meeting recording
+      await databaseService.endMeeting(meeting.id).catch(console.error);
+      return;
+    }
+
+    startMeeting(meeting.title, meeting.id);
+    meetingSession.begin(meeting.id);
+    // Start transcription if configured
+    if (isTranscriptionConfigured) {
+      startTranscription();
     }
   }, [startRecording, startMeeting, isTranscriptionConfigured, startTranscription]);
 
   // Handle pause/resume
-  const handlePauseResume = useCallback(() => {
+  const handlePauseResume = useCallback(async () => {
+    const meetingId = currentMeeting?.id;
     if (audioState.isPaused) {
       resumeRecording();
       resumeMeeting();
+      if (meetingId) await databaseService.resumeMeeting(meetingId).catch(console.error);
     } else {
       pauseRecording();
       pauseMeeting();
+      if (meetingId) await databaseService.pauseMeeting(meetingId).catch(console.error);
     }
-  }, [audioState.isPaused, pauseRecording, resumeRecording, pauseMeeting, resumeMeeting]);
+  }, [currentMeeting, audioState.isPaused, pauseRecording, resumeRecording, pauseMeeting, resumeMeeting]);
 
   // Handle stop recording
-  const handleStopRecording = useCallback(async () => {
+  // `alreadyEnded` when the meeting was stopped through the local API
+  const handleStopRecording = useCallback(async (alreadyEnded = false) => {
+    const meetingId = currentMeeting?.id;
     stopTranscription();
+    meetingSession.end();
     await stopRecording();
+    if (meetingId && !alreadyEnded) await databaseService.endMeeting(meetingId).catch(console.error);
     endMeeting();
     navigate('/history');
-  }, [stopRecording, stopTranscription, endMeeting, navigate]);
+  }, [currentMeeting, stopRecording, stopTranscription, endMeeting, navigate]);
+
+  useEffect(() => meetingSession.onRemoteStop(() => handleStopRecording(true)), [handleStopRecording]);
 
   const handleAskQuestion = async () => {
     if (!aiQuestion.trim()) return;
@@ -304,7 +337,7 @@ export function MeetingPage() {
           onStart={handleStartRecording}
           onPause={handlePauseResume}
           onResume={handlePauseResume}
-          onStop={handleStopRecording}
+          onStop={() => handleStopRecording()}
           onSettings={() => setShowAudioSettings(true)}
         />
       </div>
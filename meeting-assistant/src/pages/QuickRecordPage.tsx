@@ -0,0 +1,69 @@
+// Quick Record Page
+// Minimal recording indicator shown in its own window by quick-record mode
+
+import { useState, useEffect, useCallback, useRef } from 'react';
+import { useSearchParams } from 'react-router-dom';
+import { Square, Maximize2 } from 'lucide-react';
+import { appWindow, WebviewWindow } from '@tauri-apps/api/window';
+import { useAudioRecording } from '@/hooks';
+import { audioCapture, databaseService, meetingSession } from '@/services';
+import './QuickRecordPage.css';
+
+export function QuickRecordPage() {
+  const [searchParams] = useSearchParams();
+  const meetingId = searchParams.get('meeting');
+  const { state, error, startRecording, formattedDuration } = useAudioRecording();
+  const [stopping, setStopping] = useState(false);
+  const started = useRef(false);
+
+  // Capture starts as soon as the window opens; the meeting already exists
+  useEffect(() => {
+    if (started.current) return;
+    started.current = true;
+    startRecording().then((success) => {
+      if (success && meetingId) meetingSession.begin(meetingId);
+    });
+  }, [startRecording, meetingId]);
+
+  // `alreadyEnded` when the meeting was stopped through the local API
+  const handleStop = useCallback(async (alreadyEnded = false) => {
+    if (!meetingId || stopping) return;
+    setStopping(true);
+    meetingSession.end();
+    try {
+      await audioCapture.saveRecording(meetingId);
+      if (!alreadyEnded) await databaseService.endMeeting(meetingId);
+    } catch (err) {
+      console.error('Failed to stop quick recording:', err);
+    }
+    await appWindow.close();
+  }, [meetingId, stopping]);
+
+  useEffect(() => meetingSession.onRemoteStop(() => handleStop(true)), [handleStop]);
+
+  const handleOpenMain = useCallback(async () => {
+    const main = WebviewWindow.getByLabel('main');
+    await main?.show();
+    await main?.setFocus();
+  }, []);
+
+  return (
+    <div className="quick-record" data-tauri-drag-region>
+      <span className={`status-dot ${state.isRecording && !state.isPaused ? 'recording' : 'paused'}`} />
+      <span className="quick-record-duration" data-tauri-drag-region>
+        {error ?? (stopping ? 'Saving…' : formattedDuration)}
+      </span>
+      <button className="quick-record-btn" onClick={handleOpenMain} title="Open Meeting Assistant">
+        <Maximize2 size={16} />
+      </button>
+      <button
+        className="quick-record-btn stop"
+        onClick={() => handleStop()}
+        disabled={stopping}
+        title="Stop recording"
+      >
+        <Square size={16} />
+      </button>
+    </div>
+  );
+}
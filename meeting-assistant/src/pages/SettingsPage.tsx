@@ -8,9 +8,13 @@ import {
   Check,
   AlertCircle,
   Mic,
-  MessageSquare
+  MessageSquare,
+  Database,
+  BellOff,
+  FileJson
 } from 'lucide-react';
-import { transcriptionService, aiChatService } from '@/services';
+import { transcriptionService, aiChatService, databaseService } from '@/services';
+import type { WorkspaceReport } from '@/services/database';
 import './SettingsPage.css';
 
 interface APIKeys {
@@ -84,6 +88,29 @@ export function SettingsPage() {
     ai?: boolean;
   }>({});
 
+  const [workspaceReport, setWorkspaceReport] = useState<WorkspaceReport | null>(null);
+  const [checkingWorkspace, setCheckingWorkspace] = useState(false);
+
+  const [profileStatus, setProfileStatus] = useState<string | null>(null);
+
+  // Settings kept by the backend, which are saved as soon as they change
+  const [backendSettings, setBackendSettings] = useState<Record<string, string>>({});
+
+  useEffect(() => {
+    databaseService.getSettings()
+      .then(setBackendSettings)
+      .catch(e => console.error('Failed to load settings:', e));
+  }, []);
+
+  const updateBackendSetting = async (key: string, value: string) => {
+    try {
+      await databaseService.setSetting(key, value);
+      setBackendSettings(prev => ({ ...prev, [key]: value }));
+    } catch (e) {
+      console.error(`Failed to save ${key}:`, e);
+    }
+  };
+
   // Load settings from localStorage on mount
   useEffect(() => {
     const savedSettings = localStorage.getItem('meeting-assistant-settings');
@@ -194,6 +221,69 @@ export function SettingsPage() {
     }
   };
 
+  // Check the database for orphaned rows and other drift, optionally fixing it
+  const checkWorkspace = async (repair: boolean) => {
+    setCheckingWorkspace(true);
+    try {
+      setWorkspaceReport(await databaseService.checkWorkspace(repair));
+    } catch (e) {
+      console.error('Failed to check workspace:', e);
+    } finally {
+      setCheckingWorkspace(false);
+    }
+  };
+
+  const workspaceFindings = workspaceReport
+    ? [
+        ...workspaceReport.orphaned_rows.map(
+          orphans => `${orphans.count} ${orphans.table.replace(/_/g, ' ')} of deleted meetings`
+        ),
+        ...(workspaceReport.orphaned_annotations > 0
+          ? [`${workspaceReport.orphaned_annotations} annotations on deleted transcript entries`]
+          : []),
+        ...workspaceReport.missing_audio.map(audio => `Missing audio file ${audio.audio_path}`),
+        ...(workspaceReport.unindexed_attachments + workspaceReport.stale_index_rows > 0
+          ? ['Attachment search index out of date']
+          : []),
+      ]
+    : [];
+
+  // Export the setup to a JSON file; the saved settings go along without keys
+  const exportProfile = async () => {
+    setProfileStatus(null);
+    try {
+      const saved = localStorage.getItem('meeting-assistant-settings');
+      const path = await databaseService.exportProfile(saved ? JSON.parse(saved) : undefined);
+      if (path) setProfileStatus(`Exported to ${path}`);
+    } catch (e) {
+      setProfileStatus(String(e));
+    }
+  };
+
+  // Import a profile, keeping the API keys entered on this machine
+  const importProfile = async () => {
+    setProfileStatus(null);
+    try {
+      const result = await databaseService.importProfile();
+      if (!result) return;
+      if (result.app_settings) {
+        const imported = { ...settings, ...result.app_settings, apiKeys: settings.apiKeys } as SettingsState;
+        setSettings(imported);
+        localStorage.setItem('meeting-assistant-settings', JSON.stringify(imported));
+      }
+      setBackendSettings(await databaseService.getSettings());
+      setProfileStatus(
+        `Imported ${result.settings} settings, ${result.note_templates} templates and ` +
+        `${result.trigger_phrases} trigger phrases` +
+        (result.integration_manifests > 0
+          ? `. ${result.integration_manifests} new integrations will ask for permission before they read anything.`
+          : '')
+      );
+    } catch (e) {
+      setProfileStatus(String(e));
+    }
+  };
+
   const updateApiKey = (key: 'openaiKey' | 'anthropicKey' | 'groqKey', value: string) => {
     setSettings(prev => ({
       ...prev,
@@ -425,6 +515,111 @@ export function SettingsPage() {
           </div>
         </section>
 
+        {/* Recording */}
+        <section className="settings-section">
+          <div className="section-header">
+            <BellOff className="section-icon" />
+            <h2>Recording</h2>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-toggle">
+              <input
+                type="checkbox"
+                checked={backendSettings.silence_notifications_while_recording === 'true'}
+                onChange={e => updateBackendSetting(
+                  'silence_notifications_while_recording',
+                  e.target.checked ? 'true' : 'false'
+                )}
+              />
+              <span className="settings-label">
+                <span>Silence notifications while recording</span>
+                <span className="label-hint">
+                  Turns off GNOME notification banners while a meeting is recording, so
+                  notification sounds stay out of the audio. Not available on other desktops.
+                </span>
+              </span>
+            </label>
+          </div>
+        </section>
+
+        {/* Profile */}
+        <section className="settings-section">
+          <div className="section-header">
+            <FileJson className="section-icon" />
+            <h2>Profile</h2>
+          </div>
+          <p className="section-description">
+            Move your setup to another machine or share it with your team: settings, note
+            templates, trigger phrases and integrations. API keys, meetings and integration
+            permissions are left out.
+          </p>
+
+          <div className="settings-group">
+            <div className="input-with-button">
+              <button className="test-button" onClick={exportProfile}>
+                Export
+              </button>
+              <button className="test-button" onClick={importProfile}>
+                Import
+              </button>
+            </div>
+            {profileStatus && <span className="label-hint">{profileStatus}</span>}
+          </div>
+        </section>
+
+        {/* Data Integrity */}
+        <section className="settings-section">
+          <div className="section-header">
+            <Database className="section-icon" />
+            <h2>Data Integrity</h2>
+          </div>
+          <p className="section-description">
+            Find leftovers from deleted meetings, recordings whose audio file is gone and
+            search index drift.
+          </p>
+
+          <div className="settings-group">
+            <div className="input-with-button">
+              <button
+                className="test-button"
+                onClick={() => checkWorkspace(false)}
+                disabled={checkingWorkspace}
+              >
+                Check
+              </button>
+              {workspaceFindings.length > 0 && !workspaceReport?.repaired && (
+                <button
+                  className="test-button"
+                  onClick={() => checkWorkspace(true)}
+                  disabled={checkingWorkspace}
+                >
+                  Repair
+                </button>
+              )}
+            </div>
+          </div>
+
+          {workspaceReport && (
+            <div className="settings-group">
+              {workspaceFindings.length === 0 ? (
+                <span className="label-hint">No problems found</span>
+              ) : (
+                <>
+                  <span className="label-hint">
+                    {workspaceReport.repaired ? 'Repaired:' : 'Found:'}
+                  </span>
+                  <ul className="workspace-findings">
+                    {workspaceFindings.map(finding => (
+                      <li key={finding}>{finding}</li>
+                    ))}
+                  </ul>
+                </>
+              )}
+            </div>
+          )}
+        </section>
+
         {/* Save Button */}
         <div className="settings-actions">
           <button className={`save-button ${saved ? 'saved' : ''}`} onClick={handleSave}>
@@ -2,4 +2,7 @@
 export { HomePage } from './HomePage';
 export { MeetingPage } from './MeetingPage';
 export { HistoryPage } from './HistoryPage';
+export { MeetingDetailPage } from './MeetingDetailPage';
+export { InboxPage } from './InboxPage';
 export { SettingsPage } from './SettingsPage';
+export { QuickRecordPage } from './QuickRecordPage';
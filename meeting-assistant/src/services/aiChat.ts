@@ -1,5 +1,10 @@
 // AI Chat Service
-// Handles AI-powered question answering about meeting content using OpenAI, Anthropic, or Groq
+// Handles AI-powered question answering about meeting content using OpenAI, Anthropic, or Groq.
+// Requests go through the backend, which falls back to the configured provider chain
+// when the primary times out or is rate-limited.
+
+import { invoke } from '@tauri-apps/api/tauri';
+import type { AIProviderConfig } from './database';
 
 export interface ChatMessage {
     role: 'system' | 'user' | 'assistant';
@@ -21,6 +26,8 @@ export interface AIConfig {
 
 export interface AIResponse {
     content: string;
+    provider?: string;
+    model?: string;
     usage?: {
         promptTokens: number;
         completionTokens: number;
@@ -28,6 +35,23 @@ export interface AIResponse {
     };
 }
 
+const DEFAULT_MODELS: Record<AIProvider['type'], string> = {
+    openai: 'gpt-4o-mini',
+    anthropic: 'claude-3-haiku-20240307',
+    groq: 'llama-3.3-70b-versatile',
+};
+
+// Mirrors ai::AiCompletion
+interface AICompletion {
+    content: string;
+    provider: string;
+    model: string;
+    prompt_tokens: number;
+    completion_tokens: number;
+    attempts: number;
+    budget_warnings: string[];
+}
+
 const DEFAULT_SYSTEM_PROMPT = `You are a helpful AI meeting assistant. You have access to the meeting transcript and can answer questions about the meeting content. Be concise but thorough in your responses.
 
 When answering questions:
@@ -42,6 +66,7 @@ class AIChatService {
     private config: AIConfig | null = null;
     private conversationHistory: ChatMessage[] = [];
     private transcriptContext: string = '';
+    private attachmentContext: string = '';
 
     /**
      * Configure the AI service
@@ -64,6 +89,28 @@ class AIChatService {
         return this.config !== null && this.config.provider.apiKey.length > 0;
     }
 
+    /**
+     * The configured provider as the backend takes it, for AI commands run
+     * outside the chat such as summaries. Falls back to the saved settings
+     * when the chat hasn't been configured in this window.
+     */
+    primaryProvider(): AIProviderConfig | undefined {
+        let provider = this.config?.provider;
+        if (!provider) {
+            try {
+                const settings = JSON.parse(localStorage.getItem('meeting-assistant-settings') || '{}');
+                const type: AIProvider['type'] = settings.aiProvider || 'openai';
+                const keys = settings.apiKeys || {};
+                const apiKey = type === 'openai' ? keys.openaiKey : type === 'groq' ? keys.groqKey : keys.anthropicKey;
+                provider = apiKey ? { type, apiKey, model: settings.aiModel } : undefined;
+            } catch (e) {
+                console.error('Failed to parse settings:', e);
+            }
+        }
+        if (!provider) return undefined;
+        return { provider: provider.type, model: provider.model || DEFAULT_MODELS[provider.type], api_key: provider.apiKey };
+    }
+
     /**
      * Update the transcript context for the AI
      */
@@ -71,6 +118,13 @@ class AIChatService {
         this.transcriptContext = transcript;
     }
 
+    /**
+     * Update the attached documents (e.g. pre-meeting email threads) for the AI
+     */
+    updateAttachmentContext(context: string): void {
+        this.attachmentContext = context;
+    }
+
     /**
      * Clear conversation history
      */
@@ -99,15 +153,7 @@ class AIChatService {
             },
         ];
 
-        let response: AIResponse;
-
-        if (this.config.provider.type === 'openai') {
-            response = await this.callOpenAI(messages);
-        } else if (this.config.provider.type === 'groq') {
-            response = await this.callGroq(messages);
-        } else {
-            response = await this.callAnthropic(messages);
-        }
+        const response = await this.complete(messages);
 
         // Add to conversation history
         this.conversationHistory.push({ role: 'user', content: userMessage });
@@ -131,132 +177,41 @@ class AIChatService {
             prompt += `\n\n## Current Meeting Transcript:\n${this.transcriptContext}`;
         }
 
-        return prompt;
-    }
-
-    /**
-     * Call OpenAI API
-     */
-    private async callOpenAI(messages: ChatMessage[]): Promise<AIResponse> {
-        if (!this.config) throw new Error('Not configured');
-
-        const model = this.config.provider.model || 'gpt-4o-mini';
-
-        const response = await fetch('https://api.openai.com/v1/chat/completions', {
-            method: 'POST',
-            headers: {
-                'Content-Type': 'application/json',
-                'Authorization': `Bearer ${this.config.provider.apiKey}`,
-            },
-            body: JSON.stringify({
-                model,
-                messages,
-                max_tokens: this.config.maxTokens,
-                temperature: this.config.temperature,
-            }),
-        });
-
-        if (!response.ok) {
-            const error = await response.json();
-            throw new Error(error.error?.message || 'OpenAI API error');
-        }
-
-        const data = await response.json();
-
-        return {
-            content: data.choices[0]?.message?.content || '',
-            usage: {
-                promptTokens: data.usage?.prompt_tokens || 0,
-                completionTokens: data.usage?.completion_tokens || 0,
-                totalTokens: data.usage?.total_tokens || 0,
-            },
-        };
-    }
-
-    /**
-     * Call Anthropic API
-     */
-    private async callAnthropic(messages: ChatMessage[]): Promise<AIResponse> {
-        if (!this.config) throw new Error('Not configured');
-
-        const model = this.config.provider.model || 'claude-3-haiku-20240307';
-
-        // Extract system message
-        const systemMessage = messages.find(m => m.role === 'system')?.content || '';
-        const conversationMessages = messages
-            .filter(m => m.role !== 'system')
-            .map(m => ({
-                role: m.role as 'user' | 'assistant',
-                content: m.content,
-            }));
-
-        const response = await fetch('https://api.anthropic.com/v1/messages', {
-            method: 'POST',
-            headers: {
-                'Content-Type': 'application/json',
-                'x-api-key': this.config.provider.apiKey,
-                'anthropic-version': '2023-06-01',
-            },
-            body: JSON.stringify({
-                model,
-                max_tokens: this.config.maxTokens,
-                system: systemMessage,
-                messages: conversationMessages,
-            }),
-        });
-
-        if (!response.ok) {
-            const error = await response.json();
-            throw new Error(error.error?.message || 'Anthropic API error');
+        if (this.attachmentContext) {
+            prompt += `\n\n## Related Email Threads:\n${this.attachmentContext}`;
         }
 
-        const data = await response.json();
-
-        return {
-            content: data.content[0]?.text || '',
-            usage: {
-                promptTokens: data.usage?.input_tokens || 0,
-                completionTokens: data.usage?.output_tokens || 0,
-                totalTokens: (data.usage?.input_tokens || 0) + (data.usage?.output_tokens || 0),
-            },
-        };
+        return prompt;
     }
 
     /**
-     * Call Groq API (OpenAI-compatible)
+     * Run the completion in the backend with the configured provider as primary
      */
-    private async callGroq(messages: ChatMessage[]): Promise<AIResponse> {
+    private async complete(messages: ChatMessage[]): Promise<AIResponse> {
         if (!this.config) throw new Error('Not configured');
 
-        const model = this.config.provider.model || 'llama-3.3-70b-versatile';
-
-        const response = await fetch('https://api.groq.com/openai/v1/chat/completions', {
-            method: 'POST',
-            headers: {
-                'Content-Type': 'application/json',
-                'Authorization': `Bearer ${this.config.provider.apiKey}`,
-            },
-            body: JSON.stringify({
-                model,
-                messages,
-                max_tokens: this.config.maxTokens,
-                temperature: this.config.temperature,
-            }),
+        const { type, apiKey, model } = this.config.provider;
+        const completion = await invoke<AICompletion>('ai_chat', {
+            messages,
+            primary: { provider: type, model: model || DEFAULT_MODELS[type], api_key: apiKey },
+            maxTokens: this.config.maxTokens,
+            temperature: this.config.temperature,
+            purpose: 'chat',
         });
 
-        if (!response.ok) {
-            const error = await response.json();
-            throw new Error(error.error?.message || 'Groq API error');
+        if (completion.attempts > 1) {
+            console.warn(`AI request served by fallback ${completion.provider}/${completion.model}`);
         }
-
-        const data = await response.json();
+        completion.budget_warnings.forEach(warning => console.warn(warning));
 
         return {
-            content: data.choices[0]?.message?.content || '',
+            content: completion.content,
+            provider: completion.provider,
+            model: completion.model,
             usage: {
-                promptTokens: data.usage?.prompt_tokens || 0,
-                completionTokens: data.usage?.completion_tokens || 0,
-                totalTokens: data.usage?.total_tokens || 0,
+                promptTokens: completion.prompt_tokens,
+                completionTokens: completion.completion_tokens,
+                totalTokens: completion.prompt_tokens + completion.completion_tokens,
             },
         };
     }
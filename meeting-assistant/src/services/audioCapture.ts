@@ -3,6 +3,7 @@
 
 import { appDataDir } from '@tauri-apps/api/path';
 import { writeBinaryFile, createDir } from '@tauri-apps/api/fs';
+import { databaseService } from './database';
 
 export interface AudioDevice {
     deviceId: string;
@@ -230,8 +231,10 @@ class AudioCaptureService {
             const filePath = `${recordingsDir}/${fileName}`;
             await writeBinaryFile(filePath, uint8Array);
 
-            console.log(`Recording saved to: ${filePath}`);
-            return filePath;
+            // Hand it to the configured audio storage, which may move it off this machine
+            const location = await databaseService.saveMeetingAudio(meetingId, filePath);
+            console.log(`Recording saved to: ${location}`);
+            return location;
         } catch (error) {
             console.error('Failed to save recording:', error);
             return null;
@@ -271,8 +274,9 @@ class AudioCaptureService {
     private startVolumeMonitoring(): void {
         if (this.volumeInterval) return;
 
+        // Keeps measuring while paused, so speech can end an automatic pause
         this.volumeInterval = window.setInterval(() => {
-            if (!this.analyserNode || this.state.isPaused) return;
+            if (!this.analyserNode) return;
 
             const dataArray = new Uint8Array(this.analyserNode.frequencyBinCount);
             this.analyserNode.getByteFrequencyData(dataArray);
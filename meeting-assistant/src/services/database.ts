@@ -2,8 +2,8 @@
 // TypeScript wrapper for Tauri database commands
 
 import { invoke } from '@tauri-apps/api/tauri';
-import { save } from '@tauri-apps/api/dialog';
-import { writeTextFile } from '@tauri-apps/api/fs';
+import { open, save } from '@tauri-apps/api/dialog';
+import type { DataScope } from './integrations';
 
 // Types matching Rust models
 export interface Meeting {
@@ -18,6 +18,7 @@ export interface Meeting {
     audio_path: string | null;
     created_at: string;
     updated_at: string;
+    auto_closed?: boolean;
 }
 
 export interface Participant {
@@ -39,6 +40,34 @@ export interface TranscriptEntry {
     language: string;
     translation: string | null;
     created_at: string;
+    voice_pitch?: number | null;
+    voice_energy?: number | null;
+}
+
+export interface SpeakerCountCheck {
+    meeting_id: string;
+    detected: number;
+    expected: number;
+    diverges: boolean;
+}
+
+export interface ReclusterResult {
+    meeting_id: string;
+    speakers: number;
+    entries_changed: number;
+    entries_inferred: number;
+}
+
+export interface TranscriptPage {
+    entries: TranscriptEntry[];
+    total: number;
+    has_more: boolean;
+}
+
+export interface TranscriptWindow {
+    entries: TranscriptEntry[];
+    total: number;
+    first_index: number;
 }
 
 export interface Note {
@@ -51,15 +80,432 @@ export interface Note {
     assignee: string | null;
     deadline: string | null;
     completed: boolean;
+    deferred_until?: string | null;
+    needs_review?: boolean;
     created_at: string;
     updated_at: string;
 }
 
+/** Role or alias shown instead of a speaker's name in anonymized exports */
+export interface SpeakerAlias {
+    meeting_id: string;
+    speaker_id: string;
+    alias: string;
+}
+
+/** Private remark on a transcript entry, kept out of exports by default */
+export interface Annotation {
+    id: string;
+    meeting_id: string;
+    transcript_entry_id: string;
+    content: string;
+    created_at: string;
+    updated_at: string;
+}
+
+/** A marked moment to revisit; included in exports */
+export interface Bookmark {
+    id: string;
+    meeting_id: string;
+    /** Milliseconds from meeting start */
+    timestamp: number;
+    label: string | null;
+    /** Transcript entry being spoken at the bookmark, to jump to */
+    transcript_entry_id: string | null;
+    created_at: string;
+}
+
+export interface Chapter {
+    index: number;
+    title: string;
+    start_time: number;
+    end_time: number;
+    first_entry_id: string;
+    entry_count: number;
+    thumbnail_path: string | null;
+}
+
+export interface NoteDiff {
+    new: Note[];
+    resolved: Note[];
+    still_open: Note[];
+}
+
+export interface MeetingDiff {
+    meeting_a: Meeting;
+    meeting_b: Meeting;
+    decisions: NoteDiff;
+    action_items: NoteDiff;
+}
+
+export interface RecordingHandoff {
+    ended: Meeting | null;
+    started: Meeting;
+}
+
+// Rejection payload of commands that return a typed error
+export interface CommandError {
+    kind: 'conflict' | 'not_found' | 'database';
+    message: string;
+    active_meeting_id?: string;
+}
+
+export function isRecordingConflict(error: unknown): error is CommandError {
+    return typeof error === 'object' && error !== null && (error as CommandError).kind === 'conflict';
+}
+
+export interface Attachment {
+    id: string;
+    meeting_id: string;
+    kind: 'email';
+    title: string;
+    author: string | null;
+    sent_at: string | null;
+    source_path: string | null;
+    content: string;
+    created_at: string;
+}
+
+export interface ContextSearchHit {
+    attachment_id: string;
+    meeting_id: string;
+    title: string;
+    snippet: string;
+}
+
+export interface TriggerPhrase {
+    id: string;
+    phrase: string;
+    note_type: Note['note_type'];
+    enabled: boolean;
+}
+
+export interface InboxItem {
+    note: Note;
+    meeting_title: string;
+    meeting_start: string;
+}
+
+export interface AIProviderConfig {
+    provider: 'openai' | 'anthropic' | 'groq';
+    model: string;
+    /** Empty to reuse the primary provider's key */
+    api_key: string;
+}
+
+export interface AIUsageRecord {
+    id: string;
+    request_id: string;
+    provider: string;
+    model: string;
+    purpose: string;
+    status: 'ok' | 'timeout' | 'rate_limited' | 'unavailable' | 'over_budget' | 'error';
+    prompt_tokens: number;
+    completion_tokens: number;
+    cost_usd: number;
+    latency_ms: number;
+    error: string | null;
+    created_at: string;
+}
+
+export interface BudgetStatus {
+    provider: string;
+    budget_usd: number | null;
+    spent_usd: number;
+    remaining_usd: number | null;
+    requests: number;
+    exceeded: boolean;
+}
+
+export interface SummaryVersion {
+    id: string;
+    meeting_id: string;
+    version: number;
+    template_version: number;
+    provider: string;
+    model: string;
+    content: string;
+    created_at: string;
+}
+
+export interface SummaryFilter {
+    meeting_ids?: string[];
+    since?: string;
+    until?: string;
+    /** Skip meetings already summarized with the current prompt and model (default true) */
+    outdated_only?: boolean;
+    limit?: number;
+}
+
+export interface RegenerateSummariesResult {
+    regenerated: SummaryVersion[];
+    skipped: number;
+    /** [meeting id, error] */
+    failed: [string, string][];
+    /** Whether new recordings now go to `target`; false while any failed to move */
+    switched: boolean;
+}
+
+export type BacklogOperation = 'summarize' | 'extract_actions' | 'embed';
+
+export interface BacklogFailure {
+    meeting_id: string;
+    operation: BacklogOperation;
+    error: string;
+}
+
+/** Emitted as the `backlog-progress` event after every finished job */
+export interface BacklogProgress {
+    operations: BacklogOperation[];
+    total: number;
+    completed: number;
+    failed: BacklogFailure[];
+    running: boolean;
+    cancelled: boolean;
+    /** Set while jobs wait out a provider's rate limit */
+    paused_until: string | null;
+    started_at: string;
+    finished_at: string | null;
+}
+
+export type AudioStorage = 'local' | 's3';
+
+export interface RelocateAudioResult {
+    target: AudioStorage;
+    moved: number;
+    /** Recordings already in the target storage */
+    skipped: number;
+    /** [meeting id, error] */
+    failed: [string, string][];
+}
+
 export interface ExportResult {
     file_path: string;
     content: string;
 }
 
+export type ExportFormat = 'markdown' | 'json' | 'html';
+
+export interface ExportFileResult {
+    file_path: string;
+    format: ExportFormat;
+    entries_written: number;
+    bytes_written: number;
+}
+
+/** Payload of the `export-progress` event */
+export interface ExportProgress {
+    meeting_id: string;
+    file_path: string;
+    entries_written: number;
+    total_entries: number;
+    bytes_written: number;
+    done: boolean;
+}
+
+/** Result of `export_knowledge_base` */
+export interface KnowledgeBaseResult {
+    dest_dir: string;
+    /** The page to open first */
+    index_path: string;
+    meetings: number;
+    decisions: number;
+    bytes_written: number;
+}
+
+/** A temporary read-only link to a meeting's minutes */
+export interface ShareLink {
+    id: string;
+    meeting_id: string;
+    url: string;
+    lan: boolean;
+    expires_at: string;
+}
+
+export interface OrphanedRows {
+    table: string;
+    count: number;
+}
+
+export interface MissingAudio {
+    meeting_id: string;
+    audio_path: string;
+}
+
+export interface WorkspaceReport {
+    orphaned_rows: OrphanedRows[];
+    orphaned_annotations: number;
+    missing_audio: MissingAudio[];
+    unindexed_attachments: number;
+    stale_index_rows: number;
+    repaired: boolean;
+}
+
+export interface NoteTemplate {
+    id: string;
+    name: string;
+    note_type: Note['note_type'];
+    body: string;
+    variables: string[];
+    created_at: string;
+    updated_at: string;
+}
+
+/** What an integration reads and where it sends it */
+export interface IntegrationManifest {
+    id: string;
+    name: string;
+    reads: DataScope[];
+    sends_to: string;
+}
+
+/**
+ * Settings, templates, trigger phrases and integration manifests; API keys are
+ * left blank and integration permissions are never included
+ */
+export interface ConfigProfile {
+    version: number;
+    exported_at: string;
+    settings: Record<string, string>;
+    note_templates: NoteTemplate[];
+    trigger_phrases: TriggerPhrase[];
+    integration_manifests: IntegrationManifest[];
+    /** The frontend settings without API keys */
+    app_settings: Record<string, unknown> | null;
+}
+
+export interface ProfileImportResult {
+    settings: number;
+    note_templates: number;
+    trigger_phrases: number;
+    /** Integrations new to this machine, whose permissions are still pending */
+    integration_manifests: number;
+    app_settings: Record<string, unknown> | null;
+}
+
+export interface CalendarImportResult {
+    imported: number;
+    /** All-day, cancelled or unreadable events */
+    skipped: number;
+}
+
+export interface DayLoad {
+    date: string;
+    events: number;
+    scheduled_minutes: number;
+    typical_minutes: number;
+    projected_minutes: number;
+}
+
+export interface MeetingLoadForecast {
+    week_start: string;
+    work_minutes: number;
+    scheduled_minutes: number;
+    projected_minutes: number;
+    scheduled_share: number;
+    projected_share: number;
+    warning_share: number;
+    overloaded: boolean;
+    days: DayLoad[];
+}
+
+export interface LiveSpeaker {
+    speaker_id: string;
+    speaker_name: string;
+    since_ms: number;
+}
+
+export interface LiveState {
+    meeting_id: string;
+    title: string;
+    status: Meeting['status'];
+    started_at: string;
+    elapsed_seconds: number;
+    current_speaker: LiveSpeaker | null;
+    segments_transcribed: number;
+    audio_level: number | null;
+}
+
+export interface LocalApiInfo {
+    enabled: boolean;
+    port: number;
+    token: string;
+    base_url: string;
+}
+
+/** Payload of `remote-control` events sent when the local API changes the recording */
+export interface RemoteControlEvent {
+    action: 'started' | 'stopped' | 'paused' | 'resumed' | 'marked';
+    meeting_id: string;
+}
+
+export type MetadataFieldType = 'text' | 'number' | 'boolean';
+
+export interface MetadataField {
+    id: string;
+    name: string;
+    field_type: MetadataFieldType;
+}
+
+export interface MetadataValue {
+    field_id: string;
+    name: string;
+    field_type: MetadataFieldType;
+    /** Numbers as written, booleans as "true" or "false" */
+    value: string;
+}
+
+export interface MetadataFilter {
+    field_id: string;
+    value: string;
+}
+
+export interface MetadataGroup {
+    value: string | null;
+    meetings: number;
+    total_minutes: number;
+}
+
+export interface MetadataBreakdown {
+    field: MetadataField;
+    groups: MetadataGroup[];
+}
+
+export type PluginHook = 'transcript-segment' | 'meeting-end' | 'exporter';
+
+/** A plugin's `plugin.json` */
+export interface PluginManifest {
+    id: string;
+    name: string;
+    version: string;
+    description: string;
+    hooks: PluginHook[];
+    /** Data the plugin is given once granted */
+    permissions: DataScope[];
+    exporter: { label: string; extension: string } | null;
+    module: string;
+}
+
+export interface Plugin {
+    manifest: PluginManifest;
+    enabled: boolean;
+    /** Grant the plugin's permissions with `set_integration_permission` under this id */
+    integration_id: string;
+    installed_at: string;
+}
+
+export interface PluginExportResult {
+    plugin_id: string;
+    file_path: string;
+    bytes_written: number;
+}
+
+const EXPORT_FILTERS: Record<ExportFormat, { name: string; extensions: string[] }> = {
+    markdown: { name: 'Markdown', extensions: ['md'] },
+    json: { name: 'JSON', extensions: ['json'] },
+    html: { name: 'HTML', extensions: ['html'] },
+};
+
 class DatabaseService {
     // ========================================
     // Meeting Operations
@@ -69,6 +515,13 @@ class DatabaseService {
         return invoke<Meeting>('start_meeting', { title });
     }
 
+    /**
+     * End whichever meeting is recording and start a new one atomically.
+     */
+    async handoffRecording(newTitle: string): Promise<RecordingHandoff> {
+        return invoke<RecordingHandoff>('handoff_recording', { newTitle });
+    }
+
     async endMeeting(meetingId: string): Promise<Meeting> {
         return invoke<Meeting>('end_meeting', { meetingId });
     }
@@ -81,8 +534,11 @@ class DatabaseService {
         return invoke<void>('resume_meeting', { meetingId });
     }
 
-    async getMeetings(): Promise<Meeting[]> {
-        return invoke<Meeting[]>('get_meetings');
+    /**
+     * All meetings, or only those whose metadata matches every filter.
+     */
+    async getMeetings(metadata?: MetadataFilter[]): Promise<Meeting[]> {
+        return invoke<Meeting[]>('get_meetings', { metadata });
     }
 
     async getMeeting(meetingId: string): Promise<Meeting> {
@@ -93,12 +549,84 @@ class DatabaseService {
         return invoke<void>('delete_meeting', { meetingId });
     }
 
+    // ========================================
+    // Audio Storage Operations
+    // ========================================
+
+    /** Moves a recording written to `filePath` into the configured storage; returns its location */
+    async saveMeetingAudio(meetingId: string, filePath: string): Promise<string> {
+        return invoke<string>('save_meeting_audio', { meetingId, filePath });
+    }
+
+    /** A local file with the meeting's recording, downloaded first if kept in a bucket */
+    async getMeetingAudioFile(meetingId: string): Promise<string> {
+        return invoke<string>('get_meeting_audio_file', { meetingId });
+    }
+
+    async relocateAudio(target: AudioStorage): Promise<RelocateAudioResult> {
+        return invoke<RelocateAudioResult>('relocate_audio', { target });
+    }
+
+    // ========================================
+    // Meeting Metadata
+    // ========================================
+
+    async getMetadataFields(): Promise<MetadataField[]> {
+        return invoke<MetadataField[]>('get_metadata_fields');
+    }
+
+    /**
+     * Define a field, or rename or retype one. Retyping clears its values.
+     */
+    async saveMetadataField(name: string, fieldType: MetadataFieldType, fieldId?: string): Promise<MetadataField> {
+        return invoke<MetadataField>('save_metadata_field', { fieldId, name, fieldType });
+    }
+
+    async deleteMetadataField(fieldId: string): Promise<void> {
+        return invoke<void>('delete_metadata_field', { fieldId });
+    }
+
+    async getMeetingMetadata(meetingId: string): Promise<MetadataValue[]> {
+        return invoke<MetadataValue[]>('get_meeting_metadata', { meetingId });
+    }
+
+    /**
+     * Set a meeting's value for a field; leave `value` out or blank to clear it.
+     */
+    async setMeetingMetadata(meetingId: string, fieldId: string, value?: string): Promise<MetadataValue[]> {
+        return invoke<MetadataValue[]>('set_meeting_metadata', { meetingId, fieldId, value });
+    }
+
+    async getMetadataBreakdown(fieldId: string, rangeStart?: string, rangeEnd?: string): Promise<MetadataBreakdown> {
+        return invoke<MetadataBreakdown>('get_metadata_breakdown', { fieldId, rangeStart, rangeEnd });
+    }
+
     // ========================================
     // Transcript Operations
     // ========================================
 
-    async getTranscript(meetingId: string): Promise<TranscriptEntry[]> {
-        return invoke<TranscriptEntry[]>('get_transcript', { meetingId });
+    /** With `integrationId`, fails unless that integration was granted transcripts */
+    async getTranscript(meetingId: string, integrationId?: string): Promise<TranscriptEntry[]> {
+        return invoke<TranscriptEntry[]>('get_transcript', { meetingId, integrationId });
+    }
+
+    /**
+     * Fetch one page of the transcript. Pass the timestamp of the last entry
+     * of the previous page as `afterTimestamp` to continue.
+     */
+    async getTranscriptPage(meetingId: string, afterTimestamp?: number, limit?: number, integrationId?: string): Promise<TranscriptPage> {
+        return invoke<TranscriptPage>('get_transcript_page', { meetingId, afterTimestamp, limit, integrationId });
+    }
+
+    /**
+     * Fetch the entries around a playback position (ms) for the player view
+     */
+    async getTranscriptAround(meetingId: string, timestamp: number, window?: number, integrationId?: string): Promise<TranscriptWindow> {
+        return invoke<TranscriptWindow>('get_transcript_around', { meetingId, timestamp, window, integrationId });
+    }
+
+    async getTranscriptCount(meetingId: string): Promise<number> {
+        return invoke<number>('get_transcript_count', { meetingId });
     }
 
     async saveTranscriptEntry(entry: TranscriptEntry): Promise<void> {
@@ -109,6 +637,203 @@ class DatabaseService {
         return invoke<void>('save_transcript_batch', { entries });
     }
 
+    /**
+     * Compare the speakers found with the participant list (also emitted as
+     * `speaker-count-mismatch` when a meeting ends and they diverge)
+     */
+    async checkSpeakerCount(meetingId: string): Promise<SpeakerCountCheck | null> {
+        return invoke<SpeakerCountCheck | null>('check_speaker_count', { meetingId });
+    }
+
+    /**
+     * Re-cluster the whole transcript into exactly `expectedSpeakers` speakers
+     */
+    async reclusterSpeakers(meetingId: string, expectedSpeakers: number): Promise<ReclusterResult> {
+        return invoke<ReclusterResult>('recluster_speakers', { meetingId, expectedSpeakers });
+    }
+
+    /**
+     * Report the current input level (0-1). The backend auto-pauses the
+     * meeting after prolonged silence and resumes it when speech returns,
+     * returning the new status when it changed.
+     */
+    async reportAudioLevel(meetingId: string, level: number): Promise<Meeting['status'] | null> {
+        return invoke<Meeting['status'] | null>('report_audio_level', { meetingId, level });
+    }
+
+    /**
+     * The meeting being recorded or paused right now, or null when idle
+     */
+    async getLiveState(): Promise<LiveState | null> {
+        return invoke<LiveState | null>('get_live_state');
+    }
+
+    /**
+     * Add a key point at the current position of the recording meeting
+     */
+    async markMoment(label?: string): Promise<Note> {
+        return invoke<Note>('mark_moment', { label: label ?? null });
+    }
+
+    /**
+     * Where the local API listens and its token. Enabling it or changing the
+     * port takes effect after a restart.
+     */
+    async getLocalApiInfo(): Promise<LocalApiInfo> {
+        return invoke<LocalApiInfo>('get_local_api_info');
+    }
+
+    async regenerateLocalApiToken(): Promise<LocalApiInfo> {
+        return invoke<LocalApiInfo>('regenerate_local_api_token');
+    }
+
+    // ========================================
+    // Screen Capture & Chapter Operations
+    // ========================================
+
+    async saveScreenCapture(
+        meetingId: string,
+        timestamp: number,
+        imagePath: string,
+        ocrText: string,
+        relevanceScore: number
+    ): Promise<void> {
+        await invoke('save_screen_capture', { meetingId, timestamp, imagePath, ocrText, relevanceScore });
+    }
+
+    async getMeetingChapters(meetingId: string): Promise<Chapter[]> {
+        return invoke<Chapter[]>('get_meeting_chapters', { meetingId });
+    }
+
+    // ========================================
+    // Settings Operations
+    // ========================================
+
+    async getSettings(): Promise<Record<string, string>> {
+        return invoke<Record<string, string>>('get_settings');
+    }
+
+    async setSetting(key: string, value: string): Promise<void> {
+        return invoke<void>('set_setting', { key, value });
+    }
+
+    /**
+     * Format a length of time (e.g. "1 hr 5 min") in the `locale` setting's language
+     */
+    async formatDuration(seconds: number): Promise<string> {
+        return invoke<string>('format_duration', { seconds });
+    }
+
+    /**
+     * Format a time relative to now (e.g. "3 hours ago") in the `locale` setting's language
+     */
+    async formatRelativeTime(time: string): Promise<string> {
+        return invoke<string>('format_relative_time', { time });
+    }
+
+    // ========================================
+    // Attachment Operations
+    // ========================================
+
+    /**
+     * Attach an .eml email thread to a meeting as AI and search context
+     */
+    async attachEmail(meetingId: string, path: string): Promise<Attachment> {
+        return invoke<Attachment>('attach_email', { meetingId, path });
+    }
+
+    async getAttachments(meetingId: string): Promise<Attachment[]> {
+        return invoke<Attachment[]>('get_attachments', { meetingId });
+    }
+
+    async deleteAttachment(attachmentId: string): Promise<void> {
+        return invoke<void>('delete_attachment', { attachmentId });
+    }
+
+    async searchContext(query: string, meetingId?: string): Promise<ContextSearchHit[]> {
+        return invoke<ContextSearchHit[]>('search_context', { query, meetingId });
+    }
+
+    /**
+     * Attachment text formatted for inclusion in the AI prompt
+     */
+    async getMeetingContext(meetingId: string): Promise<string> {
+        return invoke<string>('get_meeting_context', { meetingId });
+    }
+
+    // ========================================
+    // AI Provider Operations
+    // ========================================
+
+    /**
+     * Fallback providers tried in order after the primary one
+     */
+    async getAIProviderChain(): Promise<AIProviderConfig[]> {
+        return invoke<AIProviderConfig[]>('get_ai_provider_chain');
+    }
+
+    async setAIProviderChain(chain: AIProviderConfig[]): Promise<void> {
+        return invoke<void>('set_ai_provider_chain', { chain });
+    }
+
+    async getAIUsage(since?: string): Promise<AIUsageRecord[]> {
+        return invoke<AIUsageRecord[]>('get_ai_usage', { since });
+    }
+
+    /**
+     * This month's spend per provider against its budget
+     */
+    async getBudgetStatus(): Promise<BudgetStatus[]> {
+        return invoke<BudgetStatus[]>('get_budget_status');
+    }
+
+    /**
+     * Set a provider's monthly budget in USD, or pass null to remove it.
+     * Whether an exceeded budget warns or blocks is the `ai_budget_mode` setting.
+     */
+    async setAIBudget(provider: string, monthlyUsd: number | null): Promise<BudgetStatus[]> {
+        return invoke<BudgetStatus[]>('set_ai_budget', { provider, monthlyUsd });
+    }
+
+    // ========================================
+    // Summary Operations
+    // ========================================
+
+    async generateSummary(meetingId: string, primary?: AIProviderConfig): Promise<SummaryVersion> {
+        return invoke<SummaryVersion>('generate_summary', { meetingId, primary });
+    }
+
+    /**
+     * Every stored summary of a meeting, newest version first
+     */
+    async getSummaryVersions(meetingId: string): Promise<SummaryVersion[]> {
+        return invoke<SummaryVersion[]>('get_summary_versions', { meetingId });
+    }
+
+    /**
+     * Re-summarize matching meetings with the latest prompt and model,
+     * keeping the previous versions
+     */
+    async regenerateSummaries(filter?: SummaryFilter, primary?: AIProviderConfig): Promise<RegenerateSummariesResult> {
+        return invoke<RegenerateSummariesResult>('regenerate_summaries', { filter, primary });
+    }
+
+    /**
+     * Run the given AI operations in the background for every finished
+     * meeting that hasn't had them yet
+     */
+    async processBacklog(operations: BacklogOperation[], primary?: AIProviderConfig): Promise<BacklogProgress> {
+        return invoke<BacklogProgress>('process_backlog', { operations, primary });
+    }
+
+    async getBacklogProgress(): Promise<BacklogProgress | null> {
+        return invoke<BacklogProgress | null>('get_backlog_progress');
+    }
+
+    async cancelBacklog(): Promise<boolean> {
+        return invoke<boolean>('cancel_backlog');
+    }
+
     // ========================================
     // Notes Operations
     // ========================================
@@ -129,6 +854,234 @@ class DatabaseService {
         return invoke<void>('delete_note', { noteId });
     }
 
+    /**
+     * Keep a note created from a trigger phrase (dismiss with deleteNote)
+     */
+    async confirmAutoNote(noteId: string): Promise<void> {
+        return invoke<void>('confirm_auto_note', { noteId });
+    }
+
+    // ========================================
+    // Speaker Alias Operations
+    // ========================================
+
+    async getSpeakerAliases(meetingId: string): Promise<SpeakerAlias[]> {
+        return invoke<SpeakerAlias[]>('get_speaker_aliases', { meetingId });
+    }
+
+    /**
+     * Role shown instead of the speaker's name in anonymized exports; an
+     * empty alias clears it
+     */
+    async setSpeakerAlias(meetingId: string, speakerId: string, alias: string): Promise<void> {
+        return invoke<void>('set_speaker_alias', { meetingId, speakerId, alias });
+    }
+
+    // ========================================
+    // Annotation Operations
+    // ========================================
+
+    async getAnnotations(meetingId: string): Promise<Annotation[]> {
+        return invoke<Annotation[]>('get_annotations', { meetingId });
+    }
+
+    /**
+     * Private remark on a transcript entry; left out of exports unless
+     * `includeAnnotations` is set
+     */
+    async addAnnotation(meetingId: string, transcriptEntryId: string, content: string): Promise<Annotation> {
+        return invoke<Annotation>('add_annotation', { meetingId, transcriptEntryId, content });
+    }
+
+    async updateAnnotation(annotationId: string, content: string): Promise<Annotation> {
+        return invoke<Annotation>('update_annotation', { annotationId, content });
+    }
+
+    async deleteAnnotation(annotationId: string): Promise<void> {
+        return invoke<void>('delete_annotation', { annotationId });
+    }
+
+    // ========================================
+    // Bookmark Operations
+    // ========================================
+
+    /** Marks the moment `timestamp` milliseconds into the meeting */
+    async addBookmark(meetingId: string, timestamp: number, label?: string): Promise<Bookmark> {
+        return invoke<Bookmark>('add_bookmark', { meetingId, timestamp, label });
+    }
+
+    /** Bookmarks in playback order, for a jump list */
+    async listBookmarks(meetingId: string): Promise<Bookmark[]> {
+        return invoke<Bookmark[]>('list_bookmarks', { meetingId });
+    }
+
+    async deleteBookmark(bookmarkId: string): Promise<void> {
+        return invoke<void>('delete_bookmark', { bookmarkId });
+    }
+
+    async getTriggerPhrases(): Promise<TriggerPhrase[]> {
+        return invoke<TriggerPhrase[]>('get_trigger_phrases');
+    }
+
+    async saveTriggerPhrase(
+        phrase: string,
+        noteType: Note['note_type'],
+        enabled = true,
+        phraseId?: string
+    ): Promise<TriggerPhrase> {
+        return invoke<TriggerPhrase>('save_trigger_phrase', { phraseId, phrase, noteType, enabled });
+    }
+
+    async deleteTriggerPhrase(phraseId: string): Promise<void> {
+        return invoke<void>('delete_trigger_phrase', { phraseId });
+    }
+
+    // ========================================
+    // Workspace Check Operations
+    // ========================================
+
+    /**
+     * Look for orphaned rows, missing audio files and search index drift;
+     * with `repair` they are fixed as well
+     */
+    async checkWorkspace(repair = false): Promise<WorkspaceReport> {
+        return invoke<WorkspaceReport>('check_workspace', { repair });
+    }
+
+    // ========================================
+    // Configuration Profile Operations
+    // ========================================
+
+    /**
+     * Export the configuration to a JSON file chosen by the user, along with
+     * the frontend settings (the backend drops their API keys)
+     */
+    async exportProfile(appSettings?: Record<string, unknown>): Promise<string | null> {
+        const filePath = await save({
+            defaultPath: 'meeting-assistant-profile.json',
+            filters: [{ name: 'JSON', extensions: ['json'] }],
+        });
+        if (!filePath) {
+            return null;
+        }
+        await invoke<ConfigProfile>('export_profile', { path: filePath, appSettings });
+        return filePath;
+    }
+
+    /**
+     * Import a profile chosen by the user; API keys already configured are kept.
+     * The frontend settings it carried are returned for the caller to apply.
+     */
+    async importProfile(): Promise<ProfileImportResult | null> {
+        const filePath = await open({
+            multiple: false,
+            filters: [{ name: 'JSON', extensions: ['json'] }],
+        });
+        if (typeof filePath !== 'string') {
+            return null;
+        }
+        return invoke<ProfileImportResult>('import_profile', { path: filePath });
+    }
+
+    // ========================================
+    // Calendar & Meeting Load Operations
+    // ========================================
+
+    /**
+     * Import scheduled meetings from an .ics file chosen by the user
+     */
+    async importCalendar(): Promise<CalendarImportResult | null> {
+        const filePath = await open({
+            multiple: false,
+            filters: [{ name: 'Calendar', extensions: ['ics'] }],
+        });
+        if (typeof filePath !== 'string') {
+            return null;
+        }
+        return invoke<CalendarImportResult>('import_calendar', { path: filePath });
+    }
+
+    /**
+     * Scheduled and projected meeting time for the week containing `week`
+     * (YYYY-MM-DD), this week by default
+     */
+    async getMeetingLoadForecast(week?: string): Promise<MeetingLoadForecast> {
+        return invoke<MeetingLoadForecast>('get_meeting_load_forecast', { week });
+    }
+
+    // ========================================
+    // Inbox Operations
+    // ========================================
+
+    /**
+     * List action items; pass 'me' to resolve the `my_name` setting
+     */
+    async listActionItems(assignee?: string, includeCompleted = false): Promise<InboxItem[]> {
+        return invoke<InboxItem[]>('list_action_items', { assignee, includeCompleted });
+    }
+
+    async getInbox(): Promise<InboxItem[]> {
+        return invoke<InboxItem[]>('get_inbox');
+    }
+
+    async completeActionItem(noteId: string): Promise<void> {
+        return invoke<void>('complete_action_item', { noteId });
+    }
+
+    async deferActionItem(noteId: string, until: Date | null): Promise<void> {
+        return invoke<void>('defer_action_item', { noteId, until: until?.toISOString() ?? null });
+    }
+
+    // ========================================
+    // Plugin Operations
+    // ========================================
+
+    async getPlugins(): Promise<Plugin[]> {
+        return invoke<Plugin[]>('get_plugins');
+    }
+
+    /** Install or update the plugin whose `plugin.json` the user picks */
+    async installPlugin(): Promise<Plugin | null> {
+        const manifestPath = await open({
+            multiple: false,
+            filters: [{ name: 'Plugin manifest', extensions: ['json'] }],
+        });
+        if (typeof manifestPath !== 'string') {
+            return null;
+        }
+        return invoke<Plugin>('install_plugin', { manifestPath });
+    }
+
+    async setPluginEnabled(pluginId: string, enabled: boolean): Promise<void> {
+        return invoke('set_plugin_enabled', { pluginId, enabled });
+    }
+
+    async uninstallPlugin(pluginId: string): Promise<void> {
+        return invoke('uninstall_plugin', { pluginId });
+    }
+
+    /** Prompt for a destination and export the meeting with an exporter plugin */
+    async exportWithPlugin(meetingId: string, plugin: Plugin): Promise<string | null> {
+        const exporter = plugin.manifest.exporter;
+        if (!exporter) {
+            throw new Error(`${plugin.manifest.name} doesn't export meetings`);
+        }
+        const meeting = await this.getMeeting(meetingId);
+        const filePath = await save({
+            defaultPath: `${meeting.title.replace(/ /g, '_').replace(/\//g, '-')}.${exporter.extension}`,
+            filters: [{ name: exporter.label, extensions: [exporter.extension] }],
+        });
+        if (!filePath) {
+            return null;
+        }
+        const result = await invoke<PluginExportResult>('export_meeting_with_plugin', {
+            meetingId,
+            pluginId: plugin.manifest.id,
+            filePath,
+        });
+        return result.file_path;
+    }
+
     // ========================================
     // Export Operations
     // ========================================
@@ -139,6 +1092,10 @@ class DatabaseService {
             includeTranscript?: boolean;
             includeNotes?: boolean;
             includeSummary?: string;
+            includeAnnotations?: boolean;
+            anonymize?: boolean;
+            /** The integration the export is for; fails unless it was granted what's included */
+            integrationId?: string;
         } = {}
     ): Promise<ExportResult> {
         return invoke<ExportResult>('export_meeting_markdown', {
@@ -146,34 +1103,70 @@ class DatabaseService {
             includeTranscript: options.includeTranscript ?? true,
             includeNotes: options.includeNotes ?? true,
             includeSummary: options.includeSummary,
+            includeAnnotations: options.includeAnnotations ?? false,
+            anonymize: options.anonymize ?? false,
+            integrationId: options.integrationId,
         });
     }
 
     /**
-     * Export meeting to markdown file and prompt user to save
+     * Stream an export straight to `filePath`; progress arrives as
+     * `export-progress` events
      */
-    async exportAndSave(
+    async exportMeetingToFile(
         meetingId: string,
+        format: ExportFormat,
+        filePath: string,
         options: {
             includeTranscript?: boolean;
             includeNotes?: boolean;
             includeSummary?: string;
+            includeAnnotations?: boolean;
+            anonymize?: boolean;
+            /** The integration the export is for; fails unless it was granted what's included */
+            integrationId?: string;
         } = {}
+    ): Promise<ExportFileResult> {
+        return invoke<ExportFileResult>('export_meeting_to_file', {
+            meetingId,
+            format,
+            filePath,
+            includeTranscript: options.includeTranscript ?? true,
+            includeNotes: options.includeNotes ?? true,
+            includeSummary: options.includeSummary,
+            includeAnnotations: options.includeAnnotations ?? false,
+            anonymize: options.anonymize ?? false,
+            integrationId: options.integrationId,
+        });
+    }
+
+    /**
+     * Prompt the user for a destination and export the meeting there
+     */
+    async exportAndSave(
+        meetingId: string,
+        options: {
+            includeTranscript?: boolean;
+            includeNotes?: boolean;
+            includeSummary?: string;
+            includeAnnotations?: boolean;
+            anonymize?: boolean;
+        } = {},
+        format: ExportFormat = 'markdown'
     ): Promise<string | null> {
         try {
-            // Generate markdown content
-            const result = await this.exportMeetingMarkdown(meetingId, options);
+            const meeting = await this.getMeeting(meetingId);
+            const filter = EXPORT_FILTERS[format];
 
             // Prompt user to save file
             const filePath = await save({
-                defaultPath: result.file_path,
-                filters: [{ name: 'Markdown', extensions: ['md'] }],
+                defaultPath: `${meeting.title.replace(/ /g, '_').replace(/\//g, '-')}.${filter.extensions[0]}`,
+                filters: [filter],
             });
 
             if (filePath) {
-                // Write file
-                await writeTextFile(filePath, result.content);
-                return filePath;
+                const result = await this.exportMeetingToFile(meetingId, format, filePath, options);
+                return result.file_path;
             }
 
             return null;
@@ -192,11 +1185,50 @@ class DatabaseService {
             includeTranscript?: boolean;
             includeNotes?: boolean;
             includeSummary?: string;
+            includeAnnotations?: boolean;
+            anonymize?: boolean;
         } = {}
     ): Promise<string> {
         const result = await this.exportMeetingMarkdown(meetingId, options);
         return result.content;
     }
+
+    /**
+     * Write every completed meeting into a folder as a static site, indexed by
+     * date, series, tag and participant
+     */
+    async exportKnowledgeBase(destDir: string, includeTranscripts?: boolean): Promise<KnowledgeBaseResult> {
+        return invoke<KnowledgeBaseResult>('export_knowledge_base', { destDir, includeTranscripts });
+    }
+
+    /**
+     * Serve the meeting's minutes read-only at a tokenized URL. With `lan`
+     * the link opens from other machines on the same network. With
+     * `integrationId` it fails unless that integration was granted everything
+     * the page shows.
+     */
+    async shareMeeting(meetingId: string, ttlMinutes?: number, lan?: boolean, integrationId?: string): Promise<ShareLink> {
+        return invoke<ShareLink>('share_meeting', { meetingId, ttlMinutes, lan, integrationId });
+    }
+
+    async getActiveShares(): Promise<ShareLink[]> {
+        return invoke<ShareLink[]>('get_active_shares');
+    }
+
+    async stopSharing(shareId: string): Promise<boolean> {
+        return invoke<boolean>('stop_sharing', { shareId });
+    }
+
+    /**
+     * Compare decisions and action items between two meetings of a series
+     */
+    async diffMeetings(meetingA: string, meetingB: string): Promise<MeetingDiff> {
+        return invoke<MeetingDiff>('diff_meetings', { meetingA, meetingB });
+    }
+
+    async exportMeetingDiffMarkdown(meetingA: string, meetingB: string): Promise<ExportResult> {
+        return invoke<ExportResult>('export_meeting_diff_markdown', { meetingA, meetingB });
+    }
 }
 
 // Singleton instance
@@ -8,6 +8,9 @@ export type { TranscriptionResult, TranscriptionConfig } from './transcription';
 export { aiChatService } from './aiChat';
 export type { ChatMessage, AIProvider, AIConfig, AIResponse } from './aiChat';
 
+export { meetingSession } from './meetingSession';
+export type { SessionStatus } from './meetingSession';
+
 export { databaseService } from './database';
 export type { Meeting, Participant, TranscriptEntry, Note, ExportResult } from './database';
 
@@ -1,8 +1,19 @@
 // Integration Service
 // Handles syncing notes and action items to external platforms (Slack, Notion, etc.)
 
+import { invoke } from '@tauri-apps/api/tauri';
 import type { Note } from '@/types';
 
+// Data categories an integration can be granted (mirrors DataScope in models.rs)
+export type DataScope =
+    | 'meeting-metadata'
+    | 'notes'
+    | 'action-items'
+    | 'summaries'
+    | 'transcripts'
+    | 'audio'
+    | 'annotations';
+
 export interface IntegrationConfig {
     slack?: {
         enabled: boolean;
@@ -88,6 +99,11 @@ class IntegrationService {
             return { platform: 'slack', success: false, error: 'Slack not configured' };
         }
 
+        const denied = await this.authorize('slack', this.getNoteScopes(note));
+        if (denied) {
+            return { platform: 'slack', success: false, error: denied };
+        }
+
         try {
             const { webhookUrl, botToken, channel } = this.config.slack;
 
@@ -154,6 +170,11 @@ class IntegrationService {
             return { platform: 'notion', success: false, error: 'Notion not configured' };
         }
 
+        const denied = await this.authorize('notion', this.getNoteScopes(note));
+        if (denied) {
+            return { platform: 'notion', success: false, error: denied };
+        }
+
         try {
             const { apiKey, databaseId } = this.config.notion;
 
@@ -189,6 +210,29 @@ class IntegrationService {
         }
     }
 
+    /**
+     * Check every scope with the backend permission gate.
+     * Returns the denial message, or null when all scopes are granted.
+     */
+    private async authorize(platform: string, scopes: DataScope[]): Promise<string | null> {
+        for (const scope of scopes) {
+            try {
+                await invoke('authorize_integration_access', { integrationId: platform, scope });
+            } catch (error) {
+                return typeof error === 'string' ? error : `Permission check failed for ${scope}`;
+            }
+        }
+        return null;
+    }
+
+    /**
+     * Data scopes a synced note exposes to the destination
+     */
+    private getNoteScopes(note: Note): DataScope[] {
+        const contentScope: DataScope = note.note_type === 'action-item' ? 'action-items' : 'notes';
+        return ['meeting-metadata', contentScope];
+    }
+
     /**
      * Format Slack message
      */
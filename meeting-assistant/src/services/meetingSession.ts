@@ -0,0 +1,162 @@
+// Meeting Session Service
+// Keeps the backend in step with the recording in this window: reports the
+// input level, which drives auto-pause on silence and the live state, saves
+// transcript entries as they arrive, and applies the pauses, resumes and
+// stops the backend decides on, on silence or through the local API, to
+// audio capture
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { audioCapture } from './audioCapture';
+import { databaseService } from './database';
+import type { RemoteControlEvent, TranscriptEntry as StoredTranscriptEntry } from './database';
+import type { TranscriptEntry } from '@/types';
+
+/** How often the input level is reported while recording */
+const LEVEL_REPORT_INTERVAL = 1000;
+
+export type SessionStatus = 'recording' | 'paused';
+
+type StatusCallback = (status: SessionStatus) => void;
+type StopCallback = () => void;
+
+class MeetingSessionService {
+    private meetingId: string | null = null;
+    private startedAt = 0;
+    private unsubscribeVolume: (() => void) | null = null;
+    private peakLevel = 0;
+    private lastReport = 0;
+    private reporting = false;
+    private statusCallbacks: StatusCallback[] = [];
+    private stopCallbacks: StopCallback[] = [];
+    private unlistenRemote: Promise<UnlistenFn> | null = null;
+
+    /**
+     * Start reporting for a meeting saved in the database; capture must be running
+     */
+    begin(meetingId: string): void {
+        this.end();
+        this.meetingId = meetingId;
+        this.startedAt = Date.now();
+        this.peakLevel = 0;
+        this.lastReport = Date.now();
+        this.unsubscribeVolume = audioCapture.onVolumeChange((level) => this.handleLevel(level));
+        this.unlistenRemote = listen<RemoteControlEvent>('remote-control', (event) => this.handleRemote(event.payload));
+    }
+
+    /**
+     * Stop reporting, e.g. once the meeting has ended
+     */
+    end(): void {
+        this.unsubscribeVolume?.();
+        this.unsubscribeVolume = null;
+        this.unlistenRemote?.then((unlisten) => unlisten());
+        this.unlistenRemote = null;
+        this.meetingId = null;
+    }
+
+    getMeetingId(): string | null {
+        return this.meetingId;
+    }
+
+    /**
+     * Save a live transcript entry, with the voice characteristics the
+     * diarizer measured so speakers can be re-clustered after the meeting.
+     * Entry timestamps are wall-clock times; the backend keeps them relative
+     * to the meeting start.
+     */
+    async saveEntry(entry: TranscriptEntry): Promise<void> {
+        if (!this.meetingId) return;
+        const stored: StoredTranscriptEntry = {
+            id: entry.id,
+            meeting_id: this.meetingId,
+            speaker_id: entry.speakerId,
+            speaker_name: entry.speakerName,
+            text: entry.text,
+            timestamp: Math.max(0, entry.timestamp - this.startedAt),
+            end_timestamp: Math.max(0, entry.endTimestamp - this.startedAt),
+            confidence: entry.confidence,
+            language: entry.language,
+            translation: entry.translation ?? null,
+            created_at: entry.createdAt.toISOString(),
+            voice_pitch: entry.voicePitch ?? null,
+            voice_energy: entry.voiceEnergy ?? null,
+        };
+        try {
+            await databaseService.saveTranscriptEntry(stored);
+        } catch (e) {
+            console.error('Failed to save transcript entry:', e);
+        }
+    }
+
+    /**
+     * Subscribe to pauses and resumes made by the backend rather than the user
+     */
+    onStatusChange(callback: StatusCallback): () => void {
+        this.statusCallbacks.push(callback);
+        return () => {
+            this.statusCallbacks = this.statusCallbacks.filter(cb => cb !== callback);
+        };
+    }
+
+    /**
+     * Subscribe to the meeting being ended outside this window. The backend
+     * has already ended it; the subscriber only stops capture and saves it.
+     */
+    onRemoteStop(callback: StopCallback): () => void {
+        this.stopCallbacks.push(callback);
+        return () => {
+            this.stopCallbacks = this.stopCallbacks.filter(cb => cb !== callback);
+        };
+    }
+
+    private handleRemote(event: RemoteControlEvent): void {
+        if (event.meeting_id !== this.meetingId) return;
+        switch (event.action) {
+            case 'paused':
+                this.applyStatus('paused');
+                break;
+            case 'resumed':
+                this.applyStatus('recording');
+                break;
+            case 'stopped':
+                this.stopCallbacks.forEach(cb => cb());
+                break;
+        }
+    }
+
+    private handleLevel(level: number): void {
+        // Report the loudest reading since the last report, so short bursts
+        // of speech between reports still count
+        this.peakLevel = Math.max(this.peakLevel, level);
+        const now = Date.now();
+        if (!this.meetingId || this.reporting || now - this.lastReport < LEVEL_REPORT_INTERVAL) return;
+
+        const meetingId = this.meetingId;
+        const peak = this.peakLevel;
+        this.peakLevel = 0;
+        this.lastReport = now;
+        this.reporting = true;
+        databaseService.reportAudioLevel(meetingId, peak)
+            .then((status) => {
+                if (status && status !== 'completed' && status !== 'idle' && meetingId === this.meetingId) {
+                    this.applyStatus(status);
+                }
+            })
+            .catch(e => console.error('Failed to report audio level:', e))
+            .finally(() => {
+                this.reporting = false;
+            });
+    }
+
+    private applyStatus(status: SessionStatus): void {
+        if (status === 'paused') {
+            audioCapture.pauseRecording();
+        } else {
+            audioCapture.resumeRecording();
+        }
+        this.statusCallbacks.forEach(cb => cb(status));
+    }
+}
+
+// Singleton instance
+export const meetingSession = new MeetingSessionService();
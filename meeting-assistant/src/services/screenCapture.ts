@@ -4,6 +4,7 @@
 import { appDataDir } from '@tauri-apps/api/path';
 import { writeBinaryFile, createDir } from '@tauri-apps/api/fs';
 import type { ScreenCapture } from '@/types';
+import { databaseService } from './database';
 
 export interface ScreenCaptureConfig {
     enabled: boolean;
@@ -144,6 +145,17 @@ class ScreenCaptureService {
                 createdAt: new Date(),
             };
 
+            // Persist so the history view can build chapter thumbnails
+            if (capture.imagePath) {
+                await databaseService.saveScreenCapture(
+                    meetingId,
+                    capture.timestamp,
+                    capture.imagePath,
+                    capture.ocrText,
+                    capture.relevanceScore
+                );
+            }
+
             // Notify callbacks
             this.callbacks.forEach(cb => cb(capture));
 
@@ -20,7 +20,8 @@ interface MeetingState {
     meetings: Meeting[];
 
     // Actions
-    startMeeting: (title: string) => void;
+    /** `id` is the meeting's id in the database */
+    startMeeting: (title: string, id?: string) => void;
     pauseMeeting: () => void;
     resumeMeeting: () => void;
     endMeeting: () => void;
@@ -33,9 +34,9 @@ export const useMeetingStore = create<MeetingState>((set, get) => ({
     currentMeeting: null,
     meetings: [],
 
-    startMeeting: (title: string) => {
+    startMeeting: (title: string, id?: string) => {
         const meeting: Meeting = {
-            id: crypto.randomUUID(),
+            id: id ?? crypto.randomUUID(),
             title: title || `Meeting ${new Date().toLocaleDateString()}`,
             startTime: new Date(),
             participants: [],
@@ -39,6 +39,9 @@ export interface TranscriptEntry {
     language: string;
     translation?: string;
     createdAt: Date;
+    // Voice characteristics from the live diarizer, used to re-cluster speakers
+    voicePitch?: number;
+    voiceEnergy?: number;
 }
 
 export interface TranscriptSegment {
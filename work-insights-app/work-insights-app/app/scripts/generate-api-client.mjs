@@ -0,0 +1,205 @@
+// Generates src/services/localApi.ts from the local API's OpenAPI document.
+//
+//   npm run generate:api                     # from the running app
+//   npm run generate:api -- --spec api.json  # from a saved document
+//   npm run generate:api -- --url http://127.0.0.1:47615/openapi.json
+//
+// The app must have the local API enabled for the first two forms, and the
+// token from its settings in LOCAL_API_TOKEN. Only the
+// subset of OpenAPI the app's document uses is supported: object, array,
+// string, integer, number and boolean schemas, nullable, enum, $ref,
+// single-entry allOf and oneOf.
+
+import { readFile, writeFile } from 'node:fs/promises';
+import { fileURLToPath } from 'node:url';
+
+const DEFAULT_URL = 'http://127.0.0.1:47615/openapi.json';
+const OUTPUT = fileURLToPath(new URL('../src/services/localApi.ts', import.meta.url));
+
+function parseArgs(argv) {
+  const args = { url: DEFAULT_URL, spec: null };
+  for (let i = 0; i < argv.length; i++) {
+    if (argv[i] === '--spec') args.spec = argv[++i];
+    else if (argv[i] === '--url') args.url = argv[++i];
+    else throw new Error(`Unknown argument ${argv[i]}`);
+  }
+  return args;
+}
+
+async function loadSpec({ url, spec }) {
+  if (spec) return JSON.parse(await readFile(spec, 'utf8'));
+  const response = await fetch(url, { headers: { Authorization: `Bearer ${process.env.LOCAL_API_TOKEN ?? ''}` } });
+  if (!response.ok) throw new Error(`${url} answered ${response.status}`);
+  return response.json();
+}
+
+/** A TypeScript string literal */
+function quote(value) {
+  return `'${String(value).replace(/\\/g, '\\\\').replace(/'/g, "\\'")}'`;
+}
+
+function refName(ref) {
+  return ref.split('/').pop();
+}
+
+function comment(text, indent = '') {
+  return text ? `${indent}/** ${text.replace(/\s+/g, ' ').trim()} */\n` : '';
+}
+
+function typeOf(schema) {
+  let type;
+  if (schema.$ref) type = refName(schema.$ref);
+  else if (schema.allOf && schema.allOf.length === 1) type = typeOf(schema.allOf[0]);
+  else if (schema.oneOf) type = schema.oneOf.map(typeOf).join(' | ');
+  else if (schema.enum) type = schema.enum.map(quote).join(' | ');
+  else if (schema.type === 'array') type = `${wrap(typeOf(schema.items))}[]`;
+  else if (schema.type === 'integer' || schema.type === 'number') type = 'number';
+  else if (schema.type === 'string' || schema.type === 'boolean') type = schema.type;
+  else if (schema.type === 'object' && schema.properties) type = inlineObject(schema);
+  else if (schema.type === 'object') type = 'Record<string, unknown>';
+  else throw new Error(`Unsupported schema ${JSON.stringify(schema)}`);
+  return schema.nullable ? `${type} | null` : type;
+}
+
+function wrap(type) {
+  return type.includes(' ') ? `(${type})` : type;
+}
+
+function properties(schema, indent) {
+  const required = new Set(schema.required ?? []);
+  return Object.entries(schema.properties)
+    .map(([name, property]) => {
+      const optional = required.has(name) ? '' : '?';
+      return `${comment(property.description, indent)}${indent}${name}${optional}: ${typeOf(property)};\n`;
+    })
+    .join('');
+}
+
+function inlineObject(schema) {
+  return `{ ${properties(schema, '').trim().replace(/\n/g, ' ')} }`;
+}
+
+function declaration(name, schema) {
+  const doc = comment(schema.description);
+  if (schema.type === 'object' && schema.properties) {
+    return `${doc}export interface ${name} {\n${properties(schema, '  ')}}\n`;
+  }
+  return `${doc}export type ${name} = ${typeOf(schema)};\n`;
+}
+
+/** The 200 response's media type and TypeScript type */
+function responseOf(operation) {
+  const [mediaType, media] = Object.entries(operation.responses['200'].content)[0];
+  return { mediaType, type: typeOf(media.schema) };
+}
+
+function method(path, operation) {
+  const { mediaType, type } = responseOf(operation);
+  const doc = comment(operation.summary, '    ');
+  const name = operation.operationId;
+  if (mediaType === 'text/event-stream') {
+    return (
+      `${doc}    ${name}(onEvent: (event: ${type}) => void): EventSource {\n` +
+      `      if (!transport.events) throw new Error('This transport can\\'t stream ${path}');\n` +
+      `      const source = transport.events(${quote(path)});\n` +
+      `      const handle = (message: MessageEvent<string>) => onEvent(JSON.parse(message.data) as ${type});\n` +
+      `      for (const type of EVENT_TYPES) source.addEventListener(type, handle);\n` +
+      `      return source;\n` +
+      `    },\n`
+    );
+  }
+  const read = mediaType === 'application/json' ? 'json' : 'text';
+  return `${doc}    ${name}(): Promise<${type}> {\n      return request(${quote(path)}, '${read}') as Promise<${type}>;\n    },\n`;
+}
+
+function eventTypes(spec) {
+  const events = spec.components.schemas.ApiEvent?.oneOf ?? [];
+  return events.map((event) => spec.components.schemas[refName(event.$ref)].properties.type.enum[0]);
+}
+
+function generate(spec) {
+  const server = spec.servers?.[0]?.url ?? 'http://127.0.0.1:47615';
+  const schemas = Object.entries(spec.components.schemas)
+    .map(([name, schema]) => declaration(name, schema))
+    .join('\n');
+  const methods = Object.entries(spec.paths)
+    .flatMap(([path, item]) => (item.get ? [method(path, item.get)] : []))
+    .join('\n');
+
+  return `// Generated by scripts/generate-api-client.mjs from the local API's OpenAPI
+// document, version ${spec.info.version}. Don't edit by hand: change
+// src-tauri/src/api/openapi.rs and run \`npm run generate:api\`.
+//
+// The dashboard reads through \`tauriTransport\` in src/services/tauri.ts,
+// which answers in-process; scripts and tools outside the app use
+// \`httpTransport\` with the token from the app's settings.
+
+${schemas}
+/** Names the \`/events\` stream sends events under */
+export const EVENT_TYPES = [${eventTypes(spec).map(quote).join(', ')}] as const;
+
+export class LocalApiError extends Error {
+  constructor(
+    public readonly status: number,
+    message: string,
+  ) {
+    super(message);
+    this.name = 'LocalApiError';
+  }
+}
+
+/** A route's status and body */
+export interface LocalApiReply {
+  status: number;
+  body: string;
+}
+
+/** How the client reaches the API */
+export interface LocalApiTransport {
+  /** GETs \`path\` */
+  get(path: string): Promise<LocalApiReply>;
+  /** Opens the server-sent event stream at \`path\`, if the transport can */
+  events?(path: string): EventSource;
+}
+
+/** Requests over HTTP to the app at \`baseUrl\`, with the token from its settings */
+export function httpTransport(token: string, baseUrl = ${quote(server)}): LocalApiTransport {
+  return {
+    async get(path) {
+      const response = await fetch(baseUrl + path, { headers: { Authorization: \`Bearer \${token}\` } });
+      return { status: response.status, body: await response.text() };
+    },
+    // EventSource can't send headers, so the token goes in the query
+    events(path) {
+      return new EventSource(baseUrl + path + '?token=' + encodeURIComponent(token));
+    },
+  };
+}
+
+export type LocalApiClient = ReturnType<typeof createLocalApiClient>;
+
+/** A client for the local API reached through \`transport\` */
+export function createLocalApiClient(transport: LocalApiTransport) {
+  async function request(path: string, read: 'json' | 'text'): Promise<unknown> {
+    const { status, body } = await transport.get(path);
+    if (status < 200 || status >= 300) {
+      let message = body;
+      try {
+        message = (JSON.parse(body) as ApiError).error ?? body;
+      } catch {
+        // Not JSON; use the text as is
+      }
+      throw new LocalApiError(status, message);
+    }
+    return read === 'json' ? JSON.parse(body) : body;
+  }
+
+  return {
+${methods}  };
+}
+`;
+}
+
+const spec = await loadSpec(parseArgs(process.argv.slice(2)));
+await writeFile(OUTPUT, generate(spec));
+console.log(`Wrote ${OUTPUT} from API version ${spec.info.version}`);
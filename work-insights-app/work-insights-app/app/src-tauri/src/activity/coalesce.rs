@@ -0,0 +1,173 @@
+// Coalescing
+// Focus flapping between windows, or a poll briefly finding no window,
+// leaves many short rows for the same window with little or nothing between
+// them. As an activity is written, it is folded into the row before it
+// instead when that row is the same window, idle or not, and ended no more
+// than `coalesce_gap_seconds` before the new one starts; the gap is counted
+// as part of the activity. A gap of 0 turns this off.
+//
+// `compact` does the same to activities already stored, also requiring the
+// same category and project, and vacuums the database afterwards so the file
+// shrinks. Manual and edited activities are never folded, and rows already
+// copied to a Postgres server keep their old duration there.
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::Serialize;
+
+use crate::database::queries::{get_setting, insert_activity, Activity};
+
+const GAP_KEY: &str = "coalesce_gap_seconds";
+pub const DEFAULT_GAP_SECS: i64 = 30;
+
+#[derive(Debug, Serialize)]
+pub struct CompactResult {
+    pub activities_scanned: usize,
+    pub activities_removed: usize,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+struct Stored {
+    id: i64,
+    start: i64,
+    end: i64,
+    app_name: String,
+    window_title: Option<String>,
+    is_idle: bool,
+    category: Option<String>,
+    project: Option<String>,
+    is_manual: bool,
+    is_edited: bool,
+}
+
+impl Stored {
+    /// Whether `next` continues a run starting with this row and so far
+    /// ending at `run_end`
+    fn is_continued_by(&self, next: &Stored, run_end: i64, max_gap: i64) -> bool {
+        !self.is_manual
+            && !next.is_manual
+            && !self.is_edited
+            && !next.is_edited
+            && (0..=max_gap).contains(&(next.start - run_end))
+            && self.app_name == next.app_name
+            && self.window_title == next.window_title
+            && self.is_idle == next.is_idle
+            && self.category == next.category
+            && self.project == next.project
+    }
+}
+
+/// Rows being folded into `first`, ending at `end`
+struct Run<'a> {
+    first: &'a Stored,
+    end: i64,
+    grown: bool,
+}
+
+/// The `coalesce_gap_seconds` setting
+pub fn max_gap(conn: &Connection) -> Result<i64> {
+    Ok(get_setting(conn, GAP_KEY)?
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|secs| *secs >= 0)
+        .unwrap_or(DEFAULT_GAP_SECS))
+}
+
+/// Writes a tracked activity, extending the row before it when it continues
+/// that row, and returns the id of the row holding it
+pub fn record(conn: &Connection, activity: &Activity, max_gap: i64) -> Result<i64> {
+    if max_gap > 0 && !activity.is_manual {
+        let previous = conn
+            .query_row(
+                "SELECT id, timestamp + duration_seconds
+                 FROM activities
+                 WHERE timestamp <= ?1 AND is_manual = 0 AND is_edited = 0 AND app_name = ?2 AND window_title IS ?3 AND is_idle = ?4
+                   AND id = (SELECT id FROM activities WHERE timestamp <= ?1 ORDER BY timestamp DESC, id DESC LIMIT 1)",
+                params![activity.timestamp, activity.app_name, activity.window_title, activity.is_idle],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .optional()?;
+        if let Some((id, end)) = previous {
+            let gap = activity.timestamp - end;
+            if (0..=max_gap).contains(&gap) {
+                conn.execute(
+                    "UPDATE activities SET duration_seconds = ?1 + duration_seconds + ?2 WHERE id = ?3",
+                    params![gap, activity.duration_seconds, id],
+                )?;
+                return Ok(id);
+            }
+        }
+    }
+    insert_activity(conn, activity)
+}
+
+/// Folds each run of continuing rows in the range into its first row
+pub fn compact(conn: &mut Connection, start_timestamp: i64, end_timestamp: i64, max_gap: i64) -> Result<CompactResult> {
+    let bytes_before = database_bytes(conn)?;
+    let tx = conn.transaction()?;
+    let mut scanned = 0;
+    let mut removed = 0;
+    {
+        let mut select = tx.prepare(
+            "SELECT id, timestamp, timestamp + duration_seconds, app_name, window_title, is_idle, category, project, is_manual, is_edited
+             FROM activities
+             WHERE timestamp BETWEEN ?1 AND ?2
+             ORDER BY timestamp, id",
+        )?;
+        let mut extend = tx.prepare("UPDATE activities SET duration_seconds = ?2 - timestamp WHERE id = ?1")?;
+        let mut delete = tx.prepare("DELETE FROM activities WHERE id = ?1")?;
+
+        let rows = select
+            .query_map(params![start_timestamp, end_timestamp], |row| {
+                Ok(Stored {
+                    id: row.get(0)?,
+                    start: row.get(1)?,
+                    end: row.get(2)?,
+                    app_name: row.get(3)?,
+                    window_title: row.get(4)?,
+                    is_idle: row.get(5)?,
+                    category: row.get(6)?,
+                    project: row.get(7)?,
+                    is_manual: row.get(8)?,
+                    is_edited: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut run: Option<Run> = None;
+        for row in &rows {
+            scanned += 1;
+            if let Some(run) = run.as_mut().filter(|run| run.first.is_continued_by(row, run.end, max_gap)) {
+                run.end = run.end.max(row.end);
+                run.grown = true;
+                delete.execute(params![row.id])?;
+                removed += 1;
+                continue;
+            }
+            if let Some(done) = run.replace(Run { first: row, end: row.end, grown: false }).filter(|run| run.grown) {
+                extend.execute(params![done.first.id, done.end])?;
+            }
+        }
+        if let Some(done) = run.filter(|run| run.grown) {
+            extend.execute(params![done.first.id, done.end])?;
+        }
+    }
+    tx.commit()?;
+
+    if removed > 0 {
+        conn.execute_batch("VACUUM")?;
+    }
+    Ok(CompactResult {
+        activities_scanned: scanned,
+        activities_removed: removed,
+        bytes_before,
+        bytes_after: database_bytes(conn)?,
+    })
+}
+
+fn database_bytes(conn: &Connection) -> Result<i64> {
+    conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )
+}
@@ -0,0 +1,148 @@
+// Activity edits
+// Fixes for recorded activities from the dashboard: renaming an app or
+// setting the category or project an activity was misattributed to,
+// deleting time that was tracked by accident, and merging several activities
+// into one. A merge keeps the earliest activity, with the others' durations
+// added to it, and deletes the rest; idle and active time can't be merged
+// together.
+//
+// Edited and merged activities are flagged, and recategorizing, detecting
+// projects, coalescing and rebuilding from raw snapshots leave them as they
+// are. A deleted activity leaves nothing to flag, so rebuilding its range
+// brings the time back. Edits change the local database only: an activity
+// already copied to a Postgres server keeps its old values there.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+
+use crate::database::queries::Activity;
+use crate::projects;
+
+/// Fields to change; those left out keep their value. An empty category or
+/// project clears it.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ActivityUpdate {
+    pub app_name: Option<String>,
+    pub window_title: Option<String>,
+    pub category: Option<String>,
+    pub project: Option<String>,
+}
+
+pub fn get_activity(conn: &Connection, activity_id: i64) -> rusqlite::Result<Option<Activity>> {
+    conn.query_row(
+        "SELECT id, timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project, is_edited
+         FROM activities WHERE id = ?1",
+        [activity_id],
+        |row| {
+            Ok(Activity {
+                id: Some(row.get(0)?),
+                timestamp: row.get(1)?,
+                app_name: row.get(2)?,
+                window_title: row.get(3)?,
+                duration_seconds: row.get(4)?,
+                is_idle: row.get(5)?,
+                category: row.get(6)?,
+                algorithm_version: row.get(7)?,
+                is_manual: row.get(8)?,
+                project: row.get(9)?,
+                is_edited: row.get(10)?,
+            })
+        },
+    )
+    .optional()
+}
+
+pub fn update(conn: &Connection, activity_id: i64, update: &ActivityUpdate) -> Result<Activity, String> {
+    let mut activity = get_activity(conn, activity_id)
+        .map_err(|e| e.to_string())?
+        .ok_or("Activity not found")?;
+
+    if let Some(app_name) = &update.app_name {
+        let app_name = app_name.trim();
+        if app_name.is_empty() {
+            return Err("App name cannot be empty".to_string());
+        }
+        activity.app_name = app_name.to_string();
+    }
+    if let Some(window_title) = &update.window_title {
+        activity.window_title = Some(window_title.trim().to_string()).filter(|title| !title.is_empty());
+    }
+    if let Some(category) = &update.category {
+        activity.category = match category.trim() {
+            "" => None,
+            name => Some(
+                conn.query_row("SELECT name FROM categories WHERE name = ?1", [name], |row| row.get::<_, String>(0))
+                    .optional()
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Unknown category \"{}\"", name))?,
+            ),
+        };
+    }
+
+    if let Some(project) = &update.project {
+        activity.project = Some(project.trim().to_string()).filter(|project| !project.is_empty());
+    }
+
+    activity.is_edited = true;
+    conn.execute(
+        "UPDATE activities SET app_name = ?1, window_title = ?2, category = ?3, project = ?4, is_edited = 1 WHERE id = ?5",
+        params![activity.app_name, activity.window_title, activity.category, activity.project, activity_id],
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(project) = &activity.project {
+        projects::register(conn, project).map_err(|e| e.to_string())?;
+    }
+    Ok(activity)
+}
+
+pub fn delete(conn: &Connection, activity_id: i64) -> Result<(), String> {
+    let deleted = conn
+        .execute("DELETE FROM activities WHERE id = ?1", [activity_id])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err("Activity not found".to_string());
+    }
+    Ok(())
+}
+
+/// Folds the activities into the earliest of them and returns it
+pub fn merge(conn: &mut Connection, activity_ids: &[i64]) -> Result<Activity, String> {
+    let mut ids = activity_ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() < 2 {
+        return Err("Select at least two activities to merge".to_string());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut activities = Vec::with_capacity(ids.len());
+    for id in &ids {
+        let activity = get_activity(&tx, *id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Activity {} not found", id))?;
+        activities.push(activity);
+    }
+    if activities.iter().any(|activity| activity.is_idle != activities[0].is_idle) {
+        return Err("Idle and active time can't be merged".to_string());
+    }
+
+    activities.sort_by_key(|activity| (activity.timestamp, activity.id));
+    let total: i64 = activities.iter().map(|activity| activity.duration_seconds as i64).sum();
+    let mut merged = activities.remove(0);
+    merged.duration_seconds = i32::try_from(total).map_err(|_| "Merged activity would be too long".to_string())?;
+    merged.is_edited = true;
+
+    tx.execute(
+        "UPDATE activities SET duration_seconds = ?1, is_edited = 1 WHERE id = ?2",
+        params![merged.duration_seconds, merged.id],
+    )
+    .map_err(|e| e.to_string())?;
+    for activity in &activities {
+        tx.execute("DELETE FROM activities WHERE id = ?1", params![activity.id])
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    println!("✅ Merged {} activities into {}", activities.len() + 1, merged.app_name);
+    Ok(merged)
+}
@@ -0,0 +1,160 @@
+// Untracked gaps
+// Stretches of the timeline without any activity, idle or not: the tracker
+// was off or paused, the app wasn't running, or the machine was asleep or
+// shut down. `find` lists those at least a minimum length within a range so
+// the daily timeline can show them rather than silently skipping hours. The
+// time up to the start of the run the monitor is still accumulating isn't a
+// gap, nor is anything after now.
+//
+// A gap can be annotated with a label ("commute", "dentist") for the record;
+// it stays untracked time and counts toward nothing. To count a gap as work
+// instead, add a manual activity over it. Annotations are kept in
+// `gap_annotations` and attached to whichever gap they overlap, so they
+// survive a gap later being narrowed by a manual activity.
+
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::Serialize;
+
+use crate::database::queries::Activity;
+
+pub const DEFAULT_MIN_GAP_MINUTES: i64 = 15;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GapAnnotation {
+    pub id: i64,
+    pub start: i64,
+    pub end: i64,
+    pub label: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineGap {
+    pub start: i64,
+    pub end: i64,
+    pub duration_seconds: i64,
+    pub annotation: Option<GapAnnotation>,
+}
+
+/// Gaps of at least `min_seconds` within `start..end`, oldest first. `open`
+/// is the run the monitor hasn't written yet, if any.
+pub fn find(
+    conn: &Connection,
+    start: i64,
+    end: i64,
+    min_seconds: i64,
+    open: Option<&Activity>,
+    now: i64,
+) -> Result<Vec<TimelineGap>> {
+    let end = end.min(open.map_or(now, |open| open.timestamp.min(now)));
+    if start >= end {
+        return Ok(Vec::new());
+    }
+
+    // Rows starting before the range may still run into it
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, timestamp + duration_seconds
+         FROM activities
+         WHERE timestamp < ?2 AND timestamp + duration_seconds > ?1
+         ORDER BY timestamp",
+    )?;
+    let spans = stmt
+        .query_map(params![start, end], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+
+    let annotations = get_annotations(conn, start, end)?;
+    let mut gaps = Vec::new();
+    let mut covered_until = start;
+    for (span_start, span_end) in spans.into_iter().chain([(end, end)]) {
+        let gap_end = span_start.min(end);
+        if gap_end - covered_until >= min_seconds.max(1) {
+            gaps.push(TimelineGap {
+                start: covered_until,
+                end: gap_end,
+                duration_seconds: gap_end - covered_until,
+                annotation: annotations
+                    .iter()
+                    .filter(|a| a.start < gap_end && a.end > covered_until)
+                    .max_by_key(|a| a.end.min(gap_end) - a.start.max(covered_until))
+                    .cloned(),
+            });
+        }
+        covered_until = covered_until.max(span_end);
+    }
+    Ok(gaps)
+}
+
+/// Annotations overlapping `start..end`, oldest first
+pub fn get_annotations(conn: &Connection, start: i64, end: i64) -> Result<Vec<GapAnnotation>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, start_time, end_time, label, created_at
+         FROM gap_annotations
+         WHERE start_time < ?2 AND end_time > ?1
+         ORDER BY start_time",
+    )?;
+    let annotations = stmt.query_map(params![start, end], row_to_annotation)?.collect();
+    annotations
+}
+
+/// Labels the untracked time from `start` to `end`, replacing annotations it
+/// overlaps. Fails if any activity was recorded in the span.
+pub fn annotate(conn: &mut Connection, start: i64, end: i64, label: &str) -> std::result::Result<GapAnnotation, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("A gap annotation needs a label".to_string());
+    }
+    if start >= end {
+        return Err("The gap must end after it starts".to_string());
+    }
+    let now = Utc::now().timestamp();
+    if end > now {
+        return Err("Gaps can't extend into the future".to_string());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let recorded: Option<String> = tx
+        .query_row(
+            "SELECT app_name FROM activities
+             WHERE timestamp < ?2 AND timestamp + duration_seconds > ?1
+             ORDER BY timestamp LIMIT 1",
+            params![start, end],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if let Some(app_name) = recorded {
+        return Err(format!("Time was recorded in this span ({}), so it isn't a gap", app_name));
+    }
+
+    tx.execute(
+        "DELETE FROM gap_annotations WHERE start_time < ?2 AND end_time > ?1",
+        params![start, end],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO gap_annotations (start_time, end_time, label, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![start, end, label, now],
+    )
+    .map_err(|e| e.to_string())?;
+    let annotation = GapAnnotation { id: tx.last_insert_rowid(), start, end, label: label.to_string(), created_at: now };
+    tx.commit().map_err(|e| e.to_string())?;
+
+    println!("✅ Annotated untracked gap as {} ({}s)", label, end - start);
+    Ok(annotation)
+}
+
+pub fn delete_annotation(conn: &Connection, annotation_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM gap_annotations WHERE id = ?1", params![annotation_id])?;
+    Ok(())
+}
+
+fn row_to_annotation(row: &rusqlite::Row) -> Result<GapAnnotation> {
+    Ok(GapAnnotation {
+        id: row.get(0)?,
+        start: row.get(1)?,
+        end: row.get(2)?,
+        label: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
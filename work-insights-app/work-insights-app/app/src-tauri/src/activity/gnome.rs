@@ -0,0 +1,81 @@
+// Linux GNOME (Wayland) activity tracking
+// Mutter implements none of the toplevel protocols, so on GNOME we ask the
+// shell over D-Bus. org.gnome.Shell.Introspect lists the windows with their
+// app id, title and focus. GNOME only answers callers it trusts, so users
+// have to opt in once with:
+//   gsettings set org.gnome.shell introspect true
+// Idle time comes from Mutter's IdleMonitor, which needs no opt-in.
+
+use std::collections::HashMap;
+use chrono::Utc;
+use zbus::blocking::Connection;
+use zbus::zvariant::OwnedValue;
+
+use super::ActivitySnapshot;
+
+type WindowProperties = HashMap<String, OwnedValue>;
+
+/// Whether this is a GNOME session, going by XDG_CURRENT_DESKTOP
+/// (e.g. "ubuntu:GNOME")
+pub fn is_supported() -> bool {
+    std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktops| {
+        desktops.split(':').any(|desktop| desktop.eq_ignore_ascii_case("gnome"))
+    })
+}
+
+/// Whether the shell answers our window queries, i.e. introspection is on
+pub fn has_access() -> bool {
+    get_windows().is_some()
+}
+
+pub fn get_active_window() -> Option<ActivitySnapshot> {
+    let windows = get_windows()?;
+    let focused = windows
+        .values()
+        .find(|window| window.get("has-focus").and_then(|v| bool::try_from(v).ok()) == Some(true))?;
+    let app_name = string_property(focused, "app-id")
+        .map(|app_id| app_id.trim_end_matches(".desktop").to_string())
+        .or_else(|| string_property(focused, "wm-class"))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    Some(ActivitySnapshot {
+        app_name,
+        window_title: string_property(focused, "title").unwrap_or_default(),
+        timestamp: Utc::now().timestamp(),
+    })
+}
+
+/// Seconds since the last input, as tracked by the compositor
+pub fn idle_seconds() -> Option<u64> {
+    let conn = Connection::session().ok()?;
+    let reply = conn
+        .call_method(
+            Some("org.gnome.Mutter.IdleMonitor"),
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            Some("org.gnome.Mutter.IdleMonitor"),
+            "GetIdletime",
+            &(),
+        )
+        .ok()?;
+    let idle_ms: u64 = reply.body().deserialize().ok()?;
+    Some(idle_ms / 1000)
+}
+
+fn get_windows() -> Option<HashMap<u64, WindowProperties>> {
+    let conn = Connection::session().ok()?;
+    let reply = conn
+        .call_method(
+            Some("org.gnome.Shell"),
+            "/org/gnome/Shell/Introspect",
+            Some("org.gnome.Shell.Introspect"),
+            "GetWindows",
+            &(),
+        )
+        .ok()?;
+    reply.body().deserialize().ok()
+}
+
+fn string_property(window: &WindowProperties, key: &str) -> Option<String> {
+    let value = window.get(key)?.try_clone().ok()?;
+    String::try_from(value).ok().filter(|value| !value.is_empty())
+}
@@ -0,0 +1,40 @@
+// Linux suspend notifications
+// systemd-logind broadcasts PrepareForSleep(true) on the system bus just
+// before the machine suspends or hibernates, whatever the display server.
+
+use std::thread;
+use chrono::Utc;
+use zbus::blocking::proxy::SignalIterator;
+use zbus::blocking::{Connection, Proxy};
+
+use super::record_suspend;
+
+/// Starts a thread that records each suspend. Returns false if logind isn't
+/// reachable.
+pub fn watch_suspend() -> bool {
+    let Some(signals) = subscribe() else {
+        return false;
+    };
+
+    thread::spawn(move || {
+        for message in signals {
+            // The argument is true going to sleep and false on resume
+            if message.body().deserialize::<bool>().unwrap_or(false) {
+                record_suspend(Utc::now().timestamp());
+            }
+        }
+    });
+    true
+}
+
+fn subscribe() -> Option<SignalIterator<'static>> {
+    let conn = Connection::system().ok()?;
+    let proxy = Proxy::new(
+        &conn,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .ok()?;
+    proxy.receive_signal("PrepareForSleep").ok()
+}
@@ -0,0 +1,135 @@
+// macOS activity tracking
+// The frontmost app comes from NSWorkspace. Its focused window title is read
+// through the Accessibility API, which only works once the user has allowed
+// the app under System Settings > Privacy & Security > Accessibility. Until
+// then the title falls back to the CoreGraphics window list, which macOS
+// leaves blank unless Screen Recording access has been granted. Idle time
+// comes from the HID event source and needs no permission.
+
+use std::ptr::NonNull;
+use std::sync::Once;
+use chrono::Utc;
+use objc2_app_kit::NSWorkspace;
+use objc2_application_services::{
+    kAXTrustedCheckOptionPrompt, AXError, AXIsProcessTrusted, AXIsProcessTrustedWithOptions, AXUIElement,
+};
+use objc2_core_foundation::{kCFBooleanTrue, CFArray, CFDictionary, CFNumber, CFRetained, CFString, CFType};
+use objc2_core_graphics::{
+    kCGNullWindowID, kCGWindowLayer, kCGWindowName, kCGWindowOwnerPID, CGEventSource, CGEventSourceStateID,
+    CGEventType, CGWindowListCopyWindowInfo, CGWindowListOption,
+};
+
+use super::ActivitySnapshot;
+
+static PROMPT: Once = Once::new();
+
+/// kCGAnyInputEventType, which the bindings don't name
+const ANY_INPUT_EVENT: CGEventType = CGEventType(u32::MAX);
+
+pub fn get_active_window() -> Option<ActivitySnapshot> {
+    let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+    let pid = app.processIdentifier();
+    let app_name = app
+        .localizedName()
+        .or_else(|| app.bundleIdentifier())?
+        .to_string();
+
+    let focused_title = if has_accessibility(true) { focused_window_title(pid) } else { None };
+    let window_title = focused_title
+        .or_else(|| on_screen_window_title(pid))
+        .unwrap_or_default();
+
+    Some(ActivitySnapshot {
+        app_name,
+        window_title,
+        timestamp: Utc::now().timestamp(),
+    })
+}
+
+/// Hides the frontmost app, as macOS has no public way to minimize another
+/// app's window without the Accessibility permission
+pub fn hide_frontmost() -> bool {
+    NSWorkspace::sharedWorkspace()
+        .frontmostApplication()
+        .is_some_and(|app| app.hide())
+}
+
+/// Seconds since the last keyboard, mouse or trackpad input
+pub fn idle_seconds() -> Option<u64> {
+    let seconds = CGEventSource::seconds_since_last_event_type(
+        CGEventSourceStateID::CombinedSessionState,
+        ANY_INPUT_EVENT,
+    );
+    (seconds.is_finite() && seconds >= 0.0).then_some(seconds as u64)
+}
+
+/// Whether window titles can be read through the Accessibility API. With
+/// `prompt`, the first failed check of the run asks macOS to show its
+/// permission dialog; the grant applies without restarting the app.
+pub fn has_accessibility(prompt: bool) -> bool {
+    // SAFETY: AXIsProcessTrusted has no preconditions.
+    if unsafe { AXIsProcessTrusted() } {
+        return true;
+    }
+    if prompt {
+        PROMPT.call_once(request_accessibility);
+    }
+    false
+}
+
+fn request_accessibility() {
+    // SAFETY: both statics are provided by the system frameworks and are
+    // immutable for the lifetime of the process.
+    let (key, value) = unsafe { (kAXTrustedCheckOptionPrompt, kCFBooleanTrue) };
+    let Some(value) = value else { return };
+
+    let options = CFDictionary::from_slices(&[key], &[value]);
+    // SAFETY: the options dictionary maps CFString keys to CFBoolean values
+    // as the function expects. Prompting happens asynchronously.
+    unsafe { AXIsProcessTrustedWithOptions(Some(options.as_opaque())) };
+}
+
+fn focused_window_title(pid: libc::pid_t) -> Option<String> {
+    // SAFETY: creating an element for a pid is always valid; if the process
+    // has exited, attribute lookups on it simply fail.
+    let app = unsafe { AXUIElement::new_application(pid) };
+    let window = copy_attribute(&app, "AXFocusedWindow")?.downcast::<AXUIElement>().ok()?;
+    let title = copy_attribute(&window, "AXTitle")?.downcast::<CFString>().ok()?;
+    Some(title.to_string()).filter(|title| !title.is_empty())
+}
+
+fn copy_attribute(element: &AXUIElement, attribute: &'static str) -> Option<CFRetained<CFType>> {
+    let attribute = CFString::from_static_str(attribute);
+    let mut value: *const CFType = std::ptr::null();
+    // SAFETY: `value` is a valid out pointer. On success it holds a +1
+    // reference (Copy rule), which CFRetained takes ownership of.
+    let result = unsafe { element.copy_attribute_value(&attribute, NonNull::from(&mut value)) };
+    if result != AXError::Success {
+        return None;
+    }
+    NonNull::new(value.cast_mut()).map(|value| unsafe { CFRetained::from_raw(value) })
+}
+
+/// Title of the frontmost normal-layer window owned by `pid`
+fn on_screen_window_title(pid: libc::pid_t) -> Option<String> {
+    let options = CGWindowListOption::OptionOnScreenOnly | CGWindowListOption::ExcludeDesktopElements;
+    let windows = CGWindowListCopyWindowInfo(options, kCGNullWindowID)?;
+    // SAFETY: the window list is documented as an array of dictionaries
+    // keyed by the kCGWindow* strings.
+    let windows: CFRetained<CFArray<CFDictionary<CFString, CFType>>> =
+        unsafe { CFRetained::cast_unchecked(windows) };
+    // SAFETY: the key statics are provided by CoreGraphics and never change.
+    let (owner_key, layer_key, name_key) = unsafe { (kCGWindowOwnerPID, kCGWindowLayer, kCGWindowName) };
+
+    let number = |window: &CFDictionary<CFString, CFType>, key: &CFString| {
+        window.get(key)?.downcast::<CFNumber>().ok()?.as_i32()
+    };
+
+    // The list is ordered front to back, so the first match is the key window
+    windows
+        .iter()
+        .filter(|window| number(window, owner_key) == Some(pid) && number(window, layer_key) == Some(0))
+        .find_map(|window| window.get(name_key)?.downcast::<CFString>().ok())
+        .map(|name| name.to_string())
+        .filter(|name| !name.is_empty())
+}
@@ -0,0 +1,160 @@
+// Manual activities
+// Time spent away from the computer, at a whiteboard or reading on paper,
+// entered by hand so it shows up in the stats. A manual activity is an
+// ordinary activity row flagged `is_manual`, named after its label and
+// counted as active time. It may not overlap tracked active time, nor the
+// run the monitor is still accumulating. Idle time recorded in its span is
+// cut out instead, since the user was working rather than away, so the span
+// isn't counted twice. Rebuilding from raw snapshots and recategorizing
+// leave manual activities alone.
+
+use chrono::{Local, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::categories;
+use crate::projects;
+use crate::database::queries::{insert_activity, Activity};
+
+/// Longest span one manual activity may cover
+const MAX_DURATION_SECS: i64 = 24 * 60 * 60;
+
+/// Records `label` as active from `start` to `end`. `open` is the run the
+/// monitor hasn't written yet, if any.
+pub fn add(
+    conn: &mut Connection,
+    start: i64,
+    end: i64,
+    label: &str,
+    category: Option<&str>,
+    open: Option<&Activity>,
+    now: i64,
+) -> Result<Activity, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("A manual activity needs a label".to_string());
+    }
+    if start >= end {
+        return Err("The activity must end after it starts".to_string());
+    }
+    if end > now {
+        return Err("The activity can't end in the future".to_string());
+    }
+    if end - start > MAX_DURATION_SECS {
+        return Err("A manual activity can't be longer than a day".to_string());
+    }
+
+    let category = match category.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(name) => Some(
+            conn.query_row("SELECT name FROM categories WHERE name = ?1", [name], |row| row.get::<_, String>(0))
+                .optional()
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Unknown category \"{}\"", name))?,
+        ),
+        None => None,
+    };
+
+    if let Some(open) = open.filter(|open| !open.is_idle) {
+        if open.timestamp < end && open.timestamp + open.duration_seconds as i64 > start {
+            return Err(format!("Overlaps {} being tracked since {}", open.app_name, clock(open.timestamp)));
+        }
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let overlapping = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project, is_edited
+                 FROM activities
+                 WHERE timestamp < ?2 AND timestamp + duration_seconds > ?1
+                 ORDER BY timestamp",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![start, end], |row| {
+                Ok(Activity {
+                    id: Some(row.get(0)?),
+                    timestamp: row.get(1)?,
+                    app_name: row.get(2)?,
+                    window_title: row.get(3)?,
+                    duration_seconds: row.get(4)?,
+                    is_idle: row.get(5)?,
+                    category: row.get(6)?,
+                    algorithm_version: row.get(7)?,
+                    is_manual: row.get(8)?,
+                    project: row.get(9)?,
+                    is_edited: row.get(10)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?;
+        rows
+    };
+
+    let active: Vec<&Activity> = overlapping.iter().filter(|activity| !activity.is_idle).collect();
+    if let Some(first) = active.first() {
+        return Err(format!(
+            "Overlaps {} recorded activit{}, starting with {} at {}",
+            active.len(),
+            if active.len() == 1 { "y" } else { "ies" },
+            first.app_name,
+            clock(first.timestamp)
+        ));
+    }
+
+    for idle in &overlapping {
+        let idle_end = idle.timestamp + idle.duration_seconds as i64;
+        if idle.timestamp < start {
+            tx.execute("UPDATE activities SET duration_seconds = ?1 WHERE id = ?2", params![start - idle.timestamp, idle.id])
+        } else {
+            tx.execute("DELETE FROM activities WHERE id = ?1", params![idle.id])
+        }
+        .map_err(|e| e.to_string())?;
+        if idle_end > end {
+            let rest = Activity {
+                id: None,
+                timestamp: end,
+                window_title: idle.window_title.clone(),
+                duration_seconds: (idle_end - end) as i32,
+                app_name: idle.app_name.clone(),
+                category: idle.category.clone(),
+                project: idle.project.clone(),
+                ..*idle
+            };
+            insert_activity(&tx, &rest).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut activity = Activity {
+        id: None,
+        timestamp: start,
+        app_name: label.to_string(),
+        window_title: None,
+        duration_seconds: (end - start) as i32,
+        is_idle: false,
+        category,
+        algorithm_version: None,
+        is_manual: true,
+        project: None,
+        is_edited: false,
+    };
+    if activity.category.is_none() {
+        // Done here rather than by `insert_activity` so the result has it
+        activity.category = categories::categorize(&categories::get_rules(&tx).map_err(|e| e.to_string())?, &activity);
+    }
+    activity.project = projects::Detector::load(&tx).map_err(|e| e.to_string())?.detect(&activity);
+    activity.id = Some(insert_activity(&tx, &activity).map_err(|e| e.to_string())?);
+    tx.commit().map_err(|e| e.to_string())?;
+
+    println!("✅ Added manual activity {} ({}s)", label, end - start);
+    Ok(activity)
+}
+
+/// Local "HH:MM" of a timestamp, for error messages
+fn clock(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%H:%M").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
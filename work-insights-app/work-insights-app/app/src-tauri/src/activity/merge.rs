@@ -0,0 +1,358 @@
+// Merging engine
+// Turns the stream of raw monitor polls into activities. The live monitor and
+// `rebuild_activities` share this code, and every algorithm change gets a new
+// version number, so improvements can be replayed over stored raw snapshots
+// and each activity row records which version produced it.
+//
+// Versions:
+//   1  Legacy monitor behaviour: on a switch, only the last sampling interval
+//      was recorded for the previous window. Kept to reproduce old numbers.
+//   2  A run of identical polls becomes one activity spanning from its first
+//      poll to the poll that ended it. Polls without a foreground window end
+//      the run, and gaps longer than a few intervals (sleep, crash) close it
+//      one interval after its last poll.
+//   3  As 2, plus idle detection: once input has stopped for the idle
+//      timeout, the run ends at the last input and an idle activity covers
+//      the time until input resumes.
+//   4  As 3, plus a locked session is idle from the moment it was locked,
+//      whatever window the platform still reports as foreground.
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::{coalesce, ActivitySnapshot};
+use crate::database::queries::{get_setting, Activity};
+use crate::database::snapshots::{get_raw_snapshots, RawSnapshot};
+
+pub const CURRENT_ALGORITHM_VERSION: i64 = 4;
+pub const ALGORITHM_VERSIONS: &[i64] = &[1, 2, 3, 4];
+
+/// App name recorded for idle activities
+pub const IDLE_APP_NAME: &str = "Idle";
+
+/// Polls further apart than this many intervals are treated as a gap
+pub const MAX_GAP_INTERVALS: i64 = 3;
+pub const DEFAULT_IDLE_TIMEOUT_SECS: i64 = 300;
+pub const DEFAULT_SAMPLING_INTERVAL_SECS: i64 = 10;
+/// Bounds for the `sampling_interval_seconds` setting
+pub const MIN_SAMPLING_INTERVAL_SECS: i64 = 1;
+pub const MAX_SAMPLING_INTERVAL_SECS: i64 = 300;
+
+struct Segment {
+    app_name: String,
+    window_title: String,
+    is_idle: bool,
+    started_at: i64,
+    last_seen: i64,
+}
+
+/// What a single poll observed
+enum Poll<'a> {
+    Window(&'a ActivitySnapshot),
+    /// There has been no input for the idle timeout, or the session is
+    /// locked
+    Idle,
+}
+
+pub struct Merger {
+    version: i64,
+    poll_interval: i64,
+    idle_timeout: i64,
+    open: Option<Segment>,
+}
+
+impl Merger {
+    pub fn new(version: i64, poll_interval: i64, idle_timeout: i64) -> Result<Self, String> {
+        if !ALGORITHM_VERSIONS.contains(&version) {
+            return Err(format!("Unknown merging algorithm version {}", version));
+        }
+        Ok(Self { version, poll_interval, idle_timeout, open: None })
+    }
+
+    /// Feeds one poll and returns the activity it completed, if any.
+    /// `idle_seconds` is the time since the last input, when known, and
+    /// `locked_since` when the session was locked, if it is.
+    pub fn push(
+        &mut self,
+        snapshot: Option<&ActivitySnapshot>,
+        timestamp: i64,
+        idle_seconds: Option<i64>,
+        locked_since: Option<i64>,
+    ) -> Option<Activity> {
+        let idle_seconds = if self.version >= 3 { idle_seconds } else { None };
+        let locked_since = if self.version >= 4 { locked_since } else { None };
+        let poll = match (snapshot, locked_since) {
+            (_, Some(_)) => Some(Poll::Idle),
+            (Some(s), None) => Some(match idle_seconds {
+                Some(idle) if idle >= self.idle_timeout => Poll::Idle,
+                _ => Poll::Window(s),
+            }),
+            (None, None) => None,
+        };
+        // When the idle state flips, the run changed at the last input
+        // rather than at this poll
+        let last_input = idle_seconds.map(|idle| timestamp - idle).or(locked_since);
+
+        match self.version {
+            1 => self.push_legacy(snapshot, timestamp),
+            _ => self.push_segments(poll, timestamp, last_input),
+        }
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: i64) {
+        self.idle_timeout = idle_timeout;
+    }
+
+    /// Takes effect for the gap between the latest poll and the next one
+    pub fn set_poll_interval(&mut self, poll_interval: i64) {
+        self.poll_interval = poll_interval;
+    }
+
+    /// Closes the run in progress at its last poll
+    pub fn finish(&mut self) -> Option<Activity> {
+        if self.version == 1 {
+            return None;
+        }
+        let segment = self.open.take()?;
+        let end = segment.last_seen;
+        self.to_activity(segment, end)
+    }
+
+    /// The run in progress as an activity lasting until `now`, without
+    /// closing it
+    pub fn current(&self, now: i64) -> Option<Activity> {
+        let segment = self.open.as_ref()?;
+        Some(Activity {
+            id: None,
+            timestamp: segment.started_at,
+            app_name: segment.app_name.clone(),
+            window_title: (!segment.is_idle).then(|| segment.window_title.clone()),
+            duration_seconds: (now.max(segment.last_seen) - segment.started_at) as i32,
+            is_idle: segment.is_idle,
+            category: None,
+            algorithm_version: Some(self.version),
+            is_manual: false,
+            project: None,
+            is_edited: false,
+        })
+    }
+
+    fn push_segments(&mut self, poll: Option<Poll>, timestamp: i64, last_input: Option<i64>) -> Option<Activity> {
+        if let Some(segment) = self.open.take_if(|s| timestamp - s.last_seen > self.poll_interval * MAX_GAP_INTERVALS) {
+            let end = segment.last_seen + self.poll_interval;
+            let completed = self.to_activity(segment, end);
+            self.open = poll.map(|p| Segment::start(&p, timestamp));
+            return completed;
+        }
+
+        match (self.open.as_mut(), poll) {
+            (Some(segment), Some(p)) if segment.matches(&p) => {
+                segment.last_seen = timestamp;
+                None
+            }
+            (Some(segment), Some(p)) if segment.is_idle != matches!(p, Poll::Idle) => {
+                let boundary = last_input.unwrap_or(timestamp).clamp(segment.started_at, timestamp);
+                let previous = std::mem::replace(segment, Segment::start(&p, boundary));
+                segment.last_seen = timestamp;
+                self.to_activity(previous, boundary)
+            }
+            (_, poll) => {
+                let completed = self.open.take().and_then(|segment| self.to_activity(segment, timestamp));
+                self.open = poll.map(|p| Segment::start(&p, timestamp));
+                completed
+            }
+        }
+    }
+
+    fn push_legacy(&mut self, snapshot: Option<&ActivitySnapshot>, timestamp: i64) -> Option<Activity> {
+        let snapshot = snapshot?;
+        let poll = Poll::Window(snapshot);
+        let Some(segment) = self.open.as_mut() else {
+            self.open = Some(Segment::start(&poll, timestamp));
+            return None;
+        };
+
+        if segment.matches(&poll) {
+            segment.last_seen = timestamp;
+            return None;
+        }
+
+        let previous = std::mem::replace(segment, Segment::start(&poll, timestamp));
+        let interval_start = previous.last_seen;
+        self.to_activity(Segment { started_at: interval_start, ..previous }, timestamp)
+    }
+
+    fn to_activity(&self, segment: Segment, end: i64) -> Option<Activity> {
+        let duration = end - segment.started_at;
+        (duration > 0).then_some(Activity {
+            id: None,
+            timestamp: segment.started_at,
+            app_name: segment.app_name,
+            window_title: (!segment.is_idle).then_some(segment.window_title),
+            duration_seconds: duration as i32,
+            is_idle: segment.is_idle,
+            category: None,
+            algorithm_version: Some(self.version),
+            is_manual: false,
+            project: None,
+            is_edited: false,
+        })
+    }
+}
+
+impl Segment {
+    fn start(poll: &Poll, timestamp: i64) -> Self {
+        let (app_name, window_title, is_idle) = match poll {
+            Poll::Window(s) => (s.app_name.clone(), s.window_title.clone(), false),
+            Poll::Idle => (IDLE_APP_NAME.to_string(), String::new(), true),
+        };
+        Self { app_name, window_title, is_idle, started_at: timestamp, last_seen: timestamp }
+    }
+
+    fn matches(&self, poll: &Poll) -> bool {
+        match poll {
+            Poll::Window(s) => !self.is_idle && self.app_name == s.app_name && self.window_title == s.window_title,
+            Poll::Idle => self.is_idle,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RebuildResult {
+    pub algorithm_version: i64,
+    /// The span actually rebuilt: from the first to the last raw snapshot
+    /// found in the requested range
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub snapshots_replayed: usize,
+    pub activities_deleted: usize,
+    pub activities_created: usize,
+}
+
+/// Replaces the activities in the range with ones derived from the stored raw
+/// snapshots. Only the span covered by raw snapshots is touched. When the
+/// range reaches the present, the run still in progress is left for the live
+/// monitor to record. Manual activities aren't derived from snapshots and
+/// are kept, as are activities edited by hand; rebuilt activities are cut
+/// short where they overlap either. An activity running into the span from
+/// before it keeps only its part before the span.
+pub fn rebuild_activities(
+    conn: &mut Connection,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    algorithm_version: i64,
+    poll_interval: i64,
+    now: i64,
+) -> Result<RebuildResult, String> {
+    let idle_timeout = idle_timeout(conn).map_err(|e| e.to_string())?;
+    let mut merger = Merger::new(algorithm_version, poll_interval, idle_timeout)?;
+    let snapshots = get_raw_snapshots(conn, start_timestamp, end_timestamp).map_err(|e| e.to_string())?;
+    let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) else {
+        return Err("No raw snapshots were recorded in this range".to_string());
+    };
+    let (covered_start, covered_end) = (first.timestamp, last.timestamp);
+
+    let mut activities: Vec<Activity> = snapshots
+        .iter()
+        .filter_map(|raw| merger.push(as_snapshot(raw).as_ref(), raw.timestamp, raw.idle_seconds, raw.locked_since))
+        .collect();
+    let still_tracking = now - covered_end <= poll_interval * MAX_GAP_INTERVALS;
+    if !still_tracking {
+        activities.extend(merger.finish());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE activities SET duration_seconds = ?1 - timestamp
+         WHERE timestamp < ?1 AND timestamp + duration_seconds > ?1 AND is_manual = 0 AND is_edited = 0",
+        params![covered_start],
+    )
+    .map_err(|e| e.to_string())?;
+    let deleted = tx
+        .execute(
+            "DELETE FROM activities WHERE timestamp BETWEEN ?1 AND ?2 AND is_manual = 0 AND is_edited = 0",
+            params![covered_start, covered_end],
+        )
+        .map_err(|e| e.to_string())?;
+    let rebuilt_end = activities.iter().map(|a| a.timestamp + a.duration_seconds as i64).max().unwrap_or(covered_end);
+    let kept = kept_spans(&tx, covered_start, rebuilt_end).map_err(|e| e.to_string())?;
+    let max_gap = coalesce::max_gap(&tx).map_err(|e| e.to_string())?;
+    let mut created = 0;
+    let mut previous_id = None;
+    for activity in activities.iter().flat_map(|activity| outside(activity, &kept)) {
+        let id = coalesce::record(&tx, &activity, max_gap).map_err(|e| e.to_string())?;
+        if previous_id != Some(id) {
+            created += 1;
+        }
+        previous_id = Some(id);
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(RebuildResult {
+        algorithm_version,
+        start_timestamp: covered_start,
+        end_timestamp: covered_end,
+        snapshots_replayed: snapshots.len(),
+        activities_deleted: deleted,
+        activities_created: created,
+    })
+}
+
+/// `(start, end)` of the manual and edited activities overlapping the span,
+/// by start
+fn kept_spans(conn: &Connection, start: i64, end: i64) -> rusqlite::Result<Vec<(i64, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, timestamp + duration_seconds FROM activities
+         WHERE (is_manual = 1 OR is_edited = 1) AND timestamp < ?2 AND timestamp + duration_seconds > ?1
+         ORDER BY timestamp",
+    )?;
+    let spans = stmt.query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    spans.collect()
+}
+
+/// The parts of `activity` outside the `kept` spans
+fn outside(activity: &Activity, kept: &[(i64, i64)]) -> Vec<Activity> {
+    let end = activity.timestamp + activity.duration_seconds as i64;
+    let mut pieces = Vec::new();
+    let mut cursor = activity.timestamp;
+    for &(kept_start, kept_end) in kept {
+        if kept_start >= end {
+            break;
+        }
+        if kept_start > cursor {
+            pieces.push((cursor, kept_start));
+        }
+        cursor = cursor.max(kept_end);
+    }
+    if cursor < end {
+        pieces.push((cursor, end));
+    }
+    pieces
+        .into_iter()
+        .map(|(start, end)| Activity { timestamp: start, duration_seconds: (end - start) as i32, ..activity.clone() })
+        .collect()
+}
+
+/// The `idle_timeout_seconds` setting
+pub fn idle_timeout(conn: &Connection) -> rusqlite::Result<i64> {
+    Ok(get_setting(conn, "idle_timeout_seconds")?
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS))
+}
+
+/// The `sampling_interval_seconds` setting, kept within bounds
+pub fn sampling_interval(conn: &Connection) -> rusqlite::Result<i64> {
+    Ok(get_setting(conn, "sampling_interval_seconds")?
+        .and_then(|v| v.parse::<i64>().ok())
+        .map(|secs| secs.clamp(MIN_SAMPLING_INTERVAL_SECS, MAX_SAMPLING_INTERVAL_SECS))
+        .unwrap_or(DEFAULT_SAMPLING_INTERVAL_SECS))
+}
+
+fn as_snapshot(raw: &RawSnapshot) -> Option<ActivitySnapshot> {
+    raw.app_name.as_ref().map(|app_name| ActivitySnapshot {
+        app_name: app_name.clone(),
+        window_title: raw.window_title.clone().unwrap_or_default(),
+        timestamp: raw.timestamp,
+    })
+}
@@ -1,8 +1,30 @@
+pub mod coalesce;
+pub mod edits;
+pub mod gaps;
+pub mod manual;
+pub mod merge;
 pub mod monitor;
 
 #[cfg(windows)]
 pub mod windows;
 
+#[cfg(target_os = "macos")]
+pub mod macos;
+
+#[cfg(target_os = "linux")]
+pub mod x11;
+
+#[cfg(target_os = "linux")]
+pub mod wayland;
+
+#[cfg(target_os = "linux")]
+pub mod gnome;
+
+#[cfg(target_os = "linux")]
+pub mod logind;
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::Sender;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,9 +38,205 @@ pub fn get_current_activity() -> Option<ActivitySnapshot> {
     #[cfg(windows)]
     return windows::get_active_window();
 
-    #[cfg(not(windows))]
+    #[cfg(target_os = "macos")]
+    return macos::get_active_window();
+
+    #[cfg(target_os = "linux")]
+    return match linux_backend() {
+        LinuxBackend::X11 => x11::get_active_window(),
+        LinuxBackend::Wayland => wayland::get_active_window(),
+        LinuxBackend::Gnome => gnome::get_active_window(),
+    };
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         // Placeholder for other platforms
         None
     }
 }
+
+/// Minimizes the foreground window, or hides the frontmost app on macOS.
+/// Returns false where the platform doesn't let us.
+pub fn minimize_foreground_window() -> bool {
+    #[cfg(windows)]
+    return windows::minimize_foreground();
+
+    #[cfg(target_os = "macos")]
+    return macos::hide_frontmost();
+
+    #[cfg(target_os = "linux")]
+    return match linux_backend() {
+        LinuxBackend::X11 => x11::minimize_active_window(),
+        LinuxBackend::Wayland | LinuxBackend::Gnome => false,
+    };
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        false
+    }
+}
+
+/// Seconds since the last keyboard or mouse input, or `None` where the
+/// platform doesn't tell us (the monitor then never records idle time)
+pub fn get_idle_seconds() -> Option<u64> {
+    #[cfg(windows)]
+    return windows::idle_seconds();
+
+    #[cfg(target_os = "macos")]
+    return macos::idle_seconds();
+
+    #[cfg(target_os = "linux")]
+    return match linux_backend() {
+        LinuxBackend::X11 => x11::idle_seconds(),
+        LinuxBackend::Gnome => gnome::idle_seconds(),
+        LinuxBackend::Wayland => None,
+    };
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Asks the platform to send on `changes` whenever the foreground window or
+/// its title changes. Returns false where no such notifications exist, in
+/// which case the monitor relies on polling alone.
+pub fn watch_foreground_changes(changes: Sender<()>) -> bool {
+    #[cfg(windows)]
+    return windows::watch_foreground(changes);
+
+    #[cfg(not(windows))]
+    {
+        drop(changes);
+        false
+    }
+}
+
+/// Asks the platform to send on `changes` when the session is locked or
+/// unlocked, after which `get_session_locked_since` reflects it. Returns
+/// false where lock notifications aren't available.
+pub fn watch_session_lock(changes: Sender<()>) -> bool {
+    #[cfg(windows)]
+    return windows::watch_session_lock(changes);
+
+    #[cfg(not(windows))]
+    {
+        drop(changes);
+        false
+    }
+}
+
+/// When the session was locked, as a Unix timestamp, while it is locked
+pub fn get_session_locked_since() -> Option<i64> {
+    #[cfg(windows)]
+    return windows::session_locked_since();
+
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// Whether the OS reports that the user is presenting. Only Windows tells us;
+/// elsewhere presenting is inferred from the foreground app alone.
+pub fn is_os_presenting() -> bool {
+    #[cfg(windows)]
+    return windows::is_presenting();
+
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// When the system last went to sleep, as a Unix timestamp, or 0 if it
+/// hasn't since `take_suspended_at` was last called
+static SUSPENDED_AT: AtomicI64 = AtomicI64::new(0);
+
+/// Asks the platform to report when the system suspends or hibernates, once
+/// per process. Returns false where it can't, in which case the monitor only
+/// notices sleep from the jump in wall-clock time afterwards.
+pub fn watch_suspend() -> bool {
+    static WATCHING: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+    *WATCHING.get_or_init(|| {
+        #[cfg(windows)]
+        return windows::watch_suspend();
+
+        #[cfg(target_os = "linux")]
+        return logind::watch_suspend();
+
+        #[cfg(not(any(windows, target_os = "linux")))]
+        {
+            false
+        }
+    })
+}
+
+/// Called by the platform backends as the system goes to sleep
+#[cfg(any(windows, target_os = "linux"))]
+fn record_suspend(timestamp: i64) {
+    SUSPENDED_AT.store(timestamp, Ordering::Relaxed);
+}
+
+/// When the system went to sleep since the last call, if it did
+pub fn take_suspended_at() -> Option<i64> {
+    let suspended_at = SUSPENDED_AT.swap(0, Ordering::Relaxed);
+    (suspended_at != 0).then_some(suspended_at)
+}
+
+/// Whether the OS lets us read window titles. macOS gates this behind the
+/// Accessibility permission, where `prompt` shows the system dialog if
+/// needed; GNOME on Wayland behind its introspection setting.
+pub fn has_window_title_access(prompt: bool) -> bool {
+    #[cfg(target_os = "macos")]
+    return macos::has_accessibility(prompt);
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = prompt;
+        match linux_backend() {
+            LinuxBackend::Gnome => gnome::has_access(),
+            LinuxBackend::X11 | LinuxBackend::Wayland => true,
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = prompt;
+        true
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+enum LinuxBackend {
+    X11,
+    Wayland,
+    Gnome,
+}
+
+/// Picks the capture backend for this session once, on the first poll
+#[cfg(target_os = "linux")]
+fn linux_backend() -> LinuxBackend {
+    static BACKEND: std::sync::OnceLock<LinuxBackend> = std::sync::OnceLock::new();
+
+    *BACKEND.get_or_init(|| {
+        let is_wayland = std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+            || std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+        let backend = if !is_wayland {
+            LinuxBackend::X11
+        } else if wayland::is_supported() {
+            LinuxBackend::Wayland
+        } else if gnome::is_supported() {
+            LinuxBackend::Gnome
+        } else {
+            // XWayland still lets us see X clients, which beats nothing
+            eprintln!("❌ Compositor exposes no toplevel protocol, only XWayland windows will be tracked");
+            LinuxBackend::X11
+        };
+        println!("✅ Using {:?} activity backend", backend);
+        backend
+    })
+}
@@ -1,40 +1,92 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use chrono::Utc;
+use tauri::AppHandle;
 
-use crate::database::{DbConnection, queries::{Activity, insert_activity}};
-use super::get_current_activity;
+use crate::alerts::{self, StreakTracker};
+use crate::api::events::{self, EventTracker};
+use crate::blocking;
+use crate::breaks::{self, BreakTracker};
+use crate::distractions::{self, DistractionTracker};
+use crate::friction;
+use crate::presentation;
+use crate::privacy;
+use crate::redaction;
+use crate::database::{DbConnection, queries::Activity, snapshots::record_raw_snapshot};
+use rusqlite::Connection;
+use super::{
+    get_current_activity, get_idle_seconds, get_session_locked_since, take_suspended_at, watch_foreground_changes,
+    watch_session_lock, watch_suspend, ActivitySnapshot,
+};
+use super::coalesce;
+use super::merge::{self, Merger, CURRENT_ALGORITHM_VERSION, DEFAULT_SAMPLING_INTERVAL_SECS, MAX_GAP_INTERVALS};
 
 pub struct ActivityMonitor {
     db: DbConnection,
+    app: AppHandle,
     is_running: Arc<Mutex<bool>>,
-    last_activity: Arc<Mutex<Option<String>>>,
-    last_timestamp: Arc<Mutex<i64>>,
+    /// Shared with the polling thread so the open run can be flushed from
+    /// outside it
+    merger: Arc<Mutex<Merger>>,
+    /// Wakes the polling thread, so a new sampling interval applies at once
+    /// rather than after the current wait
+    wake: Mutex<Option<Sender<()>>>,
 }
 
 impl ActivityMonitor {
-    pub fn new(db: DbConnection) -> Self {
+    pub fn new(db: DbConnection, app: AppHandle) -> Self {
+        let merger = Merger::new(CURRENT_ALGORITHM_VERSION, DEFAULT_SAMPLING_INTERVAL_SECS, merge::DEFAULT_IDLE_TIMEOUT_SECS)
+            .expect("current merging algorithm is always known");
         Self {
             db,
+            app,
             is_running: Arc::new(Mutex::new(false)),
-            last_activity: Arc::new(Mutex::new(None)),
-            last_timestamp: Arc::new(Mutex::new(Utc::now().timestamp())),
+            merger: Arc::new(Mutex::new(merger)),
+            wake: Mutex::new(None),
         }
     }
 
     pub fn start(&self) {
         let mut is_running = self.is_running.lock().unwrap();
+        if *is_running {
+            return;
+        }
         *is_running = true;
         
         let db = Arc::clone(&self.db);
+        let app = self.app.clone();
         let is_running_clone = Arc::clone(&self.is_running);
-        let last_activity = Arc::clone(&self.last_activity);
-        let last_timestamp = Arc::clone(&self.last_timestamp);
+        let merger = Arc::clone(&self.merger);
+        let (changes_tx, changes_rx) = mpsc::channel();
+        *self.wake.lock().unwrap() = Some(changes_tx.clone());
 
         thread::spawn(move || {
+            let mut streaks = StreakTracker::default();
+            let mut breaks = BreakTracker::default();
+            let mut distractions = DistractionTracker::default();
+            let mut api_events = EventTracker::default();
+
+            let watching_lock = watch_session_lock(changes_tx.clone());
+            if watching_lock {
+                println!("✅ Watching session lock changes");
+            }
+            let watching_foreground = watch_foreground_changes(changes_tx);
+            if watching_foreground {
+                println!("✅ Watching foreground window changes");
+            }
+            if watch_suspend() {
+                println!("✅ Watching for system sleep");
+            }
+
+            let mut interval = db.lock().ok()
+                .and_then(|conn| merge::sampling_interval(&conn).ok())
+                .unwrap_or(DEFAULT_SAMPLING_INTERVAL_SECS);
+            merger.lock().unwrap().set_poll_interval(interval);
+            let mut last_polled_at = None;
             loop {
-                thread::sleep(Duration::from_secs(10));
+                wait_for_change(&changes_rx, interval);
 
                 let should_continue = {
                     let running = is_running_clone.lock().unwrap();
@@ -45,75 +97,204 @@ impl ActivityMonitor {
                     break;
                 }
 
-                if let Some(snapshot) = get_current_activity() {
-                    let current_key = format!("{}:{}", snapshot.app_name, snapshot.window_title);
-                    let now = Utc::now().timestamp();
-
-                    let (should_insert, duration, _prev_app) = {
-                        let mut last_act = last_activity.lock().unwrap();
-                        let mut last_ts = last_timestamp.lock().unwrap();
-
-                        let duration = (now - *last_ts) as i32;
-                        let prev_app = last_act.clone();
-                        
-                        if let Some(ref last_key) = *last_act {
-                            if last_key != &current_key {
-                                // Activity changed, insert the previous activity
-                                let old_key = last_key.clone();
-                                *last_act = Some(current_key.clone());
-                                *last_ts = now;
-                                (true, duration, Some(old_key))
-                            } else {
-                                // Same activity, just update timestamp
-                                *last_ts = now;
-                                (false, 0, None)
-                            }
-                        } else {
-                            // First activity
-                            *last_act = Some(current_key.clone());
-                            *last_ts = now;
-                            (false, 0, None)
+                let snapshot = get_current_activity();
+                let idle_seconds = get_idle_seconds().map(|secs| secs as i64);
+                let locked_since = get_session_locked_since();
+                let polled_at = Utc::now().timestamp();
+                let suspended_at = detect_suspend(last_polled_at, polled_at, interval);
+                last_polled_at = Some(polled_at);
+
+                let (due_alerts, break_reminder, distraction_alerts, api_events_due) = match db.lock() {
+                    Ok(conn) => {
+                        // Checked again under the database lock, so nothing
+                        // is recorded after `stop` has flushed
+                        if !*is_running_clone.lock().unwrap() {
+                            break;
                         }
-                    };
-
-                    if should_insert && duration > 0 {
-                        if let Some(prev_key) = _prev_app {
-                            let parts: Vec<&str> = prev_key.split(':').collect();
-                            if parts.len() >= 2 {
-                                let activity = Activity {
-                                    id: None,
-                                    timestamp: now - duration as i64,
-                                    app_name: parts[0].to_string(),
-                                    window_title: Some(parts[1..].join(":")),
-                                    duration_seconds: duration,
-                                    is_idle: false,
-                                    category: None,
-                                };
-
-                                if let Ok(conn) = db.lock() {
-                                    match insert_activity(&conn, &activity) {
-                                        Ok(id) => {
-                                            println!("✅ Inserted activity: {} for {}s (ID: {})", activity.app_name, duration, id);
-                                        }
-                                        Err(e) => {
-                                            eprintln!("❌ Failed to insert activity: {}", e);
-                                        }
-                                    }
-                                }
+                        let mut merger = merger.lock().unwrap();
+
+                        if let Some(suspended_at) = suspended_at {
+                            // A poll without a window ends the run as the
+                            // machine went to sleep, rather than spanning it
+                            println!("✅ Resumed from sleep, closing the activity at {}", suspended_at);
+                            record_poll(&conn, &mut merger, None, suspended_at, None, None);
+                            streaks = StreakTracker::default();
+                            breaks = BreakTracker::default();
+                            distractions = DistractionTracker::default();
+                        }
+                        // Nothing counts while a launch friction prompt waits.
+                        // A blocked app is recorded as no window at all, a
+                        // private one without its name or title, and other
+                        // titles only once redacted and as privacy mode allows
+                        let held = friction::observe(&app, &conn, snapshot.as_ref(), polled_at).unwrap_or_else(|e| {
+                            eprintln!("❌ Failed to check launch friction: {}", e);
+                            false
+                        });
+                        let tracked = blocking::enforce(&conn, snapshot.clone().filter(|_| !held))
+                            .and_then(|tracked| redaction::redact(&conn, tracked))
+                            .and_then(|tracked| privacy::mask(&conn, tracked))
+                            .unwrap_or_else(|e| {
+                                eprintln!("❌ Failed to apply privacy settings: {}", e);
+                                None
+                            });
+                        record_poll(&conn, &mut merger, tracked.as_ref(), polled_at, idle_seconds, locked_since);
+
+                        // Applied after this poll, as the gap up to it was
+                        // waited out at the old interval
+                        if let Ok(new_interval) = merge::sampling_interval(&conn) {
+                            if new_interval != interval {
+                                println!("✅ Sampling every {}s", new_interval);
+                                interval = new_interval;
+                                merger.set_poll_interval(interval);
                             }
                         }
+
+                        // Whatever is behind the lock screen isn't being used
+                        let in_use = tracked.as_ref().filter(|_| locked_since.is_none());
+                        if let Err(e) = presentation::update(&app, &conn, in_use, polled_at) {
+                            eprintln!("❌ Failed to check for a presentation: {}", e);
+                        }
+                        let due_alerts = streaks
+                            .observe(&conn, in_use, polled_at)
+                            .unwrap_or_else(|e| {
+                                eprintln!("❌ Failed to evaluate streak alerts: {}", e);
+                                Vec::new()
+                            });
+                        // Time in a blocked app is still time at the screen
+                        let at_screen = snapshot.as_ref().filter(|_| locked_since.is_none());
+                        let break_reminder = breaks
+                            .observe(&conn, at_screen, idle_seconds, polled_at)
+                            .unwrap_or_else(|e| {
+                                eprintln!("❌ Failed to check for a break reminder: {}", e);
+                                None
+                            });
+                        let distraction_alerts = distractions
+                            .observe(&conn, in_use, idle_seconds, polled_at)
+                            .unwrap_or_else(|e| {
+                                eprintln!("❌ Failed to track distraction budgets: {}", e);
+                                Vec::new()
+                            });
+                        let idle_timeout = merge::idle_timeout(&conn).unwrap_or(merge::DEFAULT_IDLE_TIMEOUT_SECS);
+                        let api_events_due =
+                            api_events.observe(tracked.as_ref(), idle_seconds, idle_timeout, locked_since.is_some(), polled_at);
+                        (due_alerts, break_reminder, distraction_alerts, api_events_due)
                     }
+                    Err(_) => (Vec::new(), None, Vec::new(), Vec::new()),
+                };
+                for event in &api_events_due {
+                    events::publish(event);
+                }
+                for alert in &due_alerts {
+                    alerts::notify(&app, alert);
+                }
+                if let Some(reminder) = &break_reminder {
+                    breaks::notify(&app, reminder);
+                }
+                for alert in &distraction_alerts {
+                    distractions::notify(&app, alert);
                 }
             }
         });
     }
 
+    /// Stops polling and records the run in progress up to now
     pub fn stop(&self) {
-        let mut is_running = self.is_running.lock().unwrap();
-        *is_running = false;
+        {
+            let mut is_running = self.is_running.lock().unwrap();
+            if !*is_running {
+                return;
+            }
+            *is_running = false;
+        }
+        // Lets the polling thread exit now rather than after its wait
+        if let Some(wake) = self.wake.lock().unwrap().take() {
+            let _ = wake.send(());
+        }
+        self.flush();
+    }
+
+    /// Ends the run in progress now and writes it out, as if a poll had
+    /// found no foreground window
+    pub fn flush(&self) {
+        let Ok(conn) = self.db.lock() else {
+            return;
+        };
+        let mut merger = self.merger.lock().unwrap();
+        record_poll(&conn, &mut merger, None, Utc::now().timestamp(), None, None);
+    }
+
+    /// Makes the polling thread take a poll now and re-read its settings
+    pub fn reconfigure(&self) {
+        if let Some(wake) = self.wake.lock().unwrap().as_ref() {
+            let _ = wake.send(());
+        }
+    }
+
+    /// The activity still accumulating, up to now, while tracking
+    pub fn current_activity(&self) -> Option<Activity> {
+        if !self.is_running() {
+            return None;
+        }
+        self.merger.lock().unwrap().current(Utc::now().timestamp())
     }
 
     pub fn is_running(&self) -> bool {
         *self.is_running.lock().unwrap()
     }
 }
+
+/// Stores one poll as a raw snapshot and feeds it to the merger, writing out
+/// the activity it completes, if any
+fn record_poll(
+    conn: &Connection,
+    merger: &mut Merger,
+    snapshot: Option<&ActivitySnapshot>,
+    polled_at: i64,
+    idle_seconds: Option<i64>,
+    locked_since: Option<i64>,
+) {
+    if let Err(e) = record_raw_snapshot(conn, snapshot, idle_seconds, locked_since, polled_at) {
+        eprintln!("❌ Failed to record raw snapshot: {}", e);
+    }
+    if let Ok(idle_timeout) = merge::idle_timeout(conn) {
+        merger.set_idle_timeout(idle_timeout);
+    }
+    if let Some(activity) = merger.push(snapshot, polled_at, idle_seconds, locked_since) {
+        let recorded = coalesce::max_gap(conn).and_then(|max_gap| coalesce::record(conn, &activity, max_gap));
+        match recorded {
+            Ok(id) => {
+                println!("✅ Recorded activity: {} for {}s (ID: {})", activity.app_name, activity.duration_seconds, id);
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to insert activity: {}", e);
+            }
+        }
+    }
+}
+
+/// When the machine went to sleep since the previous poll, if it did. The
+/// platform may have told us the exact moment; otherwise a wall-clock jump of
+/// several intervals means it slept from about when the next poll was due.
+fn detect_suspend(previous_poll: Option<i64>, now: i64, interval: i64) -> Option<i64> {
+    let reported = take_suspended_at();
+    let previous = previous_poll?;
+
+    let jumped = now - previous > interval * MAX_GAP_INTERVALS;
+    reported
+        .or(jumped.then_some(previous + interval))
+        .map(|at| at.clamp(previous, now))
+}
+
+/// Sleeps until the next poll is due, waking early when the platform reports
+/// a window change or a session lock, or the settings change. Polling
+/// continues alongside the events so time in an unchanged window keeps being
+/// sampled.
+fn wait_for_change(changes: &Receiver<()>, interval_secs: i64) {
+    let interval = Duration::from_secs(interval_secs as u64);
+    match changes.recv_timeout(interval) {
+        // A switch usually arrives as a burst of events; sample once for all
+        Ok(()) => while changes.try_recv().is_ok() {},
+        Err(RecvTimeoutError::Timeout) => {}
+        Err(RecvTimeoutError::Disconnected) => thread::sleep(interval),
+    }
+}
@@ -0,0 +1,143 @@
+// Linux Wayland activity tracking
+// Wayland has no global "active window" query. Compositors built on wlroots
+// (Sway, Hyprland, river, Wayfire, labwc) expose the
+// wlr-foreign-toplevel-management protocol instead, which lists every
+// toplevel with its app_id, title and state; we report the one marked
+// activated. GNOME does not implement it, see `gnome.rs`. Idle time is not
+// available here: the idle protocols only notify after a fixed timeout.
+
+use std::collections::HashMap;
+use chrono::Utc;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle, WEnum};
+use wayland_client::backend::ObjectId;
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+use super::ActivitySnapshot;
+
+const MANAGER_INTERFACE: &str = "zwlr_foreign_toplevel_manager_v1";
+
+#[derive(Default)]
+struct Toplevel {
+    app_id: String,
+    title: String,
+    activated: bool,
+}
+
+#[derive(Default)]
+struct State {
+    toplevels: HashMap<ObjectId, Toplevel>,
+}
+
+/// Whether the compositor advertises the foreign toplevel protocol
+pub fn is_supported() -> bool {
+    let Ok(conn) = Connection::connect_to_env() else {
+        return false;
+    };
+    let Ok((globals, _queue)) = registry_queue_init::<State>(&conn) else {
+        return false;
+    };
+    globals
+        .contents()
+        .with_list(|list| list.iter().any(|global| global.interface == MANAGER_INTERFACE))
+}
+
+pub fn get_active_window() -> Option<ActivitySnapshot> {
+    // Like the X11 backend we connect per poll; binding the manager replays
+    // the current toplevels, so no state has to survive between polls
+    let conn = Connection::connect_to_env().ok()?;
+    let (globals, mut queue) = registry_queue_init::<State>(&conn).ok()?;
+    let manager: ZwlrForeignToplevelManagerV1 = globals.bind(&queue.handle(), 1..=3, ()).ok()?;
+
+    let mut state = State::default();
+    // The first roundtrip announces the toplevels, the second delivers
+    // their initial title, app_id and state
+    queue.roundtrip(&mut state).ok()?;
+    queue.roundtrip(&mut state).ok()?;
+    manager.stop();
+    let _ = conn.flush();
+
+    let active = state.toplevels.into_values().find(|toplevel| toplevel.activated)?;
+    let app_name = Some(active.app_id)
+        .filter(|app_id| !app_id.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    Some(ActivitySnapshot {
+        app_name,
+        window_title: active.title,
+        timestamp: Utc::now().timestamp(),
+    })
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.toplevels.insert(toplevel.id(), Toplevel::default());
+        }
+    }
+
+    event_created_child!(State, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        use zwlr_foreign_toplevel_handle_v1::Event;
+
+        if let Event::Closed = event {
+            state.toplevels.remove(&handle.id());
+            handle.destroy();
+            return;
+        }
+
+        let toplevel = state.toplevels.entry(handle.id()).or_default();
+        match event {
+            Event::Title { title } => toplevel.title = title,
+            Event::AppId { app_id } => toplevel.app_id = app_id,
+            // The state is an array of native-endian u32 enum values
+            Event::State { state } => {
+                toplevel.activated = state
+                    .chunks_exact(4)
+                    .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                    .any(|value| {
+                        matches!(
+                            WEnum::<zwlr_foreign_toplevel_handle_v1::State>::from(value),
+                            WEnum::Value(zwlr_foreign_toplevel_handle_v1::State::Activated)
+                        )
+                    });
+            }
+            _ => {}
+        }
+    }
+}
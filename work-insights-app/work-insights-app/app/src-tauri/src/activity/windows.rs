@@ -1,26 +1,378 @@
-// Simplified Windows activity tracking
-// This is a placeholder implementation that avoids complex Windows API calls
-// TODO: Implement proper window tracking with safer FFI bindings
+// Windows activity tracking
+// Reads the foreground window's title and the executable of the process that
+// owns it. UWP apps are hosted by ApplicationFrameHost.exe, so for those the
+// hosted app's own process is looked up among the frame's child windows.
+// Focus and title changes are pushed to the monitor through WinEvent hooks,
+// so switches between polls are not missed. Idle time comes from the last
+// input tick, and session lock/unlock from WTS session notifications
+// delivered to a hidden message-only window. Suspend is reported by a power
+// notification callback, and presenting from the shell's notification state.
 
-use super::ActivitySnapshot;
+use super::{record_suspend, ActivitySnapshot};
 use chrono::Utc;
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use windows::core::{w, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, GetLastError, BOOL, ERROR_CLASS_ALREADY_EXISTS, ERROR_SUCCESS, HANDLE, HWND, LPARAM, LRESULT, WPARAM,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Power::{PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS};
+use windows::Win32::System::RemoteDesktop::{
+    WTSRegisterSessionNotification, WTSUnRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+};
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+use windows::Win32::UI::Shell::{SHQueryUserNotificationState, QUNS_PRESENTATION_MODE};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, DEVICE_NOTIFY_CALLBACK, PBT_APMSUSPEND, EnumChildWindows, GetForegroundWindow,
+    GetMessageW, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, PostQuitMessage, RegisterClassW,
+    ShowWindowAsync, TranslateMessage, EVENT_OBJECT_NAMECHANGE, EVENT_SYSTEM_FOREGROUND, HWND_MESSAGE, MSG, OBJID_WINDOW,
+    SW_MINIMIZE, WINDOW_EX_STYLE, WINDOW_STYLE, WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS, WM_WTSSESSION_CHANGE,
+    WNDCLASSW, WTS_SESSION_LOCK, WTS_SESSION_UNLOCK,
+};
+
+const FRAME_HOST: &str = "ApplicationFrameHost";
+
+/// When the session was locked, as a Unix timestamp, or 0 while unlocked.
+/// We assume the app starts in an unlocked session.
+static LOCKED_SINCE: AtomicI64 = AtomicI64::new(0);
 
 #[cfg(windows)]
 pub fn get_active_window() -> Option<ActivitySnapshot> {
-    // Generate varying mock data to simulate activity changes
-    let timestamp = Utc::now().timestamp();
-    let app_index = (timestamp / 10) % 3; // Change app every 10 seconds
-    
-    let (app_name, window_title) = match app_index {
-        0 => ("Visual Studio Code", "work-insights-app - Dashboard.tsx"),
-        1 => ("Google Chrome", "Work Insights Documentation"),
-        2 => ("Terminal", "PowerShell"),
-        _ => ("System", "Desktop"),
-    };
-    
+    // SAFETY: GetForegroundWindow has no preconditions; it returns a null
+    // handle when no window has focus (e.g. on the lock screen).
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.is_invalid() {
+        return None;
+    }
+
+    let pid = window_process_id(hwnd)?;
+    let mut app_name = process_name(pid)?;
+    if app_name.eq_ignore_ascii_case(FRAME_HOST) {
+        if let Some(hosted) = hosted_app_process(hwnd, pid).and_then(process_name) {
+            app_name = hosted;
+        }
+    }
+
     Some(ActivitySnapshot {
-        app_name: String::from(app_name),
-        window_title: String::from(window_title),
-        timestamp,
+        app_name,
+        window_title: window_title(hwnd),
+        timestamp: Utc::now().timestamp(),
     })
 }
+
+/// Minimizes the foreground window without waiting on its thread, so a hung
+/// app can't stall the monitor
+pub fn minimize_foreground() -> bool {
+    // SAFETY: both calls accept any window handle; a null one is rejected
+    // above and a window closed meanwhile just makes the call fail.
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return false;
+        }
+        ShowWindowAsync(hwnd, SW_MINIMIZE).as_bool()
+    }
+}
+
+/// Seconds since the last keyboard or mouse input in this session
+pub fn idle_seconds() -> Option<u64> {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    // SAFETY: `info` is initialised with its size as the API requires.
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return None;
+    }
+    // Both are millisecond tick counts that wrap every ~49.7 days
+    // SAFETY: GetTickCount has no preconditions.
+    let elapsed = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+    Some(u64::from(elapsed) / 1000)
+}
+
+/// Whether Windows presentation mode is on. Full-screen apps (`QUNS_BUSY`)
+/// don't count: videos and games are full screen without anyone presenting.
+pub fn is_presenting() -> bool {
+    // SAFETY: SHQueryUserNotificationState has no preconditions.
+    unsafe { SHQueryUserNotificationState() }.is_ok_and(|state| state == QUNS_PRESENTATION_MODE)
+}
+
+fn window_title(hwnd: HWND) -> String {
+    // SAFETY: `hwnd` came from the window manager; if it has been destroyed
+    // since, both calls fail and return 0 rather than touching the buffer.
+    unsafe {
+        let len = GetWindowTextLengthW(hwnd);
+        if len <= 0 {
+            return String::new();
+        }
+        let mut buf = vec![0u16; len as usize + 1];
+        let copied = GetWindowTextW(hwnd, &mut buf);
+        String::from_utf16_lossy(&buf[..copied.max(0) as usize])
+    }
+}
+
+fn window_process_id(hwnd: HWND) -> Option<u32> {
+    let mut pid = 0u32;
+    // SAFETY: `pid` outlives the call.
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    (pid != 0).then_some(pid)
+}
+
+/// Executable name without extension, e.g. "chrome" for chrome.exe
+fn process_name(pid: u32) -> Option<String> {
+    // SAFETY: the handle is closed before returning and the buffer length is
+    // passed alongside the buffer. PROCESS_QUERY_LIMITED_INFORMATION is
+    // enough for QueryFullProcessImageNameW and is granted for elevated
+    // processes too.
+    let path = unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let result = QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buf.as_mut_ptr()), &mut len);
+        let _ = CloseHandle(handle);
+        result.ok()?;
+        String::from_utf16_lossy(&buf[..len as usize])
+    };
+
+    Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .filter(|name| !name.is_empty())
+}
+
+/// Process id of the first child window not owned by the frame host itself
+fn hosted_app_process(frame: HWND, frame_pid: u32) -> Option<u32> {
+    struct Search {
+        frame_pid: u32,
+        found: Option<u32>,
+    }
+
+    unsafe extern "system" fn visit(child: HWND, lparam: LPARAM) -> BOOL {
+        // SAFETY: lparam is the `Search` below, alive for the whole enumeration.
+        let search = &mut *(lparam.0 as *mut Search);
+        match window_process_id(child) {
+            Some(pid) if pid != search.frame_pid => {
+                search.found = Some(pid);
+                false.into()
+            }
+            _ => true.into(),
+        }
+    }
+
+    let mut search = Search { frame_pid, found: None };
+    // SAFETY: EnumChildWindows is synchronous, so `search` outlives every
+    // callback. The return value only reports whether enumeration was cut short.
+    unsafe {
+        let _ = EnumChildWindows(frame, Some(visit), LPARAM(&mut search as *mut Search as isize));
+    }
+    search.found
+}
+
+thread_local! {
+    /// Where the watcher threads' callbacks report changes; WinEvent
+    /// callbacks take no user data
+    static CHANGES: RefCell<Option<Sender<()>>> = const { RefCell::new(None) };
+}
+
+/// Starts a thread that sends on `changes` whenever the foreground window or
+/// its title changes. Returns false if the hooks could not be installed. The
+/// thread unhooks and exits once the receiver is dropped.
+pub fn watch_foreground(changes: Sender<()>) -> bool {
+    let (ready_tx, ready_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        CHANGES.with(|slot| *slot.borrow_mut() = Some(changes));
+
+        let flags = WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS;
+        // SAFETY: out-of-context hooks need no module handle; the callback is
+        // invoked on this thread while it pumps messages below.
+        let hooks = unsafe {
+            [
+                SetWinEventHook(EVENT_SYSTEM_FOREGROUND, EVENT_SYSTEM_FOREGROUND, None, Some(on_event), 0, 0, flags),
+                SetWinEventHook(EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_NAMECHANGE, None, Some(on_event), 0, 0, flags),
+            ]
+        };
+        let installed = !hooks[0].is_invalid();
+        let _ = ready_tx.send(installed);
+        if !installed {
+            return;
+        }
+
+        pump_messages();
+        // SAFETY: the hooks were installed by this thread.
+        unsafe {
+            for hook in hooks.into_iter().filter(|hook| !hook.is_invalid()) {
+                let _ = UnhookWinEvent(hook);
+            }
+        }
+    });
+
+    ready_rx.recv().unwrap_or(false)
+}
+
+/// When the session was locked, while it is locked. Only known once
+/// `watch_session_lock` is running.
+pub fn session_locked_since() -> Option<i64> {
+    let since = LOCKED_SINCE.load(Ordering::Relaxed);
+    (since != 0).then_some(since)
+}
+
+/// Starts a thread that tracks this session's lock state and sends on
+/// `changes` whenever it is locked or unlocked. Returns false if the
+/// notification window could not be registered. Like `watch_foreground`, the
+/// thread exits once the receiver is dropped.
+pub fn watch_session_lock(changes: Sender<()>) -> bool {
+    let (ready_tx, ready_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        CHANGES.with(|slot| *slot.borrow_mut() = Some(changes));
+
+        let Some(hwnd) = create_message_window() else {
+            let _ = ready_tx.send(false);
+            return;
+        };
+        // SAFETY: `hwnd` is a live window owned by this thread.
+        let registered = unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) }.is_ok();
+        let _ = ready_tx.send(registered);
+
+        if registered {
+            pump_messages();
+        }
+        // SAFETY: as above; the window is destroyed on the thread that made it.
+        unsafe {
+            if registered {
+                let _ = WTSUnRegisterSessionNotification(hwnd);
+            }
+            let _ = DestroyWindow(hwnd);
+        }
+    });
+
+    ready_rx.recv().unwrap_or(false)
+}
+
+/// Registers for suspend notifications for the rest of the process's life.
+/// Returns false if registration failed.
+pub fn watch_suspend() -> bool {
+    unsafe extern "system" fn on_power_event(_context: *const c_void, event: u32, _setting: *const c_void) -> u32 {
+        if event == PBT_APMSUSPEND {
+            record_suspend(Utc::now().timestamp());
+        }
+        ERROR_SUCCESS.0
+    }
+
+    // Never unregistered, so the parameters are leaked to outlive it
+    let params = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+        Callback: Some(on_power_event),
+        Context: std::ptr::null_mut(),
+    }));
+    let mut registration = std::ptr::null_mut();
+    // SAFETY: with DEVICE_NOTIFY_CALLBACK the recipient is a pointer to the
+    // subscribe parameters, which stay valid for the life of the process.
+    let result = unsafe {
+        PowerRegisterSuspendResumeNotification(
+            DEVICE_NOTIFY_CALLBACK,
+            HANDLE(params as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as *mut c_void),
+            &mut registration,
+        )
+    };
+    result == ERROR_SUCCESS
+}
+
+/// A hidden message-only window for session notifications, which are only
+/// delivered to windows
+fn create_message_window() -> Option<HWND> {
+    let class_name = w!("WorkInsightsSessionWatcher");
+    // SAFETY: the class name is a static string and the class outlives the
+    // window. It is still registered when tracking is restarted.
+    unsafe {
+        let instance = GetModuleHandleW(None).ok()?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(session_window_proc),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        if RegisterClassW(&class) == 0 && GetLastError() != ERROR_CLASS_ALREADY_EXISTS {
+            return None;
+        }
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            PCWSTR::null(),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            HWND_MESSAGE,
+            None,
+            instance,
+            None,
+        )
+        .ok()
+    }
+}
+
+unsafe extern "system" fn session_window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg != WM_WTSSESSION_CHANGE {
+        return DefWindowProcW(hwnd, msg, wparam, lparam);
+    }
+
+    match wparam.0 as u32 {
+        WTS_SESSION_LOCK => LOCKED_SINCE.store(Utc::now().timestamp(), Ordering::Relaxed),
+        WTS_SESSION_UNLOCK => LOCKED_SINCE.store(0, Ordering::Relaxed),
+        _ => return LRESULT(0),
+    }
+    notify_change();
+    LRESULT(0)
+}
+
+/// Runs this thread's message loop until `notify_change` finds the monitor gone
+fn pump_messages() {
+    let mut msg = MSG::default();
+    // SAFETY: standard message loop on the thread that owns the hooks or
+    // window. GetMessageW returns 0 on WM_QUIT and -1 on error.
+    unsafe {
+        while GetMessageW(&mut msg, None, 0, 0).0 > 0 {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+/// Wakes the monitor, or ends this thread's message loop if it has stopped
+fn notify_change() {
+    let delivered = CHANGES.with(|slot| slot.borrow().as_ref().is_some_and(|changes| changes.send(()).is_ok()));
+    if !delivered {
+        // SAFETY: only posts WM_QUIT to this thread's own queue.
+        unsafe { PostQuitMessage(0) };
+    }
+}
+
+unsafe extern "system" fn on_event(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    _id_child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    // Name changes fire for every control on screen; only the foreground
+    // window's own title matters
+    if event == EVENT_OBJECT_NAMECHANGE && (id_object != OBJID_WINDOW.0 || hwnd != GetForegroundWindow()) {
+        return;
+    }
+
+    notify_change();
+}
@@ -0,0 +1,108 @@
+// Linux X11 activity tracking
+// Asks the window manager for the active window through the EWMH
+// _NET_ACTIVE_WINDOW hint, then reports its WM_CLASS class (e.g. "firefox")
+// as the app and _NET_WM_NAME as the title. Works under XWayland for X
+// clients only. Idle time comes from the MIT-SCREEN-SAVER extension.
+// Minimizing asks the window manager to iconify the window (ICCCM
+// WM_CHANGE_STATE).
+
+use chrono::Utc;
+use x11rb::connection::Connection;
+use x11rb::properties::WmClass;
+use x11rb::protocol::screensaver::ConnectionExt as _;
+use x11rb::protocol::xproto::{AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::ActivitySnapshot;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_WM_NAME,
+        UTF8_STRING,
+        WM_CHANGE_STATE,
+    }
+}
+
+pub fn get_active_window() -> Option<ActivitySnapshot> {
+    // A connection per poll keeps us working across X server restarts and
+    // costs little at our sampling rate
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let atoms = Atoms::new(&conn).ok()?.reply().ok()?;
+
+    let window = active_window(&conn, root, &atoms)?;
+    let app_name = window_class(&conn, window).unwrap_or_else(|| "Unknown".to_string());
+    let window_title = window_title(&conn, window, &atoms).unwrap_or_default();
+
+    Some(ActivitySnapshot {
+        app_name,
+        window_title,
+        timestamp: Utc::now().timestamp(),
+    })
+}
+
+/// Seconds since the last keyboard or mouse input to the X server
+pub fn idle_seconds() -> Option<u64> {
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let info = conn.screensaver_query_info(root).ok()?.reply().ok()?;
+    Some(u64::from(info.ms_since_user_input) / 1000)
+}
+
+/// Asks the window manager to iconify the active window
+pub fn minimize_active_window() -> bool {
+    iconify_active_window().is_some()
+}
+
+fn iconify_active_window() -> Option<()> {
+    /// IconicState from the ICCCM
+    const ICONIC_STATE: u32 = 3;
+
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let atoms = Atoms::new(&conn).ok()?.reply().ok()?;
+    let window = active_window(&conn, root, &atoms)?;
+
+    let event = ClientMessageEvent::new(32, window, atoms.WM_CHANGE_STATE, [ICONIC_STATE, 0, 0, 0, 0]);
+    conn.send_event(false, root, EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY, event)
+        .ok()?;
+    conn.flush().ok()
+}
+
+fn active_window(conn: &RustConnection, root: Window, atoms: &Atoms) -> Option<Window> {
+    let reply = conn
+        .get_property(false, root, atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW, 0, 1)
+        .ok()?
+        .reply()
+        .ok()?;
+    let window = reply.value32()?.next()?;
+    (window != x11rb::NONE).then_some(window)
+}
+
+/// The class half of WM_CLASS names the application; the instance half is
+/// often just the binary name
+fn window_class(conn: &RustConnection, window: Window) -> Option<String> {
+    let class = WmClass::get(conn, window).ok()?.reply().ok()??;
+    let name = if class.class().is_empty() { class.instance() } else { class.class() };
+    Some(String::from_utf8_lossy(name).into_owned()).filter(|name| !name.is_empty())
+}
+
+/// _NET_WM_NAME (UTF-8), falling back to the legacy Latin-1 WM_NAME
+fn window_title(conn: &RustConnection, window: Window, atoms: &Atoms) -> Option<String> {
+    let net_name = conn
+        .get_property(false, window, atoms._NET_WM_NAME, atoms.UTF8_STRING, 0, u32::MAX)
+        .ok()?
+        .reply()
+        .ok()?;
+    if !net_name.value.is_empty() {
+        return Some(String::from_utf8_lossy(&net_name.value).into_owned());
+    }
+
+    let wm_name = conn
+        .get_property(false, window, AtomEnum::WM_NAME, AtomEnum::STRING, 0, u32::MAX)
+        .ok()?
+        .reply()
+        .ok()?;
+    Some(wm_name.value.iter().map(|&b| b as char).collect()).filter(|title: &String| !title.is_empty())
+}
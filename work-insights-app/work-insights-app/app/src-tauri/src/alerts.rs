@@ -0,0 +1,240 @@
+// App streak alerts
+// Warns when one app, or a site matched on the window title, has been in the
+// foreground continuously for longer than the user allows. Evaluated by the
+// activity monitor on every sample.
+
+use std::collections::HashMap;
+use chrono::Utc;
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::ActivitySnapshot;
+use crate::notifications::{self, NotificationKind};
+
+pub const STREAK_ALERT_EVENT: &str = "app-streak-alert";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreakAlertRule {
+    pub id: Option<i64>,
+    /// Case-insensitive substring of the app name or window title, so
+    /// "Twitter" catches the desktop app as well as the tab in a browser
+    pub pattern: String,
+    pub threshold_minutes: i64,
+    pub enabled: bool,
+    pub snoozed_until: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreakAlert {
+    pub id: Option<i64>,
+    pub rule_id: i64,
+    pub pattern: String,
+    pub app_name: String,
+    pub window_title: String,
+    pub streak_seconds: i64,
+    pub triggered_at: i64,
+}
+
+impl StreakAlertRule {
+    fn matches(&self, snapshot: &ActivitySnapshot) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        !pattern.is_empty()
+            && (snapshot.app_name.to_lowercase().contains(&pattern)
+                || snapshot.window_title.to_lowercase().contains(&pattern))
+    }
+}
+
+struct Streak {
+    started_at: i64,
+    last_alert_at: Option<i64>,
+}
+
+/// Per-rule streaks for the monitor thread. A streak breaks as soon as a
+/// sample no longer matches the rule.
+#[derive(Default)]
+pub struct StreakTracker {
+    streaks: HashMap<i64, Streak>,
+}
+
+impl StreakTracker {
+    /// Updates streaks with the latest sample and logs and returns any alerts
+    /// that became due. An alert fires once per streak, and again when a
+    /// snooze taken after it runs out while the streak continues.
+    pub fn observe(
+        &mut self,
+        conn: &Connection,
+        snapshot: Option<&ActivitySnapshot>,
+        now: i64,
+    ) -> Result<Vec<StreakAlert>> {
+        let Some(snapshot) = snapshot else {
+            self.streaks.clear();
+            return Ok(Vec::new());
+        };
+
+        let rules: Vec<StreakAlertRule> = get_rules(conn)?
+            .into_iter()
+            .filter(|rule| rule.enabled && rule.matches(snapshot))
+            .collect();
+        self.streaks.retain(|id, _| rules.iter().any(|rule| rule.id == Some(*id)));
+
+        let mut alerts = Vec::new();
+        for rule in rules {
+            let Some(rule_id) = rule.id else { continue };
+            let streak = self.streaks.entry(rule_id).or_insert(Streak {
+                started_at: now,
+                last_alert_at: None,
+            });
+
+            let streak_seconds = now - streak.started_at;
+            let snoozed = rule.snoozed_until.is_some_and(|until| until > now);
+            let due = match streak.last_alert_at {
+                None => true,
+                Some(last) => rule.snoozed_until.is_some_and(|until| until > last),
+            };
+            if streak_seconds < rule.threshold_minutes * 60 || snoozed || !due {
+                continue;
+            }
+
+            streak.last_alert_at = Some(now);
+            let mut alert = StreakAlert {
+                id: None,
+                rule_id,
+                pattern: rule.pattern.clone(),
+                app_name: snapshot.app_name.clone(),
+                window_title: snapshot.window_title.clone(),
+                streak_seconds,
+                triggered_at: now,
+            };
+            alert.id = Some(log_alert(conn, &alert)?);
+            alerts.push(alert);
+        }
+
+        Ok(alerts)
+    }
+}
+
+/// Tells the frontend and shows a system notification
+pub fn notify(app: &AppHandle, alert: &StreakAlert) {
+    if let Err(e) = app.emit(STREAK_ALERT_EVENT, alert) {
+        eprintln!("❌ Failed to emit streak alert: {}", e);
+    }
+
+    notifications::notify(
+        app,
+        NotificationKind::StreakAlert,
+        "Time for a change?",
+        &format!(
+            "You've been on {} for {} minutes straight",
+            alert.pattern,
+            alert.streak_seconds / 60
+        ),
+    );
+}
+
+pub fn get_rules(conn: &Connection) -> Result<Vec<StreakAlertRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, pattern, threshold_minutes, enabled, snoozed_until
+         FROM app_streak_rules
+         ORDER BY pattern COLLATE NOCASE",
+    )?;
+
+    let rules = stmt
+        .query_map([], |row| {
+            Ok(StreakAlertRule {
+                id: Some(row.get(0)?),
+                pattern: row.get(1)?,
+                threshold_minutes: row.get(2)?,
+                enabled: row.get(3)?,
+                snoozed_until: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(rules)
+}
+
+pub fn save_rule(conn: &Connection, rule: &StreakAlertRule) -> Result<StreakAlertRule> {
+    let id = match rule.id {
+        Some(id) => {
+            conn.execute(
+                "UPDATE app_streak_rules
+                 SET pattern = ?2, threshold_minutes = ?3, enabled = ?4, snoozed_until = ?5
+                 WHERE id = ?1",
+                params![id, rule.pattern.trim(), rule.threshold_minutes, rule.enabled, rule.snoozed_until],
+            )?;
+            id
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO app_streak_rules (pattern, threshold_minutes, enabled, snoozed_until)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![rule.pattern.trim(), rule.threshold_minutes, rule.enabled, rule.snoozed_until],
+            )?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    Ok(StreakAlertRule {
+        id: Some(id),
+        pattern: rule.pattern.trim().to_string(),
+        ..rule.clone()
+    })
+}
+
+pub fn delete_rule(conn: &Connection, rule_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM app_streak_rules WHERE id = ?1", params![rule_id])?;
+    Ok(())
+}
+
+/// Silences a rule for `minutes`; if the streak is still going when the
+/// snooze ends, the alert fires again
+pub fn snooze_rule(conn: &Connection, rule_id: i64, minutes: i64) -> Result<i64> {
+    let until = Utc::now().timestamp() + minutes * 60;
+    conn.execute(
+        "UPDATE app_streak_rules SET snoozed_until = ?2 WHERE id = ?1",
+        params![rule_id, until],
+    )?;
+    Ok(until)
+}
+
+fn log_alert(conn: &Connection, alert: &StreakAlert) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO app_streak_alerts (rule_id, pattern, app_name, window_title, streak_seconds, triggered_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            alert.rule_id,
+            alert.pattern,
+            alert.app_name,
+            alert.window_title,
+            alert.streak_seconds,
+            alert.triggered_at,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn get_alert_log(conn: &Connection, limit: i64) -> Result<Vec<StreakAlert>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, rule_id, pattern, app_name, window_title, streak_seconds, triggered_at
+         FROM app_streak_alerts
+         ORDER BY triggered_at DESC
+         LIMIT ?1",
+    )?;
+
+    let alerts = stmt
+        .query_map(params![limit], |row| {
+            Ok(StreakAlert {
+                id: Some(row.get(0)?),
+                rule_id: row.get(1)?,
+                pattern: row.get(2)?,
+                app_name: row.get(3)?,
+                window_title: row.get(4)?,
+                streak_seconds: row.get(5)?,
+                triggered_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(alerts)
+}
@@ -0,0 +1,149 @@
+// Server-sent events
+// `GET /events` keeps the connection open and streams tracker events as they
+// happen, so home-automation tools can react to them, e.g. dimming the lights
+// while a focus session runs. Each event is sent as
+// `event: <type>` with the same JSON as `data`, and a comment line goes out
+// every `KEEP_ALIVE` so proxies and clients don't drop a quiet stream. The
+// stream needs the API token like every other route; only the app's own
+// page may read it from a browser.
+//
+// Events reflect what is stored: a private app shows up as "Private", titles
+// are redacted or left out as configured, and blocked apps never appear.
+
+use std::io::Write;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use serde::Serialize;
+use tiny_http::Request;
+
+use crate::activity::ActivitySnapshot;
+use crate::focus::FocusSession;
+
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// One channel per open `/events` stream
+static SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApiEvent {
+    ActivityChanged { app_name: String, window_title: String, timestamp: i64 },
+    IdleStarted { timestamp: i64 },
+    IdleEnded { timestamp: i64 },
+    FocusSessionStarted { session: FocusSession, timestamp: i64 },
+    FocusSessionEnded { session: FocusSession, timestamp: i64 },
+}
+
+impl ApiEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            ApiEvent::ActivityChanged { .. } => "activity_changed",
+            ApiEvent::IdleStarted { .. } => "idle_started",
+            ApiEvent::IdleEnded { .. } => "idle_ended",
+            ApiEvent::FocusSessionStarted { .. } => "focus_session_started",
+            ApiEvent::FocusSessionEnded { .. } => "focus_session_ended",
+        }
+    }
+}
+
+/// Follows the monitor's samples and reports when the window in use changes
+/// or the user goes idle or comes back
+#[derive(Debug, Default)]
+pub struct EventTracker {
+    /// App and title of the last window in use
+    current: Option<(String, String)>,
+    idle: bool,
+}
+
+impl EventTracker {
+    /// `snapshot` is the sample as stored, `None` when nothing trackable is
+    /// in the foreground; the user counts as idle once `idle_seconds` reaches
+    /// `idle_timeout` or the session is locked
+    pub fn observe(
+        &mut self,
+        snapshot: Option<&ActivitySnapshot>,
+        idle_seconds: Option<i64>,
+        idle_timeout: i64,
+        locked: bool,
+        now: i64,
+    ) -> Vec<ApiEvent> {
+        let mut events = Vec::new();
+        let idle = locked || idle_seconds.is_some_and(|secs| secs >= idle_timeout);
+        if idle != self.idle {
+            self.idle = idle;
+            events.push(if idle {
+                ApiEvent::IdleStarted { timestamp: now }
+            } else {
+                ApiEvent::IdleEnded { timestamp: now }
+            });
+        }
+        if idle {
+            // Whatever is used next counts as a change, even the same window
+            self.current = None;
+            return events;
+        }
+
+        let current = snapshot.map(|s| (s.app_name.clone(), s.window_title.clone()));
+        if current != self.current {
+            if let Some((app_name, window_title)) = &current {
+                events.push(ApiEvent::ActivityChanged {
+                    app_name: app_name.clone(),
+                    window_title: window_title.clone(),
+                    timestamp: now,
+                });
+            }
+            self.current = current;
+        }
+        events
+    }
+}
+
+/// Sends the event to every open stream, forgetting those that have closed
+pub fn publish(event: &ApiEvent) {
+    let mut subscribers = SUBSCRIBERS.lock().unwrap();
+    if subscribers.is_empty() {
+        return;
+    }
+    let data = serde_json::to_string(event).unwrap_or_default();
+    let message = format!("event: {}\ndata: {}\n\n", event.name(), data);
+    subscribers.retain(|subscriber| subscriber.send(message.clone()).is_ok());
+}
+
+/// Answers the request with an event stream on a thread of its own, which
+/// ends when the client disconnects. `origin` is the app's own page when the
+/// request came from one, and is the only page allowed to read the stream.
+pub fn stream(request: Request, origin: Option<&str>) {
+    let cors = origin
+        .map(|origin| format!("Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n", origin))
+        .unwrap_or_default();
+    let (tx, rx) = mpsc::channel();
+    SUBSCRIBERS.lock().unwrap().push(tx);
+
+    thread::spawn(move || {
+        let mut writer = request.into_writer();
+        let head = format!(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/event-stream\r\n\
+             Cache-Control: no-cache\r\n\
+             Connection: keep-alive\r\n\
+             {}\r\n",
+            cors
+        );
+        if writer.write_all(head.as_bytes()).and_then(|_| writer.flush()).is_err() {
+            return;
+        }
+
+        loop {
+            let message = match rx.recv_timeout(KEEP_ALIVE) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if writer.write_all(message.as_bytes()).and_then(|_| writer.flush()).is_err() {
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,55 @@
+// Prometheus metrics
+// Served as `GET /metrics` by the local API in the text exposition format so
+// Grafana (via Prometheus or any compatible scraper) can plot tracker data.
+
+use std::fmt::Write;
+use chrono::Utc;
+use rusqlite::{Connection, Result};
+
+use crate::database::queries::{count_context_switches, get_category_totals, get_today_stats, today_start_timestamp};
+use crate::focus;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+pub fn render(conn: &Connection, tracker_up: bool) -> Result<String> {
+    let today_start = today_start_timestamp();
+    let now = Utc::now().timestamp();
+    let stats = get_today_stats(conn)?;
+    let categories = get_category_totals(conn, today_start, now)?;
+    let switches = count_context_switches(conn, today_start, now)?;
+    let focus_active = focus::get_active_session(conn)?.is_some();
+
+    let mut out = String::new();
+
+    gauge(&mut out, "work_insights_tracker_up", "Whether activity tracking is running", tracker_up as i64);
+    gauge(&mut out, "work_insights_focus_session_active", "Whether a focus session is running", focus_active as i64);
+
+    header(&mut out, "work_insights_active_seconds_today", "Active seconds since local midnight by category", "gauge");
+    for total in &categories {
+        let _ = writeln!(
+            out,
+            "work_insights_active_seconds_today{{category=\"{}\"}} {}",
+            escape_label(&total.category),
+            total.total_seconds
+        );
+    }
+
+    gauge(&mut out, "work_insights_idle_seconds_today", "Idle seconds since local midnight", stats.idle_time_seconds);
+    gauge(&mut out, "work_insights_context_switches_today", "Switches between apps since local midnight", switches);
+
+    Ok(out)
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: i64) {
+    header(out, name, help, "gauge");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
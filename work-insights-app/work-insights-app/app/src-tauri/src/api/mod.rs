@@ -0,0 +1,307 @@
+// Local HTTP API
+// Bound to 127.0.0.1 only and disabled unless `local_api_enabled` is set, so
+// site blockers and personal scripts can read tracker state without the UI.
+// `/events` streams changes as server-sent events, and `/openapi.json`
+// describes every route.
+//
+// Being on 127.0.0.1 doesn't keep web pages out, so every request needs this
+// install's token from `local_api_token`, shown in the settings, either as
+// `Authorization: Bearer <token>` or as a `token` query parameter (which is
+// all an `EventSource` can send). Requests from a browser page are refused
+// unless the page is the app's own.
+//
+// The dashboard reads the same routes in-process through the
+// `local_api_request` command, which works whether or not the server is on.
+
+pub mod events;
+pub mod metrics;
+pub mod openapi;
+
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use chrono::Utc;
+use serde::Serialize;
+use rusqlite::Connection;
+use tiny_http::{Header, Method, Request, Response, Server};
+use uuid::Uuid;
+
+use crate::activity::monitor::ActivityMonitor;
+use crate::database::{DbConnection, queries::{get_category_totals, get_setting, set_setting, today_start_timestamp}};
+use crate::focus;
+
+pub const DEFAULT_PORT: u16 = 47615;
+pub const TOKEN_KEY: &str = "local_api_token";
+
+/// Origins of the app's own webview, in builds and under `tauri dev`
+const APP_ORIGINS: [&str; 4] = [
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+    "http://localhost:1420",
+];
+
+type JsonResponse = Response<Cursor<Vec<u8>>>;
+
+/// Every route the API serves, all of them `GET`s. `openapi::spec` must
+/// describe exactly these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    Focus,
+    Categories,
+    Metrics,
+    Events,
+    OpenApi,
+}
+
+impl Route {
+    pub const ALL: [Route; 5] = [Route::Focus, Route::Categories, Route::Metrics, Route::Events, Route::OpenApi];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            Route::Focus => "/focus",
+            Route::Categories => "/categories",
+            Route::Metrics => "/metrics",
+            Route::Events => "/events",
+            Route::OpenApi => "/openapi.json",
+        }
+    }
+
+    fn find(path: &str) -> Option<Route> {
+        Route::ALL.into_iter().find(|route| route.path() == path)
+    }
+}
+
+/// A route's answer, before it's sent over HTTP or handed to the dashboard
+#[derive(Debug, Clone, Serialize)]
+pub struct Reply {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Reply {
+    fn json(status: u16, body: serde_json::Value) -> Self {
+        Reply { status, content_type: "application/json", body: body.to_string() }
+    }
+
+    fn text(status: u16, body: String, content_type: &'static str) -> Self {
+        Reply { status, content_type, body }
+    }
+
+    fn into_response(self) -> JsonResponse {
+        text_response(self.status, self.body, self.content_type)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalApiInfo {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+    pub base_url: String,
+}
+
+/// Whether the API is enabled, where, and the token requests need
+pub fn info(conn: &Connection) -> rusqlite::Result<LocalApiInfo> {
+    let enabled = get_setting(conn, "local_api_enabled")?.is_some_and(|v| v == "true");
+    let port = get_setting(conn, "local_api_port")?
+        .and_then(|v| v.parse::<u16>().ok())
+        .unwrap_or(DEFAULT_PORT);
+    Ok(LocalApiInfo { enabled, port, token: token(conn)?, base_url: format!("http://127.0.0.1:{}", port) })
+}
+
+/// This install's token, made on first use from the OS's random number
+/// generator
+pub fn token(conn: &Connection) -> rusqlite::Result<String> {
+    match get_setting(conn, TOKEN_KEY)?.filter(|token| !token.is_empty()) {
+        Some(token) => Ok(token),
+        None => regenerate_token(conn),
+    }
+}
+
+/// Replaces the token, locking out whatever used the old one
+pub fn regenerate_token(conn: &Connection) -> rusqlite::Result<String> {
+    let token = Uuid::new_v4().simple().to_string();
+    set_setting(conn, TOKEN_KEY, &token)?;
+    Ok(token)
+}
+
+pub fn start_if_enabled(db: &DbConnection, monitor: Arc<Mutex<ActivityMonitor>>) {
+    let info = match db.lock() {
+        Ok(conn) => match info(&conn) {
+            Ok(info) => info,
+            Err(e) => {
+                eprintln!("❌ Failed to read local API settings: {}", e);
+                return;
+            }
+        },
+        Err(_) => return,
+    };
+
+    if info.enabled {
+        if let Err(e) = start_server(db.clone(), monitor, info.port) {
+            eprintln!("❌ Failed to start local API on port {}: {}", info.port, e);
+        }
+    }
+}
+
+pub fn start_server(db: DbConnection, monitor: Arc<Mutex<ActivityMonitor>>, port: u16) -> Result<(), String> {
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+    println!("✅ Local API listening on http://127.0.0.1:{}", port);
+
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let origin = header(&request, "Origin").map(str::to_string);
+            if origin.as_deref().is_some_and(|origin| !APP_ORIGINS.contains(&origin)) {
+                let _ = request.respond(json_response(403, serde_json::json!({ "error": "Origin not allowed" })));
+                continue;
+            }
+            if *request.method() == Method::Options {
+                let _ = request.respond(with_cors(text_response(204, String::new(), "text/plain"), origin.as_deref()));
+                continue;
+            }
+            // Read on every request, so a new token takes effect right away
+            let token = db.lock().map_err(|e| e.to_string()).and_then(|conn| token(&conn).map_err(|e| e.to_string()));
+            match token {
+                Ok(token) if authorized(&request, &token) => {}
+                Ok(_) => {
+                    let response = json_response(401, serde_json::json!({ "error": "Missing or wrong token" }));
+                    let _ = request.respond(with_cors(response, origin.as_deref()));
+                    continue;
+                }
+                Err(e) => {
+                    let _ = request.respond(json_response(500, serde_json::json!({ "error": e })));
+                    continue;
+                }
+            }
+
+            let path = request.url().split('?').next().unwrap_or_default();
+            if *request.method() == Method::Get && Route::find(path) == Some(Route::Events) {
+                events::stream(request, origin.as_deref());
+                continue;
+            }
+            let response = route(&db, &monitor, port, request.method(), request.url()).into_response();
+            let _ = request.respond(with_cors(response, origin.as_deref()));
+        }
+    });
+
+    Ok(())
+}
+
+/// Answers a request for any route but `/events`, which only streams over
+/// HTTP
+pub fn route(db: &DbConnection, monitor: &Mutex<ActivityMonitor>, port: u16, method: &Method, url: &str) -> Reply {
+    let path = url.split('?').next().unwrap_or_default();
+    let Some(found) = Route::find(path).filter(|_| *method == Method::Get) else {
+        return Reply::json(404, serde_json::json!({ "error": "Not found" }));
+    };
+
+    let result = match found {
+        Route::Metrics => {
+            let tracker_up = monitor.lock().map(|m| m.is_running()).unwrap_or(false);
+            return match db.lock() {
+                Ok(conn) => match metrics::render(&conn, tracker_up) {
+                    Ok(body) => Reply::text(200, body, metrics::CONTENT_TYPE),
+                    Err(e) => Reply::text(500, e.to_string(), "text/plain"),
+                },
+                Err(e) => Reply::text(500, e.to_string(), "text/plain"),
+            };
+        }
+        Route::OpenApi => return Reply::json(200, openapi::spec(port)),
+        Route::Events => return Reply::json(400, serde_json::json!({ "error": "Events are only streamed over HTTP" })),
+        Route::Focus => with_conn(db, |conn| {
+            focus::get_status(conn).map(|status| to_json(&status))
+        }),
+        Route::Categories => with_conn(db, |conn| {
+            get_category_totals(conn, today_start_timestamp(), Utc::now().timestamp())
+                .map(|totals| to_json(&totals))
+        }),
+    };
+
+    match result {
+        Ok(body) => Reply::json(200, body),
+        Err(e) => Reply::json(500, serde_json::json!({ "error": e })),
+    }
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request.headers().iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+fn authorized(request: &Request, token: &str) -> bool {
+    let bearer = header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    let query = request.url().split_once('?').map(|(_, query)| query).unwrap_or_default();
+    let param = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| value);
+    bearer.or(param).is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Lets the app's own webview read the response; `origin` has already been
+/// checked against `APP_ORIGINS`
+fn with_cors(response: JsonResponse, origin: Option<&str>) -> JsonResponse {
+    let Some(origin) = origin else {
+        return response;
+    };
+    [
+        ("Access-Control-Allow-Origin", origin),
+        ("Access-Control-Allow-Headers", "Authorization"),
+        ("Vary", "Origin"),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Header::from_bytes(name.as_bytes(), value.as_bytes()).ok())
+    .fold(response, |response, header| response.with_header(header))
+}
+
+fn with_conn<F>(db: &DbConnection, f: F) -> Result<serde_json::Value, String>
+where
+    F: FnOnce(&rusqlite::Connection) -> rusqlite::Result<serde_json::Value>,
+{
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    f(&conn).map_err(|e| e.to_string())
+}
+
+fn to_json<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> JsonResponse {
+    text_response(status, body.to_string(), "application/json")
+}
+
+fn text_response(status: u16, body: String, content_type: &str) -> JsonResponse {
+    let header = Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes())
+        .expect("content type is a valid header value");
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec_describes_every_route_and_nothing_else() {
+        let spec = openapi::spec(DEFAULT_PORT);
+        let paths = spec["paths"].as_object().expect("spec has paths");
+
+        for route in Route::ALL {
+            let item = paths.get(route.path()).unwrap_or_else(|| panic!("{} is missing from the spec", route.path()));
+            assert!(item.get("get").is_some(), "{} isn't described as a GET", route.path());
+        }
+        for path in paths.keys() {
+            assert!(Route::find(path).is_some(), "the spec describes {}, which isn't served", path);
+        }
+    }
+}
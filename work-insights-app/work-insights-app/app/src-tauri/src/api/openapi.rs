@@ -0,0 +1,193 @@
+// OpenAPI description
+// The local API described as an OpenAPI 3.0 document, served as
+// `GET /openapi.json` so third-party clients can be generated against it.
+// `npm run generate:api` turns this document into `src/services/localApi.ts`,
+// the typed client the dashboard reads the routes through (in-process, via
+// `local_api_request`) and that scripts outside the app can use over HTTP.
+// Any change to a route or to the JSON it returns belongs here too, with
+// `API_VERSION` bumped; a test checks the paths match `Route::ALL`.
+
+use serde_json::{json, Value};
+
+/// Version of the API contract, not of the app
+pub const API_VERSION: &str = "2.0.0";
+
+pub fn spec(port: u16) -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Work Insights local API",
+            "version": API_VERSION,
+            "description": "Read-only tracker state for scripts and tools on this machine. \
+                            Only listens on 127.0.0.1, and only while local_api_enabled is on. \
+                            Every request needs the token shown in the app's settings, and \
+                            requests from web pages other than the app's own are refused (403)."
+        },
+        "servers": [{ "url": format!("http://127.0.0.1:{}", port) }],
+        "security": [{ "bearerToken": [] }, { "queryToken": [] }],
+        "paths": {
+            "/focus": {
+                "get": {
+                    "operationId": "getFocus",
+                    "summary": "The running focus session, if any",
+                    "responses": {
+                        "200": json_content("#/components/schemas/FocusStatus"),
+                        "500": error_response()
+                    }
+                }
+            },
+            "/categories": {
+                "get": {
+                    "operationId": "getCategories",
+                    "summary": "Time per category so far today, largest first",
+                    "responses": {
+                        "200": {
+                            "description": "OK",
+                            "content": {
+                                "application/json": {
+                                    "schema": { "type": "array", "items": { "$ref": "#/components/schemas/CategoryTotal" } }
+                                }
+                            }
+                        },
+                        "500": error_response()
+                    }
+                }
+            },
+            "/metrics": {
+                "get": {
+                    "operationId": "getMetrics",
+                    "summary": "Today's numbers in the Prometheus text exposition format",
+                    "responses": {
+                        "200": {
+                            "description": "OK",
+                            "content": { "text/plain": { "schema": { "type": "string" } } }
+                        },
+                        "500": {
+                            "description": "Failed to read the database",
+                            "content": { "text/plain": { "schema": { "type": "string" } } }
+                        }
+                    }
+                }
+            },
+            "/events": {
+                "get": {
+                    "operationId": "getEvents",
+                    "summary": "Tracker events as they happen, as server-sent events",
+                    "description": "Each event is sent with the event name set to its `type` and the \
+                                    event as JSON data. A comment line keeps quiet streams alive.",
+                    "responses": {
+                        "200": {
+                            "description": "An open event stream",
+                            "content": {
+                                "text/event-stream": { "schema": { "$ref": "#/components/schemas/ApiEvent" } }
+                            }
+                        }
+                    }
+                }
+            },
+            "/openapi.json": {
+                "get": {
+                    "operationId": "getOpenApi",
+                    "summary": "This document",
+                    "responses": {
+                        "200": {
+                            "description": "OK",
+                            "content": { "application/json": { "schema": { "type": "object" } } }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "securitySchemes": {
+                "bearerToken": { "type": "http", "scheme": "bearer" },
+                "queryToken": { "type": "apiKey", "in": "query", "name": "token" }
+            },
+            "schemas": {
+                "FocusSession": {
+                    "type": "object",
+                    "required": ["started_at", "ends_at", "label"],
+                    "properties": {
+                        "started_at": { "type": "integer", "format": "int64", "description": "Unix seconds" },
+                        "ends_at": { "type": "integer", "format": "int64", "nullable": true, "description": "Unix seconds; null for an open-ended session" },
+                        "label": { "type": "string", "nullable": true }
+                    }
+                },
+                "FocusStatus": {
+                    "type": "object",
+                    "required": ["active", "session", "remaining_seconds"],
+                    "properties": {
+                        "active": { "type": "boolean" },
+                        "session": { "allOf": [{ "$ref": "#/components/schemas/FocusSession" }], "nullable": true },
+                        "remaining_seconds": { "type": "integer", "format": "int64", "nullable": true }
+                    }
+                },
+                "CategoryTotal": {
+                    "type": "object",
+                    "required": ["category", "total_seconds"],
+                    "properties": {
+                        "category": { "type": "string" },
+                        "total_seconds": { "type": "integer", "format": "int64" }
+                    }
+                },
+                "ActivityChangedEvent": event_schema("activity_changed", json!({
+                    "app_name": { "type": "string" },
+                    "window_title": { "type": "string" }
+                })),
+                "IdleStartedEvent": event_schema("idle_started", json!({})),
+                "IdleEndedEvent": event_schema("idle_ended", json!({})),
+                "FocusSessionStartedEvent": event_schema("focus_session_started", json!({
+                    "session": { "$ref": "#/components/schemas/FocusSession" }
+                })),
+                "FocusSessionEndedEvent": event_schema("focus_session_ended", json!({
+                    "session": { "$ref": "#/components/schemas/FocusSession" }
+                })),
+                "ApiEvent": {
+                    "oneOf": [
+                        { "$ref": "#/components/schemas/ActivityChangedEvent" },
+                        { "$ref": "#/components/schemas/IdleStartedEvent" },
+                        { "$ref": "#/components/schemas/IdleEndedEvent" },
+                        { "$ref": "#/components/schemas/FocusSessionStartedEvent" },
+                        { "$ref": "#/components/schemas/FocusSessionEndedEvent" }
+                    ],
+                    "discriminator": { "propertyName": "type" }
+                },
+                "ApiError": {
+                    "type": "object",
+                    "required": ["error"],
+                    "properties": { "error": { "type": "string" } }
+                }
+            }
+        }
+    })
+}
+
+fn json_content(schema: &str) -> Value {
+    json!({
+        "description": "OK",
+        "content": { "application/json": { "schema": { "$ref": schema } } }
+    })
+}
+
+fn error_response() -> Value {
+    json!({
+        "description": "Failed to read the database",
+        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/ApiError" } } }
+    })
+}
+
+/// An `ApiEvent` variant: its `type` tag, a timestamp and `fields`
+fn event_schema(name: &str, fields: Value) -> Value {
+    let mut properties = json!({
+        "type": { "type": "string", "enum": [name] },
+        "timestamp": { "type": "integer", "format": "int64", "description": "Unix seconds" }
+    });
+    let mut required = vec!["type".to_string(), "timestamp".to_string()];
+    if let (Some(properties), Some(fields)) = (properties.as_object_mut(), fields.as_object()) {
+        for (key, schema) in fields {
+            properties.insert(key.clone(), schema.clone());
+            required.push(key.clone());
+        }
+    }
+    json!({ "type": "object", "required": required, "properties": properties })
+}
@@ -0,0 +1,76 @@
+// Blocked apps
+// Apps listed in the `blocked_apps` setting are never tracked. The activity
+// monitor treats a sample with a blocked app in the foreground as one without
+// a window, so neither its raw snapshot nor an activity is written, and it
+// doesn't count towards streaks, budgets or presentation detection. Names
+// match the app name exactly, ignoring case.
+//
+// With `blocked_apps_minimize_in_focus` on, a blocked app that takes focus
+// during a focus session is also minimized (hidden on macOS), where the
+// platform allows it.
+
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::activity::{self, ActivitySnapshot};
+use crate::database::queries::{get_setting, set_setting};
+use crate::focus;
+
+pub const BLOCKED_APPS_KEY: &str = "blocked_apps";
+const MINIMIZE_IN_FOCUS_KEY: &str = "blocked_apps_minimize_in_focus";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlockingSettings {
+    pub apps: Vec<String>,
+    pub minimize_in_focus: bool,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<BlockingSettings> {
+    Ok(BlockingSettings {
+        apps: get_blocked_apps(conn)?,
+        minimize_in_focus: get_setting(conn, MINIMIZE_IN_FOCUS_KEY)?.is_some_and(|v| v == "true"),
+    })
+}
+
+pub fn save_settings(conn: &Connection, settings: &BlockingSettings) -> Result<()> {
+    let mut apps: Vec<String> = Vec::new();
+    for app in settings.apps.iter().map(|app| app.trim()).filter(|app| !app.is_empty()) {
+        if !apps.iter().any(|existing| existing.eq_ignore_ascii_case(app)) {
+            apps.push(app.to_string());
+        }
+    }
+
+    let json = serde_json::to_string(&apps).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, BLOCKED_APPS_KEY, &json)?;
+    set_setting(conn, MINIMIZE_IN_FOCUS_KEY, &settings.minimize_in_focus.to_string())
+}
+
+pub fn get_blocked_apps(conn: &Connection) -> Result<Vec<String>> {
+    Ok(get_setting(conn, BLOCKED_APPS_KEY)?
+        .and_then(|value| serde_json::from_str::<Vec<String>>(&value).ok())
+        .unwrap_or_default())
+}
+
+pub fn is_blocked(blocked_apps: &[String], app_name: &str) -> bool {
+    blocked_apps.iter().any(|blocked| blocked.trim().eq_ignore_ascii_case(app_name.trim()))
+}
+
+/// The sample as the monitor should record it: `None` when a blocked app is
+/// in the foreground, which is then minimized if a focus session calls for it
+pub fn enforce(conn: &Connection, snapshot: Option<ActivitySnapshot>) -> Result<Option<ActivitySnapshot>> {
+    let Some(snapshot) = snapshot else {
+        return Ok(None);
+    };
+    let settings = get_settings(conn)?;
+    if !is_blocked(&settings.apps, &snapshot.app_name) {
+        return Ok(Some(snapshot));
+    }
+
+    if settings.minimize_in_focus
+        && focus::get_active_session(conn)?.is_some()
+        && activity::minimize_foreground_window()
+    {
+        println!("✅ Minimized blocked app {} during focus session", snapshot.app_name);
+    }
+    Ok(None)
+}
@@ -0,0 +1,156 @@
+// Break reminders
+// Suggests a break after `break_reminder_minutes` of continuous activity.
+// Activity counts as continuous until the user has been idle for
+// `MIN_BREAK_SECONDS`, the session is locked, or the machine sleeps; a
+// shorter pause to read something doesn't reset it. Evaluated by the
+// activity monitor on every sample.
+//
+// Without a break, the reminder repeats every `break_reminder_snooze_minutes`.
+// Snoozing holds it back for a while without resetting the activity, so it
+// fires once the snooze is over if the user is still going.
+
+use chrono::Utc;
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::ActivitySnapshot;
+use crate::database::queries::{get_setting, set_setting};
+use crate::notifications::{self, NotificationKind};
+
+pub const BREAK_REMINDER_EVENT: &str = "break-reminder";
+
+const ENABLED_KEY: &str = "break_reminder_enabled";
+const THRESHOLD_MINUTES_KEY: &str = "break_reminder_minutes";
+const SNOOZE_MINUTES_KEY: &str = "break_reminder_snooze_minutes";
+pub const SNOOZED_UNTIL_KEY: &str = "break_reminder_snoozed_until";
+/// Idle time that counts as a break
+const MIN_BREAK_SECONDS: i64 = 5 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakSettings {
+    pub enabled: bool,
+    pub threshold_minutes: i64,
+    /// How long a reminder is held back when snoozed, and how often it
+    /// repeats while no break is taken
+    pub snooze_minutes: i64,
+}
+
+impl Default for BreakSettings {
+    fn default() -> Self {
+        Self { enabled: true, threshold_minutes: 50, snooze_minutes: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakReminder {
+    /// When the continuous activity began
+    pub active_since: i64,
+    pub active_seconds: i64,
+    pub triggered_at: i64,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<BreakSettings> {
+    let defaults = BreakSettings::default();
+    let minutes = |key, default| -> Result<i64> {
+        Ok(get_setting(conn, key)?.and_then(|v| v.trim().parse().ok()).unwrap_or(default))
+    };
+
+    Ok(BreakSettings {
+        enabled: get_setting(conn, ENABLED_KEY)?.map_or(defaults.enabled, |v| v == "true"),
+        threshold_minutes: minutes(THRESHOLD_MINUTES_KEY, defaults.threshold_minutes)?,
+        snooze_minutes: minutes(SNOOZE_MINUTES_KEY, defaults.snooze_minutes)?,
+    })
+}
+
+pub fn save_settings(conn: &Connection, settings: &BreakSettings) -> std::result::Result<(), String> {
+    if settings.threshold_minutes < 1 {
+        return Err("Reminder threshold must be at least a minute".to_string());
+    }
+    if settings.snooze_minutes < 1 {
+        return Err("Snooze must be at least a minute".to_string());
+    }
+
+    let save = |key, value: String| set_setting(conn, key, &value).map_err(|e| e.to_string());
+    save(ENABLED_KEY, settings.enabled.to_string())?;
+    save(THRESHOLD_MINUTES_KEY, settings.threshold_minutes.to_string())?;
+    save(SNOOZE_MINUTES_KEY, settings.snooze_minutes.to_string())
+}
+
+/// Holds back reminders for `minutes`, or the configured snooze when not
+/// given. Returns when the snooze ends.
+pub fn snooze(conn: &Connection, minutes: Option<i64>) -> Result<i64> {
+    let minutes = match minutes {
+        Some(minutes) => minutes,
+        None => get_settings(conn)?.snooze_minutes,
+    };
+    let until = Utc::now().timestamp() + minutes * 60;
+    set_setting(conn, SNOOZED_UNTIL_KEY, &until.to_string())?;
+    Ok(until)
+}
+
+/// Continuous activity for the monitor thread
+#[derive(Default)]
+pub struct BreakTracker {
+    active_since: Option<i64>,
+    last_reminder_at: Option<i64>,
+}
+
+impl BreakTracker {
+    /// Updates the activity with the latest sample and returns a reminder if
+    /// one became due
+    pub fn observe(
+        &mut self,
+        conn: &Connection,
+        snapshot: Option<&ActivitySnapshot>,
+        idle_seconds: Option<i64>,
+        now: i64,
+    ) -> Result<Option<BreakReminder>> {
+        let on_break = snapshot.is_none() || idle_seconds.is_some_and(|idle| idle >= MIN_BREAK_SECONDS);
+        if on_break {
+            *self = Self::default();
+            return Ok(None);
+        }
+
+        let settings = get_settings(conn)?;
+        if !settings.enabled {
+            *self = Self::default();
+            return Ok(None);
+        }
+
+        let active_since = *self.active_since.get_or_insert(now);
+        let due_at = match self.last_reminder_at {
+            None => active_since + settings.threshold_minutes * 60,
+            Some(last) => last + settings.snooze_minutes * 60,
+        };
+        let snoozed = get_setting(conn, SNOOZED_UNTIL_KEY)?
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .is_some_and(|until| until > now);
+        if now < due_at || snoozed {
+            return Ok(None);
+        }
+
+        self.last_reminder_at = Some(now);
+        Ok(Some(BreakReminder { active_since, active_seconds: now - active_since, triggered_at: now }))
+    }
+}
+
+/// Tells the frontend and shows a system notification
+pub fn notify(app: &AppHandle, reminder: &BreakReminder) {
+    if let Err(e) = app.emit(BREAK_REMINDER_EVENT, reminder) {
+        eprintln!("❌ Failed to emit break reminder: {}", e);
+    }
+
+    let minutes = reminder.active_seconds / 60;
+    let duration = match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} minutes", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    };
+    notifications::notify(
+        app,
+        NotificationKind::BreakReminder,
+        "Time for a break",
+        &format!("You've been active for {} without a break", duration),
+    );
+}
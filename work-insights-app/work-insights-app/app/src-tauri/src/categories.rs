@@ -0,0 +1,310 @@
+// Categories and category rules
+// Categories are user-defined, each with a color and a rating of how
+// productive time in it is. Activities refer to them by name, so renaming
+// one carries its activities and rules along.
+//
+// Each activity is assigned a category by matching its app name or window
+// title against user-defined rules. Rules are tried in priority order and the
+// first match wins, so a specific rule ("github.com" in the title is
+// Development) can sit above a general one (any browser is Browsing). Applied
+// as activities are inserted; `recategorize` re-applies the current rules to
+// stored activities after the rules change.
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::database::queries::Activity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rating {
+    Productive,
+    Neutral,
+    Distracting,
+}
+
+impl Rating {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Rating::Productive => "productive",
+            Rating::Neutral => "neutral",
+            Rating::Distracting => "distracting",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "productive" => Rating::Productive,
+            "distracting" => Rating::Distracting,
+            _ => Rating::Neutral,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    /// CSS color, e.g. "#4f46e5"
+    pub color: String,
+    pub rating: Rating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchField {
+    App,
+    Title,
+    /// Either the app name or the window title
+    Any,
+}
+
+impl MatchField {
+    fn as_str(self) -> &'static str {
+        match self {
+            MatchField::App => "app",
+            MatchField::Title => "title",
+            MatchField::Any => "any",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "app" => MatchField::App,
+            "title" => MatchField::Title,
+            _ => MatchField::Any,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub id: Option<i64>,
+    pub category: String,
+    /// Case-insensitive substring of the field being matched
+    pub pattern: String,
+    pub match_field: MatchField,
+    /// Lower numbers are tried first
+    pub priority: i64,
+    pub enabled: bool,
+}
+
+impl CategoryRule {
+    fn matches(&self, app_name: &str, window_title: &str) -> bool {
+        let pattern = self.pattern.trim().to_lowercase();
+        let app = || app_name.to_lowercase().contains(&pattern);
+        let title = || window_title.to_lowercase().contains(&pattern);
+        !pattern.is_empty()
+            && match self.match_field {
+                MatchField::App => app(),
+                MatchField::Title => title(),
+                MatchField::Any => app() || title(),
+            }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecategorizeResult {
+    pub activities_scanned: usize,
+    pub activities_changed: usize,
+}
+
+pub fn list_categories(conn: &Connection) -> Result<Vec<Category>> {
+    let mut stmt = conn.prepare("SELECT id, name, color, rating FROM categories ORDER BY name COLLATE NOCASE")?;
+    let categories = stmt.query_map([], row_to_category)?.collect::<Result<Vec<_>>>()?;
+    Ok(categories)
+}
+
+pub fn get_category(conn: &Connection, category_id: i64) -> Result<Option<Category>> {
+    conn.query_row(
+        "SELECT id, name, color, rating FROM categories WHERE id = ?1",
+        params![category_id],
+        row_to_category,
+    )
+    .optional()
+}
+
+/// Fails with a constraint violation if the name is taken, ignoring case
+pub fn create_category(conn: &Connection, name: &str, color: &str, rating: Rating) -> Result<Category> {
+    conn.execute(
+        "INSERT INTO categories (name, color, rating) VALUES (?1, ?2, ?3)",
+        params![name.trim(), color.trim(), rating.as_str()],
+    )?;
+    Ok(Category {
+        id: conn.last_insert_rowid(),
+        name: name.trim().to_string(),
+        color: color.trim().to_string(),
+        rating,
+    })
+}
+
+/// Renames a category along with the activities and rules that use it
+pub fn rename_category(conn: &mut Connection, category_id: i64, name: &str) -> Result<Option<Category>> {
+    let Some(category) = get_category(conn, category_id)? else {
+        return Ok(None);
+    };
+    let name = name.trim();
+
+    let tx = conn.transaction()?;
+    tx.execute("UPDATE categories SET name = ?2 WHERE id = ?1", params![category_id, name])?;
+    tx.execute("UPDATE activities SET category = ?2 WHERE category = ?1 COLLATE NOCASE", params![category.name, name])?;
+    tx.execute("UPDATE category_rules SET category = ?2 WHERE category = ?1 COLLATE NOCASE", params![category.name, name])?;
+    tx.commit()?;
+
+    Ok(Some(Category { name: name.to_string(), ..category }))
+}
+
+pub fn update_category(conn: &Connection, category_id: i64, color: &str, rating: Rating) -> Result<Option<Category>> {
+    conn.execute(
+        "UPDATE categories SET color = ?2, rating = ?3 WHERE id = ?1",
+        params![category_id, color.trim(), rating.as_str()],
+    )?;
+    get_category(conn, category_id)
+}
+
+/// Deletes a category and the rules assigning it; its activities become
+/// uncategorized
+pub fn delete_category(conn: &mut Connection, category_id: i64) -> Result<()> {
+    let Some(category) = get_category(conn, category_id)? else {
+        return Ok(());
+    };
+
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM categories WHERE id = ?1", params![category_id])?;
+    tx.execute("UPDATE activities SET category = NULL WHERE category = ?1 COLLATE NOCASE", params![category.name])?;
+    tx.execute("DELETE FROM category_rules WHERE category = ?1 COLLATE NOCASE", params![category.name])?;
+    tx.commit()
+}
+
+fn row_to_category(row: &rusqlite::Row) -> Result<Category> {
+    Ok(Category {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        color: row.get(2)?,
+        rating: Rating::parse(&row.get::<_, String>(3)?),
+    })
+}
+
+/// The category of the first enabled rule matching the activity. Idle time
+/// is never categorized.
+pub fn categorize(rules: &[CategoryRule], activity: &Activity) -> Option<String> {
+    if activity.is_idle {
+        return None;
+    }
+    categorize_window(rules, &activity.app_name, activity.window_title.as_deref().unwrap_or_default())
+}
+
+/// The category of the first enabled rule matching a window
+pub fn categorize_window(rules: &[CategoryRule], app_name: &str, window_title: &str) -> Option<String> {
+    rules
+        .iter()
+        .find(|rule| rule.enabled && rule.matches(app_name, window_title))
+        .map(|rule| rule.category.clone())
+}
+
+pub fn get_rules(conn: &Connection) -> Result<Vec<CategoryRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, category, pattern, match_field, priority, enabled
+         FROM category_rules
+         ORDER BY priority, id",
+    )?;
+
+    let rules = stmt
+        .query_map([], |row| {
+            Ok(CategoryRule {
+                id: Some(row.get(0)?),
+                category: row.get(1)?,
+                pattern: row.get(2)?,
+                match_field: MatchField::parse(&row.get::<_, String>(3)?),
+                priority: row.get(4)?,
+                enabled: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(rules)
+}
+
+pub fn save_rule(conn: &Connection, rule: &CategoryRule) -> Result<CategoryRule> {
+    let (category, pattern) = (rule.category.trim(), rule.pattern.trim());
+    let id = match rule.id {
+        Some(id) => {
+            conn.execute(
+                "UPDATE category_rules
+                 SET category = ?2, pattern = ?3, match_field = ?4, priority = ?5, enabled = ?6
+                 WHERE id = ?1",
+                params![id, category, pattern, rule.match_field.as_str(), rule.priority, rule.enabled],
+            )?;
+            id
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO category_rules (category, pattern, match_field, priority, enabled)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![category, pattern, rule.match_field.as_str(), rule.priority, rule.enabled],
+            )?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    Ok(CategoryRule {
+        id: Some(id),
+        category: category.to_string(),
+        pattern: pattern.to_string(),
+        ..rule.clone()
+    })
+}
+
+pub fn delete_rule(conn: &Connection, rule_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM category_rules WHERE id = ?1", params![rule_id])?;
+    Ok(())
+}
+
+/// Re-applies the current rules to the tracked activities in the range,
+/// leaving activities no rule matches uncategorized. Manual and edited
+/// activities keep the category they were given.
+pub fn recategorize(conn: &mut Connection, start_timestamp: i64, end_timestamp: i64) -> Result<RecategorizeResult> {
+    let rules = get_rules(conn)?;
+    let tx = conn.transaction()?;
+    let mut scanned = 0;
+    let mut changed = 0;
+    {
+        let mut select = tx.prepare(
+            "SELECT id, app_name, window_title, is_idle, category
+             FROM activities
+             WHERE timestamp BETWEEN ?1 AND ?2 AND is_manual = 0 AND is_edited = 0",
+        )?;
+        let mut update = tx.prepare("UPDATE activities SET category = ?2 WHERE id = ?1")?;
+
+        let rows = select
+            .query_map(params![start_timestamp, end_timestamp], |row| {
+                let activity = Activity {
+                    id: Some(row.get(0)?),
+                    timestamp: 0,
+                    app_name: row.get(1)?,
+                    window_title: row.get(2)?,
+                    duration_seconds: 0,
+                    is_idle: row.get(3)?,
+                    category: row.get(4)?,
+                    algorithm_version: None,
+                    is_manual: false,
+                    project: None,
+                    is_edited: false,
+                };
+                Ok(activity)
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        for activity in rows {
+            scanned += 1;
+            let category = categorize(&rules, &activity);
+            if category != activity.category {
+                update.execute(params![activity.id, category])?;
+                changed += 1;
+            }
+        }
+    }
+    tx.commit()?;
+
+    Ok(RecategorizeResult { activities_scanned: scanned, activities_changed: changed })
+}
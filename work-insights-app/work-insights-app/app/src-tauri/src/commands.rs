@@ -1,40 +1,103 @@
-use tauri::State;
+use tauri::{AppHandle, State};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use chrono::Utc;
+use chrono::{Datelike, Days, Local, TimeZone, Utc};
 
-use crate::activity::monitor::ActivityMonitor;
-use crate::database::{DbConnection, queries::{get_today_stats, get_activities_by_date_range, TodayStats, Activity}};
+use crate::activity::{self, coalesce::{self, CompactResult}, edits::{self, ActivityUpdate}, gaps::{self, GapAnnotation, TimelineGap}, manual, merge::{self, RebuildResult}, monitor::ActivityMonitor};
+use crate::alerts::{self, StreakAlert, StreakAlertRule};
+use crate::api::{self, LocalApiInfo};
+use crate::blocking::{self, BlockingSettings};
+use crate::breaks::{self, BreakSettings};
+use crate::categories::{self, Category, CategoryRule, Rating, RecategorizeResult};
+use crate::database::{DbConnection, console::{self, QueryResult}, day_notes::{self, DayNote}, integrity::{self, IntegrityReport, RepairResult}, snapshots::{self, RawSnapshotExport}, storage::{self, MigrationResult, StorageStatus}, queries::{self, get_today_stats, get_activities_by_date_range, get_hourly_breakdown, parse_date, parse_month, AppUsage, CurrentSegment, HourlyBreakdown, PeriodStats, TodayStats, Activity}};
+use crate::deep_work::{self, DeepWorkSession, DeepWorkSettings};
+use crate::distractions::{self, DistractionBudget, DistractionUsage};
+use crate::export::{self, CsvExportResult, DataExportResult, DataImportResult};
+use crate::focus::{self, FocusSession};
+use crate::forecast::{self, Forecast};
+use crate::focus_timer;
+use crate::friction::{self, FrictionPrompt, FrictionStats};
+use crate::goals::{self, Goal, GoalProgress, GoalStreaks};
+use crate::insights::{self, NarrativeInsights, WeekTrends};
+use crate::notifications::{self, NotificationSettings, SuppressedNotification};
+use crate::pause::{self, PauseStatus};
+use crate::productivity::{self, ProductivityScore};
+use crate::profile::{self, ProfileImportResult, SettingsProfile};
+use crate::presentation::{self, PresentationStatus};
+use crate::privacy::{self, PrivacyMode};
+use crate::ranges::{self, RangePreset, ResolvedRange};
+use crate::projects::{self, DetectResult, Project, ProjectRule, ProjectTimeReport};
+use crate::redaction::{self, RedactionPreview, RedactionRule};
+use crate::report::{self, WeeklyReport};
+use crate::settings;
+use crate::switching::{self, SwitchingStats};
+use crate::timers::{self, TimerEntry, TimerHotkey, TimerHotkeyStatus};
+use crate::tray;
 
 pub struct AppState {
     pub monitor: Arc<Mutex<ActivityMonitor>>,
+    pub db_path: PathBuf,
 }
 
 #[tauri::command]
-pub fn start_tracking(state: State<AppState>) -> Result<(), String> {
+pub fn start_tracking(app: AppHandle, state: State<AppState>, db: State<DbConnection>) -> Result<(), String> {
+    pause::cancel(&app, &db)?;
     let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
     monitor.start();
     Ok(())
 }
 
 #[tauri::command]
-pub fn stop_tracking(state: State<AppState>) -> Result<(), String> {
+pub fn stop_tracking(app: AppHandle, state: State<AppState>, db: State<DbConnection>) -> Result<(), String> {
+    pause::cancel(&app, &db)?;
     let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
     monitor.stop();
     Ok(())
 }
 
+/// Stops tracking for `minutes`, after which it starts again by itself
+#[tauri::command]
+pub fn pause_tracking(app: AppHandle, state: State<AppState>, db: State<DbConnection>, minutes: i64) -> Result<PauseStatus, String> {
+    pause::pause(&app, &db, &state.monitor, minutes)
+}
+
+#[tauri::command]
+pub fn get_pause_status(db: State<DbConnection>) -> Result<PauseStatus, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    pause::get_status(&conn).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_tracking_status(state: State<AppState>) -> Result<bool, String> {
     let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
     Ok(monitor.is_running())
 }
 
+/// Lets the UI explain missing window titles and re-request access on macOS
+#[tauri::command]
+pub fn check_window_title_access(prompt: Option<bool>) -> bool {
+    activity::has_window_title_access(prompt.unwrap_or(false))
+}
+
 #[tauri::command]
 pub fn get_today_statistics(db: State<DbConnection>) -> Result<TodayStats, String> {
     let conn = db.lock().map_err(|e| e.to_string())?;
     get_today_stats(&conn).map_err(|e| e.to_string())
 }
 
+/// The activity being accumulated right now, which the dashboard polls.
+/// `None` while tracking is stopped or nothing is in the foreground.
+#[tauri::command]
+pub fn get_current_segment(state: State<AppState>, db: State<DbConnection>) -> Result<Option<CurrentSegment>, String> {
+    let open = state.monitor.lock().map_err(|e| e.to_string())?.current_activity();
+    let Some(open) = open else {
+        return Ok(None);
+    };
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    queries::get_current_segment(&conn, open).map(Some).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_activities(
     db: State<DbConnection>,
@@ -46,6 +109,179 @@ pub fn get_activities(
         .map_err(|e| e.to_string())
 }
 
+/// Records time away from the computer (a whiteboard session, reading) as
+/// active time under `label`. Fails if it overlaps tracked active time.
+#[tauri::command]
+pub fn add_manual_activity(
+    state: State<AppState>,
+    db: State<DbConnection>,
+    start: i64,
+    end: i64,
+    label: String,
+    category: Option<String>,
+) -> Result<Activity, String> {
+    let open = state.monitor.lock().map_err(|e| e.to_string())?.current_activity();
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    manual::add(&mut conn, start, end, &label, category.as_deref(), open.as_ref(), Utc::now().timestamp())
+}
+
+/// Stretches of at least `min_gap_minutes` (default 15) between the
+/// timestamps where nothing was recorded: tracking off, app closed, machine
+/// asleep
+#[tauri::command]
+pub fn get_untracked_gaps(
+    state: State<AppState>,
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    min_gap_minutes: Option<i64>,
+) -> Result<Vec<TimelineGap>, String> {
+    let open = state.monitor.lock().map_err(|e| e.to_string())?.current_activity();
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let min_seconds = min_gap_minutes.unwrap_or(gaps::DEFAULT_MIN_GAP_MINUTES).max(1) * 60;
+    gaps::find(&conn, start_timestamp, end_timestamp, min_seconds, open.as_ref(), Utc::now().timestamp())
+        .map_err(|e| e.to_string())
+}
+
+/// Labels untracked time without counting it as activity
+#[tauri::command]
+pub fn annotate_gap(db: State<DbConnection>, start: i64, end: i64, label: String) -> Result<GapAnnotation, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    gaps::annotate(&mut conn, start, end, &label)
+}
+
+#[tauri::command]
+pub fn delete_gap_annotation(db: State<DbConnection>, annotation_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    gaps::delete_annotation(&conn, annotation_id).map_err(|e| e.to_string())
+}
+
+/// Changes an activity's app name, window title, category or project
+#[tauri::command]
+pub fn update_activity(db: State<DbConnection>, activity_id: i64, update: ActivityUpdate) -> Result<Activity, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    edits::update(&conn, activity_id, &update)
+}
+
+#[tauri::command]
+pub fn delete_activity(db: State<DbConnection>, activity_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    edits::delete(&conn, activity_id)
+}
+
+/// Merges the activities into the earliest one, adding up their durations
+#[tauri::command]
+pub fn merge_activities(db: State<DbConnection>, activity_ids: Vec<i64>) -> Result<Activity, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    edits::merge(&mut conn, &activity_ids)
+}
+
+/// Active and idle time and the top app for each hour of a local calendar
+/// day ("YYYY-MM-DD"), for drawing the day timeline
+#[tauri::command]
+pub fn get_hourly_statistics(db: State<DbConnection>, date: String) -> Result<Vec<HourlyBreakdown>, String> {
+    let date = parse_date(&date)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    get_hourly_breakdown(&conn, date).map_err(|e| e.to_string())
+}
+
+/// Daily totals for the week starting on `week_start` ("YYYY-MM-DD")
+#[tauri::command]
+pub fn get_weekly_stats(db: State<DbConnection>, week_start: String) -> Result<PeriodStats, String> {
+    let week_start = parse_date(&week_start)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    queries::get_weekly_stats(&conn, week_start).map_err(|e| e.to_string())
+}
+
+/// Daily totals for a calendar month ("YYYY-MM")
+#[tauri::command]
+pub fn get_monthly_stats(db: State<DbConnection>, month: String) -> Result<PeriodStats, String> {
+    let month = parse_month(&month)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    queries::get_monthly_stats(&conn, month).map_err(|e| e.to_string())
+}
+
+/// Built-in and saved range presets, with the dates each covers today
+#[tauri::command]
+pub fn list_range_presets(db: State<DbConnection>) -> Result<Vec<ResolvedRange>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    ranges::list_presets(&conn)
+}
+
+#[tauri::command]
+pub fn resolve_range_preset(db: State<DbConnection>, id: String) -> Result<ResolvedRange, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    ranges::resolve(&conn, &id)
+}
+
+/// Saves a custom preset, adding it when its id is empty
+#[tauri::command]
+pub fn save_range_preset(db: State<DbConnection>, preset: RangePreset) -> Result<ResolvedRange, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    ranges::save_preset(&conn, &preset)
+}
+
+#[tauri::command]
+pub fn delete_range_preset(db: State<DbConnection>, id: String) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    ranges::delete_preset(&conn, &id).map_err(|e| e.to_string())
+}
+
+/// Projected active time per category for the week starting on `week_start`
+/// ("YYYY-MM-DD"), by default next week from Monday
+#[tauri::command]
+pub fn get_forecast(db: State<DbConnection>, week_start: Option<String>) -> Result<Forecast, String> {
+    let week_start = match week_start {
+        Some(date) => parse_date(&date)?,
+        None => {
+            let today = Local::now().date_naive();
+            today + Days::new(7 - today.weekday().num_days_from_monday() as u64)
+        }
+    };
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    forecast::get_forecast(&conn, week_start).map_err(|e| e.to_string())
+}
+
+/// The `limit` most used apps in the range (default 10)
+#[tauri::command]
+pub fn get_top_apps(
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    limit: Option<usize>,
+) -> Result<Vec<AppUsage>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    queries::get_top_apps(&conn, start_timestamp, end_timestamp, limit.unwrap_or(10).clamp(1, 100))
+        .map_err(|e| e.to_string())
+}
+
+/// Active seconds per weekday (Monday first) and hour over the range
+#[tauri::command]
+pub fn get_activity_heatmap(
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<Vec<Vec<i64>>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    queries::get_activity_heatmap(&conn, start_timestamp, end_timestamp).map_err(|e| e.to_string())
+}
+
+/// Switch rate and focus blocks per day from `start_date` to `end_date`
+/// inclusive
+#[tauri::command]
+pub fn get_switching_stats(
+    db: State<DbConnection>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<SwitchingStats>, String> {
+    let (start, end) = (parse_date(&start_date)?, parse_date(&end_date)?);
+    if (end - start).num_days() > 366 {
+        return Err("Range must not exceed a year".to_string());
+    }
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    switching::get_switching_stats(&conn, start, end).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_current_time() -> i64 {
     Utc::now().timestamp()
@@ -59,3 +295,979 @@ pub fn get_activity_count(db: State<DbConnection>) -> Result<i64, String> {
         .map_err(|e| e.to_string())?;
     Ok(count)
 }
+
+#[tauri::command]
+pub fn start_focus_session(
+    app: AppHandle,
+    db: State<DbConnection>,
+    duration_minutes: Option<i64>,
+    label: Option<String>,
+) -> Result<FocusSession, String> {
+    focus::start_session(&app, db.inner(), duration_minutes, label)
+}
+
+#[tauri::command]
+pub fn end_focus_session(
+    app: AppHandle,
+    db: State<DbConnection>,
+) -> Result<Option<FocusSession>, String> {
+    focus::end_session(&app, db.inner())
+}
+
+/// Turns the always-on-top focus timer on or off, applying it to the running
+/// session right away
+#[tauri::command]
+pub fn set_focus_timer_overlay(app: AppHandle, db: State<DbConnection>, enabled: bool) -> Result<(), String> {
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        queries::set_setting(&conn, "focus_timer_overlay", if enabled { "true" } else { "false" })
+            .map_err(|e| e.to_string())?;
+    }
+    focus_timer::sync(&app, db.inner());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_goals(db: State<DbConnection>) -> Result<Vec<Goal>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    goals::get_goals(&conn).map_err(|e| e.to_string())
+}
+
+/// Adds a goal, or replaces the one with the same id
+#[tauri::command]
+pub fn save_goal(db: State<DbConnection>, goal: Goal) -> Result<Goal, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    goals::save_goal(&conn, &goal)
+}
+
+#[tauri::command]
+pub fn delete_goal(db: State<DbConnection>, goal_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    goals::delete_goal(&conn, goal_id).map_err(|e| e.to_string())
+}
+
+/// Progress of the enabled goals on a local day ("YYYY-MM-DD")
+#[tauri::command]
+pub fn get_goal_progress(db: State<DbConnection>, date: String) -> Result<Vec<GoalProgress>, String> {
+    let date = parse_date(&date)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    goals::get_progress(&conn, date).map_err(|e| e.to_string())
+}
+
+/// Days in a row on which every enabled goal held, now and at best
+#[tauri::command]
+pub fn get_streaks(db: State<DbConnection>) -> Result<GoalStreaks, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    goals::get_streaks(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_distraction_budgets(db: State<DbConnection>) -> Result<Vec<DistractionBudget>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    distractions::get_budgets(&conn).map_err(|e| e.to_string())
+}
+
+/// Adds a budget, or replaces the one with the same id
+#[tauri::command]
+pub fn save_distraction_budget(db: State<DbConnection>, budget: DistractionBudget) -> Result<DistractionBudget, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    distractions::save_budget(&conn, &budget)
+}
+
+#[tauri::command]
+pub fn delete_distraction_budget(db: State<DbConnection>, budget_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    distractions::delete_budget(&conn, budget_id).map_err(|e| e.to_string())
+}
+
+/// Recorded time against the enabled budgets on a local day ("YYYY-MM-DD")
+#[tauri::command]
+pub fn get_distraction_usage(db: State<DbConnection>, date: String) -> Result<Vec<DistractionUsage>, String> {
+    let date = parse_date(&date)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    distractions::get_usage(&conn, date).map_err(|e| e.to_string())
+}
+
+/// Deep work sessions detected on the days from `start_date` to `end_date`
+/// inclusive
+#[tauri::command]
+pub fn get_focus_sessions(
+    db: State<DbConnection>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<DeepWorkSession>, String> {
+    let (start, end) = (parse_date(&start_date)?, parse_date(&end_date)?);
+    if (end - start).num_days() > 366 {
+        return Err("Range must not exceed a year".to_string());
+    }
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    deep_work::get_sessions(&conn, start, end).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_deep_work_settings(db: State<DbConnection>) -> Result<DeepWorkSettings, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    deep_work::get_settings(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_deep_work_settings(db: State<DbConnection>, settings: DeepWorkSettings) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    deep_work::save_settings(&conn, &settings)
+}
+
+#[tauri::command]
+pub fn get_blocking_settings(db: State<DbConnection>) -> Result<BlockingSettings, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    blocking::get_settings(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_blocking_settings(db: State<DbConnection>, settings: BlockingSettings) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    blocking::save_settings(&conn, &settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_private_apps(db: State<DbConnection>) -> Result<Vec<String>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    privacy::get_private_apps(&conn).map_err(|e| e.to_string())
+}
+
+/// Replaces the private app list, returning it as saved
+#[tauri::command]
+pub fn save_private_apps(db: State<DbConnection>, apps: Vec<String>) -> Result<Vec<String>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    privacy::save_private_apps(&conn, &apps).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_privacy_mode(db: State<DbConnection>) -> Result<PrivacyMode, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    privacy::get_mode(&conn).map_err(|e| e.to_string())
+}
+
+/// Turns privacy mode on or off from the next sample on, and shows it in
+/// the tray tooltip
+#[tauri::command]
+pub fn set_privacy_mode(app: AppHandle, db: State<DbConnection>, mode: PrivacyMode) -> Result<(), String> {
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        privacy::set_mode(&conn, mode).map_err(|e| e.to_string())?;
+    }
+    tray::refresh(&app);
+    Ok(())
+}
+
+/// Whether the local API is on, where, and the token it needs, for the
+/// settings to show
+#[tauri::command]
+pub fn get_local_api_info(db: State<DbConnection>) -> Result<LocalApiInfo, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    api::info(&conn).map_err(|e| e.to_string())
+}
+
+/// Answers a `GET` of a local API route in-process, for the dashboard's
+/// generated client. Works whether or not the HTTP server is on.
+#[tauri::command]
+pub fn local_api_request(state: State<AppState>, db: State<DbConnection>, path: String) -> Result<api::Reply, String> {
+    let port = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        api::info(&conn).map_err(|e| e.to_string())?.port
+    };
+    Ok(api::route(db.inner(), &state.monitor, port, &tiny_http::Method::Get, &path))
+}
+
+/// Replaces the local API token, locking out scripts that used the old one
+#[tauri::command]
+pub fn regenerate_local_api_token(db: State<DbConnection>) -> Result<String, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    api::regenerate_token(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_redaction_rules(db: State<DbConnection>) -> Result<Vec<RedactionRule>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    redaction::get_rules(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_redaction_rule(db: State<DbConnection>, rule: RedactionRule) -> Result<RedactionRule, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    redaction::save_rule(&conn, &rule)
+}
+
+#[tauri::command]
+pub fn delete_redaction_rule(db: State<DbConnection>, rule_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    redaction::delete_rule(&conn, rule_id).map_err(|e| e.to_string())
+}
+
+/// Recent window titles `rule` would change, and how, without saving it
+#[tauri::command]
+pub fn test_redaction_rule(
+    db: State<DbConnection>,
+    rule: RedactionRule,
+    limit: Option<usize>,
+) -> Result<Vec<RedactionPreview>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    redaction::test_rule(&conn, &rule, limit.map(|limit| limit.clamp(1, 1000)))
+}
+
+#[tauri::command]
+pub fn get_break_settings(db: State<DbConnection>) -> Result<BreakSettings, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    breaks::get_settings(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_break_settings(db: State<DbConnection>, settings: BreakSettings) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    breaks::save_settings(&conn, &settings)
+}
+
+/// Holds back break reminders for `minutes`, or the configured snooze.
+/// Returns when the snooze ends.
+#[tauri::command]
+pub fn snooze_break_reminder(db: State<DbConnection>, minutes: Option<i64>) -> Result<i64, String> {
+    if minutes.is_some_and(|minutes| minutes <= 0) {
+        return Err("Snooze duration must be positive".to_string());
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    breaks::snooze(&conn, minutes).map_err(|e| e.to_string())
+}
+
+/// Closes the focus timer until the next session starts
+#[tauri::command]
+pub fn hide_focus_timer(app: AppHandle) {
+    focus_timer::hide(&app);
+}
+
+/// The launch friction prompt waiting for an answer, if any
+#[tauri::command]
+pub fn get_friction_prompt() -> Option<FrictionPrompt> {
+    friction::get_pending()
+}
+
+/// Goes ahead with the prompt's app once its delay has passed, or backs out
+#[tauri::command]
+pub fn resolve_friction_prompt(app: AppHandle, db: State<DbConnection>, prompt_id: i64, proceed: bool) -> Result<(), String> {
+    friction::resolve(&app, db.inner(), prompt_id, proceed)
+}
+
+/// How often each app was prompted for and answered, most prompted first
+#[tauri::command]
+pub fn get_friction_stats(
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<Vec<FrictionStats>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    friction::get_stats(&conn, start_timestamp, end_timestamp).map_err(|e| e.to_string())
+}
+
+/// Starts a timer for the charge code, stopping the running one
+#[tauri::command]
+pub fn start_timer(app: AppHandle, db: State<DbConnection>, charge_code: String) -> Result<TimerEntry, String> {
+    timers::start(&app, db.inner(), &charge_code)
+}
+
+#[tauri::command]
+pub fn stop_timer(app: AppHandle, db: State<DbConnection>) -> Result<Option<TimerEntry>, String> {
+    timers::stop(&app, db.inner())
+}
+
+#[tauri::command]
+pub fn get_running_timer(db: State<DbConnection>) -> Result<Option<TimerEntry>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    timers::get_running(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_timer_hotkeys(app: AppHandle, db: State<DbConnection>) -> Result<Vec<TimerHotkeyStatus>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    timers::get_hotkeys(&app, &conn).map_err(|e| e.to_string())
+}
+
+/// Replaces the timer hotkeys; fails without changing anything if a
+/// shortcut conflicts
+#[tauri::command]
+pub fn save_timer_hotkeys(
+    app: AppHandle,
+    db: State<DbConnection>,
+    hotkeys: Vec<TimerHotkey>,
+) -> Result<Vec<TimerHotkeyStatus>, String> {
+    timers::save_hotkeys(&app, db.inner(), hotkeys)
+}
+
+#[tauri::command]
+pub async fn generate_narrative_insights(
+    db: State<'_, DbConnection>,
+    week_start: i64,
+) -> Result<NarrativeInsights, String> {
+    let (config, aggregate, trends) = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let config = insights::load_llm_config(&conn)?;
+        let mut aggregate = insights::compute_weekly_aggregate(&conn, week_start)
+            .map_err(|e| e.to_string())?;
+        if !config.share_category_names {
+            insights::anonymize_categories(&conn, &mut aggregate).map_err(|e| e.to_string())?;
+        }
+        let first_day = Local
+            .timestamp_opt(week_start, 0)
+            .single()
+            .ok_or_else(|| "Invalid week start".to_string())?
+            .date_naive();
+        let trends = insights::get_week_trends(&conn, first_day).map_err(|e| e.to_string())?;
+        (config, aggregate, trends)
+    };
+
+    let prompt = insights::build_prompt(&aggregate, &trends, config.include_day_notes);
+    let narrative = insights::request_narrative(&config, prompt).await?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    insights::store_narrative(&conn, &aggregate, &narrative).map_err(|e| e.to_string())
+}
+
+/// Trends for the week starting on `week_start` ("YYYY-MM-DD") against the
+/// week before, recomputed only when their activities changed
+#[tauri::command]
+pub fn get_week_insights(db: State<DbConnection>, week_start: String) -> Result<WeekTrends, String> {
+    let week_start = parse_date(&week_start)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    insights::get_week_trends(&conn, week_start).map_err(|e| e.to_string())
+}
+
+/// A markdown report of the week starting on `week_start` ("YYYY-MM-DD"),
+/// also written to `path` when given
+#[tauri::command]
+pub fn generate_weekly_report(
+    db: State<DbConnection>,
+    week_start: String,
+    path: Option<String>,
+) -> Result<WeeklyReport, String> {
+    let week_start = parse_date(&week_start)?;
+    let mut report = {
+        let mut conn = db.lock().map_err(|e| e.to_string())?;
+        report::generate(&mut conn, week_start).map_err(|e| e.to_string())?
+    };
+    if let Some(path) = path {
+        report::save(&mut report, &path)?;
+    }
+    Ok(report)
+}
+
+/// Scores a local calendar day ("YYYY-MM-DD") from 0 to 100
+#[tauri::command]
+pub fn get_productivity_score(db: State<DbConnection>, date: String) -> Result<ProductivityScore, String> {
+    let date = parse_date(&date)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    productivity::compute_score(&conn, date).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_day_note(db: State<DbConnection>, date: String) -> Result<Option<DayNote>, String> {
+    let date = parse_date(&date)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    day_notes::get_note(&conn, date).map_err(|e| e.to_string())
+}
+
+/// Notes from `start_date` to `end_date` inclusive, oldest first
+#[tauri::command]
+pub fn list_day_notes(db: State<DbConnection>, start_date: String, end_date: String) -> Result<Vec<DayNote>, String> {
+    let (start, end) = (parse_date(&start_date)?, parse_date(&end_date)?);
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    day_notes::get_notes(&conn, start, end).map_err(|e| e.to_string())
+}
+
+/// Saving an empty note deletes it
+#[tauri::command]
+pub fn save_day_note(db: State<DbConnection>, date: String, content: String) -> Result<Option<DayNote>, String> {
+    let date = parse_date(&date)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    if content.trim().is_empty() {
+        day_notes::delete_note(&conn, date).map_err(|e| e.to_string())?;
+        return Ok(None);
+    }
+    day_notes::save_note(&conn, date, &content).map(Some).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_day_note(db: State<DbConnection>, date: String) -> Result<(), String> {
+    let date = parse_date(&date)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    day_notes::delete_note(&conn, date).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn run_readonly_query(
+    state: State<'_, AppState>,
+    sql: String,
+) -> Result<QueryResult, String> {
+    let db_path = state.db_path.clone();
+    tauri::async_runtime::spawn_blocking(move || console::run_readonly_query(&db_path, &sql))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn get_streak_alert_rules(db: State<DbConnection>) -> Result<Vec<StreakAlertRule>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    alerts::get_rules(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_streak_alert_rule(
+    db: State<DbConnection>,
+    rule: StreakAlertRule,
+) -> Result<StreakAlertRule, String> {
+    if rule.pattern.trim().is_empty() {
+        return Err("Alert pattern must not be empty".to_string());
+    }
+    if rule.threshold_minutes <= 0 {
+        return Err("Alert threshold must be positive".to_string());
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    alerts::save_rule(&conn, &rule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_streak_alert_rule(db: State<DbConnection>, rule_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    alerts::delete_rule(&conn, rule_id).map_err(|e| e.to_string())
+}
+
+/// Returns when the snooze ends
+#[tauri::command]
+pub fn snooze_streak_alert(db: State<DbConnection>, rule_id: i64, minutes: i64) -> Result<i64, String> {
+    if minutes <= 0 {
+        return Err("Snooze duration must be positive".to_string());
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    alerts::snooze_rule(&conn, rule_id, minutes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_streak_alert_log(
+    db: State<DbConnection>,
+    limit: Option<i64>,
+) -> Result<Vec<StreakAlert>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    alerts::get_alert_log(&conn, limit.unwrap_or(100)).map_err(|e| e.to_string())
+}
+
+/// Every raw poll in the range, for attaching to bug reports and replaying
+/// the merging logic. Empty unless `raw_snapshots_enabled` was on at the time.
+#[tauri::command]
+pub fn export_raw_snapshots(
+    app: AppHandle,
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<RawSnapshotExport, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let snapshots = snapshots::get_raw_snapshots(&conn, start_timestamp, end_timestamp)
+        .map_err(|e| e.to_string())?;
+    let poll_interval = merge::sampling_interval(&conn).map_err(|e| e.to_string())?;
+
+    Ok(RawSnapshotExport {
+        app_version: app.package_info().version.to_string(),
+        exported_at: Utc::now().timestamp(),
+        start_timestamp,
+        end_timestamp,
+        poll_interval_seconds: poll_interval as u64,
+        snapshots,
+    })
+}
+
+/// Writes the activities in the range to a CSV file at `path`
+#[tauri::command]
+pub fn export_activities_csv(
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    path: String,
+) -> Result<CsvExportResult, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let result = export::export_activities_csv(&conn, start_timestamp, end_timestamp, &path)?;
+    println!("✅ Exported {} activities to {}", result.rows, path);
+    Ok(result)
+}
+
+/// Writes activities in the range, or all of them, with settings and
+/// categories to a JSON file that `import_data_json` can read back
+#[tauri::command]
+pub fn export_data_json(
+    app: AppHandle,
+    db: State<DbConnection>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+    path: String,
+) -> Result<DataExportResult, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let app_version = app.package_info().version.to_string();
+    let result = export::export_data_json(&conn, &app_version, start_timestamp, end_timestamp, &path)?;
+    println!("✅ Exported {} activities with settings to {}", result.activities, path);
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn import_data_json(db: State<DbConnection>, path: String) -> Result<DataImportResult, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let result = export::import_data_json(&mut conn, &path)?;
+    println!("✅ Imported {} activities with settings from {}", result.activities_imported, path);
+    Ok(result)
+}
+
+/// Which backend activities are kept in and, with a server, how far the
+/// copy there is behind
+#[tauri::command]
+pub async fn get_storage_status(db: State<'_, DbConnection>) -> Result<StorageStatus, String> {
+    let db = db.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || storage::get_status(&db))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Copies the local history to the server in `postgres_url` and keeps
+/// copying new activities there from now on. `from_start` offers every
+/// local activity again instead of only those not copied yet. Runs off the
+/// main thread, since it waits on the server for the whole copy.
+#[tauri::command]
+pub async fn migrate_to_server(db: State<'_, DbConnection>, from_start: Option<bool>) -> Result<MigrationResult, String> {
+    let db = db.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || storage::migrate_to_server(&db, from_start.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn rebuild_activities(
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    algorithm_version: Option<i64>,
+) -> Result<RebuildResult, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let poll_interval = merge::sampling_interval(&conn).map_err(|e| e.to_string())?;
+    merge::rebuild_activities(
+        &mut conn,
+        start_timestamp,
+        end_timestamp,
+        algorithm_version.unwrap_or(merge::CURRENT_ALGORITHM_VERSION),
+        poll_interval,
+        Utc::now().timestamp(),
+    )
+}
+
+#[tauri::command]
+pub fn list_categories(db: State<DbConnection>) -> Result<Vec<Category>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    categories::list_categories(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_category(
+    db: State<DbConnection>,
+    name: String,
+    color: String,
+    rating: Option<Rating>,
+) -> Result<Category, String> {
+    if name.trim().is_empty() {
+        return Err("Category name must not be empty".to_string());
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    categories::create_category(&conn, &name, &color, rating.unwrap_or(Rating::Neutral))
+        .map_err(|e| category_error(e, &name))
+}
+
+/// Renames the category on its activities and rules too
+#[tauri::command]
+pub fn rename_category(db: State<DbConnection>, category_id: i64, name: String) -> Result<Category, String> {
+    if name.trim().is_empty() {
+        return Err("Category name must not be empty".to_string());
+    }
+
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    categories::rename_category(&mut conn, category_id, &name)
+        .map_err(|e| category_error(e, &name))?
+        .ok_or_else(|| "Category not found".to_string())
+}
+
+#[tauri::command]
+pub fn update_category(
+    db: State<DbConnection>,
+    category_id: i64,
+    color: String,
+    rating: Rating,
+) -> Result<Category, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    categories::update_category(&conn, category_id, &color, rating)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Category not found".to_string())
+}
+
+/// Also deletes the rules assigning the category and uncategorizes its
+/// activities
+#[tauri::command]
+pub fn delete_category(db: State<DbConnection>, category_id: i64) -> Result<(), String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    categories::delete_category(&mut conn, category_id).map_err(|e| e.to_string())
+}
+
+fn category_error(e: rusqlite::Error, name: &str) -> String {
+    match e.sqlite_error_code() {
+        Some(rusqlite::ErrorCode::ConstraintViolation) => format!("A category named \"{}\" already exists", name.trim()),
+        _ => e.to_string(),
+    }
+}
+
+#[tauri::command]
+pub fn get_category_rules(db: State<DbConnection>) -> Result<Vec<CategoryRule>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    categories::get_rules(&conn).map_err(|e| e.to_string())
+}
+
+/// New and changed rules only apply to activities recorded from now on;
+/// call `recategorize_activities` to apply them to history
+#[tauri::command]
+pub fn save_category_rule(db: State<DbConnection>, rule: CategoryRule) -> Result<CategoryRule, String> {
+    if rule.category.trim().is_empty() {
+        return Err("Category must not be empty".to_string());
+    }
+    if rule.pattern.trim().is_empty() {
+        return Err("Rule pattern must not be empty".to_string());
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    categories::save_rule(&conn, &rule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_category_rule(db: State<DbConnection>, rule_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    categories::delete_rule(&conn, rule_id).map_err(|e| e.to_string())
+}
+
+/// Re-applies the category rules to stored activities, all of them unless a
+/// range is given
+#[tauri::command]
+pub fn recategorize_activities(
+    db: State<DbConnection>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+) -> Result<RecategorizeResult, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    categories::recategorize(
+        &mut conn,
+        start_timestamp.unwrap_or(i64::MIN),
+        end_timestamp.unwrap_or(i64::MAX),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_projects(db: State<DbConnection>) -> Result<Vec<Project>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    projects::list_projects(&conn).map_err(|e| e.to_string())
+}
+
+/// Projects are also created as activities are first tagged with them
+#[tauri::command]
+pub fn create_project(
+    db: State<DbConnection>,
+    name: String,
+    color: Option<String>,
+    hourly_rate: Option<f64>,
+) -> Result<Project, String> {
+    if name.trim().is_empty() {
+        return Err("Project name must not be empty".to_string());
+    }
+    check_hourly_rate(hourly_rate)?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    projects::create_project(&conn, &name, color.as_deref(), hourly_rate).map_err(|e| project_error(e, &name))
+}
+
+/// Renames the project on its activities and rules too
+#[tauri::command]
+pub fn rename_project(db: State<DbConnection>, project_id: i64, name: String) -> Result<Project, String> {
+    if name.trim().is_empty() {
+        return Err("Project name must not be empty".to_string());
+    }
+
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    projects::rename_project(&mut conn, project_id, &name)
+        .map_err(|e| project_error(e, &name))?
+        .ok_or_else(|| "Project not found".to_string())
+}
+
+#[tauri::command]
+pub fn update_project(
+    db: State<DbConnection>,
+    project_id: i64,
+    color: Option<String>,
+    hourly_rate: Option<f64>,
+) -> Result<Project, String> {
+    check_hourly_rate(hourly_rate)?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    projects::update_project(&conn, project_id, color.as_deref(), hourly_rate)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Project not found".to_string())
+}
+
+/// Also deletes the rules naming the project and untags its activities.
+/// Detection may register it again.
+#[tauri::command]
+pub fn delete_project(db: State<DbConnection>, project_id: i64) -> Result<(), String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    projects::delete_project(&mut conn, project_id).map_err(|e| e.to_string())
+}
+
+fn check_hourly_rate(hourly_rate: Option<f64>) -> Result<(), String> {
+    match hourly_rate {
+        Some(rate) if !rate.is_finite() || rate < 0.0 => Err("Hourly rate must be zero or more".to_string()),
+        _ => Ok(()),
+    }
+}
+
+fn project_error(e: rusqlite::Error, name: &str) -> String {
+    match e.sqlite_error_code() {
+        Some(rusqlite::ErrorCode::ConstraintViolation) => format!("A project named \"{}\" already exists", name.trim()),
+        _ => e.to_string(),
+    }
+}
+
+/// Active time per project in the range, with what it comes to at each
+/// project's hourly rate
+#[tauri::command]
+pub fn get_project_time(
+    db: State<DbConnection>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<ProjectTimeReport, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    projects::get_project_time(&conn, start_timestamp, end_timestamp).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_project_rules(db: State<DbConnection>) -> Result<Vec<ProjectRule>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    projects::get_rules(&conn).map_err(|e| e.to_string())
+}
+
+/// New and changed rules only apply to activities recorded from now on;
+/// call `detect_projects` to apply them to history
+#[tauri::command]
+pub fn save_project_rule(db: State<DbConnection>, rule: ProjectRule) -> Result<ProjectRule, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    projects::save_rule(&conn, &rule)
+}
+
+#[tauri::command]
+pub fn delete_project_rule(db: State<DbConnection>, rule_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    projects::delete_rule(&conn, rule_id).map_err(|e| e.to_string())
+}
+
+/// Re-applies the project rules and detection to stored activities, all of
+/// them unless a range is given
+#[tauri::command]
+pub fn detect_projects(
+    db: State<DbConnection>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+) -> Result<DetectResult, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    projects::detect(
+        &mut conn,
+        start_timestamp.unwrap_or(i64::MIN),
+        end_timestamp.unwrap_or(i64::MAX),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Sets how often the foreground window is sampled. Longer intervals save
+/// battery at the cost of precision; the monitor switches over immediately.
+#[tauri::command]
+pub fn set_sampling_interval(
+    state: State<AppState>,
+    db: State<DbConnection>,
+    seconds: i64,
+) -> Result<(), String> {
+    if !(merge::MIN_SAMPLING_INTERVAL_SECS..=merge::MAX_SAMPLING_INTERVAL_SECS).contains(&seconds) {
+        return Err(format!(
+            "Sampling interval must be between {} and {} seconds",
+            merge::MIN_SAMPLING_INTERVAL_SECS,
+            merge::MAX_SAMPLING_INTERVAL_SECS
+        ));
+    }
+
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        queries::set_setting(&conn, "sampling_interval_seconds", &seconds.to_string()).map_err(|e| e.to_string())?;
+    }
+    let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    monitor.reconfigure();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_setting(db: State<DbConnection>, key: String) -> Result<Option<String>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    settings::get(&conn, &key)
+}
+
+#[tauri::command]
+pub fn get_all_settings(db: State<DbConnection>) -> Result<BTreeMap<String, String>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    settings::get_all(&conn)
+}
+
+/// Stores a setting once its value checks out, returning it as stored, and
+/// applies the change where it wouldn't otherwise be picked up
+#[tauri::command]
+pub fn set_setting(
+    app: AppHandle,
+    state: State<AppState>,
+    db: State<DbConnection>,
+    key: String,
+    value: String,
+) -> Result<String, String> {
+    let stored = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        settings::set(&conn, &key, &value)?
+    };
+    match key.trim() {
+        "sampling_interval_seconds" | "idle_timeout_seconds" => {
+            state.monitor.lock().map_err(|e| e.to_string())?.reconfigure();
+        }
+        "privacy_mode" => tray::refresh(&app),
+        _ => {}
+    }
+    Ok(stored)
+}
+
+#[tauri::command]
+pub fn get_notification_settings(db: State<DbConnection>) -> Result<NotificationSettings, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    notifications::get_settings(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_notification_settings(db: State<DbConnection>, settings: NotificationSettings) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    notifications::save_settings(&conn, &settings)
+}
+
+/// Holds back every notification for `minutes`, e.g. during a presentation.
+/// Returns when the snooze ends.
+#[tauri::command]
+pub fn snooze_all(db: State<DbConnection>, minutes: i64) -> Result<i64, String> {
+    if minutes <= 0 {
+        return Err("Snooze duration must be positive".to_string());
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    notifications::snooze_all(&conn, minutes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_suppressed_notifications(
+    db: State<DbConnection>,
+    limit: Option<i64>,
+) -> Result<Vec<SuppressedNotification>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    notifications::get_suppressed(&conn, limit.unwrap_or(100)).map_err(|e| e.to_string())
+}
+
+/// Lets the UI hide overlays while the user presents; changes arrive as
+/// `presentation-mode-changed` events
+#[tauri::command]
+pub fn get_presentation_status() -> PresentationStatus {
+    presentation::status()
+}
+
+#[tauri::command]
+pub fn resume_notifications(db: State<DbConnection>) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    notifications::resume(&conn).map_err(|e| e.to_string())
+}
+
+/// Writes the configuration, without activity history, to a JSON profile
+#[tauri::command]
+pub fn export_settings(db: State<DbConnection>, path: String) -> Result<SettingsProfile, String> {
+    let profile = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        profile::export_profile(&conn).map_err(|e| e.to_string())?
+    };
+    let json = serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    println!("✅ Exported settings profile to {}", path);
+    Ok(profile)
+}
+
+/// Applies a profile written by `export_settings`
+#[tauri::command]
+pub fn import_settings(db: State<DbConnection>, path: String) -> Result<ProfileImportResult, String> {
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let profile: SettingsProfile =
+        serde_json::from_str(&json).map_err(|e| format!("{} is not a settings profile: {}", path, e))?;
+
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let result = profile::import_profile(&mut conn, &profile)?;
+    println!("✅ Imported settings profile from {}", path);
+    Ok(result)
+}
+
+/// Folds runs of the same window recorded with at most
+/// `coalesce_gap_seconds` between them into one activity, all of history
+/// unless a range is given, and shrinks the database file
+#[tauri::command]
+pub fn compact_activities(
+    db: State<DbConnection>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+) -> Result<CompactResult, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let max_gap = coalesce::max_gap(&conn).map_err(|e| e.to_string())?;
+    let result = coalesce::compact(
+        &mut conn,
+        start_timestamp.unwrap_or(i64::MIN),
+        end_timestamp.unwrap_or(i64::MAX),
+        max_gap,
+    )
+    .map_err(|e| e.to_string())?;
+    println!(
+        "✅ Compacted activities: {} of {} folded, {} → {} bytes",
+        result.activities_removed, result.activities_scanned, result.bytes_before, result.bytes_after
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn check_database(db: State<DbConnection>) -> Result<IntegrityReport, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    integrity::check(&conn).map_err(|e| e.to_string())
+}
+
+/// Fixes what `check_database` finds. With `dry_run` nothing is written and
+/// the result shows what would change.
+#[tauri::command]
+pub fn repair_database(db: State<DbConnection>, dry_run: bool) -> Result<RepairResult, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let result = integrity::repair(&mut conn, dry_run).map_err(|e| e.to_string())?;
+    if !dry_run {
+        println!(
+            "✅ Repaired database: {} activities clamped, {} deleted, {} orphaned alerts deleted",
+            result.activities_clamped, result.activities_deleted, result.alerts_deleted
+        );
+    }
+    Ok(result)
+}
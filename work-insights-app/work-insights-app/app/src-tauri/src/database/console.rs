@@ -0,0 +1,119 @@
+// Read-only SQL console for power users
+// Queries run on a separate read-only connection with `query_only` set, a row
+// cap and a wall-clock limit, so a custom widget can never modify or stall the
+// tracker's own connection.
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use rusqlite::{types::ValueRef, Batch, Connection, OpenFlags};
+use serde::Serialize;
+
+pub const MAX_ROWS: usize = 1000;
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub truncated: bool,
+    pub elapsed_ms: u128,
+}
+
+pub fn run_readonly_query(db_path: &Path, sql: &str) -> Result<QueryResult, String> {
+    let sql = validate_query(sql)?;
+
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute_batch("PRAGMA query_only = ON").map_err(|e| e.to_string())?;
+
+    // SQLite prepares the first statement and points past it, so whatever
+    // follows is only another statement if it prepares to one
+    let mut batch = Batch::new(&conn, sql);
+    let mut stmt = batch.next().map_err(|e| e.to_string())?.ok_or_else(|| "Query is empty".to_string())?;
+    if batch.next().map_err(|e| e.to_string())?.is_some() {
+        return Err("Only a single statement is allowed".to_string());
+    }
+    if !stmt.readonly() {
+        return Err("Only read-only statements are allowed".to_string());
+    }
+
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+
+    // Interrupt the query from a watchdog thread once the deadline passes;
+    // dropping `done_tx` when we finish lets the watchdog exit early.
+    let interrupt = conn.get_interrupt_handle();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        if let Err(mpsc::RecvTimeoutError::Timeout) = done_rx.recv_timeout(QUERY_TIMEOUT) {
+            interrupt.interrupt();
+        }
+    });
+
+    let started = Instant::now();
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    let mut query = stmt.query([]).map_err(|e| timeout_error(e, started))?;
+
+    while let Some(row) = query.next().map_err(|e| timeout_error(e, started))? {
+        if rows.len() == MAX_ROWS {
+            truncated = true;
+            break;
+        }
+        let values = (0..columns.len())
+            .map(|i| row.get_ref(i).map(value_to_json))
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?;
+        rows.push(values);
+    }
+    drop(done_tx);
+
+    Ok(QueryResult {
+        columns,
+        rows,
+        truncated,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+/// Accepts a SELECT (or WITH … SELECT) statement. That it's the only one is
+/// checked once it's prepared, since a `;` may be inside a string.
+fn validate_query(sql: &str) -> Result<&str, String> {
+    let sql = sql.trim();
+    if sql.is_empty() {
+        return Err("Query is empty".to_string());
+    }
+
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    if keyword != "SELECT" && keyword != "WITH" {
+        return Err("Only SELECT queries are allowed".to_string());
+    }
+
+    Ok(sql)
+}
+
+fn value_to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::from(i),
+        ValueRef::Real(f) => serde_json::Value::from(f),
+        ValueRef::Text(t) => serde_json::Value::from(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => serde_json::Value::from(format!("<blob {} bytes>", b.len())),
+    }
+}
+
+fn timeout_error(e: rusqlite::Error, started: Instant) -> String {
+    if started.elapsed() >= QUERY_TIMEOUT {
+        format!("Query exceeded the {}s time limit", QUERY_TIMEOUT.as_secs())
+    } else {
+        e.to_string()
+    }
+}
@@ -0,0 +1,76 @@
+// Day notes
+// A free-form markdown note per local calendar date ("shipped v2", "was
+// sick"), shown alongside that day's numbers in the daily and weekly reports.
+
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+use super::queries::DATE_FORMAT;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayNote {
+    /// "YYYY-MM-DD"
+    pub date: String,
+    pub content: String,
+    pub updated_at: i64,
+}
+
+pub fn get_note(conn: &Connection, date: NaiveDate) -> Result<Option<DayNote>> {
+    conn.query_row(
+        "SELECT date, content, updated_at FROM day_notes WHERE date = ?1",
+        params![date.format(DATE_FORMAT).to_string()],
+        row_to_note,
+    )
+    .optional()
+}
+
+/// Notes from `start` to `end` inclusive, oldest first
+pub fn get_notes(conn: &Connection, start: NaiveDate, end: NaiveDate) -> Result<Vec<DayNote>> {
+    let mut stmt = conn.prepare(
+        "SELECT date, content, updated_at
+         FROM day_notes
+         WHERE date BETWEEN ?1 AND ?2
+         ORDER BY date",
+    )?;
+
+    let notes = stmt
+        .query_map(
+            params![start.format(DATE_FORMAT).to_string(), end.format(DATE_FORMAT).to_string()],
+            row_to_note,
+        )?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(notes)
+}
+
+/// Creates or replaces the note for `date`
+pub fn save_note(conn: &Connection, date: NaiveDate, content: &str) -> Result<DayNote> {
+    let note = DayNote {
+        date: date.format(DATE_FORMAT).to_string(),
+        content: content.to_string(),
+        updated_at: Utc::now().timestamp(),
+    };
+    conn.execute(
+        "INSERT INTO day_notes (date, content, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(date) DO UPDATE SET content = excluded.content, updated_at = excluded.updated_at",
+        params![note.date, note.content, note.updated_at],
+    )?;
+    Ok(note)
+}
+
+pub fn delete_note(conn: &Connection, date: NaiveDate) -> Result<()> {
+    conn.execute(
+        "DELETE FROM day_notes WHERE date = ?1",
+        params![date.format(DATE_FORMAT).to_string()],
+    )?;
+    Ok(())
+}
+
+fn row_to_note(row: &rusqlite::Row) -> Result<DayNote> {
+    Ok(DayNote {
+        date: row.get(0)?,
+        content: row.get(1)?,
+        updated_at: row.get(2)?,
+    })
+}
@@ -0,0 +1,234 @@
+// Data integrity
+// Finds rows the rest of the app can't make sense of: activities running
+// into the next one, activities with a negative duration or a timestamp in
+// the future, streak alerts left behind by deleted rules, and anything
+// SQLite's own integrity check reports. `repair` clamps overlapping
+// activities so each ends where the next starts and deletes the corrupt and
+// orphaned rows. File-level corruption is only reported, since it needs a
+// restore from backup rather than a repair.
+
+use chrono::Utc;
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+
+/// Timestamps further ahead than this are not clock drift
+const FUTURE_TOLERANCE_SECONDS: i64 = 24 * 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Overlap,
+    NegativeDuration,
+    FutureTimestamp,
+    OrphanedAlert,
+    Corruption,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityIssue {
+    pub kind: IssueKind,
+    pub table: String,
+    pub row_id: Option<i64>,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntegrityReport {
+    pub checked_at: i64,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepairResult {
+    /// Nothing was written; the counts are what a repair would do
+    pub dry_run: bool,
+    pub activities_clamped: usize,
+    pub activities_deleted: usize,
+    pub alerts_deleted: usize,
+    /// Issues a repair can't fix
+    pub unrepaired: Vec<IntegrityIssue>,
+}
+
+pub fn check(conn: &Connection) -> Result<IntegrityReport> {
+    let now = Utc::now().timestamp();
+    let mut issues = sqlite_issues(conn)?;
+
+    for (id, app_name, duration) in negative_durations(conn)? {
+        issues.push(activity_issue(
+            IssueKind::NegativeDuration,
+            id,
+            format!("{} has a duration of {}s", app_name, duration),
+        ));
+    }
+    for (id, app_name, timestamp) in future_timestamps(conn, now)? {
+        issues.push(activity_issue(
+            IssueKind::FutureTimestamp,
+            id,
+            format!("{} is dated {}s in the future", app_name, timestamp - now),
+        ));
+    }
+    for overlap in overlaps(conn, now)? {
+        issues.push(activity_issue(
+            IssueKind::Overlap,
+            overlap.id,
+            format!(
+                "{} runs {}s into activity {}",
+                overlap.app_name,
+                overlap.timestamp + overlap.duration - overlap.next_start,
+                overlap.next_id
+            ),
+        ));
+    }
+    for (id, rule_id) in orphaned_alerts(conn)? {
+        issues.push(IntegrityIssue {
+            kind: IssueKind::OrphanedAlert,
+            table: "app_streak_alerts".to_string(),
+            row_id: Some(id),
+            detail: format!("Alert for deleted rule {}", rule_id),
+        });
+    }
+
+    Ok(IntegrityReport { checked_at: now, issues })
+}
+
+/// Fixes what `check` finds, all in one transaction. Corrupt activities are
+/// deleted before overlaps are clamped, so a bogus row doesn't cut short the
+/// one before it. With `dry_run` the transaction is rolled back.
+pub fn repair(conn: &mut Connection, dry_run: bool) -> Result<RepairResult> {
+    let now = Utc::now().timestamp();
+    let unrepaired = sqlite_issues(conn)?;
+
+    let tx = conn.transaction()?;
+    let mut activities_deleted = tx.execute(
+        "DELETE FROM activities WHERE duration_seconds < 0 OR timestamp > ?1",
+        params![now + FUTURE_TOLERANCE_SECONDS],
+    )?;
+
+    let mut activities_clamped = 0;
+    {
+        let mut update = tx.prepare("UPDATE activities SET duration_seconds = ?2 WHERE id = ?1")?;
+        let mut delete = tx.prepare("DELETE FROM activities WHERE id = ?1")?;
+        for overlap in overlaps(&tx, now)? {
+            // Two activities starting at the same second leave nothing of the first
+            let duration = overlap.next_start - overlap.timestamp;
+            if duration > 0 {
+                update.execute(params![overlap.id, duration])?;
+                activities_clamped += 1;
+            } else {
+                delete.execute(params![overlap.id])?;
+                activities_deleted += 1;
+            }
+        }
+    }
+
+    let alerts_deleted = tx.execute(
+        "DELETE FROM app_streak_alerts WHERE rule_id NOT IN (SELECT id FROM app_streak_rules)",
+        [],
+    )?;
+
+    if dry_run {
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+    }
+
+    Ok(RepairResult { dry_run, activities_clamped, activities_deleted, alerts_deleted, unrepaired })
+}
+
+struct Overlap {
+    id: i64,
+    app_name: String,
+    timestamp: i64,
+    duration: i64,
+    next_id: i64,
+    next_start: i64,
+}
+
+/// Activities ending after the next one starts, ignoring rows that are
+/// corrupt in their own right
+fn overlaps(conn: &Connection, now: i64) -> Result<Vec<Overlap>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, app_name, timestamp, duration_seconds, next_id, next_start
+         FROM (
+             SELECT id, app_name, timestamp, duration_seconds,
+                    LEAD(id) OVER w AS next_id,
+                    LEAD(timestamp) OVER w AS next_start
+             FROM activities
+             WHERE duration_seconds >= 0 AND timestamp <= ?1
+             WINDOW w AS (ORDER BY timestamp, id)
+         )
+         WHERE next_start IS NOT NULL AND timestamp + duration_seconds > next_start
+         ORDER BY timestamp, id",
+    )?;
+    let overlaps = stmt
+        .query_map(params![now + FUTURE_TOLERANCE_SECONDS], |row| {
+            Ok(Overlap {
+                id: row.get(0)?,
+                app_name: row.get(1)?,
+                timestamp: row.get(2)?,
+                duration: row.get(3)?,
+                next_id: row.get(4)?,
+                next_start: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(overlaps)
+}
+
+fn negative_durations(conn: &Connection) -> Result<Vec<(i64, String, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, app_name, duration_seconds FROM activities WHERE duration_seconds < 0 ORDER BY timestamp, id",
+    )?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+fn future_timestamps(conn: &Connection, now: i64) -> Result<Vec<(i64, String, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, app_name, timestamp FROM activities
+         WHERE timestamp > ?1 AND duration_seconds >= 0
+         ORDER BY timestamp, id",
+    )?;
+    let rows = stmt
+        .query_map(params![now + FUTURE_TOLERANCE_SECONDS], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+fn orphaned_alerts(conn: &Connection) -> Result<Vec<(i64, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, rule_id FROM app_streak_alerts
+         WHERE rule_id NOT IN (SELECT id FROM app_streak_rules)
+         ORDER BY id",
+    )?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// Whatever `PRAGMA integrity_check` reports besides "ok"
+fn sqlite_issues(conn: &Connection) -> Result<Vec<IntegrityIssue>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let messages = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(messages
+        .into_iter()
+        .filter(|message| message != "ok")
+        .map(|detail| IntegrityIssue {
+            kind: IssueKind::Corruption,
+            table: String::new(),
+            row_id: None,
+            detail,
+        })
+        .collect())
+}
+
+fn activity_issue(kind: IssueKind, id: i64, detail: String) -> IntegrityIssue {
+    IntegrityIssue { kind, table: "activities".to_string(), row_id: Some(id), detail }
+}
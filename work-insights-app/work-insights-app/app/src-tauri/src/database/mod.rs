@@ -1,5 +1,12 @@
 pub mod schema;
 pub mod queries;
+pub mod console;
+pub mod snapshots;
+pub mod day_notes;
+pub mod integrity;
+pub mod storage;
+#[cfg(feature = "postgres")]
+pub mod postgres;
 
 use rusqlite::{Connection, Result};
 use std::path::PathBuf;
@@ -0,0 +1,174 @@
+// Postgres activity storage
+// Activities on a shared server, one table for every user. Every user signs
+// in with their own login role, and the row level security policy on the
+// table limits each role to the rows whose `user_id` is its name, which new
+// rows get by default, so nobody can read or write someone else's activities
+// whatever the app sends. The table and policy are set up once by the
+// server's administrator, and connecting refuses a table without row level
+// security. A user can't have two activities starting at the same second,
+// which is how rows copied twice are recognized. Connections always use TLS,
+// verified against the system's certificate store.
+//
+// Setup, with `trackers` granted to every user's login role:
+//
+//   CREATE TABLE tracked_activities (
+//       id BIGSERIAL PRIMARY KEY,
+//       user_id TEXT NOT NULL DEFAULT current_user,
+//       timestamp BIGINT NOT NULL,
+//       app_name TEXT NOT NULL,
+//       window_title TEXT,
+//       duration_seconds INTEGER NOT NULL,
+//       is_idle BOOLEAN NOT NULL,
+//       category TEXT,
+//       algorithm_version BIGINT,
+//       is_manual BOOLEAN NOT NULL DEFAULT FALSE,
+//       project TEXT,
+//       UNIQUE (user_id, timestamp)
+//   );
+//   ALTER TABLE tracked_activities ENABLE ROW LEVEL SECURITY;
+//   ALTER TABLE tracked_activities FORCE ROW LEVEL SECURITY;
+//   CREATE POLICY own_activities ON tracked_activities
+//       USING (user_id = current_user) WITH CHECK (user_id = current_user);
+//   GRANT SELECT, INSERT ON tracked_activities TO trackers;
+//   GRANT USAGE ON SEQUENCE tracked_activities_id_seq TO trackers;
+//
+// Tables made by earlier versions, which created them on first connect, need
+// `ALTER TABLE tracked_activities ALTER COLUMN user_id SET DEFAULT
+// current_user` and their `user_id`s renamed to the matching login roles.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use postgres::config::SslMode;
+use postgres::{Client, Config};
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+use super::queries::Activity;
+use super::storage::ActivityStore;
+
+/// How long to wait for the server when the URL doesn't say
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct PostgresStore {
+    client: Client,
+    /// The login role, which the server files activities under
+    user_id: String,
+}
+
+impl PostgresStore {
+    pub fn connect(url: &str) -> Result<Self, String> {
+        let mut config: Config = url.parse().map_err(|e| format!("Invalid server URL: {}", e))?;
+        // The default, "prefer", would quietly fall back to plain text
+        config.ssl_mode(SslMode::Require);
+        if config.get_connect_timeout().is_none() {
+            config.connect_timeout(CONNECT_TIMEOUT);
+        }
+        let mut client = config.connect(tls()?).map_err(|e| format!("Failed to connect to the server: {}", e))?;
+
+        let secured: Option<bool> = client
+            .query_opt("SELECT relrowsecurity FROM pg_class WHERE oid = to_regclass('tracked_activities')", &[])
+            .map_err(|e| e.to_string())?
+            .map(|row| row.get(0));
+        match secured {
+            Some(true) => {}
+            Some(false) => return Err("Row level security is off for tracked_activities on the server; ask its administrator to set it up".to_string()),
+            None => return Err("The server has no tracked_activities table yet; ask its administrator to set it up".to_string()),
+        }
+
+        let user_id = client
+            .query_one("SELECT current_user::TEXT", &[])
+            .map(|row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        Ok(Self { client, user_id })
+    }
+}
+
+fn tls() -> Result<MakeRustlsConnect, String> {
+    let mut roots = rustls::RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    let (added, _) = roots.add_parsable_certificates(native.certs);
+    if added == 0 {
+        return Err("No trusted certificates found to verify the server with".to_string());
+    }
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::aws_lc_rs::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(MakeRustlsConnect::new(config))
+}
+
+impl ActivityStore for PostgresStore {
+    fn user_id(&self) -> Option<&str> {
+        Some(&self.user_id)
+    }
+
+    fn activities_after(&mut self, after_id: i64, limit: usize) -> Result<Vec<Activity>, String> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project
+                 FROM tracked_activities WHERE id > $1 ORDER BY id LIMIT $2",
+                &[&after_id, &(limit as i64)],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(rows
+            .iter()
+            .map(|row| Activity {
+                id: Some(row.get(0)),
+                timestamp: row.get(1),
+                app_name: row.get(2),
+                window_title: row.get(3),
+                duration_seconds: row.get(4),
+                is_idle: row.get(5),
+                category: row.get(6),
+                algorithm_version: row.get(7),
+                is_manual: row.get(8),
+                project: row.get(9),
+                // Edits aren't copied to the server
+                is_edited: false,
+            })
+            .collect())
+    }
+
+    fn insert_activities(&mut self, activities: &[Activity]) -> Result<usize, String> {
+        let mut tx = self.client.transaction().map_err(|e| e.to_string())?;
+        let stmt = tx
+            .prepare(
+                "INSERT INTO tracked_activities
+                    (timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                 ON CONFLICT (user_id, timestamp) DO NOTHING",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut inserted = 0;
+        for activity in activities {
+            inserted += tx
+                .execute(
+                    &stmt,
+                    &[
+                        &activity.timestamp,
+                        &activity.app_name,
+                        &activity.window_title,
+                        &activity.duration_seconds,
+                        &activity.is_idle,
+                        &activity.category,
+                        &activity.algorithm_version,
+                        &activity.is_manual,
+                        &activity.project,
+                    ],
+                )
+                .map_err(|e| e.to_string())? as usize;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(inserted)
+    }
+
+    fn count_activities(&mut self) -> Result<i64, String> {
+        self.client
+            .query_one("SELECT COUNT(*) FROM tracked_activities", &[])
+            .map(|row| row.get(0))
+            .map_err(|e| e.to_string())
+    }
+}
@@ -1,8 +1,13 @@
 use rusqlite::{params, Connection, Result};
 use serde::{Deserialize, Serialize};
-use chrono::Utc;
+use std::collections::{BTreeMap, HashMap};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, TimeZone, Timelike, Utc};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::categories;
+use crate::projects;
+use super::day_notes::{self, DayNote};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Activity {
     pub id: Option<i64>,
     pub timestamp: i64,
@@ -11,22 +16,81 @@ pub struct Activity {
     pub duration_seconds: i32,
     pub is_idle: bool,
     pub category: Option<String>,
+    /// Merging algorithm that derived this row; `None` for rows recorded
+    /// before algorithms were versioned
+    pub algorithm_version: Option<i64>,
+    /// Entered by the user for time away from the computer rather than
+    /// tracked
+    #[serde(default)]
+    pub is_manual: bool,
+    /// Detected from the window by `projects`, or given by hand
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Changed or merged by hand; recategorizing, project detection,
+    /// coalescing and rebuilds leave it alone
+    #[serde(default)]
+    pub is_edited: bool,
 }
 
+/// Inserts an activity, categorizing it by the current rules and detecting
+/// its project unless it already has them
 pub fn insert_activity(conn: &Connection, activity: &Activity) -> Result<i64> {
-    conn.execute(
-        "INSERT INTO activities (timestamp, app_name, window_title, duration_seconds, is_idle, category)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        params![
-            activity.timestamp,
-            activity.app_name,
-            activity.window_title,
-            activity.duration_seconds,
-            activity.is_idle,
-            activity.category,
-        ],
-    )?;
-    Ok(conn.last_insert_rowid())
+    ActivityWriter::new(conn).insert(activity)
+}
+
+/// Inserts activities like `insert_activity`, loading the category rules and
+/// project detection once for the whole batch
+pub struct ActivityWriter<'a> {
+    conn: &'a Connection,
+    rules: Option<Vec<categories::CategoryRule>>,
+    detector: Option<projects::Detector>,
+}
+
+impl<'a> ActivityWriter<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn, rules: None, detector: None }
+    }
+
+    pub fn insert(&mut self, activity: &Activity) -> Result<i64> {
+        let category = match &activity.category {
+            Some(category) => Some(category.clone()),
+            None => {
+                if self.rules.is_none() {
+                    self.rules = Some(categories::get_rules(self.conn)?);
+                }
+                categories::categorize(self.rules.as_deref().unwrap_or_default(), activity)
+            }
+        };
+        let project = match &activity.project {
+            Some(project) => Some(project.clone()),
+            None => {
+                if self.detector.is_none() {
+                    self.detector = Some(projects::Detector::load(self.conn)?);
+                }
+                self.detector.as_mut().and_then(|detector| detector.detect(activity))
+            }
+        };
+        if let Some(project) = &project {
+            projects::register(self.conn, project)?;
+        }
+        self.conn.execute(
+            "INSERT INTO activities (timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project, is_edited)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                activity.timestamp,
+                activity.app_name,
+                activity.window_title,
+                activity.duration_seconds,
+                activity.is_idle,
+                category,
+                activity.algorithm_version,
+                activity.is_manual,
+                project,
+                activity.is_edited,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
 }
 
 pub fn get_activities_by_date_range(
@@ -35,7 +99,7 @@ pub fn get_activities_by_date_range(
     end_timestamp: i64,
 ) -> Result<Vec<Activity>> {
     let mut stmt = conn.prepare(
-        "SELECT id, timestamp, app_name, window_title, duration_seconds, is_idle, category
+        "SELECT id, timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project, is_edited
          FROM activities
          WHERE timestamp BETWEEN ?1 AND ?2
          ORDER BY timestamp ASC",
@@ -51,6 +115,10 @@ pub fn get_activities_by_date_range(
                 duration_seconds: row.get(4)?,
                 is_idle: row.get(5)?,
                 category: row.get(6)?,
+                algorithm_version: row.get(7)?,
+                is_manual: row.get(8)?,
+                project: row.get(9)?,
+                is_edited: row.get(10)?,
             })
         })?
         .collect::<Result<Vec<_>>>()?;
@@ -58,39 +126,419 @@ pub fn get_activities_by_date_range(
     Ok(activities)
 }
 
-pub fn get_today_stats(conn: &Connection) -> Result<TodayStats> {
-    let today_start = chrono::Local::now()
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Reads a "YYYY-MM-DD" date as sent by the frontend
+pub fn parse_date(date: &str) -> std::result::Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), DATE_FORMAT)
+        .map_err(|_| format!("Invalid date \"{}\", expected YYYY-MM-DD", date))
+}
+
+/// Reads a "YYYY-MM" month as sent by the frontend, as its first day
+pub fn parse_month(month: &str) -> std::result::Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), DATE_FORMAT)
+        .map_err(|_| format!("Invalid month \"{}\", expected YYYY-MM", month))
+}
+
+/// First and last second of a local calendar day
+pub fn local_day_bounds(date: NaiveDate) -> (i64, i64) {
+    let midnight = |day: NaiveDate| {
+        let naive = day.and_hms_opt(0, 0, 0).unwrap();
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|t| t.timestamp())
+            // Midnight doesn't exist when a DST change skips it
+            .unwrap_or_else(|| naive.and_utc().timestamp())
+    };
+    (midnight(date), midnight(date + Days::new(1)) - 1)
+}
+
+pub fn today_start_timestamp() -> i64 {
+    chrono::Local::now()
         .date_naive()
         .and_hms_opt(0, 0, 0)
         .unwrap()
         .and_utc()
-        .timestamp();
+        .timestamp()
+}
+
+pub fn get_today_stats(conn: &Connection) -> Result<TodayStats> {
+    let today_start = today_start_timestamp();
 
     let mut stmt = conn.prepare(
         "SELECT 
             SUM(CASE WHEN is_idle = 0 THEN duration_seconds ELSE 0 END) as active_time,
-            SUM(CASE WHEN is_idle = 1 THEN duration_seconds ELSE 0 END) as idle_time,
-            COUNT(DISTINCT app_name) as app_switches
+            SUM(CASE WHEN is_idle = 1 THEN duration_seconds ELSE 0 END) as idle_time
          FROM activities
          WHERE timestamp >= ?1",
     )?;
 
-    let stats = stmt.query_row(params![today_start], |row| {
+    let mut stats = stmt.query_row(params![today_start], |row| {
         Ok(TodayStats {
             active_time_seconds: row.get::<_, Option<i64>>(0)?.unwrap_or(0),
             idle_time_seconds: row.get::<_, Option<i64>>(1)?.unwrap_or(0),
-            context_switches: row.get::<_, i64>(2)?,
+            context_switches: 0,
+            note: None,
         })
     })?;
+    stats.context_switches = count_context_switches(conn, today_start, i64::MAX)?;
+    stats.note = day_notes::get_note(conn, chrono::Local::now().date_naive())?;
 
     Ok(stats)
 }
 
+/// Number of recorded activities whose app differs from the one before it
+pub fn count_context_switches(conn: &Connection, start_timestamp: i64, end_timestamp: i64) -> Result<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM (
+            SELECT app_name, LAG(app_name) OVER (ORDER BY timestamp, id) AS prev_app
+            FROM activities
+            WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         )
+         WHERE prev_app IS NOT NULL AND prev_app != app_name",
+        params![start_timestamp, end_timestamp],
+        |row| row.get(0),
+    )
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TodayStats {
     pub active_time_seconds: i64,
     pub idle_time_seconds: i64,
     pub context_switches: i64,
+    /// The user's note for today, if they wrote one
+    pub note: Option<DayNote>,
+}
+
+/// The activity the monitor is still accumulating, which isn't stored until
+/// it ends
+#[derive(Debug, Serialize)]
+pub struct CurrentSegment {
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub is_idle: bool,
+    pub category: Option<String>,
+    pub started_at: i64,
+    pub elapsed_seconds: i64,
+    /// Today's recorded time in the app plus the part of the segment that
+    /// falls on today
+    pub app_today_seconds: i64,
+}
+
+pub fn get_current_segment(conn: &Connection, open: Activity) -> Result<CurrentSegment> {
+    let (today_start, today_end) = local_day_bounds(Local::now().date_naive());
+    let elapsed_seconds = i64::from(open.duration_seconds);
+    let category = if open.is_idle {
+        None
+    } else {
+        categories::categorize_window(
+            &categories::get_rules(conn)?,
+            &open.app_name,
+            open.window_title.as_deref().unwrap_or(""),
+        )
+    };
+
+    let recorded: i64 = conn.query_row(
+        "SELECT COALESCE(SUM(duration_seconds), 0)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND app_name = ?3 AND is_idle = ?4",
+        params![today_start, today_end, open.app_name, open.is_idle],
+        |row| row.get(0),
+    )?;
+    let in_progress_today = (open.timestamp + elapsed_seconds - open.timestamp.max(today_start)).max(0);
+
+    Ok(CurrentSegment {
+        app_name: open.app_name,
+        window_title: open.window_title,
+        is_idle: open.is_idle,
+        category,
+        started_at: open.timestamp,
+        elapsed_seconds,
+        app_today_seconds: recorded + in_progress_today,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub total_seconds: i64,
+}
+
+pub fn get_category_totals(
+    conn: &Connection,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<Vec<CategoryTotal>> {
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(category, 'Uncategorized'), SUM(duration_seconds)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         GROUP BY 1
+         ORDER BY 2 DESC",
+    )?;
+
+    let totals = stmt
+        .query_map(params![start_timestamp, end_timestamp], |row| {
+            Ok(CategoryTotal {
+                category: row.get(0)?,
+                total_seconds: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(totals)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyBreakdown {
+    /// Local hour of the day, 0-23
+    pub hour: u32,
+    pub active_seconds: i64,
+    pub idle_seconds: i64,
+    /// The app with the most active time in the hour
+    pub top_app: Option<String>,
+    pub top_app_seconds: i64,
+}
+
+/// Active and idle time per local hour of `date`, always 24 entries.
+/// Activities running across an hour boundary are split between the hours.
+pub fn get_hourly_breakdown(conn: &Connection, date: NaiveDate) -> Result<Vec<HourlyBreakdown>> {
+    let (start, end) = local_day_bounds(date);
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, duration_seconds, app_name, is_idle
+         FROM activities
+         WHERE timestamp + duration_seconds > ?1 AND timestamp <= ?2
+         ORDER BY timestamp",
+    )?;
+    let rows = stmt
+        .query_map(params![start, end], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, bool>(3)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut hours: Vec<HourlyBreakdown> = (0..24)
+        .map(|hour| HourlyBreakdown { hour, active_seconds: 0, idle_seconds: 0, top_app: None, top_app_seconds: 0 })
+        .collect();
+    let mut app_seconds: Vec<HashMap<String, i64>> = vec![HashMap::new(); 24];
+
+    for (timestamp, duration, app_name, is_idle) in rows {
+        let mut t = timestamp.max(start);
+        let activity_end = (timestamp + duration).min(end + 1);
+        while t < activity_end {
+            let hour_end = t - (t - start).rem_euclid(3600) + 3600;
+            let seconds = hour_end.min(activity_end) - t;
+            let hour = Local.timestamp_opt(t, 0).earliest().map_or(0, |local| local.hour() as usize);
+            if is_idle {
+                hours[hour].idle_seconds += seconds;
+            } else {
+                hours[hour].active_seconds += seconds;
+                *app_seconds[hour].entry(app_name.clone()).or_default() += seconds;
+            }
+            t += seconds;
+        }
+    }
+
+    for (breakdown, apps) in hours.iter_mut().zip(app_seconds) {
+        if let Some((app, seconds)) = apps.into_iter().max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0))) {
+            breakdown.top_app = Some(app);
+            breakdown.top_app_seconds = seconds;
+        }
+    }
+
+    Ok(hours)
+}
+
+/// Categories listed per day in weekly and monthly stats
+const TOP_CATEGORIES_PER_DAY: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyStats {
+    /// "YYYY-MM-DD"
+    pub date: String,
+    pub active_time_seconds: i64,
+    pub idle_time_seconds: i64,
+    pub context_switches: i64,
+    /// The day's biggest categories by active time, largest first
+    pub top_categories: Vec<CategoryTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodStats {
+    pub start_date: String,
+    pub end_date: String,
+    pub active_time_seconds: i64,
+    pub idle_time_seconds: i64,
+    pub context_switches: i64,
+    pub category_totals: Vec<CategoryTotal>,
+    /// Every day of the period in order, including days with no activity
+    pub days: Vec<DailyStats>,
+}
+
+/// The seven days starting on `week_start`
+pub fn get_weekly_stats(conn: &Connection, week_start: NaiveDate) -> Result<PeriodStats> {
+    get_period_stats(conn, week_start, week_start + Days::new(6))
+}
+
+/// The calendar month containing `month`
+pub fn get_monthly_stats(conn: &Connection, month: NaiveDate) -> Result<PeriodStats> {
+    let first = month.with_day(1).unwrap();
+    let last = first.checked_add_months(Months::new(1)).unwrap() - Days::new(1);
+    get_period_stats(conn, first, last)
+}
+
+/// Per-day totals for the local days from `start` to `end` inclusive
+pub fn get_period_stats(conn: &Connection, start: NaiveDate, end: NaiveDate) -> Result<PeriodStats> {
+    let (start_timestamp, _) = local_day_bounds(start);
+    let (_, end_timestamp) = local_day_bounds(end);
+
+    let mut days: Vec<DailyStats> = start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .map(|day| DailyStats {
+            date: day.format(DATE_FORMAT).to_string(),
+            active_time_seconds: 0,
+            idle_time_seconds: 0,
+            context_switches: 0,
+            top_categories: vec![],
+        })
+        .collect();
+    let index: HashMap<String, usize> = days.iter().enumerate().map(|(i, day)| (day.date.clone(), i)).collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT date(timestamp, 'unixepoch', 'localtime') AS day,
+            SUM(CASE WHEN is_idle = 0 THEN duration_seconds ELSE 0 END),
+            SUM(CASE WHEN is_idle = 1 THEN duration_seconds ELSE 0 END)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2
+         GROUP BY day",
+    )?;
+    let totals = stmt.query_map(params![start_timestamp, end_timestamp], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+    })?;
+    for row in totals {
+        let (date, active, idle) = row?;
+        if let Some(&i) = index.get(&date) {
+            days[i].active_time_seconds = active;
+            days[i].idle_time_seconds = idle;
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT day, COUNT(*) FROM (
+            SELECT date(timestamp, 'unixepoch', 'localtime') AS day, app_name,
+                   LAG(app_name) OVER (PARTITION BY date(timestamp, 'unixepoch', 'localtime') ORDER BY timestamp, id) AS prev_app
+            FROM activities
+            WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         )
+         WHERE prev_app IS NOT NULL AND prev_app != app_name
+         GROUP BY day",
+    )?;
+    let switches = stmt.query_map(params![start_timestamp, end_timestamp], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })?;
+    for row in switches {
+        let (date, count) = row?;
+        if let Some(&i) = index.get(&date) {
+            days[i].context_switches = count;
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT date(timestamp, 'unixepoch', 'localtime') AS day, COALESCE(category, 'Uncategorized'), SUM(duration_seconds)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         GROUP BY day, 2
+         ORDER BY day, 3 DESC",
+    )?;
+    let categories = stmt.query_map(params![start_timestamp, end_timestamp], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            CategoryTotal { category: row.get(1)?, total_seconds: row.get(2)? },
+        ))
+    })?;
+    for row in categories {
+        let (date, total) = row?;
+        if let Some(&i) = index.get(&date) {
+            if days[i].top_categories.len() < TOP_CATEGORIES_PER_DAY {
+                days[i].top_categories.push(total);
+            }
+        }
+    }
+
+    Ok(PeriodStats {
+        start_date: start.format(DATE_FORMAT).to_string(),
+        end_date: end.format(DATE_FORMAT).to_string(),
+        active_time_seconds: days.iter().map(|day| day.active_time_seconds).sum(),
+        idle_time_seconds: days.iter().map(|day| day.idle_time_seconds).sum(),
+        context_switches: days.iter().map(|day| day.context_switches).sum(),
+        category_totals: get_category_totals(conn, start_timestamp, end_timestamp)?,
+        days,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppUsage {
+    pub app_name: String,
+    pub total_seconds: i64,
+    /// Activities recorded for the app, each an unbroken stretch of use
+    pub session_count: i64,
+    /// Fraction of all active time in the range, 0-1
+    pub share: f64,
+}
+
+/// The most used apps by active time, largest first
+pub fn get_top_apps(conn: &Connection, start_timestamp: i64, end_timestamp: i64, limit: usize) -> Result<Vec<AppUsage>> {
+    let mut stmt = conn.prepare(
+        "SELECT app_name,
+            SUM(duration_seconds) AS total,
+            COUNT(*),
+            COALESCE(CAST(SUM(duration_seconds) AS REAL) / NULLIF(SUM(SUM(duration_seconds)) OVER (), 0), 0)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         GROUP BY app_name
+         ORDER BY total DESC, app_name
+         LIMIT ?3",
+    )?;
+
+    let apps = stmt
+        .query_map(params![start_timestamp, end_timestamp, limit as i64], |row| {
+            Ok(AppUsage {
+                app_name: row.get(0)?,
+                total_seconds: row.get(1)?,
+                session_count: row.get(2)?,
+                share: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(apps)
+}
+
+/// Active seconds by local day of week and hour, as 7 rows (Monday first) of
+/// 24 hours. An activity counts toward the hour it started in.
+pub fn get_activity_heatmap(conn: &Connection, start_timestamp: i64, end_timestamp: i64) -> Result<Vec<Vec<i64>>> {
+    let mut stmt = conn.prepare(
+        "SELECT (CAST(strftime('%w', timestamp, 'unixepoch', 'localtime') AS INTEGER) + 6) % 7 AS weekday,
+            CAST(strftime('%H', timestamp, 'unixepoch', 'localtime') AS INTEGER) AS hour,
+            SUM(duration_seconds)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         GROUP BY weekday, hour",
+    )?;
+
+    let mut heatmap = vec![vec![0; 24]; 7];
+    let cells = stmt.query_map(params![start_timestamp, end_timestamp], |row| {
+        Ok((row.get::<_, usize>(0)?, row.get::<_, usize>(1)?, row.get::<_, i64>(2)?))
+    })?;
+    for cell in cells {
+        let (weekday, hour, seconds) = cell?;
+        if let Some(slot) = heatmap.get_mut(weekday).and_then(|day| day.get_mut(hour)) {
+            *slot = seconds;
+        }
+    }
+
+    Ok(heatmap)
 }
 
 pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
@@ -104,6 +552,14 @@ pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
     }
 }
 
+pub fn get_all_settings(conn: &Connection) -> Result<BTreeMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT key, value FROM settings ORDER BY key")?;
+    let settings = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<BTreeMap<_, _>>>()?;
+    Ok(settings)
+}
+
 pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
     conn.execute(
         "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
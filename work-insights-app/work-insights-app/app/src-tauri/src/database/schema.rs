@@ -1,5 +1,7 @@
 use rusqlite::{Connection, Result};
 
+use crate::retention;
+
 pub fn create_tables(conn: &Connection) -> Result<()> {
     // Activities table
     conn.execute(
@@ -10,10 +12,19 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
             window_title TEXT,
             duration_seconds INTEGER NOT NULL DEFAULT 0,
             is_idle BOOLEAN NOT NULL DEFAULT 0,
-            category TEXT
+            category TEXT,
+            algorithm_version INTEGER,
+            is_manual BOOLEAN NOT NULL DEFAULT 0,
+            project TEXT,
+            is_edited BOOLEAN NOT NULL DEFAULT 0
         )",
         [],
     )?;
+    add_column_if_missing(conn, "activities", "algorithm_version", "INTEGER")?;
+    add_column_if_missing(conn, "activities", "is_manual", "BOOLEAN NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "activities", "project", "TEXT")?;
+    add_column_if_missing(conn, "activities", "data_version", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "activities", "is_edited", "BOOLEAN NOT NULL DEFAULT 0")?;
 
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_timestamp ON activities(timestamp)",
@@ -44,15 +55,290 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // Raw monitor polls, capped ring buffer (see database::snapshots)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS raw_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp INTEGER NOT NULL,
+            app_name TEXT,
+            window_title TEXT,
+            idle_seconds INTEGER,
+            locked_since INTEGER
+        )",
+        [],
+    )?;
+    add_column_if_missing(conn, "raw_snapshots", "idle_seconds", "INTEGER")?;
+    add_column_if_missing(conn, "raw_snapshots", "locked_since", "INTEGER")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_raw_snapshots_timestamp ON raw_snapshots(timestamp)",
+        [],
+    )?;
+
+    // Continuous-use alert rules and the log of alerts they triggered
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_streak_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            pattern TEXT NOT NULL,
+            threshold_minutes INTEGER NOT NULL,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            snoozed_until INTEGER
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_streak_alerts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            rule_id INTEGER NOT NULL,
+            pattern TEXT NOT NULL,
+            app_name TEXT NOT NULL,
+            window_title TEXT,
+            streak_seconds INTEGER NOT NULL,
+            triggered_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS day_notes (
+            date TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS categories (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            color TEXT NOT NULL,
+            rating TEXT NOT NULL DEFAULT 'neutral'
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS category_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            category TEXT NOT NULL,
+            pattern TEXT NOT NULL,
+            match_field TEXT NOT NULL DEFAULT 'any',
+            priority INTEGER NOT NULL DEFAULT 0,
+            enabled BOOLEAN NOT NULL DEFAULT 1
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS suppressed_notifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            suppressed_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Deep work sessions used to be stored; they are derived on every read now
+    conn.execute("DROP TABLE IF EXISTS focus_sessions", [])?;
+
+    // Launch friction prompts; proceeded stays NULL until answered
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS friction_prompts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            app_name TEXT NOT NULL,
+            shown_at INTEGER NOT NULL,
+            decided_at INTEGER,
+            proceeded INTEGER
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_friction_prompts_shown_at ON friction_prompts(shown_at)",
+        [],
+    )?;
+
+    // One row per finished day, judged once for goal streaks
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS goal_days (
+            date TEXT PRIMARY KEY,
+            goals INTEGER NOT NULL,
+            met INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    // The goals as they were from `changed_at` on, for judging past days
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS goal_history (
+            changed_at INTEGER NOT NULL,
+            goals TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Labels for stretches of the timeline nothing was recorded in
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS gap_annotations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            start_time INTEGER NOT NULL,
+            end_time INTEGER NOT NULL,
+            label TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_gap_annotations_start_time ON gap_annotations(start_time)",
+        [],
+    )?;
+
+    // Projects activities are tagged with, registered as they are first seen
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            color TEXT,
+            hourly_rate REAL,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    // Projects detected before the table existed
+    let has_projects: bool = conn.query_row("SELECT EXISTS (SELECT 1 FROM projects)", [], |row| row.get(0))?;
+    if !has_projects {
+        conn.execute(
+            "INSERT OR IGNORE INTO projects (name, created_at)
+             SELECT project, MIN(timestamp) FROM activities WHERE project IS NOT NULL GROUP BY project",
+            [],
+        )?;
+    }
+
+    // Counters bumped by every write to a table, which cached results
+    // compare against. Each activity also keeps the counter value of its
+    // last write, so a cache can tell whether anything in its range changed.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS data_versions (
+            name TEXT PRIMARY KEY,
+            version INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO data_versions (name, version) VALUES ('activities', 0), ('categories', 0)",
+        [],
+    )?;
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS activities_insert_version AFTER INSERT ON activities BEGIN
+            UPDATE data_versions SET version = version + 1 WHERE name = 'activities';
+            UPDATE activities SET data_version = (SELECT version FROM data_versions WHERE name = 'activities') WHERE id = NEW.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS activities_update_version AFTER UPDATE ON activities
+        WHEN NEW.data_version = OLD.data_version BEGIN
+            UPDATE data_versions SET version = version + 1 WHERE name = 'activities';
+            UPDATE activities SET data_version = (SELECT version FROM data_versions WHERE name = 'activities') WHERE id = NEW.id;
+        END;
+        CREATE TRIGGER IF NOT EXISTS activities_delete_version AFTER DELETE ON activities BEGIN
+            UPDATE data_versions SET version = version + 1 WHERE name = 'activities';
+        END;
+        CREATE TRIGGER IF NOT EXISTS categories_insert_version AFTER INSERT ON categories BEGIN
+            UPDATE data_versions SET version = version + 1 WHERE name = 'categories';
+        END;
+        CREATE TRIGGER IF NOT EXISTS categories_update_version AFTER UPDATE ON categories BEGIN
+            UPDATE data_versions SET version = version + 1 WHERE name = 'categories';
+        END;
+        CREATE TRIGGER IF NOT EXISTS categories_delete_version AFTER DELETE ON categories BEGIN
+            UPDATE data_versions SET version = version + 1 WHERE name = 'categories';
+        END;",
+    )?;
+
     // Initialize default settings if not exists
     conn.execute(
         "INSERT OR IGNORE INTO settings (key, value) VALUES 
             ('tracking_enabled', 'true'),
             ('idle_timeout_seconds', '300'),
-            ('data_retention_days', '30'),
-            ('blocked_apps', '[]')",
+            ('sampling_interval_seconds', '10'),
+            ('coalesce_gap_seconds', '30'),
+            ('productivity_weights', '{\"active_time\": 25, \"category\": 35, \"context_switches\": 15, \"deep_work\": 25}'),
+            ('data_retention_days', '0'),
+            ('blocked_apps', '[]'),
+            ('blocked_apps_minimize_in_focus', 'false'),
+            ('private_apps', '[]'),
+            ('privacy_mode', 'off'),
+            ('privacy_hash_salt', ''),
+            ('title_redaction_rules', '[]'),
+            ('project_rules', '[]'),
+            ('project_detection_enabled', 'true'),
+            ('local_api_enabled', 'false'),
+            ('local_api_port', '47615'),
+            ('focus_webhook_url', ''),
+            ('focus_timer_overlay', 'true'),
+            ('deep_work_min_minutes', '25'),
+            ('deep_work_max_gap_minutes', '5'),
+            ('goals', '[]'),
+            ('range_presets', '[]'),
+            ('goals_notified', ''),
+            ('distraction_budgets', '[]'),
+            ('distraction_notified', ''),
+            ('break_reminder_enabled', 'true'),
+            ('break_reminder_minutes', '50'),
+            ('break_reminder_snooze_minutes', '10'),
+            ('break_reminder_snoozed_until', ''),
+            ('llm_insights_enabled', 'false'),
+            ('llm_provider', 'openai'),
+            ('llm_endpoint', ''),
+            ('llm_model', ''),
+            ('llm_api_key', ''),
+            ('llm_include_day_notes', 'false'),
+            ('llm_share_category_names', 'false'),
+            ('raw_snapshots_enabled', 'false'),
+            ('raw_snapshots_max_rows', '50000'),
+            ('quiet_hours_start', ''),
+            ('quiet_hours_end', ''),
+            ('notifications_snoozed_until', ''),
+            ('tracking_paused_until', ''),
+            ('storage_backend', 'sqlite'),
+            ('postgres_url', ''),
+            ('storage_synced_through', '0'),
+            ('notifications_disabled', '[]'),
+            ('timer_hotkeys', '[]'),
+            ('timer_running', ''),
+            ('launch_friction_enabled', 'false'),
+            ('launch_friction_seconds', '10'),
+            ('presentation_apps', '[\"PowerPoint Slide Show\", \"Presenter View\", \"Sharing control bar\", \"is sharing your screen\"]')",
         [],
     )?;
 
+    // Retention used to be seeded at 30 days before anything pruned. Once,
+    // a 30 left from that default becomes 0 (keep everything), so history is
+    // only deleted after the user picks a period.
+    let first_run = conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?1, 'true')",
+        [retention::DEFAULT_CLEARED_KEY],
+    )? > 0;
+    if first_run {
+        conn.execute("UPDATE settings SET value = '0' WHERE key = 'data_retention_days' AND value = '30'", [])?;
+    }
+
+    // The server now files activities under the login role instead
+    conn.execute("DELETE FROM settings WHERE key = 'postgres_user_id'", [])?;
+
+    Ok(())
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
     Ok(())
 }
@@ -0,0 +1,103 @@
+// Raw monitor snapshots
+// When `raw_snapshots_enabled` is set, every poll is stored exactly as the
+// platform backend reported it, before merging into activities. They are the
+// input for `activity::merge::rebuild_activities` and can be exported to
+// chase a "my time looks wrong" report. The table is a ring buffer capped at
+// `raw_snapshots_max_rows` (the default keeps about six days at 10s polls).
+// It is off by default, as it keeps every window title seen.
+
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use super::queries::get_setting;
+use crate::activity::ActivitySnapshot;
+
+const DEFAULT_MAX_ROWS: i64 = 50_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawSnapshot {
+    pub id: i64,
+    pub timestamp: i64,
+    /// `None` when the poll found no foreground window
+    pub app_name: Option<String>,
+    pub window_title: Option<String>,
+    /// Seconds since the last input, where the platform reports it
+    pub idle_seconds: Option<i64>,
+    /// When the session was locked, if it was locked at this poll
+    pub locked_since: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RawSnapshotExport {
+    pub app_version: String,
+    pub exported_at: i64,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub poll_interval_seconds: u64,
+    pub snapshots: Vec<RawSnapshot>,
+}
+
+pub fn is_enabled(conn: &Connection) -> Result<bool> {
+    Ok(get_setting(conn, "raw_snapshots_enabled")?.is_some_and(|v| v == "true"))
+}
+
+/// Stores one poll if raw snapshots are enabled, dropping the oldest rows
+/// beyond the configured cap
+pub fn record_raw_snapshot(
+    conn: &Connection,
+    snapshot: Option<&ActivitySnapshot>,
+    idle_seconds: Option<i64>,
+    locked_since: Option<i64>,
+    timestamp: i64,
+) -> Result<()> {
+    if !is_enabled(conn)? {
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO raw_snapshots (timestamp, app_name, window_title, idle_seconds, locked_since)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            timestamp,
+            snapshot.map(|s| s.app_name.as_str()),
+            snapshot.map(|s| s.window_title.as_str()),
+            idle_seconds,
+            locked_since,
+        ],
+    )?;
+
+    let max_rows = get_setting(conn, "raw_snapshots_max_rows")?
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_ROWS);
+    conn.execute(
+        "DELETE FROM raw_snapshots WHERE id <= ?1",
+        params![conn.last_insert_rowid() - max_rows],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_raw_snapshots(conn: &Connection, start_timestamp: i64, end_timestamp: i64) -> Result<Vec<RawSnapshot>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, app_name, window_title, idle_seconds, locked_since
+         FROM raw_snapshots
+         WHERE timestamp BETWEEN ?1 AND ?2
+         ORDER BY timestamp ASC, id ASC",
+    )?;
+
+    let snapshots = stmt
+        .query_map(params![start_timestamp, end_timestamp], |row| {
+            Ok(RawSnapshot {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                app_name: row.get(2)?,
+                window_title: row.get(3)?,
+                idle_seconds: row.get(4)?,
+                locked_since: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(snapshots)
+}
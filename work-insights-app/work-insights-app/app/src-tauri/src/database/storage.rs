@@ -0,0 +1,303 @@
+// Activity storage backends
+// The tracker always records into the local SQLite database, which every
+// view reads from, and SQLite alone remains the default. Teams who centralize
+// their data can also keep activities on a shared Postgres server (builds
+// with the `postgres` feature): with `storage_backend` set to "postgres",
+// activities are pushed to `postgres_url` about a minute after they are
+// recorded. The server files them under the login role in the URL, so one
+// server holds everyone's data and each user only ever reads and writes
+// their own rows. `migrate_to_server` copies the history recorded so far in
+// one go.
+//
+// Both backends implement `ActivityStore` and copying only goes through it.
+// The local id of the last activity copied is kept in
+// `storage_synced_through`, and a server skips activities it already has
+// for the user, so a copy cut short by a lost connection simply resumes.
+// Activities changed locally after they were copied, e.g. recategorized,
+// keep their old values on the server. Only an explicit resync starts over
+// from the first local activity.
+
+use std::thread;
+use std::time::Duration;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::DbConnection;
+use super::queries::{get_setting, set_setting, Activity, ActivityWriter};
+
+pub const BACKEND_KEY: &str = "storage_backend";
+pub const URL_KEY: &str = "postgres_url";
+pub const SYNCED_THROUGH_KEY: &str = "storage_synced_through";
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+const BATCH_SIZE: usize = 500;
+
+pub trait ActivityStore {
+    /// Who the activities belong to, for stores shared between users
+    fn user_id(&self) -> Option<&str> {
+        None
+    }
+    /// Activities with an id above `after_id`, in id order, at most `limit`
+    fn activities_after(&mut self, after_id: i64, limit: usize) -> Result<Vec<Activity>, String>;
+    /// Stores the activities not stored yet, returning how many were new
+    fn insert_activities(&mut self, activities: &[Activity]) -> Result<usize, String>;
+    fn count_activities(&mut self) -> Result<i64, String>;
+}
+
+/// The local database
+pub struct SqliteStore<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> SqliteStore<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl ActivityStore for SqliteStore<'_> {
+    fn activities_after(&mut self, after_id: i64, limit: usize) -> Result<Vec<Activity>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project, is_edited
+                 FROM activities WHERE id > ?1 ORDER BY id LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+        let activities = stmt
+            .query_map(params![after_id, limit as i64], |row| {
+                Ok(Activity {
+                    id: Some(row.get(0)?),
+                    timestamp: row.get(1)?,
+                    app_name: row.get(2)?,
+                    window_title: row.get(3)?,
+                    duration_seconds: row.get(4)?,
+                    is_idle: row.get(5)?,
+                    category: row.get(6)?,
+                    algorithm_version: row.get(7)?,
+                    is_manual: row.get(8)?,
+                    project: row.get(9)?,
+                    is_edited: row.get(10)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?;
+        Ok(activities)
+    }
+
+    fn insert_activities(&mut self, activities: &[Activity]) -> Result<usize, String> {
+        let mut writer = ActivityWriter::new(self.conn);
+        let mut inserted = 0;
+        for activity in activities {
+            let exists: bool = self
+                .conn
+                .query_row("SELECT EXISTS(SELECT 1 FROM activities WHERE timestamp = ?1)", params![activity.timestamp], |row| row.get(0))
+                .map_err(|e| e.to_string())?;
+            if !exists {
+                writer.insert(activity).map_err(|e| e.to_string())?;
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+
+    fn count_activities(&mut self) -> Result<i64, String> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM activities", [], |row| row.get(0))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageStatus {
+    /// "sqlite" or "postgres"
+    pub backend: String,
+    pub user_id: Option<String>,
+    pub local_activities: i64,
+    /// Recorded locally but not copied to the server yet
+    pub pending_activities: i64,
+    pub server_activities: Option<i64>,
+    /// Why the server couldn't be reached, if it couldn't
+    pub server_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationResult {
+    pub user_id: String,
+    pub copied: usize,
+    /// Copied activities the server didn't have yet
+    pub inserted: usize,
+    pub server_activities: i64,
+}
+
+struct ServerConfig {
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    url: String,
+}
+
+fn is_server_backend(conn: &Connection) -> rusqlite::Result<bool> {
+    Ok(get_setting(conn, BACKEND_KEY)?.is_some_and(|v| v == "postgres"))
+}
+
+fn server_config(conn: &Connection) -> Result<ServerConfig, String> {
+    let url = get_setting(conn, URL_KEY)
+        .map_err(|e| e.to_string())?
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| format!("Set \"{}\" to the server's connection URL first", URL_KEY))?;
+    Ok(ServerConfig { url: url.trim().to_string() })
+}
+
+#[cfg(feature = "postgres")]
+fn connect(config: &ServerConfig) -> Result<Box<dyn ActivityStore + Send>, String> {
+    Ok(Box::new(super::postgres::PostgresStore::connect(&config.url)?))
+}
+
+#[cfg(not(feature = "postgres"))]
+fn connect(_config: &ServerConfig) -> Result<Box<dyn ActivityStore + Send>, String> {
+    Err("This build has no Postgres support; build with the \"postgres\" feature".to_string())
+}
+
+fn synced_through(conn: &Connection) -> Result<i64, String> {
+    Ok(get_setting(conn, SYNCED_THROUGH_KEY)
+        .map_err(|e| e.to_string())?
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0))
+}
+
+/// Copies the activities recorded since the last copy to `server` in
+/// batches, taking the database lock only to read each batch. Returns how
+/// many were copied and how many of those were new to the server.
+fn push_pending(db: &DbConnection, server: &mut dyn ActivityStore) -> Result<(usize, usize), String> {
+    let (mut copied, mut inserted) = (0, 0);
+    loop {
+        let batch = {
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            let after = synced_through(&conn)?;
+            SqliteStore::new(&conn).activities_after(after, BATCH_SIZE)?
+        };
+        let Some(last_id) = batch.last().and_then(|activity| activity.id) else {
+            return Ok((copied, inserted));
+        };
+
+        inserted += server.insert_activities(&batch)?;
+        copied += batch.len();
+        {
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            set_setting(&conn, SYNCED_THROUGH_KEY, &last_id.to_string()).map_err(|e| e.to_string())?;
+        }
+
+        if batch.len() < BATCH_SIZE {
+            return Ok((copied, inserted));
+        }
+    }
+}
+
+/// Copies the local history not on the configured server yet and switches
+/// `storage_backend` to "postgres", so new activities follow. With
+/// `from_start` every local activity is offered to the server again, e.g.
+/// after pointing `postgres_url` at a different server.
+pub fn migrate_to_server(db: &DbConnection, from_start: bool) -> Result<MigrationResult, String> {
+    let config = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        server_config(&conn)?
+    };
+    let mut server = connect(&config)?;
+
+    if from_start {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, SYNCED_THROUGH_KEY, "0").map_err(|e| e.to_string())?;
+    }
+    let (copied, inserted) = push_pending(db, server.as_mut())?;
+    let server_activities = server.count_activities()?;
+
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, BACKEND_KEY, "postgres").map_err(|e| e.to_string())?;
+    }
+    let user_id = server.user_id().unwrap_or_default().to_string();
+    println!("✅ Migrated {} activities to the server for {} ({} new)", copied, user_id, inserted);
+    Ok(MigrationResult { user_id, copied, inserted, server_activities })
+}
+
+pub fn get_status(db: &DbConnection) -> Result<StorageStatus, String> {
+    let (server, local_activities, pending_activities) = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let server = if is_server_backend(&conn).map_err(|e| e.to_string())? {
+            Some(server_config(&conn))
+        } else {
+            None
+        };
+        let local_activities = SqliteStore::new(&conn).count_activities()?;
+        let pending_activities: i64 = conn
+            .query_row("SELECT COUNT(*) FROM activities WHERE id > ?1", params![synced_through(&conn)?], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        (server, local_activities, pending_activities)
+    };
+
+    let Some(config) = server else {
+        return Ok(StorageStatus {
+            backend: "sqlite".to_string(),
+            user_id: None,
+            local_activities,
+            pending_activities: 0,
+            server_activities: None,
+            server_error: None,
+        });
+    };
+
+    let (user_id, counted) = match config.and_then(|config| connect(&config)) {
+        Ok(mut server) => (server.user_id().map(str::to_string), server.count_activities()),
+        Err(e) => (None, Err(e)),
+    };
+    Ok(StorageStatus {
+        backend: "postgres".to_string(),
+        user_id,
+        local_activities,
+        pending_activities,
+        server_activities: counted.as_ref().ok().copied(),
+        server_error: counted.err(),
+    })
+}
+
+/// Pushes new activities to the server every minute while `storage_backend`
+/// is "postgres", reconnecting after failures
+pub fn start_sync(db: &DbConnection) {
+    let db = std::sync::Arc::clone(db);
+
+    thread::spawn(move || {
+        let mut server: Option<Box<dyn ActivityStore + Send>> = None;
+        loop {
+            thread::sleep(SYNC_INTERVAL);
+
+            let config = match db.lock() {
+                Ok(conn) => match is_server_backend(&conn) {
+                    Ok(true) => server_config(&conn),
+                    _ => {
+                        server = None;
+                        continue;
+                    }
+                },
+                Err(_) => continue,
+            };
+            let result = config.and_then(|config| {
+                if server.is_none() {
+                    server = Some(connect(&config)?);
+                }
+                match server.as_mut() {
+                    Some(server) => push_pending(&db, server.as_mut()),
+                    None => Ok((0, 0)),
+                }
+            });
+
+            match result {
+                Ok((copied, _)) if copied > 0 => println!("✅ Copied {} activities to the server", copied),
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("❌ Failed to copy activities to the server: {}", e);
+                    server = None;
+                }
+            }
+        }
+    });
+}
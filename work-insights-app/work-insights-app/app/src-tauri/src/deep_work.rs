@@ -0,0 +1,155 @@
+// Deep work sessions
+// Finds stretches of focused work in the record after the fact, as opposed
+// to the focus sessions a user starts by hand. Consecutive active time in
+// categories rated productive forms a session; anything else in between
+// (idle time, a detour to chat) is an interruption and is allowed as long as
+// it is no longer than `deep_work_max_gap_minutes`. Sessions shorter than
+// `deep_work_min_minutes` from first to last productive second are dropped.
+//
+// This is the only definition of deep work: the productivity score and the
+// weekly trends and report all count the time in these sessions. Sessions
+// are detected per local day on every read and never stored, so
+// recategorized history and changed thresholds are always reflected.
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::database::queries::{get_setting, local_day_bounds, set_setting, DATE_FORMAT};
+
+const MIN_MINUTES_KEY: &str = "deep_work_min_minutes";
+const MAX_GAP_MINUTES_KEY: &str = "deep_work_max_gap_minutes";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepWorkSettings {
+    pub min_minutes: i64,
+    pub max_gap_minutes: i64,
+}
+
+impl Default for DeepWorkSettings {
+    fn default() -> Self {
+        Self { min_minutes: 25, max_gap_minutes: 5 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepWorkSession {
+    /// Local "YYYY-MM-DD" the session started on
+    pub date: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub focused_seconds: i64,
+    /// Time between `started_at` and `ended_at` spent on anything else
+    pub interrupted_seconds: i64,
+    /// The productive category with the most time in the session
+    pub top_category: String,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<DeepWorkSettings> {
+    let defaults = DeepWorkSettings::default();
+    let minutes = |key, default| -> Result<i64> {
+        Ok(get_setting(conn, key)?.and_then(|v| v.trim().parse().ok()).unwrap_or(default))
+    };
+
+    Ok(DeepWorkSettings {
+        min_minutes: minutes(MIN_MINUTES_KEY, defaults.min_minutes)?,
+        max_gap_minutes: minutes(MAX_GAP_MINUTES_KEY, defaults.max_gap_minutes)?,
+    })
+}
+
+pub fn save_settings(conn: &Connection, settings: &DeepWorkSettings) -> std::result::Result<(), String> {
+    if settings.min_minutes < 1 {
+        return Err("Minimum session length must be at least a minute".to_string());
+    }
+    if settings.max_gap_minutes < 0 {
+        return Err("Interruption gap must not be negative".to_string());
+    }
+
+    let save = |key, value: i64| set_setting(conn, key, &value.to_string()).map_err(|e| e.to_string());
+    save(MIN_MINUTES_KEY, settings.min_minutes)?;
+    save(MAX_GAP_MINUTES_KEY, settings.max_gap_minutes)
+}
+
+/// Sessions on the local days from `start` to `end` inclusive, oldest first
+pub fn get_sessions(conn: &Connection, start: NaiveDate, end: NaiveDate) -> Result<Vec<DeepWorkSession>> {
+    let settings = get_settings(conn)?;
+    let mut sessions = Vec::new();
+    for day in start.iter_days().take_while(|day| *day <= end) {
+        sessions.extend(detect_day(conn, day, &settings)?);
+    }
+    Ok(sessions)
+}
+
+/// Focused time in the sessions on the local days from `start` to `end`
+/// inclusive
+pub fn deep_work_seconds(conn: &Connection, start: NaiveDate, end: NaiveDate) -> Result<i64> {
+    Ok(get_sessions(conn, start, end)?.iter().map(|session| session.focused_seconds).sum())
+}
+
+fn detect_day(conn: &Connection, date: NaiveDate, settings: &DeepWorkSettings) -> Result<Vec<DeepWorkSession>> {
+    let (start, end) = local_day_bounds(date);
+    let mut stmt = conn.prepare(
+        "SELECT a.timestamp, a.duration_seconds, c.name
+         FROM activities a
+         JOIN categories c ON c.name = a.category COLLATE NOCASE
+         WHERE a.timestamp BETWEEN ?1 AND ?2 AND a.is_idle = 0 AND c.rating = 'productive'
+         ORDER BY a.timestamp, a.id",
+    )?;
+    let rows = stmt
+        .query_map(params![start, end], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let max_gap = settings.max_gap_minutes * 60;
+    let date = date.format(DATE_FORMAT).to_string();
+    let mut sessions = Vec::new();
+    // Start, end and seconds per category of the session being built
+    let mut current: Option<(i64, i64, HashMap<String, i64>)> = None;
+
+    for (timestamp, duration, category) in rows {
+        match current.as_mut() {
+            Some((_, session_end, categories)) if timestamp - *session_end <= max_gap => {
+                *session_end = (*session_end).max(timestamp + duration);
+                *categories.entry(category).or_default() += duration;
+            }
+            _ => {
+                let next = (timestamp, timestamp + duration, HashMap::from([(category, duration)]));
+                if let Some(session) = current.replace(next) {
+                    sessions.extend(finish(&date, session, settings));
+                }
+            }
+        }
+    }
+    if let Some(session) = current {
+        sessions.extend(finish(&date, session, settings));
+    }
+
+    Ok(sessions)
+}
+
+fn finish(
+    date: &str,
+    (started_at, ended_at, categories): (i64, i64, HashMap<String, i64>),
+    settings: &DeepWorkSettings,
+) -> Option<DeepWorkSession> {
+    if ended_at - started_at < settings.min_minutes * 60 {
+        return None;
+    }
+
+    let focused_seconds: i64 = categories.values().sum();
+    let top_category = categories
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .map(|(category, _)| category)
+        .unwrap_or_default();
+    Some(DeepWorkSession {
+        date: date.to_string(),
+        started_at,
+        ended_at,
+        focused_seconds,
+        interrupted_seconds: (ended_at - started_at - focused_seconds).max(0),
+        top_category,
+    })
+}
@@ -0,0 +1,284 @@
+// Distraction budgets
+// A daily allowance of minutes for an app (or a site matched on the window
+// title) or a category the user finds distracting. The activity monitor adds
+// up today's time per budget as it samples, so a budget is caught while the
+// distraction is still in the foreground rather than once its activity is
+// written, and sends one notification per budget and day when it is crossed.
+//
+// Budgets are kept as JSON in the `distraction_budgets` setting, so they
+// travel with settings profiles; which budgets have already notified today
+// is kept in `distraction_notified` so a restart doesn't repeat them.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use chrono::{Local, NaiveDate, TimeZone};
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::{merge, ActivitySnapshot};
+use crate::categories;
+use crate::database::queries::{get_setting, local_day_bounds, set_setting, DATE_FORMAT};
+use crate::notifications::{self, NotificationKind};
+
+pub const DISTRACTION_ALERT_EVENT: &str = "distraction-budget-exceeded";
+
+const BUDGETS_KEY: &str = "distraction_budgets";
+pub const NOTIFIED_KEY: &str = "distraction_notified";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetTarget {
+    /// Case-insensitive substring of the app name or window title
+    App,
+    /// A category name, matched ignoring case
+    Category,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistractionBudget {
+    pub id: Option<i64>,
+    pub target: BudgetTarget,
+    pub pattern: String,
+    pub daily_minutes: i64,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DistractionUsage {
+    pub budget: DistractionBudget,
+    pub used_seconds: i64,
+    pub budget_seconds: i64,
+    pub exceeded: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DistractionAlert {
+    pub budget_id: i64,
+    pub pattern: String,
+    pub used_seconds: i64,
+    pub budget_seconds: i64,
+    pub triggered_at: i64,
+}
+
+/// Budgets already notified on `date`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NotifiedBudgets {
+    date: String,
+    budget_ids: Vec<i64>,
+}
+
+pub fn get_budgets(conn: &Connection) -> Result<Vec<DistractionBudget>> {
+    Ok(get_setting(conn, BUDGETS_KEY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_budget(conn: &Connection, budget: &DistractionBudget) -> std::result::Result<DistractionBudget, String> {
+    if budget.pattern.trim().is_empty() {
+        return Err("A budget needs an app or category".to_string());
+    }
+    if budget.daily_minutes < 0 {
+        return Err("Daily budget must not be negative".to_string());
+    }
+
+    let mut budgets = get_budgets(conn).map_err(|e| e.to_string())?;
+    let id = budget.id.unwrap_or_else(|| budgets.iter().filter_map(|b| b.id).max().unwrap_or(0) + 1);
+    let saved = DistractionBudget { id: Some(id), pattern: budget.pattern.trim().to_string(), ..budget.clone() };
+    match budgets.iter_mut().find(|b| b.id == Some(id)) {
+        Some(existing) => *existing = saved.clone(),
+        None => budgets.push(saved.clone()),
+    }
+
+    write_budgets(conn, &budgets).map_err(|e| e.to_string())?;
+    Ok(saved)
+}
+
+pub fn delete_budget(conn: &Connection, budget_id: i64) -> Result<()> {
+    let mut budgets = get_budgets(conn)?;
+    budgets.retain(|budget| budget.id != Some(budget_id));
+    write_budgets(conn, &budgets)
+}
+
+fn write_budgets(conn: &Connection, budgets: &[DistractionBudget]) -> Result<()> {
+    let json = serde_json::to_string(budgets).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, BUDGETS_KEY, &json)
+}
+
+/// Recorded time against every enabled budget on a local day. The activity
+/// in progress isn't written yet and so isn't included.
+pub fn get_usage(conn: &Connection, date: NaiveDate) -> Result<Vec<DistractionUsage>> {
+    get_budgets(conn)?
+        .into_iter()
+        .filter(|budget| budget.enabled)
+        .map(|budget| {
+            let used_seconds = recorded_seconds(conn, &budget, date)?;
+            let budget_seconds = budget.daily_minutes * 60;
+            Ok(DistractionUsage { budget, used_seconds, budget_seconds, exceeded: used_seconds > budget_seconds })
+        })
+        .collect()
+}
+
+fn recorded_seconds(conn: &Connection, budget: &DistractionBudget, date: NaiveDate) -> Result<i64> {
+    let (start, end) = local_day_bounds(date);
+    let pattern = budget.pattern.trim().to_lowercase();
+    let sql = match budget.target {
+        BudgetTarget::App => {
+            "SELECT COALESCE(SUM(duration_seconds), 0)
+             FROM activities
+             WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+               AND (instr(lower(app_name), ?3) > 0 OR instr(lower(COALESCE(window_title, '')), ?3) > 0)"
+        }
+        BudgetTarget::Category => {
+            "SELECT COALESCE(SUM(duration_seconds), 0)
+             FROM activities
+             WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0 AND category = ?3 COLLATE NOCASE"
+        }
+    };
+    conn.query_row(sql, params![start, end, pattern], |row| row.get(0))
+}
+
+/// Today's time per budget for the monitor thread
+#[derive(Default)]
+pub struct DistractionTracker {
+    day: Option<NaiveDate>,
+    /// Seconds per budget id, starting from what was recorded when the
+    /// budget was first seen today
+    used: HashMap<i64, i64>,
+    /// The previous sample and the budgets it matched, which the time up to
+    /// the next sample is counted against
+    last: Option<(i64, Vec<i64>)>,
+}
+
+impl DistractionTracker {
+    /// Adds the time since the previous sample and returns the budgets that
+    /// were crossed and haven't notified today, marked as notified
+    pub fn observe(
+        &mut self,
+        conn: &Connection,
+        snapshot: Option<&ActivitySnapshot>,
+        idle_seconds: Option<i64>,
+        now: i64,
+    ) -> Result<Vec<DistractionAlert>> {
+        let Some(today) = Local.timestamp_opt(now, 0).single().map(|t| t.date_naive()) else {
+            return Ok(Vec::new());
+        };
+        if self.day != Some(today) {
+            *self = Self { day: Some(today), ..Self::default() };
+        }
+
+        let budgets: Vec<DistractionBudget> = get_budgets(conn)?.into_iter().filter(|budget| budget.enabled).collect();
+        for budget in &budgets {
+            let Some(id) = budget.id else { continue };
+            if let Entry::Vacant(entry) = self.used.entry(id) {
+                entry.insert(recorded_seconds(conn, budget, today)?);
+            }
+        }
+        if let Some((previous, matched)) = self.last.take() {
+            // A long gap is a sleep or a stop, not time spent
+            let elapsed = (now - previous).clamp(0, merge::MAX_SAMPLING_INTERVAL_SECS);
+            for id in matched {
+                if let Some(used) = self.used.get_mut(&id) {
+                    *used += elapsed;
+                }
+            }
+        }
+
+        let idle = idle_seconds.is_some_and(|idle| idle >= merge::idle_timeout(conn).unwrap_or(merge::DEFAULT_IDLE_TIMEOUT_SECS));
+        let matched = match snapshot.filter(|_| !idle) {
+            Some(snapshot) => {
+                let category = categories::categorize_window(
+                    &categories::get_rules(conn)?,
+                    &snapshot.app_name,
+                    &snapshot.window_title,
+                );
+                budgets
+                    .iter()
+                    .filter(|budget| matches(budget, snapshot, category.as_deref()))
+                    .filter_map(|budget| budget.id)
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.last = Some((now, matched));
+
+        self.newly_exceeded(conn, &budgets, today, now)
+    }
+
+    fn newly_exceeded(
+        &self,
+        conn: &Connection,
+        budgets: &[DistractionBudget],
+        today: NaiveDate,
+        now: i64,
+    ) -> Result<Vec<DistractionAlert>> {
+        let date = today.format(DATE_FORMAT).to_string();
+        let mut notified: NotifiedBudgets = get_setting(conn, NOTIFIED_KEY)?
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .filter(|notified: &NotifiedBudgets| notified.date == date)
+            .unwrap_or(NotifiedBudgets { date, budget_ids: Vec::new() });
+
+        let alerts: Vec<DistractionAlert> = budgets
+            .iter()
+            .filter_map(|budget| {
+                let id = budget.id?;
+                let used_seconds = *self.used.get(&id)?;
+                let budget_seconds = budget.daily_minutes * 60;
+                (used_seconds > budget_seconds && !notified.budget_ids.contains(&id)).then(|| DistractionAlert {
+                    budget_id: id,
+                    pattern: budget.pattern.clone(),
+                    used_seconds,
+                    budget_seconds,
+                    triggered_at: now,
+                })
+            })
+            .collect();
+        if !alerts.is_empty() {
+            notified.budget_ids.extend(alerts.iter().map(|alert| alert.budget_id));
+            let json = serde_json::to_string(&notified).unwrap_or_default();
+            set_setting(conn, NOTIFIED_KEY, &json)?;
+        }
+
+        Ok(alerts)
+    }
+}
+
+/// Whether an enabled budget covers the window in `snapshot`
+pub fn is_distracting(conn: &Connection, snapshot: &ActivitySnapshot) -> Result<bool> {
+    let budgets: Vec<DistractionBudget> = get_budgets(conn)?.into_iter().filter(|budget| budget.enabled).collect();
+    if budgets.is_empty() {
+        return Ok(false);
+    }
+    let category = categories::categorize_window(&categories::get_rules(conn)?, &snapshot.app_name, &snapshot.window_title);
+    Ok(budgets.iter().any(|budget| matches(budget, snapshot, category.as_deref())))
+}
+
+fn matches(budget: &DistractionBudget, snapshot: &ActivitySnapshot, category: Option<&str>) -> bool {
+    let pattern = budget.pattern.trim().to_lowercase();
+    match budget.target {
+        BudgetTarget::App => {
+            snapshot.app_name.to_lowercase().contains(&pattern)
+                || snapshot.window_title.to_lowercase().contains(&pattern)
+        }
+        BudgetTarget::Category => category.is_some_and(|category| category.to_lowercase() == pattern),
+    }
+}
+
+/// Tells the frontend and shows a system notification
+pub fn notify(app: &AppHandle, alert: &DistractionAlert) {
+    if let Err(e) = app.emit(DISTRACTION_ALERT_EVENT, alert) {
+        eprintln!("❌ Failed to emit distraction alert: {}", e);
+    }
+
+    notifications::notify(
+        app,
+        NotificationKind::DistractionAlert,
+        "Distraction budget used up",
+        &format!(
+            "{} minutes on {} today, over your {} minute budget",
+            alert.used_seconds / 60,
+            alert.pattern,
+            alert.budget_seconds / 60
+        ),
+    );
+}
@@ -0,0 +1,249 @@
+// Exports
+// Activities can leave the app in two shapes. A CSV file of a range, for
+// analysis in a spreadsheet: rows go from the database to the file one at a
+// time, so exporting a year of history doesn't need it all in memory.
+// Besides the stored columns each row carries what is awkward to work out in
+// a spreadsheet: the local date, start and end time, and the rating of the
+// activity's category.
+//
+// Fields are quoted as RFC 4180 describes when they contain a comma, quote or
+// line break. Text starting with =, +, - or @ gets a leading apostrophe so a
+// window title can't turn into a formula when the file is opened.
+//
+// And a versioned JSON document of the activities together with the
+// settings profile (settings, categories and their rules, blocked apps,
+// streak rules) for moving to another machine or archiving. Importing it
+// applies the profile as `import_settings` does and adds the activities this
+// database doesn't have yet, all in one transaction.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use chrono::{Local, TimeZone, Utc};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::database::queries::{get_activities_by_date_range, Activity};
+use crate::database::storage::{ActivityStore, SqliteStore};
+use crate::profile::{self, ProfileImportResult, SettingsProfile};
+
+/// Bump when the JSON document changes in a way older apps can't read
+pub const DATA_EXPORT_VERSION: u32 = 1;
+
+const HEADER: [&str; 13] = [
+    "id",
+    "date",
+    "start_time",
+    "end_time",
+    "timestamp",
+    "duration_seconds",
+    "app_name",
+    "window_title",
+    "category",
+    "rating",
+    "is_idle",
+    "is_manual",
+    "project",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvExportResult {
+    pub path: String,
+    pub rows: usize,
+    pub bytes: u64,
+}
+
+/// Writes the activities between the timestamps to `path`, replacing any
+/// file there once the export succeeds. A failed export leaves it as it was.
+pub fn export_activities_csv(
+    conn: &Connection,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    path: &str,
+) -> Result<CsvExportResult, String> {
+    let rows = write_replacing(path, |out| write_activities(conn, start_timestamp, end_timestamp, out))
+        .map_err(|e| format!("Failed to export activities: {}", e))?;
+    let bytes = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    Ok(CsvExportResult { path: path.to_string(), rows, bytes })
+}
+
+/// Writes to a file next to `path` and moves it over `path` only once
+/// `write` succeeds, so a failed write never costs the file already there
+fn write_replacing<T>(path: &str, write: impl FnOnce(&mut BufWriter<File>) -> Result<T, String>) -> Result<T, String> {
+    let partial = partial_path(Path::new(path));
+    let file = File::create(&partial).map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+    let mut out = BufWriter::new(file);
+    let written = write(&mut out).and_then(|value| out.flush().map(|_| value).map_err(|e| e.to_string()));
+    drop(out);
+
+    let moved = written.and_then(|value| {
+        fs::rename(&partial, path).map(|_| value).map_err(|e| format!("Failed to replace {}: {}", path, e))
+    });
+    if moved.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    moved
+}
+
+/// `path` with its file name hidden and marked as partial, in its folder so
+/// renaming it over `path` never crosses file systems
+fn partial_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!(".{}.partial", name))
+}
+
+fn write_activities(
+    conn: &Connection,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    out: &mut impl Write,
+) -> Result<usize, String> {
+    write_record(out, &HEADER.map(String::from)).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, a.timestamp, a.app_name, a.window_title, a.duration_seconds, a.is_idle,
+                    COALESCE(a.category, 'Uncategorized'), c.rating, a.is_manual, a.project
+             FROM activities a
+             LEFT JOIN categories c ON c.name = a.category
+             WHERE a.timestamp BETWEEN ?1 AND ?2
+             ORDER BY a.timestamp ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt.query(params![start_timestamp, end_timestamp]).map_err(|e| e.to_string())?;
+
+    let mut written = 0;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let record = to_record(row).map_err(|e| e.to_string())?;
+        write_record(out, &record).map_err(|e| e.to_string())?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+/// The CSV fields for one row of the query above
+fn to_record(row: &Row) -> rusqlite::Result<[String; 13]> {
+    let timestamp: i64 = row.get(1)?;
+    let duration: i64 = row.get(4)?;
+    let start = Local.timestamp_opt(timestamp, 0).single();
+    let end = Local.timestamp_opt(timestamp + duration, 0).single();
+    let is_idle: bool = row.get(5)?;
+
+    Ok([
+        row.get::<_, i64>(0)?.to_string(),
+        start.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+        start.map(|t| t.format("%H:%M:%S").to_string()).unwrap_or_default(),
+        end.map(|t| t.format("%H:%M:%S").to_string()).unwrap_or_default(),
+        timestamp.to_string(),
+        duration.to_string(),
+        row.get(2)?,
+        row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        row.get(6)?,
+        row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+        is_idle.to_string(),
+        row.get::<_, bool>(8)?.to_string(),
+        row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+    ])
+}
+
+fn write_record(out: &mut impl Write, fields: &[String]) -> std::io::Result<()> {
+    let line: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+    out.write_all(line.join(",").as_bytes())?;
+    out.write_all(b"\r\n")
+}
+
+fn escape(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataExport {
+    pub version: u32,
+    pub app_version: String,
+    pub exported_at: i64,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub profile: SettingsProfile,
+    pub activities: Vec<Activity>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataExportResult {
+    pub path: String,
+    pub activities: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataImportResult {
+    pub profile: ProfileImportResult,
+    pub activities_imported: usize,
+    /// Activities already in this database
+    pub activities_skipped: usize,
+}
+
+/// Writes activities between the timestamps, or all of them, with the
+/// settings profile to `path` as JSON, replacing any file there once the
+/// export succeeds
+pub fn export_data_json(
+    conn: &Connection,
+    app_version: &str,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+    path: &str,
+) -> Result<DataExportResult, String> {
+    let start_timestamp = start_timestamp.unwrap_or(0);
+    let end_timestamp = end_timestamp.unwrap_or(i64::MAX);
+    let data = DataExport {
+        version: DATA_EXPORT_VERSION,
+        app_version: app_version.to_string(),
+        exported_at: Utc::now().timestamp(),
+        start_timestamp,
+        end_timestamp,
+        profile: profile::export_profile(conn).map_err(|e| e.to_string())?,
+        activities: get_activities_by_date_range(conn, start_timestamp, end_timestamp).map_err(|e| e.to_string())?,
+    };
+
+    write_replacing(path, |out| serde_json::to_writer_pretty(out, &data).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Failed to export data: {}", e))?;
+
+    let bytes = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    Ok(DataExportResult { path: path.to_string(), activities: data.activities.len(), bytes })
+}
+
+/// Applies a document written by `export_data_json`. Activities starting at
+/// the same second as one already recorded are skipped, so importing the
+/// same file twice changes nothing.
+pub fn import_data_json(conn: &mut Connection, path: &str) -> Result<DataImportResult, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let data: DataExport = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("{} is not a Work Insights data export: {}", path, e))?;
+    if data.version > DATA_EXPORT_VERSION {
+        return Err(format!(
+            "Export version {} is newer than this app supports ({}); update the app first",
+            data.version, DATA_EXPORT_VERSION
+        ));
+    }
+    let checked = profile::check_profile(&data.profile)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let profile = profile::apply_profile(&tx, &checked).map_err(|e| e.to_string())?;
+    let activities_imported = SqliteStore::new(&tx).insert_activities(&data.activities)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(DataImportResult {
+        profile,
+        activities_imported,
+        activities_skipped: data.activities.len() - activities_imported,
+    })
+}
@@ -0,0 +1,190 @@
+use std::thread;
+use std::time::Duration;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::api::events::{self, ApiEvent};
+use crate::database::{DbConnection, queries::{get_setting, set_setting}};
+use crate::focus_timer;
+
+const FOCUS_SESSION_KEY: &str = "focus_session";
+pub const FOCUS_EVENT: &str = "focus-session-changed";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub started_at: i64,
+    pub ends_at: Option<i64>,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FocusStatus {
+    pub active: bool,
+    pub session: Option<FocusSession>,
+    pub remaining_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusSessionEvent {
+    pub kind: String,
+    pub session: FocusSession,
+    pub timestamp: i64,
+}
+
+/// Returns the running focus session, ignoring one whose planned end has passed.
+pub fn get_active_session(conn: &rusqlite::Connection) -> rusqlite::Result<Option<FocusSession>> {
+    let session = get_setting(conn, FOCUS_SESSION_KEY)?
+        .filter(|value| !value.is_empty())
+        .and_then(|value| serde_json::from_str::<FocusSession>(&value).ok());
+
+    let now = Utc::now().timestamp();
+    Ok(session.filter(|s| s.ends_at.is_none_or(|end| end > now)))
+}
+
+pub fn get_status(conn: &rusqlite::Connection) -> rusqlite::Result<FocusStatus> {
+    let session = get_active_session(conn)?;
+    let now = Utc::now().timestamp();
+
+    Ok(FocusStatus {
+        active: session.is_some(),
+        remaining_seconds: session.as_ref().and_then(|s| s.ends_at).map(|end| end - now),
+        session,
+    })
+}
+
+pub fn start_session(
+    app: &AppHandle,
+    db: &DbConnection,
+    duration_minutes: Option<i64>,
+    label: Option<String>,
+) -> Result<FocusSession, String> {
+    if let Some(minutes) = duration_minutes {
+        if minutes <= 0 {
+            return Err("Focus session duration must be positive".to_string());
+        }
+    }
+
+    let now = Utc::now().timestamp();
+    let session = FocusSession {
+        started_at: now,
+        ends_at: duration_minutes.map(|m| now + m * 60),
+        label,
+    };
+
+    let previous = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let previous = get_active_session(&conn).map_err(|e| e.to_string())?;
+        let value = serde_json::to_string(&session).map_err(|e| e.to_string())?;
+        set_setting(&conn, FOCUS_SESSION_KEY, &value).map_err(|e| e.to_string())?;
+        previous
+    };
+
+    if let Some(previous) = previous {
+        notify(app, db, "ended", previous);
+    }
+    notify(app, db, "started", session.clone());
+    schedule_expiry(app, db, &session);
+
+    Ok(session)
+}
+
+pub fn end_session(app: &AppHandle, db: &DbConnection) -> Result<Option<FocusSession>, String> {
+    let ended = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let ended = get_active_session(&conn).map_err(|e| e.to_string())?;
+        set_setting(&conn, FOCUS_SESSION_KEY, "").map_err(|e| e.to_string())?;
+        ended
+    };
+
+    if let Some(ref session) = ended {
+        notify(app, db, "ended", session.clone());
+    }
+
+    Ok(ended)
+}
+
+/// Re-arms the expiry timer and reopens the timer overlay for a session that
+/// survived an app restart.
+pub fn resume_expiry(app: &AppHandle, db: &DbConnection) {
+    let session = db.lock().ok().and_then(|conn| get_active_session(&conn).ok().flatten());
+    if let Some(session) = session {
+        schedule_expiry(app, db, &session);
+        focus_timer::sync(app, db);
+    }
+}
+
+fn schedule_expiry(app: &AppHandle, db: &DbConnection, session: &FocusSession) {
+    let Some(ends_at) = session.ends_at else {
+        return;
+    };
+
+    let app = app.clone();
+    let db = std::sync::Arc::clone(db);
+    let session = session.clone();
+
+    thread::spawn(move || {
+        let wait = (ends_at - Utc::now().timestamp()).max(0) as u64;
+        thread::sleep(Duration::from_secs(wait));
+
+        // Only close the session we were scheduled for; it may have been
+        // ended manually or replaced by a newer one in the meantime.
+        let still_current = match db.lock() {
+            Ok(conn) => {
+                let stored = get_setting(&conn, FOCUS_SESSION_KEY)
+                    .ok()
+                    .flatten()
+                    .and_then(|value| serde_json::from_str::<FocusSession>(&value).ok());
+                let matches = stored.as_ref() == Some(&session);
+                if matches {
+                    let _ = set_setting(&conn, FOCUS_SESSION_KEY, "");
+                }
+                matches
+            }
+            Err(_) => false,
+        };
+
+        if still_current {
+            notify(&app, &db, "ended", session);
+        }
+    });
+}
+
+/// Publishes a start/end transition to the frontend and, if configured, to an
+/// external webhook so blocker tools can toggle along with us.
+fn notify(app: &AppHandle, db: &DbConnection, kind: &str, session: FocusSession) {
+    let event = FocusSessionEvent {
+        kind: kind.to_string(),
+        session,
+        timestamp: Utc::now().timestamp(),
+    };
+    let session = event.session.clone();
+    events::publish(&match kind {
+        "started" => ApiEvent::FocusSessionStarted { session, timestamp: event.timestamp },
+        _ => ApiEvent::FocusSessionEnded { session, timestamp: event.timestamp },
+    });
+
+    if let Err(e) = app.emit(FOCUS_EVENT, &event) {
+        eprintln!("❌ Failed to emit focus event: {}", e);
+    }
+    focus_timer::sync(app, db);
+
+    let webhook_url = db
+        .lock()
+        .ok()
+        .and_then(|conn| get_setting(&conn, "focus_webhook_url").ok().flatten())
+        .filter(|url| !url.is_empty());
+
+    if let Some(url) = webhook_url {
+        thread::spawn(move || {
+            let result = reqwest::blocking::Client::new()
+                .post(&url)
+                .timeout(Duration::from_secs(5))
+                .json(&event)
+                .send();
+            if let Err(e) = result {
+                eprintln!("❌ Focus webhook to {} failed: {}", url, e);
+            }
+        });
+    }
+}
@@ -0,0 +1,86 @@
+// Focus timer overlay
+// A small always-on-top window counting down the running focus session, so
+// the time left stays in view while the dashboard is hidden in the tray. The
+// focus session subsystem opens it when a session starts and closes it when
+// the session ends or expires; it is also closed while the user is
+// presenting and reopened afterwards. The window renders `get_focus_status` and
+// `focus-session-changed` events, and stops the session through
+// `end_focus_session`.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::database::queries::get_setting;
+use crate::database::DbConnection;
+use crate::focus::get_active_session;
+use crate::presentation;
+
+pub const WINDOW_LABEL: &str = "focus-timer";
+
+const WIDTH: f64 = 240.0;
+const HEIGHT: f64 = 88.0;
+/// Distance from the top right corner of the primary monitor
+const MARGIN: f64 = 24.0;
+
+/// The `focus_timer_overlay` setting
+pub fn is_enabled(conn: &rusqlite::Connection) -> bool {
+    get_setting(conn, "focus_timer_overlay").ok().flatten().is_none_or(|value| value == "true")
+}
+
+/// Opens the overlay while a session is running, the overlay is enabled and
+/// the user isn't presenting, and closes it otherwise
+pub fn sync(app: &AppHandle, db: &DbConnection) {
+    if let Ok(conn) = db.lock() {
+        sync_locked(app, &conn);
+    }
+}
+
+/// `sync` for callers already holding the database lock
+pub fn sync_locked(app: &AppHandle, conn: &rusqlite::Connection) {
+    let visible = is_enabled(conn) && !presentation::is_presenting() && get_active_session(conn).ok().flatten().is_some();
+    if visible {
+        show(app);
+    } else {
+        hide(app);
+    }
+}
+
+pub fn show(app: &AppHandle) {
+    let handle = app.clone();
+    // Building a window from a synchronous command deadlocks on Windows, so
+    // it is always done on the event loop
+    let result = app.run_on_main_thread(move || {
+        if handle.get_webview_window(WINDOW_LABEL).is_some() {
+            return;
+        }
+
+        let mut builder = WebviewWindowBuilder::new(&handle, WINDOW_LABEL, WebviewUrl::App("index.html#focus-timer".into()))
+            .title("Focus timer")
+            .inner_size(WIDTH, HEIGHT)
+            .resizable(false)
+            .decorations(false)
+            .always_on_top(true)
+            .visible_on_all_workspaces(true)
+            .skip_taskbar(true)
+            .focused(false);
+        if let Ok(Some(monitor)) = handle.primary_monitor() {
+            let size = monitor.size().to_logical::<f64>(monitor.scale_factor());
+            let origin = monitor.position().to_logical::<f64>(monitor.scale_factor());
+            builder = builder.position(origin.x + size.width - WIDTH - MARGIN, origin.y + MARGIN);
+        }
+
+        if let Err(e) = builder.build() {
+            eprintln!("❌ Failed to open focus timer: {}", e);
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("❌ Failed to open focus timer: {}", e);
+    }
+}
+
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        if let Err(e) = window.close() {
+            eprintln!("❌ Failed to close focus timer: {}", e);
+        }
+    }
+}
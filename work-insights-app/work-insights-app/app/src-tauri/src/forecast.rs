@@ -0,0 +1,119 @@
+// Workload forecast
+// Projects a week's active time per category from the weeks before it,
+// computed locally. Each day of the week is forecast as the average of the
+// same weekday over the last `HISTORY_WEEKS` weeks (a seasonal moving
+// average), so a light Friday stays light. Weeks before tracking began don't
+// count towards the average, or a new user's forecast would be diluted by
+// empty history.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Days, NaiveDate};
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+
+use crate::database::queries::{local_day_bounds, CategoryTotal, DATE_FORMAT};
+
+const HISTORY_WEEKS: u64 = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayForecast {
+    /// "YYYY-MM-DD"
+    pub date: String,
+    pub total_seconds: i64,
+    /// Largest first
+    pub categories: Vec<CategoryTotal>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Forecast {
+    pub week_start: String,
+    /// Weeks of history the forecast is based on, 0 when there is none
+    pub history_weeks: i64,
+    pub total_seconds: i64,
+    /// Largest first
+    pub category_totals: Vec<CategoryTotal>,
+    pub days: Vec<DayForecast>,
+}
+
+/// Forecast for the seven days starting on `week_start`
+pub fn get_forecast(conn: &Connection, week_start: NaiveDate) -> Result<Forecast> {
+    let history_start = week_start - Days::new(7 * HISTORY_WEEKS);
+    let (start_timestamp, _) = local_day_bounds(history_start);
+    let (_, end_timestamp) = local_day_bounds(week_start - Days::new(1));
+
+    let first_activity: Option<i64> = conn.query_row(
+        "SELECT MIN(timestamp) FROM activities WHERE timestamp BETWEEN ?1 AND ?2",
+        params![start_timestamp, end_timestamp],
+        |row| row.get(0),
+    )?;
+    // Full or partial weeks from the first recorded activity on
+    let history_weeks = first_activity
+        .map(|first| ((end_timestamp - first) / (7 * 86_400) + 1).min(HISTORY_WEEKS as i64))
+        .unwrap_or(0);
+
+    let mut stmt = conn.prepare(
+        "SELECT date(timestamp, 'unixepoch', 'localtime') AS day, COALESCE(category, 'Uncategorized'), SUM(duration_seconds)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         GROUP BY day, 2",
+    )?;
+    let rows = stmt
+        .query_map(params![start_timestamp, end_timestamp], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    // Seconds per weekday (Monday first) and category over the history
+    let mut by_weekday: Vec<HashMap<String, i64>> = vec![HashMap::new(); 7];
+    for (day, category, seconds) in rows {
+        let Ok(day) = NaiveDate::parse_from_str(&day, DATE_FORMAT) else { continue };
+        *by_weekday[day.weekday().num_days_from_monday() as usize].entry(category).or_default() += seconds;
+    }
+
+    let mut category_totals: BTreeMap<String, i64> = BTreeMap::new();
+    let days: Vec<DayForecast> = week_start
+        .iter_days()
+        .take(7)
+        .map(|day| {
+            let history = &by_weekday[day.weekday().num_days_from_monday() as usize];
+            let mut categories: Vec<CategoryTotal> = history
+                .iter()
+                .filter(|_| history_weeks > 0)
+                .map(|(category, seconds)| CategoryTotal {
+                    category: category.clone(),
+                    total_seconds: seconds / history_weeks,
+                })
+                .filter(|total| total.total_seconds > 0)
+                .collect();
+            sort_largest_first(&mut categories);
+            for total in &categories {
+                *category_totals.entry(total.category.clone()).or_default() += total.total_seconds;
+            }
+
+            DayForecast {
+                date: day.format(DATE_FORMAT).to_string(),
+                total_seconds: categories.iter().map(|total| total.total_seconds).sum(),
+                categories,
+            }
+        })
+        .collect();
+
+    let mut category_totals: Vec<CategoryTotal> = category_totals
+        .into_iter()
+        .map(|(category, total_seconds)| CategoryTotal { category, total_seconds })
+        .collect();
+    sort_largest_first(&mut category_totals);
+
+    Ok(Forecast {
+        week_start: week_start.format(DATE_FORMAT).to_string(),
+        history_weeks,
+        total_seconds: days.iter().map(|day| day.total_seconds).sum(),
+        category_totals,
+        days,
+    })
+}
+
+fn sort_largest_first(totals: &mut [CategoryTotal]) {
+    totals.sort_by(|a, b| b.total_seconds.cmp(&a.total_seconds).then_with(|| a.category.cmp(&b.category)));
+}
@@ -0,0 +1,236 @@
+// Launch friction
+// With `launch_friction_enabled` on, an app an enabled distraction budget
+// covers that takes focus is held behind a full-screen "Are you sure?"
+// prompt. Blocked apps aren't prompted for, since they are never tracked.
+// Going ahead is only possible once `launch_friction_seconds` have passed,
+// and until the prompt is answered the activity monitor records no window,
+// so none of the wait counts. Going ahead lets the app through for a while;
+// backing out closes the prompt and minimizes the app where the platform
+// allows it, and so does closing the prompt window.
+//
+// Every prompt and its answer is logged in `friction_prompts`, so
+// `get_stats` can show how often the user goes ahead or backs out per app.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use chrono::Utc;
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::activity::{self, ActivitySnapshot};
+use crate::database::{DbConnection, queries::get_setting};
+use crate::distractions;
+
+pub const FRICTION_EVENT: &str = "friction-prompt-changed";
+pub const WINDOW_LABEL: &str = "friction";
+
+const DEFAULT_DELAY_SECS: i64 = 10;
+/// How long an app stays let through after going ahead
+const ALLOW_SECS: i64 = 15 * 60;
+/// Time for focus to return to the app before it is minimized
+const BACK_OUT_SETTLE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrictionPrompt {
+    pub id: i64,
+    pub app_name: String,
+    pub shown_at: i64,
+    /// When going ahead becomes possible
+    pub ready_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrictionStats {
+    pub app_name: String,
+    pub prompts: i64,
+    pub proceeded: i64,
+    pub backed_out: i64,
+}
+
+struct State {
+    pending: Option<FrictionPrompt>,
+    /// Lowercased app names let through, and until when
+    allowed: Vec<(String, i64)>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { pending: None, allowed: Vec::new() });
+
+fn is_enabled(conn: &Connection) -> Result<bool> {
+    Ok(get_setting(conn, "launch_friction_enabled")?.is_some_and(|value| value == "true"))
+}
+
+fn delay_seconds(conn: &Connection) -> Result<i64> {
+    Ok(get_setting(conn, "launch_friction_seconds")?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_DELAY_SECS))
+}
+
+pub fn get_pending() -> Option<FrictionPrompt> {
+    STATE.lock().unwrap().pending.clone()
+}
+
+/// Whether the sample should be held back: a prompt is waiting for an
+/// answer, or `snapshot` is an app that needs one, in which case the prompt
+/// is opened. Called by the activity monitor on every sample.
+pub fn observe(app: &AppHandle, conn: &Connection, snapshot: Option<&ActivitySnapshot>, now: i64) -> Result<bool> {
+    let mut state = STATE.lock().unwrap();
+    if state.pending.is_some() {
+        return Ok(true);
+    }
+    let Some(snapshot) = snapshot else {
+        return Ok(false);
+    };
+    if !is_enabled(conn)? {
+        return Ok(false);
+    }
+
+    let app_key = snapshot.app_name.trim().to_lowercase();
+    state.allowed.retain(|(_, until)| *until > now);
+    if state.allowed.iter().any(|(allowed, _)| *allowed == app_key) {
+        return Ok(false);
+    }
+    if !distractions::is_distracting(conn, snapshot)? {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "INSERT INTO friction_prompts (app_name, shown_at) VALUES (?1, ?2)",
+        params![snapshot.app_name, now],
+    )?;
+    let prompt = FrictionPrompt {
+        id: conn.last_insert_rowid(),
+        app_name: snapshot.app_name.clone(),
+        shown_at: now,
+        ready_at: now + delay_seconds(conn)?,
+    };
+    println!("✅ Holding {} behind the launch friction prompt", prompt.app_name);
+    state.pending = Some(prompt.clone());
+    drop(state);
+
+    show(app);
+    if let Err(e) = app.emit(FRICTION_EVENT, Some(&prompt)) {
+        eprintln!("❌ Failed to emit friction prompt: {}", e);
+    }
+    Ok(true)
+}
+
+/// Answers the waiting prompt `prompt_id`: going ahead lets its app through,
+/// backing out minimizes it
+pub fn resolve(app: &AppHandle, db: &DbConnection, prompt_id: i64, proceed: bool) -> std::result::Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let mut state = STATE.lock().unwrap();
+    let prompt = match state.pending {
+        Some(ref prompt) if prompt.id == prompt_id => prompt.clone(),
+        _ => return Err("That prompt is no longer waiting".to_string()),
+    };
+    let now = Utc::now().timestamp();
+    if proceed && now < prompt.ready_at {
+        return Err(format!("Wait {} more seconds", prompt.ready_at - now));
+    }
+
+    conn.execute(
+        "UPDATE friction_prompts SET decided_at = ?1, proceeded = ?2 WHERE id = ?3",
+        params![now, proceed, prompt.id],
+    )
+    .map_err(|e| e.to_string())?;
+    state.pending = None;
+    if proceed {
+        state.allowed.push((prompt.app_name.trim().to_lowercase(), now + ALLOW_SECS));
+    }
+    drop(state);
+    drop(conn);
+    println!("✅ {} {}", if proceed { "Went ahead with" } else { "Backed out of" }, prompt.app_name);
+
+    hide(app);
+    if let Err(e) = app.emit(FRICTION_EVENT, None::<FrictionPrompt>) {
+        eprintln!("❌ Failed to emit friction prompt: {}", e);
+    }
+    if !proceed {
+        thread::spawn(move || {
+            thread::sleep(BACK_OUT_SETTLE);
+            let in_front = activity::get_current_activity()
+                .is_some_and(|snapshot| snapshot.app_name.trim().eq_ignore_ascii_case(prompt.app_name.trim()));
+            if in_front && !activity::minimize_foreground_window() {
+                println!("✅ Could not minimize {} on this platform", prompt.app_name);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Prompts per app shown within `start..end`, most first
+pub fn get_stats(conn: &Connection, start: i64, end: i64) -> Result<Vec<FrictionStats>> {
+    let mut stmt = conn.prepare(
+        "SELECT app_name, COUNT(*), COALESCE(SUM(proceeded = 1), 0), COALESCE(SUM(proceeded = 0), 0)
+         FROM friction_prompts
+         WHERE shown_at BETWEEN ?1 AND ?2
+         GROUP BY app_name COLLATE NOCASE
+         ORDER BY COUNT(*) DESC, app_name",
+    )?;
+    let stats = stmt
+        .query_map(params![start, end], |row| {
+            Ok(FrictionStats {
+                app_name: row.get(0)?,
+                prompts: row.get(1)?,
+                proceeded: row.get(2)?,
+                backed_out: row.get(3)?,
+            })
+        })?
+        .collect();
+    stats
+}
+
+fn show(app: &AppHandle) {
+    let handle = app.clone();
+    // Built on the event loop, like the focus timer overlay
+    let result = app.run_on_main_thread(move || {
+        if let Some(window) = handle.get_webview_window(WINDOW_LABEL) {
+            let _ = window.set_focus();
+            return;
+        }
+
+        let built = WebviewWindowBuilder::new(&handle, WINDOW_LABEL, WebviewUrl::App("index.html#friction".into()))
+            .title("Are you sure?")
+            .fullscreen(true)
+            .decorations(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(true)
+            .build();
+        match built {
+            Ok(window) => {
+                let app = handle.clone();
+                // Closing the prompt some other way counts as backing out
+                window.on_window_event(move |event| {
+                    if let WindowEvent::Destroyed = event {
+                        if let Some(prompt) = get_pending() {
+                            let db = app.state::<DbConnection>();
+                            if let Err(e) = resolve(&app, db.inner(), prompt.id, false) {
+                                eprintln!("❌ Failed to back out of friction prompt: {}", e);
+                            }
+                        }
+                    }
+                });
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to open friction prompt: {}", e);
+                // Nothing to answer, so don't hold the app back
+                STATE.lock().unwrap().pending = None;
+            }
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("❌ Failed to open friction prompt: {}", e);
+        STATE.lock().unwrap().pending = None;
+    }
+}
+
+fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        if let Err(e) = window.close() {
+            eprintln!("❌ Failed to close friction prompt: {}", e);
+        }
+    }
+}
@@ -0,0 +1,386 @@
+// Daily goals
+// Targets for a day's time, either overall active time or time in one
+// category, as a floor ("at least 5h active") or a ceiling ("at most 1h in
+// Entertainment"). Goals are kept as JSON in the `goals` setting, so they
+// travel with settings profiles.
+//
+// A background check looks at today's progress every minute and sends one
+// notification per goal and day: when a floor is met, or when a ceiling is
+// exceeded. Which goals have already notified is kept in the
+// `goals_notified` setting so a restart doesn't repeat them.
+//
+// Streaks count consecutive days on which every enabled goal held: floors
+// met and ceilings not exceeded. Each finished day is judged once, against
+// the goals in effect at its end, and kept in `goal_days`, so changing goals
+// or pruning old activities doesn't rewrite past streaks. The goals are
+// copied to `goal_history` whenever they are seen to have changed, at most a
+// minute after any change, and days from before the first copy aren't
+// judged since nothing says which goals applied then. Days without any
+// tracked activity, or without enabled goals, are skipped rather than
+// breaking a streak.
+
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Days, Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::{DbConnection, queries::{get_setting, local_day_bounds, set_setting, DATE_FORMAT}};
+use crate::notifications::{self, NotificationKind};
+
+pub const GOAL_REACHED_EVENT: &str = "goal-reached";
+
+const GOALS_KEY: &str = "goals";
+pub const NOTIFIED_KEY: &str = "goals_notified";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalMetric {
+    ActiveTime,
+    /// Active time in the goal's category
+    CategoryTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalComparison {
+    AtLeast,
+    AtMost,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: Option<i64>,
+    pub metric: GoalMetric,
+    /// Required for `CategoryTime`, matched ignoring case
+    pub category: Option<String>,
+    pub comparison: GoalComparison,
+    pub target_minutes: i64,
+    pub enabled: bool,
+}
+
+impl Goal {
+    fn label(&self) -> String {
+        let what = match (self.metric, self.category.as_deref()) {
+            (GoalMetric::CategoryTime, Some(category)) => format!("in {}", category),
+            _ => "active".to_string(),
+        };
+        let bound = match self.comparison {
+            GoalComparison::AtLeast => "At least",
+            GoalComparison::AtMost => "At most",
+        };
+        format!("{} {} {}", bound, format_minutes(self.target_minutes), what)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    InProgress,
+    /// An `AtLeast` goal reached its target
+    Met,
+    /// An `AtMost` goal went over its target
+    Exceeded,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    pub actual_seconds: i64,
+    pub target_seconds: i64,
+    /// Actual against target, may pass 1
+    pub ratio: f64,
+    pub status: GoalStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalStreaks {
+    /// Days in a row up to today, today included once its goals hold
+    pub current: i64,
+    pub best: i64,
+    /// Whether every enabled goal holds so far today
+    pub today_met: bool,
+    /// Most recent day whose goals all held, "YYYY-MM-DD"
+    pub last_met_date: Option<String>,
+}
+
+/// Goals already notified on `date`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NotifiedGoals {
+    date: String,
+    goal_ids: Vec<i64>,
+}
+
+pub fn get_goals(conn: &Connection) -> Result<Vec<Goal>> {
+    Ok(get_setting(conn, GOALS_KEY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_goal(conn: &Connection, goal: &Goal) -> std::result::Result<Goal, String> {
+    if goal.target_minutes <= 0 {
+        return Err("Target must be positive".to_string());
+    }
+    let category = goal.category.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if goal.metric == GoalMetric::CategoryTime && category.is_none() {
+        return Err("Category goals need a category".to_string());
+    }
+
+    let mut goals = get_goals(conn).map_err(|e| e.to_string())?;
+    let id = goal.id.unwrap_or_else(|| goals.iter().filter_map(|g| g.id).max().unwrap_or(0) + 1);
+    let saved = Goal {
+        id: Some(id),
+        category: category.filter(|_| goal.metric == GoalMetric::CategoryTime).map(str::to_string),
+        ..goal.clone()
+    };
+    match goals.iter_mut().find(|g| g.id == Some(id)) {
+        Some(existing) => *existing = saved.clone(),
+        None => goals.push(saved.clone()),
+    }
+
+    write_goals(conn, &goals).map_err(|e| e.to_string())?;
+    Ok(saved)
+}
+
+pub fn delete_goal(conn: &Connection, goal_id: i64) -> Result<()> {
+    let mut goals = get_goals(conn)?;
+    goals.retain(|goal| goal.id != Some(goal_id));
+    write_goals(conn, &goals)
+}
+
+fn write_goals(conn: &Connection, goals: &[Goal]) -> Result<()> {
+    let json = serde_json::to_string(goals).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, GOALS_KEY, &json)
+}
+
+/// Progress of every enabled goal on a local day
+pub fn get_progress(conn: &Connection, date: NaiveDate) -> Result<Vec<GoalProgress>> {
+    progress_toward(conn, date, get_goals(conn)?)
+}
+
+/// Progress of the enabled `goals` on a local day
+fn progress_toward(conn: &Connection, date: NaiveDate, goals: Vec<Goal>) -> Result<Vec<GoalProgress>> {
+    let (start, end) = local_day_bounds(date);
+
+    let mut progress = Vec::new();
+    for goal in goals.into_iter().filter(|goal| goal.enabled) {
+        let actual_seconds: i64 = conn.query_row(
+            "SELECT COALESCE(SUM(duration_seconds), 0)
+             FROM activities
+             WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+               AND (?3 IS NULL OR category = ?3 COLLATE NOCASE)",
+            params![start, end, goal.category.as_deref().filter(|_| goal.metric == GoalMetric::CategoryTime)],
+            |row| row.get(0),
+        )?;
+        let target_seconds = goal.target_minutes * 60;
+        let status = match goal.comparison {
+            GoalComparison::AtLeast if actual_seconds >= target_seconds => GoalStatus::Met,
+            GoalComparison::AtMost if actual_seconds > target_seconds => GoalStatus::Exceeded,
+            _ => GoalStatus::InProgress,
+        };
+        progress.push(GoalProgress {
+            goal,
+            actual_seconds,
+            target_seconds,
+            ratio: actual_seconds as f64 / target_seconds as f64,
+            status,
+        });
+    }
+
+    Ok(progress)
+}
+
+/// Whether the goal holds for the day so far: a floor met or a ceiling not
+/// exceeded
+fn held(progress: &GoalProgress) -> bool {
+    match progress.goal.comparison {
+        GoalComparison::AtLeast => progress.status == GoalStatus::Met,
+        GoalComparison::AtMost => progress.status != GoalStatus::Exceeded,
+    }
+}
+
+/// Copies the goals to `goal_history` if they changed since the last copy
+fn note_goals(conn: &Connection) -> Result<()> {
+    let current = serde_json::to_string(&get_goals(conn)?).unwrap_or_else(|_| "[]".to_string());
+    let last: Option<String> = conn
+        .query_row("SELECT goals FROM goal_history ORDER BY changed_at DESC, rowid DESC LIMIT 1", [], |row| row.get(0))
+        .optional()?;
+    if last.as_deref() != Some(current.as_str()) {
+        conn.execute(
+            "INSERT INTO goal_history (changed_at, goals) VALUES (?1, ?2)",
+            params![chrono::Utc::now().timestamp(), current],
+        )?;
+    }
+    Ok(())
+}
+
+/// The goals in effect at the end of `date`, if they were recorded by then
+fn goals_on(conn: &Connection, date: NaiveDate) -> Result<Option<Vec<Goal>>> {
+    let (_, end) = local_day_bounds(date);
+    let goals: Option<String> = conn
+        .query_row(
+            "SELECT goals FROM goal_history WHERE changed_at <= ?1 ORDER BY changed_at DESC, rowid DESC LIMIT 1",
+            [end],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(goals.map(|goals| serde_json::from_str(&goals).unwrap_or_default()))
+}
+
+/// Judges the finished days not judged yet, up to yesterday, and stores
+/// them in `goal_days`
+pub fn record_finished_days(conn: &Connection) -> Result<()> {
+    note_goals(conn)?;
+
+    let yesterday = Local::now().date_naive() - Days::new(1);
+    let last: Option<String> = conn
+        .query_row("SELECT MAX(date) FROM goal_days", [], |row| row.get(0))
+        .optional()?
+        .flatten();
+    let first = match last.and_then(|date| NaiveDate::parse_from_str(&date, DATE_FORMAT).ok()) {
+        Some(date) => date + Days::new(1),
+        None => {
+            let recorded: Option<i64> = conn.query_row("SELECT MIN(changed_at) FROM goal_history", [], |row| row.get(0))?;
+            let Some(recorded) = recorded.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)) else {
+                return Ok(());
+            };
+            recorded.with_timezone(&Local).date_naive()
+        }
+    };
+
+    let mut date = first;
+    while date <= yesterday {
+        let (start, end) = local_day_bounds(date);
+        let tracked: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM activities WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0)",
+            params![start, end],
+            |row| row.get(0),
+        )?;
+        let progress = match goals_on(conn, date)? {
+            Some(goals) if tracked => progress_toward(conn, date, goals)?,
+            _ => Vec::new(),
+        };
+        let goals = progress.len() as i64;
+        let met = progress.iter().filter(|p| held(p)).count() as i64;
+        conn.execute(
+            "INSERT OR REPLACE INTO goal_days (date, goals, met) VALUES (?1, ?2, ?3)",
+            params![date.format(DATE_FORMAT).to_string(), goals, met],
+        )?;
+        date = date + Days::new(1);
+    }
+    Ok(())
+}
+
+pub fn get_streaks(conn: &Connection) -> Result<GoalStreaks> {
+    record_finished_days(conn)?;
+
+    let mut stmt = conn.prepare("SELECT date, goals = met FROM goal_days WHERE goals > 0 ORDER BY date")?;
+    let days = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+
+    let (mut run, mut best) = (0, 0);
+    let mut last_met_date = None;
+    for (date, held) in days {
+        if held {
+            run += 1;
+            best = best.max(run);
+            last_met_date = Some(date);
+        } else {
+            run = 0;
+        }
+    }
+
+    let today = Local::now().date_naive();
+    let progress = get_progress(conn, today)?;
+    let today_met = !progress.is_empty() && progress.iter().all(held);
+    let current = if today_met { run + 1 } else { run };
+    if today_met {
+        last_met_date = Some(today.format(DATE_FORMAT).to_string());
+    }
+
+    Ok(GoalStreaks {
+        current,
+        best: best.max(current),
+        today_met,
+        last_met_date,
+    })
+}
+
+/// Checks today's goals every minute for the lifetime of the app
+pub fn start_checker(app: &AppHandle, db: &DbConnection) {
+    let app = app.clone();
+    let db = std::sync::Arc::clone(db);
+
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+
+        let reached = match db.lock() {
+            Ok(conn) => {
+                if let Err(e) = record_finished_days(&conn) {
+                    eprintln!("❌ Failed to record goal streak days: {}", e);
+                }
+                newly_reached(&conn).unwrap_or_else(|e| {
+                    eprintln!("❌ Failed to check goals: {}", e);
+                    Vec::new()
+                })
+            }
+            Err(_) => Vec::new(),
+        };
+        // Sent without the database lock, which `notify` takes itself
+        for progress in &reached {
+            notify(&app, progress);
+        }
+    });
+}
+
+/// Goals that reached their target today and haven't notified yet, marked
+/// as notified
+fn newly_reached(conn: &Connection) -> Result<Vec<GoalProgress>> {
+    let today = Local::now().date_naive();
+    let date = today.format(DATE_FORMAT).to_string();
+
+    let mut notified: NotifiedGoals = get_setting(conn, NOTIFIED_KEY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .filter(|notified: &NotifiedGoals| notified.date == date)
+        .unwrap_or(NotifiedGoals { date, goal_ids: Vec::new() });
+
+    let reached: Vec<GoalProgress> = get_progress(conn, today)?
+        .into_iter()
+        .filter(|progress| progress.status != GoalStatus::InProgress)
+        .filter(|progress| progress.goal.id.is_some_and(|id| !notified.goal_ids.contains(&id)))
+        .collect();
+    if !reached.is_empty() {
+        notified.goal_ids.extend(reached.iter().filter_map(|progress| progress.goal.id));
+        let json = serde_json::to_string(&notified).unwrap_or_default();
+        set_setting(conn, NOTIFIED_KEY, &json)?;
+    }
+
+    Ok(reached)
+}
+
+fn notify(app: &AppHandle, progress: &GoalProgress) {
+    if let Err(e) = app.emit(GOAL_REACHED_EVENT, progress) {
+        eprintln!("❌ Failed to emit goal event: {}", e);
+    }
+
+    let (title, body) = match progress.status {
+        GoalStatus::Exceeded => (
+            "Goal limit exceeded",
+            format!("{}: {} so far today", progress.goal.label(), format_minutes(progress.actual_seconds / 60)),
+        ),
+        _ => ("Goal met", format!("{} reached for today", progress.goal.label())),
+    };
+    notifications::notify(app, NotificationKind::GoalReached, title, &body);
+}
+
+fn format_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
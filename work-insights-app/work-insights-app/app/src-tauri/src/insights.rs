@@ -0,0 +1,528 @@
+// Weekly insights
+// Narratives are written by a language model the user opts into with
+// `llm_insights_enabled`: a local Ollama server or any OpenAI-compatible
+// endpoint, per `llm_provider`. They are generated from aggregates only
+// (category totals, time-of-day buckets and the week's trends); app names
+// and window titles never leave the machine. Category names are replaced by
+// their rating ("productive category 1") unless `llm_share_category_names`
+// is on, since a category can name a client or project, and day notes are
+// only sent when `llm_include_day_notes` is on.
+//
+// Trends are computed locally: how a week's active time, focus time and
+// context switching compare with the week before, and which apps rose or
+// fell the most. Focus time is the time in deep work sessions, as in the
+// productivity score and the weekly report. Trends are cached in
+// `insights_cache` next to the week's narrative together with a fingerprint
+// of what they were computed from; a cached entry is used as long as no
+// activity in either week was added, removed or edited in any way, no
+// category was changed (deep work depends on their ratings) and the deep
+// work settings are the same.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{Days, Local, NaiveDate, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::categories;
+use crate::database::day_notes::{self, DayNote};
+use crate::database::queries::{self, get_category_totals, get_setting, local_day_bounds, CategoryTotal, DATE_FORMAT};
+use crate::deep_work;
+
+const WEEK_SECONDS: i64 = 7 * 24 * 60 * 60;
+const OLLAMA_DEFAULT_ENDPOINT: &str = "http://localhost:11434";
+/// Changes smaller than this share of the previous week count as steady
+const STEADY_CHANGE: f64 = 0.1;
+/// Apps listed as rising or falling, each way
+const APP_TRENDS: usize = 5;
+/// Apps whose time changed by less than this aren't listed
+const MIN_APP_CHANGE_SECONDS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartOfDayTotals {
+    pub morning_seconds: i64,
+    pub afternoon_seconds: i64,
+    pub evening_seconds: i64,
+    pub night_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyAggregate {
+    pub week_start: i64,
+    pub active_seconds: i64,
+    pub idle_seconds: i64,
+    pub previous_week_active_seconds: i64,
+    pub daily_active_seconds: Vec<i64>,
+    pub part_of_day: PartOfDayTotals,
+    pub categories: Vec<CategoryTotal>,
+    /// The user's notes for the days of the week
+    pub notes: Vec<DayNote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NarrativeInsights {
+    pub week_start: i64,
+    pub narrative: String,
+    pub generated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LlmProvider {
+    /// `/chat/completions` on the endpoint, with an optional bearer key
+    OpenAiCompatible,
+    /// Ollama's own `/api/chat`
+    Ollama,
+}
+
+pub struct LlmConfig {
+    pub provider: LlmProvider,
+    pub endpoint: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    /// Whether day notes are sent along; they are the user's own words
+    /// rather than aggregates, so this is a separate opt-in
+    pub include_day_notes: bool,
+    /// Whether category names are sent as they are
+    pub share_category_names: bool,
+}
+
+pub fn compute_weekly_aggregate(conn: &Connection, week_start: i64) -> Result<WeeklyAggregate> {
+    let week_end = week_start + WEEK_SECONDS - 1;
+
+    let (active_seconds, idle_seconds) = conn.query_row(
+        "SELECT
+            COALESCE(SUM(CASE WHEN is_idle = 0 THEN duration_seconds ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN is_idle = 1 THEN duration_seconds ELSE 0 END), 0)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2",
+        params![week_start, week_end],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+    )?;
+
+    let previous_week_active_seconds = conn.query_row(
+        "SELECT COALESCE(SUM(duration_seconds), 0)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0",
+        params![week_start - WEEK_SECONDS, week_start - 1],
+        |row| row.get::<_, i64>(0),
+    )?;
+
+    let mut daily_active_seconds = vec![0; 7];
+    let mut stmt = conn.prepare(
+        "SELECT (timestamp - ?1) / 86400 AS day, SUM(duration_seconds)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         GROUP BY day",
+    )?;
+    let rows = stmt.query_map(params![week_start, week_end], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+    })?;
+    for row in rows {
+        let (day, seconds) = row?;
+        if let Some(slot) = daily_active_seconds.get_mut(day as usize) {
+            *slot = seconds;
+        }
+    }
+
+    let part_of_day = conn.query_row(
+        "SELECT
+            COALESCE(SUM(CASE WHEN hour BETWEEN 5 AND 11 THEN duration_seconds ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN hour BETWEEN 12 AND 16 THEN duration_seconds ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN hour BETWEEN 17 AND 21 THEN duration_seconds ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN hour >= 22 OR hour < 5 THEN duration_seconds ELSE 0 END), 0)
+         FROM (
+            SELECT CAST(strftime('%H', timestamp, 'unixepoch', 'localtime') AS INTEGER) AS hour,
+                   duration_seconds
+            FROM activities
+            WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         )",
+        params![week_start, week_end],
+        |row| {
+            Ok(PartOfDayTotals {
+                morning_seconds: row.get(0)?,
+                afternoon_seconds: row.get(1)?,
+                evening_seconds: row.get(2)?,
+                night_seconds: row.get(3)?,
+            })
+        },
+    )?;
+
+    let notes = match Local.timestamp_opt(week_start, 0).single() {
+        Some(start) => {
+            let first_day = start.date_naive();
+            day_notes::get_notes(conn, first_day, first_day + chrono::Days::new(6))?
+        }
+        None => Vec::new(),
+    };
+
+    Ok(WeeklyAggregate {
+        week_start,
+        active_seconds,
+        idle_seconds,
+        previous_week_active_seconds,
+        daily_active_seconds,
+        part_of_day,
+        categories: get_category_totals(conn, week_start, week_end)?,
+        notes,
+    })
+}
+
+/// Reads the LLM settings, refusing unless the user explicitly opted in.
+pub fn load_llm_config(conn: &Connection) -> std::result::Result<LlmConfig, String> {
+    let setting = |key: &str| -> std::result::Result<Option<String>, String> {
+        get_setting(conn, key)
+            .map(|v| v.filter(|s| !s.is_empty()))
+            .map_err(|e| e.to_string())
+    };
+
+    if setting("llm_insights_enabled")?.as_deref() != Some("true") {
+        return Err("LLM insights are disabled in settings".to_string());
+    }
+
+    let provider = match setting("llm_provider")?.as_deref() {
+        Some("ollama") => LlmProvider::Ollama,
+        _ => LlmProvider::OpenAiCompatible,
+    };
+    let endpoint = match (setting("llm_endpoint")?, provider) {
+        (Some(endpoint), _) => endpoint,
+        (None, LlmProvider::Ollama) => OLLAMA_DEFAULT_ENDPOINT.to_string(),
+        (None, LlmProvider::OpenAiCompatible) => return Err("No LLM endpoint configured".to_string()),
+    };
+
+    Ok(LlmConfig {
+        provider,
+        endpoint,
+        model: setting("llm_model")?
+            .ok_or_else(|| "No LLM model configured".to_string())?,
+        api_key: setting("llm_api_key")?,
+        include_day_notes: setting("llm_include_day_notes")?.as_deref() == Some("true"),
+        share_category_names: setting("llm_share_category_names")?.as_deref() == Some("true"),
+    })
+}
+
+/// Replaces category names with their rating and rank, e.g. "productive
+/// category 2" for the second largest productive category of the week
+pub fn anonymize_categories(conn: &Connection, aggregate: &mut WeeklyAggregate) -> Result<()> {
+    let ratings: HashMap<String, categories::Rating> = categories::list_categories(conn)?
+        .into_iter()
+        .map(|category| (category.name.to_lowercase(), category.rating))
+        .collect();
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for total in &mut aggregate.categories {
+        if total.category == "Uncategorized" {
+            continue;
+        }
+        let rating = ratings.get(&total.category.to_lowercase()).map_or("unrated", |rating| rating.as_str());
+        let count = counts.entry(rating).or_default();
+        *count += 1;
+        total.category = format!("{} category {}", rating, count);
+    }
+    Ok(())
+}
+
+/// The prompt for `aggregate`, with the week's `trends` less the app lists
+pub fn build_prompt(aggregate: &WeeklyAggregate, trends: &WeekTrends, include_day_notes: bool) -> String {
+    let mut aggregate = aggregate.clone();
+    if !include_day_notes {
+        aggregate.notes.clear();
+    }
+    let notes_hint = if aggregate.notes.is_empty() {
+        ""
+    } else {
+        " The notes are the person's own remarks about individual days; use them \
+         to explain patterns where they fit."
+    };
+
+    let stats = serde_json::json!({
+        "week": aggregate,
+        "trends": {
+            "active_seconds": trends.active_seconds,
+            "focus_seconds": trends.focus_seconds,
+            "context_switches_per_hour": trends.context_switches_per_hour,
+        },
+    });
+    format!(
+        "You are summarizing one week of a person's computer usage for their own \
+         private dashboard. Using only the aggregate statistics below (all durations \
+         in seconds, days start at week_start; trends compare the week with the \
+         previous one, focus being time in deep work sessions: stretches of work in \
+         productive categories with only short interruptions), write two to four \
+         short, neutral, non-judgmental sentences about notable patterns, such as \
+         when focused work happened or how the week compared to the previous one. \
+         Do not invent data.{}\n\n{}",
+        notes_hint,
+        serde_json::to_string_pretty(&stats).unwrap_or_default()
+    )
+}
+
+/// Asks the configured provider's chat endpoint for the narrative
+pub async fn request_narrative(config: &LlmConfig, prompt: String) -> std::result::Result<String, String> {
+    let endpoint = config.endpoint.trim_end_matches('/');
+    let (url, body) = match config.provider {
+        LlmProvider::OpenAiCompatible => (
+            format!("{}/chat/completions", endpoint),
+            serde_json::json!({
+                "model": config.model,
+                "messages": [{ "role": "user", "content": prompt }],
+                "temperature": 0.4,
+            }),
+        ),
+        LlmProvider::Ollama => (
+            format!("{}/api/chat", endpoint),
+            serde_json::json!({
+                "model": config.model,
+                "messages": [{ "role": "user", "content": prompt }],
+                "stream": false,
+                "options": { "temperature": 0.4 },
+            }),
+        ),
+    };
+
+    let mut request = reqwest::Client::new()
+        .post(&url)
+        .timeout(Duration::from_secs(60))
+        .json(&body);
+    if let Some(ref key) = config.api_key {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request.send().await.map_err(|e| format!("LLM request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("LLM returned HTTP {}: {}", status, text));
+    }
+
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
+
+    let content = match config.provider {
+        LlmProvider::OpenAiCompatible => &json["choices"][0]["message"]["content"],
+        LlmProvider::Ollama => &json["message"]["content"],
+    };
+    content
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .ok_or_else(|| "LLM response did not contain a message".to_string())
+}
+
+pub fn store_narrative(
+    conn: &Connection,
+    aggregate: &WeeklyAggregate,
+    narrative: &str,
+) -> Result<NarrativeInsights> {
+    let insights = NarrativeInsights {
+        week_start: aggregate.week_start,
+        narrative: narrative.to_string(),
+        generated_at: Utc::now().timestamp(),
+    };
+
+    store_cached(
+        conn,
+        insights.week_start,
+        &[
+            ("narrative", serde_json::json!(insights.narrative)),
+            ("aggregate", serde_json::json!(aggregate)),
+        ],
+    )?;
+
+    Ok(insights)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendDirection {
+    Rising,
+    Falling,
+    Steady,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricTrend {
+    pub current: f64,
+    pub previous: f64,
+    /// Relative to the previous week, e.g. 0.25 for a quarter more; `None`
+    /// when the previous week had nothing to compare with
+    pub change: Option<f64>,
+    pub direction: TrendDirection,
+}
+
+impl MetricTrend {
+    fn new(current: f64, previous: f64) -> Self {
+        let change = (previous > 0.0).then(|| (current - previous) / previous);
+        let direction = match change {
+            Some(change) if change >= STEADY_CHANGE => TrendDirection::Rising,
+            Some(change) if change <= -STEADY_CHANGE => TrendDirection::Falling,
+            Some(_) => TrendDirection::Steady,
+            None if current > 0.0 => TrendDirection::Rising,
+            None => TrendDirection::Steady,
+        };
+        Self { current, previous, change, direction }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppTrend {
+    pub app_name: String,
+    pub seconds: i64,
+    pub previous_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekTrends {
+    /// "YYYY-MM-DD"
+    pub week_start: String,
+    pub active_seconds: MetricTrend,
+    /// Time in deep work sessions
+    pub focus_seconds: MetricTrend,
+    /// App switches per active hour
+    pub context_switches_per_hour: MetricTrend,
+    /// Apps with the most time gained over the previous week, largest first
+    pub rising_apps: Vec<AppTrend>,
+    /// Apps with the most time lost, largest first
+    pub falling_apps: Vec<AppTrend>,
+    pub generated_at: i64,
+}
+
+/// Trends for the week starting on `week_start`, from the cache unless the
+/// activities of the week or the one before, the categories or the deep work
+/// settings changed since
+pub fn get_week_trends(conn: &Connection, week_start: NaiveDate) -> Result<WeekTrends> {
+    let (key, _) = local_day_bounds(week_start);
+    let (range_start, _) = local_day_bounds(week_start - Days::new(7));
+    let (_, range_end) = local_day_bounds(week_start + Days::new(6));
+    let fingerprint = activities_fingerprint(conn, range_start, range_end)?;
+
+    let cached: Option<String> = conn
+        .query_row("SELECT insights_json FROM insights_cache WHERE week_start = ?1", params![key], |row| row.get(0))
+        .optional()?;
+    let cached = cached
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .filter(|cached| cached["trends_fingerprint"] == fingerprint.as_str())
+        .and_then(|cached| serde_json::from_value::<WeekTrends>(cached["trends"].clone()).ok());
+    if let Some(trends) = cached {
+        return Ok(trends);
+    }
+
+    let trends = compute_trends(conn, week_start)?;
+    store_cached(
+        conn,
+        key,
+        &[
+            ("trends", serde_json::json!(trends)),
+            ("trends_fingerprint", serde_json::json!(fingerprint)),
+        ],
+    )?;
+    Ok(trends)
+}
+
+fn compute_trends(conn: &Connection, week_start: NaiveDate) -> Result<WeekTrends> {
+    let previous_start = week_start - Days::new(7);
+    let week = queries::get_weekly_stats(conn, week_start)?;
+    let previous = queries::get_weekly_stats(conn, previous_start)?;
+
+    let (start, _) = local_day_bounds(week_start);
+    let (previous_start_timestamp, _) = local_day_bounds(previous_start);
+    let (_, end) = local_day_bounds(week_start + Days::new(6));
+    let focus = deep_work::deep_work_seconds(conn, week_start, week_start + Days::new(6))?;
+    let previous_focus = deep_work::deep_work_seconds(conn, previous_start, week_start - Days::new(1))?;
+
+    let per_hour = |switches: i64, active_seconds: i64| {
+        if active_seconds > 0 {
+            switches as f64 / (active_seconds as f64 / 3600.0)
+        } else {
+            0.0
+        }
+    };
+
+    let current_apps = app_totals(conn, start, end)?;
+    let mut previous_apps = app_totals(conn, previous_start_timestamp, start - 1)?;
+    let mut apps: Vec<AppTrend> = current_apps
+        .into_iter()
+        .map(|(app_name, seconds)| {
+            let previous_seconds = previous_apps.remove(&app_name).unwrap_or(0);
+            AppTrend { app_name, seconds, previous_seconds }
+        })
+        .collect();
+    // Apps not used at all this week
+    apps.extend(previous_apps.into_iter().map(|(app_name, previous_seconds)| AppTrend {
+        app_name,
+        seconds: 0,
+        previous_seconds,
+    }));
+    apps.retain(|app| (app.seconds - app.previous_seconds).abs() >= MIN_APP_CHANGE_SECONDS);
+    apps.sort_by(|a, b| {
+        (b.seconds - b.previous_seconds)
+            .cmp(&(a.seconds - a.previous_seconds))
+            .then_with(|| a.app_name.cmp(&b.app_name))
+    });
+    let rising_apps: Vec<AppTrend> = apps.iter().filter(|app| app.seconds > app.previous_seconds).take(APP_TRENDS).cloned().collect();
+    let falling_apps: Vec<AppTrend> = apps.iter().rev().filter(|app| app.seconds < app.previous_seconds).take(APP_TRENDS).cloned().collect();
+
+    Ok(WeekTrends {
+        week_start: week_start.format(DATE_FORMAT).to_string(),
+        active_seconds: MetricTrend::new(week.active_time_seconds as f64, previous.active_time_seconds as f64),
+        focus_seconds: MetricTrend::new(focus as f64, previous_focus as f64),
+        context_switches_per_hour: MetricTrend::new(
+            per_hour(week.context_switches, week.active_time_seconds),
+            per_hour(previous.context_switches, previous.active_time_seconds),
+        ),
+        rising_apps,
+        falling_apps,
+        generated_at: Utc::now().timestamp(),
+    })
+}
+
+fn app_totals(conn: &Connection, start: i64, end: i64) -> Result<HashMap<String, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT app_name, SUM(duration_seconds)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2 AND is_idle = 0
+         GROUP BY app_name",
+    )?;
+    let totals = stmt
+        .query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>>>()?;
+    Ok(totals)
+}
+
+/// Changes whenever an activity in the range is recorded, imported, deleted
+/// or edited, a category is changed or the deep work settings are
+fn activities_fingerprint(conn: &Connection, start: i64, end: i64) -> Result<String> {
+    let activities: String = conn.query_row(
+        "SELECT COUNT(*), COALESCE(MAX(id), 0), COALESCE(MAX(data_version), 0)
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2",
+        params![start, end],
+        |row| Ok(format!("{}:{}:{}", row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+    )?;
+    let categories: i64 = conn
+        .query_row("SELECT version FROM data_versions WHERE name = 'categories'", [], |row| row.get(0))
+        .optional()?
+        .unwrap_or(0);
+    let deep_work = deep_work::get_settings(conn)?;
+    Ok(format!("{}:{}:{}:{}", activities, categories, deep_work.min_minutes, deep_work.max_gap_minutes))
+}
+
+/// Sets `entries` in the week's cached JSON, keeping what else is there
+fn store_cached(conn: &Connection, week_start: i64, entries: &[(&str, serde_json::Value)]) -> Result<()> {
+    let existing: Option<String> = conn
+        .query_row("SELECT insights_json FROM insights_cache WHERE week_start = ?1", params![week_start], |row| row.get(0))
+        .optional()?;
+    let mut cached = existing
+        .and_then(|json| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json).ok())
+        .unwrap_or_default();
+    for (key, value) in entries {
+        cached.insert(key.to_string(), value.clone());
+    }
+
+    conn.execute(
+        "INSERT OR REPLACE INTO insights_cache (week_start, insights_json, generated_at)
+         VALUES (?1, ?2, ?3)",
+        params![week_start, serde_json::Value::Object(cached).to_string(), Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
@@ -1,13 +1,42 @@
 mod database;
 mod activity;
 mod commands;
+mod focus;
+mod focus_timer;
+mod friction;
+mod forecast;
+mod breaks;
+mod goals;
+mod api;
+mod insights;
+mod alerts;
+mod blocking;
+mod categories;
+mod deep_work;
+mod distractions;
+mod export;
+mod notifications;
+mod pause;
+mod presentation;
+mod privacy;
+mod productivity;
+mod profile;
+mod projects;
+mod ranges;
+mod redaction;
+mod report;
+mod retention;
+mod settings;
+mod switching;
+mod timers;
+mod tray;
 
 use std::sync::{Arc, Mutex};
 use activity::monitor::ActivityMonitor;
 use commands::AppState;
 use tauri::Manager;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{TrayIconBuilder, TrayIconEvent},
 };
 
@@ -15,6 +44,8 @@ use tauri::{
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--minimized"]),
@@ -27,10 +58,10 @@ pub fn run() {
             std::fs::create_dir_all(&app_data_dir).expect("Failed to create app data directory");
             
             let db_path = app_data_dir.join("work_insights.db");
-            let db = database::initialize_database(db_path).expect("Failed to initialize database");
+            let db = database::initialize_database(db_path.clone()).expect("Failed to initialize database");
             
             // Initialize activity monitor
-            let monitor = Arc::new(Mutex::new(ActivityMonitor::new(Arc::clone(&db))));
+            let monitor = Arc::new(Mutex::new(ActivityMonitor::new(Arc::clone(&db), app.handle().clone())));
             
             // Auto-start tracking if enabled
             let should_auto_start = {
@@ -43,14 +74,30 @@ pub fn run() {
                 }
             };
 
-            if should_auto_start {
+            // A pause that outlived the last run keeps tracking off until it ends
+            let paused = pause::restore(app.handle(), &db, &monitor);
+
+            if should_auto_start && !paused {
                 if let Ok(mon) = monitor.lock() {
                     mon.start();
                 }
             }
             
+            // Restore any timed focus session and the timer hotkeys, close a
+            // timer left running, start checking goals and pruning old data,
+            // and expose the local API if enabled
+            focus::resume_expiry(app.handle(), &db);
+            timers::register_saved(app.handle(), &db);
+            timers::close_stale(&db);
+            goals::start_checker(app.handle(), &db);
+            retention::start(app.handle(), &db);
+            database::storage::start_sync(&db);
+            api::start_if_enabled(&db, Arc::clone(&monitor));
+            let tooltip = db.lock().map(|conn| tray::tooltip(&conn)).unwrap_or_default();
+
             let app_state = AppState {
                 monitor: Arc::clone(&monitor),
+                db_path,
             };
 
             app.manage(db);
@@ -58,12 +105,22 @@ pub fn run() {
 
             // Create system tray menu
             let show_i = MenuItem::with_id(app, "show", "Show Dashboard", true, None::<&str>)?;
+            let pause_items = tray::PAUSE_MINUTES
+                .iter()
+                .map(|minutes| MenuItem::with_id(app, format!("pause_{}", minutes), format!("{} min", minutes), true, None::<&str>))
+                .collect::<tauri::Result<Vec<_>>>()?;
+            let pause_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> =
+                pause_items.iter().map(|item| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>).collect();
+            let pause_i = Submenu::with_items(app, "Pause tracking", true, &pause_refs)?;
+            let resume_i = MenuItem::with_id(app, "resume", "Resume tracking", true, None::<&str>)?;
+            let separator = PredefinedMenuItem::separator(app)?;
             let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
+            let menu = Menu::with_items(app, &[&show_i, &separator, &pause_i, &resume_i, &separator, &quit_i])?;
 
             // Build system tray
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(tray::TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
+                .tooltip(tooltip)
                 .menu(&menu)
                 .show_menu_on_left_click(false)
                 .on_menu_event(|app, event| match event.id.as_ref() {
@@ -76,7 +133,12 @@ pub fn run() {
                     "quit" => {
                         app.exit(0);
                     }
-                    _ => {}
+                    "resume" => tray::resume_tracking(app),
+                    id => {
+                        if let Some(minutes) = id.strip_prefix("pause_").and_then(|m| m.parse().ok()) {
+                            tray::pause_tracking(app, minutes);
+                        }
+                    }
                 })
                 .on_tray_icon_event(|tray, event| {
                     if let TrayIconEvent::Click { button: tauri::tray::MouseButton::Left, .. } = event {
@@ -92,8 +154,8 @@ pub fn run() {
             // Handle window close event - minimize to tray instead of closing
             if let Some(window) = app.get_webview_window("main") {
                 let app_handle = app.handle().clone();
-                window.on_window_event(move |event| {
-                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                window.on_window_event(move |event| match event {
+                    tauri::WindowEvent::CloseRequested { api, .. } => {
                         // Prevent the window from closing
                         api.prevent_close();
                         // Hide the window instead
@@ -101,6 +163,9 @@ pub fn run() {
                             let _ = win.hide();
                         }
                     }
+                    // Only happens as the app goes down
+                    tauri::WindowEvent::Destroyed => flush_activity(&app_handle),
+                    _ => {}
                 });
             }
             
@@ -110,11 +175,142 @@ pub fn run() {
             commands::start_tracking,
             commands::stop_tracking,
             commands::get_tracking_status,
+            commands::pause_tracking,
+            commands::get_pause_status,
+            commands::check_window_title_access,
             commands::get_today_statistics,
+            commands::get_current_segment,
+            commands::get_hourly_statistics,
+            commands::get_weekly_stats,
+            commands::get_monthly_stats,
+            commands::list_range_presets,
+            commands::resolve_range_preset,
+            commands::save_range_preset,
+            commands::delete_range_preset,
+            commands::get_forecast,
+            commands::get_top_apps,
+            commands::get_activity_heatmap,
+            commands::get_switching_stats,
             commands::get_activities,
+            commands::add_manual_activity,
+            commands::get_untracked_gaps,
+            commands::annotate_gap,
+            commands::delete_gap_annotation,
+            commands::update_activity,
+            commands::delete_activity,
+            commands::merge_activities,
             commands::get_current_time,
             commands::get_activity_count,
+            commands::start_focus_session,
+            commands::end_focus_session,
+            commands::set_focus_timer_overlay,
+            commands::hide_focus_timer,
+            commands::get_friction_prompt,
+            commands::resolve_friction_prompt,
+            commands::get_friction_stats,
+            commands::start_timer,
+            commands::stop_timer,
+            commands::get_running_timer,
+            commands::get_timer_hotkeys,
+            commands::save_timer_hotkeys,
+            commands::get_goals,
+            commands::save_goal,
+            commands::delete_goal,
+            commands::get_goal_progress,
+            commands::get_streaks,
+            commands::get_distraction_budgets,
+            commands::save_distraction_budget,
+            commands::delete_distraction_budget,
+            commands::get_distraction_usage,
+            commands::get_focus_sessions,
+            commands::get_deep_work_settings,
+            commands::save_deep_work_settings,
+            commands::get_blocking_settings,
+            commands::save_blocking_settings,
+            commands::get_private_apps,
+            commands::save_private_apps,
+            commands::get_privacy_mode,
+            commands::set_privacy_mode,
+            commands::get_local_api_info,
+            commands::local_api_request,
+            commands::regenerate_local_api_token,
+            commands::get_redaction_rules,
+            commands::save_redaction_rule,
+            commands::delete_redaction_rule,
+            commands::test_redaction_rule,
+            commands::get_break_settings,
+            commands::save_break_settings,
+            commands::snooze_break_reminder,
+            commands::generate_narrative_insights,
+            commands::get_week_insights,
+            commands::generate_weekly_report,
+            commands::get_productivity_score,
+            commands::get_day_note,
+            commands::list_day_notes,
+            commands::save_day_note,
+            commands::delete_day_note,
+            commands::run_readonly_query,
+            commands::get_streak_alert_rules,
+            commands::save_streak_alert_rule,
+            commands::delete_streak_alert_rule,
+            commands::snooze_streak_alert,
+            commands::get_streak_alert_log,
+            commands::export_raw_snapshots,
+            commands::export_activities_csv,
+            commands::export_data_json,
+            commands::import_data_json,
+            commands::get_storage_status,
+            commands::migrate_to_server,
+            commands::rebuild_activities,
+            commands::set_sampling_interval,
+            commands::list_categories,
+            commands::create_category,
+            commands::rename_category,
+            commands::update_category,
+            commands::delete_category,
+            commands::get_category_rules,
+            commands::save_category_rule,
+            commands::delete_category_rule,
+            commands::recategorize_activities,
+            commands::list_projects,
+            commands::create_project,
+            commands::rename_project,
+            commands::update_project,
+            commands::delete_project,
+            commands::get_project_time,
+            commands::get_project_rules,
+            commands::save_project_rule,
+            commands::delete_project_rule,
+            commands::detect_projects,
+            commands::get_setting,
+            commands::get_all_settings,
+            commands::set_setting,
+            commands::get_notification_settings,
+            commands::save_notification_settings,
+            commands::snooze_all,
+            commands::resume_notifications,
+            commands::get_suppressed_notifications,
+            commands::get_presentation_status,
+            commands::export_settings,
+            commands::import_settings,
+            commands::compact_activities,
+            commands::check_database,
+            commands::repair_database,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Record the activity still in progress, which would otherwise
+            // only be written at the next focus change
+            if let tauri::RunEvent::Exit = event {
+                flush_activity(app);
+            }
+        });
+}
+
+/// Stops the monitor, which records the activity in progress up to now
+fn flush_activity(app: &tauri::AppHandle) {
+    if let Ok(monitor) = app.state::<AppState>().monitor.lock() {
+        monitor.stop();
+    }
 }
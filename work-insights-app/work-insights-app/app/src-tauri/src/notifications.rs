@@ -0,0 +1,221 @@
+// Notification manager
+// Every system notification goes through `notify`, which holds it back
+// during quiet hours, while notifications are snoozed, while the user is
+// presenting, or when the user has turned its kind off. Only the popup is
+// suppressed: features still emit their in-app events, and held-back
+// notifications are logged so they can be reviewed afterwards.
+
+use chrono::{Local, NaiveTime, Utc};
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::database::{DbConnection, queries::{get_setting, set_setting}};
+use crate::presentation;
+
+const QUIET_START_KEY: &str = "quiet_hours_start";
+const QUIET_END_KEY: &str = "quiet_hours_end";
+const SNOOZED_UNTIL_KEY: &str = "notifications_snoozed_until";
+const DISABLED_KINDS_KEY: &str = "notifications_disabled";
+const TIME_FORMAT: &str = "%H:%M";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    StreakAlert,
+    GoalReached,
+    BreakReminder,
+    DistractionAlert,
+    TimerSwitched,
+}
+
+impl NotificationKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotificationKind::StreakAlert => "streak_alert",
+            NotificationKind::GoalReached => "goal_reached",
+            NotificationKind::BreakReminder => "break_reminder",
+            NotificationKind::DistractionAlert => "distraction_alert",
+            NotificationKind::TimerSwitched => "timer_switched",
+        }
+    }
+}
+
+/// Why a notification was held back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuppressionReason {
+    Presenting,
+    Snoozed,
+    QuietHours,
+    Disabled,
+}
+
+impl SuppressionReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            SuppressionReason::Presenting => "presenting",
+            SuppressionReason::Snoozed => "snoozed",
+            SuppressionReason::QuietHours => "quiet_hours",
+            SuppressionReason::Disabled => "disabled",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [Self::Presenting, Self::Snoozed, Self::QuietHours, Self::Disabled]
+            .into_iter()
+            .find(|reason| reason.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuppressedNotification {
+    pub id: i64,
+    /// Stored as text, so kinds from other versions still load
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub reason: Option<SuppressionReason>,
+    pub suppressed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Local "HH:MM" times; the range may wrap past midnight. Quiet hours
+    /// are off unless both are set.
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    pub snoozed_until: Option<i64>,
+    pub disabled_kinds: Vec<NotificationKind>,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<NotificationSettings> {
+    let text = |key| -> Result<Option<String>> { Ok(get_setting(conn, key)?.filter(|v| !v.trim().is_empty())) };
+
+    Ok(NotificationSettings {
+        quiet_hours_start: text(QUIET_START_KEY)?,
+        quiet_hours_end: text(QUIET_END_KEY)?,
+        snoozed_until: text(SNOOZED_UNTIL_KEY)?.and_then(|v| v.parse().ok()),
+        disabled_kinds: text(DISABLED_KINDS_KEY)?
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default(),
+    })
+}
+
+/// Saves quiet hours and the disabled kinds. The snooze is left alone; see
+/// `snooze_all`.
+pub fn save_settings(conn: &Connection, settings: &NotificationSettings) -> std::result::Result<(), String> {
+    for time in [&settings.quiet_hours_start, &settings.quiet_hours_end].into_iter().flatten() {
+        parse_time(time).ok_or_else(|| format!("Invalid time \"{}\", expected HH:MM", time))?;
+    }
+    let disabled = serde_json::to_string(&settings.disabled_kinds).map_err(|e| e.to_string())?;
+
+    let save = |key, value: &str| set_setting(conn, key, value).map_err(|e| e.to_string());
+    save(QUIET_START_KEY, settings.quiet_hours_start.as_deref().unwrap_or(""))?;
+    save(QUIET_END_KEY, settings.quiet_hours_end.as_deref().unwrap_or(""))?;
+    save(DISABLED_KINDS_KEY, &disabled)
+}
+
+/// Holds back all notifications for `minutes`. Returns when the snooze ends.
+pub fn snooze_all(conn: &Connection, minutes: i64) -> Result<i64> {
+    let until = Utc::now().timestamp() + minutes * 60;
+    set_setting(conn, SNOOZED_UNTIL_KEY, &until.to_string())?;
+    Ok(until)
+}
+
+/// Lifts a snooze early
+pub fn resume(conn: &Connection) -> Result<()> {
+    set_setting(conn, SNOOZED_UNTIL_KEY, "")
+}
+
+/// Why a notification of `kind` can't be shown right now, if it can't
+pub fn suppression(conn: &Connection, kind: NotificationKind) -> Result<Option<SuppressionReason>> {
+    let settings = get_settings(conn)?;
+    let now = Utc::now().timestamp();
+
+    let quiet = match (settings.quiet_hours_start.as_deref(), settings.quiet_hours_end.as_deref()) {
+        (Some(start), Some(end)) => in_quiet_hours(start, end, Local::now().time()),
+        _ => false,
+    };
+    Ok(if presentation::is_presenting() {
+        Some(SuppressionReason::Presenting)
+    } else if settings.snoozed_until.is_some_and(|until| until > now) {
+        Some(SuppressionReason::Snoozed)
+    } else if quiet {
+        Some(SuppressionReason::QuietHours)
+    } else if settings.disabled_kinds.contains(&kind) {
+        Some(SuppressionReason::Disabled)
+    } else {
+        None
+    })
+}
+
+/// Shows a system notification unless the settings hold it back, in which
+/// case it is logged instead
+pub fn notify(app: &AppHandle, kind: NotificationKind, title: &str, body: &str) {
+    if let Some(db) = app.try_state::<DbConnection>() {
+        if let Ok(conn) = db.lock() {
+            // Better an unwanted popup than a silently lost one
+            if let Ok(Some(reason)) = suppression(&conn, kind) {
+                println!("✅ Held back {:?} notification ({:?})", kind, reason);
+                if let Err(e) = log_suppressed(&conn, kind, title, body, reason) {
+                    eprintln!("❌ Failed to log held back notification: {}", e);
+                }
+                return;
+            }
+        }
+    }
+
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("❌ Failed to show {:?} notification: {}", kind, e);
+    }
+}
+
+fn log_suppressed(conn: &Connection, kind: NotificationKind, title: &str, body: &str, reason: SuppressionReason) -> Result<()> {
+    conn.execute(
+        "INSERT INTO suppressed_notifications (kind, title, body, reason, suppressed_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![kind.as_str(), title, body, reason.as_str(), Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+/// Held-back notifications, newest first
+pub fn get_suppressed(conn: &Connection, limit: i64) -> Result<Vec<SuppressedNotification>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, title, body, reason, suppressed_at
+         FROM suppressed_notifications
+         ORDER BY suppressed_at DESC, id DESC
+         LIMIT ?1",
+    )?;
+
+    let notifications = stmt
+        .query_map(params![limit], |row| {
+            Ok(SuppressedNotification {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                title: row.get(2)?,
+                body: row.get(3)?,
+                reason: SuppressionReason::parse(&row.get::<_, String>(4)?),
+                suppressed_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(notifications)
+}
+
+fn in_quiet_hours(start: &str, end: &str, now: NaiveTime) -> bool {
+    let (Some(start), Some(end)) = (parse_time(start), parse_time(end)) else {
+        return false;
+    };
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), TIME_FORMAT).ok()
+}
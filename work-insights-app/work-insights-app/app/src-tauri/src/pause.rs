@@ -0,0 +1,138 @@
+// Pausing tracking
+// "Pause 15/30/60 min" in the tray stops the activity monitor and starts it
+// again once the time is up. The end of the pause is kept in the
+// `tracking_paused_until` setting, so an app restarted meanwhile stays paused
+// instead of auto-starting tracking, and resumes on time. Starting or
+// stopping tracking by hand cancels the pause.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use chrono::Utc;
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::monitor::ActivityMonitor;
+use crate::database::{DbConnection, queries::{get_setting, set_setting}};
+use crate::tray;
+
+pub const PAUSED_UNTIL_KEY: &str = "tracking_paused_until";
+pub const PAUSE_EVENT: &str = "tracking-pause-changed";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PauseStatus {
+    pub paused: bool,
+    pub paused_until: Option<i64>,
+}
+
+/// When the current pause ends, if tracking is paused
+pub fn paused_until(conn: &Connection) -> Result<Option<i64>> {
+    let now = Utc::now().timestamp();
+    Ok(get_setting(conn, PAUSED_UNTIL_KEY)?
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|until| *until > now))
+}
+
+pub fn get_status(conn: &Connection) -> Result<PauseStatus> {
+    let paused_until = paused_until(conn)?;
+    Ok(PauseStatus { paused: paused_until.is_some(), paused_until })
+}
+
+/// Stops tracking now and schedules it to start again in `minutes`
+pub fn pause(
+    app: &AppHandle,
+    db: &DbConnection,
+    monitor: &Arc<Mutex<ActivityMonitor>>,
+    minutes: i64,
+) -> std::result::Result<PauseStatus, String> {
+    if minutes <= 0 {
+        return Err("Pause duration must be positive".to_string());
+    }
+
+    monitor.lock().map_err(|e| e.to_string())?.stop();
+    let until = Utc::now().timestamp() + minutes * 60;
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        set_setting(&conn, PAUSED_UNTIL_KEY, &until.to_string()).map_err(|e| e.to_string())?;
+    }
+    println!("✅ Tracking paused for {} minutes", minutes);
+
+    schedule_resume(app, db, monitor, until);
+    notify(app, PauseStatus { paused: true, paused_until: Some(until) });
+    Ok(PauseStatus { paused: true, paused_until: Some(until) })
+}
+
+/// Forgets a pause, so its scheduled resume does nothing. Call when tracking
+/// is started or stopped by hand.
+pub fn cancel(app: &AppHandle, db: &DbConnection) -> std::result::Result<(), String> {
+    let was_paused = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let was_paused = paused_until(&conn).map_err(|e| e.to_string())?.is_some();
+        set_setting(&conn, PAUSED_UNTIL_KEY, "").map_err(|e| e.to_string())?;
+        was_paused
+    };
+    if was_paused {
+        notify(app, PauseStatus { paused: false, paused_until: None });
+    }
+    Ok(())
+}
+
+/// Re-arms the resume for a pause that survived a restart. Returns whether
+/// tracking is paused, in which case it must not be auto-started.
+pub fn restore(app: &AppHandle, db: &DbConnection, monitor: &Arc<Mutex<ActivityMonitor>>) -> bool {
+    let until = db.lock().ok().and_then(|conn| paused_until(&conn).ok().flatten());
+    match until {
+        Some(until) => {
+            println!("✅ Tracking stays paused until {}", until);
+            schedule_resume(app, db, monitor, until);
+            true
+        }
+        None => false,
+    }
+}
+
+fn schedule_resume(app: &AppHandle, db: &DbConnection, monitor: &Arc<Mutex<ActivityMonitor>>, until: i64) {
+    let app = app.clone();
+    let db = Arc::clone(db);
+    let monitor = Arc::clone(monitor);
+
+    thread::spawn(move || {
+        let wait = (until - Utc::now().timestamp()).max(0) as u64;
+        thread::sleep(Duration::from_secs(wait));
+
+        // Only resume for the pause we were scheduled for; it may have been
+        // cancelled or replaced by a longer one in the meantime
+        let still_current = match db.lock() {
+            Ok(conn) => {
+                let stored = get_setting(&conn, PAUSED_UNTIL_KEY)
+                    .ok()
+                    .flatten()
+                    .and_then(|v| v.trim().parse::<i64>().ok());
+                let matches = stored == Some(until);
+                if matches {
+                    let _ = set_setting(&conn, PAUSED_UNTIL_KEY, "");
+                }
+                matches
+            }
+            Err(_) => false,
+        };
+        if !still_current {
+            return;
+        }
+
+        if let Ok(monitor) = monitor.lock() {
+            monitor.start();
+        }
+        println!("✅ Tracking resumed after pause");
+        notify(&app, PauseStatus { paused: false, paused_until: None });
+    });
+}
+
+/// Tells the frontend and brings the tray tooltip up to date
+fn notify(app: &AppHandle, status: PauseStatus) {
+    if let Err(e) = app.emit(PAUSE_EVENT, status) {
+        eprintln!("❌ Failed to emit pause change: {}", e);
+    }
+    tray::refresh(app);
+}
@@ -0,0 +1,83 @@
+// Presentation detection
+// Notices when the user is presenting or sharing their screen, so that
+// notifications are held back and the focus timer overlay is hidden until
+// they're done. Windows reports presentation mode directly; on every
+// platform the foreground window is also matched against the
+// `presentation_apps` setting. Evaluated by the activity monitor on every
+// sample.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::activity::{is_os_presenting, ActivitySnapshot};
+use crate::database::queries::get_setting;
+use crate::focus_timer;
+
+pub const PRESENTATION_EVENT: &str = "presentation-mode-changed";
+
+/// Presenting only ends once nothing has looked like it for this long, so
+/// glancing at notes in another window doesn't bring popups back mid-talk
+const GRACE_SECS: i64 = 120;
+
+/// When the current presentation started, or 0 when not presenting
+static PRESENTING_SINCE: AtomicI64 = AtomicI64::new(0);
+/// When the last sample looked like presenting
+static LAST_SEEN: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PresentationStatus {
+    pub presenting: bool,
+    pub since: Option<i64>,
+}
+
+pub fn status() -> PresentationStatus {
+    let since = PRESENTING_SINCE.load(Ordering::Relaxed);
+    PresentationStatus { presenting: since != 0, since: (since != 0).then_some(since) }
+}
+
+pub fn is_presenting() -> bool {
+    PRESENTING_SINCE.load(Ordering::Relaxed) != 0
+}
+
+/// Updates the presenting state with the latest sample, hides or restores
+/// the focus timer overlay and tells the UI when presenting starts or ends
+pub fn update(app: &AppHandle, conn: &Connection, snapshot: Option<&ActivitySnapshot>, now: i64) -> Result<()> {
+    let patterns = presentation_apps(conn)?;
+    let looks_like_presenting = is_os_presenting()
+        || snapshot.is_some_and(|snapshot| {
+            let app_name = snapshot.app_name.to_lowercase();
+            let window_title = snapshot.window_title.to_lowercase();
+            patterns.iter().any(|pattern| app_name.contains(pattern) || window_title.contains(pattern))
+        });
+
+    if looks_like_presenting {
+        LAST_SEEN.store(now, Ordering::Relaxed);
+    }
+    let was_presenting = is_presenting();
+    let presenting = looks_like_presenting || (was_presenting && now - LAST_SEEN.load(Ordering::Relaxed) < GRACE_SECS);
+    if presenting == was_presenting {
+        return Ok(());
+    }
+
+    PRESENTING_SINCE.store(if presenting { now } else { 0 }, Ordering::Relaxed);
+    println!("✅ Presentation {}", if presenting { "started" } else { "ended" });
+    focus_timer::sync_locked(app, conn);
+    if let Err(e) = app.emit(PRESENTATION_EVENT, status()) {
+        eprintln!("❌ Failed to emit presentation change: {}", e);
+    }
+    Ok(())
+}
+
+/// The `presentation_apps` setting: case-insensitive substrings of the app
+/// name or window title seen while presenting
+fn presentation_apps(conn: &Connection) -> Result<Vec<String>> {
+    Ok(get_setting(conn, "presentation_apps")?
+        .and_then(|value| serde_json::from_str::<Vec<String>>(&value).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pattern| pattern.trim().to_lowercase())
+        .filter(|pattern| !pattern.is_empty())
+        .collect())
+}
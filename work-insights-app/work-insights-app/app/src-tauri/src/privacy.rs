@@ -0,0 +1,136 @@
+// Private apps and privacy mode
+// Apps listed in the `private_apps` setting, such as password managers,
+// banking apps or a personal browser, are tracked as time only: the monitor
+// replaces each sample of one with a "Private" app without a window title
+// before anything is stored, so neither raw snapshots nor activities hold the
+// real name or title. Names match the app name exactly, ignoring case.
+//
+// Privacy mode does the same for every app's window title while it is on,
+// keeping app names so time per app, categories by app and switching stats
+// still work. Titles are either left out or replaced by a salted hash, which
+// keeps windows of the same app apart without revealing what they showed.
+// The salt is made per install, so hashes can't be matched against a list of
+// likely titles computed elsewhere. Category rules that match on titles
+// don't apply to what is recorded meanwhile.
+
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::activity::ActivitySnapshot;
+use crate::database::queries::{get_setting, set_setting};
+
+/// App name recorded in place of a private app
+pub const PRIVATE_APP_NAME: &str = "Private";
+
+const PRIVATE_APPS_KEY: &str = "private_apps";
+const MODE_KEY: &str = "privacy_mode";
+pub const HASH_SALT_KEY: &str = "privacy_hash_salt";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyMode {
+    /// Window titles are stored as seen
+    #[default]
+    Off,
+    /// Window titles are stored empty
+    Omit,
+    /// Window titles are stored as a salted hash
+    Hash,
+}
+
+impl PrivacyMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            PrivacyMode::Off => "off",
+            PrivacyMode::Omit => "omit",
+            PrivacyMode::Hash => "hash",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "omit" => PrivacyMode::Omit,
+            "hash" => PrivacyMode::Hash,
+            _ => PrivacyMode::Off,
+        }
+    }
+}
+
+pub fn get_private_apps(conn: &Connection) -> Result<Vec<String>> {
+    Ok(get_setting(conn, PRIVATE_APPS_KEY)?
+        .and_then(|value| serde_json::from_str::<Vec<String>>(&value).ok())
+        .unwrap_or_default())
+}
+
+/// Saves the list without blanks or duplicates and returns what was saved
+pub fn save_private_apps(conn: &Connection, apps: &[String]) -> Result<Vec<String>> {
+    let mut saved: Vec<String> = Vec::new();
+    for app in apps.iter().map(|app| app.trim()).filter(|app| !app.is_empty()) {
+        if !saved.iter().any(|existing| existing.eq_ignore_ascii_case(app)) {
+            saved.push(app.to_string());
+        }
+    }
+
+    let json = serde_json::to_string(&saved).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, PRIVATE_APPS_KEY, &json)?;
+    Ok(saved)
+}
+
+pub fn is_private(private_apps: &[String], app_name: &str) -> bool {
+    private_apps.iter().any(|private| private.trim().eq_ignore_ascii_case(app_name.trim()))
+}
+
+pub fn get_mode(conn: &Connection) -> Result<PrivacyMode> {
+    Ok(get_setting(conn, MODE_KEY)?.map_or(PrivacyMode::Off, |value| PrivacyMode::parse(value.trim())))
+}
+
+pub fn set_mode(conn: &Connection, mode: PrivacyMode) -> Result<()> {
+    set_setting(conn, MODE_KEY, mode.as_str())
+}
+
+/// The sample as the monitor should store it: a private app masked, and any
+/// other app's title dropped or hashed in privacy mode
+pub fn mask(conn: &Connection, snapshot: Option<ActivitySnapshot>) -> Result<Option<ActivitySnapshot>> {
+    let Some(snapshot) = snapshot else {
+        return Ok(None);
+    };
+    if is_private(&get_private_apps(conn)?, &snapshot.app_name) {
+        return Ok(Some(ActivitySnapshot {
+            app_name: PRIVATE_APP_NAME.to_string(),
+            window_title: String::new(),
+            timestamp: snapshot.timestamp,
+        }));
+    }
+
+    let window_title = match get_mode(conn)? {
+        PrivacyMode::Off => return Ok(Some(snapshot)),
+        PrivacyMode::Omit => String::new(),
+        PrivacyMode::Hash if snapshot.window_title.is_empty() => String::new(),
+        PrivacyMode::Hash => hash_title(&hash_salt(conn)?, &snapshot.window_title),
+    };
+    Ok(Some(ActivitySnapshot { window_title, ..snapshot }))
+}
+
+/// "#" and the first 16 hex digits of SHA-256 over salt and title
+fn hash_title(salt: &str, title: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt.as_bytes())
+        .chain_update([0])
+        .chain_update(title.as_bytes())
+        .finalize();
+    let hex: String = digest.iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+    format!("#{}", hex)
+}
+
+/// This install's salt, made on first use from the OS's random number
+/// generator
+fn hash_salt(conn: &Connection) -> Result<String> {
+    if let Some(salt) = get_setting(conn, HASH_SALT_KEY)?.filter(|salt| !salt.is_empty()) {
+        return Ok(salt);
+    }
+    let salt = Uuid::new_v4().simple().to_string();
+    set_setting(conn, HASH_SALT_KEY, &salt)?;
+    Ok(salt)
+}
@@ -0,0 +1,121 @@
+// Daily productivity score
+// Boils a day down to one 0-100 number for the dashboard headline. Four
+// components, each scored 0-1, are combined by the weights in the
+// `productivity_weights` setting:
+//   active_time       active time against a full working day
+//   category          share of active time in productive categories, with
+//                     neutral and uncategorized time counting half
+//   context_switches  fewer app switches per active hour is better
+//   deep_work         time in deep work sessions (see deep_work.rs) against
+//                     a target
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::database::queries::{count_context_switches, get_setting, local_day_bounds, DATE_FORMAT};
+use crate::deep_work;
+
+const FULL_DAY_ACTIVE_SECONDS: i64 = 6 * 3600;
+const DEEP_WORK_TARGET_SECONDS: i64 = 2 * 3600;
+/// Switching this often per active hour scores zero
+const MAX_SWITCHES_PER_HOUR: f64 = 30.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreWeights {
+    pub active_time: f64,
+    pub category: f64,
+    pub context_switches: f64,
+    pub deep_work: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self { active_time: 25.0, category: 35.0, context_switches: 15.0, deep_work: 25.0 }
+    }
+}
+
+/// Each component scored from 0 to 1
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreComponents {
+    pub active_time: f64,
+    pub category: f64,
+    pub context_switches: f64,
+    pub deep_work: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductivityScore {
+    pub date: String,
+    pub score: u8,
+    pub active_seconds: i64,
+    pub productive_seconds: i64,
+    pub distracting_seconds: i64,
+    pub deep_work_seconds: i64,
+    pub context_switches: i64,
+    pub components: ScoreComponents,
+    pub weights: ScoreWeights,
+}
+
+/// The `productivity_weights` setting. Weights are relative, so they need
+/// not add up to 100.
+pub fn load_weights(conn: &Connection) -> Result<ScoreWeights> {
+    Ok(get_setting(conn, "productivity_weights")?
+        .and_then(|value| serde_json::from_str::<ScoreWeights>(&value).ok())
+        .filter(|w| {
+            let all = [w.active_time, w.category, w.context_switches, w.deep_work];
+            all.iter().all(|weight| *weight >= 0.0) && all.iter().sum::<f64>() > 0.0
+        })
+        .unwrap_or_default())
+}
+
+pub fn compute_score(conn: &Connection, date: NaiveDate) -> Result<ProductivityScore> {
+    let (start, end) = local_day_bounds(date);
+    let weights = load_weights(conn)?;
+
+    let (active_seconds, productive_seconds, distracting_seconds) = conn.query_row(
+        "SELECT
+            COALESCE(SUM(a.duration_seconds), 0),
+            COALESCE(SUM(CASE WHEN c.rating = 'productive' THEN a.duration_seconds ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN c.rating = 'distracting' THEN a.duration_seconds ELSE 0 END), 0)
+         FROM activities a
+         LEFT JOIN categories c ON c.name = a.category COLLATE NOCASE
+         WHERE a.timestamp BETWEEN ?1 AND ?2 AND a.is_idle = 0",
+        params![start, end],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+    )?;
+    let context_switches = count_context_switches(conn, start, end)?;
+    let deep_work_seconds = deep_work::deep_work_seconds(conn, date, date)?;
+
+    let ratio = |part: i64, whole: i64| if whole > 0 { (part as f64 / whole as f64).clamp(0.0, 1.0) } else { 0.0 };
+    let neutral_seconds = active_seconds - productive_seconds - distracting_seconds;
+    let active_hours = active_seconds as f64 / 3600.0;
+    let components = ScoreComponents {
+        active_time: ratio(active_seconds, FULL_DAY_ACTIVE_SECONDS),
+        category: ratio(productive_seconds * 2 + neutral_seconds, active_seconds * 2),
+        context_switches: if active_seconds > 0 {
+            (1.0 - context_switches as f64 / active_hours / MAX_SWITCHES_PER_HOUR).clamp(0.0, 1.0)
+        } else {
+            0.0
+        },
+        deep_work: ratio(deep_work_seconds, DEEP_WORK_TARGET_SECONDS),
+    };
+
+    let total_weight = weights.active_time + weights.category + weights.context_switches + weights.deep_work;
+    let weighted = components.active_time * weights.active_time
+        + components.category * weights.category
+        + components.context_switches * weights.context_switches
+        + components.deep_work * weights.deep_work;
+
+    Ok(ProductivityScore {
+        date: date.format(DATE_FORMAT).to_string(),
+        score: (weighted / total_weight * 100.0).round() as u8,
+        active_seconds,
+        productive_seconds,
+        distracting_seconds,
+        deep_work_seconds,
+        context_switches,
+        components,
+        weights,
+    })
+}
@@ -0,0 +1,195 @@
+// Settings profiles
+// A user's configuration as one JSON file, so it can move to another machine
+// without the activity history: settings (daily goals included), blocked
+// apps, categories with their rules, and app streak alert rules. Secrets and
+// per-machine state (the running focus session, snoozes, the LLM API key,
+// the local API token, timer hotkeys and the running timer) stay behind.
+//
+// Importing merges categories by name and streak rules by pattern, and
+// replaces the category rules outright since their order decides which one
+// matches. Activities recorded before the import keep their categories until
+// `recategorize_activities` is run.
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::{self, StreakAlertRule};
+use crate::api;
+use crate::blocking::BLOCKED_APPS_KEY;
+use crate::breaks;
+use crate::distractions;
+use crate::categories::{self, CategoryRule, Rating};
+use crate::database::queries::{get_all_settings, set_setting};
+use crate::database::storage;
+use crate::goals;
+use crate::pause;
+use crate::privacy;
+use crate::retention;
+use crate::settings;
+use crate::timers;
+
+pub const PROFILE_VERSION: u32 = 1;
+
+/// Settings that belong to this machine or are secret
+const EXCLUDED_SETTINGS: [&str; 15] = [
+    "focus_session",
+    "notifications_snoozed_until",
+    goals::NOTIFIED_KEY,
+    distractions::NOTIFIED_KEY,
+    breaks::SNOOZED_UNTIL_KEY,
+    pause::PAUSED_UNTIL_KEY,
+    privacy::HASH_SALT_KEY,
+    "llm_api_key",
+    storage::URL_KEY,
+    storage::SYNCED_THROUGH_KEY,
+    BLOCKED_APPS_KEY,
+    timers::HOTKEYS_KEY,
+    timers::RUNNING_KEY,
+    api::TOKEN_KEY,
+    retention::DEFAULT_CLEARED_KEY,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileCategory {
+    pub name: String,
+    pub color: String,
+    pub rating: Rating,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsProfile {
+    pub version: u32,
+    pub exported_at: i64,
+    pub settings: BTreeMap<String, String>,
+    #[serde(default)]
+    pub blocked_apps: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<ProfileCategory>,
+    #[serde(default)]
+    pub category_rules: Vec<CategoryRule>,
+    #[serde(default)]
+    pub streak_rules: Vec<StreakAlertRule>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileImportResult {
+    pub settings: usize,
+    pub blocked_apps: usize,
+    pub categories: usize,
+    pub category_rules: usize,
+    pub streak_rules: usize,
+}
+
+pub fn export_profile(conn: &Connection) -> Result<SettingsProfile> {
+    let all_settings = get_all_settings(conn)?;
+
+    let blocked_apps = all_settings
+        .get(BLOCKED_APPS_KEY)
+        .and_then(|value| serde_json::from_str(value).ok())
+        .unwrap_or_default();
+    let settings = all_settings
+        .into_iter()
+        .filter(|(key, _)| !EXCLUDED_SETTINGS.contains(&key.as_str()))
+        .collect();
+
+    let categories = categories::list_categories(conn)?
+        .into_iter()
+        .map(|category| ProfileCategory { name: category.name, color: category.color, rating: category.rating })
+        .collect();
+    let category_rules = categories::get_rules(conn)?
+        .into_iter()
+        .map(|rule| CategoryRule { id: None, ..rule })
+        .collect();
+    let streak_rules = alerts::get_rules(conn)?
+        .into_iter()
+        .map(|rule| StreakAlertRule { id: None, snoozed_until: None, ..rule })
+        .collect();
+
+    Ok(SettingsProfile {
+        version: PROFILE_VERSION,
+        exported_at: Utc::now().timestamp(),
+        settings,
+        blocked_apps,
+        categories,
+        category_rules,
+        streak_rules,
+    })
+}
+
+/// Applies a profile in one transaction, so a profile that fails halfway
+/// leaves the configuration as it was
+pub fn import_profile(conn: &mut Connection, profile: &SettingsProfile) -> std::result::Result<ProfileImportResult, String> {
+    let profile = check_profile(profile)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let result = apply_profile(&tx, &profile).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// The profile with its settings as `settings::validate` stores them, or
+/// why it can't be imported
+pub fn check_profile(profile: &SettingsProfile) -> std::result::Result<SettingsProfile, String> {
+    if profile.version > PROFILE_VERSION {
+        return Err(format!(
+            "Profile version {} is newer than this app supports ({}); update the app first",
+            profile.version, PROFILE_VERSION
+        ));
+    }
+    let stored = profile
+        .settings
+        .iter()
+        .filter(|(key, _)| !EXCLUDED_SETTINGS.contains(&key.as_str()))
+        .map(|(key, value)| Ok((key.clone(), settings::validate(key, value)?)))
+        .collect::<std::result::Result<BTreeMap<_, _>, String>>()?;
+    if let Some(rule) = profile.category_rules.iter().find(|rule| rule.category.trim().is_empty() || rule.pattern.trim().is_empty()) {
+        return Err(format!("Category rule \"{}\" needs a category and a pattern", rule.pattern));
+    }
+    Ok(SettingsProfile { settings: stored, ..profile.clone() })
+}
+
+/// Applies a profile `check_profile` returned, within the caller's transaction
+pub fn apply_profile(conn: &Connection, profile: &SettingsProfile) -> Result<ProfileImportResult> {
+    for (key, value) in &profile.settings {
+        set_setting(conn, key, value)?;
+    }
+    let blocked_apps = serde_json::to_string(&profile.blocked_apps).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, BLOCKED_APPS_KEY, &blocked_apps)?;
+
+    let existing = categories::list_categories(conn)?;
+    for category in &profile.categories {
+        match existing.iter().find(|c| c.name.eq_ignore_ascii_case(category.name.trim())) {
+            Some(current) => {
+                categories::update_category(conn, current.id, &category.color, category.rating)?;
+            }
+            None => {
+                categories::create_category(conn, &category.name, &category.color, category.rating)?;
+            }
+        }
+    }
+
+    conn.execute("DELETE FROM category_rules", [])?;
+    for rule in &profile.category_rules {
+        categories::save_rule(conn, &CategoryRule { id: None, ..rule.clone() })?;
+    }
+
+    let existing = alerts::get_rules(conn)?;
+    for rule in &profile.streak_rules {
+        let id = existing
+            .iter()
+            .find(|r| r.pattern.eq_ignore_ascii_case(rule.pattern.trim()))
+            .and_then(|r| r.id);
+        alerts::save_rule(conn, &StreakAlertRule { id, snoozed_until: None, ..rule.clone() })?;
+    }
+
+    Ok(ProfileImportResult {
+        settings: profile.settings.len(),
+        blocked_apps: profile.blocked_apps.len(),
+        categories: profile.categories.len(),
+        category_rules: profile.category_rules.len(),
+        streak_rules: profile.streak_rules.len(),
+    })
+}
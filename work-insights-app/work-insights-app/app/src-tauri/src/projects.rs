@@ -0,0 +1,514 @@
+// Project detection
+// Each tracked activity is assigned the project it was spent on, so time per
+// project can be reported without tagging anything by hand. User-defined
+// rules come first: a regular expression matched against the window title
+// or app name, naming the project directly or through `$1`-style group
+// references, such as `(?i)jira.*\b([A-Z]+)-\d+` → `$1`. Rules are tried in
+// the order they were added and kept as JSON in the `project_rules` setting.
+//
+// When no rule matches and `project_detection_enabled` is on, the window is
+// recognized instead, by the app's exact executable or application name:
+// - VS Code and its forks show the open folder in the title, as in
+//   "main.rs - crate - Visual Studio Code"
+// - JetBrains IDEs start the title with the project, as in "crate – main.rs"
+// - Terminals often show the working directory, as in "me@host: ~/src/crate";
+//   a directory inside a git repository on this machine counts as the
+//   repository's project
+//
+// Applied as activities are inserted, like category rules, and `detect`
+// re-applies the current rules and heuristics to stored activities. Idle
+// time has no project, and titles recorded in privacy mode can't be matched.
+//
+// Every project an activity is tagged with is registered in `projects`,
+// where it can be given a color and an hourly rate for `get_project_time`
+// to bill against. Activities and rules refer to projects by name, so
+// renaming one carries them along.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::categories::MatchField;
+use crate::database::queries::{get_setting, set_setting, Activity};
+
+const RULES_KEY: &str = "project_rules";
+const DETECTION_KEY: &str = "project_detection_enabled";
+
+// App names as compared by `app_key`
+const EDITOR_APPS: [&str; 11] = [
+    "code", "code - insiders", "code-insiders", "code-oss", "visual studio code", "vscodium", "codium", "cursor",
+    "windsurf", "positron", "visual studio code - insiders",
+];
+/// The end of titles naming VS Code or a fork of it
+const EDITOR_TITLES: [&str; 5] = ["visual studio code", "vscodium", "cursor", "windsurf", "positron"];
+const JETBRAINS_APPS: [&str; 15] = [
+    "intellij idea", "intellij", "idea", "pycharm", "webstorm", "clion", "goland", "rider", "rustrover", "phpstorm",
+    "rubymine", "datagrip", "android studio", "studio", "aqua",
+];
+const TERMINAL_APPS: [&str; 26] = [
+    "terminal", "windowsterminal", "iterm", "iterm2", "konsole", "alacritty", "kitty", "wezterm", "wezterm-gui",
+    "ghostty", "tilix", "terminator", "xterm", "foot", "footclient", "hyper", "warp", "powershell", "pwsh", "cmd",
+    "mintty", "gnome-terminal", "gnome-terminal-server", "xfce4-terminal", "kgx", "ptyxis",
+];
+/// Editions and the like following a JetBrains IDE's name
+const EDITION_SUFFIXES: [&str; 10] = [
+    " community edition", " professional edition", " ultimate edition", " community", " ultimate", "-community",
+    "-professional", "-ultimate", "-ce", " ce",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectRule {
+    pub id: Option<i64>,
+    /// Regular expression; prefix with `(?i)` to ignore case
+    pub pattern: String,
+    /// Project name, which may refer to groups of the pattern as `$1`
+    pub project: String,
+    pub match_field: MatchField,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DetectResult {
+    pub activities_scanned: usize,
+    pub activities_changed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Project {
+    pub id: i64,
+    pub name: String,
+    /// CSS color; `None` leaves it to the dashboard
+    pub color: Option<String>,
+    /// Billed per hour of active time, in the user's currency
+    pub hourly_rate: Option<f64>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectTime {
+    pub project: String,
+    pub color: Option<String>,
+    pub total_seconds: i64,
+    pub activity_count: i64,
+    /// Local days with any time on the project
+    pub days: i64,
+    /// Fraction of all active time in the range, 0-1
+    pub share: f64,
+    /// The time at the project's hourly rate, if it has one
+    pub billable_amount: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectTimeReport {
+    /// Largest first
+    pub projects: Vec<ProjectTime>,
+    /// Active time not tagged with any project
+    pub unassigned_seconds: i64,
+}
+
+pub fn list_projects(conn: &Connection) -> Result<Vec<Project>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, color, hourly_rate, created_at FROM projects ORDER BY name COLLATE NOCASE",
+    )?;
+    let projects = stmt.query_map([], row_to_project)?.collect::<Result<Vec<_>>>()?;
+    Ok(projects)
+}
+
+pub fn get_project(conn: &Connection, project_id: i64) -> Result<Option<Project>> {
+    conn.query_row(
+        "SELECT id, name, color, hourly_rate, created_at FROM projects WHERE id = ?1",
+        params![project_id],
+        row_to_project,
+    )
+    .optional()
+}
+
+/// Adds a project unless one of that name, ignoring case, exists
+pub fn register(conn: &Connection, name: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO projects (name, created_at) VALUES (?1, ?2)",
+        params![name.trim(), Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+/// Fails with a constraint violation if the name is taken, ignoring case
+pub fn create_project(conn: &Connection, name: &str, color: Option<&str>, hourly_rate: Option<f64>) -> Result<Project> {
+    let color = color.map(str::trim).filter(|color| !color.is_empty());
+    let created_at = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO projects (name, color, hourly_rate, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![name.trim(), color, hourly_rate, created_at],
+    )?;
+    Ok(Project {
+        id: conn.last_insert_rowid(),
+        name: name.trim().to_string(),
+        color: color.map(String::from),
+        hourly_rate,
+        created_at,
+    })
+}
+
+/// Renames a project along with its activities and the rules naming it
+/// outright
+pub fn rename_project(conn: &mut Connection, project_id: i64, name: &str) -> Result<Option<Project>> {
+    let Some(project) = get_project(conn, project_id)? else {
+        return Ok(None);
+    };
+    let name = name.trim();
+
+    let tx = conn.transaction()?;
+    tx.execute("UPDATE projects SET name = ?2 WHERE id = ?1", params![project_id, name])?;
+    tx.execute("UPDATE activities SET project = ?2 WHERE project = ?1 COLLATE NOCASE", params![project.name, name])?;
+    let mut rules = get_rules(&tx)?;
+    for rule in rules.iter_mut().filter(|rule| rule.project.eq_ignore_ascii_case(&project.name)) {
+        rule.project = name.to_string();
+    }
+    write_rules(&tx, &rules)?;
+    tx.commit()?;
+
+    Ok(Some(Project { name: name.to_string(), ..project }))
+}
+
+pub fn update_project(
+    conn: &Connection,
+    project_id: i64,
+    color: Option<&str>,
+    hourly_rate: Option<f64>,
+) -> Result<Option<Project>> {
+    conn.execute(
+        "UPDATE projects SET color = ?2, hourly_rate = ?3 WHERE id = ?1",
+        params![project_id, color.map(str::trim).filter(|color| !color.is_empty()), hourly_rate],
+    )?;
+    get_project(conn, project_id)
+}
+
+/// Deletes a project and the rules naming it outright; its activities are
+/// left without a project
+pub fn delete_project(conn: &mut Connection, project_id: i64) -> Result<()> {
+    let Some(project) = get_project(conn, project_id)? else {
+        return Ok(());
+    };
+
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM projects WHERE id = ?1", params![project_id])?;
+    tx.execute("UPDATE activities SET project = NULL WHERE project = ?1 COLLATE NOCASE", params![project.name])?;
+    let mut rules = get_rules(&tx)?;
+    rules.retain(|rule| !rule.project.eq_ignore_ascii_case(&project.name));
+    write_rules(&tx, &rules)?;
+    tx.commit()
+}
+
+fn row_to_project(row: &rusqlite::Row) -> Result<Project> {
+    Ok(Project {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        color: row.get(2)?,
+        hourly_rate: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Active time per project in the range
+pub fn get_project_time(conn: &Connection, start_timestamp: i64, end_timestamp: i64) -> Result<ProjectTimeReport> {
+    let mut stmt = conn.prepare(
+        "SELECT a.project,
+            p.color,
+            p.hourly_rate,
+            SUM(a.duration_seconds) AS total,
+            COUNT(*),
+            COUNT(DISTINCT date(a.timestamp, 'unixepoch', 'localtime')),
+            COALESCE(CAST(SUM(a.duration_seconds) AS REAL) / NULLIF(SUM(SUM(a.duration_seconds)) OVER (), 0), 0)
+         FROM activities a
+         LEFT JOIN projects p ON p.name = a.project
+         WHERE a.timestamp BETWEEN ?1 AND ?2 AND a.is_idle = 0
+         GROUP BY a.project
+         ORDER BY total DESC, a.project",
+    )?;
+
+    let mut report = ProjectTimeReport { projects: Vec::new(), unassigned_seconds: 0 };
+    let mut rows = stmt.query(params![start_timestamp, end_timestamp])?;
+    while let Some(row) = rows.next()? {
+        let total_seconds: i64 = row.get(3)?;
+        let Some(project) = row.get::<_, Option<String>>(0)? else {
+            report.unassigned_seconds = total_seconds;
+            continue;
+        };
+        let hourly_rate: Option<f64> = row.get(2)?;
+        report.projects.push(ProjectTime {
+            project,
+            color: row.get(1)?,
+            total_seconds,
+            activity_count: row.get(4)?,
+            days: row.get(5)?,
+            share: row.get(6)?,
+            billable_amount: hourly_rate.map(|rate| total_seconds as f64 / 3600.0 * rate),
+        });
+    }
+    Ok(report)
+}
+
+pub fn get_rules(conn: &Connection) -> Result<Vec<ProjectRule>> {
+    Ok(get_setting(conn, RULES_KEY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_rule(conn: &Connection, rule: &ProjectRule) -> std::result::Result<ProjectRule, String> {
+    compile(rule)?;
+    if rule.project.trim().is_empty() {
+        return Err("A rule needs a project".to_string());
+    }
+
+    let mut rules = get_rules(conn).map_err(|e| e.to_string())?;
+    let id = rule.id.unwrap_or_else(|| rules.iter().filter_map(|r| r.id).max().unwrap_or(0) + 1);
+    let saved = ProjectRule { id: Some(id), project: rule.project.trim().to_string(), ..rule.clone() };
+    match rules.iter_mut().find(|r| r.id == Some(id)) {
+        Some(existing) => *existing = saved.clone(),
+        None => rules.push(saved.clone()),
+    }
+
+    write_rules(conn, &rules).map_err(|e| e.to_string())?;
+    // A fixed name is a project from now on; `$1`-style ones are registered
+    // as they match
+    if !saved.project.contains('$') {
+        register(conn, &saved.project).map_err(|e| e.to_string())?;
+    }
+    Ok(saved)
+}
+
+pub fn delete_rule(conn: &Connection, rule_id: i64) -> Result<()> {
+    let mut rules = get_rules(conn)?;
+    rules.retain(|rule| rule.id != Some(rule_id));
+    write_rules(conn, &rules)
+}
+
+fn write_rules(conn: &Connection, rules: &[ProjectRule]) -> Result<()> {
+    let json = serde_json::to_string(rules).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, RULES_KEY, &json)
+}
+
+fn compile(rule: &ProjectRule) -> std::result::Result<Regex, String> {
+    if rule.pattern.trim().is_empty() {
+        return Err("A rule needs a pattern".to_string());
+    }
+    Regex::new(&rule.pattern).map_err(|e| format!("Invalid pattern: {}", e))
+}
+
+/// The enabled rules and heuristics, loaded once for a batch of activities
+pub struct Detector {
+    rules: Vec<(Regex, ProjectRule)>,
+    heuristics: bool,
+    /// Repository found for each terminal directory seen so far
+    repos: HashMap<String, Option<String>>,
+}
+
+impl Detector {
+    /// Rules that no longer compile are skipped
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let rules = get_rules(conn)?
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| compile(&rule).ok().map(|regex| (regex, rule)))
+            .collect();
+        let heuristics = get_setting(conn, DETECTION_KEY)?.as_deref() != Some("false");
+        Ok(Self { rules, heuristics, repos: HashMap::new() })
+    }
+
+    pub fn detect(&mut self, activity: &Activity) -> Option<String> {
+        if activity.is_idle {
+            return None;
+        }
+        self.detect_window(&activity.app_name, activity.window_title.as_deref().unwrap_or_default())
+    }
+
+    pub fn detect_window(&mut self, app_name: &str, window_title: &str) -> Option<String> {
+        for (regex, rule) in &self.rules {
+            let fields: &[&str] = match rule.match_field {
+                MatchField::App => &[app_name],
+                MatchField::Title => &[window_title],
+                MatchField::Any => &[window_title, app_name],
+            };
+            for field in fields {
+                if let Some(captures) = regex.captures(field) {
+                    let mut project = String::new();
+                    captures.expand(&rule.project, &mut project);
+                    let project = project.trim();
+                    if !project.is_empty() {
+                        return Some(project.to_string());
+                    }
+                }
+            }
+        }
+
+        if !self.heuristics || window_title.trim().is_empty() {
+            return None;
+        }
+        let app = app_key(app_name);
+        if EDITOR_APPS.contains(&app.as_str()) {
+            editor_project(window_title)
+        } else if JETBRAINS_APPS.contains(&app.as_str()) {
+            jetbrains_project(window_title)
+        } else if TERMINAL_APPS.contains(&app.as_str()) {
+            let dir = terminal_dir(window_title)?;
+            self.repos.entry(dir.clone()).or_insert_with(|| repo_name(&dir)).clone()
+        } else {
+            None
+        }
+    }
+}
+
+/// The app name lowercased, without ".exe", a reverse-DNS prefix as in
+/// "org.wezfurlong.wezterm", a "jetbrains-" prefix, the "64" of 64-bit
+/// launchers like "idea64" or an edition like "PyCharm Community Edition"
+fn app_key(app_name: &str) -> String {
+    let mut key = app_name.trim().to_lowercase();
+    if let Some(stem) = key.strip_suffix(".exe") {
+        key = stem.to_string();
+    }
+    if !key.contains(' ') && key.matches('.').count() >= 2 {
+        key = key.rsplit('.').next().unwrap_or_default().to_string();
+    }
+    if let Some(rest) = key.strip_prefix("jetbrains-") {
+        key = rest.to_string();
+    }
+    if let Some(rest) = EDITION_SUFFIXES.iter().find_map(|suffix| key.strip_suffix(suffix)) {
+        key = rest.to_string();
+    }
+    if let Some(rest) = key.strip_suffix("64") {
+        key = rest.to_string();
+    }
+    key
+}
+
+/// Title segments split on the separators editors use
+fn segments(title: &str) -> Vec<String> {
+    title
+        .replace(" — ", " - ")
+        .replace(" – ", " - ")
+        .split(" - ")
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// The segment without a trailing "[SSH: host]" or "(Workspace)"
+fn strip_decorations(segment: &str) -> &str {
+    let mut segment = segment.trim();
+    loop {
+        let stripped = match segment.chars().next_back() {
+            Some(']') => segment.rfind(" [").map(|i| &segment[..i]),
+            Some(')') => segment.rfind(" (").map(|i| &segment[..i]),
+            _ => None,
+        };
+        match stripped {
+            Some(rest) if !rest.trim().is_empty() => segment = rest.trim(),
+            _ => return segment,
+        }
+    }
+}
+
+/// "● main.rs - crate - Visual Studio Code": the folder is the segment
+/// before the app name. A window showing no file isn't attributed, since its
+/// one segment may be the folder or just a tab like "Welcome".
+fn editor_project(title: &str) -> Option<String> {
+    let mut parts = segments(title);
+    if parts.last().is_some_and(|part| EDITOR_TITLES.contains(&part.to_lowercase().as_str())) {
+        parts.pop();
+    }
+    if parts.len() < 2 {
+        return None;
+    }
+    Some(strip_decorations(&parts[parts.len() - 1]).to_string()).filter(|project| !project.is_empty())
+}
+
+/// "crate – main.rs" or, in older versions, "crate [~/src/crate] – …"
+fn jetbrains_project(title: &str) -> Option<String> {
+    let parts = segments(title);
+    if parts.len() < 2 {
+        return None;
+    }
+    Some(strip_decorations(&parts[0]).to_string()).filter(|project| !project.is_empty())
+}
+
+/// The first path in a terminal title: "me@host: ~/src/crate",
+/// "~/src/crate — zsh" or "PS C:\src\crate>"
+fn terminal_dir(title: &str) -> Option<String> {
+    static PATH: OnceLock<Regex> = OnceLock::new();
+    let regex = PATH.get_or_init(|| Regex::new(r"(?:^|[\s:@])((?:~|/|[A-Za-z]:\\)[^\s:>]*)").unwrap());
+    let path = regex.captures(title)?.get(1)?.as_str().trim_end_matches(['/', '\\']);
+    (!path.is_empty() && path != "~").then(|| path.to_string())
+}
+
+/// The name of the git repository containing `dir`, if it is on this machine
+fn repo_name(dir: &str) -> Option<String> {
+    let path = match dir.strip_prefix('~') {
+        Some(rest) => {
+            let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+            PathBuf::from(home).join(rest.trim_start_matches(['/', '\\']))
+        }
+        None => PathBuf::from(dir),
+    };
+    path.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Re-applies the current rules and heuristics to the tracked activities in
+/// the range, clearing the project of those nothing matches. Manual and
+/// edited activities keep the project they were given.
+pub fn detect(conn: &mut Connection, start_timestamp: i64, end_timestamp: i64) -> Result<DetectResult> {
+    let mut detector = Detector::load(conn)?;
+    let tx = conn.transaction()?;
+    let mut scanned = 0;
+    let mut changed = 0;
+    {
+        let mut select = tx.prepare(
+            "SELECT id, app_name, window_title, is_idle, project
+             FROM activities
+             WHERE timestamp BETWEEN ?1 AND ?2 AND is_manual = 0 AND is_edited = 0",
+        )?;
+        let mut update = tx.prepare("UPDATE activities SET project = ?2 WHERE id = ?1")?;
+
+        let rows = select
+            .query_map(params![start_timestamp, end_timestamp], |row| {
+                Ok(Activity {
+                    id: Some(row.get(0)?),
+                    timestamp: 0,
+                    app_name: row.get(1)?,
+                    window_title: row.get(2)?,
+                    duration_seconds: 0,
+                    is_idle: row.get(3)?,
+                    category: None,
+                    algorithm_version: None,
+                    is_manual: false,
+                    project: row.get(4)?,
+                    is_edited: false,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut found = HashSet::new();
+        for activity in rows {
+            scanned += 1;
+            let project = detector.detect(&activity);
+            if project != activity.project {
+                update.execute(params![activity.id, project])?;
+                changed += 1;
+            }
+            found.extend(project);
+        }
+        for project in &found {
+            register(&tx, project)?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(DetectResult { activities_scanned: scanned, activities_changed: changed })
+}
@@ -0,0 +1,185 @@
+// Named time ranges
+// Presets such as "Today", "This week" or "Last 30 days" are resolved here
+// rather than in the frontend, so every view agrees on where a day or week
+// starts: days are local calendar days, bounded as in `local_day_bounds`, and
+// weeks start on Monday. Resolving a preset gives both the "YYYY-MM-DD" dates
+// the date-based commands take and the timestamps the others take.
+//
+// Users can save their own presets, either a fixed span of dates or the last
+// N days, as JSON in the `range_presets` setting.
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::database::queries::{get_setting, local_day_bounds, parse_date, set_setting, DATE_FORMAT};
+
+const PRESETS_KEY: &str = "range_presets";
+const CUSTOM_ID_PREFIX: &str = "custom-";
+const MAX_DAYS: u32 = 3650;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RangeSpec {
+    Today,
+    Yesterday,
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    LastMonth,
+    /// The last `days` days, today included
+    LastDays { days: u32 },
+    /// "YYYY-MM-DD" to "YYYY-MM-DD", both included
+    Fixed { start_date: String, end_date: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangePreset {
+    /// Built-in presets have fixed ids such as "today"; saved ones are
+    /// "custom-<n>", or empty when first saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub spec: RangeSpec,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedRange {
+    #[serde(flatten)]
+    pub preset: RangePreset,
+    pub start_date: String,
+    pub end_date: String,
+    /// First second of `start_date`
+    pub start_timestamp: i64,
+    /// Last second of `end_date`
+    pub end_timestamp: i64,
+}
+
+fn builtin_presets() -> Vec<RangePreset> {
+    [
+        ("today", "Today", RangeSpec::Today),
+        ("yesterday", "Yesterday", RangeSpec::Yesterday),
+        ("this_week", "This week", RangeSpec::ThisWeek),
+        ("last_week", "Last week", RangeSpec::LastWeek),
+        ("last_7_days", "Last 7 days", RangeSpec::LastDays { days: 7 }),
+        ("last_30_days", "Last 30 days", RangeSpec::LastDays { days: 30 }),
+        ("this_month", "This month", RangeSpec::ThisMonth),
+        ("last_month", "Last month", RangeSpec::LastMonth),
+    ]
+    .into_iter()
+    .map(|(id, name, spec)| RangePreset { id: id.to_string(), name: name.to_string(), spec, builtin: true })
+    .collect()
+}
+
+fn get_custom_presets(conn: &Connection) -> Result<Vec<RangePreset>> {
+    Ok(get_setting(conn, PRESETS_KEY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+fn write_custom_presets(conn: &Connection, presets: &[RangePreset]) -> Result<()> {
+    let json = serde_json::to_string(presets).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, PRESETS_KEY, &json)
+}
+
+/// Built-in presets, then saved ones, each resolved against today
+pub fn list_presets(conn: &Connection) -> std::result::Result<Vec<ResolvedRange>, String> {
+    let today = Local::now().date_naive();
+    let custom = get_custom_presets(conn).map_err(|e| e.to_string())?;
+    builtin_presets()
+        .into_iter()
+        .chain(custom)
+        .map(|preset| resolve_preset(preset, today))
+        .collect()
+}
+
+/// The preset with `id` resolved against today
+pub fn resolve(conn: &Connection, id: &str) -> std::result::Result<ResolvedRange, String> {
+    let preset = builtin_presets()
+        .into_iter()
+        .chain(get_custom_presets(conn).map_err(|e| e.to_string())?)
+        .find(|preset| preset.id == id)
+        .ok_or_else(|| format!("Unknown range preset \"{}\"", id))?;
+    resolve_preset(preset, Local::now().date_naive())
+}
+
+pub fn save_preset(conn: &Connection, preset: &RangePreset) -> std::result::Result<ResolvedRange, String> {
+    let name = preset.name.trim();
+    if name.is_empty() {
+        return Err("A range preset needs a name".to_string());
+    }
+    if builtin_presets().iter().any(|builtin| builtin.id == preset.id) {
+        return Err("Built-in range presets can't be changed".to_string());
+    }
+    // Checks the spec before anything is stored
+    let today = Local::now().date_naive();
+    bounds(&preset.spec, today)?;
+
+    let mut presets = get_custom_presets(conn).map_err(|e| e.to_string())?;
+    let id = if preset.id.is_empty() {
+        let next = presets
+            .iter()
+            .filter_map(|p| p.id.strip_prefix(CUSTOM_ID_PREFIX)?.parse::<i64>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        format!("{}{}", CUSTOM_ID_PREFIX, next)
+    } else {
+        preset.id.clone()
+    };
+    let saved = RangePreset { id: id.clone(), name: name.to_string(), spec: preset.spec.clone(), builtin: false };
+    match presets.iter_mut().find(|p| p.id == id) {
+        Some(existing) => *existing = saved.clone(),
+        None => presets.push(saved.clone()),
+    }
+
+    write_custom_presets(conn, &presets).map_err(|e| e.to_string())?;
+    resolve_preset(saved, today)
+}
+
+pub fn delete_preset(conn: &Connection, id: &str) -> Result<()> {
+    let mut presets = get_custom_presets(conn)?;
+    presets.retain(|preset| preset.id != id);
+    write_custom_presets(conn, &presets)
+}
+
+fn resolve_preset(preset: RangePreset, today: NaiveDate) -> std::result::Result<ResolvedRange, String> {
+    let (start, end) = bounds(&preset.spec, today)?;
+    Ok(ResolvedRange {
+        start_date: start.format(DATE_FORMAT).to_string(),
+        end_date: end.format(DATE_FORMAT).to_string(),
+        start_timestamp: local_day_bounds(start).0,
+        end_timestamp: local_day_bounds(end).1,
+        preset,
+    })
+}
+
+/// First and last day of the range, as of `today`
+fn bounds(spec: &RangeSpec, today: NaiveDate) -> std::result::Result<(NaiveDate, NaiveDate), String> {
+    let week_start = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let month_start = today.with_day(1).unwrap_or(today);
+
+    Ok(match spec {
+        RangeSpec::Today => (today, today),
+        RangeSpec::Yesterday => (today - Days::new(1), today - Days::new(1)),
+        RangeSpec::ThisWeek => (week_start, week_start + Days::new(6)),
+        RangeSpec::LastWeek => (week_start - Days::new(7), week_start - Days::new(1)),
+        RangeSpec::ThisMonth => (month_start, month_start + Months::new(1) - Days::new(1)),
+        RangeSpec::LastMonth => (month_start - Months::new(1), month_start - Days::new(1)),
+        RangeSpec::LastDays { days } => {
+            if !(1..=MAX_DAYS).contains(days) {
+                return Err(format!("A range must cover between 1 and {} days", MAX_DAYS));
+            }
+            (today - Days::new(*days as u64 - 1), today)
+        }
+        RangeSpec::Fixed { start_date, end_date } => {
+            let (start, end) = (parse_date(start_date)?, parse_date(end_date)?);
+            if start > end {
+                return Err("A range can't end before it starts".to_string());
+            }
+            (start, end)
+        }
+    })
+}
@@ -0,0 +1,164 @@
+// Window title redaction
+// User-defined regular expressions that the activity monitor applies to
+// every window title before it is stored, so e-mail subjects, document names
+// or ticket numbers never reach raw snapshots or activities. Each rule
+// replaces its matches with its replacement text (`$1`-style group
+// references work), and rules run in the order they were added.
+//
+// Rules are kept as JSON in the `title_redaction_rules` setting, and a rule
+// that doesn't compile is refused however it is saved. Should stored rules
+// still fail to load or compile, the monitor redacts whole titles rather
+// than store them as they are. Titles already stored aren't rewritten;
+// `test_rule` shows what a rule would do to recent ones.
+
+use regex::Regex;
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::activity::ActivitySnapshot;
+use crate::database::queries::{get_setting, set_setting};
+
+pub const RULES_KEY: &str = "title_redaction_rules";
+/// Distinct recent titles a rule is tested against by default
+const DEFAULT_TEST_TITLES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub id: Option<i64>,
+    /// Regular expression; prefix with `(?i)` to ignore case
+    pub pattern: String,
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+    pub enabled: bool,
+}
+
+fn default_replacement() -> String {
+    "[redacted]".to_string()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedactionPreview {
+    pub app_name: String,
+    pub original: String,
+    pub redacted: String,
+}
+
+pub fn get_rules(conn: &Connection) -> Result<Vec<RedactionRule>> {
+    Ok(get_setting(conn, RULES_KEY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+/// The rules in `value` if each of them compiles, for checking the setting
+/// before it is stored
+pub fn parse_rules(value: &str) -> std::result::Result<Vec<RedactionRule>, String> {
+    let rules: Vec<RedactionRule> =
+        serde_json::from_str(value).map_err(|e| format!("\"{}\" must be a list of rules: {}", RULES_KEY, e))?;
+    for rule in &rules {
+        compile(rule).map_err(|e| format!("Rule \"{}\": {}", rule.pattern, e))?;
+    }
+    Ok(rules)
+}
+
+pub fn save_rule(conn: &Connection, rule: &RedactionRule) -> std::result::Result<RedactionRule, String> {
+    compile(rule)?;
+
+    let mut rules = get_rules(conn).map_err(|e| e.to_string())?;
+    let id = rule.id.unwrap_or_else(|| rules.iter().filter_map(|r| r.id).max().unwrap_or(0) + 1);
+    let saved = RedactionRule { id: Some(id), ..rule.clone() };
+    match rules.iter_mut().find(|r| r.id == Some(id)) {
+        Some(existing) => *existing = saved.clone(),
+        None => rules.push(saved.clone()),
+    }
+
+    write_rules(conn, &rules).map_err(|e| e.to_string())?;
+    Ok(saved)
+}
+
+pub fn delete_rule(conn: &Connection, rule_id: i64) -> Result<()> {
+    let mut rules = get_rules(conn)?;
+    rules.retain(|rule| rule.id != Some(rule_id));
+    write_rules(conn, &rules)
+}
+
+fn write_rules(conn: &Connection, rules: &[RedactionRule]) -> Result<()> {
+    let json = serde_json::to_string(rules).unwrap_or_else(|_| "[]".to_string());
+    set_setting(conn, RULES_KEY, &json)
+}
+
+fn compile(rule: &RedactionRule) -> std::result::Result<Regex, String> {
+    if rule.pattern.trim().is_empty() {
+        return Err("A rule needs a pattern".to_string());
+    }
+    Regex::new(&rule.pattern).map_err(|e| format!("Invalid pattern: {}", e))
+}
+
+/// The title with each rule applied in turn
+fn apply<S: AsRef<str>>(rules: &[(Regex, S)], title: &str) -> String {
+    rules.iter().fold(title.to_string(), |title, (regex, replacement)| {
+        regex.replace_all(&title, replacement.as_ref()).into_owned()
+    })
+}
+
+/// The sample as the monitor should store it, with its title redacted by
+/// every enabled rule. If the rules can't be read or one of them doesn't
+/// compile, the whole title is redacted, since what it should have hidden
+/// is unknown.
+pub fn redact(conn: &Connection, snapshot: Option<ActivitySnapshot>) -> Result<Option<ActivitySnapshot>> {
+    let Some(mut snapshot) = snapshot else {
+        return Ok(None);
+    };
+    let Some(value) = get_setting(conn, RULES_KEY)? else {
+        return Ok(Some(snapshot));
+    };
+    let compiled: Option<Vec<(Regex, String)>> = serde_json::from_str::<Vec<RedactionRule>>(&value)
+        .ok()
+        .and_then(|rules| {
+            rules
+                .into_iter()
+                .filter(|rule| rule.enabled)
+                .map(|rule| compile(&rule).ok().map(|regex| (regex, rule.replacement)))
+                .collect()
+        });
+    match compiled {
+        Some(compiled) => snapshot.window_title = apply(&compiled, &snapshot.window_title),
+        None => snapshot.window_title = default_replacement(),
+    }
+    Ok(Some(snapshot))
+}
+
+/// What `rule` would do to the most recent distinct titles, listing only
+/// those it changes
+pub fn test_rule(
+    conn: &Connection,
+    rule: &RedactionRule,
+    limit: Option<usize>,
+) -> std::result::Result<Vec<RedactionPreview>, String> {
+    let regex = compile(rule)?;
+    let compiled = [(regex, rule.replacement.as_str())];
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT app_name, window_title
+             FROM activities
+             WHERE is_idle = 0 AND window_title IS NOT NULL AND window_title != ''
+             GROUP BY app_name, window_title
+             ORDER BY MAX(timestamp) DESC
+             LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let titles = stmt
+        .query_map(params![limit.unwrap_or(DEFAULT_TEST_TITLES) as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>>>())
+        .map_err(|e| e.to_string())?;
+
+    Ok(titles
+        .into_iter()
+        .filter_map(|(app_name, original)| {
+            let redacted = apply(&compiled, &original);
+            (redacted != original).then_some(RedactionPreview { app_name, original, redacted })
+        })
+        .collect())
+}
@@ -0,0 +1,187 @@
+// Weekly report
+// A markdown summary of one week to keep, paste into a status update or
+// share: total active time, the top apps, the category breakdown, the best
+// focus day, and each figure against the week before. It is built from the
+// same aggregates as the dashboard (weekly stats, top apps, deep work
+// sessions), so the numbers match what the app shows. The best focus day is
+// the one with the most deep work, falling back to the most active day for
+// weeks without any.
+
+use std::fs;
+use chrono::{Days, NaiveDate};
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+
+use crate::database::queries::{self, local_day_bounds, CategoryTotal, PeriodStats, DATE_FORMAT};
+use crate::deep_work::{self, DeepWorkSession};
+
+const TOP_APPS: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyReport {
+    pub week_start: String,
+    pub week_end: String,
+    pub markdown: String,
+    /// Where the report was saved, if it was
+    pub path: Option<String>,
+}
+
+pub fn generate(conn: &mut Connection, week_start: NaiveDate) -> Result<WeeklyReport> {
+    let week_end = week_start + Days::new(6);
+    let (start_timestamp, _) = local_day_bounds(week_start);
+    let (_, end_timestamp) = local_day_bounds(week_end);
+
+    let week = queries::get_weekly_stats(conn, week_start)?;
+    let previous = queries::get_weekly_stats(conn, week_start - Days::new(7))?;
+    let apps = queries::get_top_apps(conn, start_timestamp, end_timestamp, TOP_APPS)?;
+    let sessions = deep_work::get_sessions(conn, week_start, week_end)?;
+    let previous_deep_work: i64 = deep_work::get_sessions(conn, week_start - Days::new(7), week_start - Days::new(1))?
+        .iter()
+        .map(|session| session.focused_seconds)
+        .sum();
+
+    let mut md = format!(
+        "# Week of {} to {}\n\n",
+        week_start.format("%B %-d, %Y"),
+        week_end.format("%B %-d, %Y")
+    );
+
+    let deep_work: i64 = sessions.iter().map(|session| session.focused_seconds).sum();
+    md.push_str("## Summary\n\n");
+    md.push_str(&format!(
+        "- **Active time:** {}{}\n",
+        duration(week.active_time_seconds),
+        change(week.active_time_seconds, previous.active_time_seconds, true)
+    ));
+    md.push_str(&format!("- **Idle time:** {}\n", duration(week.idle_time_seconds)));
+    md.push_str(&format!(
+        "- **Deep work:** {} in {} session{}{}\n",
+        duration(deep_work),
+        sessions.len(),
+        if sessions.len() == 1 { "" } else { "s" },
+        change(deep_work, previous_deep_work, true)
+    ));
+    md.push_str(&format!(
+        "- **Context switches:** {}{}\n",
+        week.context_switches,
+        change(week.context_switches, previous.context_switches, false)
+    ));
+    md.push_str(&format!("- **Best focus day:** {}\n\n", best_focus_day(&week, &sessions)));
+
+    md.push_str("## Days\n\n| Day | Active | Deep work | Switches | Top category |\n|---|---:|---:|---:|---|\n");
+    for day in &week.days {
+        let focused: i64 = sessions.iter().filter(|s| s.date == day.date).map(|s| s.focused_seconds).sum();
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            day_label(&day.date),
+            duration(day.active_time_seconds),
+            duration(focused),
+            day.context_switches,
+            day.top_categories.first().map_or("-".to_string(), |c| cell(&c.category)),
+        ));
+    }
+    md.push('\n');
+
+    md.push_str("## Top apps\n\n");
+    if apps.is_empty() {
+        md.push_str("No activity recorded.\n\n");
+    } else {
+        md.push_str("| App | Time | Share |\n|---|---:|---:|\n");
+        for app in &apps {
+            md.push_str(&format!("| {} | {} | {:.0}% |\n", cell(&app.app_name), duration(app.total_seconds), app.share * 100.0));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Categories\n\n");
+    if week.category_totals.is_empty() {
+        md.push_str("No activity recorded.\n");
+    } else {
+        md.push_str("| Category | Time | Share | Previous week |\n|---|---:|---:|---:|\n");
+        for category in &week.category_totals {
+            md.push_str(&format!(
+                "| {} | {} | {:.0}% | {} |\n",
+                cell(&category.category),
+                duration(category.total_seconds),
+                share(category.total_seconds, week.active_time_seconds),
+                duration(previous_total(&previous.category_totals, &category.category)),
+            ));
+        }
+    }
+
+    Ok(WeeklyReport {
+        week_start: week.start_date,
+        week_end: week.end_date,
+        markdown: md,
+        path: None,
+    })
+}
+
+/// Writes the report's markdown to `path`, replacing any file there
+pub fn save(report: &mut WeeklyReport, path: &str) -> std::result::Result<(), String> {
+    fs::write(path, &report.markdown).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    report.path = Some(path.to_string());
+    Ok(())
+}
+
+fn best_focus_day(week: &PeriodStats, sessions: &[DeepWorkSession]) -> String {
+    let focused = |date: &str| -> i64 { sessions.iter().filter(|s| s.date == date).map(|s| s.focused_seconds).sum() };
+    let best = week
+        .days
+        .iter()
+        .max_by_key(|day| (focused(&day.date), day.active_time_seconds))
+        .filter(|day| day.active_time_seconds > 0);
+
+    match best {
+        Some(day) if focused(&day.date) > 0 => {
+            format!("{} ({} of deep work)", day_label(&day.date), duration(focused(&day.date)))
+        }
+        Some(day) => format!("{} ({} active, no deep work)", day_label(&day.date), duration(day.active_time_seconds)),
+        None => "none".to_string(),
+    }
+}
+
+fn previous_total(totals: &[CategoryTotal], category: &str) -> i64 {
+    totals.iter().find(|total| total.category == category).map_or(0, |total| total.total_seconds)
+}
+
+/// " (+2h 10m, +12% vs previous week)", or a plain count when the values
+/// aren't durations
+fn change(current: i64, previous: i64, is_duration: bool) -> String {
+    if previous == 0 {
+        return if current == 0 { String::new() } else { " (no data the previous week)".to_string() };
+    }
+    let delta = current - previous;
+    let percent = delta as f64 * 100.0 / previous as f64;
+    let amount = if is_duration { duration(delta.abs()) } else { delta.abs().to_string() };
+    format!(" ({}{}, {:+.0}% vs previous week)", if delta < 0 { "-" } else { "+" }, amount, percent)
+}
+
+fn share(seconds: i64, total: i64) -> f64 {
+    if total > 0 {
+        seconds as f64 * 100.0 / total as f64
+    } else {
+        0.0
+    }
+}
+
+fn duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// "Wed Oct 14" from "YYYY-MM-DD"
+fn day_label(date: &str) -> String {
+    NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .map(|day| day.format("%a %b %-d").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// Text safe inside a markdown table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
@@ -0,0 +1,86 @@
+// Data retention
+// Opt-in: `data_retention_days` is 0, keep everything, until the user sets a
+// period. Once one is set, activities and raw snapshots older than it are
+// deleted at startup and then daily. The frontend is told how many rows went
+// through a `data-pruned` event, and when a prune removes a lot the database
+// is vacuumed so the file actually shrinks.
+
+use std::thread;
+use std::time::Duration;
+use chrono::Utc;
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::{DbConnection, queries::{delete_old_activities, get_setting}};
+
+pub const DATA_PRUNED_EVENT: &str = "data-pruned";
+/// Set once the old 30-day default has been turned into keep-everything
+pub const DEFAULT_CLEARED_KEY: &str = "data_retention_default_cleared";
+
+const RUN_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Give startup a moment before the first prune takes the database lock
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+/// Rows deleted in one prune above which the database is vacuumed
+const VACUUM_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneResult {
+    pub retention_days: i64,
+    /// Anything before this timestamp was deleted
+    pub cutoff: i64,
+    pub activities_deleted: usize,
+    pub snapshots_deleted: usize,
+    pub vacuumed: bool,
+}
+
+pub fn start(app: &AppHandle, db: &DbConnection) {
+    let app = app.clone();
+    let db = std::sync::Arc::clone(db);
+
+    thread::spawn(move || {
+        thread::sleep(STARTUP_DELAY);
+        loop {
+            let pruned = match db.lock() {
+                Ok(conn) => prune(&conn).unwrap_or_else(|e| {
+                    eprintln!("❌ Failed to prune old data: {}", e);
+                    None
+                }),
+                Err(_) => None,
+            };
+            if let Some(result) = pruned.filter(|r| r.activities_deleted + r.snapshots_deleted > 0) {
+                println!(
+                    "✅ Pruned {} activities and {} snapshots older than {} days",
+                    result.activities_deleted, result.snapshots_deleted, result.retention_days
+                );
+                if let Err(e) = app.emit(DATA_PRUNED_EVENT, &result) {
+                    eprintln!("❌ Failed to emit prune result: {}", e);
+                }
+            }
+            thread::sleep(RUN_INTERVAL);
+        }
+    });
+}
+
+/// Deletes what is older than the retention period, or does nothing when
+/// `data_retention_days` is 0 (keep everything) or not a number of days
+pub fn prune(conn: &Connection) -> Result<Option<PruneResult>> {
+    let Some(retention_days) = get_setting(conn, "data_retention_days")?
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|days| *days > 0)
+    else {
+        return Ok(None);
+    };
+
+    let cutoff = Utc::now().timestamp() - retention_days * 24 * 60 * 60;
+    let activities_deleted = delete_old_activities(conn, retention_days)?;
+    let snapshots_deleted = conn.execute("DELETE FROM raw_snapshots WHERE timestamp < ?1", params![cutoff])?;
+    conn.execute("DELETE FROM gap_annotations WHERE end_time < ?1", params![cutoff])?;
+
+    let vacuumed = activities_deleted + snapshots_deleted >= VACUUM_THRESHOLD;
+    if vacuumed {
+        conn.execute_batch("VACUUM")?;
+    }
+
+    Ok(Some(PruneResult { retention_days, cutoff, activities_deleted, snapshots_deleted, vacuumed }))
+}
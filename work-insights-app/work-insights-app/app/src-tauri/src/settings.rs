@@ -0,0 +1,184 @@
+// Settings access for the frontend
+// The `settings` table is a plain key/value store; the settings page reads
+// and writes it through `get_setting`, `set_setting` and `get_all_settings`.
+// Values for keys the backend reads are checked and normalized here before
+// they are stored, so a typo can't leave the tracker running on a fallback
+// value without anyone noticing; JSON values are read into the type the
+// backend reads them as and stored as that type writes them. Unknown keys
+// are stored as given, for preferences only the frontend uses.
+//
+// Keys the app keeps its own state in, such as the running focus session or
+// snooze deadlines, can't be set this way and are left out of
+// `get_all_settings`; they have commands of their own.
+
+use std::collections::BTreeMap;
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::activity::merge;
+use crate::api;
+use crate::breaks;
+use crate::database::queries::{get_all_settings, get_setting, set_setting};
+use crate::database::storage;
+use crate::distractions::{self, DistractionBudget};
+use crate::goals::{self, Goal};
+use crate::notifications::NotificationKind;
+use crate::pause;
+use crate::privacy;
+use crate::productivity::ScoreWeights;
+use crate::projects::ProjectRule;
+use crate::ranges::RangePreset;
+use crate::redaction;
+use crate::retention;
+use crate::timers;
+
+/// Keys written only by the app itself
+const INTERNAL_KEYS: [&str; 12] = [
+    "focus_session",
+    "notifications_snoozed_until",
+    goals::NOTIFIED_KEY,
+    distractions::NOTIFIED_KEY,
+    breaks::SNOOZED_UNTIL_KEY,
+    pause::PAUSED_UNTIL_KEY,
+    privacy::HASH_SALT_KEY,
+    storage::SYNCED_THROUGH_KEY,
+    timers::HOTKEYS_KEY,
+    timers::RUNNING_KEY,
+    api::TOKEN_KEY,
+    retention::DEFAULT_CLEARED_KEY,
+];
+
+const BOOLEAN_KEYS: [&str; 12] = [
+    "tracking_enabled",
+    "auto_start_tracking",
+    "local_api_enabled",
+    "focus_timer_overlay",
+    "blocked_apps_minimize_in_focus",
+    "launch_friction_enabled",
+    "break_reminder_enabled",
+    "llm_insights_enabled",
+    "llm_include_day_notes",
+    "llm_share_category_names",
+    "raw_snapshots_enabled",
+    "project_detection_enabled",
+];
+
+/// Whole-number keys and the range each accepts
+const INTEGER_KEYS: [(&str, i64, i64); 11] = [
+    // 0 keeps everything
+    ("data_retention_days", 0, 3650),
+    ("idle_timeout_seconds", 30, 24 * 60 * 60),
+    ("sampling_interval_seconds", merge::MIN_SAMPLING_INTERVAL_SECS, merge::MAX_SAMPLING_INTERVAL_SECS),
+    ("coalesce_gap_seconds", 0, 300),
+    ("local_api_port", 1024, 65535),
+    ("deep_work_min_minutes", 1, 8 * 60),
+    ("deep_work_max_gap_minutes", 0, 60),
+    ("break_reminder_minutes", 1, 8 * 60),
+    ("break_reminder_snooze_minutes", 1, 4 * 60),
+    ("raw_snapshots_max_rows", 1000, 100_000_000),
+    ("launch_friction_seconds", 1, 300),
+];
+
+
+pub fn get(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    if INTERNAL_KEYS.contains(&key) {
+        return Err(format!("\"{}\" is managed by the app", key));
+    }
+    get_setting(conn, key).map_err(|e| e.to_string())
+}
+
+pub fn get_all(conn: &Connection) -> Result<BTreeMap<String, String>, String> {
+    let mut settings = get_all_settings(conn).map_err(|e| e.to_string())?;
+    settings.retain(|key, _| !INTERNAL_KEYS.contains(&key.as_str()));
+    Ok(settings)
+}
+
+/// Checks the value for `key` and stores it, returning what was stored
+pub fn set(conn: &Connection, key: &str, value: &str) -> Result<String, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Setting key cannot be empty".to_string());
+    }
+    if INTERNAL_KEYS.contains(&key) {
+        return Err(format!("\"{}\" is managed by the app", key));
+    }
+
+    let value = validate(key, value)?;
+    set_setting(conn, key, &value).map_err(|e| e.to_string())?;
+    Ok(value)
+}
+
+/// The value as it should be stored for `key`, or why it can't be
+pub fn validate(key: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+
+    if BOOLEAN_KEYS.contains(&key) {
+        return match value.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok("true".to_string()),
+            "false" | "0" | "no" | "off" => Ok("false".to_string()),
+            _ => Err(format!("\"{}\" must be true or false", key)),
+        };
+    }
+
+    if let Some((_, min, max)) = INTEGER_KEYS.iter().find(|(k, _, _)| *k == key) {
+        return match value.parse::<i64>() {
+            Ok(n) if (*min..=*max).contains(&n) => Ok(n.to_string()),
+            _ => Err(format!("\"{}\" must be a whole number between {} and {}", key, min, max)),
+        };
+    }
+
+    if key == redaction::RULES_KEY {
+        return redaction::parse_rules(value).map(|_| value.to_string());
+    }
+
+    match key {
+        "productivity_weights" => typed::<ScoreWeights>(key, value),
+        "blocked_apps" | "private_apps" | "presentation_apps" => typed::<Vec<String>>(key, value),
+        "project_rules" => typed::<Vec<ProjectRule>>(key, value),
+        "goals" => typed::<Vec<Goal>>(key, value),
+        "range_presets" => typed::<Vec<RangePreset>>(key, value),
+        "distraction_budgets" => typed::<Vec<DistractionBudget>>(key, value),
+        "notifications_disabled" => typed::<Vec<NotificationKind>>(key, value),
+        "privacy_mode" => match value {
+            "off" | "omit" | "hash" => Ok(value.to_string()),
+            _ => Err("\"privacy_mode\" must be off, omit or hash".to_string()),
+        },
+        "llm_provider" => match value {
+            "openai" | "ollama" => Ok(value.to_string()),
+            _ => Err("\"llm_provider\" must be openai or ollama".to_string()),
+        },
+        storage::BACKEND_KEY => match value {
+            "sqlite" | "postgres" => Ok(value.to_string()),
+            _ => Err(format!("\"{}\" must be sqlite or postgres", key)),
+        },
+        storage::URL_KEY => {
+            if value.is_empty() || value.starts_with("postgres://") || value.starts_with("postgresql://") {
+                Ok(value.to_string())
+            } else {
+                Err(format!("\"{}\" must be a postgres:// URL, or empty", key))
+            }
+        }
+        "quiet_hours_start" | "quiet_hours_end" => {
+            if value.is_empty() || chrono::NaiveTime::parse_from_str(value, "%H:%M").is_ok() {
+                Ok(value.to_string())
+            } else {
+                Err(format!("\"{}\" must be a time as HH:MM, or empty", key))
+            }
+        }
+        "focus_webhook_url" | "llm_endpoint" => {
+            if value.is_empty() || value.starts_with("http://") || value.starts_with("https://") {
+                Ok(value.to_string())
+            } else {
+                Err(format!("\"{}\" must be an http(s) URL, or empty", key))
+            }
+        }
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// `value` read as the JSON the backend expects for `key`, written back out
+fn typed<T: DeserializeOwned + Serialize>(key: &str, value: &str) -> Result<String, String> {
+    let parsed: T = serde_json::from_str(value).map_err(|e| format!("\"{}\" isn't valid: {}", key, e))?;
+    serde_json::to_string(&parsed).map_err(|e| e.to_string())
+}
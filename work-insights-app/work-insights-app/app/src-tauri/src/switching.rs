@@ -0,0 +1,101 @@
+// Context switching
+// How fragmented each day was. A focus block is a run of active time on one
+// app; it ends at a switch to another app, at idle time, or at a gap in the
+// record longer than `MAX_BREAK_SECONDS`. Switches are counted the same way
+// as everywhere else, between consecutive active activities on different
+// apps, so idle time in between doesn't hide a switch.
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+
+use crate::database::queries::{local_day_bounds, DATE_FORMAT};
+
+/// Gaps in the record up to this long don't end a focus block
+const MAX_BREAK_SECONDS: i64 = 2 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusBlock {
+    pub app_name: String,
+    pub started_at: i64,
+    /// Active seconds, not counting breaks within the block
+    pub active_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitchingStats {
+    /// "YYYY-MM-DD"
+    pub date: String,
+    pub active_seconds: i64,
+    pub context_switches: i64,
+    /// Switches per active hour
+    pub switches_per_hour: f64,
+    pub focus_blocks: i64,
+    pub average_block_seconds: i64,
+    pub longest_block: Option<FocusBlock>,
+}
+
+/// One entry per local day from `start` to `end` inclusive
+pub fn get_switching_stats(conn: &Connection, start: NaiveDate, end: NaiveDate) -> Result<Vec<SwitchingStats>> {
+    start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .map(|day| day_stats(conn, day))
+        .collect()
+}
+
+fn day_stats(conn: &Connection, date: NaiveDate) -> Result<SwitchingStats> {
+    let (start, end) = local_day_bounds(date);
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, duration_seconds, app_name, is_idle
+         FROM activities
+         WHERE timestamp BETWEEN ?1 AND ?2
+         ORDER BY timestamp, id",
+    )?;
+    let rows = stmt
+        .query_map(params![start, end], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, bool>(3)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut active_seconds = 0;
+    let mut context_switches = 0;
+    let mut blocks: Vec<FocusBlock> = Vec::new();
+    let mut last_app: Option<String> = None;
+    // End of the current block, `None` once idle time or a long gap closed it
+    let mut block_end: Option<i64> = None;
+
+    for (timestamp, duration, app_name, is_idle) in rows {
+        if is_idle {
+            block_end = None;
+            continue;
+        }
+        active_seconds += duration;
+
+        let switched = last_app.as_ref().is_some_and(|app| *app != app_name);
+        if switched {
+            context_switches += 1;
+        }
+        let continues = !switched && block_end.is_some_and(|end| timestamp - end <= MAX_BREAK_SECONDS);
+        match blocks.last_mut() {
+            Some(block) if continues => block.active_seconds += duration,
+            _ => blocks.push(FocusBlock { app_name: app_name.clone(), started_at: timestamp, active_seconds: duration }),
+        }
+
+        block_end = Some(timestamp + duration);
+        last_app = Some(app_name);
+    }
+
+    let focus_blocks = blocks.len() as i64;
+    let active_hours = active_seconds as f64 / 3600.0;
+    Ok(SwitchingStats {
+        date: date.format(DATE_FORMAT).to_string(),
+        active_seconds,
+        context_switches,
+        switches_per_hour: if active_seconds > 0 { context_switches as f64 / active_hours } else { 0.0 },
+        focus_blocks,
+        average_block_seconds: if focus_blocks > 0 { active_seconds / focus_blocks } else { 0 },
+        // The earliest of equally long blocks
+        longest_block: blocks.into_iter().rev().max_by_key(|block| block.active_seconds),
+    })
+}
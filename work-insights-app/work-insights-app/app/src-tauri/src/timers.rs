@@ -0,0 +1,433 @@
+// Charge-code timers
+// Billing time the user declares: a timer records that work on a charge code
+// ("Client A") started and stopped, as the user said rather than as
+// detected. At most one timer runs, kept in `timer_running`; starting another
+// stops the running one first, so switching clients is a single action.
+//
+// A stopped run is written into the activities, so it shows up in the stats,
+// the reports and the project time like any other time. Tracked active time
+// in its span is attributed to the charge code as its project (and counts as
+// edited, so project detection leaves it alone), and the rest of the span,
+// idle or with nothing recorded, becomes manual activities named after the
+// charge code. A run counts for at most `MAX_RUN_SECS`; one still running
+// when the app starts is closed where the last activity recorded after it
+// ended, as nothing after that is known.
+//
+// Each charge code can be bound to a global hotkey, kept in `timer_hotkeys`
+// as `[{"shortcut": "CmdOrCtrl+Alt+1", "charge_code": "Client A"}]`.
+// Pressing a hotkey starts its timer, switching from whatever was running,
+// and pressing it again while that timer runs stops it. Bindings are only
+// saved once every shortcut is registered: a shortcut bound twice, already
+// used for something else in this app, or refused by the system because
+// another application holds it fails the save and the previous bindings stay
+// in place. The app can only see its own shortcuts, so another application's
+// only shows up when registering it fails.
+
+use std::str::FromStr;
+use chrono::Utc;
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::commands::AppState;
+use crate::database::{DbConnection, queries::{get_setting, set_setting, Activity, ActivityWriter}};
+use crate::notifications::{self, NotificationKind};
+
+pub const HOTKEYS_KEY: &str = "timer_hotkeys";
+pub const RUNNING_KEY: &str = "timer_running";
+pub const TIMER_EVENT: &str = "timer-changed";
+
+/// Longest run a timer records, so one left running overnight doesn't bill
+/// the night
+const MAX_RUN_SECS: i64 = 12 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimerEntry {
+    pub charge_code: String,
+    pub started_at: i64,
+    /// None while the timer is running
+    pub ended_at: Option<i64>,
+    /// Up to now for the running timer
+    pub duration_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerHotkey {
+    pub shortcut: String,
+    pub charge_code: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimerHotkeyStatus {
+    pub shortcut: String,
+    pub charge_code: String,
+    /// False when the shortcut couldn't be registered at startup, usually
+    /// because another application holds it
+    pub registered: bool,
+}
+
+/// What `timer_running` holds
+#[derive(Serialize, Deserialize)]
+struct RunningTimer {
+    charge_code: String,
+    started_at: i64,
+}
+
+pub fn get_running(conn: &Connection) -> Result<Option<TimerEntry>> {
+    let running = get_setting(conn, RUNNING_KEY)?.and_then(|value| serde_json::from_str::<RunningTimer>(&value).ok());
+    Ok(running.map(|running| TimerEntry {
+        duration_seconds: (Utc::now().timestamp() - running.started_at).max(0),
+        charge_code: running.charge_code,
+        started_at: running.started_at,
+        ended_at: None,
+    }))
+}
+
+/// Stops the running timer at `ended_at`, writing its run into the
+/// activities, and returns it
+fn finish(conn: &mut Connection, ended_at: i64) -> std::result::Result<Option<TimerEntry>, String> {
+    let Some(mut entry) = get_running(conn).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let ended_at = ended_at.clamp(entry.started_at, entry.started_at + MAX_RUN_SECS);
+    record(conn, &entry.charge_code, entry.started_at, ended_at).map_err(|e| e.to_string())?;
+    set_setting(conn, RUNNING_KEY, "").map_err(|e| e.to_string())?;
+    entry.ended_at = Some(ended_at);
+    entry.duration_seconds = ended_at - entry.started_at;
+    Ok(Some(entry))
+}
+
+/// Writes `charge_code`'s run from `start` to `end` into the activities:
+/// active time in the span moves to the charge code's project, and what
+/// isn't covered by it becomes manual activities for the charge code
+fn record(conn: &mut Connection, charge_code: &str, start: i64, end: i64) -> Result<()> {
+    if end <= start {
+        return Ok(());
+    }
+    let tx = conn.transaction()?;
+    let overlapping = {
+        let mut stmt = tx.prepare(
+            "SELECT id, timestamp, app_name, window_title, duration_seconds, is_idle, category, algorithm_version, is_manual, project, is_edited
+             FROM activities
+             WHERE timestamp < ?2 AND timestamp + duration_seconds > ?1
+             ORDER BY timestamp",
+        )?;
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok(Activity {
+                id: Some(row.get(0)?),
+                timestamp: row.get(1)?,
+                app_name: row.get(2)?,
+                window_title: row.get(3)?,
+                duration_seconds: row.get(4)?,
+                is_idle: row.get(5)?,
+                category: row.get(6)?,
+                algorithm_version: row.get(7)?,
+                is_manual: row.get(8)?,
+                project: row.get(9)?,
+                is_edited: row.get(10)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>>>()?
+    };
+
+    // Each row keeps its parts outside the span; inside it, active time is
+    // attributed to the charge code and idle time is dropped
+    let mut writer = ActivityWriter::new(&tx);
+    let mut covered = Vec::new();
+    for activity in &overlapping {
+        let activity_end = activity.timestamp + activity.duration_seconds as i64;
+        if activity.timestamp < start {
+            tx.execute("UPDATE activities SET duration_seconds = ?1 WHERE id = ?2", params![start - activity.timestamp, activity.id])?;
+        } else {
+            tx.execute("DELETE FROM activities WHERE id = ?1", params![activity.id])?;
+        }
+        if activity_end > end {
+            let rest = Activity { id: None, timestamp: end, duration_seconds: (activity_end - end) as i32, ..activity.clone() };
+            writer.insert(&rest)?;
+        }
+        if !activity.is_idle {
+            let (inside_start, inside_end) = (activity.timestamp.max(start), activity_end.min(end));
+            let billed = Activity {
+                id: None,
+                timestamp: inside_start,
+                duration_seconds: (inside_end - inside_start) as i32,
+                project: Some(charge_code.to_string()),
+                is_edited: true,
+                ..activity.clone()
+            };
+            writer.insert(&billed)?;
+            covered.push((inside_start, inside_end));
+        }
+    }
+
+    let mut gaps = Vec::new();
+    let mut cursor = start;
+    for (covered_start, covered_end) in covered {
+        if covered_start > cursor {
+            gaps.push((cursor, covered_start));
+        }
+        cursor = cursor.max(covered_end);
+    }
+    if cursor < end {
+        gaps.push((cursor, end));
+    }
+    for (gap_start, gap_end) in gaps {
+        let manual = Activity {
+            id: None,
+            timestamp: gap_start,
+            app_name: charge_code.to_string(),
+            window_title: None,
+            duration_seconds: (gap_end - gap_start) as i32,
+            is_idle: false,
+            category: None,
+            algorithm_version: None,
+            is_manual: true,
+            project: Some(charge_code.to_string()),
+            is_edited: false,
+        };
+        writer.insert(&manual)?;
+    }
+    tx.commit()
+}
+
+/// Writes out the run the monitor is accumulating, so the part of it inside
+/// a timer's span is stored before the timer's run is recorded
+fn flush_tracking(app: &AppHandle) {
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(monitor) = state.monitor.lock() {
+            monitor.flush();
+        }
+    }
+}
+
+/// Starts a timer for `charge_code`, stopping the running one
+pub fn start(app: &AppHandle, db: &DbConnection, charge_code: &str) -> std::result::Result<TimerEntry, String> {
+    let charge_code = charge_code.trim();
+    if charge_code.is_empty() {
+        return Err("Charge code cannot be empty".to_string());
+    }
+
+    flush_tracking(app);
+    let entry = {
+        let mut conn = db.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now().timestamp();
+        finish(&mut conn, now)?;
+        let running = RunningTimer { charge_code: charge_code.to_string(), started_at: now };
+        let value = serde_json::to_string(&running).map_err(|e| e.to_string())?;
+        set_setting(&conn, RUNNING_KEY, &value).map_err(|e| e.to_string())?;
+        TimerEntry { charge_code: running.charge_code, started_at: now, ended_at: None, duration_seconds: 0 }
+    };
+
+    emit(app, Some(&entry));
+    Ok(entry)
+}
+
+/// Stops the running timer, returning it
+pub fn stop(app: &AppHandle, db: &DbConnection) -> std::result::Result<Option<TimerEntry>, String> {
+    flush_tracking(app);
+    let stopped = {
+        let mut conn = db.lock().map_err(|e| e.to_string())?;
+        finish(&mut conn, Utc::now().timestamp())?
+    };
+
+    if stopped.is_some() {
+        emit(app, None);
+    }
+    Ok(stopped)
+}
+
+/// Closes a timer left running when the app last quit, at the end of the
+/// last activity tracked after it started
+pub fn close_stale(db: &DbConnection) {
+    let closed = db.lock().map_err(|e| e.to_string()).and_then(|mut conn| {
+        let Some(running) = get_running(&conn).map_err(|e| e.to_string())? else {
+            return Ok(None);
+        };
+        let last_seen: Option<i64> = conn
+            .query_row(
+                "SELECT MAX(timestamp + duration_seconds) FROM activities
+                 WHERE is_manual = 0 AND timestamp + duration_seconds > ?1",
+                [running.started_at],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        finish(&mut conn, last_seen.unwrap_or(running.started_at))
+    });
+    match closed {
+        Ok(Some(entry)) => println!("✅ Closed the {} timer left running ({}s recorded)", entry.charge_code, entry.duration_seconds),
+        Ok(None) => {}
+        Err(e) => eprintln!("❌ Failed to close the running timer: {}", e),
+    }
+}
+
+fn emit(app: &AppHandle, running: Option<&TimerEntry>) {
+    if let Err(e) = app.emit(TIMER_EVENT, running) {
+        eprintln!("❌ Failed to emit timer event: {}", e);
+    }
+}
+
+/// What a hotkey press does: switch to `charge_code`, or stop it if it is
+/// the timer already running
+fn toggle(app: &AppHandle, charge_code: &str) {
+    let db = app.state::<DbConnection>();
+    let running = db.lock().ok().and_then(|conn| get_running(&conn).ok().flatten());
+
+    if running.as_ref().is_some_and(|entry| entry.charge_code == charge_code) {
+        match stop(app, db.inner()) {
+            Ok(Some(entry)) => notifications::notify(
+                app,
+                NotificationKind::TimerSwitched,
+                "Timer stopped",
+                &format!("{}: {}", entry.charge_code, duration(entry.duration_seconds)),
+            ),
+            Ok(None) => {}
+            Err(e) => eprintln!("❌ Failed to stop timer: {}", e),
+        }
+        return;
+    }
+
+    match start(app, db.inner(), charge_code) {
+        Ok(entry) => {
+            let body = match running {
+                Some(previous) => format!(
+                    "Billing {} (stopped {} after {})",
+                    entry.charge_code,
+                    previous.charge_code,
+                    duration(previous.duration_seconds)
+                ),
+                None => format!("Billing {}", entry.charge_code),
+            };
+            notifications::notify(app, NotificationKind::TimerSwitched, "Timer started", &body);
+        }
+        Err(e) => eprintln!("❌ Failed to start timer for {}: {}", charge_code, e),
+    }
+}
+
+fn duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// The saved bindings; an unreadable value counts as none
+pub fn load_hotkeys(conn: &Connection) -> Result<Vec<TimerHotkey>> {
+    Ok(get_setting(conn, HOTKEYS_KEY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+pub fn get_hotkeys(app: &AppHandle, conn: &Connection) -> Result<Vec<TimerHotkeyStatus>> {
+    Ok(load_hotkeys(conn)?
+        .into_iter()
+        .map(|hotkey| TimerHotkeyStatus {
+            registered: Shortcut::from_str(&hotkey.shortcut).is_ok_and(|s| app.global_shortcut().is_registered(s)),
+            shortcut: hotkey.shortcut,
+            charge_code: hotkey.charge_code,
+        })
+        .collect())
+}
+
+fn register(app: &AppHandle, shortcut: Shortcut, charge_code: String) -> std::result::Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _, event| {
+            if event.state == ShortcutState::Pressed {
+                toggle(app, &charge_code);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+fn register_all(app: &AppHandle, hotkeys: &[(Shortcut, TimerHotkey)]) -> Vec<String> {
+    hotkeys
+        .iter()
+        .filter_map(|(shortcut, hotkey)| {
+            register(app, *shortcut, hotkey.charge_code.clone())
+                .err()
+                .map(|e| format!("{} is taken by another application ({})", hotkey.shortcut, e))
+        })
+        .collect()
+}
+
+fn unregister_all(app: &AppHandle, hotkeys: &[(Shortcut, TimerHotkey)]) {
+    for (shortcut, _) in hotkeys {
+        if app.global_shortcut().is_registered(*shortcut) {
+            let _ = app.global_shortcut().unregister(*shortcut);
+        }
+    }
+}
+
+/// Bindings with their parsed shortcuts, skipping ones that don't parse
+fn parsed(hotkeys: Vec<TimerHotkey>) -> Vec<(Shortcut, TimerHotkey)> {
+    hotkeys
+        .into_iter()
+        .filter_map(|hotkey| Shortcut::from_str(&hotkey.shortcut).ok().map(|shortcut| (shortcut, hotkey)))
+        .collect()
+}
+
+/// Registers the saved bindings at startup. A shortcut another application
+/// holds is skipped and shows up as unregistered in `get_hotkeys`.
+pub fn register_saved(app: &AppHandle, db: &DbConnection) {
+    let hotkeys = match db.lock() {
+        Ok(conn) => load_hotkeys(&conn).unwrap_or_default(),
+        Err(_) => return,
+    };
+    for conflict in register_all(app, &parsed(hotkeys)) {
+        eprintln!("❌ Timer hotkey not registered: {}", conflict);
+    }
+}
+
+/// Replaces the bindings, registering the new shortcuts before they are
+/// saved. On any conflict nothing changes and the error lists them all.
+pub fn save_hotkeys(app: &AppHandle, db: &DbConnection, hotkeys: Vec<TimerHotkey>) -> std::result::Result<Vec<TimerHotkeyStatus>, String> {
+    let mut bindings: Vec<(Shortcut, TimerHotkey)> = Vec::new();
+    let mut conflicts = Vec::new();
+    for hotkey in hotkeys {
+        let hotkey = TimerHotkey {
+            shortcut: hotkey.shortcut.trim().to_string(),
+            charge_code: hotkey.charge_code.trim().to_string(),
+        };
+        if hotkey.charge_code.is_empty() {
+            return Err(format!("{} needs a charge code", hotkey.shortcut));
+        }
+        let shortcut = Shortcut::from_str(&hotkey.shortcut)
+            .map_err(|e| format!("Invalid shortcut \"{}\": {}", hotkey.shortcut, e))?;
+        if let Some((_, other)) = bindings.iter().find(|(s, _)| s.id() == shortcut.id()) {
+            conflicts.push(format!("{} is bound to both {} and {}", hotkey.shortcut, other.charge_code, hotkey.charge_code));
+            continue;
+        }
+        bindings.push((shortcut, hotkey));
+    }
+
+    let previous = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        parsed(load_hotkeys(&conn).map_err(|e| e.to_string())?)
+    };
+    for (shortcut, hotkey) in &bindings {
+        let ours = previous.iter().any(|(s, _)| s.id() == shortcut.id());
+        if !ours && app.global_shortcut().is_registered(*shortcut) {
+            conflicts.push(format!("{} is already used elsewhere in the app", hotkey.shortcut));
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(format!("Hotkey conflicts: {}", conflicts.join("; ")));
+    }
+
+    unregister_all(app, &previous);
+    let failed = register_all(app, &bindings);
+    if !failed.is_empty() {
+        unregister_all(app, &bindings);
+        register_all(app, &previous);
+        return Err(format!("Hotkey conflicts: {}", failed.join("; ")));
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let hotkeys: Vec<TimerHotkey> = bindings.into_iter().map(|(_, hotkey)| hotkey).collect();
+    let value = serde_json::to_string(&hotkeys).map_err(|e| e.to_string())?;
+    set_setting(&conn, HOTKEYS_KEY, &value).map_err(|e| e.to_string())?;
+    println!("✅ Saved {} timer hotkey(s)", hotkeys.len());
+    get_hotkeys(app, &conn).map_err(|e| e.to_string())
+}
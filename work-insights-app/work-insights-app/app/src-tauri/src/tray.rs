@@ -0,0 +1,69 @@
+// Tray icon
+// The tooltip names the app and any state the user should be able to see at
+// a glance without opening the window, such as a pause or privacy mode being
+// on. The menu's pause and resume items are handled here as well.
+
+use chrono::{Local, TimeZone};
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::AppState;
+use crate::database::DbConnection;
+use crate::pause;
+use crate::privacy::{self, PrivacyMode};
+
+pub const TRAY_ID: &str = "main";
+const APP_NAME: &str = "Work Insights";
+/// Lengths offered under "Pause tracking", as `pause_<minutes>` menu items
+pub const PAUSE_MINUTES: [i64; 3] = [15, 30, 60];
+
+pub fn tooltip(conn: &Connection) -> String {
+    let mut parts = vec![APP_NAME.to_string()];
+    if let Some(until) = pause::paused_until(conn).ok().flatten() {
+        match Local.timestamp_opt(until, 0).single() {
+            Some(until) => parts.push(format!("Paused until {}", until.format("%H:%M"))),
+            None => parts.push("Paused".to_string()),
+        }
+    }
+    match privacy::get_mode(conn).unwrap_or_default() {
+        PrivacyMode::Off => {}
+        PrivacyMode::Omit => parts.push("Privacy mode (no window titles)".to_string()),
+        PrivacyMode::Hash => parts.push("Privacy mode (hashed window titles)".to_string()),
+    }
+    parts.join(" · ")
+}
+
+/// Handles a `pause_<minutes>` menu item
+pub fn pause_tracking(app: &AppHandle, minutes: i64) {
+    let state = app.state::<AppState>();
+    let db = app.state::<DbConnection>();
+    if let Err(e) = pause::pause(app, &db, &state.monitor, minutes) {
+        eprintln!("❌ Failed to pause tracking: {}", e);
+    }
+}
+
+/// Handles the "Resume tracking" menu item, ending a pause early
+pub fn resume_tracking(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let db = app.state::<DbConnection>();
+    if let Err(e) = pause::cancel(app, &db) {
+        eprintln!("❌ Failed to end pause: {}", e);
+    }
+    let Ok(monitor) = state.monitor.lock() else {
+        return;
+    };
+    monitor.start();
+}
+
+/// Brings the tooltip in line with the current settings
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let Some(tooltip) = app.try_state::<DbConnection>().and_then(|db| db.lock().ok().map(|conn| tooltip(&conn))) else {
+        return;
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        eprintln!("❌ Failed to update tray tooltip: {}", e);
+    }
+}
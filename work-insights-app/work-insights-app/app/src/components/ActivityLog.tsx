@@ -0,0 +1,206 @@
+import { useEffect, useState } from 'react';
+import { tauriService } from '../services/tauri';
+import { formatDuration } from '../utils/formatters';
+import type { Activity, ActivityUpdate, Category, Project } from '../types';
+
+// Today's activities with the fixes the backend supports: renaming the app,
+// setting the category or project, deleting time tracked by accident and
+// merging several activities into the earliest of them.
+
+const startOfToday = () => {
+  const start = new Date();
+  start.setHours(0, 0, 0, 0);
+  return Math.floor(start.getTime() / 1000);
+};
+
+const buttonStyle = {
+  padding: '4px 10px',
+  borderRadius: '6px',
+  border: '1px solid rgba(255, 255, 255, 0.3)',
+  background: 'rgba(255, 255, 255, 0.1)',
+  color: '#ffffff',
+  fontSize: '12px',
+  cursor: 'pointer',
+};
+
+const inputStyle = {
+  padding: '4px 8px',
+  borderRadius: '6px',
+  border: '1px solid rgba(255, 255, 255, 0.3)',
+  background: 'rgba(0, 0, 0, 0.2)',
+  color: '#ffffff',
+  fontSize: '13px',
+};
+
+export const ActivityLog = () => {
+  const [activities, setActivities] = useState<Activity[]>([]);
+  const [categories, setCategories] = useState<Category[]>([]);
+  const [projects, setProjects] = useState<Project[]>([]);
+  const [selected, setSelected] = useState<number[]>([]);
+  const [editing, setEditing] = useState<number | null>(null);
+  const [draft, setDraft] = useState<ActivityUpdate>({});
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchActivities = async () => {
+    try {
+      const now = Math.floor(Date.now() / 1000);
+      const today = await tauriService.getActivities(startOfToday(), now);
+      setActivities(today.reverse());
+      setSelected((ids) => ids.filter((id) => today.some((activity) => activity.id === id)));
+    } catch (error) {
+      console.error('Failed to get activities:', error);
+    }
+  };
+
+  useEffect(() => {
+    fetchActivities();
+    tauriService.listCategories().then(setCategories).catch(console.error);
+    tauriService.listProjects().then(setProjects).catch(console.error);
+  }, []);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await action();
+    } catch (error) {
+      setError(String(error));
+    }
+    await fetchActivities();
+  };
+
+  const startEditing = (activity: Activity) => {
+    setEditing(activity.id ?? null);
+    setDraft({
+      app_name: activity.app_name,
+      category: activity.category ?? '',
+      project: activity.project ?? '',
+    });
+  };
+
+  const saveEdit = (activityId: number) =>
+    run(async () => {
+      await tauriService.updateActivity(activityId, draft);
+      setEditing(null);
+    });
+
+  const remove = (activity: Activity) => {
+    if (activity.id === undefined) return;
+    if (!confirm(`Delete ${formatDuration(activity.duration_seconds)} of ${activity.app_name}?`)) return;
+    run(() => tauriService.deleteActivity(activity.id as number));
+  };
+
+  const mergeSelected = () =>
+    run(async () => {
+      await tauriService.mergeActivities(selected);
+      setSelected([]);
+    });
+
+  const toggle = (activityId: number) => {
+    setSelected((ids) => (ids.includes(activityId) ? ids.filter((id) => id !== activityId) : [...ids, activityId]));
+  };
+
+  return (
+    <div style={{
+      marginTop: '32px',
+      background: 'rgba(255, 255, 255, 0.1)',
+      backdropFilter: 'blur(10px)',
+      borderRadius: '16px',
+      padding: '32px',
+      border: '1px solid rgba(255, 255, 255, 0.2)',
+      boxShadow: '0 8px 32px rgba(0, 0, 0, 0.1)'
+    }}>
+      <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', marginBottom: '16px' }}>
+        <h2 style={{ fontSize: '24px', fontWeight: '700', color: '#ffffff' }}>
+          Today's Activities
+        </h2>
+        <div style={{ display: 'flex', gap: '8px' }}>
+          <button style={buttonStyle} onClick={fetchActivities}>Refresh</button>
+          <button
+            style={{ ...buttonStyle, opacity: selected.length < 2 ? 0.5 : 1 }}
+            disabled={selected.length < 2}
+            onClick={mergeSelected}
+          >
+            Merge selected ({selected.length})
+          </button>
+        </div>
+      </div>
+
+      {error && (
+        <p style={{ color: '#fecaca', fontSize: '13px', marginBottom: '12px' }}>{error}</p>
+      )}
+
+      <datalist id="activity-projects">
+        {projects.map((project) => <option key={project.id} value={project.name} />)}
+      </datalist>
+
+      {activities.length === 0 ? (
+        <p style={{ color: 'rgba(255, 255, 255, 0.7)', fontSize: '14px' }}>Nothing tracked yet today</p>
+      ) : (
+        <div style={{ display: 'flex', flexDirection: 'column', gap: '6px', maxHeight: '420px', overflowY: 'auto' }}>
+          {activities.map((activity) => {
+            const id = activity.id as number;
+            return (
+              <div key={id} style={{
+                display: 'flex',
+                alignItems: 'center',
+                gap: '12px',
+                padding: '8px 12px',
+                borderRadius: '8px',
+                background: 'rgba(255, 255, 255, 0.05)',
+                color: '#ffffff',
+                fontSize: '13px'
+              }}>
+                <input type="checkbox" checked={selected.includes(id)} onChange={() => toggle(id)} />
+                <span style={{ width: '64px', fontFamily: 'monospace', opacity: 0.8 }}>
+                  {new Date(activity.timestamp * 1000).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}
+                </span>
+
+                {editing === id ? (
+                  <>
+                    <input
+                      style={{ ...inputStyle, flex: '1' }}
+                      value={draft.app_name ?? ''}
+                      onChange={(e) => setDraft({ ...draft, app_name: e.target.value })}
+                    />
+                    <select
+                      style={inputStyle}
+                      value={draft.category ?? ''}
+                      onChange={(e) => setDraft({ ...draft, category: e.target.value })}
+                    >
+                      <option value="">Uncategorized</option>
+                      {categories.map((category) => (
+                        <option key={category.id} value={category.name}>{category.name}</option>
+                      ))}
+                    </select>
+                    <input
+                      style={inputStyle}
+                      list="activity-projects"
+                      placeholder="Project"
+                      value={draft.project ?? ''}
+                      onChange={(e) => setDraft({ ...draft, project: e.target.value })}
+                    />
+                    <button style={buttonStyle} onClick={() => saveEdit(id)}>Save</button>
+                    <button style={buttonStyle} onClick={() => setEditing(null)}>Cancel</button>
+                  </>
+                ) : (
+                  <>
+                    <span style={{ flex: '1', overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }}>
+                      <strong>{activity.app_name}</strong>
+                      {activity.window_title && <span style={{ opacity: 0.7 }}> — {activity.window_title}</span>}
+                    </span>
+                    <span style={{ opacity: 0.8 }}>{activity.category ?? 'Uncategorized'}</span>
+                    {activity.project && <span style={{ opacity: 0.8 }}>📁 {activity.project}</span>}
+                    {activity.is_edited && <span style={{ opacity: 0.6, fontSize: '11px' }}>edited</span>}
+                    <span style={{ width: '56px', textAlign: 'right' }}>{formatDuration(activity.duration_seconds)}</span>
+                    <button style={buttonStyle} onClick={() => startEditing(activity)}>Edit</button>
+                    <button style={buttonStyle} onClick={() => remove(activity)}>Delete</button>
+                  </>
+                )}
+              </div>
+            );
+          })}
+        </div>
+      )}
+    </div>
+  );
+};
@@ -3,6 +3,9 @@ import { useTrackingStore } from '../store/trackingStore';
 import { formatDuration } from '../utils/formatters';
 import { invoke } from '@tauri-apps/api/core';
 import { autostartService } from '../services/autostart';
+import { tauriService } from '../services/tauri';
+import { ActivityLog } from './ActivityLog';
+import type { CurrentSegment, Forecast, LocalApiInfo } from '../types';
 
 export const Dashboard = () => {
   const { 
@@ -20,6 +23,9 @@ export const Dashboard = () => {
   const [currentTime, setCurrentTime] = useState<Date>(new Date());
   const [autostartEnabled, setAutostartEnabled] = useState<boolean>(false);
   const [showSettings, setShowSettings] = useState<boolean>(false);
+  const [forecast, setForecast] = useState<Forecast | null>(null);
+  const [segment, setSegment] = useState<CurrentSegment | null>(null);
+  const [localApi, setLocalApi] = useState<LocalApiInfo | null>(null);
 
   useEffect(() => {
     // Update current time every second
@@ -29,6 +35,8 @@ export const Dashboard = () => {
 
     // Check autostart status
     checkAutostartStatus();
+    fetchForecast();
+    tauriService.getLocalApiInfo().then(setLocalApi).catch(console.error);
 
     return () => clearInterval(timeInterval);
   }, []);
@@ -42,6 +50,34 @@ export const Dashboard = () => {
     }
   };
 
+  const fetchForecast = async () => {
+    try {
+      setForecast(await invoke<Forecast>('get_forecast'));
+    } catch (error) {
+      console.error('Failed to get forecast:', error);
+    }
+  };
+
+  const toggleLocalApi = async () => {
+    if (!localApi) return;
+    try {
+      await tauriService.setSetting('local_api_enabled', String(!localApi.enabled));
+      setLocalApi({ ...localApi, enabled: !localApi.enabled });
+    } catch (error) {
+      console.error('Failed to change the local API setting:', error);
+    }
+  };
+
+  const regenerateToken = async () => {
+    if (!localApi) return;
+    if (!confirm('Scripts using the current token will stop working. Create a new token?')) return;
+    try {
+      setLocalApi({ ...localApi, token: await tauriService.regenerateLocalApiToken() });
+    } catch (error) {
+      console.error('Failed to create a new token:', error);
+    }
+  };
+
   const toggleAutostart = async () => {
     try {
       if (autostartEnabled) {
@@ -63,12 +99,14 @@ export const Dashboard = () => {
     fetchTodayStats();
     setLastUpdate(new Date());
     updateActivityCount();
+    fetchSegment();
     
     // Set up interval for updates
     const interval = setInterval(() => {
       fetchTodayStats();
       setLastUpdate(new Date());
       updateActivityCount();
+      fetchSegment();
     }, 5000); // Update every 5 seconds for better responsiveness
 
     return () => clearInterval(interval);
@@ -83,6 +121,15 @@ export const Dashboard = () => {
     }
   };
 
+  // The activity still being accumulated, which isn't in today's stats yet
+  const fetchSegment = async () => {
+    try {
+      setSegment(await tauriService.getCurrentSegment());
+    } catch (error) {
+      console.error('Failed to get current segment:', error);
+    }
+  };
+
   const handleToggleTracking = async () => {
     if (isTracking) {
       await stopTracking();
@@ -92,6 +139,7 @@ export const Dashboard = () => {
     await fetchTodayStats();
     setLastUpdate(new Date());
     updateActivityCount();
+    fetchSegment();
   };
 
   return (
@@ -211,6 +259,21 @@ export const Dashboard = () => {
               ? '✅ Tracking active - Your activity is being monitored' 
               : '⏸️ Tracking paused - No data is being collected'}
           </span>
+          {isTracking && segment && (
+            <span style={{
+              marginLeft: 'auto',
+              color: 'rgba(255, 255, 255, 0.9)',
+              fontSize: '13px',
+              overflow: 'hidden',
+              textOverflow: 'ellipsis',
+              whiteSpace: 'nowrap'
+            }}>
+              Now: <strong>{segment.is_idle ? 'Idle' : segment.app_name}</strong>
+              {segment.category && ` (${segment.category})`}
+              {' '}for {formatDuration(segment.elapsed_seconds)}
+              {!segment.is_idle && ` • ${formatDuration(segment.app_today_seconds)} today`}
+            </span>
+          )}
         </div>
 
         {/* Stats Grid */}
@@ -405,6 +468,46 @@ export const Dashboard = () => {
           )}
         </div>
 
+        {/* Projected Workload */}
+        {forecast && forecast.history_weeks > 0 && (
+          <div style={{
+            marginTop: '32px',
+            background: 'rgba(255, 255, 255, 0.1)',
+            backdropFilter: 'blur(10px)',
+            borderRadius: '16px',
+            padding: '32px',
+            border: '1px solid rgba(255, 255, 255, 0.2)',
+            boxShadow: '0 8px 32px rgba(0, 0, 0, 0.1)'
+          }}>
+            <h2 style={{ fontSize: '24px', fontWeight: '700', color: '#ffffff', marginBottom: '8px' }}>
+              Projected Workload
+            </h2>
+            <p style={{ fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)', marginBottom: '24px' }}>
+              {formatDuration(forecast.total_seconds)} expected next week, based on the last {forecast.history_weeks} week{forecast.history_weeks === 1 ? '' : 's'}
+            </p>
+            <div style={{ display: 'flex', flexDirection: 'column', gap: '12px' }}>
+              {forecast.category_totals.slice(0, 5).map((total) => (
+                <div key={total.category}>
+                  <div style={{ display: 'flex', justifyContent: 'space-between', color: '#ffffff', fontSize: '14px', marginBottom: '4px' }}>
+                    <span>{total.category}</span>
+                    <span>{formatDuration(total.total_seconds)}</span>
+                  </div>
+                  <div style={{ height: '8px', borderRadius: '4px', background: 'rgba(255, 255, 255, 0.1)' }}>
+                    <div style={{
+                      height: '100%',
+                      borderRadius: '4px',
+                      background: '#10b981',
+                      width: `${(total.total_seconds / forecast.category_totals[0].total_seconds) * 100}%`
+                    }} />
+                  </div>
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
+
+        <ActivityLog />
+
         {/* Privacy Notice */}
         <div style={{
           marginTop: '32px',
@@ -509,6 +612,60 @@ export const Dashboard = () => {
                 </button>
               </div>
 
+              {/* Local API */}
+              {localApi && (
+                <div style={{
+                  padding: '16px',
+                  background: 'rgba(255, 255, 255, 0.05)',
+                  borderRadius: '8px',
+                  marginBottom: '12px'
+                }}>
+                  <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between' }}>
+                    <div>
+                      <div style={{ fontSize: '15px', fontWeight: '600', color: '#ffffff', marginBottom: '4px' }}>
+                        🔌 Local API
+                      </div>
+                      <div style={{ fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)' }}>
+                        Lets scripts on this computer read tracker state at {localApi.base_url}. Takes effect on restart.
+                      </div>
+                    </div>
+                    <button
+                      onClick={toggleLocalApi}
+                      style={{
+                        padding: '8px 16px',
+                        borderRadius: '8px',
+                        border: 'none',
+                        cursor: 'pointer',
+                        fontWeight: '600',
+                        fontSize: '13px',
+                        background: localApi.enabled ? '#10b981' : '#6b7280',
+                        color: '#ffffff',
+                      }}
+                    >
+                      {localApi.enabled ? 'Enabled' : 'Disabled'}
+                    </button>
+                  </div>
+                  <div style={{ display: 'flex', alignItems: 'center', gap: '8px', marginTop: '12px', fontSize: '13px', color: '#ffffff' }}>
+                    <span style={{ opacity: 0.7 }}>Token</span>
+                    <code style={{ flex: '1', fontFamily: 'monospace', wordBreak: 'break-all' }}>{localApi.token}</code>
+                    <button
+                      onClick={regenerateToken}
+                      style={{
+                        padding: '4px 10px',
+                        borderRadius: '6px',
+                        border: '1px solid rgba(255, 255, 255, 0.3)',
+                        background: 'rgba(255, 255, 255, 0.1)',
+                        color: '#ffffff',
+                        fontSize: '12px',
+                        cursor: 'pointer',
+                      }}
+                    >
+                      New token
+                    </button>
+                  </div>
+                </div>
+              )}
+
               {/* Background Running Info */}
               <div style={{
                 padding: '16px',
@@ -0,0 +1,118 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { localApi } from '../services/tauri';
+import { FocusStatus } from '../types';
+
+// Always-on-top countdown shown in its own window while a focus session runs.
+// The backend opens and closes the window; this only renders the session.
+
+const formatClock = (seconds: number): string => {
+  const clamped = Math.max(0, seconds);
+  const hours = Math.floor(clamped / 3600);
+  const minutes = Math.floor((clamped % 3600) / 60);
+  const secs = clamped % 60;
+  const pad = (n: number) => n.toString().padStart(2, '0');
+
+  return hours > 0 ? `${hours}:${pad(minutes)}:${pad(secs)}` : `${pad(minutes)}:${pad(secs)}`;
+};
+
+export const FocusTimer = () => {
+  const [status, setStatus] = useState<FocusStatus | null>(null);
+  const [now, setNow] = useState<number>(Math.floor(Date.now() / 1000));
+
+  useEffect(() => {
+    const fetchStatus = async () => {
+      try {
+        setStatus(await localApi.getFocus());
+      } catch (error) {
+        console.error('Failed to get focus status:', error);
+      }
+    };
+
+    fetchStatus();
+    const unlisten = listen('focus-session-changed', fetchStatus);
+
+    const tick = setInterval(() => {
+      setNow(Math.floor(Date.now() / 1000));
+    }, 1000);
+
+    return () => {
+      clearInterval(tick);
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  const stopSession = async () => {
+    try {
+      await invoke('end_focus_session');
+    } catch (error) {
+      console.error('Failed to end focus session:', error);
+    }
+  };
+
+  const session = status?.session;
+  // Timed sessions count down; open-ended ones count up
+  const clock = session
+    ? session.ends_at !== null
+      ? formatClock(session.ends_at - now)
+      : formatClock(now - session.started_at)
+    : '--:--';
+
+  return (
+    <div
+      data-tauri-drag-region
+      style={{
+        height: '100vh',
+        display: 'flex',
+        alignItems: 'center',
+        justifyContent: 'space-between',
+        gap: '12px',
+        padding: '12px 16px',
+        background: 'linear-gradient(135deg, #667eea 0%, #764ba2 100%)',
+        userSelect: 'none',
+        cursor: 'move'
+      }}
+    >
+      <div data-tauri-drag-region style={{ minWidth: 0 }}>
+        <div data-tauri-drag-region style={{ fontSize: '11px', color: 'rgba(255, 255, 255, 0.7)', whiteSpace: 'nowrap', overflow: 'hidden', textOverflow: 'ellipsis' }}>
+          {session?.label || 'FOCUS'}
+        </div>
+        <div data-tauri-drag-region style={{ fontSize: '28px', fontWeight: '700', color: '#ffffff', fontFamily: 'monospace' }}>
+          {clock}
+        </div>
+      </div>
+
+      <div style={{ display: 'flex', gap: '6px' }}>
+        <button
+          onClick={stopSession}
+          disabled={!session}
+          style={{
+            background: 'rgba(255, 255, 255, 0.2)',
+            border: '1px solid rgba(255, 255, 255, 0.3)',
+            borderRadius: '8px',
+            color: '#ffffff',
+            padding: '6px 10px',
+            fontSize: '12px',
+            cursor: 'pointer'
+          }}
+        >
+          Stop
+        </button>
+        <button
+          onClick={() => invoke('hide_focus_timer')}
+          title="Hide until the next session"
+          style={{
+            background: 'transparent',
+            border: 'none',
+            color: 'rgba(255, 255, 255, 0.7)',
+            fontSize: '16px',
+            cursor: 'pointer'
+          }}
+        >
+          ×
+        </button>
+      </div>
+    </div>
+  );
+};
@@ -0,0 +1,94 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { FrictionPrompt as Prompt } from '../types';
+
+// Full-screen "Are you sure?" prompt shown before a distracting app counts.
+// The backend opens the window and decides when going ahead is allowed;
+// this only shows the wait and sends the answer.
+
+export const FrictionPrompt = () => {
+  const [prompt, setPrompt] = useState<Prompt | null>(null);
+  const [now, setNow] = useState<number>(Math.floor(Date.now() / 1000));
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const fetchPrompt = async () => {
+      try {
+        setPrompt(await invoke<Prompt | null>('get_friction_prompt'));
+      } catch (error) {
+        console.error('Failed to get friction prompt:', error);
+      }
+    };
+
+    fetchPrompt();
+    const unlisten = listen('friction-prompt-changed', fetchPrompt);
+
+    const tick = setInterval(() => {
+      setNow(Math.floor(Date.now() / 1000));
+    }, 1000);
+
+    return () => {
+      clearInterval(tick);
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  const answer = async (proceed: boolean) => {
+    if (!prompt) return;
+    try {
+      await invoke('resolve_friction_prompt', { promptId: prompt.id, proceed });
+    } catch (error) {
+      setError(String(error));
+    }
+  };
+
+  const remaining = prompt ? Math.max(0, prompt.ready_at - now) : 0;
+  const buttonStyle = {
+    border: '1px solid rgba(255, 255, 255, 0.3)',
+    borderRadius: '8px',
+    color: '#ffffff',
+    padding: '12px 24px',
+    fontSize: '16px',
+    cursor: 'pointer'
+  };
+
+  return (
+    <div
+      style={{
+        height: '100vh',
+        display: 'flex',
+        flexDirection: 'column',
+        alignItems: 'center',
+        justifyContent: 'center',
+        gap: '24px',
+        background: 'linear-gradient(135deg, #667eea 0%, #764ba2 100%)',
+        color: '#ffffff',
+        userSelect: 'none'
+      }}
+    >
+      <div style={{ fontSize: '36px', fontWeight: '700' }}>Are you sure?</div>
+      <div style={{ fontSize: '18px', color: 'rgba(255, 255, 255, 0.8)' }}>
+        {prompt ? `You're about to open ${prompt.app_name}.` : 'Nothing to confirm.'}
+      </div>
+      {error && <div style={{ fontSize: '14px', color: '#fecaca' }}>{error}</div>}
+
+      <div style={{ display: 'flex', gap: '12px' }}>
+        <button
+          onClick={() => answer(false)}
+          disabled={!prompt}
+          style={{ ...buttonStyle, background: 'rgba(255, 255, 255, 0.3)' }}
+        >
+          Back out
+        </button>
+        <button
+          onClick={() => answer(true)}
+          disabled={!prompt || remaining > 0}
+          style={{ ...buttonStyle, background: 'transparent', opacity: remaining > 0 ? 0.5 : 1 }}
+        >
+          {remaining > 0 ? `Go ahead in ${remaining}s` : 'Go ahead'}
+        </button>
+      </div>
+    </div>
+  );
+};
@@ -1,10 +1,18 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { FocusTimer } from "./components/FocusTimer";
+import { FrictionPrompt } from "./components/FrictionPrompt";
 import "./index.css";
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {window.location.hash === "#focus-timer" ? (
+      <FocusTimer />
+    ) : window.location.hash === "#friction" ? (
+      <FrictionPrompt />
+    ) : (
+      <App />
+    )}
   </React.StrictMode>,
 );
@@ -0,0 +1,157 @@
+// Generated by scripts/generate-api-client.mjs from the local API's OpenAPI
+// document, version 2.0.0. Don't edit by hand: change
+// src-tauri/src/api/openapi.rs and run `npm run generate:api`.
+//
+// The dashboard reads through `tauriTransport` in src/services/tauri.ts,
+// which answers in-process; scripts and tools outside the app use
+// `httpTransport` with the token from the app's settings.
+
+export interface ActivityChangedEvent {
+  app_name: string;
+  /** Unix seconds */
+  timestamp: number;
+  type: 'activity_changed';
+  window_title: string;
+}
+
+export interface ApiError {
+  error: string;
+}
+
+export type ApiEvent = ActivityChangedEvent | IdleStartedEvent | IdleEndedEvent | FocusSessionStartedEvent | FocusSessionEndedEvent;
+
+export interface CategoryTotal {
+  category: string;
+  total_seconds: number;
+}
+
+export interface FocusSession {
+  /** Unix seconds; null for an open-ended session */
+  ends_at: number | null;
+  label: string | null;
+  /** Unix seconds */
+  started_at: number;
+}
+
+export interface FocusSessionEndedEvent {
+  session: FocusSession;
+  /** Unix seconds */
+  timestamp: number;
+  type: 'focus_session_ended';
+}
+
+export interface FocusSessionStartedEvent {
+  session: FocusSession;
+  /** Unix seconds */
+  timestamp: number;
+  type: 'focus_session_started';
+}
+
+export interface FocusStatus {
+  active: boolean;
+  remaining_seconds: number | null;
+  session: FocusSession | null;
+}
+
+export interface IdleEndedEvent {
+  /** Unix seconds */
+  timestamp: number;
+  type: 'idle_ended';
+}
+
+export interface IdleStartedEvent {
+  /** Unix seconds */
+  timestamp: number;
+  type: 'idle_started';
+}
+
+/** Names the `/events` stream sends events under */
+export const EVENT_TYPES = ['activity_changed', 'idle_started', 'idle_ended', 'focus_session_started', 'focus_session_ended'] as const;
+
+export class LocalApiError extends Error {
+  constructor(
+    public readonly status: number,
+    message: string,
+  ) {
+    super(message);
+    this.name = 'LocalApiError';
+  }
+}
+
+/** A route's status and body */
+export interface LocalApiReply {
+  status: number;
+  body: string;
+}
+
+/** How the client reaches the API */
+export interface LocalApiTransport {
+  /** GETs `path` */
+  get(path: string): Promise<LocalApiReply>;
+  /** Opens the server-sent event stream at `path`, if the transport can */
+  events?(path: string): EventSource;
+}
+
+/** Requests over HTTP to the app at `baseUrl`, with the token from its settings */
+export function httpTransport(token: string, baseUrl = 'http://127.0.0.1:47615'): LocalApiTransport {
+  return {
+    async get(path) {
+      const response = await fetch(baseUrl + path, { headers: { Authorization: `Bearer ${token}` } });
+      return { status: response.status, body: await response.text() };
+    },
+    // EventSource can't send headers, so the token goes in the query
+    events(path) {
+      return new EventSource(baseUrl + path + '?token=' + encodeURIComponent(token));
+    },
+  };
+}
+
+export type LocalApiClient = ReturnType<typeof createLocalApiClient>;
+
+/** A client for the local API reached through `transport` */
+export function createLocalApiClient(transport: LocalApiTransport) {
+  async function request(path: string, read: 'json' | 'text'): Promise<unknown> {
+    const { status, body } = await transport.get(path);
+    if (status < 200 || status >= 300) {
+      let message = body;
+      try {
+        message = (JSON.parse(body) as ApiError).error ?? body;
+      } catch {
+        // Not JSON; use the text as is
+      }
+      throw new LocalApiError(status, message);
+    }
+    return read === 'json' ? JSON.parse(body) : body;
+  }
+
+  return {
+    /** Time per category so far today, largest first */
+    getCategories(): Promise<CategoryTotal[]> {
+      return request('/categories', 'json') as Promise<CategoryTotal[]>;
+    },
+
+    /** Tracker events as they happen, as server-sent events */
+    getEvents(onEvent: (event: ApiEvent) => void): EventSource {
+      if (!transport.events) throw new Error('This transport can\'t stream /events');
+      const source = transport.events('/events');
+      const handle = (message: MessageEvent<string>) => onEvent(JSON.parse(message.data) as ApiEvent);
+      for (const type of EVENT_TYPES) source.addEventListener(type, handle);
+      return source;
+    },
+
+    /** The running focus session, if any */
+    getFocus(): Promise<FocusStatus> {
+      return request('/focus', 'json') as Promise<FocusStatus>;
+    },
+
+    /** Today's numbers in the Prometheus text exposition format */
+    getMetrics(): Promise<string> {
+      return request('/metrics', 'text') as Promise<string>;
+    },
+
+    /** This document */
+    getOpenApi(): Promise<Record<string, unknown>> {
+      return request('/openapi.json', 'json') as Promise<Record<string, unknown>>;
+    },
+  };
+}
@@ -1,5 +1,43 @@
 import { invoke } from '@tauri-apps/api/core';
-import { TodayStats, Activity } from '../types';
+import { createLocalApiClient, LocalApiReply, LocalApiTransport } from './localApi';
+import {
+  TodayStats,
+  Activity,
+  ActivityUpdate,
+  Category,
+  CompactResult,
+  CurrentSegment,
+  GapAnnotation,
+  DetectProjectsResult,
+  Project,
+  ProjectRule,
+  ProjectTimeReport,
+  TimelineGap,
+  CsvExportResult,
+  DataExportResult,
+  DataImportResult,
+  FrictionStats,
+  GoalStreaks,
+  LocalApiInfo,
+  MigrationResult,
+  NarrativeInsights,
+  StorageStatus,
+  TimerEntry,
+  TimerHotkey,
+  TimerHotkeyStatus,
+  WeeklyReport,
+  WeekTrends,
+} from '../types';
+
+/** Reaches the local API in-process, whether or not its HTTP server is on */
+export const tauriTransport: LocalApiTransport = {
+  get(path) {
+    return invoke<LocalApiReply>('local_api_request', { path });
+  },
+};
+
+/** The local API's generated client, as the app's windows use it */
+export const localApi = createLocalApiClient(tauriTransport);
 
 export const tauriService = {
   async startTracking(): Promise<void> {
@@ -18,11 +56,191 @@ export const tauriService = {
     return invoke('get_today_statistics');
   },
 
+  async getCurrentSegment(): Promise<CurrentSegment | null> {
+    return invoke('get_current_segment');
+  },
+
   async getActivities(startTimestamp: number, endTimestamp: number): Promise<Activity[]> {
     return invoke('get_activities', { startTimestamp, endTimestamp });
   },
 
+  /** Records offline work as active time; rejects when it overlaps tracked active time */
+  async addManualActivity(start: number, end: number, label: string, category?: string): Promise<Activity> {
+    return invoke('add_manual_activity', { start, end, label, category });
+  },
+
+  /** Untracked stretches of at least `minGapMinutes` (default 15) */
+  async getUntrackedGaps(startTimestamp: number, endTimestamp: number, minGapMinutes?: number): Promise<TimelineGap[]> {
+    return invoke('get_untracked_gaps', { startTimestamp, endTimestamp, minGapMinutes });
+  },
+
+  async annotateGap(start: number, end: number, label: string): Promise<GapAnnotation> {
+    return invoke('annotate_gap', { start, end, label });
+  },
+
+  async deleteGapAnnotation(annotationId: number): Promise<void> {
+    return invoke('delete_gap_annotation', { annotationId });
+  },
+
+  async listCategories(): Promise<Category[]> {
+    return invoke('list_categories');
+  },
+
+  async updateActivity(activityId: number, update: ActivityUpdate): Promise<Activity> {
+    return invoke('update_activity', { activityId, update });
+  },
+
+  async deleteActivity(activityId: number): Promise<void> {
+    return invoke('delete_activity', { activityId });
+  },
+
+  /** Folds the activities into the earliest one, adding up their durations */
+  async mergeActivities(activityIds: number[]): Promise<Activity> {
+    return invoke('merge_activities', { activityIds });
+  },
+
+  /** Folds runs of the same window into one activity and shrinks the database */
+  async compactActivities(startTimestamp?: number, endTimestamp?: number): Promise<CompactResult> {
+    return invoke('compact_activities', { startTimestamp, endTimestamp });
+  },
+
+  async listProjects(): Promise<Project[]> {
+    return invoke('list_projects');
+  },
+
+  async createProject(name: string, color?: string, hourlyRate?: number): Promise<Project> {
+    return invoke('create_project', { name, color, hourlyRate });
+  },
+
+  async renameProject(projectId: number, name: string): Promise<Project> {
+    return invoke('rename_project', { projectId, name });
+  },
+
+  async updateProject(projectId: number, color: string | null, hourlyRate: number | null): Promise<Project> {
+    return invoke('update_project', { projectId, color, hourlyRate });
+  },
+
+  async deleteProject(projectId: number): Promise<void> {
+    return invoke('delete_project', { projectId });
+  },
+
+  /** Active time per project, with billable amounts for projects with a rate */
+  async getProjectTime(startTimestamp: number, endTimestamp: number): Promise<ProjectTimeReport> {
+    return invoke('get_project_time', { startTimestamp, endTimestamp });
+  },
+
+  async getProjectRules(): Promise<ProjectRule[]> {
+    return invoke('get_project_rules');
+  },
+
+  async saveProjectRule(rule: ProjectRule): Promise<ProjectRule> {
+    return invoke('save_project_rule', { rule });
+  },
+
+  async deleteProjectRule(ruleId: number): Promise<void> {
+    return invoke('delete_project_rule', { ruleId });
+  },
+
+  /** Re-applies project rules and detection to history, all of it by default */
+  async detectProjects(startTimestamp?: number, endTimestamp?: number): Promise<DetectProjectsResult> {
+    return invoke('detect_projects', { startTimestamp, endTimestamp });
+  },
+
+  async exportActivitiesCsv(startTimestamp: number, endTimestamp: number, path: string): Promise<CsvExportResult> {
+    return invoke('export_activities_csv', { startTimestamp, endTimestamp, path });
+  },
+
+  /** Activities in the range (all of them when omitted) with settings and categories, for moving machines */
+  async exportDataJson(path: string, startTimestamp?: number, endTimestamp?: number): Promise<DataExportResult> {
+    return invoke('export_data_json', { startTimestamp, endTimestamp, path });
+  },
+
+  async importDataJson(path: string): Promise<DataImportResult> {
+    return invoke('import_data_json', { path });
+  },
+
+  /** Asks the configured language model (opt-in via `llm_insights_enabled`) to describe the week's aggregates */
+  async generateNarrativeInsights(weekStart: number): Promise<NarrativeInsights> {
+    return invoke('generate_narrative_insights', { weekStart });
+  },
+
+  /** Trends of the week starting on `weekStart` ("YYYY-MM-DD") against the week before */
+  async getWeekInsights(weekStart: string): Promise<WeekTrends> {
+    return invoke('get_week_insights', { weekStart });
+  },
+
+  /** Markdown report of the week starting on `weekStart` ("YYYY-MM-DD"), also saved to `path` when given */
+  async generateWeeklyReport(weekStart: string, path?: string): Promise<WeeklyReport> {
+    return invoke('generate_weekly_report', { weekStart, path });
+  },
+
+  /** How often each app was held behind the launch friction prompt and how it was answered */
+  async getFrictionStats(startTimestamp: number, endTimestamp: number): Promise<FrictionStats[]> {
+    return invoke('get_friction_stats', { startTimestamp, endTimestamp });
+  },
+
+  /** Days in a row on which every enabled goal held; days without tracking don't break a streak */
+  async getStreaks(): Promise<GoalStreaks> {
+    return invoke('get_streaks');
+  },
+
+  /** Starts a timer for the charge code, stopping the running one; stopped runs show up as activities of the charge code's project */
+  async startTimer(chargeCode: string): Promise<TimerEntry> {
+    return invoke('start_timer', { chargeCode });
+  },
+
+  async stopTimer(): Promise<TimerEntry | null> {
+    return invoke('stop_timer');
+  },
+
+  async getRunningTimer(): Promise<TimerEntry | null> {
+    return invoke('get_running_timer');
+  },
+
+  async getTimerHotkeys(): Promise<TimerHotkeyStatus[]> {
+    return invoke('get_timer_hotkeys');
+  },
+
+  /** Rejects listing every conflicting shortcut, leaving the previous hotkeys in place */
+  async saveTimerHotkeys(hotkeys: TimerHotkey[]): Promise<TimerHotkeyStatus[]> {
+    return invoke('save_timer_hotkeys', { hotkeys });
+  },
+
+  async getStorageStatus(): Promise<StorageStatus> {
+    return invoke('get_storage_status');
+  },
+
+  /**
+   * Copies the local activities not on the server in `postgres_url` yet and keeps syncing new ones.
+   * `fromStart` offers every local activity again.
+   */
+  async migrateToServer(fromStart = false): Promise<MigrationResult> {
+    return invoke('migrate_to_server', { fromStart });
+  },
+
   async getCurrentTime(): Promise<number> {
     return invoke('get_current_time');
   },
+
+  async getSetting(key: string): Promise<string | null> {
+    return invoke('get_setting', { key });
+  },
+
+  async getAllSettings(): Promise<Record<string, string>> {
+    return invoke('get_all_settings');
+  },
+
+  /** Rejects with a message when the value isn't valid for the key; resolves to the value as stored */
+  async setSetting(key: string, value: string): Promise<string> {
+    return invoke('set_setting', { key, value });
+  },
+
+  async getLocalApiInfo(): Promise<LocalApiInfo> {
+    return invoke('get_local_api_info');
+  },
+
+  /** Resolves to the new token; scripts using the old one are locked out */
+  async regenerateLocalApiToken(): Promise<string> {
+    return invoke('regenerate_local_api_token');
+  },
 };
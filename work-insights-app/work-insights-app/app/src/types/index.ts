@@ -1,3 +1,9 @@
+// Shapes the local API serves come from its generated client, which the
+// dashboard reads them through
+import type { CategoryTotal } from '../services/localApi';
+
+export type { ApiEvent, CategoryTotal, FocusSession, FocusStatus } from '../services/localApi';
+
 export interface Activity {
   id?: number;
   timestamp: number;
@@ -6,12 +12,488 @@ export interface Activity {
   duration_seconds: number;
   is_idle: boolean;
   category?: string;
+  algorithm_version?: number;
+  /** Entered by hand for time away from the computer */
+  is_manual?: boolean;
+  /** Detected from the window title, or set by hand */
+  project?: string;
+  /** Changed or merged by hand, so automatic passes leave it alone */
+  is_edited?: boolean;
+}
+
+export interface Category {
+  id: number;
+  name: string;
+  color: string;
+  rating: 'productive' | 'neutral' | 'distracting';
+}
+
+/** A label for untracked time; it doesn't count as activity */
+export interface GapAnnotation {
+  id: number;
+  start: number;
+  end: number;
+  label: string;
+  created_at: number;
+}
+
+/** A stretch of the timeline nothing was recorded in */
+export interface TimelineGap {
+  start: number;
+  end: number;
+  duration_seconds: number;
+  annotation: GapAnnotation | null;
+}
+
+/** Fields to change on an activity; an empty category or project clears it */
+export interface ActivityUpdate {
+  app_name?: string;
+  window_title?: string;
+  category?: string;
+  project?: string;
 }
 
 export interface TodayStats {
   active_time_seconds: number;
   idle_time_seconds: number;
   context_switches: number;
+  note?: DayNote | null;
+}
+
+export interface CurrentSegment {
+  app_name: string;
+  window_title: string | null;
+  is_idle: boolean;
+  category: string | null;
+  started_at: number;
+  elapsed_seconds: number;
+  app_today_seconds: number;
+}
+
+/** Where the local API listens and the token its requests need */
+export interface LocalApiInfo {
+  enabled: boolean;
+  port: number;
+  token: string;
+  base_url: string;
+}
+
+export interface HourlyBreakdown {
+  hour: number;
+  active_seconds: number;
+  idle_seconds: number;
+  top_app: string | null;
+  top_app_seconds: number;
+}
+
+export interface DailyStats {
+  date: string;
+  active_time_seconds: number;
+  idle_time_seconds: number;
+  context_switches: number;
+  top_categories: CategoryTotal[];
+}
+
+export interface PeriodStats {
+  start_date: string;
+  end_date: string;
+  active_time_seconds: number;
+  idle_time_seconds: number;
+  context_switches: number;
+  category_totals: CategoryTotal[];
+  days: DailyStats[];
+}
+
+export interface AppUsage {
+  app_name: string;
+  total_seconds: number;
+  session_count: number;
+  share: number;
+}
+
+export interface DayNote {
+  date: string;
+  content: string;
+  updated_at: number;
+}
+
+export interface FocusBlock {
+  app_name: string;
+  started_at: number;
+  active_seconds: number;
+}
+
+export interface SwitchingStats {
+  date: string;
+  active_seconds: number;
+  context_switches: number;
+  switches_per_hour: number;
+  focus_blocks: number;
+  average_block_seconds: number;
+  longest_block: FocusBlock | null;
+}
+
+export type CategoryRating = 'productive' | 'neutral' | 'distracting';
+
+export interface CategoryRule {
+  id: number | null;
+  category: string;
+  pattern: string;
+  match_field: 'app' | 'title' | 'any';
+  priority: number;
+  enabled: boolean;
+}
+
+export interface Project {
+  id: number;
+  name: string;
+  /** CSS color; null leaves it to the dashboard */
+  color: string | null;
+  hourly_rate: number | null;
+  created_at: number;
+}
+
+export interface ProjectTime {
+  project: string;
+  color: string | null;
+  total_seconds: number;
+  activity_count: number;
+  days: number;
+  share: number;
+  /** The time at the project's hourly rate, if it has one */
+  billable_amount: number | null;
+}
+
+export interface ProjectTimeReport {
+  projects: ProjectTime[];
+  unassigned_seconds: number;
+}
+
+export interface ProjectRule {
+  id: number | null;
+  /** Regular expression; `$1` in `project` refers to its first group */
+  pattern: string;
+  project: string;
+  match_field: 'app' | 'title' | 'any';
+  enabled: boolean;
+}
+
+export interface DetectProjectsResult {
+  activities_scanned: number;
+  activities_changed: number;
+}
+
+export interface StreakAlertRule {
+  id: number | null;
+  pattern: string;
+  threshold_minutes: number;
+  enabled: boolean;
+  snoozed_until: number | null;
+}
+
+export interface SettingsProfile {
+  version: number;
+  exported_at: number;
+  settings: Record<string, string>;
+  blocked_apps: string[];
+  categories: { name: string; color: string; rating: CategoryRating }[];
+  category_rules: CategoryRule[];
+  streak_rules: StreakAlertRule[];
+}
+
+export interface ProfileImportResult {
+  settings: number;
+  blocked_apps: number;
+  categories: number;
+  category_rules: number;
+  streak_rules: number;
+}
+
+export type IntegrityIssueKind =
+  | 'overlap'
+  | 'negative_duration'
+  | 'future_timestamp'
+  | 'orphaned_alert'
+  | 'corruption';
+
+export interface IntegrityIssue {
+  kind: IntegrityIssueKind;
+  table: string;
+  row_id: number | null;
+  detail: string;
+}
+
+export interface IntegrityReport {
+  checked_at: number;
+  issues: IntegrityIssue[];
+}
+
+export interface RepairResult {
+  dry_run: boolean;
+  activities_clamped: number;
+  activities_deleted: number;
+  alerts_deleted: number;
+  unrepaired: IntegrityIssue[];
+}
+
+export interface CompactResult {
+  activities_scanned: number;
+  activities_removed: number;
+  bytes_before: number;
+  bytes_after: number;
+}
+
+export interface Goal {
+  id: number | null;
+  metric: 'active_time' | 'category_time';
+  category: string | null;
+  comparison: 'at_least' | 'at_most';
+  target_minutes: number;
+  enabled: boolean;
+}
+
+export interface GoalProgress {
+  goal: Goal;
+  actual_seconds: number;
+  target_seconds: number;
+  ratio: number;
+  status: 'in_progress' | 'met' | 'exceeded';
+}
+
+export interface GoalStreaks {
+  /** Days in a row up to today, today included once its goals hold */
+  current: number;
+  best: number;
+  today_met: boolean;
+  last_met_date: string | null;
+}
+
+export interface DeepWorkSession {
+  date: string;
+  started_at: number;
+  ended_at: number;
+  focused_seconds: number;
+  interrupted_seconds: number;
+  top_category: string;
+}
+
+export interface DeepWorkSettings {
+  min_minutes: number;
+  max_gap_minutes: number;
+}
+
+export interface DayForecast {
+  date: string;
+  total_seconds: number;
+  categories: CategoryTotal[];
+}
+
+export interface Forecast {
+  week_start: string;
+  history_weeks: number;
+  total_seconds: number;
+  category_totals: CategoryTotal[];
+  days: DayForecast[];
+}
+
+export type BudgetTarget = 'app' | 'category';
+
+export interface DistractionBudget {
+  id: number | null;
+  target: BudgetTarget;
+  pattern: string;
+  daily_minutes: number;
+  enabled: boolean;
+}
+
+export interface DistractionUsage {
+  budget: DistractionBudget;
+  used_seconds: number;
+  budget_seconds: number;
+  exceeded: boolean;
+}
+
+export interface DistractionAlert {
+  budget_id: number;
+  pattern: string;
+  used_seconds: number;
+  budget_seconds: number;
+  triggered_at: number;
+}
+
+export type RangeSpec =
+  | { type: 'today' | 'yesterday' | 'this_week' | 'last_week' | 'this_month' | 'last_month' }
+  | { type: 'last_days'; days: number }
+  | { type: 'fixed'; start_date: string; end_date: string };
+
+export interface RangePreset {
+  id: string;
+  name: string;
+  spec: RangeSpec;
+  builtin: boolean;
+}
+
+export interface ResolvedRange extends RangePreset {
+  start_date: string;
+  end_date: string;
+  start_timestamp: number;
+  end_timestamp: number;
+}
+
+/** Payload of `data-pruned` events */
+export interface PruneResult {
+  retention_days: number;
+  cutoff: number;
+  activities_deleted: number;
+  snapshots_deleted: number;
+  vacuumed: boolean;
+}
+
+export interface CsvExportResult {
+  path: string;
+  rows: number;
+  bytes: number;
+}
+
+export interface DataExportResult {
+  path: string;
+  activities: number;
+  bytes: number;
+}
+
+export type TrendDirection = 'rising' | 'falling' | 'steady';
+
+export interface MetricTrend {
+  current: number;
+  previous: number;
+  /** Relative to the previous week, e.g. 0.25 for a quarter more; null when there is nothing to compare with */
+  change: number | null;
+  direction: TrendDirection;
+}
+
+export interface AppTrend {
+  app_name: string;
+  seconds: number;
+  previous_seconds: number;
+}
+
+export interface WeekTrends {
+  week_start: string;
+  active_seconds: MetricTrend;
+  /** Time in deep work sessions: productive work with only short interruptions */
+  focus_seconds: MetricTrend;
+  context_switches_per_hour: MetricTrend;
+  rising_apps: AppTrend[];
+  falling_apps: AppTrend[];
+  generated_at: number;
+}
+
+export interface NarrativeInsights {
+  week_start: number;
+  narrative: string;
+  generated_at: number;
+}
+
+export interface WeeklyReport {
+  week_start: string;
+  week_end: string;
+  markdown: string;
+  /** Where the report was saved, if it was */
+  path: string | null;
+}
+
+export interface DataImportResult {
+  profile: ProfileImportResult;
+  activities_imported: number;
+  /** Activities already in this database */
+  activities_skipped: number;
+}
+
+export interface StorageStatus {
+  backend: 'sqlite' | 'postgres';
+  user_id: string | null;
+  local_activities: number;
+  /** Recorded locally but not copied to the server yet */
+  pending_activities: number;
+  server_activities: number | null;
+  server_error: string | null;
+}
+
+export interface MigrationResult {
+  user_id: string;
+  copied: number;
+  inserted: number;
+  server_activities: number;
+}
+
+export interface PauseStatus {
+  paused: boolean;
+  paused_until: number | null;
+}
+
+export interface BlockingSettings {
+  apps: string[];
+  minimize_in_focus: boolean;
+}
+
+export type PrivacyMode = 'off' | 'omit' | 'hash';
+
+export interface RedactionRule {
+  id?: number | null;
+  pattern: string;
+  replacement: string;
+  enabled: boolean;
+}
+
+export interface RedactionPreview {
+  app_name: string;
+  original: string;
+  redacted: string;
+}
+
+export interface BreakSettings {
+  enabled: boolean;
+  threshold_minutes: number;
+  snooze_minutes: number;
+}
+
+export interface BreakReminder {
+  active_since: number;
+  active_seconds: number;
+  triggered_at: number;
+}
+
+export interface FrictionPrompt {
+  id: number;
+  app_name: string;
+  shown_at: number;
+  /** When going ahead becomes possible */
+  ready_at: number;
+}
+
+export interface FrictionStats {
+  app_name: string;
+  prompts: number;
+  proceeded: number;
+  backed_out: number;
+}
+
+export interface TimerEntry {
+  charge_code: string;
+  started_at: number;
+  /** null while the timer is running */
+  ended_at: number | null;
+  duration_seconds: number;
+}
+
+export interface TimerHotkey {
+  /** e.g. "CmdOrCtrl+Alt+1" */
+  shortcut: string;
+  charge_code: string;
+}
+
+export interface TimerHotkeyStatus extends TimerHotkey {
+  registered: boolean;
 }
 
 export interface TrackingState {